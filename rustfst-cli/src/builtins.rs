@@ -0,0 +1,785 @@
+use std::process;
+#[cfg(feature = "bench")]
+use std::time::Duration;
+
+use anyhow::Result;
+use clap::{App, Arg, ArgMatches, SubCommand};
+
+use crate::binary_fst_algorithm::BinaryFstAlgorithm;
+#[cfg(feature = "bench")]
+use crate::cmds::bench::{CriterionBench, BENCH_ALGORITHMS};
+use crate::cmds::compose::ComposeAlgorithm;
+use crate::cmds::connect::ConnectAlgorithm;
+use crate::cmds::coverage::coverage_cli;
+use crate::cmds::determinize::DeterminizeAlgorithm;
+use crate::cmds::equal::{equal_fsts, NOT_EQUAL_EXIT_CODE};
+use crate::cmds::info::info;
+use crate::cmds::invert::InvertAlgorithm;
+use crate::cmds::isomorphic::isomorphic_fsts;
+use crate::cmds::map::MapAlgorithm;
+use crate::cmds::minimize::MinimizeAlgorithm;
+use crate::cmds::optimize::OptimizeAlgorithm;
+use crate::cmds::project::ProjectFstAlgorithm;
+use crate::cmds::prune::PruneAlgorithm;
+use crate::cmds::push::PushAlgorithm;
+use crate::cmds::randgen::RandGenAlgorithm;
+use crate::cmds::reverse::ReverseAlgorithm;
+use crate::cmds::rm_final_epsilon::RmFinalEpsilonAlgorithm;
+use crate::cmds::shortest_path::{run_and_print_paths, ShortestPathAlgorithm};
+use crate::cmds::topsort::TopsortAlgorithm;
+use crate::cmds::tr_sort::TrsortAlgorithm;
+use crate::script::Script;
+use crate::subcommand::{one_in_one_out_options, two_in_one_out_options, Subcommand};
+use crate::unary_fst_algorithm::UnaryFstAlgorithm;
+
+/// Subcommand shipped with the CLI.
+struct BuiltinSubcommand {
+    name: &'static str,
+    command: fn() -> App<'static, 'static>,
+    run: fn(&ArgMatches) -> Result<()>,
+}
+
+impl Subcommand for BuiltinSubcommand {
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn command(&self) -> App<'static, 'static> {
+        (self.command)()
+    }
+
+    fn run(&self, m: &ArgMatches) -> Result<()> {
+        (self.run)(m)
+    }
+}
+
+pub(crate) fn builtin_subcommands() -> Vec<Box<dyn Subcommand>> {
+    let subcommands = vec![
+        BuiltinSubcommand {
+            name: "determinize",
+            command: || {
+                one_in_one_out_options(
+                    SubCommand::with_name("determinize")
+                        .about("Determinize algorithm.")
+                        .arg(
+                            Arg::with_name("det_type")
+                                .long("det_type")
+                                .possible_values(&["functional", "nonfunctional", "disambiguate"])
+                                .takes_value(true)
+                                .default_value("functional"),
+                        ),
+                )
+            },
+            run: |m| {
+                DeterminizeAlgorithm::new(
+                    m.value_of("in.fst").unwrap(),
+                    m.value_of("out.fst").unwrap(),
+                    m.value_of("det_type").unwrap(),
+                )
+                .run_cli_or_bench(m)
+            },
+        },
+        BuiltinSubcommand {
+            name: "minimize",
+            command: || {
+                one_in_one_out_options(
+                    SubCommand::with_name("minimize")
+                        .about("Minimization algorithm.")
+                        .arg(
+                            Arg::with_name("allow_nondet")
+                                .help("Minimize non-deterministic FSTs ?")
+                                .long("allow_nondet"),
+                        ),
+                )
+            },
+            run: |m| {
+                MinimizeAlgorithm::new(
+                    m.value_of("in.fst").unwrap(),
+                    m.is_present("allow_nondet"),
+                    m.value_of("out.fst").unwrap(),
+                )
+                .run_cli_or_bench(m)
+            },
+        },
+        BuiltinSubcommand {
+            name: "connect",
+            command: || {
+                one_in_one_out_options(SubCommand::with_name("connect").about("Connect algorithm."))
+            },
+            run: |m| {
+                ConnectAlgorithm::new(
+                    m.value_of("in.fst").unwrap(),
+                    m.value_of("out.fst").unwrap(),
+                )
+                .run_cli_or_bench(m)
+            },
+        },
+        BuiltinSubcommand {
+            name: "tr_sort",
+            command: || {
+                one_in_one_out_options(
+                    SubCommand::with_name("tr_sort")
+                        .about("Trsort algorithm.")
+                        .alias("arcsort")
+                        .arg(
+                            Arg::with_name("sort_type")
+                                .help("Comparison method.")
+                                .long("sort_type")
+                                .takes_value(true)
+                                .possible_values(&["ilabel", "olabel"])
+                                .default_value("ilabel"),
+                        ),
+                )
+            },
+            run: |m| {
+                TrsortAlgorithm::new(
+                    m.value_of("in.fst").unwrap(),
+                    m.value_of("sort_type").unwrap(),
+                    m.value_of("out.fst").unwrap(),
+                )
+                .run_cli_or_bench(m)
+            },
+        },
+        BuiltinSubcommand {
+            name: "project",
+            command: || {
+                one_in_one_out_options(
+                    SubCommand::with_name("project")
+                        .about("Project algorithm.")
+                        .arg(
+                            Arg::with_name("project_output")
+                                .help("Project output (vs. input)")
+                                .long("project_output"),
+                        ),
+                )
+            },
+            run: |m| {
+                ProjectFstAlgorithm::new(
+                    m.value_of("in.fst").unwrap(),
+                    m.is_present("project_output"),
+                    m.value_of("out.fst").unwrap(),
+                )
+                .run_cli_or_bench(m)
+            },
+        },
+        BuiltinSubcommand {
+            name: "invert",
+            command: || {
+                one_in_one_out_options(SubCommand::with_name("invert").about("Invert algorithm."))
+            },
+            run: |m| {
+                InvertAlgorithm::new(
+                    m.value_of("in.fst").unwrap(),
+                    m.value_of("out.fst").unwrap(),
+                )
+                .run_cli_or_bench(m)
+            },
+        },
+        BuiltinSubcommand {
+            name: "topsort",
+            command: || {
+                one_in_one_out_options(SubCommand::with_name("topsort").about("Topsort algorithm."))
+            },
+            run: |m| {
+                TopsortAlgorithm::new(
+                    m.value_of("in.fst").unwrap(),
+                    m.value_of("out.fst").unwrap(),
+                )
+                .run_cli_or_bench(m)
+            },
+        },
+        BuiltinSubcommand {
+            name: "optimize",
+            command: || {
+                one_in_one_out_options(
+                    SubCommand::with_name("optimize").about("Optimize algorithm."),
+                )
+            },
+            run: |m| {
+                OptimizeAlgorithm::new(
+                    m.value_of("in.fst").unwrap(),
+                    m.value_of("out.fst").unwrap(),
+                )
+                .run_cli_or_bench(m)
+            },
+        },
+        BuiltinSubcommand {
+            name: "reverse",
+            command: || {
+                one_in_one_out_options(SubCommand::with_name("reverse").about("Reverse algorithm."))
+            },
+            run: |m| {
+                ReverseAlgorithm::new(
+                    m.value_of("in.fst").unwrap(),
+                    m.value_of("out.fst").unwrap(),
+                )
+                .run_cli_or_bench(m)
+            },
+        },
+        BuiltinSubcommand {
+            name: "map",
+            command: || {
+                one_in_one_out_options(
+                    SubCommand::with_name("map")
+                        .about("Applies an operation to each tr of an FST.")
+                        .arg(
+                            Arg::with_name("map_type")
+                                .long("map_type")
+                                .possible_values(&[
+                                    "arc_sum",
+                                    "arc_unique",
+                                    "tr_sum",
+                                    "tr_unique",
+                                    "identity",
+                                    "input_epsilon",
+                                    "invert",
+                                    "output_epsilon",
+                                    "plus",
+                                    "quantize",
+                                    "rmweight",
+                                    "times",
+                                ])
+                                .takes_value(true)
+                                .default_value("identity")
+                                .help("Map operation."),
+                        )
+                        .arg(
+                            Arg::with_name("weight")
+                                .long("weight")
+                                .takes_value(true)
+                                .required_ifs(&[("map_type", "plus"), ("map_type", "times")]),
+                        ),
+                )
+            },
+            run: |m| {
+                MapAlgorithm::new(
+                    m.value_of("in.fst").unwrap(),
+                    m.value_of("map_type").unwrap(),
+                    m.value_of("weight"),
+                    m.value_of("out.fst").unwrap(),
+                )
+                .run_cli_or_bench(m)
+            },
+        },
+        BuiltinSubcommand {
+            name: "shortestpath",
+            command: || {
+                one_in_one_out_options(
+                    SubCommand::with_name("shortestpath")
+                        .about("Shortest Path algorithm.")
+                        .arg(
+                            Arg::with_name("nshortest")
+                                .long("nshortest")
+                                .takes_value(true)
+                                .default_value("1")
+                                .help("Return N-shortest paths"),
+                        )
+                        .arg(
+                            Arg::with_name("unique")
+                                .long("unique")
+                                .help("Return unique strings"),
+                        )
+                        .arg(
+                            Arg::with_name("print")
+                                .long("print")
+                                .help("Print the paths, decoded with the symbol tables, on the standard output."),
+                        )
+                        .arg(
+                            Arg::with_name("json")
+                                .long("json")
+                                .help("Print the paths as JSON on the standard output."),
+                        )
+                        .arg(
+                            Arg::with_name("isymbols")
+                                .long("isymbols")
+                                .takes_value(true)
+                                .help("Input symbol table in text format, used to print the paths."),
+                        )
+                        .arg(
+                            Arg::with_name("osymbols")
+                                .long("osymbols")
+                                .takes_value(true)
+                                .help("Output symbol table in text format, used to print the paths."),
+                        ),
+                )
+            },
+            run: |m| {
+                let algorithm = ShortestPathAlgorithm::new(
+                    m.value_of("in.fst").unwrap(),
+                    m.is_present("unique"),
+                    m.value_of("nshortest").unwrap().parse().unwrap(),
+                    m.value_of("out.fst").unwrap(),
+                );
+                if m.is_present("print") || m.is_present("json") {
+                    run_and_print_paths(
+                        &algorithm,
+                        m.value_of("isymbols"),
+                        m.value_of("osymbols"),
+                        m.is_present("json"),
+                    )
+                } else {
+                    algorithm.run_cli_or_bench(m)
+                }
+            },
+        },
+        BuiltinSubcommand {
+            name: "prune",
+            command: || {
+                one_in_one_out_options(
+                    SubCommand::with_name("prune")
+                        .about("Prune algorithm.")
+                        .arg(
+                            Arg::with_name("weight")
+                                .long("weight")
+                                .takes_value(true)
+                                .help("Remove the paths costing more than the best path plus this threshold."),
+                        )
+                        .arg(
+                            Arg::with_name("nstate")
+                                .long("nstate")
+                                .takes_value(true)
+                                .help("Maximum number of states to keep, the best ones being kept."),
+                        )
+                        .arg(
+                            Arg::with_name("delta")
+                                .long("delta")
+                                .takes_value(true)
+                                .default_value("0.0009765625")
+                                .help("Convergence delta of the shortest distance."),
+                        ),
+                )
+            },
+            run: |m| {
+                PruneAlgorithm::new(
+                    m.value_of("in.fst").unwrap(),
+                    m.value_of("out.fst").unwrap(),
+                    m.value_of("weight").map(|s| s.parse()).transpose()?,
+                    m.value_of("nstate").map(|s| s.parse()).transpose()?,
+                )
+                .with_delta(m.value_of("delta").unwrap().parse()?)
+                .run_cli_or_bench(m)
+            },
+        },
+        BuiltinSubcommand {
+            name: "rmfinalepsilon",
+            command: || {
+                one_in_one_out_options(
+                    SubCommand::with_name("rmfinalepsilon").about("RmFinalEpsilon algorithm."),
+                )
+            },
+            run: |m| {
+                RmFinalEpsilonAlgorithm::new(
+                    m.value_of("in.fst").unwrap(),
+                    m.value_of("out.fst").unwrap(),
+                )
+                .run_cli_or_bench(m)
+            },
+        },
+        BuiltinSubcommand {
+            name: "push",
+            command: || {
+                one_in_one_out_options(
+                    SubCommand::with_name("push")
+                        .about("Push Weights/Labels algorithm")
+                        .arg(
+                            Arg::with_name("to_final").long("to_final").help(
+                                "Push towards the final states instead of the initial state.",
+                            ),
+                        )
+                        .arg(
+                            Arg::with_name("push_weights")
+                                .long("push_weights")
+                                .help("Push the weights."),
+                        )
+                        .arg(
+                            Arg::with_name("push_labels")
+                                .long("push_labels")
+                                .help("Push the output labels."),
+                        )
+                        .arg(
+                            Arg::with_name("remove_total_weight")
+                                .long("remove_total_weight")
+                                .help("Remove the total weight when pushing the weights."),
+                        )
+                        .arg(
+                            Arg::with_name("remove_common_affix")
+                                .long("remove_common_affix")
+                                .help("Remove the common prefix/suffix when pushing the labels."),
+                        )
+                        .arg(
+                            Arg::with_name("delta")
+                                .long("delta")
+                                .takes_value(true)
+                                .default_value("0.0009765625")
+                                .help("Convergence delta of the shortest distance."),
+                        ),
+                )
+            },
+            run: |m| {
+                PushAlgorithm::new(
+                    m.value_of("in.fst").unwrap(),
+                    m.value_of("out.fst").unwrap(),
+                    m.is_present("to_final"),
+                    m.is_present("push_weights"),
+                    m.is_present("push_labels"),
+                    m.is_present("remove_total_weight"),
+                    m.is_present("remove_common_affix"),
+                )
+                .with_delta(m.value_of("delta").unwrap().parse()?)
+                .run_cli_or_bench(m)
+            },
+        },
+        BuiltinSubcommand {
+            name: "randgen",
+            command: || {
+                one_in_one_out_options(
+                    SubCommand::with_name("randgen")
+                        .about("Random path generation algorithm.")
+                        .arg(
+                            Arg::with_name("select")
+                                .long("select")
+                                .possible_values(&["uniform", "log_prob"])
+                                .takes_value(true)
+                                .default_value("uniform")
+                                .help("How a transition is selected at each state."),
+                        )
+                        .arg(
+                            Arg::with_name("seed")
+                                .long("seed")
+                                .takes_value(true)
+                                .help("Random seed, drawn from the system entropy if missing."),
+                        )
+                        .arg(
+                            Arg::with_name("max_length")
+                                .long("max_length")
+                                .takes_value(true)
+                                .help("Maximum number of transitions of a path."),
+                        )
+                        .arg(
+                            Arg::with_name("npath")
+                                .long("npath")
+                                .takes_value(true)
+                                .default_value("1")
+                                .help("Number of paths to generate."),
+                        )
+                        .arg(
+                            Arg::with_name("weighted")
+                                .long("weighted")
+                                .help("Output a tree weighted by the path counts."),
+                        )
+                        .arg(
+                            Arg::with_name("remove_total_weight")
+                                .long("remove_total_weight")
+                                .help("Remove the total weight when the output is weighted."),
+                        ),
+                )
+            },
+            run: |m| {
+                RandGenAlgorithm::new(
+                    m.value_of("in.fst").unwrap(),
+                    m.value_of("out.fst").unwrap(),
+                    m.value_of("select").unwrap(),
+                )
+                .with_seed(m.value_of("seed").map(|s| s.parse()).transpose()?)
+                .with_max_length(
+                    m.value_of("max_length")
+                        .map(|s| s.parse())
+                        .transpose()?
+                        .unwrap_or(usize::MAX),
+                )
+                .with_npath(m.value_of("npath").unwrap().parse()?)
+                .with_weighted(m.is_present("weighted"))
+                .with_remove_total_weight(m.is_present("remove_total_weight"))
+                .run_cli_or_bench(m)
+            },
+        },
+        BuiltinSubcommand {
+            name: "compose",
+            command: || {
+                two_in_one_out_options(
+                    SubCommand::with_name("compose")
+                        .about("Compose algorithm")
+                        .arg(
+                            Arg::with_name("compose_type")
+                                .long("compose_type")
+                                .possible_values(&["default", "lookahead"])
+                                .takes_value(true)
+                                .default_value("default"),
+                        )
+                        .arg(
+                            Arg::with_name("lookahead")
+                                .long("lookahead")
+                                .help("Use the lookahead composition. Same as --compose_type=lookahead."),
+                        )
+                        .arg(
+                            Arg::with_name("compose_filter")
+                                .long("compose_filter")
+                                .possible_values(&[
+                                    "auto",
+                                    "null",
+                                    "trivial",
+                                    "sequence",
+                                    "alt_sequence",
+                                    "match",
+                                    "no_match",
+                                ])
+                                .takes_value(true)
+                                .default_value("auto")
+                                .help("Composition filter. Only supported by the default composition."),
+                        )
+                        .arg(
+                            Arg::with_name("connect")
+                                .long("connect")
+                                .possible_values(&["true", "false"])
+                                .takes_value(true)
+                                .default_value("true")
+                                .help("Whether to trim the output FST."),
+                        ),
+                )
+            },
+            run: |m| {
+                let compose_type = if m.is_present("lookahead") {
+                    "lookahead"
+                } else {
+                    m.value_of("compose_type").unwrap()
+                };
+                ComposeAlgorithm::new(
+                    m.value_of("in_1.fst").unwrap(),
+                    m.value_of("in_2.fst").unwrap(),
+                    m.value_of("out.fst").unwrap(),
+                    compose_type,
+                )
+                .with_compose_filter(m.value_of("compose_filter").unwrap())
+                .with_connect(m.value_of("connect").unwrap() == "true")
+                .run_cli_or_bench(m)
+            },
+        },
+        BuiltinSubcommand {
+            name: "info",
+            command: || {
+                SubCommand::with_name("info")
+                    .about("Prints information about an FST.")
+                    .version("1.0")
+                    .author("Alexandre Caulier <alexandre.caulier@protonmail.com>")
+                    .arg(
+                        Arg::with_name("in.fst")
+                            .help("Path to input fst file.")
+                            .required(true),
+                    )
+                    .arg(
+                        Arg::with_name("json")
+                            .long("json")
+                            .help("Print the information as JSON."),
+                    )
+            },
+            run: |m| info(m.value_of("in.fst").unwrap(), m.is_present("json")),
+        },
+        BuiltinSubcommand {
+            name: "coverage",
+            command: || {
+                SubCommand::with_name("coverage")
+                    .about("Runs a corpus of strings through an acceptor and reports the rejected ones.")
+                    .version("1.0")
+                    .author("Alexandre Caulier <alexandre.caulier@protonmail.com>")
+                    .arg(
+                        Arg::with_name("in.fst")
+                            .help("Path to input fst file.")
+                            .required(true),
+                    )
+                    .arg(
+                        Arg::with_name("corpus")
+                            .help("Path to the corpus, one string per line. The symbols are separated by spaces and looked up in the input symbol table of the fst, if any, or are integer labels.")
+                            .required(true),
+                    )
+                    .arg(
+                        Arg::with_name("json")
+                            .long("json")
+                            .help("Print the report as JSON."),
+                    )
+            },
+            run: |m| {
+                coverage_cli(
+                    m.value_of("in.fst").unwrap(),
+                    m.value_of("corpus").unwrap(),
+                    m.is_present("json"),
+                )
+            },
+        },
+        BuiltinSubcommand {
+            name: "isomorphic",
+            command: || {
+                SubCommand::with_name("isomorphic")
+                    .about("Checks whether two FSTs are isomorphic.")
+                    .version("1.0")
+                    .author("Alexandre Caulier <alexandre.caulier@protonmail.com>")
+                    .arg(
+                        Arg::with_name("in_1.fst")
+                            .help("Path to the first input fst file.")
+                            .required(true),
+                    )
+                    .arg(
+                        Arg::with_name("in_2.fst")
+                            .help("Path to the second input fst file.")
+                            .required(true),
+                    )
+                    .arg(
+                        Arg::with_name("delta")
+                            .long("delta")
+                            .takes_value(true)
+                            .default_value("0.0009765625")
+                            .help("Comparison/quantization delta."),
+                    )
+                    .arg(
+                        Arg::with_name("json")
+                            .long("json")
+                            .help("Print the result as JSON."),
+                    )
+            },
+            run: |m| {
+                isomorphic_fsts(
+                    m.value_of("in_1.fst").unwrap(),
+                    m.value_of("in_2.fst").unwrap(),
+                    m.value_of("delta").unwrap().parse()?,
+                    m.is_present("json"),
+                )
+            },
+        },
+        BuiltinSubcommand {
+            name: "equal",
+            command: || {
+                SubCommand::with_name("equal")
+                    .about("Checks whether two FSTs are equal, exiting with the status 2 if they are not.")
+                    .version("1.0")
+                    .author("Alexandre Caulier <alexandre.caulier@protonmail.com>")
+                    .arg(
+                        Arg::with_name("in_1.fst")
+                            .help("Path to the first input fst file.")
+                            .required(true),
+                    )
+                    .arg(
+                        Arg::with_name("in_2.fst")
+                            .help("Path to the second input fst file.")
+                            .required(true),
+                    )
+                    .arg(
+                        Arg::with_name("delta")
+                            .long("delta")
+                            .takes_value(true)
+                            .default_value("0.0009765625")
+                            .help("Comparison delta of the weights."),
+                    )
+                    .arg(
+                        Arg::with_name("json")
+                            .long("json")
+                            .help("Print the result as JSON."),
+                    )
+            },
+            run: |m| {
+                let equal = equal_fsts(
+                    m.value_of("in_1.fst").unwrap(),
+                    m.value_of("in_2.fst").unwrap(),
+                    m.value_of("delta").unwrap().parse()?,
+                    m.is_present("json"),
+                )?;
+                if !equal {
+                    process::exit(NOT_EQUAL_EXIT_CODE);
+                }
+                Ok(())
+            },
+        },
+        #[cfg(feature = "bench")]
+        BuiltinSubcommand {
+            name: "bench",
+            command: || {
+                SubCommand::with_name("bench")
+                    .about("Criterion benchmarks of algorithms on FSTs read from files.")
+                    .version("1.0")
+                    .author("Alexandre Caulier <alexandre.caulier@protonmail.com>")
+                    .arg(
+                        Arg::with_name("in.fst")
+                            .help("Paths to the input fst files.")
+                            .required(true)
+                            .multiple(true),
+                    )
+                    .arg(
+                        Arg::with_name("algorithms")
+                            .long("algorithms")
+                            .takes_value(true)
+                            .multiple(true)
+                            .use_delimiter(true)
+                            .possible_values(BENCH_ALGORITHMS)
+                            .default_value("compose,determinize,minimize,shortestpath")
+                            .help("Algorithms to benchmark."),
+                    )
+                    .arg(
+                        Arg::with_name("rhs")
+                            .long("rhs")
+                            .takes_value(true)
+                            .help("Path to the fst composed on the right of the input fsts."),
+                    )
+                    .arg(
+                        Arg::with_name("sample_size")
+                            .long("sample_size")
+                            .takes_value(true)
+                            .default_value("10")
+                            .help("Number of samples per benchmark, at least 10."),
+                    )
+                    .arg(
+                        Arg::with_name("measurement_time")
+                            .long("measurement_time")
+                            .takes_value(true)
+                            .default_value("5")
+                            .help("Time spent measuring each benchmark, in seconds."),
+                    )
+                    .arg(
+                        Arg::with_name("save_baseline")
+                            .long("save_baseline")
+                            .takes_value(true)
+                            .help("Saves the results under this name."),
+                    )
+                    .arg(
+                        Arg::with_name("baseline")
+                            .long("baseline")
+                            .takes_value(true)
+                            .conflicts_with("save_baseline")
+                            .help("Compares the results with this saved baseline."),
+                    )
+            },
+            run: |m| {
+                let algorithms: Vec<&str> = m.values_of("algorithms").unwrap().collect();
+                let paths_in: Vec<&str> = m.values_of("in.fst").unwrap().collect();
+                CriterionBench::new(&algorithms, &paths_in)
+                    .with_rhs(m.value_of("rhs"))
+                    .with_sample_size(m.value_of("sample_size").unwrap().parse()?)
+                    .with_measurement_time(Duration::from_secs_f64(
+                        m.value_of("measurement_time").unwrap().parse()?,
+                    ))
+                    .with_save_baseline(m.value_of("save_baseline"))
+                    .with_baseline(m.value_of("baseline"))
+                    .run()
+            },
+        },
+        BuiltinSubcommand {
+            name: "run",
+            command: || {
+                SubCommand::with_name("run")
+                    .about("Runs a script of operations on in-memory FSTs.")
+                    .version("1.0")
+                    .author("Alexandre Caulier <alexandre.caulier@protonmail.com>")
+                    .arg(
+                        Arg::with_name("script")
+                            .help("Path to the script file.")
+                            .required(true),
+                    )
+            },
+            run: |m| {
+                Script::read(m.value_of("script").unwrap())?.run()?;
+                Ok(())
+            },
+        },
+    ];
+    subcommands
+        .into_iter()
+        .map(|s| Box::new(s) as Box<dyn Subcommand>)
+        .collect()
+}
@@ -0,0 +1,202 @@
+use std::path::Path;
+use std::time::Duration;
+
+use anyhow::{bail, Context, Result};
+use criterion::{BatchSize, BenchmarkId, Criterion};
+
+use rustfst::prelude::*;
+
+use crate::binary_fst_algorithm::BinaryFstAlgorithm;
+use crate::cmds::compose::ComposeAlgorithm;
+use crate::cmds::determinize::DeterminizeAlgorithm;
+use crate::cmds::minimize::MinimizeAlgorithm;
+use crate::cmds::shortest_path::ShortestPathAlgorithm;
+use crate::unary_fst_algorithm::UnaryFstAlgorithm;
+
+/// Algorithms that can be benchmarked by the `bench` subcommand.
+pub const BENCH_ALGORITHMS: &[&str] = &["compose", "determinize", "minimize", "shortestpath"];
+
+/// Criterion benchmark of algorithms on FSTs read from files. Each algorithm is run on each
+/// input FST, the composition using the FST `rhs` as its right operand.
+///
+/// The reports are written by Criterion in `target/criterion`, with a group per algorithm and
+/// a benchmark per input file, so that the same command run on two versions of rustfst
+/// measures the regressions. Use `save_baseline` and `baseline` to compare named runs.
+///
+/// Only built with the `bench` feature, which pulls Criterion.
+#[derive(Debug, Clone)]
+pub struct CriterionBench {
+    algorithms: Vec<String>,
+    paths_in: Vec<String>,
+    path_rhs: Option<String>,
+    sample_size: usize,
+    measurement_time: Duration,
+    warm_up_time: Duration,
+    save_baseline: Option<String>,
+    baseline: Option<String>,
+}
+
+impl CriterionBench {
+    pub fn new(algorithms: &[&str], paths_in: &[&str]) -> Self {
+        Self {
+            algorithms: algorithms.iter().map(|a| a.to_string()).collect(),
+            paths_in: paths_in.iter().map(|p| p.to_string()).collect(),
+            path_rhs: None,
+            sample_size: 10,
+            measurement_time: Duration::from_secs(5),
+            warm_up_time: Duration::from_secs(1),
+            save_baseline: None,
+            baseline: None,
+        }
+    }
+
+    pub fn with_rhs(self, path_rhs: Option<&str>) -> Self {
+        Self {
+            path_rhs: path_rhs.map(|p| p.to_string()),
+            ..self
+        }
+    }
+
+    pub fn with_sample_size(self, sample_size: usize) -> Self {
+        Self {
+            sample_size,
+            ..self
+        }
+    }
+
+    pub fn with_measurement_time(self, measurement_time: Duration) -> Self {
+        Self {
+            measurement_time,
+            ..self
+        }
+    }
+
+    pub fn with_warm_up_time(self, warm_up_time: Duration) -> Self {
+        Self {
+            warm_up_time,
+            ..self
+        }
+    }
+
+    /// Saves the results under this name instead of the default baseline.
+    pub fn with_save_baseline(self, save_baseline: Option<&str>) -> Self {
+        Self {
+            save_baseline: save_baseline.map(|b| b.to_string()),
+            ..self
+        }
+    }
+
+    /// Compares the results with this baseline without overwriting it.
+    pub fn with_baseline(self, baseline: Option<&str>) -> Self {
+        Self {
+            baseline: baseline.map(|b| b.to_string()),
+            ..self
+        }
+    }
+
+    fn criterion(&self) -> Result<Criterion> {
+        // Criterion panics on fewer samples.
+        if self.sample_size < 10 {
+            bail!(
+                "The sample size must be at least 10, got {}",
+                self.sample_size
+            );
+        }
+        let mut criterion = Criterion::default()
+            .sample_size(self.sample_size)
+            .measurement_time(self.measurement_time)
+            .warm_up_time(self.warm_up_time);
+        if let Some(name) = &self.save_baseline {
+            criterion = criterion.save_baseline(name.clone());
+        }
+        if let Some(name) = &self.baseline {
+            criterion = criterion.retain_baseline(name.clone());
+        }
+        Ok(criterion)
+    }
+
+    pub fn run(&self) -> Result<()> {
+        for algorithm in self.algorithms.iter() {
+            if !BENCH_ALGORITHMS.contains(&algorithm.as_str()) {
+                bail!(
+                    "Unexpected algorithm {}, expected one of {:?}",
+                    algorithm,
+                    BENCH_ALGORITHMS
+                );
+            }
+        }
+        let rhs = match (
+            &self.path_rhs,
+            self.algorithms.iter().any(|a| a == "compose"),
+        ) {
+            (Some(path), _) => Some(VectorFst::<TropicalWeight>::read(path)?),
+            (None, true) => bail!("The compose benchmark requires the right operand --rhs"),
+            (None, false) => None,
+        };
+        let mut inputs = vec![];
+        for path in self.paths_in.iter() {
+            let name = Path::new(path)
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_else(|| path.clone());
+            inputs.push((name, VectorFst::<TropicalWeight>::read(path)?));
+        }
+
+        let mut criterion = self.criterion()?;
+        for algorithm in self.algorithms.iter() {
+            let mut group = criterion.benchmark_group(algorithm.as_str());
+            if let ("compose", Some(rhs)) = (algorithm.as_str(), &rhs) {
+                let compose = ComposeAlgorithm::new("", "", "", "default");
+                for (name, fst) in inputs.iter() {
+                    // Fails before the measurements rather than panicking inside of them.
+                    compose
+                        .run_algorithm(fst.clone(), rhs.clone())
+                        .with_context(|| format!("compose failed on {}", name))?;
+                    group.bench_with_input(BenchmarkId::from_parameter(name), fst, |b, fst| {
+                        b.iter_batched(
+                            || (fst.clone(), rhs.clone()),
+                            |(fst, rhs)| compose.run_algorithm(fst, rhs).unwrap(),
+                            BatchSize::LargeInput,
+                        )
+                    });
+                }
+            } else {
+                let unary: Box<dyn UnaryFstAlgorithm> = match algorithm.as_str() {
+                    "determinize" => Box::new(DeterminizeAlgorithm::new("", "", "functional")),
+                    "minimize" => Box::new(MinimizeAlgorithm::new("", false, "")),
+                    _ => Box::new(ShortestPathAlgorithm::new("", false, 1, "")),
+                };
+                for (name, fst) in inputs.iter() {
+                    unary
+                        .run_algorithm(fst.clone())
+                        .with_context(|| format!("{} failed on {}", algorithm, name))?;
+                    group.bench_with_input(BenchmarkId::from_parameter(name), fst, |b, fst| {
+                        b.iter_batched(
+                            || fst.clone(),
+                            |fst| unary.run_algorithm(fst).unwrap(),
+                            BatchSize::LargeInput,
+                        )
+                    });
+                }
+            }
+            group.finish();
+        }
+        criterion.final_summary();
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_criterion_bench_checks_config() {
+        assert!(CriterionBench::new(&["connect"], &["a.fst"]).run().is_err());
+        assert!(CriterionBench::new(&["compose"], &["a.fst"]).run().is_err());
+        assert!(CriterionBench::new(&["minimize"], &[])
+            .with_sample_size(5)
+            .run()
+            .is_err());
+    }
+}
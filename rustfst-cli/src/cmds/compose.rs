@@ -1,9 +1,14 @@
-use anyhow::Result;
+use anyhow::{bail, Result};
+use log::debug;
 
 use rustfst::algorithms::compose::{
-    compose, ComposeFst, ComposeFstOpOptions, LabelReachableData, MatcherFst,
+    compose_with_config, ComposeConfig, ComposeFilterEnum, ComposeFst, ComposeFstOpOptions,
+    LabelReachableData, MatcherFst,
 };
+use rustfst::algorithms::connect;
 use rustfst::fst_impls::VectorFst;
+use rustfst::fst_properties::FstProperties;
+use rustfst::fst_traits::CoreFst;
 use rustfst::semirings::TropicalWeight;
 
 use crate::binary_fst_algorithm::BinaryFstAlgorithm;
@@ -19,7 +24,7 @@ use rustfst::algorithms::compose::lookahead_matchers::{
 };
 use rustfst::algorithms::compose::matchers::{MatchType, Matcher, MatcherFlags, SortedMatcher};
 use rustfst::algorithms::lazy::SimpleHashMapCache;
-use rustfst::algorithms::tr_compares::ILabelCompare;
+use rustfst::algorithms::tr_compares::{ILabelCompare, OLabelCompare};
 use rustfst::algorithms::tr_sort;
 
 #[derive(Debug, Clone, Copy)]
@@ -33,6 +38,8 @@ pub struct ComposeAlgorithm {
     path_in_2: String,
     path_out: String,
     compose_type: ComposeType,
+    compose_filter: ComposeFilterEnum,
+    connect: bool,
 }
 
 #[derive(Debug, Clone, PartialOrd, PartialEq)]
@@ -67,14 +74,33 @@ impl BinaryFstAlgorithm for ComposeAlgorithm {
 
     fn run_algorithm(
         &self,
-        fst_1: VectorFst<TropicalWeight>,
+        mut fst_1: VectorFst<TropicalWeight>,
         mut fst_2: VectorFst<TropicalWeight>,
     ) -> Result<VectorFst<TropicalWeight>> {
+        // The matchers require the output labels of the first FST and the input labels of the
+        // second one to be sorted. Otherwise the composition silently misses some paths.
+        if !fst_1.properties().contains(FstProperties::O_LABEL_SORTED) {
+            debug!("Sorting the transitions of the first FST on the output labels");
+            tr_sort(&mut fst_1, OLabelCompare {});
+        }
+        if !fst_2.properties().contains(FstProperties::I_LABEL_SORTED) {
+            debug!("Sorting the transitions of the second FST on the input labels");
+            tr_sort(&mut fst_2, ILabelCompare {});
+        }
+
         match self.compose_type {
             ComposeType::Default => {
-                compose::<TropicalWeight, VectorFst<_>, VectorFst<_>, _, _, _>(&fst_1, &fst_2)
+                let config = ComposeConfig::default()
+                    .with_compose_filter(self.compose_filter)
+                    .with_connect(self.connect);
+                compose_with_config::<TropicalWeight, VectorFst<_>, VectorFst<_>, _, _, _>(
+                    &fst_1, &fst_2, config,
+                )
             }
             ComposeType::LookAhead => {
+                if self.compose_filter != ComposeFilterEnum::AutoFilter {
+                    bail!("The lookahead composition doesn't support the compose_filter option");
+                }
                 type TLaFst<'a, S, F> = MatcherFst<
                     S,
                     F,
@@ -177,7 +203,11 @@ impl BinaryFstAlgorithm for ComposeAlgorithm {
                         compose_options,
                     )?;
 
-                dyn_fst.compute()
+                let mut fst: VectorFst<_> = dyn_fst.compute()?;
+                if self.connect {
+                    connect(&mut fst)?;
+                }
+                Ok(fst)
             }
         }
     }
@@ -195,6 +225,29 @@ impl ComposeAlgorithm {
             path_in_2: path_in_2.to_string(),
             path_out: path_out.to_string(),
             compose_type,
+            compose_filter: ComposeFilterEnum::AutoFilter,
+            connect: true,
+        }
+    }
+
+    pub fn with_compose_filter(self, compose_filter: &str) -> Self {
+        let compose_filter = match compose_filter {
+            "auto" => ComposeFilterEnum::AutoFilter,
+            "null" => ComposeFilterEnum::NullFilter,
+            "trivial" => ComposeFilterEnum::TrivialFilter,
+            "sequence" => ComposeFilterEnum::SequenceFilter,
+            "alt_sequence" => ComposeFilterEnum::AltSequenceFilter,
+            "match" => ComposeFilterEnum::MatchFilter,
+            "no_match" => ComposeFilterEnum::NoMatchFilter,
+            _ => panic!("Unexpected compose_filter : {}", compose_filter),
+        };
+        Self {
+            compose_filter,
+            ..self
         }
     }
+
+    pub fn with_connect(self, connect: bool) -> Self {
+        Self { connect, ..self }
+    }
 }
@@ -0,0 +1,86 @@
+use std::fs::read_to_string;
+
+use anyhow::{Context, Result};
+use serde_json::json;
+
+use rustfst::algo::apply::coverage;
+use rustfst::prelude::*;
+use rustfst::NO_LABEL;
+
+/// Parses a line of the corpus : symbols of the input table of the FST if it has one,
+/// integer labels otherwise. The unknown symbols are mapped to `NO_LABEL`, which blocks.
+fn parse_line(line: &str, symt: Option<&SymbolTable>) -> Result<Vec<Label>> {
+    line.split_whitespace()
+        .map(|token| match symt {
+            Some(symt) => Ok(symt.get_label(token).unwrap_or(NO_LABEL)),
+            None => token
+                .parse::<Label>()
+                .with_context(|| format!("Invalid label {:?}", token)),
+        })
+        .collect()
+}
+
+/// Runs each line of the corpus through the FST and prints the coverage report, with the
+/// position, the symbol and the states of each failure.
+pub fn coverage_cli(path_in: &str, path_corpus: &str, json: bool) -> Result<()> {
+    let fst = VectorFst::<TropicalWeight>::read(path_in)?;
+    let corpus = read_to_string(path_corpus)
+        .with_context(|| format!("Can't open corpus file : {:?}", path_corpus))?;
+    let lines: Vec<&str> = corpus.lines().collect();
+    let strings = lines
+        .iter()
+        .enumerate()
+        .map(|(idx, line)| {
+            parse_line(line, fst.input_symbols().map(|s| s.as_ref()))
+                .with_context(|| format!("Line {} of the corpus", idx + 1))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let report = coverage(&fst, &strings)?;
+
+    let failures: Vec<_> = report
+        .strings
+        .iter()
+        .enumerate()
+        .filter(|(_, s)| !s.is_accepted())
+        .map(|(idx, s)| {
+            let tokens: Vec<_> = lines[idx].split_whitespace().collect();
+            (idx, s, s.blocking_label.map(|_| tokens[s.matched_len]))
+        })
+        .collect();
+
+    if json {
+        let value = json!({
+            "num_strings": report.num_strings(),
+            "num_accepted": report.num_accepted(),
+            "acceptance_rate": report.acceptance_rate(),
+            "num_rejected_at_end": report.num_rejected_at_end(),
+            "failures": failures.iter().map(|(idx, s, token)| json!({
+                "line": idx + 1,
+                "matched_len": s.matched_len,
+                "blocking_symbol": token,
+                "blocking_states": s.blocking_states,
+            })).collect::<Vec<_>>(),
+        });
+        println!("{}", serde_json::to_string_pretty(&value)?);
+    } else {
+        println!("{}", report);
+        for (idx, s, token) in failures.iter() {
+            let states: Vec<_> = s.blocking_states.iter().map(|s| s.to_string()).collect();
+            match token {
+                Some(token) => println!(
+                    "line {} : blocked on {:?} after {} symbols in states {}",
+                    idx + 1,
+                    token,
+                    s.matched_len,
+                    states.join(" ")
+                ),
+                None => println!(
+                    "line {} : read entirely, ending in the non-final states {}",
+                    idx + 1,
+                    states.join(" ")
+                ),
+            }
+        }
+    }
+    Ok(())
+}
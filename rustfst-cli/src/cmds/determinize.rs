@@ -1,6 +1,8 @@
 use anyhow::Result;
 
-use rustfst::algorithms::determinize::{DeterminizeConfig, DeterminizeType};
+use rustfst::algorithms::determinize::{
+    determinize_with_config, DeterminizeConfig, DeterminizeType,
+};
 use rustfst::prelude::*;
 
 use crate::unary_fst_algorithm::UnaryFstAlgorithm;
@@ -26,7 +28,7 @@ impl UnaryFstAlgorithm for DeterminizeAlgorithm {
 
     fn run_algorithm(&self, fst: VectorFst<TropicalWeight>) -> Result<VectorFst<TropicalWeight>> {
         let det_config = DeterminizeConfig::default().with_det_type(self.det_type);
-        let fst = determinize::determinize_with_config(&fst, det_config)?;
+        let fst = determinize_with_config(&fst, det_config)?;
         Ok(fst)
     }
 }
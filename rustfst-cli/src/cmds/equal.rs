@@ -0,0 +1,24 @@
+use anyhow::Result;
+use serde_json::json;
+
+use rustfst::algorithms::{equal_with_config, EqualConfig};
+use rustfst::prelude::*;
+
+/// Exit status of the `equal` subcommand when the FSTs aren't equal, as `fstequal` of OpenFst.
+pub const NOT_EQUAL_EXIT_CODE: i32 = 2;
+
+/// Checks whether two FSTs are equal, prints the result and returns it.
+pub fn equal_fsts(path_in_1: &str, path_in_2: &str, delta: f32, json: bool) -> Result<bool> {
+    let fst_1 = VectorFst::<TropicalWeight>::read(path_in_1)?;
+    let fst_2 = VectorFst::<TropicalWeight>::read(path_in_2)?;
+    let res = equal_with_config(&fst_1, &fst_2, EqualConfig::default().with_delta(delta))?;
+    if json {
+        let value = json!({ "equal": res, "delta": delta });
+        println!("{}", serde_json::to_string_pretty(&value)?);
+    } else if res {
+        println!("FSTs are equal");
+    } else {
+        println!("FSTs are not equal");
+    }
+    Ok(res)
+}
@@ -0,0 +1,88 @@
+use std::fmt;
+
+use anyhow::Result;
+use serde_json::{json, Value};
+
+use rustfst::fst_properties::FstProperties;
+use rustfst::prelude::*;
+
+/// Summary of an FST, as printed by the `info` subcommand.
+pub struct FstInfo {
+    pub num_states: usize,
+    pub num_trs: usize,
+    pub num_final_states: usize,
+    pub start: Option<StateId>,
+    pub properties: FstProperties,
+    pub num_input_symbols: Option<usize>,
+    pub num_output_symbols: Option<usize>,
+}
+
+impl FstInfo {
+    pub fn new(fst: &VectorFst<TropicalWeight>) -> Result<Self> {
+        let mut num_trs = 0;
+        for state in fst.states_range() {
+            num_trs += fst.num_trs(state)?;
+        }
+        Ok(Self {
+            num_states: fst.num_states(),
+            num_trs,
+            num_final_states: fst.final_states_iter().count(),
+            start: fst.start(),
+            properties: fst.properties(),
+            num_input_symbols: fst.input_symbols().map(|s| s.len()),
+            num_output_symbols: fst.output_symbols().map(|s| s.len()),
+        })
+    }
+
+    fn property_names(&self) -> Vec<String> {
+        if self.properties.is_empty() {
+            return vec![];
+        }
+        format!("{:?}", self.properties)
+            .split(" | ")
+            .map(|p| p.to_string())
+            .collect()
+    }
+
+    pub fn to_json(&self) -> Value {
+        json!({
+            "num_states": self.num_states,
+            "num_trs": self.num_trs,
+            "num_final_states": self.num_final_states,
+            "start": self.start,
+            "properties": self.property_names(),
+            "num_input_symbols": self.num_input_symbols,
+            "num_output_symbols": self.num_output_symbols,
+        })
+    }
+}
+
+impl fmt::Display for FstInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "# of states\t\t{}", self.num_states)?;
+        writeln!(f, "# of trs\t\t{}", self.num_trs)?;
+        writeln!(f, "# of final states\t{}", self.num_final_states)?;
+        match self.start {
+            Some(s) => writeln!(f, "initial state\t\t{}", s)?,
+            None => writeln!(f, "initial state\t\tnone")?,
+        }
+        if let Some(n) = self.num_input_symbols {
+            writeln!(f, "input symbols\t\t{}", n)?;
+        }
+        if let Some(n) = self.num_output_symbols {
+            writeln!(f, "output symbols\t\t{}", n)?;
+        }
+        write!(f, "properties\t\t{}", self.property_names().join(" "))
+    }
+}
+
+pub fn info(path_in: &str, json: bool) -> Result<()> {
+    let fst = VectorFst::<TropicalWeight>::read(path_in)?;
+    let info = FstInfo::new(&fst)?;
+    if json {
+        println!("{}", serde_json::to_string_pretty(&info.to_json())?);
+    } else {
+        println!("{}", info);
+    }
+    Ok(())
+}
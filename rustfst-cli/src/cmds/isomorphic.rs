@@ -0,0 +1,21 @@
+use anyhow::Result;
+use serde_json::json;
+
+use rustfst::algorithms::{isomorphic_with_config, IsomorphicConfig};
+use rustfst::prelude::*;
+
+/// Checks whether two FSTs are isomorphic and prints the result.
+pub fn isomorphic_fsts(path_in_1: &str, path_in_2: &str, delta: f32, json: bool) -> Result<()> {
+    let fst_1 = VectorFst::<TropicalWeight>::read(path_in_1)?;
+    let fst_2 = VectorFst::<TropicalWeight>::read(path_in_2)?;
+    let res = isomorphic_with_config(&fst_1, &fst_2, IsomorphicConfig::new(delta))?;
+    if json {
+        let value = json!({ "isomorphic": res, "delta": delta });
+        println!("{}", serde_json::to_string_pretty(&value)?);
+    } else if res {
+        println!("FSTs are isomorphic");
+    } else {
+        println!("FSTs are not isomorphic");
+    }
+    Ok(())
+}
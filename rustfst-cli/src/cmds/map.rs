@@ -1,6 +1,7 @@
 use anyhow::{bail, Result};
 use unsafe_unwrap::UnsafeUnwrap;
 
+use rustfst::algorithms::{tr_map, tr_mappers, tr_sum, tr_unique};
 use rustfst::prelude::*;
 
 use crate::unary_fst_algorithm::UnaryFstAlgorithm;
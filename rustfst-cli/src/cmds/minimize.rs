@@ -1,3 +1,4 @@
+use rustfst::algorithms::{minimize_with_config, MinimizeConfig};
 use rustfst::prelude::*;
 
 use crate::unary_fst_algorithm::UnaryFstAlgorithm;
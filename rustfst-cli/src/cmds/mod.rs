@@ -1,12 +1,20 @@
+#[cfg(feature = "bench")]
+pub mod bench;
 pub mod compose;
 pub mod connect;
+pub mod coverage;
 pub mod determinize;
+pub mod equal;
+pub mod info;
 pub mod invert;
+pub mod isomorphic;
 pub mod map;
 pub mod minimize;
 pub mod optimize;
 pub mod project;
+pub mod prune;
 pub mod push;
+pub mod randgen;
 pub mod reverse;
 pub mod rm_final_epsilon;
 pub mod shortest_path;
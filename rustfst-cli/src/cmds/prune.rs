@@ -0,0 +1,62 @@
+use anyhow::Result;
+
+use rustfst::algorithms::{prune_with_config, PruneConfig};
+use rustfst::prelude::*;
+use rustfst::KDELTA;
+
+use crate::unary_fst_algorithm::UnaryFstAlgorithm;
+
+pub struct PruneAlgorithm {
+    path_in: String,
+    path_out: String,
+    weight_threshold: Option<f32>,
+    state_threshold: Option<StateId>,
+    delta: f32,
+}
+
+impl UnaryFstAlgorithm for PruneAlgorithm {
+    fn get_path_in(&self) -> &str {
+        self.path_in.as_str()
+    }
+
+    fn get_path_out(&self) -> &str {
+        self.path_out.as_str()
+    }
+
+    fn get_algorithm_name(&self) -> String {
+        "prune".to_string()
+    }
+
+    fn run_algorithm(
+        &self,
+        mut fst: VectorFst<TropicalWeight>,
+    ) -> Result<VectorFst<TropicalWeight>> {
+        let weight_threshold = self
+            .weight_threshold
+            .map_or_else(TropicalWeight::zero, TropicalWeight::new);
+        let config = PruneConfig::new(weight_threshold, self.state_threshold, self.delta);
+        prune_with_config(&mut fst, config)?;
+        Ok(fst)
+    }
+}
+
+impl PruneAlgorithm {
+    pub fn new(
+        path_in: &str,
+        path_out: &str,
+        weight_threshold: Option<f32>,
+        state_threshold: Option<StateId>,
+    ) -> Self {
+        Self {
+            path_in: path_in.to_string(),
+            path_out: path_out.to_string(),
+            weight_threshold,
+            state_threshold,
+            delta: KDELTA,
+        }
+    }
+
+    pub fn with_delta(self, delta: f32) -> Self {
+        Self { delta, ..self }
+    }
+}
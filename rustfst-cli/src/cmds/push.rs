@@ -1,6 +1,8 @@
 use anyhow::Result;
 
+use rustfst::algorithms::{push_with_config, PushConfig, PushType, ReweightType};
 use rustfst::prelude::*;
+use rustfst::KDELTA;
 
 use crate::unary_fst_algorithm::UnaryFstAlgorithm;
 
@@ -9,6 +11,7 @@ pub struct PushAlgorithm {
     path_out: String,
     push_type: PushType,
     reweight_type: ReweightType,
+    delta: f32,
 }
 
 impl UnaryFstAlgorithm for PushAlgorithm {
@@ -25,7 +28,12 @@ impl UnaryFstAlgorithm for PushAlgorithm {
     }
 
     fn run_algorithm(&self, fst: VectorFst<TropicalWeight>) -> Result<VectorFst<TropicalWeight>> {
-        push(&fst, self.reweight_type, self.push_type)
+        push_with_config(
+            &fst,
+            self.reweight_type,
+            self.push_type,
+            PushConfig::new(self.delta),
+        )
     }
 }
 
@@ -61,6 +69,11 @@ impl PushAlgorithm {
             } else {
                 ReweightType::ReweightToInitial
             },
+            delta: KDELTA,
         }
     }
+
+    pub fn with_delta(self, delta: f32) -> Self {
+        Self { delta, ..self }
+    }
 }
@@ -0,0 +1,109 @@
+use anyhow::Result;
+
+use rustfst::algorithms::randgen::{
+    randgen_with_config, LogProbTrSelector, RandGenConfig, TrSelector, UniformTrSelector,
+};
+use rustfst::prelude::*;
+
+use crate::unary_fst_algorithm::UnaryFstAlgorithm;
+
+/// How the transitions are selected at each state, as the `--select` of `fstrandgen`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RandGenSelect {
+    Uniform,
+    LogProb,
+}
+
+pub struct RandGenAlgorithm {
+    path_in: String,
+    path_out: String,
+    select: RandGenSelect,
+    seed: Option<u64>,
+    max_length: usize,
+    npath: usize,
+    weighted: bool,
+    remove_total_weight: bool,
+}
+
+impl UnaryFstAlgorithm for RandGenAlgorithm {
+    fn get_path_in(&self) -> &str {
+        self.path_in.as_str()
+    }
+
+    fn get_path_out(&self) -> &str {
+        self.path_out.as_str()
+    }
+
+    fn get_algorithm_name(&self) -> String {
+        "randgen".to_string()
+    }
+
+    fn run_algorithm(&self, fst: VectorFst<TropicalWeight>) -> Result<VectorFst<TropicalWeight>> {
+        match (self.select, self.seed) {
+            (RandGenSelect::Uniform, Some(seed)) => {
+                self.randgen(&fst, UniformTrSelector::from_seed(seed))
+            }
+            (RandGenSelect::Uniform, None) => self.randgen(&fst, UniformTrSelector::new()),
+            (RandGenSelect::LogProb, Some(seed)) => {
+                self.randgen(&fst, LogProbTrSelector::from_seed(seed))
+            }
+            (RandGenSelect::LogProb, None) => self.randgen(&fst, LogProbTrSelector::new()),
+        }
+    }
+}
+
+impl RandGenAlgorithm {
+    pub fn new(path_in: &str, path_out: &str, select: &str) -> Self {
+        let select = match select {
+            "uniform" => RandGenSelect::Uniform,
+            "log_prob" => RandGenSelect::LogProb,
+            _ => panic!("Unexpected select type : {}", select),
+        };
+        Self {
+            path_in: path_in.to_string(),
+            path_out: path_out.to_string(),
+            select,
+            seed: None,
+            max_length: usize::MAX,
+            npath: 1,
+            weighted: false,
+            remove_total_weight: false,
+        }
+    }
+
+    pub fn with_seed(self, seed: Option<u64>) -> Self {
+        Self { seed, ..self }
+    }
+
+    pub fn with_max_length(self, max_length: usize) -> Self {
+        Self { max_length, ..self }
+    }
+
+    pub fn with_npath(self, npath: usize) -> Self {
+        Self { npath, ..self }
+    }
+
+    pub fn with_weighted(self, weighted: bool) -> Self {
+        Self { weighted, ..self }
+    }
+
+    pub fn with_remove_total_weight(self, remove_total_weight: bool) -> Self {
+        Self {
+            remove_total_weight,
+            ..self
+        }
+    }
+
+    fn randgen<S: TrSelector>(
+        &self,
+        fst: &VectorFst<TropicalWeight>,
+        selector: S,
+    ) -> Result<VectorFst<TropicalWeight>> {
+        let config = RandGenConfig::new(selector)
+            .with_max_length(self.max_length)
+            .with_npath(self.npath)
+            .with_weighted(self.weighted)
+            .with_remove_total_weight(self.remove_total_weight);
+        randgen_with_config(fst, config)
+    }
+}
@@ -1,5 +1,6 @@
 use anyhow::Result;
 
+use rustfst::algorithms::rm_final_epsilon;
 use rustfst::prelude::*;
 
 use crate::unary_fst_algorithm::UnaryFstAlgorithm;
@@ -1,7 +1,14 @@
+use std::cmp::Ordering;
+use std::fmt;
+use std::sync::Arc;
+
+use anyhow::{format_err, Result};
+use serde_json::{json, Value};
+
+use rustfst::algorithms::{shortest_path_with_config, ShortestPathConfig};
 use rustfst::prelude::*;
 
 use crate::unary_fst_algorithm::UnaryFstAlgorithm;
-use anyhow::Result;
 
 pub struct ShortestPathAlgorithm {
     path_in: String,
@@ -41,3 +48,114 @@ impl ShortestPathAlgorithm {
         }
     }
 }
+
+/// A path of the output of the shortest path algorithm. The strings are only available when
+/// the FST has the corresponding symbol table.
+pub struct DecodedPath {
+    pub ilabels: Vec<Label>,
+    pub olabels: Vec<Label>,
+    pub istring: Option<String>,
+    pub ostring: Option<String>,
+    pub weight: f32,
+}
+
+impl DecodedPath {
+    pub fn to_json(&self) -> Value {
+        json!({
+            "ilabels": self.ilabels,
+            "olabels": self.olabels,
+            "istring": self.istring,
+            "ostring": self.ostring,
+            "weight": self.weight,
+        })
+    }
+}
+
+fn labels_to_string(labels: &[Label]) -> String {
+    labels
+        .iter()
+        .map(|l| l.to_string())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+impl fmt::Display for DecodedPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let istring = self
+            .istring
+            .clone()
+            .unwrap_or_else(|| labels_to_string(&self.ilabels));
+        let ostring = self
+            .ostring
+            .clone()
+            .unwrap_or_else(|| labels_to_string(&self.olabels));
+        write!(f, "{}\t{}\t{}", istring, ostring, self.weight)
+    }
+}
+
+fn decode(symt: Option<&Arc<SymbolTable>>, labels: &[Label]) -> Result<Option<String>> {
+    let symt = match symt {
+        Some(symt) => symt,
+        None => return Ok(None),
+    };
+    let symbols = labels
+        .iter()
+        .filter(|l| **l != EPS_LABEL)
+        .map(|l| {
+            symt.get_symbol(*l)
+                .ok_or_else(|| format_err!("Label {} is missing from the symbol table", l))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    Ok(Some(symbols.join(" ")))
+}
+
+/// Lists the paths of the FST from the best one to the worst one.
+pub fn decode_paths(fst: &VectorFst<TropicalWeight>) -> Result<Vec<DecodedPath>> {
+    let mut paths = vec![];
+    for path in fst.paths_iter() {
+        paths.push(DecodedPath {
+            istring: decode(fst.input_symbols(), &path.ilabels)?,
+            ostring: decode(fst.output_symbols(), &path.olabels)?,
+            ilabels: path.ilabels,
+            olabels: path.olabels,
+            weight: *path.weight.value(),
+        });
+    }
+    paths.sort_by(|a, b| a.weight.partial_cmp(&b.weight).unwrap_or(Ordering::Equal));
+    Ok(paths)
+}
+
+/// Paths of the output of the shortest path algorithm, with their labels and weights.
+pub fn paths_to_json(paths: &[DecodedPath]) -> Value {
+    let paths: Vec<Value> = paths.iter().map(|p| p.to_json()).collect();
+    json!({ "paths": paths })
+}
+
+/// Runs the shortest path algorithm, writes the output FST then prints its paths on the
+/// standard output, as text (one `input output weight` line per path) or as JSON. The symbol
+/// tables given as text files replace the ones of the FST.
+pub fn run_and_print_paths(
+    algorithm: &ShortestPathAlgorithm,
+    isymbols: Option<&str>,
+    osymbols: Option<&str>,
+    json: bool,
+) -> Result<()> {
+    let mut fst = algorithm.run_algorithm(algorithm.read()?)?;
+    if let Some(path) = isymbols {
+        fst.set_input_symbols(Arc::new(SymbolTable::read_text(path)?));
+    }
+    if let Some(path) = osymbols {
+        fst.set_output_symbols(Arc::new(SymbolTable::read_text(path)?));
+    }
+    algorithm.write(&fst)?;
+
+    let paths = decode_paths(&fst)?;
+    if json {
+        println!("{}", serde_json::to_string_pretty(&paths_to_json(&paths))?);
+    } else {
+        for path in paths.iter() {
+            println!("{}", path);
+        }
+    }
+    Ok(())
+}
@@ -1,3 +1,4 @@
+use rustfst::algorithms::top_sort;
 use rustfst::prelude::*;
 
 use anyhow::Result;
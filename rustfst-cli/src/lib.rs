@@ -0,0 +1,43 @@
+//! Command line interface of rustfst.
+//!
+//! The subcommands are registered in a [`Cli`] through the [`Subcommand`] trait, so that other
+//! crates can build a CLI with their own subcommands while reusing the builtin ones and the
+//! reading, writing and benchmarking of the FSTs.
+//!
+//! ```no_run
+//! use anyhow::Result;
+//! use clap::{App, ArgMatches, SubCommand};
+//! use rustfst_cli::{one_in_one_out_options, Cli, Subcommand};
+//!
+//! struct Lattice;
+//!
+//! impl Subcommand for Lattice {
+//!     fn name(&self) -> &'static str {
+//!         "lattice"
+//!     }
+//!
+//!     fn command(&self) -> App<'static, 'static> {
+//!         one_in_one_out_options(SubCommand::with_name("lattice").about("Lattice tool."))
+//!     }
+//!
+//!     fn run(&self, m: &ArgMatches) -> Result<()> {
+//!         println!("{}", m.value_of("in.fst").unwrap());
+//!         Ok(())
+//!     }
+//! }
+//!
+//! fn main() {
+//!     let mut cli = Cli::new();
+//!     cli.register(Lattice);
+//!     cli.run();
+//! }
+//! ```
+
+pub mod binary_fst_algorithm;
+mod builtins;
+pub mod cmds;
+pub mod script;
+pub mod subcommand;
+pub mod unary_fst_algorithm;
+
+pub use crate::subcommand::{one_in_one_out_options, two_in_one_out_options, Cli, Subcommand};
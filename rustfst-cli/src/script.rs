@@ -0,0 +1,359 @@
+//! Script mode : runs a sequence of operations on named in-memory FSTs, so that the
+//! intermediate results of a pipeline don't have to be written to disk and parsed again.
+//!
+//! Each line of a script is either an assignment `name = operation arguments...` or a
+//! `write name path` statement. Empty lines and lines starting with `#` are ignored.
+//!
+//! ```text
+//! # Builds a determinized and minimized lexicon composed with a grammar.
+//! lexicon = read lexicon.fst
+//! grammar = read grammar.fst
+//! lexicon = determinize lexicon nonfunctional
+//! lexicon = minimize lexicon allow_nondet
+//! graph = compose lexicon grammar
+//! write graph graph.fst
+//! ```
+//!
+//! Available operations :
+//! - `read <path>`
+//! - `compose <fst_1> <fst_2> [default|lookahead]`
+//! - `determinize <fst> [functional|nonfunctional|disambiguate]`
+//! - `minimize <fst> [allow_nondet]`
+//! - `project <fst> [input|output]`
+//! - `tr_sort <fst> [ilabel|olabel]`
+//! - `shortestpath <fst> [<nshortest>] [unique]`
+//! - `map <fst> <map_type> [<weight>]`
+//! - `push <fst> [to_final] [push_weights] [push_labels] [remove_total_weight] [remove_common_affix]`
+//! - `connect`, `invert`, `optimize`, `reverse`, `rmfinalepsilon` and `topsort` taking a
+//!   single FST.
+
+use std::collections::HashMap;
+use std::fs::read_to_string;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Instant;
+
+use anyhow::{bail, format_err, Context, Result};
+use colored::Colorize;
+use log::{debug, info};
+
+use rustfst::prelude::*;
+
+use crate::binary_fst_algorithm::BinaryFstAlgorithm;
+use crate::cmds::compose::ComposeAlgorithm;
+use crate::cmds::connect::ConnectAlgorithm;
+use crate::cmds::determinize::DeterminizeAlgorithm;
+use crate::cmds::invert::InvertAlgorithm;
+use crate::cmds::map::MapAlgorithm;
+use crate::cmds::minimize::MinimizeAlgorithm;
+use crate::cmds::optimize::OptimizeAlgorithm;
+use crate::cmds::project::ProjectFstAlgorithm;
+use crate::cmds::push::PushAlgorithm;
+use crate::cmds::reverse::ReverseAlgorithm;
+use crate::cmds::rm_final_epsilon::RmFinalEpsilonAlgorithm;
+use crate::cmds::shortest_path::ShortestPathAlgorithm;
+use crate::cmds::topsort::TopsortAlgorithm;
+use crate::cmds::tr_sort::TrsortAlgorithm;
+use crate::unary_fst_algorithm::UnaryFstAlgorithm;
+
+type ScriptFst = VectorFst<TropicalWeight>;
+
+/// FSTs held by the variables of a script.
+type Variables = HashMap<String, Arc<ScriptFst>>;
+
+/// Takes the FST out of the `Arc`, copying it only if it is still shared.
+fn into_owned(fst: Arc<ScriptFst>) -> ScriptFst {
+    Arc::try_unwrap(fst).unwrap_or_else(|fst| (*fst).clone())
+}
+
+const MAP_TYPES: &[&str] = &[
+    "arc_sum",
+    "arc_unique",
+    "tr_sum",
+    "tr_unique",
+    "identity",
+    "input_epsilon",
+    "invert",
+    "output_epsilon",
+    "plus",
+    "quantize",
+    "rmweight",
+    "times",
+];
+
+/// A line of a script.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Statement {
+    /// Line number, starting at 1.
+    pub line: usize,
+    /// Variable assigned with the result of the operation. `None` for `write`.
+    pub output: Option<String>,
+    pub operation: String,
+    pub args: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Script {
+    pub statements: Vec<Statement>,
+}
+
+impl Script {
+    pub fn parse(text: &str) -> Result<Self> {
+        let mut statements = vec![];
+        for (idx, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let tokens: Vec<&str> = line.split_whitespace().collect();
+            let statement = match tokens.as_slice() {
+                [output, "=", operation, args @ ..] => Statement {
+                    line: idx + 1,
+                    output: Some(output.to_string()),
+                    operation: operation.to_string(),
+                    args: args.iter().map(|a| a.to_string()).collect(),
+                },
+                ["write", args @ ..] => Statement {
+                    line: idx + 1,
+                    output: None,
+                    operation: "write".to_string(),
+                    args: args.iter().map(|a| a.to_string()).collect(),
+                },
+                _ => bail!(
+                    "Line {} : expected `name = operation ...` or `write name path`",
+                    idx + 1
+                ),
+            };
+            statements.push(statement);
+        }
+        Ok(Self { statements })
+    }
+
+    pub fn read<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let text = read_to_string(path.as_ref())
+            .with_context(|| format!("Can't open script : {:?}", path.as_ref()))?;
+        Self::parse(&text)
+    }
+
+    /// Runs the statements in order and returns the FSTs held by the variables at the end
+    /// of the script.
+    pub fn run(&self) -> Result<HashMap<String, ScriptFst>> {
+        let mut variables = Variables::new();
+        for statement in self.statements.iter() {
+            info!(
+                "Line {} : running {}",
+                statement.line,
+                statement.operation.blue()
+            );
+            let start = Instant::now();
+            run_statement(statement, &mut variables).with_context(|| {
+                format!("Line {} : {} failed", statement.line, statement.operation)
+            })?;
+            debug!("Duration : {:?}", start.elapsed());
+        }
+        Ok(variables
+            .into_iter()
+            .map(|(name, fst)| (name, into_owned(fst)))
+            .collect())
+    }
+}
+
+fn check_option(option: &str, allowed: &[&str]) -> Result<()> {
+    if !allowed.contains(&option) {
+        bail!(
+            "Unexpected option {}, expected one of {:?}",
+            option,
+            allowed
+        );
+    }
+    Ok(())
+}
+
+fn run_statement(statement: &Statement, variables: &mut Variables) -> Result<()> {
+    let args: Vec<&str> = statement.args.iter().map(|a| a.as_str()).collect();
+    // The operations consume their operands : an operand is copied only if its variable
+    // still holds it afterwards. The variable reassigned by the statement is released, so
+    // that e.g. `a = connect a` runs on the FST of `a` without copying it, unless the
+    // statement reads it several times.
+    let mut reassigned = match &statement.output {
+        Some(output) if args.iter().filter(|a| *a == output).count() == 1 => {
+            variables.remove(output)
+        }
+        _ => None,
+    };
+    let mut get = |name: &str| -> Result<Arc<ScriptFst>> {
+        if statement.output.as_deref() == Some(name) {
+            if let Some(fst) = reassigned.take() {
+                return Ok(fst);
+            }
+        }
+        variables
+            .get(name)
+            .map(Arc::clone)
+            .ok_or_else(|| format_err!("Unknown variable {}", name))
+    };
+    let mut operand = |name: &str| get(name).map(into_owned);
+
+    let fst = match (statement.operation.as_str(), args.as_slice()) {
+        ("write", [name, path]) => {
+            return variables
+                .get(*name)
+                .ok_or_else(|| format_err!("Unknown variable {}", name))?
+                .write(path);
+        }
+        ("read", [path]) => ScriptFst::read(path)?,
+        ("compose", [fst_1, fst_2, options @ ..]) => {
+            let compose_type = options.first().cloned().unwrap_or("default");
+            check_option(compose_type, &["default", "lookahead"])?;
+            ComposeAlgorithm::new("", "", "", compose_type)
+                .run_algorithm(operand(fst_1)?, operand(fst_2)?)?
+        }
+        ("determinize", [fst, options @ ..]) => {
+            let det_type = options.first().cloned().unwrap_or("functional");
+            check_option(det_type, &["functional", "nonfunctional", "disambiguate"])?;
+            DeterminizeAlgorithm::new("", "", det_type).run_algorithm(operand(fst)?)?
+        }
+        ("minimize", [fst, options @ ..]) => {
+            for option in options {
+                check_option(option, &["allow_nondet"])?;
+            }
+            MinimizeAlgorithm::new("", options.contains(&"allow_nondet"), "")
+                .run_algorithm(operand(fst)?)?
+        }
+        ("project", [fst, options @ ..]) => {
+            let project_type = options.first().cloned().unwrap_or("input");
+            check_option(project_type, &["input", "output"])?;
+            ProjectFstAlgorithm::new("", project_type == "output", "")
+                .run_algorithm(operand(fst)?)?
+        }
+        ("tr_sort", [fst, options @ ..]) => {
+            let sort_type = options.first().cloned().unwrap_or("ilabel");
+            check_option(sort_type, &["ilabel", "olabel"])?;
+            TrsortAlgorithm::new("", sort_type, "").run_algorithm(operand(fst)?)?
+        }
+        ("shortestpath", [fst, options @ ..]) => {
+            let mut nshortest = 1;
+            let mut unique = false;
+            for option in options {
+                if *option == "unique" {
+                    unique = true;
+                } else {
+                    nshortest = option
+                        .parse()
+                        .map_err(|_| format_err!("Unexpected option {}", option))?;
+                }
+            }
+            ShortestPathAlgorithm::new("", unique, nshortest, "").run_algorithm(operand(fst)?)?
+        }
+        ("map", [fst, map_type, options @ ..]) => {
+            check_option(map_type, MAP_TYPES)?;
+            let weight = options.first().cloned();
+            if let Some(w) = weight {
+                w.parse::<f32>()
+                    .map_err(|_| format_err!("Can't parse weight {}", w))?;
+            } else if *map_type == "plus" || *map_type == "times" {
+                bail!("map {} requires a weight", map_type);
+            }
+            MapAlgorithm::new("", map_type, weight, "").run_algorithm(operand(fst)?)?
+        }
+        ("push", [fst, options @ ..]) => {
+            let flags = [
+                "to_final",
+                "push_weights",
+                "push_labels",
+                "remove_total_weight",
+                "remove_common_affix",
+            ];
+            for option in options {
+                check_option(option, &flags)?;
+            }
+            let has = |flag: &str| options.contains(&flag);
+            PushAlgorithm::new(
+                "",
+                "",
+                has("to_final"),
+                has("push_weights"),
+                has("push_labels"),
+                has("remove_total_weight"),
+                has("remove_common_affix"),
+            )
+            .run_algorithm(operand(fst)?)?
+        }
+        ("connect", [fst]) => ConnectAlgorithm::new("", "").run_algorithm(operand(fst)?)?,
+        ("invert", [fst]) => InvertAlgorithm::new("", "").run_algorithm(operand(fst)?)?,
+        ("optimize", [fst]) => OptimizeAlgorithm::new("", "").run_algorithm(operand(fst)?)?,
+        ("reverse", [fst]) => ReverseAlgorithm::new("", "").run_algorithm(operand(fst)?)?,
+        ("rmfinalepsilon", [fst]) => {
+            RmFinalEpsilonAlgorithm::new("", "").run_algorithm(operand(fst)?)?
+        }
+        ("topsort", [fst]) => TopsortAlgorithm::new("", "").run_algorithm(operand(fst)?)?,
+        (operation, _) => bail!(
+            "Unknown operation or wrong number of arguments : {} {:?}",
+            operation,
+            args
+        ),
+    };
+
+    let output = statement
+        .output
+        .clone()
+        .ok_or_else(|| format_err!("The result of {} must be assigned", statement.operation))?;
+    variables.insert(output, Arc::new(fst));
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_script() -> Result<()> {
+        let script = Script::parse(
+            "# comment\n\na = read a.fst\nb = determinize a nonfunctional\nwrite b b.fst\n",
+        )?;
+        assert_eq!(script.statements.len(), 3);
+        assert_eq!(script.statements[1].line, 4);
+        assert_eq!(script.statements[1].output, Some("b".to_string()));
+        assert_eq!(script.statements[1].operation, "determinize");
+        assert_eq!(script.statements[1].args, vec!["a", "nonfunctional"]);
+        assert_eq!(script.statements[2].output, None);
+
+        assert!(Script::parse("determinize a\n").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_run_unknown_variable() -> Result<()> {
+        let script = Script::parse("b = connect a\n")?;
+        assert!(script.run().is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_run_statement_operands() -> Result<()> {
+        let mut fst = ScriptFst::new();
+        let s0 = fst.add_state();
+        let s1 = fst.add_state();
+        fst.set_start(s0)?;
+        fst.set_final(s1, TropicalWeight::one())?;
+        fst.emplace_tr(s0, 1, 1, TropicalWeight::one(), s1)?;
+        // Not coaccessible.
+        fst.add_state();
+
+        let mut variables = Variables::new();
+        variables.insert("a".to_string(), Arc::new(fst.clone()));
+        let script = Script::parse("b = connect a\na = connect a\nc = compose a a\n")?;
+
+        run_statement(&script.statements[0], &mut variables)?;
+        assert_eq!(*variables["a"], fst);
+        assert_eq!(variables["b"].num_states(), 2);
+
+        run_statement(&script.statements[1], &mut variables)?;
+        assert_eq!(variables["a"], variables["b"]);
+
+        run_statement(&script.statements[2], &mut variables)?;
+        assert_eq!(variables["a"], variables["b"]);
+        assert_eq!(variables["c"].num_states(), 2);
+        Ok(())
+    }
+}
@@ -0,0 +1,186 @@
+use std::ffi::OsString;
+use std::process;
+
+use anyhow::{format_err, Result};
+use clap::{App, Arg, ArgMatches};
+use log::error;
+
+use crate::builtins::builtin_subcommands;
+
+/// A subcommand of the CLI. External crates can implement this trait and register their
+/// subcommands with [`Cli::register`] to build their own CLI on top of the existing one.
+///
+/// The reading and writing of the FSTs as well as the benchmark mode can be reused by
+/// implementing `UnaryFstAlgorithm` or `BinaryFstAlgorithm` and calling `run_cli_or_bench`
+/// from [`Subcommand::run`].
+pub trait Subcommand {
+    /// Name of the subcommand on the command line.
+    fn name(&self) -> &'static str;
+
+    /// Declares the subcommand and its arguments. Use [`one_in_one_out_options`] or
+    /// [`two_in_one_out_options`] to add the usual paths and benchmark options.
+    fn command(&self) -> App<'static, 'static>;
+
+    /// Runs the subcommand with the arguments parsed from the command line.
+    fn run(&self, m: &ArgMatches) -> Result<()>;
+}
+
+/// The command line interface : the set of the registered subcommands.
+pub struct Cli {
+    subcommands: Vec<Box<dyn Subcommand>>,
+}
+
+impl Default for Cli {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Cli {
+    /// CLI with all the builtin subcommands.
+    pub fn new() -> Self {
+        Self {
+            subcommands: builtin_subcommands(),
+        }
+    }
+
+    /// CLI without any subcommand.
+    pub fn empty() -> Self {
+        Self {
+            subcommands: vec![],
+        }
+    }
+
+    /// Registers a subcommand. It replaces any subcommand with the same name.
+    pub fn register<S: Subcommand + 'static>(&mut self, subcommand: S) -> &mut Self {
+        self.subcommands.retain(|s| s.name() != subcommand.name());
+        self.subcommands.push(Box::new(subcommand));
+        self
+    }
+
+    pub fn subcommand_names(&self) -> Vec<&'static str> {
+        self.subcommands.iter().map(|s| s.name()).collect()
+    }
+
+    pub fn app(&self) -> App<'static, 'static> {
+        let mut app = App::new("rustfst")
+            .version("1.0")
+            .author("Alexandre Caulier <alexandre.caulier@protonmail.com>")
+            .about("Rustfst CLI");
+        for subcommand in self.subcommands.iter() {
+            app = app.subcommand(subcommand.command());
+        }
+        app
+    }
+
+    /// Dispatches the parsed command line to the matching subcommand.
+    pub fn handle(&self, matches: &ArgMatches) -> Result<()> {
+        let (name, m) = matches.subcommand();
+        let m = m.ok_or_else(|| format_err!("Unknown subcommand {}.", name))?;
+        self.subcommands
+            .iter()
+            .find(|s| s.name() == name)
+            .ok_or_else(|| format_err!("Unknown subcommand {}.", name))?
+            .run(m)
+    }
+
+    /// Parses the given arguments and runs the matching subcommand.
+    pub fn run_from<I, T>(&self, args: I) -> Result<()>
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<OsString> + Clone,
+    {
+        let matches = self.app().get_matches_from_safe(args)?;
+        self.handle(&matches)
+    }
+
+    /// Parses the arguments of the process, sets up the logger and runs the matching
+    /// subcommand.
+    pub fn run(&self) {
+        let matches = self.app().get_matches();
+
+        let env = env_logger::Env::default().filter_or(env_logger::DEFAULT_FILTER_ENV, "debug");
+
+        env_logger::Builder::from_env(env)
+            .default_format_timestamp_nanos(true)
+            .init();
+
+        if let Err(e) = self.handle(&matches) {
+            error!("{:?}", e);
+            process::exit(exitcode::OK)
+        }
+    }
+}
+
+pub fn one_in_one_out_options<'a, 'b>(command: clap::App<'a, 'b>) -> clap::App<'a, 'b> {
+    command
+        .version("1.0")
+        .author("Alexandre Caulier <alexandre.caulier@protonmail.com>")
+        .arg(
+            Arg::with_name("in.fst")
+                .help("Path to input fst file.")
+                .required(true),
+        )
+        .arg(
+            Arg::with_name("out.fst")
+                .help("Path to output fst file.")
+                .required(true),
+        ).arg(
+            Arg::with_name("bench")
+                .long("bench")
+                .help("Whether to run multiple times the algorithm in order to have a reliable time measurement.")
+        ).arg(
+            Arg::with_name("n_iters")
+                .long("n_iters")
+                .default_value("10")
+                .help("Number of iterations to run for the benchmark.")
+        ).arg(
+            Arg::with_name("n_warm_ups")
+                .long("n_warm_ups")
+                .default_value("3")
+                .help("Number of warm ups run before the actual benchmark.")
+        ).arg(
+        Arg::with_name("export-markdown")
+            .long("export-markdown")
+            .takes_value(true)
+    )
+}
+
+pub fn two_in_one_out_options<'a, 'b>(command: clap::App<'a, 'b>) -> clap::App<'a, 'b> {
+    command
+        .version("1.0")
+        .author("Alexandre Caulier <alexandre.caulier@protonmail.com>")
+        .arg(
+            Arg::with_name("in_1.fst")
+                .help("Path to the first input fst file.")
+                .required(true),
+        )
+        .arg(
+            Arg::with_name("in_2.fst")
+                .help("Path to the second input fst file.")
+                .required(true),
+        )
+        .arg(
+            Arg::with_name("out.fst")
+                .help("Path to output fst file.")
+                .required(true),
+        ).arg(
+        Arg::with_name("bench")
+            .long("bench")
+            .help("Whether to run multiple times the algorithm in order to have a reliable time measurement.")
+    ).arg(
+        Arg::with_name("n_iters")
+            .long("n_iters")
+            .default_value("10")
+            .help("Number of iterations to run for the benchmark.")
+    ).arg(
+        Arg::with_name("n_warm_ups")
+            .long("n_warm_ups")
+            .default_value("3")
+            .help("Number of warm ups run before the actual benchmark.")
+    ).arg(
+        Arg::with_name("export-markdown")
+            .long("export-markdown")
+            .takes_value(true)
+    )
+}
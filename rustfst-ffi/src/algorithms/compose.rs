@@ -7,8 +7,8 @@ use crate::{get, wrap, CLabel, RUSTFST_FFI_RESULT};
 use ffi_convert::*;
 use rustfst::algorithms::compose::matchers::MatcherRewriteMode;
 use rustfst::algorithms::compose::{
-    compose, compose_with_config, ComposeConfig, ComposeFilterEnum, MatcherConfig,
-    SigmaMatcherConfig,
+    compose, compose_with_config, ComposeConfig, ComposeFilterEnum, ComposeTimesOrder,
+    MatcherConfig, SigmaMatcherConfig,
 };
 use rustfst::fst_impls::VectorFst;
 use rustfst::semirings::TropicalWeight;
@@ -53,6 +53,35 @@ impl CReprOf<ComposeFilterEnum> for CComposeFilterEnum {
     }
 }
 
+#[derive(RawPointerConverter, Debug)]
+pub struct CComposeTimesOrder(pub(crate) usize);
+
+impl AsRust<ComposeTimesOrder> for CComposeTimesOrder {
+    fn as_rust(&self) -> Result<ComposeTimesOrder, AsRustError> {
+        match self.0 {
+            0 => Ok(ComposeTimesOrder::Fst1Fst2),
+            1 => Ok(ComposeTimesOrder::Fst2Fst1),
+            _ => Err(AsRustError::Other(Box::new(EnumConversionError {}))),
+        }
+    }
+}
+
+impl CDrop for CComposeTimesOrder {
+    fn do_drop(&mut self) -> Result<(), CDropError> {
+        Ok(())
+    }
+}
+
+impl CReprOf<ComposeTimesOrder> for CComposeTimesOrder {
+    fn c_repr_of(value: ComposeTimesOrder) -> Result<CComposeTimesOrder, CReprOfError> {
+        let variant = match value {
+            ComposeTimesOrder::Fst1Fst2 => 0,
+            ComposeTimesOrder::Fst2Fst1 => 1,
+        };
+        Ok(CComposeTimesOrder(variant))
+    }
+}
+
 #[derive(RawPointerConverter, Debug, Clone)]
 pub struct CMatcherRewriteMode(pub(crate) usize);
 
@@ -167,6 +196,7 @@ pub struct CComposeConfig {
     pub connect: bool,
     pub matcher1_config: CMatcherConfig,
     pub matcher2_config: CMatcherConfig,
+    pub times_order: CComposeTimesOrder,
 }
 
 #[derive(Debug)]
@@ -255,6 +285,7 @@ pub extern "C" fn fst_compose_config_new(
             matcher2_config,
             compose_filter: CComposeFilterEnum(compose_filter as usize),
             connect,
+            times_order: CComposeTimesOrder(0),
         };
         unsafe { *config = compose_config.into_raw_pointer() };
         Ok(())
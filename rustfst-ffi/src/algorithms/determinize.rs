@@ -42,13 +42,27 @@ impl CReprOf<DeterminizeType> for CDeterminizeType {
     }
 }
 
-#[derive(AsRust, CReprOf, CDrop, RawPointerConverter)]
-#[target_type(DeterminizeConfig)]
+#[derive(CDrop, RawPointerConverter)]
 pub struct CDeterminizeConfig {
     delta: f32,
     det_type: CDeterminizeType,
 }
 
+impl AsRust<DeterminizeConfig> for CDeterminizeConfig {
+    fn as_rust(&self) -> Result<DeterminizeConfig, AsRustError> {
+        Ok(DeterminizeConfig::new(self.delta, self.det_type.as_rust()?))
+    }
+}
+
+impl CReprOf<DeterminizeConfig> for CDeterminizeConfig {
+    fn c_repr_of(input: DeterminizeConfig) -> Result<Self, CReprOfError> {
+        Ok(Self {
+            delta: input.delta,
+            det_type: CDeterminizeType::c_repr_of(input.det_type)?,
+        })
+    }
+}
+
 #[no_mangle]
 pub extern "C" fn fst_determinize_config_new(
     delta: libc::c_float,
@@ -8,14 +8,33 @@ use rustfst::algorithms::{shortest_path, shortest_path_with_config, ShortestPath
 use rustfst::fst_impls::VectorFst;
 use rustfst::semirings::TropicalWeight;
 
-#[derive(AsRust, CReprOf, CDrop, RawPointerConverter)]
-#[target_type(ShortestPathConfig)]
+#[derive(CDrop, RawPointerConverter)]
 pub struct CShortestPathConfig {
     delta: f32,
     nshortest: usize,
     unique: bool,
 }
 
+impl AsRust<ShortestPathConfig> for CShortestPathConfig {
+    fn as_rust(&self) -> Result<ShortestPathConfig, AsRustError> {
+        Ok(ShortestPathConfig::new(
+            self.delta,
+            self.nshortest,
+            self.unique,
+        ))
+    }
+}
+
+impl CReprOf<ShortestPathConfig> for CShortestPathConfig {
+    fn c_repr_of(input: ShortestPathConfig) -> Result<Self, CReprOfError> {
+        Ok(Self {
+            delta: input.delta,
+            nshortest: input.nshortest,
+            unique: input.unique,
+        })
+    }
+}
+
 #[no_mangle]
 pub extern "C" fn fst_shortest_path_config_new(
     delta: libc::c_float,
@@ -0,0 +1,137 @@
+//! Algorithms grouped by namespace, e.g. `rustfst::algo::shortest::shortest_path` or
+//! `rustfst::algo::compose::compose`.
+//!
+//! This is the preferred path to the algorithms. `rustfst::algorithms` keeps its flat
+//! re-exports, while the prelude only re-exports the most common algorithms.
+//!
+//! ```
+//! # use anyhow::Result;
+//! # fn main() -> Result<()> {
+//! use rustfst::algo::shortest::shortest_path;
+//! use rustfst::algo::structure::connect;
+//! use rustfst::fst_impls::VectorFst;
+//! use rustfst::fst_traits::{Fst, MutableFst};
+//! use rustfst::semirings::{Semiring, TropicalWeight};
+//! use rustfst::Tr;
+//!
+//! let mut fst = VectorFst::<TropicalWeight>::new();
+//! let s0 = fst.add_state();
+//! let s1 = fst.add_state();
+//! fst.set_start(s0)?;
+//! fst.set_final(s1, TropicalWeight::one())?;
+//! fst.add_tr(s0, Tr::new(1, 1, 2.0, s1))?;
+//! fst.add_tr(s0, Tr::new(2, 2, 1.0, s1))?;
+//! connect(&mut fst)?;
+//! let best: VectorFst<_> = shortest_path(&fst)?;
+//! assert_eq!(best.paths_iter().next().unwrap().ilabels, vec![2]);
+//! # Ok(())
+//! # }
+//! ```
+
+/// Composition of two FSTs, static or lazy, with its matchers and filters.
+pub use crate::algorithms::compose;
+
+/// Determinization, static or lazy.
+pub use crate::algorithms::determinize;
+
+/// Minimization, its combination with the determinization, and the disambiguation.
+pub mod minimize {
+    pub use crate::algorithms::{
+        acceptor_minimize, approx_minimize, approx_minimize_with_config, dafsa_update,
+        disambiguate, minimize, minimize_partition, minimize_with_config, optimize, weighted_dafsa,
+        ApproxMinimizeConfig, DafsaUpdater, MinimizeConfig,
+    };
+}
+
+/// Rational operations : closure, concatenation, union and their variants.
+pub mod rational {
+    pub use crate::algorithms::{closure, concat, union};
+    pub use crate::algorithms::{complement, priority_union};
+    pub use crate::algorithms::{contains, not_followed_by, optionalize, sigma_star};
+}
+
+/// Removal of the epsilon transitions and of the final epsilon transitions, and analysis of
+/// the epsilon cycles.
+pub mod epsilon {
+    pub use crate::algorithms::rm_epsilon::*;
+    pub use crate::algorithms::{
+        collapse_epsilon_cycles, epsilon_cycles, rm_final_epsilon, EpsilonCycle,
+    };
+}
+
+/// Shortest paths and shortest distances.
+pub mod shortest {
+    pub use crate::algorithms::{
+        all_pairs_shortest_distance, all_pairs_shortest_distance_with_filter,
+        linear_compose_shortest_path, prune, prune_fst, prune_trs, prune_with_config,
+        shortest_distance, shortest_distance_with_config, shortest_distance_with_queue,
+        shortest_path, shortest_path_with_config, shortest_path_with_queue, PruneConfig,
+        ShortestDistanceConfig, ShortestPathConfig, ShortestPathsIterator, WeightGraph,
+    };
+}
+
+/// Transformations of the structure of an FST : trimming, sorting, inversion, projection,
+/// reversal and conversion to another FST type.
+pub mod structure {
+    pub use crate::algorithms::{
+        add_super_final_state, condense, connect, fst_convert, fst_convert_from_ref, hot_tr_sort,
+        invert, project, relabel_pairs, reverse, state_sort, top_sort, tr_sort, tr_sum, tr_unique,
+        ProjectType,
+    };
+}
+
+/// Pushing, reweighting and conversion of the weights, and their transition matrices.
+pub mod weights {
+    pub use crate::algorithms::factor_weight;
+    pub use crate::algorithms::weight_converters;
+    pub use crate::algorithms::{
+        push, push_weights, push_weights_with_config, push_with_config, quantize_sweep, reweight,
+        weight_convert, LinearRepresentation, PushConfig, PushType, PushWeightsConfig,
+        QuantizeSweepPoint, QuantizeSweepReport, ReweightType, TransitionMatrix, WeightConverter,
+    };
+}
+
+/// Mapping of the transitions and final weights.
+pub mod map {
+    pub use crate::algorithms::tr_filters;
+    pub use crate::algorithms::tr_mappers;
+    pub use crate::algorithms::{tr_map, FinalTr, MapFinalAction, TrMapper};
+}
+
+/// Comparisons of FSTs and of transitions.
+pub mod compare {
+    pub use crate::algorithms::tr_compares;
+    pub use crate::algorithms::{
+        canonicalize, content_hash, content_hash_with_config, equal, equal_with_config, equivalent,
+        equivalent_with_config, fingerprint, isomorphic, isomorphic_with_config, ContentHashConfig,
+        EqualConfig, EquivalentConfig, IsomorphicConfig,
+    };
+}
+
+/// Application of an FST to sequences of labels.
+pub mod apply {
+    pub use crate::algorithms::{
+        accepts, coverage, longest_match, rewrite_leftmost_longest, score_sequence, sequentialize,
+        string_coverage, transduce, CoverageReport, SequentializeConfig, StreamingApplier,
+        StringCoverage,
+    };
+}
+
+#[cfg(feature = "asr")]
+pub use crate::algorithms::asr;
+pub use crate::algorithms::encode;
+pub use crate::algorithms::flag_diacritics;
+#[cfg(feature = "lattice")]
+pub use crate::algorithms::lattice;
+pub use crate::algorithms::lazy;
+#[cfg(feature = "ngram")]
+pub use crate::algorithms::ngram;
+pub use crate::algorithms::randgen;
+pub use crate::algorithms::replace;
+pub use crate::algorithms::synchronize;
+
+/// Queue disciplines of the shortest distance and of the other traversals.
+pub mod queues {
+    pub use crate::algorithms::queues::*;
+    pub use crate::algorithms::{Queue, QueueType};
+}
@@ -1,5 +1,6 @@
 use anyhow::Result;
 
+use crate::algorithms::tr_filters::{AnyTrFilter, TrFilter};
 use crate::fst_traits::ExpandedFst;
 use crate::fst_traits::Fst;
 use crate::semirings::StarSemiring;
@@ -9,6 +10,12 @@ use crate::Trs;
 /// The shortest distance from `p` to `q` is the ⊕-sum of the weights
 /// of all the paths between `p` and `q`.
 ///
+/// The distances are computed with the generic Floyd-Warshall algorithm (Gauss-Jordan
+/// elimination), the cycles through a state being summed with the `closure` of the
+/// `StarSemiring`, so that the FST may have cycles in any semiring with a closure. It takes a
+/// time cubic and a memory quadratic in the number of states : it is meant for small machines.
+/// See [`all_pairs_shortest_distance_with_filter`] to only follow some of the transitions.
+///
 /// # Example
 /// ```
 /// # #[macro_use] extern crate rustfst;
@@ -42,6 +49,42 @@ pub fn all_pairs_shortest_distance<W, F>(fst: &F) -> Result<Vec<Vec<W>>>
 where
     F: Fst<W> + ExpandedFst<W>,
     W: StarSemiring,
+{
+    all_pairs_shortest_distance_with_filter(fst, &AnyTrFilter {})
+}
+
+/// Compute the shortest distance from each state to every other states, only following the
+/// transitions accepted by `tr_filter`, e.g. the epsilon-closure matrix of an FST with an
+/// `EpsilonTrFilter`. See [`all_pairs_shortest_distance`].
+///
+/// # Example
+/// ```
+/// # use rustfst::semirings::{Semiring, ProbabilityWeight};
+/// # use rustfst::fst_impls::VectorFst;
+/// # use rustfst::fst_traits::MutableFst;
+/// # use rustfst::algorithms::all_pairs_shortest_distance_with_filter;
+/// # use rustfst::algorithms::tr_filters::EpsilonTrFilter;
+/// # use rustfst::{Tr, EPS_LABEL};
+/// # use anyhow::Result;
+/// # fn main() -> Result<()> {
+/// let mut fst = VectorFst::<ProbabilityWeight>::new();
+/// fst.add_states(2);
+/// fst.add_tr(0, Tr::new(EPS_LABEL, EPS_LABEL, 0.5, 1))?;
+/// fst.add_tr(0, Tr::new(1, 1, 0.5, 1))?;
+///
+/// let closure = all_pairs_shortest_distance_with_filter(&fst, &EpsilonTrFilter {})?;
+/// assert_eq!(closure[0][1], ProbabilityWeight::new(0.5));
+/// # Ok(())
+/// # }
+/// ```
+pub fn all_pairs_shortest_distance_with_filter<W, F, A>(
+    fst: &F,
+    tr_filter: &A,
+) -> Result<Vec<Vec<W>>>
+where
+    F: Fst<W> + ExpandedFst<W>,
+    W: StarSemiring,
+    A: TrFilter<W>,
 {
     let num_states = fst.num_states();
 
@@ -50,7 +93,12 @@ where
 
     // Iterator over the wFST to add the weight of the trs
     for state_id in fst.states_iter() {
-        for tr in fst.get_trs(state_id)?.trs() {
+        for tr in fst
+            .get_trs(state_id)?
+            .trs()
+            .iter()
+            .filter(|tr| tr_filter.keep(tr))
+        {
             let nextstate = tr.nextstate;
             let weight = &tr.weight;
 
@@ -79,3 +127,37 @@ where
 
     Ok(d)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::algorithms::tr_filters::EpsilonTrFilter;
+    use crate::fst_impls::VectorFst;
+    use crate::fst_traits::MutableFst;
+    use crate::semirings::{ProbabilityWeight, Semiring};
+    use crate::{Tr, EPS_LABEL};
+
+    #[test]
+    fn test_all_pairs_shortest_distance_epsilon_cycle() -> Result<()> {
+        // An epsilon cycle of weight 0.25 between 0 and 1, and a non-epsilon tr.
+        let mut fst = VectorFst::<ProbabilityWeight>::new();
+        fst.add_states(3);
+        fst.add_tr(0, Tr::new(EPS_LABEL, EPS_LABEL, 0.5, 1))?;
+        fst.add_tr(1, Tr::new(EPS_LABEL, EPS_LABEL, 0.5, 0))?;
+        fst.add_tr(1, Tr::new(1, 1, 0.5, 2))?;
+
+        let closure = all_pairs_shortest_distance_with_filter(&fst, &EpsilonTrFilter {})?;
+        let expected = [[4.0 / 3.0, 2.0 / 3.0, 0.0], [2.0 / 3.0, 4.0 / 3.0, 0.0]];
+        for (row, expected_row) in closure.iter().zip(expected.iter()) {
+            for (w, e) in row.iter().zip(expected_row.iter()) {
+                assert!(w.approx_equal(ProbabilityWeight::new(*e), 1e-5));
+            }
+        }
+        assert_eq!(closure[2][2], ProbabilityWeight::one());
+
+        let distances = all_pairs_shortest_distance(&fst)?;
+        assert!(distances[0][2].approx_equal(ProbabilityWeight::new(1.0 / 3.0), 1e-5));
+        Ok(())
+    }
+}
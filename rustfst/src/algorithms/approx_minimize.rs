@@ -0,0 +1,213 @@
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+use anyhow::Result;
+
+use crate::algorithms::connect;
+use crate::fst_traits::{ExpandedFst, MutableFst};
+use crate::semirings::WeightQuantize;
+use crate::{Label, StateId, Trs, KDELTA};
+
+/// Configuration of [`approx_minimize_with_config`].
+#[derive(Clone, Copy, Debug, PartialOrd, PartialEq)]
+pub struct ApproxMinimizeConfig {
+    depth: usize,
+    delta: f32,
+}
+
+impl ApproxMinimizeConfig {
+    pub fn new(depth: usize, delta: f32) -> Self {
+        Self { depth, delta }
+    }
+
+    pub fn with_depth(self, depth: usize) -> Self {
+        Self { depth, ..self }
+    }
+
+    pub fn with_delta(self, delta: f32) -> Self {
+        Self { delta, ..self }
+    }
+}
+
+/// Quantized final weight and trs of a state, the nextstates being replaced by their classes.
+type Signature<W> = (Option<W>, Vec<(Label, Label, W, usize)>);
+
+/// Classes of the states whose paths of at most `depth` trs have the same labels and the same
+/// weights once quantized, computed by refining the classes one tr deeper at a time.
+fn k_step_classes<W, F>(fst: &F, config: &ApproxMinimizeConfig) -> Result<Vec<usize>>
+where
+    W: WeightQuantize,
+    F: ExpandedFst<W>,
+{
+    let mut classes = vec![0; fst.num_states()];
+    let mut num_classes = 0;
+    for depth in 0..=config.depth {
+        let mut class_ids: HashMap<Signature<W>, usize> = HashMap::new();
+        let mut next_classes = Vec::with_capacity(classes.len());
+        for state in fst.states_range() {
+            let final_weight = fst
+                .final_weight(state)?
+                .map(|w| w.quantize(config.delta))
+                .transpose()?;
+            let mut trs = vec![];
+            if depth > 0 {
+                for tr in fst.get_trs(state)?.trs() {
+                    trs.push((
+                        tr.ilabel,
+                        tr.olabel,
+                        tr.weight.quantize(config.delta)?,
+                        classes[tr.nextstate as usize],
+                    ));
+                }
+                trs.sort_by(|a, b| {
+                    (a.0, a.1, a.3)
+                        .cmp(&(b.0, b.1, b.3))
+                        .then_with(|| a.2.partial_cmp(&b.2).unwrap_or(Ordering::Equal))
+                });
+            }
+            let new_id = class_ids.len();
+            next_classes.push(*class_ids.entry((final_weight, trs)).or_insert(new_id));
+        }
+        classes = next_classes;
+        // Each round refines the classes, the same number of classes means the same classes.
+        if class_ids.len() == num_classes {
+            break;
+        }
+        num_classes = class_ids.len();
+    }
+    Ok(classes)
+}
+
+/// Approximate minimization merging the states indistinguishable up to `depth` trs, with the
+/// weights quantized with `KDELTA`. See [`approx_minimize_with_config`].
+pub fn approx_minimize<W, F>(ifst: &mut F, depth: usize) -> Result<()>
+where
+    W: WeightQuantize,
+    F: MutableFst<W> + ExpandedFst<W>,
+{
+    approx_minimize_with_config(ifst, ApproxMinimizeConfig::new(depth, KDELTA))
+}
+
+/// Approximate minimization merging the states indistinguishable up to `depth` trs, i.e. whose
+/// paths of at most `depth` trs have the same labels and final states, and the same weights
+/// once quantized with `delta` (bisimulation up to `depth`).
+///
+/// Each class of states is replaced by its first state, keeping its trs and weights, so that
+/// the paths longer than `depth` may be added or lost. The coarser the `delta` and the smaller
+/// the `depth`, the smaller the result : on the noisy weighted graphs where `minimize` barely
+/// merges anything, this trades the exactness for a large reduction of the size. With a
+/// `depth` of `usize::MAX`, only the states equivalent once their weights are quantized are
+/// merged.
+///
+/// Unlike `minimize`, the weights are not pushed beforehand and the FST doesn't need to be
+/// deterministic.
+///
+/// # Example
+/// ```
+/// # use rustfst::algorithms::{approx_minimize_with_config, ApproxMinimizeConfig};
+/// # use rustfst::fst_impls::VectorFst;
+/// # use rustfst::fst_traits::{ExpandedFst, MutableFst};
+/// # use rustfst::semirings::{Semiring, TropicalWeight};
+/// # use rustfst::Tr;
+/// # use anyhow::Result;
+/// # fn main() -> Result<()> {
+/// // Two branches whose weights only differ by noise.
+/// let mut fst = VectorFst::<TropicalWeight>::new();
+/// fst.add_states(4);
+/// fst.set_start(0)?;
+/// fst.add_tr(0, Tr::new(1, 1, 1.0, 1))?;
+/// fst.add_tr(0, Tr::new(2, 2, 1.0, 2))?;
+/// fst.add_tr(1, Tr::new(3, 3, 0.5, 3))?;
+/// fst.add_tr(2, Tr::new(3, 3, 0.501, 3))?;
+/// fst.set_final(3, TropicalWeight::one())?;
+///
+/// approx_minimize_with_config(&mut fst, ApproxMinimizeConfig::new(usize::MAX, 0.01))?;
+/// assert_eq!(fst.num_states(), 3);
+/// # Ok(())
+/// # }
+/// ```
+pub fn approx_minimize_with_config<W, F>(ifst: &mut F, config: ApproxMinimizeConfig) -> Result<()>
+where
+    W: WeightQuantize,
+    F: MutableFst<W> + ExpandedFst<W>,
+{
+    // The states on no successful path would otherwise stand for useful ones.
+    connect(ifst)?;
+    let classes = k_step_classes(ifst, &config)?;
+
+    let num_classes = classes.iter().max().map_or(0, |c| c + 1);
+    let mut representatives = vec![None; num_classes];
+    for (state, class) in classes.iter().enumerate() {
+        representatives[*class].get_or_insert(state as StateId);
+    }
+    // Can't fail as each class has at least one state.
+    let representatives: Vec<StateId> = representatives.into_iter().map(Option::unwrap).collect();
+
+    let mut dstates = vec![];
+    for (state, class) in classes.iter().enumerate() {
+        let state = state as StateId;
+        if representatives[*class] != state {
+            dstates.push(state);
+            continue;
+        }
+        let mut it_tr = ifst.tr_iter_mut(state)?;
+        for idx_tr in 0..it_tr.len() {
+            let nextstate = unsafe { it_tr.get_unchecked(idx_tr) }.nextstate;
+            let nextstate = representatives[classes[nextstate as usize]];
+            unsafe { it_tr.set_nextstate_unchecked(idx_tr, nextstate) };
+        }
+    }
+    if let Some(start) = ifst.start() {
+        ifst.set_start(representatives[classes[start as usize]])?;
+    }
+    ifst.del_states(dstates)?;
+    connect(ifst)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::algorithms::accepts;
+    use crate::fst_impls::VectorFst;
+    use crate::semirings::{Semiring, TropicalWeight};
+    use crate::Tr;
+
+    #[test]
+    fn test_approx_minimize() -> Result<()> {
+        // 1 c d and 2 c e, the states after 1 and 2 only differing after two trs.
+        let mut fst = VectorFst::<TropicalWeight>::new();
+        fst.add_states(6);
+        fst.set_start(0)?;
+        fst.add_tr(0, Tr::new(1, 1, 1.0, 1))?;
+        fst.add_tr(0, Tr::new(2, 2, 1.0, 2))?;
+        fst.add_tr(1, Tr::new(3, 3, 1.0, 3))?;
+        fst.add_tr(2, Tr::new(3, 3, 1.0, 4))?;
+        fst.add_tr(3, Tr::new(4, 4, 1.0, 5))?;
+        fst.add_tr(4, Tr::new(5, 5, 1.0, 5))?;
+        fst.set_final(5, TropicalWeight::one())?;
+
+        let mut exact = fst.clone();
+        approx_minimize(&mut exact, 2)?;
+        assert_eq!(exact.num_states(), 6);
+
+        let mut approx = fst.clone();
+        approx_minimize(&mut approx, 1)?;
+        assert_eq!(approx.num_states(), 4);
+        assert!(accepts(&approx, &[1, 3, 4])?);
+        assert!(accepts(&approx, &[2, 3, 4])?);
+        assert!(!accepts(&approx, &[2, 3, 5])?);
+
+        // The weights differing by less than delta don't prevent the merge.
+        let mut noisy = fst.clone();
+        noisy.set_final(5, 0.001)?;
+        noisy.add_tr(1, Tr::new(6, 6, 1.0, 5))?;
+        noisy.add_tr(2, Tr::new(6, 6, 1.002, 5))?;
+        let mut merged = noisy.clone();
+        approx_minimize_with_config(&mut merged, ApproxMinimizeConfig::new(1, 0.01))?;
+        assert_eq!(merged.num_states(), 4);
+        approx_minimize_with_config(&mut noisy, ApproxMinimizeConfig::new(1, KDELTA))?;
+        assert_eq!(noisy.num_states(), 6);
+        Ok(())
+    }
+}
@@ -0,0 +1,305 @@
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, VecDeque};
+
+use anyhow::{bail, Result};
+
+use crate::algorithms::asr::hmm::{HmmTopology, TransitionModel};
+use crate::fst_impls::VectorFst;
+use crate::fst_traits::MutableFst;
+use crate::semirings::Semiring;
+use crate::{Label, StateId, Tr, EPS_LABEL};
+
+/// Configuration of the context dependency : the units are windows of `context_width`
+/// phones centered on the phone at `central_position`. The default is the triphones of
+/// Kaldi, `N = 3` and `P = 1`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ContextDependencyConfig {
+    pub context_width: usize,
+    pub central_position: usize,
+}
+
+impl Default for ContextDependencyConfig {
+    fn default() -> Self {
+        Self {
+            context_width: 3,
+            central_position: 1,
+        }
+    }
+}
+
+impl ContextDependencyConfig {
+    pub fn new(context_width: usize, central_position: usize) -> Self {
+        Self {
+            context_width,
+            central_position,
+        }
+    }
+
+    pub fn with_context_width(self, context_width: usize) -> Self {
+        Self {
+            context_width,
+            ..self
+        }
+    }
+
+    pub fn with_central_position(self, central_position: usize) -> Self {
+        Self {
+            central_position,
+            ..self
+        }
+    }
+}
+
+/// Meaning of an input label of the C transducer.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum ContextLabel {
+    /// Window of `context_width` phones, `EPS_LABEL` standing for the sentence boundaries.
+    Window(Vec<Label>),
+    /// Disambiguation symbol passed through C.
+    Disambig(Label),
+}
+
+/// C transducer built by [`c_transducer`] and the meaning of its input labels, as the
+/// `ilabels` files of Kaldi : the input label `i` stands for `ilabel_info[i - 1]`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CTransducer<W: Semiring> {
+    pub fst: VectorFst<W>,
+    pub ilabel_info: Vec<ContextLabel>,
+    pub config: ContextDependencyConfig,
+}
+
+impl<W: Semiring> CTransducer<W> {
+    pub fn ilabel_info(&self, ilabel: Label) -> Option<&ContextLabel> {
+        if ilabel == EPS_LABEL {
+            return None;
+        }
+        self.ilabel_info.get(ilabel as usize - 1)
+    }
+
+    /// Transition model of the windows of C, the HMM of each window being the topology of its
+    /// central phone. It is the input of `h_transducer`.
+    pub fn transition_model<T>(&self, topology: T) -> Result<TransitionModel>
+    where
+        T: Fn(Label) -> Option<HmmTopology>,
+    {
+        let mut units = vec![];
+        for (idx, info) in self.ilabel_info.iter().enumerate() {
+            if let ContextLabel::Window(window) = info {
+                let phone = window[self.config.central_position];
+                match topology(phone) {
+                    Some(t) => units.push(((idx + 1) as Label, t)),
+                    None => bail!("No HMM topology for the phone {}", phone),
+                }
+            }
+        }
+        TransitionModel::new(units)
+    }
+
+    /// Input labels of the disambiguation symbols, to be given to `h_transducer`.
+    pub fn disambig_ilabels(&self) -> Vec<Label> {
+        self.ilabel_info
+            .iter()
+            .enumerate()
+            .filter(|(_, info)| matches!(info, ContextLabel::Disambig(_)))
+            .map(|(idx, _)| (idx + 1) as Label)
+            .collect()
+    }
+}
+
+/// State of C : the last `N - 1` phones read, padded with epsilons, and whether the end of the
+/// sentence has been read.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct ContextTuple {
+    history: Vec<Label>,
+    ended: bool,
+}
+
+/// Builds the context dependency transducer C, mapping the sequences of context-dependent
+/// units to the sequences of phones, as `ComposeContext` from Kaldi does before its
+/// composition with L.
+///
+/// The states hold the last `N - 1` phones. Reading a phone outputs it and inputs the window
+/// made of the state and of the phone, so that the inputs are delayed by `N - 1 - P` phones.
+/// The windows are padded with `EPS_LABEL` at the sentence boundaries and the windows whose
+/// central phone is padding have an epsilon input. The end of the sentence is read with
+/// transitions having an epsilon output which flush the pending windows towards the final
+/// states. Each disambiguation symbol `d` loops on the states before the end of the sentence
+/// with the output `d`, so that C can be composed with an L having disambiguation symbols.
+/// Only the states reachable from the start state are built, the weights are `W::one()`.
+///
+/// # Example
+/// ```
+/// # use rustfst::algorithms::asr::{c_transducer, ContextDependencyConfig, ContextLabel};
+/// # use rustfst::semirings::TropicalWeight;
+/// # use anyhow::Result;
+/// # fn main() -> Result<()> {
+/// let c = c_transducer::<TropicalWeight>(&[1, 2], &[], ContextDependencyConfig::default())?;
+/// assert!(c
+///     .ilabel_info
+///     .contains(&ContextLabel::Window(vec![0, 1, 2])));
+/// # Ok(())
+/// # }
+/// ```
+pub fn c_transducer<W: Semiring>(
+    phones: &[Label],
+    disambig_symbols: &[Label],
+    config: ContextDependencyConfig,
+) -> Result<CTransducer<W>> {
+    let n = config.context_width;
+    let p = config.central_position;
+    if n == 0 || p >= n {
+        bail!(
+            "c_transducer : the central position {} must be lower than the context width {}",
+            p,
+            n
+        );
+    }
+    for label in phones.iter().chain(disambig_symbols.iter()) {
+        if *label == EPS_LABEL {
+            bail!("c_transducer : the phones and the disambiguation symbols can't be epsilon");
+        }
+    }
+    if disambig_symbols.iter().any(|d| phones.contains(d)) {
+        bail!("c_transducer : a disambiguation symbol is also a phone");
+    }
+
+    let mut fst = VectorFst::new();
+    let mut ilabel_info = vec![];
+    let mut ilabels: HashMap<ContextLabel, Label> = HashMap::new();
+    let mut get_ilabel = |info: ContextLabel| -> Label {
+        *ilabels.entry(info.clone()).or_insert_with(|| {
+            ilabel_info.push(info);
+            ilabel_info.len() as Label
+        })
+    };
+    let disambig_ilabels: Vec<Label> = disambig_symbols
+        .iter()
+        .map(|d| get_ilabel(ContextLabel::Disambig(*d)))
+        .collect();
+
+    let mut window_ilabel = |window: &[Label]| -> Label {
+        if window[p] == EPS_LABEL {
+            EPS_LABEL
+        } else {
+            get_ilabel(ContextLabel::Window(window.to_vec()))
+        }
+    };
+
+    let mut tuples: HashMap<ContextTuple, StateId> = HashMap::new();
+    let mut queue = VecDeque::new();
+    let start_tuple = ContextTuple {
+        history: vec![EPS_LABEL; n - 1],
+        ended: false,
+    };
+    let start = fst.add_state();
+    fst.set_start(start)?;
+    tuples.insert(start_tuple.clone(), start);
+    queue.push_back((start_tuple, start));
+
+    while let Some((tuple, state)) = queue.pop_front() {
+        let pending = tuple.history[p..].iter().any(|l| *l != EPS_LABEL);
+        if !pending {
+            fst.set_final(state, W::one())?;
+        }
+
+        let mut next_trs = vec![];
+        if !tuple.ended {
+            for phone in phones {
+                let mut window = tuple.history.clone();
+                window.push(*phone);
+                let ilabel = window_ilabel(&window);
+                let next = ContextTuple {
+                    history: window[1..].to_vec(),
+                    ended: false,
+                };
+                next_trs.push((ilabel, *phone, next));
+            }
+            for (d, ilabel) in disambig_symbols.iter().zip(disambig_ilabels.iter()) {
+                fst.add_tr(state, Tr::new(*ilabel, *d, W::one(), state))?;
+            }
+        }
+        if pending {
+            let mut window = tuple.history.clone();
+            window.push(EPS_LABEL);
+            let ilabel = window_ilabel(&window);
+            let next = ContextTuple {
+                history: window[1..].to_vec(),
+                ended: true,
+            };
+            next_trs.push((ilabel, EPS_LABEL, next));
+        }
+
+        for (ilabel, olabel, next) in next_trs {
+            let nextstate = match tuples.entry(next) {
+                Entry::Occupied(e) => *e.get(),
+                Entry::Vacant(e) => {
+                    let s = fst.add_state();
+                    queue.push_back((e.key().clone(), s));
+                    e.insert(s);
+                    s
+                }
+            };
+            fst.add_tr(state, Tr::new(ilabel, olabel, W::one(), nextstate))?;
+        }
+    }
+
+    Ok(CTransducer {
+        fst,
+        ilabel_info,
+        config,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::algorithms::{invert, transduce};
+    use crate::semirings::TropicalWeight;
+
+    fn windows(c: &CTransducer<TropicalWeight>, phones: &[Label]) -> Result<Vec<ContextLabel>> {
+        let mut inverted = c.fst.clone();
+        invert(&mut inverted);
+        let res = transduce(&inverted, phones, 1)?;
+        assert_eq!(res.len(), 1);
+        Ok(res[0]
+            .0
+            .iter()
+            .map(|l| c.ilabel_info(*l).unwrap().clone())
+            .collect())
+    }
+
+    #[test]
+    fn test_c_transducer_triphones() -> Result<()> {
+        let c =
+            c_transducer::<TropicalWeight>(&[1, 2], &[100], ContextDependencyConfig::default())?;
+        assert_eq!(
+            windows(&c, &[1, 100, 2])?,
+            vec![
+                ContextLabel::Disambig(100),
+                ContextLabel::Window(vec![0, 1, 2]),
+                ContextLabel::Window(vec![1, 2, 0]),
+            ]
+        );
+        assert_eq!(windows(&c, &[])?, vec![]);
+        assert_eq!(c.disambig_ilabels(), vec![1]);
+
+        let model = c.transition_model(|_| HmmTopology::bakis(1, 0.5).ok())?;
+        assert_eq!(model.units().len(), c.ilabel_info.len() - 1);
+        Ok(())
+    }
+
+    #[test]
+    fn test_c_transducer_right_context() -> Result<()> {
+        let config = ContextDependencyConfig::new(2, 0);
+        let c = c_transducer::<TropicalWeight>(&[1, 2], &[], config)?;
+        assert_eq!(
+            windows(&c, &[2, 1])?,
+            vec![
+                ContextLabel::Window(vec![2, 1]),
+                ContextLabel::Window(vec![1, 0]),
+            ]
+        );
+        Ok(())
+    }
+}
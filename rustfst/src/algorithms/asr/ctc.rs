@@ -0,0 +1,147 @@
+use std::sync::Arc;
+
+use anyhow::{bail, format_err, Result};
+
+use crate::fst_traits::MutableFst;
+use crate::semirings::Semiring;
+use crate::{Label, SymbolTable, Tr, EPS_LABEL};
+
+/// Builds the CTC topology transducer `T`, mapping the sequences of CTC units read frame by
+/// frame to the sequences of tokens : the repetitions of a token are collapsed and the blanks
+/// are removed.
+///
+/// The state 0 is the start state, reached after a blank, and the state `k + 1` is reached after
+/// the `k`-th token. Every state is final. The blank and the repetition of the last token loop
+/// with an epsilon output label, any other token moves to its state and outputs it, so that
+/// a token repeated across a blank is output twice. The weights are all `W::one()`.
+///
+/// # Example
+/// ```
+/// # use rustfst::algorithms::asr::ctc_topology;
+/// # use rustfst::algorithms::transduce;
+/// # use rustfst::fst_impls::VectorFst;
+/// # use rustfst::semirings::{Semiring, TropicalWeight};
+/// # use anyhow::Result;
+/// # fn main() -> Result<()> {
+/// let blank = 3;
+/// let topology: VectorFst<TropicalWeight> = ctc_topology(&[1, 2], blank)?;
+/// let res = transduce(&topology, &[1, 1, 3, 1, 2, 2, 3], 1)?;
+/// assert_eq!(res, vec![(vec![1, 1, 2], TropicalWeight::one())]);
+/// # Ok(())
+/// # }
+/// ```
+pub fn ctc_topology<W, F>(tokens: &[Label], blank: Label) -> Result<F>
+where
+    W: Semiring,
+    F: MutableFst<W>,
+{
+    if blank == EPS_LABEL {
+        bail!("ctc_topology : the blank can't be the epsilon label");
+    }
+    for (idx, token) in tokens.iter().enumerate() {
+        if *token == EPS_LABEL || *token == blank {
+            bail!(
+                "ctc_topology : the token {} can't be the epsilon label nor the blank",
+                token
+            );
+        }
+        if tokens[..idx].contains(token) {
+            bail!("ctc_topology : the token {} is repeated", token);
+        }
+    }
+
+    let mut fst = F::new();
+    let blank_state = fst.add_state();
+    fst.set_start(blank_state)?;
+    fst.set_final(blank_state, W::one())?;
+    let token_states = tokens
+        .iter()
+        .map(|_| {
+            let s = fst.add_state();
+            fst.set_final(s, W::one())?;
+            Ok(s)
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let states = std::iter::once((blank, blank_state))
+        .chain(tokens.iter().cloned().zip(token_states.iter().cloned()));
+    for (last, state) in states {
+        fst.add_tr(state, Tr::new(blank, EPS_LABEL, W::one(), blank_state))?;
+        for (token, token_state) in tokens.iter().zip(token_states.iter()) {
+            let olabel = if *token == last { EPS_LABEL } else { *token };
+            fst.add_tr(state, Tr::new(*token, olabel, W::one(), *token_state))?;
+        }
+    }
+    Ok(fst)
+}
+
+/// Builds the CTC topology transducer over all the symbols of `symt` except epsilon and the
+/// blank, with [`ctc_topology`]. The symbol table is set as input and output symbols.
+pub fn ctc_topology_from_symbol_table<W, F>(symt: &Arc<SymbolTable>, blank: &str) -> Result<F>
+where
+    W: Semiring,
+    F: MutableFst<W>,
+{
+    let blank = symt
+        .get_label(blank)
+        .ok_or_else(|| format_err!("ctc_topology : missing blank symbol {}", blank))?;
+    let tokens: Vec<Label> = symt
+        .labels()
+        .filter(|l| *l != EPS_LABEL && *l != blank)
+        .collect();
+    let mut fst: F = ctc_topology(&tokens, blank)?;
+    fst.set_input_symbols(Arc::clone(symt));
+    fst.set_output_symbols(Arc::clone(symt));
+    Ok(fst)
+}
+
+/// Collapses a sequence of CTC units, for instance the best path of a `ScoreMatrixFst` : the
+/// repetitions are merged and the blanks removed.
+pub fn ctc_collapse(labels: &[Label], blank: Label) -> Vec<Label> {
+    let mut res = vec![];
+    let mut last = blank;
+    for label in labels {
+        if *label != last && *label != blank {
+            res.push(*label);
+        }
+        last = *label;
+    }
+    res
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::algorithms::transduce;
+    use crate::fst_impls::VectorFst;
+    use crate::fst_traits::{CoreFst, ExpandedFst};
+    use crate::semirings::TropicalWeight;
+
+    #[test]
+    fn test_ctc_topology_matches_collapse() -> Result<()> {
+        let mut symt = SymbolTable::new();
+        symt.add_symbols(vec!["<blk>", "a", "b", "c"]);
+        let symt = Arc::new(symt);
+        let topology: VectorFst<TropicalWeight> = ctc_topology_from_symbol_table(&symt, "<blk>")?;
+        assert_eq!(topology.num_states(), 4);
+        assert_eq!(topology.num_trs(0)?, 4);
+
+        let blank = 1;
+        let inputs: Vec<Vec<Label>> = vec![
+            vec![],
+            vec![1, 1],
+            vec![2, 2, 2],
+            vec![2, 1, 2, 3, 3, 4, 1],
+            vec![4, 3, 4, 4, 1, 4],
+        ];
+        for input in inputs {
+            let res = transduce(&topology, &input, 1)?;
+            assert_eq!(res.len(), 1);
+            assert_eq!(res[0].0, ctc_collapse(&input, blank));
+        }
+
+        assert!(ctc_topology::<TropicalWeight, VectorFst<_>>(&[1, 2], 2).is_err());
+        Ok(())
+    }
+}
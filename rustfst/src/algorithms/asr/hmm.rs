@@ -0,0 +1,437 @@
+use std::collections::HashMap;
+
+use anyhow::{bail, Result};
+
+use crate::algorithms::asr::self_loops::SelfLoop;
+use crate::fst_traits::MutableFst;
+use crate::semirings::Semiring;
+use crate::{Label, StateId, Tr, EPS_LABEL};
+
+/// Transition of an HMM state.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HmmTransition {
+    /// Destination HMM state. The number of states of the topology stands for the exit.
+    pub nextstate: usize,
+    pub prob: f32,
+}
+
+/// Emitting state of an HMM.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct HmmState {
+    pub transitions: Vec<HmmTransition>,
+}
+
+/// Topology of the HMM of a phone, as in the `topo` files of Kaldi. The state 0 is the
+/// entry state and the transitions to `states.len()` leave the HMM.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HmmTopology {
+    pub states: Vec<HmmState>,
+}
+
+impl HmmTopology {
+    pub fn new(states: Vec<HmmState>) -> Result<Self> {
+        if states.is_empty() {
+            bail!("HmmTopology : at least one state is needed");
+        }
+        for (idx, state) in states.iter().enumerate() {
+            let mut total = 0.0;
+            for tr in state.transitions.iter() {
+                if tr.nextstate > states.len() {
+                    bail!(
+                        "HmmTopology : state {} has a transition to an unknown state",
+                        idx
+                    );
+                }
+                if tr.prob <= 0.0 {
+                    bail!("HmmTopology : state {} has a transition without mass", idx);
+                }
+                total += tr.prob;
+            }
+            if (total - 1.0).abs() > 1e-3 {
+                bail!(
+                    "HmmTopology : the transitions of the state {} sum to {}",
+                    idx,
+                    total
+                );
+            }
+        }
+        Ok(Self { states })
+    }
+
+    /// Left-to-right topology of `num_states` states, each with a self-loop of probability
+    /// `self_loop_prob`. `bakis(3, 0.75)` is the usual topology of the phones of Kaldi.
+    pub fn bakis(num_states: usize, self_loop_prob: f32) -> Result<Self> {
+        let states = (0..num_states)
+            .map(|idx| HmmState {
+                transitions: vec![
+                    HmmTransition {
+                        nextstate: idx,
+                        prob: self_loop_prob,
+                    },
+                    HmmTransition {
+                        nextstate: idx + 1,
+                        prob: 1.0 - self_loop_prob,
+                    },
+                ],
+            })
+            .collect();
+        Self::new(states)
+    }
+
+    pub fn num_states(&self) -> usize {
+        self.states.len()
+    }
+}
+
+/// Transition of the HMM of a unit identified by a transition id.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TransitionInfo {
+    /// Label of the unit, phone or context-dependent phone.
+    pub unit: Label,
+    pub hmm_state: usize,
+    /// Index of the transition in the HMM state.
+    pub transition: usize,
+    pub nextstate: usize,
+    pub prob: f32,
+}
+
+impl TransitionInfo {
+    pub fn is_self_loop(&self) -> bool {
+        self.nextstate == self.hmm_state
+    }
+}
+
+/// Numbering of the transitions of the HMMs of a set of units, as the transition ids of
+/// Kaldi without the sharing of the pdfs. The transition ids start at 1, in the order of the
+/// units, then of the HMM states, then of the transitions.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TransitionModel {
+    units: Vec<(Label, HmmTopology)>,
+    infos: Vec<TransitionInfo>,
+    ids: HashMap<(Label, usize, usize), Label>,
+}
+
+impl TransitionModel {
+    pub fn new(units: Vec<(Label, HmmTopology)>) -> Result<Self> {
+        let mut infos = vec![];
+        let mut ids = HashMap::new();
+        for (unit, topology) in units.iter() {
+            if *unit == EPS_LABEL {
+                bail!("TransitionModel : a unit can't be the epsilon label");
+            }
+            for (hmm_state, state) in topology.states.iter().enumerate() {
+                for (transition, tr) in state.transitions.iter().enumerate() {
+                    if ids
+                        .insert((*unit, hmm_state, transition), (infos.len() + 1) as Label)
+                        .is_some()
+                    {
+                        bail!("TransitionModel : the unit {} is repeated", unit);
+                    }
+                    infos.push(TransitionInfo {
+                        unit: *unit,
+                        hmm_state,
+                        transition,
+                        nextstate: tr.nextstate,
+                        prob: tr.prob,
+                    });
+                }
+            }
+        }
+        Ok(Self { units, infos, ids })
+    }
+
+    pub fn units(&self) -> &[(Label, HmmTopology)] {
+        &self.units
+    }
+
+    pub fn num_transition_ids(&self) -> usize {
+        self.infos.len()
+    }
+
+    pub fn transition_id(&self, unit: Label, hmm_state: usize, transition: usize) -> Option<Label> {
+        self.ids.get(&(unit, hmm_state, transition)).cloned()
+    }
+
+    pub fn info(&self, transition_id: Label) -> Option<&TransitionInfo> {
+        if transition_id == EPS_LABEL {
+            return None;
+        }
+        self.infos.get(transition_id as usize - 1)
+    }
+
+    /// Input label standing for the `idx`-th disambiguation symbol in the H transducer, after
+    /// the transition ids.
+    pub fn disambig_label(&self, idx: usize) -> Label {
+        (self.infos.len() + 1 + idx) as Label
+    }
+
+    /// Whether the label is one of the disambiguation symbols of `disambig_label`.
+    pub fn is_disambig_label(&self, label: Label) -> bool {
+        label as usize > self.infos.len()
+    }
+
+    /// Self-loops of the HMM states for `add_self_loops`, keyed by the transition ids leaving
+    /// the states. The forward weights put back the probability mass of the self-loops
+    /// removed by `h_transducer` when renormalizing the other transitions.
+    pub fn self_loops<W: Semiring<Type = f32>>(
+        &self,
+        transition_scale: f32,
+        self_loop_scale: f32,
+    ) -> HashMap<Label, SelfLoop<W>> {
+        let mut self_loops = HashMap::new();
+        for (idx, info) in self.infos.iter().enumerate() {
+            if info.is_self_loop() {
+                continue;
+            }
+            let self_loop = self.infos.iter().enumerate().find(|(_, other)| {
+                other.unit == info.unit && other.hmm_state == info.hmm_state && other.is_self_loop()
+            });
+            if let Some((loop_idx, loop_info)) = self_loop {
+                self_loops.insert(
+                    (idx + 1) as Label,
+                    SelfLoop {
+                        ilabel: (loop_idx + 1) as Label,
+                        weight: W::new(-loop_info.prob.ln() * self_loop_scale),
+                        forward_weight: W::new(-(1.0 - loop_info.prob).ln() * transition_scale),
+                    },
+                );
+            }
+        }
+        self_loops
+    }
+}
+
+/// Configuration of [`h_transducer`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct HTransducerConfig {
+    /// Whether the self-loops are built. When they are not, as in Kaldi, the probabilities of
+    /// the other transitions are renormalized and the self-loops are added once the graph is
+    /// built with `add_self_loops`.
+    pub include_self_loops: bool,
+    /// Scale applied to the weights of the transitions.
+    pub transition_scale: f32,
+    /// Disambiguation symbols of the output of H, mapped to the inputs `disambig_label(idx)`.
+    pub disambig_symbols: Vec<Label>,
+}
+
+impl Default for HTransducerConfig {
+    fn default() -> Self {
+        Self {
+            include_self_loops: false,
+            transition_scale: 1.0,
+            disambig_symbols: vec![],
+        }
+    }
+}
+
+impl HTransducerConfig {
+    pub fn new(
+        include_self_loops: bool,
+        transition_scale: f32,
+        disambig_symbols: Vec<Label>,
+    ) -> Self {
+        Self {
+            include_self_loops,
+            transition_scale,
+            disambig_symbols,
+        }
+    }
+
+    pub fn with_include_self_loops(self, include_self_loops: bool) -> Self {
+        Self {
+            include_self_loops,
+            ..self
+        }
+    }
+
+    pub fn with_transition_scale(self, transition_scale: f32) -> Self {
+        Self {
+            transition_scale,
+            ..self
+        }
+    }
+
+    pub fn with_disambig_symbols(self, disambig_symbols: Vec<Label>) -> Self {
+        Self {
+            disambig_symbols,
+            ..self
+        }
+    }
+}
+
+/// Weight of a transition of an HMM : its scaled negative log probability, renormalized
+/// without the self-loop if needed.
+pub(crate) fn transition_weight<W: Semiring<Type = f32>>(
+    model: &TransitionModel,
+    info: &TransitionInfo,
+    include_self_loops: bool,
+    transition_scale: f32,
+) -> W {
+    let mut prob = info.prob;
+    if !include_self_loops && !info.is_self_loop() {
+        let topology = &model
+            .units
+            .iter()
+            .find(|(unit, _)| *unit == info.unit)
+            .unwrap()
+            .1;
+        let self_loop_prob: f32 = topology.states[info.hmm_state]
+            .transitions
+            .iter()
+            .filter(|tr| tr.nextstate == info.hmm_state)
+            .map(|tr| tr.prob)
+            .sum();
+        if self_loop_prob < 1.0 {
+            prob /= 1.0 - self_loop_prob;
+        }
+    }
+    W::new(-prob.ln() * transition_scale)
+}
+
+/// Builds the H transducer of the HCLG decoding graphs : it maps the sequences of transition
+/// ids to the sequences of units (the input labels of C) whose HMMs produce them, as
+/// `GetHTransducer` from Kaldi.
+///
+/// The start state is the only final state : the HMM of each unit is entered from it, with the
+/// unit as output label on the first transition, and the transitions leaving the HMM go back
+/// to it. The weights are the negative log probabilities of the transitions scaled by the
+/// `transition_scale`. Each disambiguation symbol `d` of the config is read as
+/// `model.disambig_label(idx)` on a self-loop of the start state outputting `d`.
+///
+/// # Example
+/// ```
+/// # use rustfst::algorithms::asr::{h_transducer, HTransducerConfig, HmmTopology, TransitionModel};
+/// # use rustfst::algorithms::transduce;
+/// # use rustfst::fst_impls::VectorFst;
+/// # use rustfst::semirings::TropicalWeight;
+/// # use anyhow::Result;
+/// # fn main() -> Result<()> {
+/// let model = TransitionModel::new(vec![(1, HmmTopology::bakis(2, 0.5)?)])?;
+/// let h: VectorFst<TropicalWeight> = h_transducer(&model, &HTransducerConfig::default())?;
+/// // Without the self-loops, the transition ids 2 and 4 leave the two states.
+/// let res = transduce(&h, &[2, 4, 2, 4], 1)?;
+/// assert_eq!(res[0].0, vec![1, 1]);
+/// # Ok(())
+/// # }
+/// ```
+pub fn h_transducer<W, F>(model: &TransitionModel, config: &HTransducerConfig) -> Result<F>
+where
+    W: Semiring<Type = f32>,
+    F: MutableFst<W>,
+{
+    let mut fst = F::new();
+    let start = fst.add_state();
+    fst.set_start(start)?;
+    fst.set_final(start, W::one())?;
+
+    for (unit, topology) in model.units.iter() {
+        // States of the HMM states of the unit, the entry state only exists with its self-loop.
+        let hmm_states: Vec<StateId> = (0..topology.num_states())
+            .map(|_| fst.add_state())
+            .collect();
+        let hmm_state_of = |s: usize| -> StateId {
+            if s == topology.num_states() {
+                start
+            } else {
+                hmm_states[s]
+            }
+        };
+        for (hmm_state, state) in topology.states.iter().enumerate() {
+            for transition in 0..state.transitions.len() {
+                let tid = model.transition_id(*unit, hmm_state, transition).unwrap();
+                let info = model.info(tid).unwrap();
+                if info.is_self_loop() && !config.include_self_loops {
+                    continue;
+                }
+                let weight: W = transition_weight(
+                    model,
+                    info,
+                    config.include_self_loops,
+                    config.transition_scale,
+                );
+                let nextstate = hmm_state_of(info.nextstate);
+                if hmm_state == 0 {
+                    // The first transition of the unit outputs it.
+                    fst.add_tr(start, Tr::new(tid, *unit, weight.clone(), nextstate))?;
+                    if info.is_self_loop() {
+                        fst.add_tr(hmm_states[0], Tr::new(tid, EPS_LABEL, weight, nextstate))?;
+                    }
+                } else {
+                    fst.add_tr(
+                        hmm_states[hmm_state],
+                        Tr::new(tid, EPS_LABEL, weight, nextstate),
+                    )?;
+                }
+            }
+        }
+        // The transitions leaving the entry state after its self-loop.
+        if config.include_self_loops {
+            for (transition, tr) in topology.states[0].transitions.iter().enumerate() {
+                if tr.nextstate == 0 {
+                    continue;
+                }
+                let tid = model.transition_id(*unit, 0, transition).unwrap();
+                let weight: W = transition_weight(
+                    model,
+                    model.info(tid).unwrap(),
+                    true,
+                    config.transition_scale,
+                );
+                fst.add_tr(
+                    hmm_states[0],
+                    Tr::new(tid, EPS_LABEL, weight, hmm_state_of(tr.nextstate)),
+                )?;
+            }
+        }
+    }
+
+    for (idx, disambig) in config.disambig_symbols.iter().enumerate() {
+        fst.add_tr(
+            start,
+            Tr::new(model.disambig_label(idx), *disambig, W::one(), start),
+        )?;
+    }
+    Ok(fst)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::algorithms::{connect, transduce};
+    use crate::fst_impls::VectorFst;
+    use crate::fst_traits::ExpandedFst;
+    use crate::semirings::TropicalWeight;
+
+    #[test]
+    fn test_h_transducer() -> Result<()> {
+        let model = TransitionModel::new(vec![
+            (1, HmmTopology::bakis(2, 0.5)?),
+            (2, HmmTopology::bakis(1, 0.5)?),
+        ])?;
+        assert_eq!(model.num_transition_ids(), 6);
+        assert_eq!(model.transition_id(2, 0, 1), Some(6));
+        assert!(model.info(3).unwrap().is_self_loop());
+
+        let config = HTransducerConfig::default()
+            .with_include_self_loops(true)
+            .with_disambig_symbols(vec![100]);
+        let h: VectorFst<TropicalWeight> = h_transducer(&model, &config)?;
+        let res = transduce(&h, &[1, 1, 2, 3, 4, 7, 5, 6], 1)?;
+        assert_eq!(res.len(), 1);
+        assert_eq!(res[0].0, vec![1, 100, 2]);
+        let expected = -(0.5f32.ln()) * 7.0;
+        assert!((res[0].1.value() - expected).abs() < 1e-4);
+        assert!(transduce(&h, &[2, 1], 1)?.is_empty());
+
+        let mut h: VectorFst<TropicalWeight> = h_transducer(&model, &HTransducerConfig::default())?;
+        let res = transduce(&h, &[2, 4, 6], 1)?;
+        assert_eq!(res[0].0, vec![1, 2]);
+        assert!(res[0].1.value().abs() < 1e-6);
+        let num_states = h.num_states();
+        connect(&mut h)?;
+        // Without self-loops the entry states are not reachable.
+        assert_eq!(h.num_states(), num_states - 2);
+        Ok(())
+    }
+}
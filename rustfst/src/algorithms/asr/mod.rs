@@ -0,0 +1,15 @@
+//! Builders of the transducers composed into the decoding graphs of speech recognition, for
+//! instance `H ∘ C ∘ L ∘ G` for HMM based models and `T ∘ L ∘ G` for end-to-end CTC models.
+
+pub use self::context::{c_transducer, CTransducer, ContextDependencyConfig, ContextLabel};
+pub use self::ctc::{ctc_collapse, ctc_topology, ctc_topology_from_symbol_table};
+pub use self::hmm::{
+    h_transducer, HTransducerConfig, HmmState, HmmTopology, HmmTransition, TransitionInfo,
+    TransitionModel,
+};
+pub use self::self_loops::{add_self_loops, remove_disambiguation_symbols, SelfLoop};
+
+mod context;
+mod ctc;
+mod hmm;
+mod self_loops;
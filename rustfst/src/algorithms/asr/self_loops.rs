@@ -0,0 +1,186 @@
+use std::collections::{BTreeMap, HashMap};
+use std::ops::RangeBounds;
+
+use anyhow::Result;
+
+use crate::fst_traits::{ExpandedFst, MutableFst};
+use crate::semirings::Semiring;
+use crate::{Label, Tr, EPS_LABEL};
+
+/// Self-loop to add before the transitions with a given input label.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SelfLoop<W> {
+    /// Input label of the self-loop. Its output label is epsilon.
+    pub ilabel: Label,
+    pub weight: W,
+    /// Weight ⊗-multiplied to the paths leaving the self-loop, for instance to put back the
+    /// probability mass taken by the self-loop.
+    pub forward_weight: W,
+}
+
+/// Adds the self-loops removed from the H transducer once the decoding graph is built, as
+/// `AddSelfLoops` from Kaldi with reordering.
+///
+/// `self_loops` maps input labels to the self-loops to add on the source states of the
+/// transitions having them, for instance the transition ids leaving an HMM state to the
+/// self-loop of the HMM state. When the other transitions of a state, or its final weight,
+/// don't share the same self-loop, the transitions of each self-loop are moved to a new state
+/// reached with an epsilon transition, so that no other path goes through the self-loop.
+/// The self-loops with the same input label must have the same weights.
+///
+/// The properties of the FST are recomputed.
+///
+/// # Example
+/// ```
+/// # use std::collections::HashMap;
+/// # use rustfst::algorithms::asr::{add_self_loops, SelfLoop};
+/// # use rustfst::fst_impls::VectorFst;
+/// # use rustfst::fst_traits::{CoreFst, MutableFst};
+/// # use rustfst::semirings::{Semiring, TropicalWeight};
+/// # use rustfst::Tr;
+/// # use anyhow::Result;
+/// # fn main() -> Result<()> {
+/// let mut fst = VectorFst::<TropicalWeight>::new();
+/// let s0 = fst.add_state();
+/// let s1 = fst.add_state();
+/// fst.set_start(s0)?;
+/// fst.add_tr(s0, Tr::new(2, 2, 0.0, s1))?;
+/// fst.set_final(s1, TropicalWeight::one())?;
+///
+/// let mut self_loops = HashMap::new();
+/// let self_loop = SelfLoop {
+///     ilabel: 1,
+///     weight: TropicalWeight::new(0.5),
+///     forward_weight: TropicalWeight::new(1.0),
+/// };
+/// self_loops.insert(2, self_loop);
+/// add_self_loops(&mut fst, &self_loops)?;
+/// assert_eq!(fst.num_trs(s0)?, 2);
+/// # Ok(())
+/// # }
+/// ```
+pub fn add_self_loops<W, F>(fst: &mut F, self_loops: &HashMap<Label, SelfLoop<W>>) -> Result<()>
+where
+    W: Semiring,
+    F: MutableFst<W> + ExpandedFst<W>,
+{
+    // The range is computed once : the states added for the self-loops are not visited.
+    for state in fst.states_range() {
+        let is_final = fst.is_final(state)?;
+        let trs = fst.pop_trs(state)?;
+        let mut others = vec![];
+        let mut classes: BTreeMap<Label, (&SelfLoop<W>, Vec<Tr<W>>)> = BTreeMap::new();
+        for tr in trs {
+            match self_loops.get(&tr.ilabel) {
+                Some(self_loop) => classes
+                    .entry(self_loop.ilabel)
+                    .or_insert_with(|| (self_loop, vec![]))
+                    .1
+                    .push(tr),
+                None => others.push(tr),
+            }
+        }
+
+        if classes.len() == 1 && others.is_empty() && !is_final {
+            let (self_loop, trs) = classes.into_iter().next().unwrap().1;
+            let tr = Tr::new(self_loop.ilabel, EPS_LABEL, self_loop.weight.clone(), state);
+            fst.add_tr(state, tr)?;
+            for mut tr in trs {
+                tr.weight.times_assign(&self_loop.forward_weight)?;
+                fst.add_tr(state, tr)?;
+            }
+            continue;
+        }
+
+        for tr in others {
+            fst.add_tr(state, tr)?;
+        }
+        for (_, (self_loop, trs)) in classes {
+            let loop_state = fst.add_state();
+            let tr = Tr::new(
+                EPS_LABEL,
+                EPS_LABEL,
+                self_loop.forward_weight.clone(),
+                loop_state,
+            );
+            fst.add_tr(state, tr)?;
+            let tr = Tr::new(
+                self_loop.ilabel,
+                EPS_LABEL,
+                self_loop.weight.clone(),
+                loop_state,
+            );
+            fst.add_tr(loop_state, tr)?;
+            for tr in trs {
+                fst.add_tr(loop_state, tr)?;
+            }
+        }
+    }
+    fst.compute_and_update_properties_all()?;
+    Ok(())
+}
+
+/// Replaces by epsilon the input labels in `range`, such as the disambiguation symbols of
+/// the decoding graph once it has been determinized, as `fstrmsymbols` does. Returns the
+/// number of relabeled transitions. The properties are updated along with the labels.
+pub fn remove_disambiguation_symbols<W, F, R>(fst: &mut F, range: R) -> Result<usize>
+where
+    W: Semiring,
+    F: MutableFst<W> + ExpandedFst<W>,
+    R: RangeBounds<Label>,
+{
+    let mut num_relabeled = 0;
+    for state in fst.states_range() {
+        let mut it_tr = fst.tr_iter_mut(state)?;
+        for idx in 0..it_tr.len() {
+            let ilabel = it_tr.get(idx).unwrap().ilabel;
+            if ilabel != EPS_LABEL && range.contains(&ilabel) {
+                it_tr.set_ilabel(idx, EPS_LABEL)?;
+                num_relabeled += 1;
+            }
+        }
+    }
+    Ok(num_relabeled)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::algorithms::asr::{h_transducer, HTransducerConfig, HmmTopology, TransitionModel};
+    use crate::algorithms::transduce;
+    use crate::fst_impls::VectorFst;
+    use crate::fst_traits::CoreFst;
+    use crate::semirings::TropicalWeight;
+    use crate::Trs;
+
+    #[test]
+    fn test_add_self_loops_to_h() -> Result<()> {
+        let model = TransitionModel::new(vec![(1, HmmTopology::bakis(2, 0.5)?)])?;
+        let config = HTransducerConfig::default().with_disambig_symbols(vec![100]);
+        let mut h: VectorFst<TropicalWeight> = h_transducer(&model, &config)?;
+        add_self_loops(&mut h, &model.self_loops(1.0, 1.0))?;
+
+        let with_self_loops: VectorFst<TropicalWeight> =
+            h_transducer(&model, &config.clone().with_include_self_loops(true))?;
+        for input in [vec![2, 4], vec![1, 1, 2, 3, 4, 2, 4]].iter() {
+            let res = transduce(&h, input, 1)?;
+            let expected = transduce(&with_self_loops, input, 1)?;
+            assert_eq!(res.len(), 1);
+            assert_eq!(res[0].0, expected[0].0);
+            assert!((res[0].1.value() - expected[0].1.value()).abs() < 1e-4);
+        }
+
+        let start = h.start().unwrap();
+        assert_eq!(
+            remove_disambiguation_symbols(&mut h, model.disambig_label(0)..)?,
+            1
+        );
+        assert!(h
+            .get_trs(start)?
+            .trs()
+            .iter()
+            .all(|tr| tr.ilabel <= model.num_transition_ids() as Label));
+        Ok(())
+    }
+}
@@ -0,0 +1,158 @@
+use std::cmp::Ordering;
+use std::collections::VecDeque;
+
+use anyhow::Result;
+
+use crate::algorithms::tr_compares::{tr_compare, TrCompare};
+use crate::algorithms::{state_sort, tr_sort};
+use crate::fst_properties::FstProperties;
+use crate::fst_traits::MutableFst;
+use crate::semirings::Semiring;
+use crate::{StateId, Tr, Trs};
+
+/// Compare trs in the order input label, output label, nextstate and weight.
+pub struct CanonicalTrCompare {}
+
+impl TrCompare for CanonicalTrCompare {
+    fn compare<W: Semiring>(a: &Tr<W>, b: &Tr<W>) -> Ordering {
+        a.ilabel
+            .cmp(&b.ilabel)
+            .then(a.olabel.cmp(&b.olabel))
+            .then(a.nextstate.cmp(&b.nextstate))
+            .then(a.weight.partial_cmp(&b.weight).unwrap_or(Ordering::Equal))
+    }
+
+    fn properties(inprops: FstProperties) -> FstProperties {
+        let mut outprops =
+            (inprops & FstProperties::arcsort_properties()) | FstProperties::I_LABEL_SORTED;
+        if inprops.contains(FstProperties::ACCEPTOR) {
+            outprops |= FstProperties::O_LABEL_SORTED;
+        }
+        outprops
+    }
+}
+
+/// Numbers the states in the BFS order from the start state, following the trs of each state
+/// in the order in which they are given. The states not accessible from the start state are
+/// numbered after the accessible ones, in their original order.
+///
+/// Returns the new ID of each state, in the format expected by `state_sort`.
+pub(crate) fn bfs_state_order<W: Semiring>(
+    start: Option<StateId>,
+    trs: &[Vec<Tr<W>>],
+) -> Vec<StateId> {
+    let mut new_ids: Vec<Option<StateId>> = vec![None; trs.len()];
+    let mut next_id: StateId = 0;
+    let mut queue = VecDeque::new();
+    if let Some(start) = start {
+        new_ids[start as usize] = Some(next_id);
+        next_id += 1;
+        queue.push_back(start);
+    }
+    while let Some(state) = queue.pop_front() {
+        for tr in trs[state as usize].iter() {
+            if new_ids[tr.nextstate as usize].is_none() {
+                new_ids[tr.nextstate as usize] = Some(next_id);
+                next_id += 1;
+                queue.push_back(tr.nextstate);
+            }
+        }
+    }
+    new_ids
+        .into_iter()
+        .map(|new_id| {
+            new_id.unwrap_or_else(|| {
+                next_id += 1;
+                next_id - 1
+            })
+        })
+        .collect()
+}
+
+/// Puts the FST in a canonical form : the states are renumbered in the BFS order from the start
+/// state, the trs of each state being followed by input label, output label and weight, then
+/// the trs are sorted by input label, output label, nextstate and weight.
+///
+/// Two FSTs built independently which only differ by the numbering of their states and the
+/// order of their trs are equal once canonicalized, so comparing or diffing them becomes
+/// trivial. The states not accessible from the start state are kept and numbered after the
+/// accessible ones, in their original order. When a state has several trs with the same labels
+/// and weight, the form depends on the original order of those trs.
+///
+/// # Example
+/// ```
+/// # #[macro_use] extern crate rustfst;
+/// # use rustfst::utils::transducer;
+/// # use rustfst::algorithms::{canonicalize, state_sort};
+/// # use rustfst::fst_impls::VectorFst;
+/// # use rustfst::semirings::{Semiring, TropicalWeight};
+/// # use anyhow::Result;
+/// # fn main() -> Result<()> {
+/// let fst : VectorFst<TropicalWeight> = fst![1, 2 => 3, 4; 0.5];
+/// let mut renumbered = fst.clone();
+/// state_sort(&mut renumbered, &[2, 0, 1])?;
+/// assert_ne!(fst, renumbered);
+///
+/// canonicalize(&mut renumbered)?;
+/// assert_eq!(fst, renumbered);
+/// # Ok(())
+/// # }
+/// ```
+pub fn canonicalize<W, F>(fst: &mut F) -> Result<()>
+where
+    W: Semiring,
+    F: MutableFst<W>,
+{
+    let mut trs = Vec::with_capacity(fst.num_states());
+    for state in fst.states_range() {
+        let mut trs_state = fst.get_trs(state)?.trs().to_vec();
+        trs_state.sort_by(tr_compare);
+        trs.push(trs_state);
+    }
+    let order = bfs_state_order(fst.start(), &trs);
+    state_sort(fst, &order)?;
+    tr_sort(fst, CanonicalTrCompare {});
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::fst_impls::VectorFst;
+    use crate::fst_traits::CoreFst;
+    use crate::semirings::TropicalWeight;
+
+    #[test]
+    fn test_canonicalize() -> Result<()> {
+        let mut fst_1 = VectorFst::<TropicalWeight>::new();
+        fst_1.add_states(4);
+        fst_1.set_start(0)?;
+        fst_1.add_tr(0, Tr::new(2, 2, 1.0, 2))?;
+        fst_1.add_tr(0, Tr::new(1, 1, 1.0, 1))?;
+        fst_1.add_tr(1, Tr::new(3, 3, 1.0, 2))?;
+        fst_1.set_final(2, 0.5)?;
+        fst_1.set_final(3, 0.0)?;
+
+        // Same FST with the states renumbered and the trs reordered.
+        let mut fst_2 = VectorFst::<TropicalWeight>::new();
+        fst_2.add_states(4);
+        fst_2.set_start(2)?;
+        fst_2.add_tr(2, Tr::new(1, 1, 1.0, 0))?;
+        fst_2.add_tr(2, Tr::new(2, 2, 1.0, 3))?;
+        fst_2.add_tr(0, Tr::new(3, 3, 1.0, 3))?;
+        fst_2.set_final(3, 0.5)?;
+        fst_2.set_final(1, 0.0)?;
+
+        canonicalize(&mut fst_1)?;
+        canonicalize(&mut fst_2)?;
+        assert_eq!(fst_1, fst_2);
+
+        assert_eq!(fst_1.start(), Some(0));
+        assert_eq!(fst_1.get_trs(0)?.trs()[0].nextstate, 1);
+        assert_eq!(fst_1.get_trs(0)?.trs()[1].nextstate, 2);
+        // The inaccessible state is numbered last.
+        assert_eq!(fst_1.final_weight(3)?, Some(TropicalWeight::one()));
+        Ok(())
+    }
+}
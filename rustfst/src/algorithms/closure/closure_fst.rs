@@ -15,6 +15,29 @@ use crate::{StateId, SymbolTable, Tr, TrsVec, EPS_LABEL, NO_LABEL};
 /// with weight a, xx to yy with weight Times(a, a), xxx to yyy with weight
 /// Times(Times(a, a), a), etc. If closure_type == CLOSURE_STAR, then the empty
 /// string is transduced to itself with weight Weight::One() as well.
+///
+/// The states are expanded on demand, without copying the input FSTs upfront, e.g. when the
+/// lazy FST is read by another algorithm. `compute` turns it into a static FST.
+///
+/// # Example
+/// ```
+/// # use anyhow::Result;
+/// # fn main() -> Result<()> {
+/// # use rustfst::algorithms::accepts;
+/// # use rustfst::algorithms::closure::{ClosureFst, ClosureType};
+/// # use rustfst::fst_impls::VectorFst;
+/// # use rustfst::semirings::{Semiring, TropicalWeight};
+/// # use rustfst::utils::acceptor;
+/// let fst: VectorFst<TropicalWeight> = acceptor(&[1, 2], TropicalWeight::one());
+/// let closure_fst = ClosureFst::new(fst, ClosureType::ClosurePlus)?;
+/// assert!(accepts(&closure_fst, &[1, 2, 1, 2])?);
+/// assert!(!accepts(&closure_fst, &[])?);
+///
+/// let computed: VectorFst<_> = closure_fst.compute()?;
+/// assert!(accepts(&computed, &[1, 2, 1, 2, 1, 2])?);
+/// # Ok(())
+/// # }
+/// ```
 #[derive(Debug)]
 pub struct ClosureFst<W: Semiring, F: Fst<W> + 'static>(ReplaceFst<W, F, F>, FstProperties);
 
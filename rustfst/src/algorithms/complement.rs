@@ -0,0 +1,142 @@
+use std::collections::BTreeSet;
+
+use anyhow::Result;
+
+use crate::fst_properties::FstProperties;
+use crate::fst_traits::{ExpandedFst, MutableFst};
+use crate::semirings::Semiring;
+use crate::{Label, StateId, SymbolTable, Tr, Trs, EPS_LABEL};
+
+/// Complements a deterministic unweighted acceptor in place, over the labels of a symbol
+/// table: the result accepts exactly the strings of labels of `symt` (epsilon excluded)
+/// that are not accepted by the input.
+///
+/// The input is first completed with a sink state, so that every label of the alphabet can
+/// be read from every state, then its final and non-final states are swapped. The weights of
+/// the input are ignored and those of the output are all `W::one()`. An error is returned
+/// if the input is not a deterministic acceptor without epsilons, or if it uses labels
+/// missing from the symbol table.
+///
+/// # Example
+/// ```
+/// # #[macro_use] extern crate rustfst;
+/// # use rustfst::algorithms::{accepts, complement};
+/// # use rustfst::fst_impls::VectorFst;
+/// # use rustfst::semirings::{BooleanWeight, Semiring};
+/// # use rustfst::utils::acceptor;
+/// # use rustfst::SymbolTable;
+/// # use anyhow::Result;
+/// # fn main() -> Result<()> {
+/// let symt = symt!["a", "b"];
+/// let mut fst : VectorFst<BooleanWeight> = fst![1, 2];
+/// complement(&mut fst, &symt)?;
+///
+/// assert!(!accepts(&fst, &[1, 2])?);
+/// assert!(accepts(&fst, &[1])?);
+/// assert!(accepts(&fst, &[1, 2, 2])?);
+/// assert!(accepts(&fst, &[])?);
+/// # Ok(())
+/// # }
+/// ```
+pub fn complement<W, F>(fst: &mut F, symt: &SymbolTable) -> Result<()>
+where
+    W: Semiring,
+    F: MutableFst<W> + ExpandedFst<W>,
+{
+    let alphabet: BTreeSet<Label> = symt.labels().filter(|l| *l != EPS_LABEL).collect();
+    complement_over_alphabet(fst, &alphabet)
+}
+
+pub(crate) fn complement_over_alphabet<W, F>(fst: &mut F, alphabet: &BTreeSet<Label>) -> Result<()>
+where
+    W: Semiring,
+    F: MutableFst<W> + ExpandedFst<W>,
+{
+    let required =
+        FstProperties::ACCEPTOR | FstProperties::I_DETERMINISTIC | FstProperties::NO_EPSILONS;
+    if !fst
+        .compute_and_update_properties(required)?
+        .contains(required)
+    {
+        bail!("complement : the input must be a deterministic acceptor without epsilons");
+    }
+    // Checked before any modification so that the FST is left untouched on error.
+    for state in fst.states_range() {
+        for tr in fst.get_trs(state)?.trs() {
+            if !alphabet.contains(&tr.ilabel) {
+                bail!(
+                    "complement : the label {} is not part of the alphabet",
+                    tr.ilabel
+                );
+            }
+        }
+    }
+
+    if fst.start().is_none() {
+        let start = fst.add_state();
+        fst.set_start(start)?;
+    }
+
+    // The sink state is completed like the other ones: it loops on all the labels and,
+    // not being final in the input, it is final in the complement.
+    fst.add_state();
+    for state in fst.states_range() {
+        let mut missing = alphabet.clone();
+        for tr in fst.pop_trs(state)? {
+            missing.remove(&tr.ilabel);
+            fst.add_tr(state, Tr::new(tr.ilabel, tr.olabel, W::one(), tr.nextstate))?;
+        }
+        let sink = (fst.num_states() - 1) as StateId;
+        for label in missing {
+            fst.add_tr(state, Tr::new(label, label, W::one(), sink))?;
+        }
+        if fst.is_final(state)? {
+            fst.delete_final_weight(state)?;
+        } else {
+            fst.set_final(state, W::one())?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::algorithms::accepts;
+    use crate::fst_impls::VectorFst;
+    use crate::semirings::TropicalWeight;
+    use crate::symt;
+
+    #[test]
+    fn test_complement_empty_fst() -> Result<()> {
+        let symt = symt!["a", "b"];
+        let mut fst = VectorFst::<TropicalWeight>::new();
+        complement(&mut fst, &symt)?;
+        assert!(accepts(&fst, &[])?);
+        assert!(accepts(&fst, &[2, 1, 1])?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_complement_rejects_non_deterministic_fst() -> Result<()> {
+        let symt = symt!["a"];
+        let mut fst = VectorFst::<TropicalWeight>::new();
+        let s0 = fst.add_state();
+        let s1 = fst.add_state();
+        fst.set_start(s0)?;
+        fst.add_tr(s0, Tr::new(1, 1, 1.0, s0))?;
+        fst.add_tr(s0, Tr::new(1, 1, 1.0, s1))?;
+        assert!(complement(&mut fst, &symt).is_err());
+
+        let mut fst = VectorFst::<TropicalWeight>::new();
+        let s0 = fst.add_state();
+        fst.set_start(s0)?;
+        fst.add_tr(s0, Tr::new(1, 1, 1.0, s0))?;
+        fst.add_tr(s0, Tr::new(2, 2, 1.0, s0))?;
+        let copy = fst.clone();
+        assert!(complement(&mut fst, &symt).is_err());
+        assert_eq!(fst, copy);
+        Ok(())
+    }
+}
@@ -0,0 +1,246 @@
+use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
+
+use anyhow::Result;
+
+use crate::algorithms::compose::{compose_with_config, ComposeConfig};
+use crate::algorithms::{fingerprint, fst_convert_from_ref};
+use crate::fst_impls::VectorFst;
+use crate::fst_traits::{CoreFst, ExpandedFst, Fst};
+use crate::semirings::{Semiring, SerializableSemiring};
+use crate::Trs;
+
+/// Bucket of a cached composition: fingerprints of the two operands.
+type ComposeCacheKey = (u64, u64);
+
+/// Cached composition along with the full key it has been computed for, compared on a hit
+/// so that a collision of the fingerprints can't return the composition of other operands.
+#[derive(Debug, Clone)]
+struct ComposeCacheEntry<W: Semiring> {
+    fst1: VectorFst<W>,
+    fst2: VectorFst<W>,
+    config: ComposeConfig,
+    result: Arc<VectorFst<W>>,
+}
+
+/// Whether `fst` has the same content as `cached`, symbol tables included.
+fn same_content<W: Semiring, F: ExpandedFst<W>>(fst: &F, cached: &VectorFst<W>) -> Result<bool> {
+    if fst.start() != cached.start()
+        || fst.num_states() != cached.num_states()
+        || fst.input_symbols() != cached.input_symbols()
+        || fst.output_symbols() != cached.output_symbols()
+    {
+        return Ok(false);
+    }
+    for state in fst.states_range() {
+        if fst.final_weight(state)? != cached.final_weight(state)?
+            || fst.get_trs(state)?.trs() != cached.get_trs(state)?.trs()
+        {
+            return Ok(false);
+        }
+    }
+    Ok(true)
+}
+
+/// Cache of the results of compositions, keyed on the operands and on the composition
+/// options. Useful for services composing the same pairs of FSTs over and over. When the
+/// cache is full, the oldest result is evicted.
+///
+/// The results are looked up by the fingerprints of the operands, then the operands and the
+/// options are compared with those of the cached result : a copy of both operands is kept
+/// along with each result, and a hit costs a traversal of the operands on top of the
+/// computation of their fingerprints.
+///
+/// # Example
+/// ```
+/// # #[macro_use] extern crate rustfst;
+/// # use rustfst::utils::{acceptor, transducer};
+/// # use rustfst::algorithms::compose::{ComposeCache, ComposeConfig};
+/// # use rustfst::fst_impls::VectorFst;
+/// # use rustfst::semirings::{Semiring, TropicalWeight};
+/// # use anyhow::Result;
+/// # fn main() -> Result<()> {
+/// let fst_1 : VectorFst<TropicalWeight> = fst![1, 2 => 3, 4];
+/// let fst_2 : VectorFst<TropicalWeight> = fst![3, 4 => 5, 6];
+///
+/// let mut cache = ComposeCache::new(16);
+/// let res_1 = cache.compose(&fst_1, &fst_2, ComposeConfig::default())?;
+/// let res_2 = cache.compose(&fst_1, &fst_2, ComposeConfig::default())?;
+/// assert_eq!(cache.num_hits(), 1);
+/// assert_eq!(res_1, res_2);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct ComposeCache<W: Semiring> {
+    results: HashMap<ComposeCacheKey, Vec<ComposeCacheEntry<W>>>,
+    /// Bucket of each cached result, from the oldest one. The results of a bucket are
+    /// stored from the oldest one as well.
+    insertion_order: VecDeque<ComposeCacheKey>,
+    capacity: usize,
+    num_hits: usize,
+    num_misses: usize,
+}
+
+impl<W: SerializableSemiring> ComposeCache<W> {
+    /// Creates a cache keeping at most `capacity` results.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            results: HashMap::new(),
+            insertion_order: VecDeque::new(),
+            capacity,
+            num_hits: 0,
+            num_misses: 0,
+        }
+    }
+
+    /// Returns the composition of `fst1` and `fst2`, computing it only if the same
+    /// composition isn't cached yet.
+    pub fn compose<F1: ExpandedFst<W>, F2: ExpandedFst<W>>(
+        &mut self,
+        fst1: &F1,
+        fst2: &F2,
+        config: ComposeConfig,
+    ) -> Result<Arc<VectorFst<W>>> {
+        let key = (fingerprint(fst1)?, fingerprint(fst2)?);
+        if let Some(entries) = self.results.get(&key) {
+            for entry in entries.iter() {
+                if entry.config == config
+                    && same_content(fst1, &entry.fst1)?
+                    && same_content(fst2, &entry.fst2)?
+                {
+                    self.num_hits += 1;
+                    return Ok(Arc::clone(&entry.result));
+                }
+            }
+        }
+        self.num_misses += 1;
+        let res: Arc<VectorFst<W>> = Arc::new(compose_with_config::<W, F1, F2, _, _, _>(
+            fst1,
+            fst2,
+            config.clone(),
+        )?);
+        if self.capacity > 0 {
+            if self.len() >= self.capacity {
+                if let Some(oldest) = self.insertion_order.pop_front() {
+                    if let Some(entries) = self.results.get_mut(&oldest) {
+                        entries.remove(0);
+                        if entries.is_empty() {
+                            self.results.remove(&oldest);
+                        }
+                    }
+                }
+            }
+            self.insertion_order.push_back(key);
+            self.results
+                .entry(key)
+                .or_default()
+                .push(ComposeCacheEntry {
+                    fst1: fst_convert_from_ref(fst1),
+                    fst2: fst_convert_from_ref(fst2),
+                    config,
+                    result: Arc::clone(&res),
+                });
+        }
+        Ok(res)
+    }
+
+    /// Number of compositions answered from the cache.
+    pub fn num_hits(&self) -> usize {
+        self.num_hits
+    }
+
+    /// Number of compositions computed.
+    pub fn num_misses(&self) -> usize {
+        self.num_misses
+    }
+
+    /// Number of results currently cached.
+    pub fn len(&self) -> usize {
+        self.insertion_order.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.results.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.results.clear();
+        self.insertion_order.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::fst_traits::MutableFst;
+    use crate::semirings::TropicalWeight;
+    use crate::Tr;
+
+    #[test]
+    fn test_compose_cache_eviction() -> Result<()> {
+        let mut fsts = vec![];
+        for label in 1..4 {
+            let mut fst = VectorFst::<TropicalWeight>::new();
+            let s0 = fst.add_state();
+            let s1 = fst.add_state();
+            fst.set_start(s0)?;
+            fst.add_tr(s0, Tr::new(label, label, 1.0, s1))?;
+            fst.set_final(s1, TropicalWeight::one())?;
+            fsts.push(fst);
+        }
+
+        let mut cache = ComposeCache::new(2);
+        for fst in fsts.iter() {
+            cache.compose(fst, fst, ComposeConfig::default())?;
+        }
+        assert_eq!(cache.len(), 2);
+        assert_eq!(cache.num_misses(), 3);
+
+        // The first composition has been evicted, the last one is still cached.
+        cache.compose(&fsts[2], &fsts[2], ComposeConfig::default())?;
+        assert_eq!(cache.num_hits(), 1);
+        cache.compose(&fsts[0], &fsts[0], ComposeConfig::default())?;
+        assert_eq!(cache.num_misses(), 4);
+
+        let mut copy = fsts[1].clone();
+        copy.set_final(1, 2.0)?;
+        let res = cache.compose(&fsts[2], &copy, ComposeConfig::default())?;
+        assert_eq!(cache.num_misses(), 5);
+        assert_eq!(res.start(), None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_compose_cache_compares_full_key() -> Result<()> {
+        let mut fst = VectorFst::<TropicalWeight>::new();
+        fst.add_states(2);
+        fst.set_start(0)?;
+        fst.add_tr(0, Tr::new(1, 1, 1.0, 1))?;
+        fst.set_final(1, TropicalWeight::one())?;
+
+        let mut cache = ComposeCache::new(4);
+        let res = cache.compose(&fst, &fst, ComposeConfig::default())?;
+
+        // Simulates a collision of the fingerprints : the entry of the bucket has been
+        // computed for other operands and must not be returned.
+        let mut other = fst.clone();
+        other.set_final(1, 3.0)?;
+        let key = (fingerprint(&other)?, fingerprint(&other)?);
+        let entry = cache.results.values().next().unwrap()[0].clone();
+        cache.results.insert(key, vec![entry]);
+        cache.insertion_order.push_back(key);
+
+        let other_res = cache.compose(&other, &other, ComposeConfig::default())?;
+        assert_eq!(cache.num_hits(), 0);
+        assert_ne!(other_res, res);
+
+        let config = ComposeConfig::default().with_connect(false);
+        cache.compose(&fst, &fst, config)?;
+        assert_eq!(cache.num_hits(), 0);
+        cache.compose(&fst, &fst, ComposeConfig::default())?;
+        assert_eq!(cache.num_hits(), 1);
+        Ok(())
+    }
+}
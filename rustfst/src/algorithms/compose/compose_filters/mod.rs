@@ -11,6 +11,9 @@ pub use no_match_compose_filter::{NoMatchComposeFilter, NoMatchComposeFilterBuil
 pub use null_compose_filter::{NullComposeFilter, NullComposeFilterBuilder};
 pub use sequence_compose_filter::{SequenceComposeFilter, SequenceComposeFilterBuilder};
 pub use trivial_compose_filter::{TrivialComposeFilter, TrivialComposeFilterBuilder};
+pub use vocabulary_compose_filter::{
+    Vocabulary, VocabularyComposeFilter, VocabularyComposeFilterBuilder,
+};
 
 use crate::algorithms::compose::filter_states::FilterState;
 use crate::algorithms::compose::matchers::Matcher;
@@ -26,6 +29,7 @@ mod no_match_compose_filter;
 mod null_compose_filter;
 mod sequence_compose_filter;
 mod trivial_compose_filter;
+mod vocabulary_compose_filter;
 
 pub trait ComposeFilterBuilder<W: Semiring, F1, F2, B1, B2, M1, M2>: Debug + Clone
 where
@@ -0,0 +1,321 @@
+use std::borrow::Borrow;
+use std::fmt::Debug;
+use std::iter::FromIterator;
+use std::marker::PhantomData;
+use std::sync::Arc;
+
+use anyhow::Result;
+
+use crate::algorithms::compose::compose_filters::{ComposeFilter, ComposeFilterBuilder};
+use crate::algorithms::compose::filter_states::FilterState;
+use crate::algorithms::compose::matchers::Matcher;
+use crate::fst_properties::FstProperties;
+use crate::fst_traits::Fst;
+use crate::semirings::Semiring;
+use crate::{Label, StateId, SymbolTable, Tr, EPS_LABEL, NO_LABEL};
+
+const WORD_BITS: usize = 64;
+
+/// Set of labels stored as a bitset, e.g. the words allowed on the output of a decoding.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Vocabulary {
+    bits: Vec<u64>,
+    len: usize,
+}
+
+impl Vocabulary {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Labels of the `symbols` in `symt`, failing if one of them is missing from the table.
+    pub fn from_symbols<S: AsRef<str>, I: IntoIterator<Item = S>>(
+        symt: &SymbolTable,
+        symbols: I,
+    ) -> Result<Self> {
+        let mut vocabulary = Self::new();
+        for symbol in symbols {
+            let symbol = symbol.as_ref();
+            let label = symt
+                .get_label(symbol)
+                .ok_or_else(|| format_err!("Symbol {} is missing from the symbol table", symbol))?;
+            vocabulary.insert(label);
+        }
+        Ok(vocabulary)
+    }
+
+    /// Adds the label, returning whether it was not already in the vocabulary.
+    pub fn insert(&mut self, label: Label) -> bool {
+        let label = label as usize;
+        let word = label / WORD_BITS;
+        if word >= self.bits.len() {
+            self.bits.resize(word + 1, 0);
+        }
+        let mask = 1 << (label % WORD_BITS);
+        if self.bits[word] & mask != 0 {
+            return false;
+        }
+        self.bits[word] |= mask;
+        self.len += 1;
+        true
+    }
+
+    #[inline]
+    pub fn contains(&self, label: Label) -> bool {
+        let label = label as usize;
+        matches!(self.bits.get(label / WORD_BITS), Some(word) if word & (1 << (label % WORD_BITS)) != 0)
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+}
+
+impl FromIterator<Label> for Vocabulary {
+    fn from_iter<I: IntoIterator<Item = Label>>(iter: I) -> Self {
+        let mut vocabulary = Self::new();
+        for label in iter {
+            vocabulary.insert(label);
+        }
+        vocabulary
+    }
+}
+
+/// This filter wraps another filter and only lets through the matches whose output label, i.e.
+/// the output label of the tr of the second FST, is epsilon or belongs to the vocabulary. The
+/// composition is then restricted to the output vocabulary without composing with a constraint
+/// FST.
+#[derive(Debug, Clone)]
+pub struct VocabularyComposeFilter<W, F1, F2, B1, B2, M1, M2, CF>
+where
+    W: Semiring,
+    F1: Fst<W>,
+    F2: Fst<W>,
+    B1: Borrow<F1> + Debug,
+    B2: Borrow<F2> + Debug,
+    M1: Matcher<W, F1, B1>,
+    M2: Matcher<W, F2, B2>,
+    CF: ComposeFilter<W, F1, F2, B1, B2, M1, M2>,
+{
+    filter: CF,
+    vocabulary: Option<Arc<Vocabulary>>,
+    ghost: PhantomData<(W, F1, F2, B1, B2, M1, M2)>,
+}
+
+#[derive(Debug)]
+pub struct VocabularyComposeFilterBuilder<W, F1, F2, B1, B2, M1, M2, CFB>
+where
+    W: Semiring,
+    F1: Fst<W>,
+    F2: Fst<W>,
+    B1: Borrow<F1> + Debug,
+    B2: Borrow<F2> + Debug,
+    M1: Matcher<W, F1, B1>,
+    M2: Matcher<W, F2, B2>,
+    CFB: ComposeFilterBuilder<W, F1, F2, B1, B2, M1, M2>,
+{
+    filter_builder: CFB,
+    vocabulary: Option<Arc<Vocabulary>>,
+    ghost: PhantomData<(W, F1, F2, B1, B2, M1, M2)>,
+}
+
+impl<W, F1, F2, B1, B2, M1, M2, CFB> VocabularyComposeFilterBuilder<W, F1, F2, B1, B2, M1, M2, CFB>
+where
+    W: Semiring,
+    F1: Fst<W>,
+    F2: Fst<W>,
+    B1: Borrow<F1> + Debug,
+    B2: Borrow<F2> + Debug,
+    M1: Matcher<W, F1, B1>,
+    M2: Matcher<W, F2, B2>,
+    CFB: ComposeFilterBuilder<W, F1, F2, B1, B2, M1, M2>,
+{
+    /// Restricts the filters built by `filter_builder` to the output labels in `vocabulary`.
+    /// Built with `ComposeFilterBuilder::new` instead, the filter lets all the matches through.
+    pub fn from_builder(filter_builder: CFB, vocabulary: Arc<Vocabulary>) -> Self {
+        Self {
+            filter_builder,
+            vocabulary: Some(vocabulary),
+            ghost: PhantomData,
+        }
+    }
+}
+
+impl<W, F1, F2, B1, B2, M1, M2, CFB> Clone
+    for VocabularyComposeFilterBuilder<W, F1, F2, B1, B2, M1, M2, CFB>
+where
+    W: Semiring,
+    F1: Fst<W>,
+    F2: Fst<W>,
+    B1: Borrow<F1> + Debug,
+    B2: Borrow<F2> + Debug,
+    M1: Matcher<W, F1, B1>,
+    M2: Matcher<W, F2, B2>,
+    CFB: ComposeFilterBuilder<W, F1, F2, B1, B2, M1, M2>,
+{
+    fn clone(&self) -> Self {
+        VocabularyComposeFilterBuilder {
+            filter_builder: self.filter_builder.clone(),
+            vocabulary: self.vocabulary.clone(),
+            ghost: PhantomData,
+        }
+    }
+}
+
+impl<W, F1, F2, B1, B2, M1, M2, CFB> ComposeFilterBuilder<W, F1, F2, B1, B2, M1, M2>
+    for VocabularyComposeFilterBuilder<W, F1, F2, B1, B2, M1, M2, CFB>
+where
+    W: Semiring,
+    F1: Fst<W>,
+    F2: Fst<W>,
+    B1: Borrow<F1> + Debug,
+    B2: Borrow<F2> + Debug,
+    M1: Matcher<W, F1, B1>,
+    M2: Matcher<W, F2, B2>,
+    CFB: ComposeFilterBuilder<W, F1, F2, B1, B2, M1, M2>,
+{
+    type IM1 = CFB::IM1;
+    type IM2 = CFB::IM2;
+    type CF = VocabularyComposeFilter<W, F1, F2, B1, B2, CFB::IM1, CFB::IM2, CFB::CF>;
+
+    fn new(fst1: B1, fst2: B2, matcher1: Option<M1>, matcher2: Option<M2>) -> Result<Self> {
+        Ok(Self {
+            filter_builder: CFB::new(fst1, fst2, matcher1, matcher2)?,
+            vocabulary: None,
+            ghost: PhantomData,
+        })
+    }
+
+    fn build(&self) -> Result<Self::CF> {
+        Ok(VocabularyComposeFilter {
+            filter: self.filter_builder.build()?,
+            vocabulary: self.vocabulary.clone(),
+            ghost: PhantomData,
+        })
+    }
+}
+
+impl<W, F1, F2, B1, B2, M1, M2, CF> ComposeFilter<W, F1, F2, B1, B2, M1, M2>
+    for VocabularyComposeFilter<W, F1, F2, B1, B2, M1, M2, CF>
+where
+    W: Semiring,
+    F1: Fst<W>,
+    F2: Fst<W>,
+    B1: Borrow<F1> + Debug,
+    B2: Borrow<F2> + Debug,
+    M1: Matcher<W, F1, B1>,
+    M2: Matcher<W, F2, B2>,
+    CF: ComposeFilter<W, F1, F2, B1, B2, M1, M2>,
+{
+    type FS = CF::FS;
+
+    fn start(&self) -> Self::FS {
+        self.filter.start()
+    }
+
+    fn set_state(&mut self, s1: StateId, s2: StateId, filter_state: &Self::FS) -> Result<()> {
+        self.filter.set_state(s1, s2, filter_state)
+    }
+
+    fn filter_tr(&mut self, arc1: &mut Tr<W>, arc2: &mut Tr<W>) -> Result<Self::FS> {
+        let fs = self.filter.filter_tr(arc1, arc2)?;
+        if let Some(vocabulary) = &self.vocabulary {
+            // The epsilon loops of the second FST have a `NO_LABEL` output label.
+            let olabel = arc2.olabel;
+            if olabel != EPS_LABEL && olabel != NO_LABEL && !vocabulary.contains(olabel) {
+                return Ok(Self::FS::new_no_state());
+            }
+        }
+        Ok(fs)
+    }
+
+    fn filter_final(&self, w1: &mut W, w2: &mut W) -> Result<()> {
+        self.filter.filter_final(w1, w2)
+    }
+
+    fn matcher1(&self) -> &M1 {
+        self.filter.matcher1()
+    }
+
+    fn matcher2(&self) -> &M2 {
+        self.filter.matcher2()
+    }
+
+    fn matcher1_shared(&self) -> &Arc<M1> {
+        self.filter.matcher1_shared()
+    }
+
+    fn matcher2_shared(&self) -> &Arc<M2> {
+        self.filter.matcher2_shared()
+    }
+
+    fn properties(&self, inprops: FstProperties) -> FstProperties {
+        self.filter.properties(inprops)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::algorithms::compose::{compose, compose_with_vocabulary};
+    use crate::fst_impls::VectorFst;
+    use crate::fst_traits::{Fst, MutableFst};
+    use crate::semirings::TropicalWeight;
+    use crate::utils::acceptor;
+
+    type F = VectorFst<TropicalWeight>;
+
+    #[test]
+    fn test_vocabulary_compose_filter() -> Result<()> {
+        // The lexicon maps 1 to the words 10 or 11, and 2 to the word 12.
+        let mut lexicon = F::new();
+        lexicon.add_states(2);
+        lexicon.set_start(0)?;
+        lexicon.add_tr(0, Tr::new(1, 10, 1.0, 1))?;
+        lexicon.add_tr(0, Tr::new(1, 11, 2.0, 1))?;
+        lexicon.add_tr(0, Tr::new(2, 12, 1.0, 1))?;
+        lexicon.add_tr(0, Tr::new(3, EPS_LABEL, 0.5, 1))?;
+        lexicon.set_final(1, TropicalWeight::one())?;
+        let input: F = acceptor(&[1], TropicalWeight::one());
+
+        let vocabulary: Vocabulary = vec![11, 12].into_iter().collect();
+        assert_eq!(vocabulary.len(), 2);
+        assert!(vocabulary.contains(11) && !vocabulary.contains(10));
+        assert!(!vocabulary.contains(1000));
+
+        let composed =
+            compose_with_vocabulary::<_, F, F, F, _, _>(&input, &lexicon, vocabulary.clone())?;
+        let paths: Vec<_> = composed.paths_iter().collect();
+        assert_eq!(paths.len(), 1);
+        assert_eq!(paths[0].olabels, vec![11]);
+        assert_eq!(paths[0].weight, TropicalWeight::new(2.0));
+
+        // The epsilon output labels are always allowed.
+        let eps_input: F = acceptor(&[3], TropicalWeight::one());
+        let composed =
+            compose_with_vocabulary::<_, F, F, F, _, _>(&eps_input, &lexicon, Vocabulary::new())?;
+        assert_eq!(composed.paths_iter().count(), 1);
+
+        // Same result as the composition with the constraint acceptor of the vocabulary.
+        let mut constraint = F::new();
+        constraint.add_state();
+        constraint.set_start(0)?;
+        constraint.set_final(0, TropicalWeight::one())?;
+        for label in [11, 12].iter() {
+            constraint.add_tr(0, Tr::new(*label, *label, TropicalWeight::one(), 0))?;
+        }
+        let restricted = compose::<_, F, F, F, _, _>(&lexicon, &constraint)?;
+        let expected = compose::<_, F, F, F, _, _>(&input, &restricted)?;
+        let composed = compose_with_vocabulary::<_, F, F, F, _, _>(&input, &lexicon, vocabulary)?;
+        assert_eq!(
+            expected.paths_iter().collect::<Vec<_>>(),
+            composed.paths_iter().collect::<Vec<_>>()
+        );
+        Ok(())
+    }
+}
@@ -1,6 +1,7 @@
 use anyhow::Result;
 use std::borrow::Borrow;
 use std::fmt::Debug;
+use std::ops::Range;
 use std::path::Path;
 
 use crate::algorithms::compose::compose_filters::{
@@ -8,13 +9,15 @@ use crate::algorithms::compose::compose_filters::{
 };
 use crate::algorithms::compose::matchers::{GenericMatcher, Matcher};
 use crate::algorithms::compose::{
-    ComposeFstOp, ComposeFstOpOptions, ComposeFstOpState, ComposeStateTuple,
+    ComposeFstOp, ComposeFstOpOptions, ComposeFstOpState, ComposeStateTuple, ComposeTimesOrder,
 };
 use crate::algorithms::lazy::{
-    FstCache, LazyFst, SerializableCache, SerializableLazyFst, SimpleVecCache,
+    ExpansionObserver, FstCache, LazyFst, SerializableCache, SerializableLazyFst, SimpleVecCache,
 };
 use crate::fst_properties::FstProperties;
-use crate::fst_traits::{AllocableFst, CoreFst, Fst, FstIterator, MutableFst, StateIterator};
+use crate::fst_traits::{
+    AllocableFst, CoreFst, Fst, FstIterData, FstIterator, MutableFst, StateIterator,
+};
 use crate::parsers::SerializeBinary;
 use crate::semirings::{Semiring, SerializableSemiring};
 use crate::{StateId, SymbolTable, TrsVec};
@@ -64,6 +67,7 @@ fn create_base<
 >(
     fst1: B1,
     fst2: B2,
+    times_order: ComposeTimesOrder,
 ) -> Result<
     ComposeFstOp<
         W,
@@ -84,9 +88,10 @@ fn create_base<
         >,
     >,
 > {
-    // TODO: change this once Lookahead matchers are supported.
+    // The lookahead composition relabels fst2, see `LookAheadComposeFst::new_lookahead`.
     let opts =
-        ComposeFstOpOptions::<GenericMatcher<_, _, _>, GenericMatcher<_, _, _>, _, _>::default();
+        ComposeFstOpOptions::<GenericMatcher<_, _, _>, GenericMatcher<_, _, _>, _, _>::default()
+            .with_times_order(times_order);
     let compose_impl = ComposeFstOp::new(fst1, fst2, opts)?;
     Ok(compose_impl)
 }
@@ -162,6 +167,46 @@ where
     pub fn compute<F: MutableFst<W> + AllocableFst<W>>(&self) -> Result<F> {
         self.0.compute()
     }
+
+    /// Limits the number of states expanded by the Lazy FST.
+    /// See [`LazyFst::with_max_expanded_states`].
+    pub fn with_max_expanded_states(self, max_expanded_states: usize) -> Self {
+        ComposeFst(self.0.with_max_expanded_states(max_expanded_states))
+    }
+
+    /// Reports the expansions of the Lazy FST to `observer`.
+    /// See [`LazyFst::with_expansion_observer`].
+    pub fn with_expansion_observer<O: ExpansionObserver + 'static>(self, observer: O) -> Self {
+        ComposeFst(self.0.with_expansion_observer(observer))
+    }
+
+    /// Turns the part of the Lazy FST expanded so far into a static one, preserving the state
+    /// ids. See [`LazyFst::freeze_visited`].
+    pub fn freeze_visited<F: MutableFst<W> + AllocableFst<W>>(&self) -> Result<F> {
+        self.0.freeze_visited()
+    }
+
+    /// Expands all the states of the Lazy FST. See [`LazyFst::expand_all_states`].
+    pub fn expand_all_states(&self) -> Result<()> {
+        self.0.expand_all_states()
+    }
+
+    /// Range of the states known to the cache. See [`LazyFst::known_states_range`].
+    pub fn known_states_range(&self) -> Range<StateId> {
+        self.0.known_states_range()
+    }
+
+    /// Iterates over all the states, returning the expansion errors. See
+    /// [`LazyFst::try_fst_iter`].
+    pub fn try_fst_iter(&self) -> impl Iterator<Item = Result<FstIterData<W, TrsVec<W>>>> + '_ {
+        self.0.try_fst_iter()
+    }
+
+    /// Evicts the cached states unreachable from `live_states`, returning their number.
+    /// See [`LazyFst::trim_cache`].
+    pub fn trim_cache(&self, live_states: &[StateId]) -> usize {
+        self.0.trim_cache(live_states)
+    }
 }
 
 impl<W, F1, F2, B1, B2>
@@ -191,9 +236,18 @@ where
     B2: Borrow<F2> + Debug + Clone,
 {
     pub fn new_auto(fst1: B1, fst2: B2) -> Result<Self> {
+        Self::new_auto_with_times_order(fst1, fst2, ComposeTimesOrder::default())
+    }
+
+    /// Same as `new_auto`, multiplying the weights of the matched trs in `times_order`.
+    pub fn new_auto_with_times_order(
+        fst1: B1,
+        fst2: B2,
+        times_order: ComposeTimesOrder,
+    ) -> Result<Self> {
         let isymt = fst1.borrow().input_symbols().cloned();
         let osymt = fst2.borrow().output_symbols().cloned();
-        let compose_impl = create_base(fst1, fst2)?;
+        let compose_impl = create_base(fst1, fst2, times_order)?;
         let fst_cache = SimpleVecCache::default();
         let fst = LazyFst::from_op_and_cache(compose_impl, fst_cache, isymt, osymt);
         Ok(ComposeFst(fst))
@@ -358,8 +412,11 @@ where
 mod test {
     use super::*;
     use crate::algorithms::compose::matchers::SortedMatcher;
+    use crate::algorithms::compose::{compose_with_config, ComposeConfig};
     use crate::fst_impls::VectorFst;
-    use crate::semirings::TropicalWeight;
+    use crate::fst_traits::ExpandedFst;
+    use crate::semirings::{StringWeightRestrict, TropicalWeight};
+    use crate::{Tr, Trs};
 
     #[test]
     fn test_compose_fst_sync() {
@@ -394,4 +451,99 @@ mod test {
             >,
         >();
     }
+
+    #[test]
+    fn test_compose_fst_freeze_visited() -> Result<()> {
+        let mut fst1 = VectorFst::<TropicalWeight>::new();
+        let s0 = fst1.add_state();
+        let s1 = fst1.add_state();
+        let s2 = fst1.add_state();
+        fst1.set_start(s0)?;
+        fst1.set_final(s2, TropicalWeight::one())?;
+        fst1.add_tr(s0, Tr::new(1, 1, 1.0, s1))?;
+        fst1.add_tr(s0, Tr::new(2, 2, 2.0, s2))?;
+        fst1.add_tr(s1, Tr::new(2, 2, 3.0, s2))?;
+        let fst2 = fst1.clone();
+
+        let compose_fst =
+            ComposeFst::<_, VectorFst<_>, VectorFst<_>, _, _, _, _, _>::new_auto(fst1, fst2)?;
+        let start = compose_fst.start().unwrap();
+        let num_start_trs = compose_fst.get_trs(start)?.len();
+        let frozen: VectorFst<_> = compose_fst.freeze_visited()?;
+        assert_eq!(frozen.start(), Some(start));
+        assert_eq!(frozen.num_trs(start)?, num_start_trs);
+        assert_eq!(frozen.num_states(), num_start_trs + 1);
+        for tr in frozen.get_trs(start)?.trs() {
+            assert_eq!(frozen.num_trs(tr.nextstate)?, 0);
+            assert_eq!(frozen.final_weight(tr.nextstate)?, None);
+        }
+
+        compose_fst.expand_all_states()?;
+        let frozen: VectorFst<_> = compose_fst.freeze_visited()?;
+        let computed: VectorFst<_> = compose_fst.compute()?;
+        assert_eq!(frozen, computed);
+        Ok(())
+    }
+
+    #[test]
+    fn test_compose_fst_trim_cache() -> Result<()> {
+        let mut fst1 = VectorFst::<TropicalWeight>::new();
+        fst1.add_states(3);
+        fst1.set_start(0)?;
+        fst1.set_final(2, TropicalWeight::one())?;
+        fst1.add_tr(0, Tr::new(1, 1, 1.0, 1))?;
+        fst1.add_tr(1, Tr::new(2, 2, 2.0, 2))?;
+        let fst2 = fst1.clone();
+
+        let compose_fst =
+            ComposeFst::<_, VectorFst<_>, VectorFst<_>, _, _, _, _, _>::new_auto(fst1, fst2)?;
+        compose_fst.expand_all_states()?;
+        let expected: VectorFst<_> = compose_fst.compute()?;
+        let start = compose_fst.start().unwrap();
+        let next = compose_fst.get_trs(start)?.trs()[0].nextstate;
+
+        // Only the start state is behind the frontier.
+        assert_eq!(compose_fst.trim_cache(&[next]), 1);
+        assert_eq!(compose_fst.trim_cache(&[next]), 0);
+        let frozen: VectorFst<_> = compose_fst.freeze_visited()?;
+        assert_eq!(frozen.num_trs(start)?, 0);
+        assert_eq!(frozen.num_trs(next)?, 1);
+
+        // The evicted states are expanded again when needed.
+        let computed: VectorFst<_> = compose_fst.compute()?;
+        assert_eq!(computed, expected);
+        Ok(())
+    }
+
+    #[test]
+    fn test_compose_fst_times_order() -> Result<()> {
+        let mut fst1 = VectorFst::<StringWeightRestrict>::new();
+        fst1.add_states(2);
+        fst1.set_start(0)?;
+        fst1.set_final(1, StringWeightRestrict::one())?;
+        fst1.add_tr(0, Tr::new(1, 2, StringWeightRestrict::from(vec![1]), 1))?;
+        let mut fst2 = VectorFst::<StringWeightRestrict>::new();
+        fst2.add_states(2);
+        fst2.set_start(0)?;
+        fst2.set_final(1, StringWeightRestrict::one())?;
+        fst2.add_tr(0, Tr::new(2, 3, StringWeightRestrict::from(vec![2]), 1))?;
+
+        let weight = |times_order| -> Result<StringWeightRestrict> {
+            let config = ComposeConfig::default().with_times_order(times_order);
+            let fst: VectorFst<_> = compose_with_config::<_, VectorFst<_>, VectorFst<_>, _, _, _>(
+                &fst1, &fst2, config,
+            )?;
+            let start = fst.start().unwrap();
+            Ok(fst.get_trs(start)?.trs()[0].weight.clone())
+        };
+        assert_eq!(
+            weight(ComposeTimesOrder::Fst1Fst2)?,
+            StringWeightRestrict::from(vec![1, 2])
+        );
+        assert_eq!(
+            weight(ComposeTimesOrder::Fst2Fst1)?,
+            StringWeightRestrict::from(vec![2, 1])
+        );
+        Ok(())
+    }
 }
@@ -13,13 +13,13 @@ use crate::algorithms::compose::filter_states::FilterState;
 use crate::algorithms::compose::lookahead_filters::lookahead_selector::Selector;
 use crate::algorithms::compose::matchers::{IterItemMatcher, MatcherFlags};
 use crate::algorithms::compose::matchers::{MatchType, Matcher, REQUIRE_PRIORITY};
-use crate::algorithms::compose::{ComposeFstOpOptions, ComposeStateTuple};
+use crate::algorithms::compose::{ComposeFstOpOptions, ComposeStateTuple, ComposeTimesOrder};
 use crate::algorithms::lazy::{AccessibleOpState, FstOp, SerializableOpState, StateTable};
 use crate::fst_properties::mutable_properties::compose_properties;
 use crate::fst_properties::FstProperties;
 use crate::fst_traits::Fst;
 use crate::parsers::SerializeBinary;
-use crate::semirings::Semiring;
+use crate::semirings::{Semiring, SemiringProperties};
 use crate::{StateId, Tr, Trs, TrsVec, EPS_LABEL, NO_LABEL};
 
 #[derive(Debug, Clone)]
@@ -82,6 +82,7 @@ where
     >,
     match_type: MatchType,
     properties: FstProperties,
+    times_order: ComposeTimesOrder,
     fst1: B1,
     fst2: B2,
 }
@@ -103,6 +104,7 @@ where
             compose_state: self.compose_state.clone(),
             match_type: self.match_type,
             properties: self.properties,
+            times_order: self.times_order,
             fst1: self.fst1.clone(),
             fst2: self.fst2.clone(),
         }
@@ -151,6 +153,17 @@ where
         let compose_filter = compose_filter_builder.build()?;
         let match_type = Self::match_type(compose_filter.matcher1(), compose_filter.matcher2())?;
 
+        // The weights pushed by the lookahead filters are divided in any order.
+        let lookahead_weight = (compose_filter.matcher1().flags()
+            | compose_filter.matcher2().flags())
+        .contains(MatcherFlags::LOOKAHEAD_WEIGHT);
+        if lookahead_weight {
+            W::properties().check(
+                SemiringProperties::COMMUTATIVE,
+                "ComposeFst with lookahead weight pushing",
+            )?;
+        }
+
         let fprops1 = fst1.borrow().properties();
         let fprops2 = fst2.borrow().properties();
         let cprops = compose_properties(fprops1, fprops2);
@@ -161,6 +174,7 @@ where
             compose_state: opts.op_state.unwrap_or_else(ComposeFstOpState::new),
             match_type,
             properties,
+            times_order: opts.times_order,
             fst1,
             fst2,
         })
@@ -275,7 +289,7 @@ where
             s1: arc1.nextstate,
             s2: arc2.nextstate,
         };
-        arc1.weight.times_assign(arc2.weight)?;
+        self.times_order.times(&mut arc1.weight, arc2.weight)?;
         Ok(Tr::new(
             arc1.ilabel,
             arc2.olabel,
@@ -440,7 +454,7 @@ where
         compose_filter.set_state(s1, s2, &tuple.fs)?;
         compose_filter.filter_final(&mut final1, &mut final2)?;
 
-        final1.times_assign(&final2)?;
+        self.times_order.times(&mut final1, final2)?;
         if final1.is_zero() {
             Ok(None)
         } else {
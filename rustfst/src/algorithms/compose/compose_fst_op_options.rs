@@ -1,8 +1,42 @@
+use anyhow::Result;
+
+use crate::semirings::Semiring;
+
+/// Order of the factors of the product of the weights of two matched trs or final states,
+/// which matters for the non-commutative semirings such as the string and Gallic ones.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd)]
+pub enum ComposeTimesOrder {
+    /// `w1 ⊗ w2`, the weight of the first FST on the left.
+    Fst1Fst2,
+    /// `w2 ⊗ w1`, e.g. when composing the reversed FSTs.
+    Fst2Fst1,
+}
+
+#[allow(clippy::derivable_impls)]
+impl Default for ComposeTimesOrder {
+    fn default() -> Self {
+        ComposeTimesOrder::Fst1Fst2
+    }
+}
+
+impl ComposeTimesOrder {
+    pub(crate) fn times<W: Semiring>(self, w1: &mut W, w2: W) -> Result<()> {
+        match self {
+            ComposeTimesOrder::Fst1Fst2 => w1.times_assign(w2),
+            ComposeTimesOrder::Fst2Fst1 => {
+                *w1 = w2.times(&*w1)?;
+                Ok(())
+            }
+        }
+    }
+}
+
 pub struct ComposeFstOpOptions<M1, M2, CFB, OS> {
     pub matcher1: Option<M1>,
     pub matcher2: Option<M2>,
     pub filter_builder: Option<CFB>,
     pub op_state: Option<OS>,
+    pub times_order: ComposeTimesOrder,
 }
 
 impl<M1, M2, CFB, OS> Default for ComposeFstOpOptions<M1, M2, CFB, OS> {
@@ -12,6 +46,7 @@ impl<M1, M2, CFB, OS> Default for ComposeFstOpOptions<M1, M2, CFB, OS> {
             matcher2: None,
             filter_builder: None,
             op_state: None,
+            times_order: ComposeTimesOrder::default(),
         }
     }
 }
@@ -33,6 +68,14 @@ impl<M1, M2, CFB, OS> ComposeFstOpOptions<M1, M2, CFB, OS> {
             matcher2: matcher2.into(),
             filter_builder: filter.into(),
             op_state: op_state.into(),
+            times_order: ComposeTimesOrder::default(),
+        }
+    }
+
+    pub fn with_times_order(self, times_order: ComposeTimesOrder) -> Self {
+        Self {
+            times_order,
+            ..self
         }
     }
 }
@@ -0,0 +1,492 @@
+use std::sync::Arc;
+
+use anyhow::Result;
+
+use crate::algorithms::compose::compose_filters::{
+    AltSequenceComposeFilterBuilder, ComposeFilterBuilder,
+};
+use crate::algorithms::compose::lookahead_filters::{
+    LookAheadComposeFilterBuilder, PushLabelsComposeFilterBuilder, PushWeightsComposeFilterBuilder,
+    SMatchOutput,
+};
+use crate::algorithms::compose::lookahead_matchers::{
+    LabelLookAheadMatcher, LookaheadMatcher, MatcherFlagsTrait,
+};
+use crate::algorithms::compose::matchers::{MatchType, Matcher, MatcherFlags, SortedMatcher};
+use crate::algorithms::compose::{ComposeFst, ComposeFstOpOptions, LabelReachableData, MatcherFst};
+use crate::algorithms::lazy::{FstCache, SimpleVecCache};
+use crate::fst_impls::VectorFst;
+use crate::fst_traits::{AllocableFst, ExpandedFst, Fst, MutableFst};
+use crate::semirings::{
+    Semiring, SemiringProperties, SerializableSemiring, WeaklyDivisibleSemiring, WeightQuantize,
+};
+
+/// Flags of the matcher of the `olabel_lookahead` FSTs of OpenFst : lookahead on the output
+/// labels, pushing the weights and the labels.
+#[derive(Debug, Clone, PartialOrd, PartialEq)]
+pub struct OLabelLookAheadFlags {}
+
+impl MatcherFlagsTrait for OLabelLookAheadFlags {
+    fn flags() -> MatcherFlags {
+        MatcherFlags::OLABEL_LOOKAHEAD_FLAGS
+    }
+}
+
+/// Flags of a matcher looking ahead on the output labels to push them, but not the weights.
+#[derive(Debug, Clone, PartialOrd, PartialEq)]
+pub struct OLabelPushLabelsFlags {}
+
+impl MatcherFlagsTrait for OLabelPushLabelsFlags {
+    fn flags() -> MatcherFlags {
+        MatcherFlags::OUTPUT_LOOKAHEAD_MATCHER
+            | MatcherFlags::LOOKAHEAD_PREFIX
+            | MatcherFlags::LOOKAHEAD_EPSILONS
+            | MatcherFlags::LOOKAHEAD_NON_EPSILON_PREFIX
+    }
+}
+
+/// Output label lookahead matcher over an FST `F`.
+pub type OLabelLookAheadMatcher<W, F, MFT = OLabelLookAheadFlags> =
+    LabelLookAheadMatcher<W, F, Arc<F>, SortedMatcher<W, F, Arc<F>>, MFT>;
+
+/// FST relabeled on its output labels, carrying the reachable labels of each state, as the
+/// `olabel_lookahead` FSTs of OpenFst.
+pub type OLabelLookAheadFst<W, MFT = OLabelLookAheadFlags> = MatcherFst<
+    W,
+    VectorFst<W>,
+    Arc<VectorFst<W>>,
+    OLabelLookAheadMatcher<W, VectorFst<W>, MFT>,
+    LabelReachableData,
+>;
+
+type LookAheadMatcher1<W, MFT> = OLabelLookAheadMatcher<W, OLabelLookAheadFst<W, MFT>, MFT>;
+type LookAheadMatcher2<W> = SortedMatcher<W, VectorFst<W>, Arc<VectorFst<W>>>;
+
+type SeqFilterBuilder<W, F1, M1> = AltSequenceComposeFilterBuilder<
+    W,
+    F1,
+    VectorFst<W>,
+    Arc<F1>,
+    Arc<VectorFst<W>>,
+    M1,
+    LookAheadMatcher2<W>,
+>;
+type LookFilterBuilder<W, F1, M1> = LookAheadComposeFilterBuilder<
+    W,
+    F1,
+    VectorFst<W>,
+    Arc<F1>,
+    Arc<VectorFst<W>>,
+    M1,
+    LookAheadMatcher2<W>,
+    SeqFilterBuilder<W, F1, M1>,
+    SMatchOutput,
+>;
+type PushWeightsFilterBuilder<W, F1, M1> = PushWeightsComposeFilterBuilder<
+    W,
+    F1,
+    VectorFst<W>,
+    Arc<F1>,
+    Arc<VectorFst<W>>,
+    M1,
+    LookAheadMatcher2<W>,
+    LookFilterBuilder<W, F1, M1>,
+    SMatchOutput,
+>;
+
+/// Lookahead compose filter, pushing the labels and the weights, of the `olabel_lookahead`
+/// composition, looking ahead on the output labels of `F1` with the matcher `M1`.
+pub type OLabelLookAheadComposeFilterBuilder<
+    W,
+    F1 = OLabelLookAheadFst<W>,
+    M1 = LookAheadMatcher1<W, OLabelLookAheadFlags>,
+> = PushLabelsComposeFilterBuilder<
+    W,
+    F1,
+    VectorFst<W>,
+    Arc<F1>,
+    Arc<VectorFst<W>>,
+    M1,
+    LookAheadMatcher2<W>,
+    PushWeightsFilterBuilder<W, F1, M1>,
+    SMatchOutput,
+>;
+
+/// Lookahead compose filter pushing the output labels only.
+pub type OLabelPushLabelsComposeFilterBuilder<W> = PushLabelsComposeFilterBuilder<
+    W,
+    OLabelLookAheadFst<W, OLabelPushLabelsFlags>,
+    VectorFst<W>,
+    Arc<OLabelLookAheadFst<W, OLabelPushLabelsFlags>>,
+    Arc<VectorFst<W>>,
+    LookAheadMatcher1<W, OLabelPushLabelsFlags>,
+    LookAheadMatcher2<W>,
+    LookFilterBuilder<
+        W,
+        OLabelLookAheadFst<W, OLabelPushLabelsFlags>,
+        LookAheadMatcher1<W, OLabelPushLabelsFlags>,
+    >,
+    SMatchOutput,
+>;
+
+/// Lazy lookahead composition of an FST `F1`, looked ahead on its output labels with the
+/// matcher `M1`, with a `VectorFst`. By default, `F1` is an `olabel_lookahead` FST.
+pub type LookAheadComposeFst<
+    W,
+    F1 = OLabelLookAheadFst<W>,
+    M1 = LookAheadMatcher1<W, OLabelLookAheadFlags>,
+    Cache = SimpleVecCache<W>,
+> = ComposeFst<
+    W,
+    F1,
+    VectorFst<W>,
+    Arc<F1>,
+    Arc<VectorFst<W>>,
+    M1,
+    LookAheadMatcher2<W>,
+    OLabelLookAheadComposeFilterBuilder<W, F1, M1>,
+    Cache,
+>;
+
+/// Lazy composition of a `VectorFst` with another one, pushing the output labels of the first
+/// one on the fly.
+pub type PushLabelsComposeFst<W, Cache = SimpleVecCache<W>> = ComposeFst<
+    W,
+    OLabelLookAheadFst<W, OLabelPushLabelsFlags>,
+    VectorFst<W>,
+    Arc<OLabelLookAheadFst<W, OLabelPushLabelsFlags>>,
+    Arc<VectorFst<W>>,
+    LookAheadMatcher1<W, OLabelPushLabelsFlags>,
+    LookAheadMatcher2<W>,
+    OLabelPushLabelsComposeFilterBuilder<W>,
+    Cache,
+>;
+
+/// Builds the lazy composition of `fst1`, looked ahead on its output labels with `matcher1`,
+/// with `fst2` and the filter `CFB`.
+fn new_with_lookahead_matcher<W, F1, M1, CFB, Cache>(
+    fst1: Arc<F1>,
+    fst2: Arc<VectorFst<W>>,
+    matcher1: M1,
+) -> Result<
+    ComposeFst<
+        W,
+        F1,
+        VectorFst<W>,
+        Arc<F1>,
+        Arc<VectorFst<W>>,
+        M1,
+        LookAheadMatcher2<W>,
+        CFB,
+        Cache,
+    >,
+>
+where
+    W: Semiring,
+    F1: Fst<W>,
+    M1: LookaheadMatcher<W, F1, Arc<F1>>,
+    CFB: ComposeFilterBuilder<
+        W,
+        F1,
+        VectorFst<W>,
+        Arc<F1>,
+        Arc<VectorFst<W>>,
+        M1,
+        LookAheadMatcher2<W>,
+    >,
+    Cache: FstCache<W> + Default,
+{
+    let matcher2 = LookAheadMatcher2::new(Arc::clone(&fst2), MatchType::MatchInput)?;
+    let compose_filter = CFB::new(
+        Arc::clone(&fst1),
+        Arc::clone(&fst2),
+        Some(matcher1),
+        Some(matcher2),
+    )?;
+    let opts = ComposeFstOpOptions::new(None, None, compose_filter, None);
+    ComposeFst::new_with_options(fst1, fst2, opts)
+}
+
+/// Relabels `fst1` and `fst2` for a lookahead on the output labels of `fst1`, and builds the
+/// lazy composition with the filter `CFB`.
+fn new_olabel_lookahead<W, MFT, CFB, Cache>(
+    fst1: VectorFst<W>,
+    fst2: VectorFst<W>,
+) -> Result<
+    ComposeFst<
+        W,
+        OLabelLookAheadFst<W, MFT>,
+        VectorFst<W>,
+        Arc<OLabelLookAheadFst<W, MFT>>,
+        Arc<VectorFst<W>>,
+        LookAheadMatcher1<W, MFT>,
+        LookAheadMatcher2<W>,
+        CFB,
+        Cache,
+    >,
+>
+where
+    W: Semiring,
+    MFT: MatcherFlagsTrait + 'static,
+    CFB: ComposeFilterBuilder<
+        W,
+        OLabelLookAheadFst<W, MFT>,
+        VectorFst<W>,
+        Arc<OLabelLookAheadFst<W, MFT>>,
+        Arc<VectorFst<W>>,
+        LookAheadMatcher1<W, MFT>,
+        LookAheadMatcher2<W>,
+    >,
+    Cache: FstCache<W> + Default,
+{
+    let mut fst2 = fst2;
+    // The input labels of fst2 are sorted by the relabeling.
+    let fst1 = Arc::new(OLabelLookAheadFst::<W, MFT>::new_with_relabeling(
+        fst1, &mut fst2, true,
+    )?);
+    let matcher1 = LookAheadMatcher1::new_with_data(
+        Arc::clone(&fst1),
+        MatchType::MatchOutput,
+        fst1.data(MatchType::MatchOutput).cloned(),
+    )?;
+    new_with_lookahead_matcher(fst1, Arc::new(fst2), matcher1)
+}
+
+impl<W, F1, M1, Cache> LookAheadComposeFst<W, F1, M1, Cache>
+where
+    W: SerializableSemiring + WeaklyDivisibleSemiring + WeightQuantize,
+    F1: ExpandedFst<W>,
+    M1: LookaheadMatcher<W, F1, Arc<F1>>,
+    Cache: FstCache<W> + Default,
+{
+    /// Composes `fst1` with `fst2` looking ahead on the output labels of `fst1` with
+    /// `matcher1`, pushing the weights and the labels towards the start.
+    ///
+    /// `matcher1` must match the output labels of `fst1`. A `TrLookAheadMatcher` looks ahead
+    /// on the transitions of any FST sorted on its output labels, while a
+    /// `LabelLookAheadMatcher` requires the reachable labels computed by a `MatcherFst`, see
+    /// `new_lookahead` for the relabeling. The input labels of `fst2` must be sorted.
+    ///
+    /// Pushing the weights requires a commutative semiring.
+    pub fn new_with_matcher(fst1: Arc<F1>, fst2: Arc<VectorFst<W>>, matcher1: M1) -> Result<Self> {
+        W::properties().check(
+            SemiringProperties::COMMUTATIVE,
+            "LookAheadComposeFst::new_with_matcher",
+        )?;
+        new_with_lookahead_matcher(fst1, fst2, matcher1)
+    }
+}
+
+impl<W, Cache>
+    LookAheadComposeFst<W, OLabelLookAheadFst<W>, LookAheadMatcher1<W, OLabelLookAheadFlags>, Cache>
+where
+    W: SerializableSemiring + WeaklyDivisibleSemiring + WeightQuantize,
+    Cache: FstCache<W> + Default,
+{
+    /// Composes `fst1` with `fst2` looking ahead on the output labels of `fst1`, as OpenFst
+    /// does when `fst1` is an `olabel_lookahead` FST.
+    ///
+    /// The output labels of `fst1` are relabeled so that the labels reachable from each state
+    /// form intervals, and the input labels of `fst2` accordingly (their symbol tables are
+    /// dropped). A transition of `fst1` is then followed only if one of the labels it reaches
+    /// matches a transition of the current state of `fst2` : composing a lexicon with a grammar
+    /// doesn't expand the dead-end paths of the words the grammar can't accept. The weights and
+    /// the labels are pushed towards the start, so the result is equivalent but not identical
+    /// to the one of `ComposeFst::new_auto`.
+    ///
+    /// Pushing the weights requires a commutative semiring.
+    pub fn new_lookahead(fst1: VectorFst<W>, fst2: VectorFst<W>) -> Result<Self> {
+        W::properties().check(
+            SemiringProperties::COMMUTATIVE,
+            "LookAheadComposeFst::new_lookahead",
+        )?;
+        new_olabel_lookahead(fst1, fst2)
+    }
+}
+
+impl<W, Cache> PushLabelsComposeFst<W, Cache>
+where
+    W: Semiring,
+    Cache: FstCache<W> + Default,
+{
+    /// Composes `fst1` with `fst2` as `LookAheadComposeFst::new_lookahead`, but only pushing
+    /// the output labels of `fst1`, not the weights.
+    ///
+    /// As soon as a single output label is reachable from a state of `fst1`, e.g. the word of
+    /// a pronunciation lexicon once its pronunciation has been disambiguated, it is emitted
+    /// and matched against `fst2` instead of at the end of the pronunciation. The output delay
+    /// of the result is reduced without pushing the labels of the whole composed graph
+    /// afterwards, so that a streaming recognizer outputs the words earlier. The weights are
+    /// left in place, so any semiring is supported.
+    pub fn new_push_labels(fst1: VectorFst<W>, fst2: VectorFst<W>) -> Result<Self> {
+        new_olabel_lookahead(fst1, fst2)
+    }
+}
+
+/// Computes the lookahead composition of `fst1` and `fst2`.
+/// See [`LookAheadComposeFst::new_lookahead`].
+pub fn compose_lookahead<W, F3>(fst1: VectorFst<W>, fst2: VectorFst<W>) -> Result<F3>
+where
+    W: SerializableSemiring + WeaklyDivisibleSemiring + WeightQuantize,
+    F3: MutableFst<W> + AllocableFst<W>,
+{
+    LookAheadComposeFst::<W>::new_lookahead(fst1, fst2)?.compute()
+}
+
+/// Computes the composition of `fst1` and `fst2` pushing the output labels of `fst1`.
+/// See [`PushLabelsComposeFst::new_push_labels`].
+pub fn compose_push_labels<W, F3>(fst1: VectorFst<W>, fst2: VectorFst<W>) -> Result<F3>
+where
+    W: Semiring,
+    F3: MutableFst<W> + AllocableFst<W>,
+{
+    PushLabelsComposeFst::<W>::new_push_labels(fst1, fst2)?.compute()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::algorithms::compose::compose;
+    use crate::algorithms::compose::lookahead_matchers::TrLookAheadMatcher;
+    use crate::algorithms::connect;
+    use crate::fst_traits::{CoreFst, ExpandedFst, Fst};
+    use crate::semirings::{StringWeightRestrict, TropicalWeight};
+    use crate::utils::acceptor;
+    use crate::{FstPath, Tr, Trs, EPS_LABEL};
+
+    #[test]
+    fn test_compose_lookahead() -> Result<()> {
+        // Lexicon : "1 2" -> 10 and "3 4" -> 11, the words being output at their end.
+        let mut lexicon = VectorFst::<TropicalWeight>::new();
+        lexicon.add_states(5);
+        lexicon.set_start(0)?;
+        lexicon.add_tr(0, Tr::new(1, EPS_LABEL, 1.0, 1))?;
+        lexicon.add_tr(1, Tr::new(2, 10, 2.0, 4))?;
+        lexicon.add_tr(0, Tr::new(3, EPS_LABEL, 1.0, 2))?;
+        lexicon.add_tr(2, Tr::new(4, 11, 1.0, 3))?;
+        lexicon.add_tr(3, Tr::new(EPS_LABEL, EPS_LABEL, 0.0, 4))?;
+        lexicon.set_final(4, 0.5)?;
+        // Grammar accepting the word 10 only.
+        let grammar: VectorFst<TropicalWeight> = acceptor(&[10], TropicalWeight::new(3.0));
+
+        let lookahead: VectorFst<TropicalWeight> =
+            compose_lookahead(lexicon.clone(), grammar.clone())?;
+        let plain: VectorFst<TropicalWeight> =
+            ComposeFst::<_, VectorFst<_>, VectorFst<_>, _, _, _, _, _>::new_auto(
+                &lexicon, &grammar,
+            )?
+            .compute()?;
+
+        // The path of the word 11 is not expanded.
+        assert!(lookahead.num_states() < plain.num_states());
+
+        let mut lookahead = lookahead;
+        connect(&mut lookahead)?;
+        let paths: Vec<_> = lookahead.paths_iter().collect();
+        assert_eq!(
+            paths,
+            vec![FstPath::new(vec![1, 2], vec![10], TropicalWeight::new(6.5))]
+        );
+
+        let plain_paths: Vec<_> =
+            compose::<_, VectorFst<_>, VectorFst<_>, VectorFst<_>, _, _>(&lexicon, &grammar)?
+                .paths_iter()
+                .collect();
+        assert_eq!(paths, plain_paths);
+        Ok(())
+    }
+
+    #[test]
+    fn test_compose_lookahead_with_tr_matcher() -> Result<()> {
+        type TrMatcher<W> = TrLookAheadMatcher<
+            W,
+            VectorFst<W>,
+            Arc<VectorFst<W>>,
+            SortedMatcher<W, VectorFst<W>, Arc<VectorFst<W>>>,
+            OLabelLookAheadFlags,
+        >;
+
+        // Lexicon : "1 2" -> 10 and "3 4" -> 11, not relabeled.
+        let mut lexicon = VectorFst::<TropicalWeight>::new();
+        lexicon.add_states(4);
+        lexicon.set_start(0)?;
+        lexicon.add_tr(0, Tr::new(1, EPS_LABEL, 1.0, 1))?;
+        lexicon.add_tr(1, Tr::new(2, 10, 2.0, 3))?;
+        lexicon.add_tr(0, Tr::new(3, EPS_LABEL, 1.0, 2))?;
+        lexicon.add_tr(2, Tr::new(4, 11, 1.0, 3))?;
+        lexicon.set_final(3, 0.5)?;
+        let grammar: VectorFst<TropicalWeight> = acceptor(&[10], TropicalWeight::new(3.0));
+        let plain_paths: Vec<_> =
+            compose::<_, VectorFst<_>, VectorFst<_>, VectorFst<_>, _, _>(&lexicon, &grammar)?
+                .paths_iter()
+                .collect();
+
+        let lexicon = Arc::new(lexicon);
+        let matcher1 = TrMatcher::new(Arc::clone(&lexicon), MatchType::MatchOutput)?;
+        let mut lookahead: VectorFst<TropicalWeight> =
+            LookAheadComposeFst::<_, _, _>::new_with_matcher(lexicon, Arc::new(grammar), matcher1)?
+                .compute()?;
+        connect(&mut lookahead)?;
+        let paths: Vec<_> = lookahead.paths_iter().collect();
+        assert_eq!(paths, plain_paths);
+        Ok(())
+    }
+
+    #[test]
+    fn test_compose_lookahead_non_commutative() -> Result<()> {
+        let fst1: VectorFst<StringWeightRestrict> = acceptor(&[1], StringWeightRestrict::one());
+        let fst2 = fst1.clone();
+        assert!(LookAheadComposeFst::<StringWeightRestrict>::new_lookahead(fst1, fst2).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_compose_push_labels() -> Result<()> {
+        // Lexicon : "1 2 3" -> 10 and "4 5 6" -> 11, the words being output at their end.
+        let mut lexicon = VectorFst::<TropicalWeight>::new();
+        lexicon.add_states(6);
+        lexicon.set_start(0)?;
+        lexicon.add_tr(0, Tr::new(1, EPS_LABEL, 1.0, 1))?;
+        lexicon.add_tr(1, Tr::new(2, EPS_LABEL, 0.0, 2))?;
+        lexicon.add_tr(2, Tr::new(3, 10, 0.0, 3))?;
+        lexicon.add_tr(0, Tr::new(4, EPS_LABEL, 2.0, 4))?;
+        lexicon.add_tr(4, Tr::new(5, EPS_LABEL, 0.0, 5))?;
+        lexicon.add_tr(5, Tr::new(6, 11, 0.0, 3))?;
+        lexicon.set_final(3, TropicalWeight::one())?;
+        // Grammar accepting the words 10 and 11.
+        let mut grammar = VectorFst::<TropicalWeight>::new();
+        grammar.add_states(2);
+        grammar.set_start(0)?;
+        grammar.add_tr(0, Tr::new(10, 10, 2.0, 1))?;
+        grammar.add_tr(0, Tr::new(11, 11, 3.0, 1))?;
+        grammar.set_final(1, TropicalWeight::one())?;
+
+        let mut pushed: VectorFst<TropicalWeight> =
+            compose_push_labels(lexicon.clone(), grammar.clone())?;
+        connect(&mut pushed)?;
+
+        // The words are output as soon as their first phone is read.
+        let start = pushed.start().unwrap();
+        let mut first_trs: Vec<_> = pushed
+            .get_trs(start)?
+            .trs()
+            .iter()
+            .map(|tr| (tr.ilabel, tr.olabel))
+            .collect();
+        first_trs.sort_unstable();
+        assert_eq!(first_trs, vec![(1, 10), (4, 11)]);
+
+        let paths: Vec<_> = pushed.paths_iter().collect();
+        let plain_paths: Vec<_> =
+            compose::<_, VectorFst<_>, VectorFst<_>, VectorFst<_>, _, _>(&lexicon, &grammar)?
+                .paths_iter()
+                .collect();
+        assert_eq!(paths.len(), 2);
+        assert_eq!(paths.len(), plain_paths.len());
+        for path in plain_paths.iter() {
+            assert!(paths.contains(path));
+        }
+        Ok(())
+    }
+}
@@ -5,14 +5,15 @@ use std::sync::Arc;
 use anyhow::Result;
 
 use crate::algorithms::compose::compose_filters::{
-    AltSequenceComposeFilterBuilder, MatchComposeFilterBuilder, NoMatchComposeFilterBuilder,
-    NullComposeFilterBuilder, SequenceComposeFilterBuilder, TrivialComposeFilterBuilder,
+    AltSequenceComposeFilterBuilder, ComposeFilterBuilder, MatchComposeFilterBuilder,
+    NoMatchComposeFilterBuilder, NullComposeFilterBuilder, SequenceComposeFilterBuilder,
+    TrivialComposeFilterBuilder, Vocabulary, VocabularyComposeFilterBuilder,
 };
-use crate::algorithms::compose::matchers::{Matcher, SigmaMatcher, SortedMatcher};
+use crate::algorithms::compose::matchers::{GenericMatcher, Matcher, SigmaMatcher, SortedMatcher};
+use crate::algorithms::compose::matchers::{MatchType, MatcherRewriteMode};
 use crate::algorithms::compose::ComposeFst;
+use crate::algorithms::compose::{ComposeFstOpOptions, ComposeTimesOrder};
 use crate::fst_traits::{AllocableFst, ExpandedFst, Fst, MutableFst};
-use crate::prelude::compose::matchers::{MatchType, MatcherRewriteMode};
-use crate::prelude::compose::ComposeFstOpOptions;
 use crate::semirings::Semiring;
 use crate::Label;
 
@@ -51,6 +52,7 @@ pub struct ComposeConfig {
     pub matcher1_config: MatcherConfig,
     pub matcher2_config: MatcherConfig,
     pub connect: bool,
+    pub times_order: ComposeTimesOrder,
 }
 
 impl Default for ComposeConfig {
@@ -60,6 +62,42 @@ impl Default for ComposeConfig {
             matcher1_config: MatcherConfig::default(),
             matcher2_config: MatcherConfig::default(),
             connect: true,
+            times_order: ComposeTimesOrder::default(),
+        }
+    }
+}
+
+impl ComposeConfig {
+    pub fn with_compose_filter(self, compose_filter: ComposeFilterEnum) -> Self {
+        Self {
+            compose_filter,
+            ..self
+        }
+    }
+
+    pub fn with_matcher1_config(self, matcher1_config: MatcherConfig) -> Self {
+        Self {
+            matcher1_config,
+            ..self
+        }
+    }
+
+    pub fn with_matcher2_config(self, matcher2_config: MatcherConfig) -> Self {
+        Self {
+            matcher2_config,
+            ..self
+        }
+    }
+
+    pub fn with_connect(self, connect: bool) -> Self {
+        Self { connect, ..self }
+    }
+
+    /// Order of the product of the weights of the matched trs, `w1 ⊗ w2` by default.
+    pub fn with_times_order(self, times_order: ComposeTimesOrder) -> Self {
+        Self {
+            times_order,
+            ..self
         }
     }
 }
@@ -114,9 +152,11 @@ macro_rules! run_compose {
         $f1: ty, $f2: ty,
         $builder: tt,
         $matcher1: expr, $matcher1_ty: ty,
-        $matcher2: expr, $matcher2_ty: ty
+        $matcher2: expr, $matcher2_ty: ty,
+        $times_order: expr
     ) => {{
-        let compose_fst_op_opts = ComposeFstOpOptions::new($matcher1, $matcher2, None, None);
+        let compose_fst_op_opts = ComposeFstOpOptions::new($matcher1, $matcher2, None, None)
+            .with_times_order($times_order);
         ComposeFst::<
             _,
             $f1,
@@ -134,28 +174,28 @@ macro_rules! run_compose {
 macro_rules! compose_generate_matchers {
     (
         $fst1: expr, $fst2: expr, $f1: ty, $f2: ty,
-        $builder: tt, $matcher1_enum: expr, $matcher2_enum: expr
+        $builder: tt, $matcher1_enum: expr, $matcher2_enum: expr, $times_order: expr
     ) => {
         {
             match ($matcher1_enum, $matcher2_enum) {
                 (MatcherEnum::SortedMatcher(m1), MatcherEnum::SortedMatcher(m2)) => {
                     run_compose!(
-                        $fst1.borrow(), $fst2.borrow(), $f1, $f2, $builder, Some(m1), SortedMatcher<_, _, _>, Some(m2), SortedMatcher<_,_,_>
+                        $fst1.borrow(), $fst2.borrow(), $f1, $f2, $builder, Some(m1), SortedMatcher<_, _, _>, Some(m2), SortedMatcher<_,_,_>, $times_order
                     )
                 },
                 (MatcherEnum::SigmaMatcher(m1), MatcherEnum::SortedMatcher(m2)) => {
                     run_compose!(
-                        $fst1.borrow(), $fst2.borrow(), $f1, $f2, $builder, Some(m1), SigmaMatcher<_, _, _, _>, Some(m2), SortedMatcher<_,_,_>
+                        $fst1.borrow(), $fst2.borrow(), $f1, $f2, $builder, Some(m1), SigmaMatcher<_, _, _, _>, Some(m2), SortedMatcher<_,_,_>, $times_order
                     )
                 },
                 (MatcherEnum::SortedMatcher(m1), MatcherEnum::SigmaMatcher(m2)) => {
                     run_compose!(
-                        $fst1.borrow(), $fst2.borrow(), $f1, $f2, $builder, Some(m1), SortedMatcher<_, _, _>, Some(m2), SigmaMatcher<_,_,_,_>
+                        $fst1.borrow(), $fst2.borrow(), $f1, $f2, $builder, Some(m1), SortedMatcher<_, _, _>, Some(m2), SigmaMatcher<_,_,_,_>, $times_order
                     )
                 },
                 (MatcherEnum::SigmaMatcher(m1), MatcherEnum::SigmaMatcher(m2)) => {
                     run_compose!(
-                        $fst1.borrow(), $fst2.borrow(), $f1, $f2, $builder, Some(m1), SigmaMatcher<_, _, _, _>, Some(m2), SigmaMatcher<_,_,_,_>
+                        $fst1.borrow(), $fst2.borrow(), $f1, $f2, $builder, Some(m1), SigmaMatcher<_, _, _, _>, Some(m2), SigmaMatcher<_,_,_,_>, $times_order
                     )
                 }
             }
@@ -185,7 +225,7 @@ pub fn compose_with_config<
     let mut ofst: F3 = match config.compose_filter {
         ComposeFilterEnum::AutoFilter => {
             if config.matcher1_config.empty() && config.matcher2_config.empty() {
-                ComposeFst::new_auto(fst1, fst2)?.compute()?
+                ComposeFst::new_auto_with_times_order(fst1, fst2, config.times_order)?.compute()?
             } else {
                 bail!("Custom MatcherConfig not supported with AutoFilter")
             }
@@ -198,7 +238,8 @@ pub fn compose_with_config<
                 F2,
                 NullComposeFilterBuilder,
                 matcher1,
-                matcher2
+                matcher2,
+                config.times_order
             )
         }
         ComposeFilterEnum::SequenceFilter => {
@@ -209,7 +250,8 @@ pub fn compose_with_config<
                 F2,
                 SequenceComposeFilterBuilder,
                 matcher1,
-                matcher2
+                matcher2,
+                config.times_order
             )
         }
         ComposeFilterEnum::AltSequenceFilter => {
@@ -220,7 +262,8 @@ pub fn compose_with_config<
                 F2,
                 AltSequenceComposeFilterBuilder,
                 matcher1,
-                matcher2
+                matcher2,
+                config.times_order
             )
         }
         ComposeFilterEnum::MatchFilter => {
@@ -231,7 +274,8 @@ pub fn compose_with_config<
                 F2,
                 MatchComposeFilterBuilder,
                 matcher1,
-                matcher2
+                matcher2,
+                config.times_order
             )
         }
         ComposeFilterEnum::NoMatchFilter => {
@@ -242,7 +286,8 @@ pub fn compose_with_config<
                 F2,
                 NoMatchComposeFilterBuilder,
                 matcher1,
-                matcher2
+                matcher2,
+                config.times_order
             )
         }
         ComposeFilterEnum::TrivialFilter => {
@@ -253,7 +298,8 @@ pub fn compose_with_config<
                 F2,
                 TrivialComposeFilterBuilder,
                 matcher1,
-                matcher2
+                matcher2,
+                config.times_order
             )
         }
     };
@@ -304,3 +350,75 @@ pub fn compose<
     let config = ComposeConfig::default();
     compose_with_config(fst1, fst2, config)
 }
+
+/// Composes two transducers as `compose`, only keeping the paths whose output labels belong to
+/// `vocabulary`, e.g. to restrict a decoding to a subset of the words of a lexicon. The trs of
+/// `fst2` with other output labels are filtered during the composition by a
+/// `VocabularyComposeFilter`, which is cheaper than composing the result with the acceptor of
+/// the vocabulary.
+///
+/// # Example
+/// ```
+/// # use anyhow::Result;
+/// # use rustfst::algorithms::compose::compose_filters::Vocabulary;
+/// # use rustfst::algorithms::compose::compose_with_vocabulary;
+/// # use rustfst::fst_impls::VectorFst;
+/// # use rustfst::fst_traits::{Fst, MutableFst};
+/// # use rustfst::semirings::{Semiring, TropicalWeight};
+/// # use rustfst::utils::acceptor;
+/// # use rustfst::Tr;
+/// # fn main() -> Result<()> {
+/// let mut lexicon = VectorFst::<TropicalWeight>::new();
+/// lexicon.add_states(2);
+/// lexicon.set_start(0)?;
+/// lexicon.add_tr(0, Tr::new(1, 10, 1.0, 1))?;
+/// lexicon.add_tr(0, Tr::new(1, 11, 2.0, 1))?;
+/// lexicon.set_final(1, TropicalWeight::one())?;
+/// let input: VectorFst<TropicalWeight> = acceptor(&[1], TropicalWeight::one());
+///
+/// let vocabulary: Vocabulary = vec![11].into_iter().collect();
+/// let composed = compose_with_vocabulary::<_, VectorFst<_>, VectorFst<_>, VectorFst<_>, _, _>(
+///     &input,
+///     &lexicon,
+///     vocabulary,
+/// )?;
+/// let paths: Vec<_> = composed.paths_iter().collect();
+/// assert_eq!(paths.len(), 1);
+/// assert_eq!(paths[0].olabels, vec![11]);
+/// # Ok(())
+/// # }
+/// ```
+pub fn compose_with_vocabulary<
+    W: Semiring,
+    F1: ExpandedFst<W>,
+    F2: ExpandedFst<W>,
+    F3: MutableFst<W> + AllocableFst<W>,
+    B1: Borrow<F1> + Debug + Clone,
+    B2: Borrow<F2> + Debug + Clone,
+>(
+    fst1: B1,
+    fst2: B2,
+    vocabulary: Vocabulary,
+) -> Result<F3> {
+    let filter_builder = SequenceComposeFilterBuilder::<
+        _,
+        F1,
+        F2,
+        B1,
+        B2,
+        GenericMatcher<_, _, _>,
+        GenericMatcher<_, _, _>,
+    >::new(fst1.clone(), fst2.clone(), None, None)?;
+    let filter_builder =
+        VocabularyComposeFilterBuilder::from_builder(filter_builder, Arc::new(vocabulary));
+    let opts = ComposeFstOpOptions::<GenericMatcher<_, _, _>, GenericMatcher<_, _, _>, _, _>::new(
+        None,
+        None,
+        filter_builder,
+        None,
+    );
+    let mut ofst: F3 =
+        ComposeFst::<_, F1, F2, B1, B2, _, _, _>::new_with_options(fst1, fst2, opts)?.compute()?;
+    crate::algorithms::connect(&mut ofst)?;
+    Ok(ofst)
+}
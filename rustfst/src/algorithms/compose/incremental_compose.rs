@@ -0,0 +1,262 @@
+use std::borrow::Borrow;
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+use std::marker::PhantomData;
+
+use anyhow::Result;
+use superslice::Ext;
+
+use crate::algorithms::connect;
+use crate::fst_impls::VectorFst;
+use crate::fst_traits::{ExpandedFst, Fst, MutableFst};
+use crate::semirings::Semiring;
+use crate::{Label, StateId, Tr, Trs, EPS_LABEL};
+
+/// Composition whose left operand is a linear acceptor growing one frame at a time, e.g. the
+/// frames of an utterance being decoded, against a static right operand, e.g. the decoding
+/// graph. Each frame is a set of alternative labels with their weights, read between the
+/// state of the left operand ending the previous frame and a new state.
+///
+/// Only the states of the composition on the last state of the left operand, the frontier,
+/// are extended when a frame is pushed : the states built for the previous frames are never
+/// revisited, so that the cost of a frame doesn't depend on the length of the utterance.
+/// The epsilon transitions of the right operand are followed as soon as a state is created,
+/// and those of the left operand are read before them, as with the `SequenceComposeFilter`,
+/// so that no redundant epsilon path is created.
+///
+/// # Example
+/// ```
+/// # use rustfst::algorithms::compose::IncrementalCompose;
+/// # use rustfst::fst_impls::VectorFst;
+/// # use rustfst::fst_traits::{Fst, MutableFst};
+/// # use rustfst::semirings::{Semiring, TropicalWeight};
+/// # use rustfst::Tr;
+/// # use anyhow::Result;
+/// # fn main() -> Result<()> {
+/// // Maps 1 to 10 and 2 to 11, any number of times.
+/// let mut graph = VectorFst::<TropicalWeight>::new();
+/// graph.add_state();
+/// graph.set_start(0)?;
+/// graph.add_tr(0, Tr::new(1, 10, 0.0, 0))?;
+/// graph.add_tr(0, Tr::new(2, 11, 0.0, 0))?;
+/// graph.set_final(0, TropicalWeight::one())?;
+///
+/// let mut composition = IncrementalCompose::<_, VectorFst<_>, _>::new(&graph)?;
+/// composition.push_frame(&[(1, TropicalWeight::new(0.5)), (2, TropicalWeight::new(1.0))])?;
+/// composition.push_frame(&[(2, TropicalWeight::new(0.2))])?;
+/// assert_eq!(composition.num_frames(), 2);
+///
+/// let lattice = composition.compute()?;
+/// let mut olabels: Vec<_> = lattice.paths_iter().map(|p| p.olabels).collect();
+/// olabels.sort();
+/// assert_eq!(olabels, vec![vec![10, 11], vec![11, 11]]);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct IncrementalCompose<W: Semiring, F2: ExpandedFst<W>, B2: Borrow<F2>> {
+    fst2: B2,
+    ofst: VectorFst<W>,
+    /// States on the last state of the left operand, keyed by the state of the right operand
+    /// and whether they are reached by an epsilon transition of the right operand.
+    frontier: HashMap<(StateId, bool), StateId>,
+    num_frames: usize,
+    ghost: PhantomData<F2>,
+}
+
+impl<W: Semiring, F2: ExpandedFst<W>, B2: Borrow<F2>> IncrementalCompose<W, F2, B2> {
+    /// Starts the composition with an empty left operand. The trs of `fst2` must be sorted by
+    /// input labels, see `tr_sort`.
+    pub fn new(fst2: B2) -> Result<Self> {
+        for state in fst2.borrow().states_range() {
+            let trs = fst2.borrow().get_trs(state)?;
+            if trs.trs().windows(2).any(|w| w[0].ilabel > w[1].ilabel) {
+                bail!(
+                    "IncrementalCompose : the trs of the right operand must be sorted by input labels, state {} is not",
+                    state
+                );
+            }
+        }
+        let mut ofst = VectorFst::new();
+        if let Some(osymt) = fst2.borrow().output_symbols() {
+            ofst.set_output_symbols(osymt.clone());
+        }
+        let mut composition = Self {
+            fst2,
+            ofst,
+            frontier: HashMap::new(),
+            num_frames: 0,
+            ghost: PhantomData,
+        };
+        if let Some(start2) = composition.fst2.borrow().start() {
+            let mut queue = vec![];
+            let start = composition.find_or_add_state(start2, false, &mut queue);
+            composition.ofst.set_start(start)?;
+            composition.expand_epsilons(queue)?;
+        }
+        Ok(composition)
+    }
+
+    fn find_or_add_state(
+        &mut self,
+        state2: StateId,
+        after_epsilon: bool,
+        queue: &mut Vec<(StateId, StateId)>,
+    ) -> StateId {
+        match self.frontier.entry((state2, after_epsilon)) {
+            Entry::Occupied(e) => *e.get(),
+            Entry::Vacant(e) => {
+                let state = self.ofst.add_state();
+                e.insert(state);
+                queue.push((state2, state));
+                state
+            }
+        }
+    }
+
+    /// Follows the epsilon transitions of the right operand from the new frontier states.
+    fn expand_epsilons(&mut self, mut queue: Vec<(StateId, StateId)>) -> Result<()> {
+        while let Some((state2, state)) = queue.pop() {
+            let trs2 = self.fst2.borrow().get_trs(state2)?;
+            for tr2 in trs2.trs().iter().take_while(|tr| tr.ilabel == EPS_LABEL) {
+                let nextstate = self.find_or_add_state(tr2.nextstate, true, &mut queue);
+                let tr = Tr::new(EPS_LABEL, tr2.olabel, tr2.weight.clone(), nextstate);
+                self.ofst.add_tr(state, tr)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Appends a frame to the left operand and extends the frontier with it. Each
+    /// `(label, weight)` is a transition of the frame, the epsilon label skipping the frame.
+    pub fn push_frame(&mut self, frame: &[(Label, W)]) -> Result<()> {
+        let mut previous_frontier: Vec<_> = self.frontier.drain().collect();
+        // Keeps the states of the result in the order of their creation.
+        previous_frontier.sort_by_key(|(_, state)| *state);
+        let mut queue = vec![];
+        for ((state2, after_epsilon), state) in previous_frontier {
+            let trs2 = self.fst2.borrow().get_trs(state2)?;
+            let trs2 = trs2.trs();
+            for (label, weight) in frame.iter() {
+                if *label == EPS_LABEL {
+                    // The epsilons of the left operand are read before those of the right one.
+                    if !after_epsilon {
+                        let nextstate = self.find_or_add_state(state2, false, &mut queue);
+                        let tr = Tr::new(EPS_LABEL, EPS_LABEL, weight.clone(), nextstate);
+                        self.ofst.add_tr(state, tr)?;
+                    }
+                    continue;
+                }
+                let begin = trs2.lower_bound_by(|tr| tr.ilabel.cmp(label));
+                for tr2 in trs2[begin..].iter().take_while(|tr| tr.ilabel == *label) {
+                    let nextstate = self.find_or_add_state(tr2.nextstate, false, &mut queue);
+                    let tr = Tr::new(*label, tr2.olabel, weight.times(&tr2.weight)?, nextstate);
+                    self.ofst.add_tr(state, tr)?;
+                }
+            }
+        }
+        self.expand_epsilons(queue)?;
+        self.num_frames += 1;
+        Ok(())
+    }
+
+    /// Number of frames pushed so far.
+    pub fn num_frames(&self) -> usize {
+        self.num_frames
+    }
+
+    /// Number of states of the composition on the last state of the left operand.
+    pub fn frontier_len(&self) -> usize {
+        self.frontier.len()
+    }
+
+    /// Composition built so far, without final states.
+    pub fn fst(&self) -> &VectorFst<W> {
+        &self.ofst
+    }
+
+    /// Composition of the frames pushed so far with the right operand, the left operand ending
+    /// after the last frame. Only the states on a successful path are kept. The composition
+    /// can still be extended by pushing frames afterwards.
+    pub fn compute(&self) -> Result<VectorFst<W>> {
+        let mut ofst = self.ofst.clone();
+        for ((state2, _), state) in self.frontier.iter() {
+            if let Some(final_weight) = self.fst2.borrow().final_weight(*state2)? {
+                ofst.set_final(*state, final_weight)?;
+            }
+        }
+        connect(&mut ofst)?;
+        Ok(ofst)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::algorithms::compose::compose;
+    use crate::semirings::TropicalWeight;
+    use crate::FstPath;
+
+    type F = VectorFst<TropicalWeight>;
+
+    fn sorted_paths(fst: &F) -> Vec<FstPath<TropicalWeight>> {
+        let mut paths: Vec<_> = fst.paths_iter().collect();
+        paths.sort_by(|p1, p2| {
+            (&p1.ilabels, &p1.olabels)
+                .cmp(&(&p2.ilabels, &p2.olabels))
+                .then(p1.weight.value().partial_cmp(p2.weight.value()).unwrap())
+        });
+        paths
+    }
+
+    #[test]
+    fn test_incremental_compose() -> Result<()> {
+        // 1 2 is read as the word 10, 2 as the word 11, and an epsilon outputs the word 12.
+        let mut graph = F::new();
+        graph.add_states(3);
+        graph.set_start(0)?;
+        graph.add_tr(0, Tr::new(1, 0, 0.5, 1))?;
+        graph.add_tr(0, Tr::new(2, 11, 1.0, 2))?;
+        graph.add_tr(1, Tr::new(2, 10, 0.0, 2))?;
+        graph.add_tr(2, Tr::new(EPS_LABEL, 12, 0.2, 0))?;
+        graph.set_final(2, TropicalWeight::one())?;
+
+        let frames = [
+            vec![(1, TropicalWeight::new(1.0)), (2, TropicalWeight::new(2.0))],
+            vec![
+                (EPS_LABEL, TropicalWeight::new(0.1)),
+                (2, TropicalWeight::new(0.5)),
+            ],
+            vec![(2, TropicalWeight::new(0.3))],
+        ];
+        let mut utterance = F::new();
+        utterance.add_state();
+        utterance.set_start(0)?;
+        let mut composition = IncrementalCompose::<_, F, _>::new(&graph)?;
+        for frame in frames.iter() {
+            let state = utterance.num_states() as StateId;
+            utterance.add_state();
+            for (label, weight) in frame.iter() {
+                utterance.add_tr(state - 1, Tr::new(*label, *label, *weight, state))?;
+            }
+            composition.push_frame(frame)?;
+
+            // The lattice of the prefix is the composition with the prefix.
+            let mut prefix = utterance.clone();
+            prefix.set_final(state, TropicalWeight::one())?;
+            let expected = compose::<_, F, F, F, _, _>(&prefix, &graph)?;
+            assert_eq!(
+                sorted_paths(&composition.compute()?),
+                sorted_paths(&expected)
+            );
+        }
+        assert_eq!(composition.num_frames(), 3);
+        assert!(composition.compute()?.paths_iter().count() > 0);
+
+        let mut unsorted = graph.clone();
+        unsorted.add_tr(0, Tr::new(1, 10, 0.0, 2))?;
+        assert!(IncrementalCompose::<_, F, _>::new(&unsorted).is_err());
+        Ok(())
+    }
+}
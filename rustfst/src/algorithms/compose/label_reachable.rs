@@ -112,6 +112,9 @@ impl LabelReachableData {
             }
         }
 
+        // The pairs are sorted so that the relabeling doesn't depend on the iteration order
+        // of the HashMap.
+        pairs.sort_unstable();
         pairs
     }
 }
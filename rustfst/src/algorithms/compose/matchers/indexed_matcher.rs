@@ -0,0 +1,291 @@
+use std::borrow::Borrow;
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::marker::PhantomData;
+use std::ops::Range;
+use std::sync::Arc;
+
+use anyhow::Result;
+use superslice::Ext;
+
+use crate::algorithms::compose::lookahead_matchers::{LookAheadMatcherData, LookaheadMatcher};
+use crate::algorithms::compose::matchers::sorted_matcher::IteratorSortedMatcher;
+use crate::algorithms::compose::matchers::{MatchType, Matcher, MatcherFlags};
+use crate::fst_traits::{ExpandedFst, Fst};
+use crate::semirings::Semiring;
+use crate::{Label, StateId, Tr, Trs};
+
+/// Minimum number of trs of a state for it to be indexed by `IndexedMatcher::new`.
+pub const DEFAULT_MIN_INDEXED_TRS: usize = 32;
+
+/// Per-state index from the labels to the ranges of the trs bearing them, built once on a
+/// static FST whose trs are sorted on the matched side.
+///
+/// Only the states with at least `min_num_trs` trs are indexed, e.g. the backoff states of a
+/// language model : the memory of the index is traded for constant time lookups where the
+/// binary search of the `SortedMatcher` is the most expensive.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LabelIndex {
+    match_type: MatchType,
+    states: Vec<Option<HashMap<Label, Range<usize>>>>,
+}
+
+impl LabelIndex {
+    pub fn new<W: Semiring, F: ExpandedFst<W>>(
+        fst: &F,
+        match_type: MatchType,
+        min_num_trs: usize,
+    ) -> Result<Self> {
+        let get_label: fn(&Tr<W>) -> Label = match match_type {
+            MatchType::MatchInput => |tr: &Tr<W>| tr.ilabel,
+            MatchType::MatchOutput => |tr: &Tr<W>| tr.olabel,
+            _ => bail!("Unsupported match_type : {:?}", match_type),
+        };
+        let mut states = Vec::with_capacity(fst.num_states());
+        for state in fst.states_range() {
+            let trs = fst.get_trs(state)?;
+            let trs = trs.trs();
+            if trs
+                .windows(2)
+                .any(|pair| get_label(&pair[0]) > get_label(&pair[1]))
+            {
+                bail!(
+                    "The trs of state {} are not sorted for {:?}",
+                    state,
+                    match_type
+                );
+            }
+            if trs.len() < min_num_trs {
+                states.push(None);
+                continue;
+            }
+            let mut ranges: HashMap<Label, Range<usize>> = HashMap::new();
+            for (pos, tr) in trs.iter().enumerate() {
+                ranges.entry(get_label(tr)).or_insert(pos..pos).end = pos + 1;
+            }
+            states.push(Some(ranges));
+        }
+        Ok(Self { match_type, states })
+    }
+
+    pub fn match_type(&self) -> MatchType {
+        self.match_type
+    }
+
+    pub fn num_indexed_states(&self) -> usize {
+        self.states.iter().filter(|s| s.is_some()).count()
+    }
+
+    /// Range of the trs of `state` labeled with `label`, empty if there is none.
+    /// `None` if the state is not indexed.
+    pub fn range(&self, state: StateId, label: Label) -> Option<Range<usize>> {
+        self.states
+            .get(state as usize)
+            .and_then(|s| s.as_ref())
+            .map(|ranges| ranges.get(&label).cloned().unwrap_or(0..0))
+    }
+}
+
+/// Matcher over an FST sorted on the matched side, looking up the labels in a `LabelIndex`
+/// on the indexed states and by binary search, as the `SortedMatcher`, on the others.
+#[derive(Debug, Clone, PartialEq)]
+pub struct IndexedMatcher<W, F, B>
+where
+    W: Semiring,
+    F: ExpandedFst<W>,
+    B: Borrow<F>,
+{
+    fst: B,
+    match_type: MatchType,
+    index: Arc<LabelIndex>,
+    w: PhantomData<(W, F)>,
+}
+
+impl<W, F, B> IndexedMatcher<W, F, B>
+where
+    W: Semiring,
+    F: ExpandedFst<W>,
+    B: Borrow<F> + Debug,
+{
+    /// Matcher sharing an index built beforehand, e.g. by several compositions with the same
+    /// static FST.
+    pub fn new_with_index(fst: B, index: Arc<LabelIndex>) -> Result<Self> {
+        if index.states.len() != fst.borrow().num_states() {
+            bail!(
+                "The index has {} states but the FST has {} states",
+                index.states.len(),
+                fst.borrow().num_states()
+            );
+        }
+        Ok(Self {
+            fst,
+            match_type: index.match_type,
+            index,
+            w: PhantomData,
+        })
+    }
+
+    pub fn index(&self) -> &Arc<LabelIndex> {
+        &self.index
+    }
+}
+
+impl<W, F, B> Matcher<W, F, B> for IndexedMatcher<W, F, B>
+where
+    W: Semiring,
+    F: ExpandedFst<W>,
+    B: Borrow<F> + Debug,
+{
+    type Iter = IteratorSortedMatcher<W, F::TRS>;
+
+    fn new(fst: B, match_type: MatchType) -> Result<Self> {
+        let index = LabelIndex::new(fst.borrow(), match_type, DEFAULT_MIN_INDEXED_TRS)?;
+        Self::new_with_index(fst, Arc::new(index))
+    }
+
+    fn iter(&self, state: StateId, label: Label) -> Result<Self::Iter> {
+        let match_type = self.match_type;
+        let index = &self.index;
+        Ok(IteratorSortedMatcher::new_with_pos(
+            self.fst.borrow().get_trs(state)?,
+            label,
+            match_type,
+            |trs, match_label| match index.range(state, match_label) {
+                // The label is not on the state : starting past the end matches nothing.
+                Some(range) if range.is_empty() => trs.len(),
+                Some(range) => range.start,
+                None => match match_type {
+                    MatchType::MatchInput => trs.lower_bound_by(|x| x.ilabel.cmp(&match_label)),
+                    _ => trs.lower_bound_by(|x| x.olabel.cmp(&match_label)),
+                },
+            },
+        ))
+    }
+
+    fn final_weight(&self, state: StateId) -> Result<Option<W>> {
+        self.fst.borrow().final_weight(state)
+    }
+
+    fn match_type(&self, _test: bool) -> Result<MatchType> {
+        // The sorting has been checked when building the index.
+        Ok(self.match_type)
+    }
+
+    fn flags(&self) -> MatcherFlags {
+        MatcherFlags::empty()
+    }
+
+    fn priority(&self, state: StateId) -> Result<usize> {
+        self.fst.borrow().num_trs(state)
+    }
+
+    fn fst(&self) -> &B {
+        &self.fst
+    }
+}
+
+impl<W, F, B> LookaheadMatcher<W, F, B> for IndexedMatcher<W, F, B>
+where
+    W: Semiring,
+    F: ExpandedFst<W>,
+    B: Borrow<F> + Debug,
+{
+    type MatcherData = ();
+
+    fn data(&self) -> Option<&Arc<Self::MatcherData>> {
+        unreachable!()
+    }
+
+    fn new_with_data(
+        _fst: B,
+        _match_type: MatchType,
+        _data: Option<Arc<Self::MatcherData>>,
+    ) -> Result<Self>
+    where
+        Self: std::marker::Sized,
+    {
+        unreachable!()
+    }
+
+    fn create_data<F2: Fst<W>, BF2: Borrow<F2>>(
+        _fst: BF2,
+        _match_type: MatchType,
+    ) -> Result<Option<Self::MatcherData>> {
+        unreachable!()
+    }
+
+    fn init_lookahead_fst<LF: Fst<W>, BLF: Borrow<LF>>(&mut self, _lfst: &BLF) -> Result<()> {
+        unreachable!()
+    }
+
+    fn lookahead_fst<LF: Fst<W>, BLF: Borrow<LF>>(
+        &self,
+        _matcher_state: StateId,
+        _lfst: &BLF,
+        _lfst_state: StateId,
+    ) -> Result<Option<LookAheadMatcherData<W>>> {
+        unreachable!()
+    }
+
+    fn lookahead_label(&self, _state: StateId, _label: Label) -> Result<bool> {
+        unreachable!()
+    }
+
+    fn lookahead_prefix(
+        &self,
+        _tr: &mut Tr<W>,
+        _la_matcher_data: &LookAheadMatcherData<W>,
+    ) -> bool {
+        unreachable!()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::algorithms::compose::matchers::{IterItemMatcher, SortedMatcher};
+    use crate::fst_impls::VectorFst;
+    use crate::fst_traits::MutableFst;
+    use crate::semirings::TropicalWeight;
+    use crate::{EPS_LABEL, NO_LABEL};
+
+    fn labels<I: Iterator<Item = IterItemMatcher<TropicalWeight>>>(iter: I) -> Vec<Label> {
+        iter.map(|item| match item {
+            IterItemMatcher::Tr(tr) => tr.ilabel,
+            IterItemMatcher::EpsLoop => NO_LABEL,
+        })
+        .collect()
+    }
+
+    #[test]
+    fn test_indexed_matcher() -> Result<()> {
+        let mut fst = VectorFst::<TropicalWeight>::new();
+        fst.add_states(2);
+        fst.set_start(0)?;
+        fst.add_tr(0, Tr::new(EPS_LABEL, 1, 1.0, 1))?;
+        for label in 1..50 {
+            fst.add_tr(0, Tr::new(2 * label, 1, 1.0, 1))?;
+            fst.add_tr(0, Tr::new(2 * label, 2, 2.0, 1))?;
+        }
+        fst.add_tr(1, Tr::new(3, 3, 1.0, 1))?;
+        fst.add_tr(1, Tr::new(4, 4, 1.0, 0))?;
+
+        let indexed = IndexedMatcher::<_, VectorFst<_>, _>::new(&fst, MatchType::MatchInput)?;
+        assert_eq!(indexed.index().num_indexed_states(), 1);
+        let sorted = SortedMatcher::<_, VectorFst<_>, _>::new(&fst, MatchType::MatchInput)?;
+        for state in 0..2 {
+            for label in [EPS_LABEL, NO_LABEL, 1, 3, 4, 10, 98, 99, 1000].iter() {
+                assert_eq!(
+                    labels(indexed.iter(state, *label)?),
+                    labels(sorted.iter(state, *label)?)
+                );
+            }
+        }
+
+        let mut unsorted = fst.clone();
+        unsorted.add_tr(1, Tr::new(1, 1, 1.0, 1))?;
+        assert!(LabelIndex::new(&unsorted, MatchType::MatchInput, 1).is_err());
+        Ok(())
+    }
+}
@@ -4,6 +4,7 @@ use anyhow::Result;
 
 use bitflags::bitflags;
 pub use generic_matcher::GenericMatcher;
+pub use indexed_matcher::{IndexedMatcher, LabelIndex, DEFAULT_MIN_INDEXED_TRS};
 pub use multi_eps_matcher::{MultiEpsMatcher, MultiEpsMatcherFlags};
 pub use sigma_matcher::SigmaMatcher;
 pub use sorted_matcher::SortedMatcher;
@@ -15,6 +16,7 @@ use crate::{Tr, EPS_LABEL, NO_LABEL};
 use std::borrow::Borrow;
 
 mod generic_matcher;
+mod indexed_matcher;
 mod multi_eps_matcher;
 mod sigma_matcher;
 mod sorted_matcher;
@@ -308,10 +308,10 @@ where
 
 #[cfg(test)]
 mod tests {
+    use crate::algorithms::compose::compose_filters::SequenceComposeFilterBuilder;
     use crate::algorithms::compose::matchers::SortedMatcher;
     use crate::algorithms::compose::{ComposeFst, ComposeFstOpOptions};
     use crate::fst_traits::{MutableFst, SerializableFst};
-    use crate::prelude::compose::compose_filters::SequenceComposeFilterBuilder;
     use crate::prelude::{tr_sort, ILabelCompare, OLabelCompare, VectorFst};
     use crate::semirings::TropicalWeight;
     use crate::utils::acceptor;
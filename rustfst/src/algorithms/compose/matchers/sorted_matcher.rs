@@ -122,6 +122,26 @@ impl<W: Semiring, T: Trs<W>> Clone for IteratorSortedMatcher<W, T> {
 
 impl<W: Semiring, T: Trs<W>> IteratorSortedMatcher<W, T> {
     pub fn new(trs: T, match_label: Label, match_type: MatchType) -> Self {
+        Self::new_with_pos(
+            trs,
+            match_label,
+            match_type,
+            |trs, match_label| match match_type {
+                MatchType::MatchInput => trs.lower_bound_by(|x| x.ilabel.cmp(&match_label)),
+                MatchType::MatchOutput => trs.lower_bound_by(|x| x.olabel.cmp(&match_label)),
+                _ => panic!("Shouldn't happen : {:?}", match_type),
+            },
+        )
+    }
+
+    /// Iterator starting at the position of the first tr labeled with `match_label`, as
+    /// returned by `find_pos`, which is not called when matching epsilon.
+    pub(crate) fn new_with_pos<P: FnOnce(&T, Label) -> usize>(
+        trs: T,
+        match_label: Label,
+        match_type: MatchType,
+        find_pos: P,
+    ) -> Self {
         // If we have to match epsilon, an epsilon loop is added
         let current_loop = match_label == EPS_LABEL;
 
@@ -137,11 +157,7 @@ impl<W: Semiring, T: Trs<W>> IteratorSortedMatcher<W, T> {
         let pos = if current_loop {
             0
         } else {
-            match match_type {
-                MatchType::MatchInput => trs.lower_bound_by(|x| x.ilabel.cmp(&match_label)),
-                MatchType::MatchOutput => trs.lower_bound_by(|x| x.olabel.cmp(&match_label)),
-                _ => panic!("Shouldn't happen : {:?}", match_type),
-            }
+            find_pos(&trs, match_label)
         };
 
         Self {
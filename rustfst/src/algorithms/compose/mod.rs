@@ -1,12 +1,20 @@
 pub use self::add_on::FstAddOn;
+pub use self::compose_cache::ComposeCache;
 pub use self::compose_fst::ComposeFst;
 pub use self::compose_fst_op::{ComposeFstOp, ComposeFstOpState};
-pub use self::compose_fst_op_options::ComposeFstOpOptions;
+pub use self::compose_fst_op_options::{ComposeFstOpOptions, ComposeTimesOrder};
+pub use self::compose_lookahead::{
+    compose_lookahead, compose_push_labels, LookAheadComposeFst,
+    OLabelLookAheadComposeFilterBuilder, OLabelLookAheadFlags, OLabelLookAheadFst,
+    OLabelLookAheadMatcher, OLabelPushLabelsComposeFilterBuilder, OLabelPushLabelsFlags,
+    PushLabelsComposeFst,
+};
 pub use self::compose_state_tuple::ComposeStateTuple;
 pub use self::compose_static::{
-    compose, compose_with_config, ComposeConfig, ComposeFilterEnum, MatcherConfig,
-    SigmaMatcherConfig,
+    compose, compose_with_config, compose_with_vocabulary, ComposeConfig, ComposeFilterEnum,
+    MatcherConfig, SigmaMatcherConfig,
 };
+pub use self::incremental_compose::IncrementalCompose;
 pub use self::interval_reach_visitor::IntervalReachVisitor;
 pub use self::interval_set::{IntInterval, IntervalSet};
 pub use self::label_reachable::{LabelReachable, LabelReachableData};
@@ -21,10 +29,13 @@ pub mod lookahead_matchers;
 pub mod matchers;
 
 mod add_on;
+mod compose_cache;
 mod compose_fst;
 mod compose_fst_op;
+mod compose_lookahead;
 mod compose_state_tuple;
 mod compose_static;
+mod incremental_compose;
 mod interval_reach_visitor;
 mod interval_set;
 mod label_reachable;
@@ -13,6 +13,30 @@ use crate::{StateId, SymbolTable, Tr, TrsVec, EPS_LABEL, NO_LABEL};
 /// FST. If FST1 transduces string x to y with weight a and FST2 transduces
 /// string w to v with weight b, then their concatenation transduces string xw
 /// to yv with Times(a, b).
+///
+/// The states are expanded on demand, without copying the input FSTs upfront, e.g. when the
+/// lazy FST is read by another algorithm. `compute` turns it into a static FST.
+///
+/// # Example
+/// ```
+/// # use anyhow::Result;
+/// # fn main() -> Result<()> {
+/// # use rustfst::algorithms::accepts;
+/// # use rustfst::algorithms::concat::ConcatFst;
+/// # use rustfst::fst_impls::VectorFst;
+/// # use rustfst::semirings::{Semiring, TropicalWeight};
+/// # use rustfst::utils::acceptor;
+/// let fst1: VectorFst<TropicalWeight> = acceptor(&[1, 2], TropicalWeight::one());
+/// let fst2: VectorFst<TropicalWeight> = acceptor(&[3], TropicalWeight::one());
+/// let concat_fst = ConcatFst::new(fst1, fst2)?;
+/// assert!(accepts(&concat_fst, &[1, 2, 3])?);
+/// assert!(!accepts(&concat_fst, &[1, 2])?);
+///
+/// let computed: VectorFst<_> = concat_fst.compute()?;
+/// assert!(accepts(&computed, &[1, 2, 3])?);
+/// # Ok(())
+/// # }
+/// ```
 #[derive(Debug)]
 pub struct ConcatFst<W: Semiring, F: Fst<W> + 'static>(ReplaceFst<W, F, F>, FstProperties);
 
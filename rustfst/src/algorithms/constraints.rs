@@ -0,0 +1,131 @@
+use anyhow::Result;
+
+use crate::algorithms::complement::complement_over_alphabet;
+use crate::algorithms::concat::concat;
+use crate::algorithms::determinize::determinize;
+use crate::algorithms::rm_epsilon::rm_epsilon;
+use crate::algorithms::tr_map;
+use crate::algorithms::tr_mappers::RmWeightMapper;
+use crate::fst_traits::{AllocableFst, ExpandedFst, MutableFst};
+use crate::semirings::{Semiring, WeaklyDivisibleSemiring, WeightQuantize};
+use crate::{SymbolTable, Tr, EPS_LABEL};
+
+/// Builds the acceptor of all the strings of labels of `symt` (epsilon excluded), i.e. a
+/// single final state looping on each label.
+pub fn sigma_star<W: Semiring, F: MutableFst<W>>(symt: &SymbolTable) -> Result<F> {
+    let mut fst = F::new();
+    let s = fst.add_state();
+    fst.set_start(s)?;
+    fst.set_final(s, W::one())?;
+    for label in symt.labels().filter(|l| *l != EPS_LABEL) {
+        fst.add_tr(s, Tr::new(label, label, W::one(), s))?;
+    }
+    Ok(fst)
+}
+
+/// Makes the FST optional : the result also accepts the empty string, with a weight of
+/// `W::one()`. A new final start state is added with an epsilon transition to the former
+/// start state.
+pub fn optionalize<W: Semiring, F: MutableFst<W>>(fst: &mut F) -> Result<()> {
+    let start = fst.start();
+    let nstart = fst.add_state();
+    fst.set_start(nstart)?;
+    fst.set_final(nstart, W::one())?;
+    if let Some(start) = start {
+        fst.add_tr(nstart, Tr::new(EPS_LABEL, EPS_LABEL, W::one(), start))?;
+    }
+    Ok(())
+}
+
+/// Builds `sigma* fst sigma*` over the labels of `symt`, which accepts the strings containing
+/// a string accepted by `fst`.
+pub fn contains<W, F>(fst: &F, symt: &SymbolTable) -> Result<F>
+where
+    W: Semiring,
+    F: ExpandedFst<W> + MutableFst<W> + AllocableFst<W>,
+{
+    let mut res: F = sigma_star(symt)?;
+    concat(&mut res, fst)?;
+    concat(&mut res, &sigma_star::<W, F>(symt)?)?;
+    Ok(res)
+}
+
+/// Builds the deterministic acceptor of the strings of labels of `symt` in which no string
+/// accepted by `a` is immediately followed by a string accepted by `b`, i.e. the complement of
+/// `sigma* a b sigma*`.
+///
+/// The weights of `a` and `b` are ignored and those of the output are all `W::one()`.
+///
+/// # Example
+/// ```
+/// # #[macro_use] extern crate rustfst;
+/// # use rustfst::algorithms::{accepts, not_followed_by};
+/// # use rustfst::fst_impls::VectorFst;
+/// # use rustfst::semirings::{Semiring, TropicalWeight};
+/// # use rustfst::utils::acceptor;
+/// # use rustfst::SymbolTable;
+/// # use anyhow::Result;
+/// # fn main() -> Result<()> {
+/// let symt = symt!["a", "b", "c"];
+/// let a : VectorFst<TropicalWeight> = fst![1];
+/// let b : VectorFst<TropicalWeight> = fst![2];
+/// let fst : VectorFst<TropicalWeight> = not_followed_by(&a, &b, &symt)?;
+///
+/// assert!(accepts(&fst, &[2, 1, 3, 2])?);
+/// assert!(!accepts(&fst, &[3, 1, 2, 3])?);
+/// # Ok(())
+/// # }
+/// ```
+pub fn not_followed_by<W, F>(a: &F, b: &F, symt: &SymbolTable) -> Result<F>
+where
+    W: WeaklyDivisibleSemiring + WeightQuantize,
+    F: ExpandedFst<W> + MutableFst<W> + AllocableFst<W>,
+{
+    let mut pattern: F = sigma_star(symt)?;
+    concat(&mut pattern, a)?;
+    concat(&mut pattern, b)?;
+    concat(&mut pattern, &sigma_star::<W, F>(symt)?)?;
+    tr_map(&mut pattern, &RmWeightMapper {})?;
+    rm_epsilon(&mut pattern)?;
+    let mut fst: F = determinize(&pattern)?;
+    let alphabet = symt.labels().filter(|l| *l != EPS_LABEL).collect();
+    complement_over_alphabet(&mut fst, &alphabet)?;
+    Ok(fst)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::algorithms::accepts;
+    use crate::fst_impls::VectorFst;
+    use crate::semirings::TropicalWeight;
+    use crate::utils::acceptor;
+    use crate::{fst, symt};
+
+    #[test]
+    fn test_constraints() -> Result<()> {
+        let symt = symt!["a", "b", "c"];
+        let sigma: VectorFst<TropicalWeight> = sigma_star(&symt)?;
+        assert!(accepts(&sigma, &[])?);
+        assert!(accepts(&sigma, &[3, 1, 2])?);
+
+        let ab: VectorFst<TropicalWeight> = fst![1, 2];
+        let fst = contains(&ab, &symt)?;
+        assert!(accepts(&fst, &[3, 1, 2, 1])?);
+        assert!(!accepts(&fst, &[2, 1, 3])?);
+
+        let mut fst = ab.clone();
+        optionalize(&mut fst)?;
+        assert!(accepts(&fst, &[])?);
+        assert!(accepts(&fst, &[1, 2])?);
+
+        let a: VectorFst<TropicalWeight> = fst![1];
+        let b: VectorFst<TropicalWeight> = fst![2];
+        let fst = not_followed_by(&a, &b, &symt)?;
+        assert!(accepts(&fst, &[])?);
+        assert!(accepts(&fst, &[1, 1, 3, 2])?);
+        assert!(!accepts(&fst, &[1, 1, 2])?);
+        Ok(())
+    }
+}
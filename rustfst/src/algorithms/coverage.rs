@@ -0,0 +1,225 @@
+use std::collections::BTreeMap;
+use std::fmt;
+
+use anyhow::Result;
+
+use crate::algorithms::score_sequence::{advance_frontier, input_epsilon_closure, Frontier};
+use crate::fst_traits::Fst;
+use crate::semirings::Semiring;
+use crate::{Label, StateId, KSHORTESTDELTA};
+
+/// Outcome of the reading of a string by an acceptor.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StringCoverage<W> {
+    /// ⊕-sum of the weights of the successful paths of the string, `None` if it is rejected.
+    pub weight: Option<W>,
+    /// Length of the longest prefix of the string read by the FST.
+    pub matched_len: usize,
+    /// Label on which the reading stopped, `None` if the whole string has been read.
+    pub blocking_label: Option<Label>,
+    /// States reached by the longest matched prefix if the string is rejected, i.e. the
+    /// states missing a transition on `blocking_label` or a final weight.
+    pub blocking_states: Vec<StateId>,
+}
+
+impl<W> StringCoverage<W> {
+    pub fn is_accepted(&self) -> bool {
+        self.weight.is_some()
+    }
+}
+
+/// Coverage of a corpus of strings by an acceptor, with the outcome of each string and
+/// aggregate statistics over the rejected ones.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CoverageReport<W> {
+    /// Outcome of each string, in the order of the corpus.
+    pub strings: Vec<StringCoverage<W>>,
+}
+
+impl<W> CoverageReport<W> {
+    pub fn num_strings(&self) -> usize {
+        self.strings.len()
+    }
+
+    pub fn num_accepted(&self) -> usize {
+        self.strings.iter().filter(|s| s.is_accepted()).count()
+    }
+
+    /// Fraction of the strings accepted, 0 for an empty corpus.
+    pub fn acceptance_rate(&self) -> f32 {
+        if self.strings.is_empty() {
+            return 0.0;
+        }
+        self.num_accepted() as f32 / self.num_strings() as f32
+    }
+
+    /// Rejected strings read entirely, but ending in non-final states.
+    pub fn num_rejected_at_end(&self) -> usize {
+        self.strings
+            .iter()
+            .filter(|s| !s.is_accepted() && s.blocking_label.is_none())
+            .count()
+    }
+
+    /// Number of rejected strings blocked on each label.
+    pub fn blocking_labels(&self) -> BTreeMap<Label, usize> {
+        let mut counts = BTreeMap::new();
+        for label in self.strings.iter().filter_map(|s| s.blocking_label) {
+            *counts.entry(label).or_insert(0) += 1;
+        }
+        counts
+    }
+
+    /// Number of rejected strings blocked in each state, the states where the grammar is
+    /// most likely to miss a transition coming first.
+    pub fn blocking_states(&self) -> Vec<(StateId, usize)> {
+        let mut counts = BTreeMap::new();
+        for string in self.strings.iter() {
+            for state in string.blocking_states.iter() {
+                *counts.entry(*state).or_insert(0) += 1;
+            }
+        }
+        let mut counts: Vec<_> = counts.into_iter().collect();
+        counts.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+        counts
+    }
+}
+
+impl<W> fmt::Display for CoverageReport<W> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "# of strings\t\t{}", self.num_strings())?;
+        writeln!(f, "# of accepted strings\t{}", self.num_accepted())?;
+        writeln!(
+            f,
+            "acceptance rate\t\t{:.2}%",
+            100.0 * self.acceptance_rate()
+        )?;
+        write!(f, "# rejected at the end\t{}", self.num_rejected_at_end())
+    }
+}
+
+/// Reads `ilabels` with the FST, stopping at the first label that can't be read.
+pub fn string_coverage<W: Semiring, F: Fst<W>>(
+    fst: &F,
+    ilabels: &[Label],
+) -> Result<StringCoverage<W>> {
+    let mut frontier = Frontier::new();
+    if let Some(start) = fst.start() {
+        frontier.insert(start, W::one());
+        input_epsilon_closure(fst, &mut frontier, KSHORTESTDELTA)?;
+    }
+
+    for (idx, label) in ilabels.iter().enumerate() {
+        let mut next = advance_frontier(fst, &frontier, *label)?;
+        if next.is_empty() {
+            return Ok(StringCoverage {
+                weight: None,
+                matched_len: idx,
+                blocking_label: Some(*label),
+                blocking_states: frontier.keys().cloned().collect(),
+            });
+        }
+        input_epsilon_closure(fst, &mut next, KSHORTESTDELTA)?;
+        frontier = next;
+    }
+
+    let mut weight = None;
+    for (state, w) in frontier.iter() {
+        if let Some(final_weight) = fst.final_weight(*state)? {
+            weight
+                .get_or_insert_with(W::zero)
+                .plus_assign(w.times(final_weight)?)?;
+        }
+    }
+    let blocking_states = if weight.is_some() {
+        vec![]
+    } else {
+        frontier.keys().cloned().collect()
+    };
+    Ok(StringCoverage {
+        weight,
+        matched_len: ilabels.len(),
+        blocking_label: None,
+        blocking_states,
+    })
+}
+
+/// Runs a corpus of strings through an acceptor (or the input side of a transducer) and
+/// reports which strings are accepted and, for the rejected ones, how far they have been
+/// read and in which states, e.g. to check the coverage of a grammar on a test corpus.
+///
+/// # Example
+/// ```
+/// # #[macro_use] extern crate rustfst;
+/// # use rustfst::algorithms::coverage;
+/// # use rustfst::fst_impls::VectorFst;
+/// # use rustfst::semirings::{Semiring, TropicalWeight};
+/// # use rustfst::utils::acceptor;
+/// # use anyhow::Result;
+/// # fn main() -> Result<()> {
+/// let fst : VectorFst<TropicalWeight> = fst![1, 2, 3];
+/// let corpus = vec![vec![1, 2, 3], vec![1, 3], vec![1, 2]];
+/// let report = coverage(&fst, corpus)?;
+///
+/// assert_eq!(report.num_accepted(), 1);
+/// assert_eq!(report.strings[1].matched_len, 1);
+/// assert_eq!(report.strings[1].blocking_label, Some(3));
+/// assert_eq!(report.num_rejected_at_end(), 1);
+/// # Ok(())
+/// # }
+/// ```
+pub fn coverage<W, F, I, L>(fst: &F, corpus: I) -> Result<CoverageReport<W>>
+where
+    W: Semiring,
+    F: Fst<W>,
+    I: IntoIterator<Item = L>,
+    L: AsRef<[Label]>,
+{
+    let strings = corpus
+        .into_iter()
+        .map(|ilabels| string_coverage(fst, ilabels.as_ref()))
+        .collect::<Result<Vec<_>>>()?;
+    Ok(CoverageReport { strings })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::fst_impls::VectorFst;
+    use crate::fst_traits::MutableFst;
+    use crate::semirings::TropicalWeight;
+    use crate::{Tr, EPS_LABEL};
+
+    #[test]
+    fn test_coverage() -> Result<()> {
+        // 1 (2 | eps) 3
+        let mut fst = VectorFst::<TropicalWeight>::new();
+        fst.add_states(4);
+        fst.set_start(0)?;
+        fst.add_tr(0, Tr::new(1, 1, 1.0, 1))?;
+        fst.add_tr(1, Tr::new(2, 2, 1.0, 2))?;
+        fst.add_tr(1, Tr::new(EPS_LABEL, EPS_LABEL, 0.5, 2))?;
+        fst.add_tr(2, Tr::new(3, 3, 1.0, 3))?;
+        fst.set_final(3, 0.0)?;
+
+        let corpus = vec![vec![1, 3], vec![1, 2, 3], vec![1, 4], vec![1, 2], vec![2]];
+        let report = coverage(&fst, &corpus)?;
+        assert_eq!(report.num_strings(), 5);
+        assert_eq!(report.num_accepted(), 2);
+        assert_eq!(report.strings[0].weight, Some(TropicalWeight::new(2.5)));
+        assert!((report.acceptance_rate() - 0.4).abs() < 1e-6);
+
+        let blocked = &report.strings[2];
+        assert_eq!(blocked.matched_len, 1);
+        assert_eq!(blocked.blocking_label, Some(4));
+        assert_eq!(blocked.blocking_states, vec![1, 2]);
+        assert_eq!(report.strings[3].blocking_states, vec![2]);
+        assert_eq!(report.num_rejected_at_end(), 1);
+
+        let labels: Vec<_> = report.blocking_labels().into_iter().collect();
+        assert_eq!(labels, vec![(2, 1), (4, 1)]);
+        assert_eq!(report.blocking_states(), vec![(2, 2), (0, 1), (1, 1)]);
+        Ok(())
+    }
+}
@@ -0,0 +1,598 @@
+use std::collections::HashMap;
+
+use anyhow::Result;
+
+use crate::fst_properties::FstProperties;
+use crate::fst_traits::{ExpandedFst, MutableFst};
+use crate::semirings::{DivideType, Semiring, WeaklyDivisibleSemiring, WeightQuantize};
+use crate::{Label, StateId, Tr, Trs, EPS_LABEL, KDELTA};
+
+/// State of the word being inserted, whose transitions to the states of the words already
+/// inserted are final.
+struct PendingState<W> {
+    label: Label,
+    final_weight: Option<W>,
+    /// Label, weight before pushing and registered destination of each transition.
+    trs: Vec<(Label, W, StateId)>,
+}
+
+impl<W: WeaklyDivisibleSemiring> PendingState<W> {
+    fn new(label: Label) -> Self {
+        Self {
+            label,
+            final_weight: None,
+            trs: vec![],
+        }
+    }
+
+    /// ⊕-sum of the weights of the suffixes leaving this state.
+    fn potential(&self) -> Result<W> {
+        let mut potential = self.final_weight.clone().unwrap_or_else(W::zero);
+        for (_, weight, _) in self.trs.iter() {
+            potential.plus_assign(weight)?;
+        }
+        Ok(potential)
+    }
+}
+
+type Signature<W> = (Option<W>, Vec<(Label, W, StateId)>);
+
+/// Builder of the minimal acceptor, registering the states whose suffixes are all known.
+struct DafsaBuilder<W, F> {
+    fst: F,
+    register: HashMap<Signature<W>, StateId>,
+}
+
+impl<W, F> DafsaBuilder<W, F>
+where
+    W: WeaklyDivisibleSemiring + WeightQuantize,
+    F: MutableFst<W>,
+{
+    /// Pushes the weights of `state` towards the start state and returns the equivalent
+    /// registered state, created if there is none, with the weight pushed out of it.
+    fn register(&mut self, state: PendingState<W>) -> Result<(StateId, W)> {
+        let potential = state.potential()?;
+        let final_weight = match &state.final_weight {
+            Some(w) => Some(w.divide(&potential, DivideType::DivideLeft)?),
+            None => None,
+        };
+        let mut trs = Vec::with_capacity(state.trs.len());
+        for (label, weight, nextstate) in state.trs.iter() {
+            let weight = weight.divide(&potential, DivideType::DivideLeft)?;
+            trs.push((*label, weight, *nextstate));
+        }
+        let signature = signature(&final_weight, &trs)?;
+        if let Some(s) = self.register.get(&signature) {
+            return Ok((*s, potential));
+        }
+        let s = add_state(&mut self.fst, &final_weight, &trs)?;
+        self.register.insert(signature, s);
+        Ok((s, potential))
+    }
+
+    /// Registers the states of `path` past the first `len` ones.
+    fn collapse(&mut self, path: &mut Vec<PendingState<W>>, len: usize) -> Result<()> {
+        while path.len() > len {
+            // Can't fail as path is longer than len >= 1.
+            let state = path.pop().unwrap();
+            let label = state.label;
+            let (s, weight) = self.register(state)?;
+            path.last_mut().unwrap().trs.push((label, weight, s));
+        }
+        Ok(())
+    }
+}
+
+/// Signature of a state whose weights have been pushed. The weights are compared after
+/// quantization, to merge the states whose weights only differ by rounding errors.
+fn signature<W: WeightQuantize>(
+    final_weight: &Option<W>,
+    trs: &[(Label, W, StateId)],
+) -> Result<Signature<W>> {
+    Ok((
+        final_weight
+            .as_ref()
+            .map(|w| w.quantize(KDELTA))
+            .transpose()?,
+        trs.iter()
+            .map(|(l, w, n)| Ok((*l, w.quantize(KDELTA)?, *n)))
+            .collect::<Result<Vec<_>>>()?,
+    ))
+}
+
+fn add_state<W, F>(
+    fst: &mut F,
+    final_weight: &Option<W>,
+    trs: &[(Label, W, StateId)],
+) -> Result<StateId>
+where
+    W: WeaklyDivisibleSemiring,
+    F: MutableFst<W>,
+{
+    let s = fst.add_state();
+    if let Some(final_weight) = final_weight {
+        fst.set_final(s, final_weight.clone())?;
+    }
+    for (label, weight, nextstate) in trs.iter() {
+        fst.add_tr(s, Tr::new(*label, *label, weight.clone(), *nextstate))?;
+    }
+    Ok(s)
+}
+
+/// Builds the minimal deterministic acceptor of a list of weighted words (DAFSA), e.g. a
+/// unigram lexicon, in a single pass over the words.
+///
+/// The words must be sorted in strictly increasing lexicographic order of their labels. The
+/// weights are pushed towards the start state, as `push_weights` with
+/// `ReweightType::ReweightToInitial` would do : the weight of each transition is the
+/// ⊕-sum of the weights of the words going through it, divided by the weight of the words
+/// sharing its prefix. The total weight is left on the transitions and the final weight of
+/// the start state. The states are merged as soon as all the words going through them have
+/// been inserted, so that the memory used is proportional to the size of the result.
+///
+/// With the weight `W::one()` for all the words, in an idempotent semiring such as the
+/// tropical one, the result is the minimal unweighted acceptor of the words.
+///
+/// # Example
+/// ```
+/// # use rustfst::algorithms::{score_sequence, weighted_dafsa};
+/// # use rustfst::fst_impls::VectorFst;
+/// # use rustfst::fst_traits::ExpandedFst;
+/// # use rustfst::semirings::{Semiring, TropicalWeight};
+/// # use anyhow::Result;
+/// # fn main() -> Result<()> {
+/// let words = vec![
+///     (vec![1, 3], TropicalWeight::new(1.0)),
+///     (vec![2, 3], TropicalWeight::new(2.0)),
+/// ];
+/// let fst : VectorFst<TropicalWeight> = weighted_dafsa(words)?;
+///
+/// // The weights are on the trs leaving the start state : the states after 1 and 2 are merged.
+/// assert_eq!(fst.num_states(), 3);
+/// assert_eq!(score_sequence(&fst, &[2, 3])?, Some(TropicalWeight::new(2.0)));
+/// # Ok(())
+/// # }
+/// ```
+pub fn weighted_dafsa<W, F, I, L>(words: I) -> Result<F>
+where
+    W: WeaklyDivisibleSemiring + WeightQuantize,
+    F: MutableFst<W>,
+    I: IntoIterator<Item = (L, W)>,
+    L: AsRef<[Label]>,
+{
+    let mut builder = DafsaBuilder {
+        fst: F::new(),
+        register: HashMap::new(),
+    };
+    // The start state is pending until the end, the path of the previous word after it.
+    let mut path = vec![PendingState::new(0)];
+    let mut previous: Option<Vec<Label>> = None;
+    for (word, weight) in words {
+        let word = word.as_ref();
+        if weight.is_zero() {
+            bail!("weighted_dafsa : the word {:?} has a zero weight", word);
+        }
+        let prefix_len = match &previous {
+            Some(previous) => {
+                if previous.as_slice() >= word {
+                    bail!(
+                        "weighted_dafsa : the words are not sorted, {:?} is after {:?}",
+                        word,
+                        previous
+                    );
+                }
+                previous
+                    .iter()
+                    .zip(word.iter())
+                    .take_while(|(a, b)| a == b)
+                    .count()
+            }
+            None => 0,
+        };
+        builder.collapse(&mut path, prefix_len + 1)?;
+        path.extend(word[prefix_len..].iter().map(|l| PendingState::new(*l)));
+        // Can't fail as path contains the start state.
+        path.last_mut().unwrap().final_weight = Some(weight);
+        previous = Some(word.to_vec());
+    }
+    builder.collapse(&mut path, 1)?;
+
+    // The start state keeps the total weight, hence is never merged.
+    let start = path.pop().unwrap();
+    let mut fst = builder.fst;
+    let s = add_state(&mut fst, &start.final_weight, &start.trs)?;
+    fst.set_start(s)?;
+    Ok(fst)
+}
+
+/// Incremental update of a minimal deterministic acceptor of weighted words, as built by
+/// `weighted_dafsa`, keeping it minimal and its weights pushed after each insertion or removal.
+///
+/// Only the states on the path of the updated word are rebuilt : they are registered bottom-up
+/// as in `weighted_dafsa`, the equivalent states already in the FST being reused, and the
+/// states no longer reachable are released. The cost of an update is then proportional to the
+/// length of the word and the number of transitions of the states on its path, whatever the
+/// number of words in the FST, which avoids a full rebuild to update a large dictionary.
+///
+/// The released states are recycled by the following updates and removed from the FST by
+/// `into_fst`.
+///
+/// # Example
+/// ```
+/// # use rustfst::algorithms::{score_sequence, weighted_dafsa, DafsaUpdater};
+/// # use rustfst::fst_impls::VectorFst;
+/// # use rustfst::fst_traits::ExpandedFst;
+/// # use rustfst::semirings::{Semiring, TropicalWeight};
+/// # use anyhow::Result;
+/// # fn main() -> Result<()> {
+/// let words = vec![
+///     (vec![1, 3], TropicalWeight::new(1.0)),
+///     (vec![2, 3], TropicalWeight::new(2.0)),
+/// ];
+/// let fst : VectorFst<TropicalWeight> = weighted_dafsa(words)?;
+///
+/// let mut updater = DafsaUpdater::new(fst)?;
+/// updater.insert(&[3, 3], TropicalWeight::new(3.0))?;
+/// assert!(updater.remove(&[1, 3])?);
+/// let fst = updater.into_fst()?;
+///
+/// assert_eq!(fst.num_states(), 3);
+/// assert_eq!(score_sequence(&fst, &[3, 3])?, Some(TropicalWeight::new(3.0)));
+/// assert_eq!(score_sequence(&fst, &[1, 3])?, None);
+/// # Ok(())
+/// # }
+/// ```
+pub struct DafsaUpdater<W, F> {
+    fst: F,
+    /// Registered state of each signature, the start state excluded.
+    register: HashMap<Signature<W>, StateId>,
+    /// Number of transitions to each state.
+    in_degrees: Vec<usize>,
+    /// States no longer reachable, without transitions nor final weight.
+    free_states: Vec<StateId>,
+}
+
+impl<W, F> DafsaUpdater<W, F>
+where
+    W: WeaklyDivisibleSemiring + WeightQuantize,
+    F: MutableFst<W>,
+{
+    /// Registers the states of the FST, which must be a minimal deterministic acyclic acceptor
+    /// whose weights are pushed towards the start state, e.g. the result of `weighted_dafsa`.
+    pub fn new(mut fst: F) -> Result<Self> {
+        let props =
+            FstProperties::ACCEPTOR | FstProperties::I_DETERMINISTIC | FstProperties::ACYCLIC;
+        if !fst.compute_and_update_properties(props)?.contains(props) {
+            bail!("DafsaUpdater : the FST must be a deterministic acyclic acceptor");
+        }
+        let mut register = HashMap::new();
+        let mut in_degrees = vec![0; fst.num_states()];
+        for state in fst.states_range() {
+            let trs = state_trs(&fst, state)?;
+            for (_, _, nextstate) in trs.iter() {
+                in_degrees[*nextstate as usize] += 1;
+            }
+            if Some(state) != fst.start() {
+                let signature = signature(&fst.final_weight(state)?, &trs)?;
+                register.entry(signature).or_insert(state);
+            }
+        }
+        Ok(Self {
+            fst,
+            register,
+            in_degrees,
+            free_states: vec![],
+        })
+    }
+
+    /// Inserts a word, replacing its weight if it is already in the FST.
+    pub fn insert(&mut self, word: &[Label], weight: W) -> Result<()> {
+        if weight.is_zero() {
+            bail!("DafsaUpdater : the word {:?} has a zero weight", word);
+        }
+        self.update(word, Some(weight))?;
+        Ok(())
+    }
+
+    /// Removes a word, returning false if it isn't in the FST.
+    pub fn remove(&mut self, word: &[Label]) -> Result<bool> {
+        self.update(word, None)
+    }
+
+    /// Deletes the released states and returns the updated FST.
+    pub fn into_fst(mut self) -> Result<F> {
+        self.fst.del_states(self.free_states)?;
+        Ok(self.fst)
+    }
+
+    fn update(&mut self, word: &[Label], weight: Option<W>) -> Result<bool> {
+        let start = match self.fst.start() {
+            Some(start) => start,
+            None if weight.is_none() => return Ok(false),
+            None => {
+                let start = self.new_state();
+                self.fst.set_start(start)?;
+                start
+            }
+        };
+
+        // Longest prefix of the word in the FST, with the weight of the prefix of each state.
+        let mut states = vec![(start, W::one())];
+        for label in word.iter() {
+            let (state, prefix_weight) = states.last().unwrap();
+            let tr = match self
+                .fst
+                .get_trs(*state)?
+                .trs()
+                .iter()
+                .find(|tr| tr.ilabel == *label)
+            {
+                Some(tr) => tr.clone(),
+                None => break,
+            };
+            let prefix_weight = prefix_weight.times(&tr.weight)?;
+            states.push((tr.nextstate, prefix_weight));
+        }
+        if weight.is_none()
+            && (states.len() <= word.len() || !self.fst.is_final(states.last().unwrap().0)?)
+        {
+            return Ok(false);
+        }
+
+        // The states of the path, with the weights before pushing, as in `weighted_dafsa`.
+        let mut path = Vec::with_capacity(word.len() + 1);
+        for (idx, (state, prefix_weight)) in states.iter().enumerate() {
+            let mut pending = PendingState::new(if idx == 0 { EPS_LABEL } else { word[idx - 1] });
+            pending.final_weight = match self.fst.final_weight(*state)? {
+                Some(w) => Some(prefix_weight.times(w)?),
+                None => None,
+            };
+            for tr in self.fst.get_trs(*state)?.trs() {
+                if idx < word.len() && tr.ilabel == word[idx] {
+                    continue;
+                }
+                pending
+                    .trs
+                    .push((tr.ilabel, prefix_weight.times(&tr.weight)?, tr.nextstate));
+            }
+            path.push(pending);
+        }
+        path.extend(
+            word[states.len() - 1..]
+                .iter()
+                .map(|l| PendingState::new(*l)),
+        );
+        path.last_mut().unwrap().final_weight = weight;
+
+        while path.len() > 1 {
+            let state = path.pop().unwrap();
+            let label = state.label;
+            if let Some((s, weight)) = self.register(state)? {
+                path.last_mut().unwrap().trs.push((label, weight, s));
+            }
+        }
+
+        // The start state keeps the total weight, hence is rebuilt in place.
+        let mut pending_start = path.pop().unwrap();
+        pending_start.trs.sort_by_key(|(label, _, _)| *label);
+        let old_trs = state_trs(&self.fst, start)?;
+        self.fst.delete_trs(start)?;
+        self.fst.delete_final_weight(start)?;
+        self.set_state(start, &pending_start.final_weight, &pending_start.trs)?;
+        // Released once the new transitions are counted, not to release the states they reuse.
+        for (_, _, nextstate) in old_trs {
+            self.release(nextstate)?;
+        }
+        Ok(true)
+    }
+
+    /// Pushes the weights of `state` and returns the equivalent registered state, created if
+    /// there is none, with the weight pushed out of it. Returns `None` if no word goes through
+    /// the state.
+    fn register(&mut self, mut state: PendingState<W>) -> Result<Option<(StateId, W)>> {
+        if state.final_weight.is_none() && state.trs.is_empty() {
+            return Ok(None);
+        }
+        state.trs.sort_by_key(|(label, _, _)| *label);
+        let potential = state.potential()?;
+        let final_weight = match &state.final_weight {
+            Some(w) => Some(w.divide(&potential, DivideType::DivideLeft)?),
+            None => None,
+        };
+        let mut trs = Vec::with_capacity(state.trs.len());
+        for (label, weight, nextstate) in state.trs.iter() {
+            let weight = weight.divide(&potential, DivideType::DivideLeft)?;
+            trs.push((*label, weight, *nextstate));
+        }
+        let signature = signature(&final_weight, &trs)?;
+        if let Some(s) = self.register.get(&signature) {
+            return Ok(Some((*s, potential)));
+        }
+        let s = self.new_state();
+        self.set_state(s, &final_weight, &trs)?;
+        self.register.insert(signature, s);
+        Ok(Some((s, potential)))
+    }
+
+    fn new_state(&mut self) -> StateId {
+        match self.free_states.pop() {
+            Some(s) => s,
+            None => {
+                self.in_degrees.push(0);
+                self.fst.add_state()
+            }
+        }
+    }
+
+    fn set_state(
+        &mut self,
+        state: StateId,
+        final_weight: &Option<W>,
+        trs: &[(Label, W, StateId)],
+    ) -> Result<()> {
+        if let Some(final_weight) = final_weight {
+            self.fst.set_final(state, final_weight.clone())?;
+        }
+        for (label, weight, nextstate) in trs.iter() {
+            self.in_degrees[*nextstate as usize] += 1;
+            self.fst
+                .add_tr(state, Tr::new(*label, *label, weight.clone(), *nextstate))?;
+        }
+        Ok(())
+    }
+
+    /// Removes a transition to `state`, releasing the states no longer reachable.
+    fn release(&mut self, state: StateId) -> Result<()> {
+        let mut queue = vec![state];
+        while let Some(state) = queue.pop() {
+            self.in_degrees[state as usize] -= 1;
+            if self.in_degrees[state as usize] > 0 {
+                continue;
+            }
+            let trs = state_trs(&self.fst, state)?;
+            let signature = signature(&self.fst.final_weight(state)?, &trs)?;
+            if self.register.get(&signature) == Some(&state) {
+                self.register.remove(&signature);
+            }
+            queue.extend(trs.into_iter().map(|(_, _, nextstate)| nextstate));
+            self.fst.delete_trs(state)?;
+            self.fst.delete_final_weight(state)?;
+            self.free_states.push(state);
+        }
+        Ok(())
+    }
+}
+
+fn state_trs<W: Semiring, F: ExpandedFst<W>>(
+    fst: &F,
+    state: StateId,
+) -> Result<Vec<(Label, W, StateId)>> {
+    Ok(fst
+        .get_trs(state)?
+        .trs()
+        .iter()
+        .map(|tr| (tr.ilabel, tr.weight.clone(), tr.nextstate))
+        .collect())
+}
+
+/// Inserts and removes words in a minimal deterministic acceptor built by `weighted_dafsa`,
+/// keeping it minimal. A word with a weight is inserted, replacing its previous weight if it is
+/// already in the FST, a word without weight is removed. See [`DafsaUpdater`].
+pub fn dafsa_update<W, F, I, L>(fst: F, updates: I) -> Result<F>
+where
+    W: WeaklyDivisibleSemiring + WeightQuantize,
+    F: MutableFst<W>,
+    I: IntoIterator<Item = (L, Option<W>)>,
+    L: AsRef<[Label]>,
+{
+    let mut updater = DafsaUpdater::new(fst)?;
+    for (word, weight) in updates {
+        match weight {
+            Some(weight) => updater.insert(word.as_ref(), weight)?,
+            None => {
+                updater.remove(word.as_ref())?;
+            }
+        }
+    }
+    updater.into_fst()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::algorithms::{accepts, score_sequence};
+    use crate::fst_impls::VectorFst;
+    use crate::fst_traits::{CoreFst, ExpandedFst};
+    use crate::semirings::{LogWeight, Semiring, TropicalWeight};
+    use crate::Trs;
+
+    #[test]
+    fn test_weighted_dafsa() -> Result<()> {
+        // "ab", "abc", "bc", "bbc" : the suffixes "c" are shared.
+        let words = vec![
+            (vec![1, 2], LogWeight::new(1.0)),
+            (vec![1, 2, 3], LogWeight::new(2.0)),
+            (vec![2, 2, 3], LogWeight::new(3.0)),
+            (vec![2, 3], LogWeight::new(3.0)),
+        ];
+        let fst: VectorFst<LogWeight> = weighted_dafsa(words.clone())?;
+        for (word, weight) in words.iter() {
+            let score = score_sequence(&fst, word)?.unwrap();
+            assert!(score.approx_equal(weight, KDELTA));
+        }
+        assert!(!accepts(&fst, &[1])?);
+        assert!(!accepts(&fst, &[2, 2])?);
+
+        // The weights of the transitions leaving a state other than the start one sum to one.
+        let start = fst.start().unwrap();
+        for state in fst.states_range().filter(|s| *s != start) {
+            let mut total = fst.final_weight(state)?.unwrap_or_else(LogWeight::zero);
+            for tr in fst.get_trs(state)?.trs() {
+                total.plus_assign(tr.weight)?;
+            }
+            assert!(total.approx_equal(LogWeight::one(), KDELTA));
+        }
+
+        let unweighted: VectorFst<TropicalWeight> = weighted_dafsa(
+            words
+                .iter()
+                .map(|(w, _)| (w.clone(), TropicalWeight::one())),
+        )?;
+        // Only the final state after "c" is shared.
+        assert_eq!(unweighted.num_states(), 6);
+
+        assert!(weighted_dafsa::<TropicalWeight, VectorFst<_>, _, _>(vec![
+            (vec![2], TropicalWeight::one()),
+            (vec![1], TropicalWeight::one()),
+        ])
+        .is_err());
+        Ok(())
+    }
+    #[test]
+    fn test_dafsa_update() -> Result<()> {
+        let words = vec![
+            (vec![1, 2], LogWeight::new(1.0)),
+            (vec![1, 2, 3], LogWeight::new(2.0)),
+            (vec![2, 2, 3], LogWeight::new(3.0)),
+            (vec![2, 3], LogWeight::new(3.0)),
+        ];
+        let fst: VectorFst<LogWeight> = weighted_dafsa(words.clone())?;
+        let updates = vec![
+            (vec![2, 3], None),
+            (vec![1, 3], Some(LogWeight::new(0.5))),
+            (vec![1, 2], Some(LogWeight::new(4.0))),
+            (vec![], Some(LogWeight::new(2.0))),
+            (vec![3, 2, 3], Some(LogWeight::new(1.0))),
+            (vec![4], None),
+        ];
+        let fst = dafsa_update(fst, updates)?;
+
+        let expected = vec![
+            (vec![], LogWeight::new(2.0)),
+            (vec![1, 2], LogWeight::new(4.0)),
+            (vec![1, 2, 3], LogWeight::new(2.0)),
+            (vec![1, 3], LogWeight::new(0.5)),
+            (vec![2, 2, 3], LogWeight::new(3.0)),
+            (vec![3, 2, 3], LogWeight::new(1.0)),
+        ];
+        let rebuilt: VectorFst<LogWeight> = weighted_dafsa(expected.clone())?;
+        assert_eq!(fst.num_states(), rebuilt.num_states());
+        for (word, weight) in expected.iter() {
+            let score = score_sequence(&fst, word)?.unwrap();
+            assert!(score.approx_equal(weight, KDELTA));
+        }
+        assert!(!accepts(&fst, &[2, 3])?);
+
+        // Removing all the words leaves the start state only.
+        let mut updater = DafsaUpdater::new(fst)?;
+        for (word, _) in expected.iter() {
+            assert!(updater.remove(word)?);
+        }
+        assert!(!updater.remove(&[1, 2])?);
+        let fst = updater.into_fst()?;
+        assert_eq!(fst.num_states(), 1);
+        assert_eq!(fst.num_trs(fst.start().unwrap())?, 0);
+        Ok(())
+    }
+}
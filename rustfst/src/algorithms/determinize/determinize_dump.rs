@@ -0,0 +1,246 @@
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+use std::fmt;
+
+use anyhow::Result;
+
+use crate::algorithms::determinize::determinize_static::determinize_with_config_and_dump;
+use crate::algorithms::determinize::{DeterminizeConfig, DeterminizeStateTuple, DeterminizeStats};
+use crate::fst_traits::{AllocableFst, ExpandedFst, MutableFst};
+use crate::semirings::{WeaklyDivisibleSemiring, WeightQuantize};
+use crate::{Semiring, StateId};
+
+/// Weighted subset of a state of the result of a determinization, as text.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DumpedSubset {
+    pub filter_state: StateId,
+    /// States of the input FST with their residual weight, formatted with `Debug`.
+    pub elements: Vec<(StateId, String)>,
+}
+
+/// Contents of the weighted subsets built by a determinization, keyed by the state of the
+/// result, to debug the drift of the residual weights. The weights are formatted with `Debug`
+/// so that the dump doesn't depend on the semiring : the residuals of the determinization of
+/// a transducer are gallic weights, showing the pending output labels along with the weights.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DeterminizeDump {
+    pub subsets: BTreeMap<StateId, DumpedSubset>,
+    /// Greatest residual weight of the subsets w.r.t. `PartialOrd`, formatted with `Debug`.
+    pub max_residual: Option<String>,
+}
+
+impl DeterminizeDump {
+    pub(crate) fn from_subsets<'a, W, I>(subsets: I) -> Self
+    where
+        W: Semiring,
+        I: IntoIterator<Item = (StateId, &'a DeterminizeStateTuple<W>)>,
+    {
+        let mut max_residual: Option<&W> = None;
+        let subsets = subsets
+            .into_iter()
+            .map(|(state, tuple)| {
+                for e in tuple.subset.iter() {
+                    if max_residual
+                        .map(|w| e.weight.partial_cmp(w) == Some(Ordering::Greater))
+                        .unwrap_or(true)
+                    {
+                        max_residual = Some(&e.weight);
+                    }
+                }
+                let elements = tuple
+                    .subset
+                    .iter()
+                    .map(|e| (e.state, format!("{:?}", e.weight)))
+                    .collect();
+                let subset = DumpedSubset {
+                    filter_state: tuple.filter_state,
+                    elements,
+                };
+                (state, subset)
+            })
+            .collect();
+        Self {
+            subsets,
+            max_residual: max_residual.map(|w| format!("{:?}", w)),
+        }
+    }
+
+    /// Keys the subsets by other states, given as the pairs of the new state and of the state
+    /// it stands for. The subsets of the states left out are dropped.
+    pub(crate) fn rekey<I: IntoIterator<Item = (StateId, StateId)>>(self, states: I) -> Self {
+        let subsets = states
+            .into_iter()
+            .filter_map(|(new_state, state)| {
+                self.subsets
+                    .get(&state)
+                    .map(|subset| (new_state, subset.clone()))
+            })
+            .collect();
+        Self {
+            subsets,
+            max_residual: self.max_residual,
+        }
+    }
+
+    /// Statistics on the subsets, with the `num_largest_subsets` largest ones, to diagnose the
+    /// determinizations that blow up.
+    pub fn stats(&self, num_largest_subsets: usize) -> DeterminizeStats {
+        DeterminizeStats::from_dump(self, num_largest_subsets)
+    }
+}
+
+/// One line per state of the result : its id, the filter state, then the `state:residual`
+/// elements of its subset, separated by tabs.
+impl fmt::Display for DeterminizeDump {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (i, (state, subset)) in self.subsets.iter().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            write!(f, "{}\t{}", state, subset.filter_state)?;
+            for (s, weight) in subset.elements.iter() {
+                write!(f, "\t{}:{}", s, weight)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Determinizes the FST as `determinize_with_config` and dumps the weighted subsets built,
+/// keyed by the states of the result. See [`DeterminizeDump::stats`] for a summary of the
+/// dump.
+///
+/// A transducer is determinized as an acceptor over the gallic semiring, whose final weights
+/// are then factored : the states added to write the residual output strings have no subset.
+///
+/// # Example
+/// ```
+/// # use rustfst::algorithms::determinize::{determinize_with_dump, DeterminizeConfig};
+/// # use rustfst::fst_impls::VectorFst;
+/// # use rustfst::fst_traits::MutableFst;
+/// # use rustfst::semirings::{Semiring, TropicalWeight};
+/// # use rustfst::Tr;
+/// # use anyhow::Result;
+/// # fn main() -> Result<()> {
+/// let mut fst = VectorFst::<TropicalWeight>::new();
+/// fst.add_states(3);
+/// fst.set_start(0)?;
+/// fst.add_tr(0, Tr::new(1, 1, 1.0, 1))?;
+/// fst.add_tr(0, Tr::new(1, 1, 3.0, 2))?;
+/// fst.set_final(1, TropicalWeight::one())?;
+/// fst.set_final(2, TropicalWeight::one())?;
+///
+/// let (_, dump): (VectorFst<_>, _) = determinize_with_dump(&fst, DeterminizeConfig::default())?;
+/// let states: Vec<_> = dump.subsets[&1].elements.iter().map(|e| e.0).collect();
+/// assert_eq!(states, vec![1, 2]);
+///
+/// // The residual weights of the states 1 and 2 in the subset of the state 1 are 0 and 2.
+/// let one = TropicalWeight::one();
+/// assert_eq!(
+///     dump.to_string(),
+///     format!("0\t0\t0:{:?}\n1\t0\t1:{:?}\t2:{:?}", one, one, TropicalWeight::new(2.0))
+/// );
+/// assert_eq!(dump.stats(1).max_subset_size(), 2);
+/// # Ok(())
+/// # }
+/// ```
+pub fn determinize_with_dump<W, F1, F2>(
+    fst_in: &F1,
+    config: DeterminizeConfig,
+) -> Result<(F2, DeterminizeDump)>
+where
+    W: WeaklyDivisibleSemiring + WeightQuantize,
+    F1: ExpandedFst<W>,
+    F2: MutableFst<W> + AllocableFst<W>,
+{
+    let (fst_res, dump) = determinize_with_config_and_dump(fst_in, config, true)?;
+    Ok((fst_res, dump.unwrap_or_default()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::algorithms::determinize::{determinize_with_config, DeterminizeType};
+    use crate::fst_impls::VectorFst;
+    use crate::fst_traits::CoreFst;
+    use crate::semirings::TropicalWeight;
+    use crate::{Label, Tr, Trs};
+
+    #[test]
+    fn test_determinize_with_dump() -> Result<()> {
+        // 1:2 then 3:4, or 1:5 then 3:4 : the output labels 2 and 5 are pending in the subset
+        // reached by 1.
+        let mut fst = VectorFst::<TropicalWeight>::new();
+        fst.add_states(4);
+        fst.set_start(0)?;
+        fst.add_tr(0, Tr::new(1, 2, 1.0, 1))?;
+        fst.add_tr(0, Tr::new(1, 5, 2.0, 2))?;
+        fst.add_tr(1, Tr::new(3, 4, 0.0, 3))?;
+        fst.add_tr(2, Tr::new(3, 4, 0.5, 3))?;
+        fst.set_final(3, 0.0)?;
+
+        let config =
+            DeterminizeConfig::default().with_det_type(DeterminizeType::DeterminizeNonFunctional);
+        let (det, dump): (VectorFst<_>, _) = determinize_with_dump(&fst, config)?;
+        let det_ref: VectorFst<_> = determinize_with_config(&fst, config)?;
+        assert_eq!(det, det_ref);
+
+        assert_eq!(dump.subsets.len(), 3);
+        let subset = &dump.subsets[&1];
+        let states: Vec<_> = subset.elements.iter().map(|e| e.0).collect();
+        assert_eq!(states, vec![1, 2]);
+        assert_ne!(subset.elements[0].1, subset.elements[1].1);
+
+        let text = dump.to_string();
+        assert_eq!(text.lines().count(), 3);
+        assert!(text.lines().nth(1).unwrap().starts_with("1\t0\t1:"));
+        Ok(())
+    }
+    #[test]
+    fn test_determinize_with_dump_keyed_by_result_states() -> Result<()> {
+        // The subset reached by 1 is final with the pending output labels 2 and 3 : the
+        // states added to write them shift the ids of the states of the result reached by 4
+        // then 5 w.r.t. the gallic acceptor.
+        let mut fst = VectorFst::<TropicalWeight>::new();
+        fst.add_states(5);
+        fst.set_start(0)?;
+        fst.add_tr(0, Tr::new(1, 2, 0.0, 1))?;
+        fst.add_tr(0, Tr::new(1, 3, 0.0, 2))?;
+        fst.add_tr(1, Tr::new(4, 4, 0.0, 3))?;
+        fst.add_tr(2, Tr::new(4, 4, 0.0, 3))?;
+        fst.add_tr(3, Tr::new(5, 5, 0.0, 4))?;
+        fst.set_final(1, 0.0)?;
+        fst.set_final(2, 0.0)?;
+        fst.set_final(4, 0.0)?;
+
+        let config =
+            DeterminizeConfig::default().with_det_type(DeterminizeType::DeterminizeNonFunctional);
+        let (det, dump): (VectorFst<_>, _) = determinize_with_dump(&fst, config)?;
+
+        let next_state = |s: StateId, ilabel: Label| -> Result<StateId> {
+            det.get_trs(s)?
+                .trs()
+                .iter()
+                .find(|tr| tr.ilabel == ilabel)
+                .map(|tr| tr.nextstate)
+                .ok_or_else(|| format_err!("No transition with the input label {}", ilabel))
+        };
+        let start = det.start().unwrap();
+        let s1 = next_state(start, 1)?;
+        let s2 = next_state(s1, 4)?;
+        let s3 = next_state(s2, 5)?;
+
+        assert_eq!(dump.subsets.len(), 4);
+        for (state, expected) in [
+            (start, vec![0]),
+            (s1, vec![1, 2]),
+            (s2, vec![3]),
+            (s3, vec![4]),
+        ] {
+            let states: Vec<_> = dump.subsets[&state].elements.iter().map(|e| e.0).collect();
+            assert_eq!(states, expected);
+        }
+        Ok(())
+    }
+}
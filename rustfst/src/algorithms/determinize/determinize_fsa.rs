@@ -1,5 +1,5 @@
 use crate::algorithms::determinize::divisors::CommonDivisor;
-use crate::algorithms::determinize::DeterminizeFsaOp;
+use crate::algorithms::determinize::{DeterminizeDump, DeterminizeFsaOp};
 use crate::algorithms::lazy::{LazyFst, SimpleHashMapCache};
 use crate::fst_properties::FstProperties;
 use crate::fst_traits::{AllocableFst, CoreFst, Fst, FstIterator, MutableFst, StateIterator};
@@ -159,6 +159,11 @@ where
         self.0.compute()
     }
 
+    /// Weighted subsets of the states expanded so far.
+    pub fn dump(&self) -> DeterminizeDump {
+        self.0.op.dump()
+    }
+
     pub fn out_dist(self) -> Result<Vec<W>> {
         self.0.op.out_dist()
     }
@@ -0,0 +1,240 @@
+use std::borrow::Borrow;
+use std::fmt::Debug;
+use std::sync::Arc;
+
+use anyhow::Result;
+
+use crate::algorithms::determinize::{DefaultCommonDivisor, DeterminizeFsa, DeterminizeStats};
+use crate::fst_properties::mutable_properties::determinize_properties;
+use crate::fst_properties::FstProperties;
+use crate::fst_traits::{AllocableFst, CoreFst, Fst, FstIterator, MutableFst, StateIterator};
+use crate::semirings::{SemiringProperties, WeaklyDivisibleSemiring, WeightQuantize};
+use crate::{StateId, SymbolTable, TrsVec, KDELTA};
+
+type InnerDeterminizeFsa<W, F, B> = DeterminizeFsa<W, F, DefaultCommonDivisor, B, Vec<W>>;
+
+/// Determinizes an acceptor, as `determinize` does. This version is a Delayed FST : the
+/// weighted subset of a state is only built when its transitions or its final weight are
+/// requested, so that a decoding exploring a small part of the result doesn't pay for the
+/// determinization of the whole FST, as the `DeterminizeFst` of OpenFst.
+///
+/// A transducer must first be turned into an acceptor, e.g. with `LabelPairTable::encode_fst`.
+///
+/// # Example
+/// ```
+/// # use rustfst::algorithms::determinize::DeterminizeFst;
+/// # use rustfst::fst_impls::VectorFst;
+/// # use rustfst::fst_traits::{CoreFst, MutableFst};
+/// # use rustfst::semirings::{Semiring, TropicalWeight};
+/// # use rustfst::{Tr, Trs};
+/// # use anyhow::Result;
+/// # fn main() -> Result<()> {
+/// let mut fst = VectorFst::<TropicalWeight>::new();
+/// fst.add_states(3);
+/// fst.set_start(0)?;
+/// fst.add_tr(0, Tr::new(1, 1, 1.0, 1))?;
+/// fst.add_tr(0, Tr::new(1, 1, 3.0, 2))?;
+/// fst.set_final(1, TropicalWeight::one())?;
+/// fst.set_final(2, TropicalWeight::one())?;
+///
+/// let lazy_fst = DeterminizeFst::<_, VectorFst<_>, _>::new(fst)?;
+/// let start = lazy_fst.start().unwrap();
+/// let trs = lazy_fst.get_trs(start)?;
+/// assert_eq!(trs.len(), 1);
+/// assert_eq!(trs.trs()[0].weight, TropicalWeight::new(1.0));
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct DeterminizeFst<W, F, B>(InnerDeterminizeFsa<W, F, B>, FstProperties)
+where
+    W: WeaklyDivisibleSemiring + WeightQuantize,
+    F: Fst<W>,
+    B: Borrow<F> + Debug;
+
+impl<W, F, B> CoreFst<W> for DeterminizeFst<W, F, B>
+where
+    W: WeaklyDivisibleSemiring + WeightQuantize,
+    F: Fst<W>,
+    B: Borrow<F> + Debug,
+{
+    type TRS = TrsVec<W>;
+
+    fn start(&self) -> Option<StateId> {
+        self.0.start()
+    }
+
+    fn final_weight(&self, state_id: StateId) -> Result<Option<W>> {
+        self.0.final_weight(state_id)
+    }
+
+    unsafe fn final_weight_unchecked(&self, state_id: StateId) -> Option<W> {
+        self.0.final_weight_unchecked(state_id)
+    }
+
+    fn num_trs(&self, s: StateId) -> Result<usize> {
+        self.0.num_trs(s)
+    }
+
+    unsafe fn num_trs_unchecked(&self, s: StateId) -> usize {
+        self.0.num_trs_unchecked(s)
+    }
+
+    fn get_trs(&self, state_id: StateId) -> Result<Self::TRS> {
+        self.0.get_trs(state_id)
+    }
+
+    unsafe fn get_trs_unchecked(&self, state_id: StateId) -> Self::TRS {
+        self.0.get_trs_unchecked(state_id)
+    }
+
+    fn properties(&self) -> FstProperties {
+        self.1
+    }
+
+    fn num_input_epsilons(&self, state: StateId) -> Result<usize> {
+        self.0.num_input_epsilons(state)
+    }
+
+    fn num_output_epsilons(&self, state: StateId) -> Result<usize> {
+        self.0.num_output_epsilons(state)
+    }
+}
+
+impl<'a, W, F, B> StateIterator<'a> for DeterminizeFst<W, F, B>
+where
+    W: WeaklyDivisibleSemiring + WeightQuantize + 'a,
+    F: Fst<W> + 'a,
+    B: Borrow<F> + Debug + 'a,
+{
+    type Iter = <InnerDeterminizeFsa<W, F, B> as StateIterator<'a>>::Iter;
+
+    fn states_iter(&'a self) -> Self::Iter {
+        self.0.states_iter()
+    }
+}
+
+impl<'a, W, F, B> FstIterator<'a, W> for DeterminizeFst<W, F, B>
+where
+    W: WeaklyDivisibleSemiring + WeightQuantize,
+    F: Fst<W> + 'a,
+    B: Borrow<F> + Debug + 'a,
+{
+    type FstIter = <InnerDeterminizeFsa<W, F, B> as FstIterator<'a, W>>::FstIter;
+
+    fn fst_iter(&'a self) -> Self::FstIter {
+        self.0.fst_iter()
+    }
+}
+
+impl<W, F, B> Fst<W> for DeterminizeFst<W, F, B>
+where
+    W: WeaklyDivisibleSemiring + WeightQuantize,
+    F: Fst<W> + 'static,
+    B: Borrow<F> + Debug + 'static,
+{
+    fn input_symbols(&self) -> Option<&Arc<SymbolTable>> {
+        self.0.input_symbols()
+    }
+
+    fn output_symbols(&self) -> Option<&Arc<SymbolTable>> {
+        self.0.output_symbols()
+    }
+
+    fn set_input_symbols(&mut self, symt: Arc<SymbolTable>) {
+        self.0.set_input_symbols(symt)
+    }
+
+    fn set_output_symbols(&mut self, symt: Arc<SymbolTable>) {
+        self.0.set_output_symbols(symt)
+    }
+
+    fn take_input_symbols(&mut self) -> Option<Arc<SymbolTable>> {
+        self.0.take_input_symbols()
+    }
+
+    fn take_output_symbols(&mut self) -> Option<Arc<SymbolTable>> {
+        self.0.take_output_symbols()
+    }
+}
+
+impl<W, F, B> DeterminizeFst<W, F, B>
+where
+    W: WeaklyDivisibleSemiring + WeightQuantize,
+    F: Fst<W>,
+    B: Borrow<F> + Debug,
+{
+    pub fn new(fst: B) -> Result<Self> {
+        Self::new_with_delta(fst, KDELTA)
+    }
+
+    /// Lazy determinization, the residual weights of the subsets being quantized with `delta`.
+    pub fn new_with_delta(fst: B, delta: f32) -> Result<Self> {
+        W::properties().check(SemiringProperties::LEFT_SEMIRING, "DeterminizeFst")?;
+        let iprops = fst.borrow().properties();
+        let det_fsa = DeterminizeFsa::new(fst, None, delta)?;
+        Ok(DeterminizeFst(
+            det_fsa,
+            determinize_properties(iprops, false, true),
+        ))
+    }
+
+    /// Turns the Lazy FST into a static one.
+    pub fn compute<F2: MutableFst<W> + AllocableFst<W>>(&self) -> Result<F2> {
+        let mut fst_res: F2 = self.0.compute()?;
+        fst_res.set_properties(self.1);
+        Ok(fst_res)
+    }
+
+    /// Statistics on the subsets of the states expanded so far.
+    pub fn stats(&self, num_largest_subsets: usize) -> DeterminizeStats {
+        self.0.dump().stats(num_largest_subsets)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::algorithms::determinize::determinize;
+    use crate::fst_impls::VectorFst;
+    use crate::semirings::{Semiring, TropicalWeight};
+    use crate::{Tr, Trs};
+
+    #[test]
+    fn test_determinize_fst() -> Result<()> {
+        // Each label i opens a subset of the states 1..=i, all of them leading to the state 5.
+        let mut fst = VectorFst::<TropicalWeight>::new();
+        fst.add_states(6);
+        fst.set_start(0)?;
+        for i in 1..5 {
+            for s in 1..=i {
+                fst.add_tr(0, Tr::new(i, i, s as f32, s))?;
+            }
+            fst.add_tr(i, Tr::new(10, 10, 0.0, 5))?;
+        }
+        fst.set_final(5, TropicalWeight::one())?;
+
+        let det_ref: VectorFst<TropicalWeight> = determinize(&fst)?;
+
+        let lazy_fst = DeterminizeFst::<_, VectorFst<_>, _>::new(&fst)?;
+        assert!(lazy_fst
+            .properties()
+            .contains(FstProperties::I_DETERMINISTIC));
+        // Only the subsets reached by the expanded states are built.
+        let start = lazy_fst.start().unwrap();
+        let trs = lazy_fst.get_trs(start)?;
+        assert_eq!(trs.len(), 4);
+        assert_eq!(lazy_fst.stats(0).num_subsets(), 5);
+        lazy_fst.get_trs(trs.trs()[0].nextstate)?;
+        assert_eq!(lazy_fst.stats(0).num_subsets(), 6);
+
+        let det: VectorFst<TropicalWeight> = lazy_fst.compute()?;
+        assert_eq!(det, det_ref);
+
+        let mut transducer = fst.clone();
+        transducer.add_tr(1, Tr::new(1, 2, 0.0, 1))?;
+        assert!(DeterminizeFst::<_, VectorFst<_>, _>::new(&transducer).is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,278 @@
+use std::collections::{BTreeMap, HashMap, VecDeque};
+
+use anyhow::Result;
+
+use crate::algorithms::determinize::{DeterminizeElement, WeightedSubset};
+use crate::fst_properties::FstProperties;
+use crate::fst_traits::{ExpandedFst, MutableFst};
+use crate::semirings::{DivideType, SemiringProperties, WeaklyDivisibleSemiring, WeightQuantize};
+use crate::{Label, StateId, Tr, Trs, EPS_LABEL, KDELTA};
+
+struct PartialDeterminizer<'a, W: WeaklyDivisibleSemiring, F1, F2> {
+    ifst: &'a F1,
+    ofst: F2,
+    max_depth: usize,
+    /// Output state of each quantized subset.
+    subsets: HashMap<WeightedSubset<W>, StateId>,
+    /// Subsets to expand with their depth.
+    queue: VecDeque<(StateId, WeightedSubset<W>, usize)>,
+    /// Copy of each input state below the frontier.
+    copies: Vec<Option<StateId>>,
+    copy_queue: Vec<StateId>,
+}
+
+impl<'a, W, F1, F2> PartialDeterminizer<'a, W, F1, F2>
+where
+    W: WeaklyDivisibleSemiring + WeightQuantize,
+    F1: ExpandedFst<W>,
+    F2: MutableFst<W>,
+{
+    fn find_subset(&mut self, subset: WeightedSubset<W>, depth: usize) -> Result<StateId> {
+        let key = WeightedSubset::from_vec(
+            subset
+                .iter()
+                .map(|e| Ok(DeterminizeElement::new(e.state, e.weight.quantize(KDELTA)?)))
+                .collect::<Result<Vec<_>>>()?,
+        );
+        if let Some(s) = self.subsets.get(&key) {
+            return Ok(*s);
+        }
+        let s = if depth >= self.max_depth
+            && subset.pairs.len() == 1
+            && subset.pairs[0].weight.is_one()
+        {
+            // No need for a special tr to reach a single state.
+            self.find_copy(subset.pairs[0].state)
+        } else {
+            let s = self.ofst.add_state();
+            self.queue.push_back((s, subset, depth));
+            s
+        };
+        self.subsets.insert(key, s);
+        Ok(s)
+    }
+
+    fn find_copy(&mut self, state: StateId) -> StateId {
+        match self.copies[state as usize] {
+            Some(s) => s,
+            None => {
+                let s = self.ofst.add_state();
+                self.copies[state as usize] = Some(s);
+                self.copy_queue.push(state);
+                s
+            }
+        }
+    }
+
+    fn expand(&mut self, s: StateId, subset: &WeightedSubset<W>, depth: usize) -> Result<()> {
+        let mut final_weight = W::zero();
+        // Weight and residual weight of each destination state of each label.
+        let mut label_trs: BTreeMap<Label, (W, BTreeMap<StateId, W>)> = BTreeMap::new();
+        for element in subset.iter() {
+            if let Some(w) = self.ifst.final_weight(element.state)? {
+                final_weight.plus_assign(element.weight.times(w)?)?;
+            }
+            for tr in self.ifst.get_trs(element.state)?.trs() {
+                let weight = element.weight.times(&tr.weight)?;
+                if weight.is_zero() {
+                    continue;
+                }
+                let (total, residuals) = label_trs
+                    .entry(tr.ilabel)
+                    .or_insert_with(|| (W::zero(), BTreeMap::new()));
+                total.plus_assign(&weight)?;
+                let residual = residuals.entry(tr.nextstate).or_insert_with(W::zero);
+                residual.plus_assign(weight)?;
+            }
+        }
+        if !final_weight.is_zero() {
+            self.ofst.set_final(s, final_weight)?;
+        }
+        for (label, (weight, residuals)) in label_trs {
+            if weight.is_zero() {
+                continue;
+            }
+            let pairs = residuals
+                .into_iter()
+                .map(|(state, w)| {
+                    Ok(DeterminizeElement::new(
+                        state,
+                        w.divide(&weight, DivideType::DivideLeft)?,
+                    ))
+                })
+                .collect::<Result<Vec<_>>>()?;
+            let nextstate = self.find_subset(WeightedSubset::from_vec(pairs), depth + 1)?;
+            self.ofst
+                .add_tr(s, Tr::new(label, label, weight, nextstate))?;
+        }
+        Ok(())
+    }
+
+    /// Links a subset of the frontier to the copies of its states.
+    fn expand_frontier(&mut self, s: StateId, subset: &WeightedSubset<W>) -> Result<()> {
+        for element in subset.iter() {
+            let copy = self.find_copy(element.state);
+            self.ofst.add_tr(
+                s,
+                Tr::new(EPS_LABEL, EPS_LABEL, element.weight.clone(), copy),
+            )?;
+        }
+        Ok(())
+    }
+
+    fn copy(&mut self, state: StateId) -> Result<()> {
+        // Can't fail as the state has been queued by find_copy.
+        let s = self.copies[state as usize].unwrap();
+        if let Some(w) = self.ifst.final_weight(state)? {
+            self.ofst.set_final(s, w)?;
+        }
+        for tr in self.ifst.get_trs(state)?.trs() {
+            let nextstate = self.find_copy(tr.nextstate);
+            self.ofst.add_tr(
+                s,
+                Tr::new(tr.ilabel, tr.olabel, tr.weight.clone(), nextstate),
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Determinizes an acceptor on the strings of at most `max_depth` labels only.
+///
+/// The states reached by the prefixes shorter than `max_depth` are weighted subsets, as in
+/// `determinize`, so that the first labels of a string (e.g. the first frames of a decoding
+/// graph) are matched deterministically. Each subset reached by a prefix of `max_depth`
+/// labels is linked by epsilon transitions, weighted by its residual weights, to copies of the
+/// states of the input FST, which keep the rest of the FST unchanged and non-deterministic.
+/// The cost of the determinization is then bounded by the number of prefixes, even on FSTs
+/// whose full determinization blows up.
+///
+/// As in `determinize`, epsilon transitions are treated as regular symbols. The weight must be
+/// left distributive and a transducer must first be turned into an acceptor, e.g. with
+/// `LabelPairTable::encode_fst`.
+///
+/// # Example
+/// ```
+/// # use rustfst::algorithms::determinize::determinize_partial;
+/// # use rustfst::algorithms::score_sequence;
+/// # use rustfst::fst_impls::VectorFst;
+/// # use rustfst::fst_traits::{CoreFst, MutableFst};
+/// # use rustfst::semirings::{Semiring, TropicalWeight};
+/// # use rustfst::Tr;
+/// # use anyhow::Result;
+/// # fn main() -> Result<()> {
+/// let mut fst = VectorFst::<TropicalWeight>::new();
+/// fst.add_states(4);
+/// fst.set_start(0)?;
+/// fst.add_tr(0, Tr::new(1, 1, 1.0, 1))?;
+/// fst.add_tr(0, Tr::new(1, 1, 2.0, 2))?;
+/// fst.add_tr(1, Tr::new(2, 2, 1.0, 3))?;
+/// fst.add_tr(2, Tr::new(3, 3, 1.0, 3))?;
+/// fst.set_final(3, TropicalWeight::one())?;
+///
+/// let partial: VectorFst<TropicalWeight> = determinize_partial(&fst, 1)?;
+/// // A single tr labeled 1 leaves the start state.
+/// assert_eq!(partial.num_trs(partial.start().unwrap())?, 1);
+/// assert_eq!(score_sequence(&partial, &[1, 3])?, Some(TropicalWeight::new(3.0)));
+/// # Ok(())
+/// # }
+/// ```
+pub fn determinize_partial<W, F1, F2>(fst_in: &F1, max_depth: usize) -> Result<F2>
+where
+    W: WeaklyDivisibleSemiring + WeightQuantize,
+    F1: ExpandedFst<W>,
+    F2: MutableFst<W>,
+{
+    W::properties().check(SemiringProperties::LEFT_SEMIRING, "determinize_partial")?;
+    if !fst_in.properties().contains(FstProperties::ACCEPTOR) {
+        bail!("determinize_partial : the FST must be an acceptor")
+    }
+    let mut ofst = F2::new();
+    ofst.set_symts_from_fst(fst_in);
+    let start = match fst_in.start() {
+        Some(start) => start,
+        None => return Ok(ofst),
+    };
+
+    let mut determinizer = PartialDeterminizer {
+        ifst: fst_in,
+        ofst,
+        max_depth,
+        subsets: HashMap::new(),
+        queue: VecDeque::new(),
+        copies: vec![None; fst_in.num_states()],
+        copy_queue: vec![],
+    };
+    let start_subset = WeightedSubset::from_vec(vec![DeterminizeElement::new(start, W::one())]);
+    let ostart = determinizer.find_subset(start_subset, 0)?;
+    determinizer.ofst.set_start(ostart)?;
+
+    // Breadth-first, so that the depth of a subset is the length of its shortest prefix.
+    while let Some((s, subset, depth)) = determinizer.queue.pop_front() {
+        if depth < max_depth {
+            determinizer.expand(s, &subset, depth)?;
+        } else {
+            determinizer.expand_frontier(s, &subset)?;
+        }
+    }
+    while let Some(state) = determinizer.copy_queue.pop() {
+        determinizer.copy(state)?;
+    }
+    Ok(determinizer.ofst)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::algorithms::score_sequence;
+    use crate::fst_impls::VectorFst;
+    use crate::fst_traits::CoreFst;
+    use crate::semirings::{Semiring, TropicalWeight};
+
+    #[test]
+    fn test_determinize_partial() -> Result<()> {
+        // Strings 1 (2|3)* 4, the choice of the path after 1 being only known at the end.
+        let mut fst = VectorFst::<TropicalWeight>::new();
+        fst.add_states(4);
+        fst.set_start(0)?;
+        fst.add_tr(0, Tr::new(1, 1, 1.0, 1))?;
+        fst.add_tr(0, Tr::new(1, 1, 2.0, 2))?;
+        for s in 1..3 {
+            fst.add_tr(s, Tr::new(2, 2, s as f32, s))?;
+            fst.add_tr(s, Tr::new(3, 3, 1.0, s))?;
+            fst.add_tr(s, Tr::new(4, 4, 3.0 - s as f32, 3))?;
+        }
+        fst.set_final(3, TropicalWeight::one())?;
+        let strings = [vec![1, 4], vec![1, 2, 2, 4], vec![1, 3, 2, 4], vec![1, 2]];
+
+        for max_depth in 0..4 {
+            let partial: VectorFst<TropicalWeight> = determinize_partial(&fst, max_depth)?;
+            for string in strings.iter() {
+                assert_eq!(
+                    score_sequence(&partial, string)?,
+                    score_sequence(&fst, string)?
+                );
+            }
+            // With a depth of 0, the result is a copy of the input.
+            let start = partial.start().unwrap();
+            assert_eq!(partial.num_trs(start)?, if max_depth == 0 { 2 } else { 1 });
+        }
+
+        // The states of the prefixes of at most one label are deterministic, the subset of
+        // the prefix 1 2 is linked to the copies of 1 and 2 by epsilon transitions.
+        let mut partial: VectorFst<TropicalWeight> = determinize_partial(&fst, 2)?;
+        assert!(!partial
+            .compute_and_update_properties(FstProperties::I_DETERMINISTIC)?
+            .contains(FstProperties::I_DETERMINISTIC));
+        let after_1 = partial.get_trs(0)?.trs()[0].nextstate;
+        let labels: Vec<_> = partial
+            .get_trs(after_1)?
+            .trs()
+            .iter()
+            .map(|tr| tr.ilabel)
+            .collect();
+        assert_eq!(labels, vec![2, 3, 4]);
+        Ok(())
+    }
+}
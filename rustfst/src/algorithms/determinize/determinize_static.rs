@@ -3,12 +3,14 @@ use std::borrow::Borrow;
 use anyhow::Result;
 
 use crate::algorithms::determinize::divisors::CommonDivisor;
-use crate::algorithms::determinize::DeterminizeFsa;
 use crate::algorithms::determinize::{DefaultCommonDivisor, DeterminizeType, GallicCommonDivisor};
+use crate::algorithms::determinize::{DeterminizeDump, DeterminizeFsa};
 use crate::algorithms::factor_weight::factor_iterators::{
     GallicFactor, GallicFactorMin, GallicFactorRestrict,
 };
-use crate::algorithms::factor_weight::{factor_weight, FactorWeightOptions, FactorWeightType};
+use crate::algorithms::factor_weight::{
+    FactorIterator, FactorWeightFst, FactorWeightOptions, FactorWeightType,
+};
 use crate::algorithms::weight_convert;
 use crate::algorithms::weight_converters::{FromGallicConverter, ToGallicConverter};
 use crate::fst_impls::VectorFst;
@@ -19,7 +21,7 @@ use crate::semirings::SemiringProperties;
 use crate::semirings::{
     GallicWeight, GallicWeightMin, GallicWeightRestrict, WeaklyDivisibleSemiring, WeightQuantize,
 };
-use crate::{EPS_LABEL, KDELTA};
+use crate::{Label, EPS_LABEL, KDELTA};
 
 pub fn determinize_with_distance<W, F1, F2>(
     ifst: &F1,
@@ -38,7 +40,12 @@ where
     fst.compute_with_distance()
 }
 
-pub fn determinize_fsa<W, F1, F2, CD>(fst_in: &F1, delta: f32) -> Result<F2>
+/// Determinizes the acceptor and, if `dump` is true, dumps the subsets built.
+pub(crate) fn determinize_fsa_with_dump<W, F1, F2, CD>(
+    fst_in: &F1,
+    delta: f32,
+    dump: bool,
+) -> Result<(F2, Option<DeterminizeDump>)>
 where
     W: WeaklyDivisibleSemiring + WeightQuantize,
     F1: Fst<W>,
@@ -49,83 +56,132 @@ where
         bail!("determinize_fsa : weight must be left distributive")
     }
     let det_fsa: DeterminizeFsa<W, F1, CD, _, Vec<W>> = DeterminizeFsa::new(fst_in, None, delta)?;
-    det_fsa.compute()
+    let fst_res = det_fsa.compute()?;
+    let dump = if dump { Some(det_fsa.dump()) } else { None };
+    Ok((fst_res, dump))
 }
 
-pub fn determinize_fst<W, F1, F2>(fst_in: &F1, det_type: DeterminizeType, delta: f32) -> Result<F2>
+/// Determinizes the transducer and, if `dump` is true, dumps the subsets built by the
+/// determinization of its gallic acceptor, keyed by the states of the result.
+pub(crate) fn determinize_fst_with_dump<W, F1, F2>(
+    fst_in: &F1,
+    config: DeterminizeConfig,
+    dump: bool,
+) -> Result<(F2, Option<DeterminizeDump>)>
 where
     W: WeaklyDivisibleSemiring + WeightQuantize + 'static,
     F1: ExpandedFst<W>,
     F2: MutableFst<W> + AllocableFst<W>,
 {
+    let delta = config.delta;
     let mut to_gallic = ToGallicConverter {};
     let mut from_gallic = FromGallicConverter {
-        superfinal_label: EPS_LABEL,
+        superfinal_label: config.subsequential_label,
     };
 
     let factor_opts = FactorWeightOptions {
         delta: KDELTA,
         mode: FactorWeightType::FACTOR_FINAL_WEIGHTS,
-        final_ilabel: EPS_LABEL,
-        final_olabel: EPS_LABEL,
-        increment_final_ilabel: false,
-        increment_final_olabel: false,
+        final_ilabel: config.subsequential_label,
+        final_olabel: config.subsequential_label,
+        increment_final_ilabel: config.increment_subsequential_label,
+        increment_final_olabel: config.increment_subsequential_label,
     };
 
-    match det_type {
+    match config.det_type {
         DeterminizeType::DeterminizeDisambiguate => {
             if !W::properties().contains(SemiringProperties::PATH) {
                 bail!("determinize : weight needs to have the path property to disambiguate output")
             }
             let fsa: VectorFst<GallicWeightMin<W>> =
                 weight_convert(fst_in.borrow(), &mut to_gallic)?;
-            let determinized_fsa: VectorFst<GallicWeightMin<W>> =
-                determinize_fsa::<_, VectorFst<_>, _, GallicCommonDivisor>(&fsa, delta)?;
-            let factored_determinized_fsa: VectorFst<GallicWeightMin<W>> =
-                factor_weight::<_, VectorFst<GallicWeightMin<W>>, _, _, GallicFactorMin<W>>(
-                    &determinized_fsa,
-                    factor_opts,
+            let (determinized_fsa, dump): (VectorFst<GallicWeightMin<W>>, _) =
+                determinize_fsa_with_dump::<_, VectorFst<_>, _, GallicCommonDivisor>(
+                    &fsa, delta, dump,
                 )?;
-            weight_convert(&factored_determinized_fsa, &mut from_gallic)
+            let (factored_determinized_fsa, dump) = factor_gallic_weights::<_, GallicFactorMin<W>>(
+                &determinized_fsa,
+                factor_opts,
+                dump,
+            )?;
+            Ok((
+                weight_convert(&factored_determinized_fsa, &mut from_gallic)?,
+                dump,
+            ))
         }
         DeterminizeType::DeterminizeFunctional => {
             let fsa: VectorFst<GallicWeightRestrict<W>> =
                 weight_convert(fst_in.borrow(), &mut to_gallic)?;
-            let determinized_fsa: VectorFst<GallicWeightRestrict<W>> =
-                determinize_fsa::<_, VectorFst<_>, _, GallicCommonDivisor>(&fsa, delta)?;
-            let factored_determinized_fsa: VectorFst<GallicWeightRestrict<W>> =
-                factor_weight::<
-                    _,
-                    VectorFst<GallicWeightRestrict<W>>,
-                    _,
-                    _,
-                    GallicFactorRestrict<W>,
-                >(&determinized_fsa, factor_opts)?;
-            weight_convert(&factored_determinized_fsa, &mut from_gallic)
+            let (determinized_fsa, dump): (VectorFst<GallicWeightRestrict<W>>, _) =
+                determinize_fsa_with_dump::<_, VectorFst<_>, _, GallicCommonDivisor>(
+                    &fsa, delta, dump,
+                )?;
+            let (factored_determinized_fsa, dump) = factor_gallic_weights::<
+                _,
+                GallicFactorRestrict<W>,
+            >(
+                &determinized_fsa, factor_opts, dump
+            )?;
+            Ok((
+                weight_convert(&factored_determinized_fsa, &mut from_gallic)?,
+                dump,
+            ))
         }
         DeterminizeType::DeterminizeNonFunctional => {
             let fsa: VectorFst<GallicWeight<W>> = weight_convert(fst_in.borrow(), &mut to_gallic)?;
-            let determinized_fsa: VectorFst<GallicWeight<W>> =
-                determinize_fsa::<_, VectorFst<_>, _, GallicCommonDivisor>(&fsa, delta)?;
-            let factored_determinized_fsa: VectorFst<GallicWeight<W>> =
-                factor_weight::<_, VectorFst<GallicWeight<W>>, _, _, GallicFactor<W>>(
-                    &determinized_fsa,
-                    factor_opts,
+            let (determinized_fsa, dump): (VectorFst<GallicWeight<W>>, _) =
+                determinize_fsa_with_dump::<_, VectorFst<_>, _, GallicCommonDivisor>(
+                    &fsa, delta, dump,
                 )?;
-            weight_convert(&factored_determinized_fsa, &mut from_gallic)
+            let (factored_determinized_fsa, dump) =
+                factor_gallic_weights::<_, GallicFactor<W>>(&determinized_fsa, factor_opts, dump)?;
+            Ok((
+                weight_convert(&factored_determinized_fsa, &mut from_gallic)?,
+                dump,
+            ))
         }
     }
 }
 
+/// Factors the final weights of the determinized gallic acceptor, keying the dump of its
+/// subsets by the states of the factored acceptor, which are those of the result.
+fn factor_gallic_weights<W, FI>(
+    determinized_fsa: &VectorFst<W>,
+    factor_opts: FactorWeightOptions,
+    dump: Option<DeterminizeDump>,
+) -> Result<(VectorFst<W>, Option<DeterminizeDump>)>
+where
+    W: WeightQuantize,
+    FI: FactorIterator<W>,
+{
+    let factor_fst = FactorWeightFst::<_, VectorFst<W>, _, FI>::new(determinized_fsa, factor_opts)?;
+    let factored_fsa = factor_fst.compute()?;
+    let dump = dump.map(|dump| dump.rekey(factor_fst.input_states()));
+    Ok((factored_fsa, dump))
+}
+
 #[derive(Clone, Debug, Copy, PartialOrd, PartialEq)]
 pub struct DeterminizeConfig {
     pub delta: f32,
     pub det_type: DeterminizeType,
+    /// Input label of the transitions added to write the residual output strings of the final
+    /// states, when a transducer is not subsequential (e.g. a lexicon with homophones). These
+    /// outputs are otherwise written on epsilon-input transitions to a super-final state.
+    pub subsequential_label: Label,
+    /// If true, the residual output strings of a same final state are written on transitions
+    /// with distinct input labels (`subsequential_label`, `subsequential_label + 1`...) so
+    /// that the result is deterministic even when a final state has several outputs.
+    pub increment_subsequential_label: bool,
 }
 
 impl DeterminizeConfig {
     pub fn new(delta: f32, det_type: DeterminizeType) -> Self {
-        Self { delta, det_type }
+        Self {
+            delta,
+            det_type,
+            subsequential_label: EPS_LABEL,
+            increment_subsequential_label: false,
+        }
     }
 
     pub fn with_delta(self, delta: f32) -> Self {
@@ -135,14 +191,25 @@ impl DeterminizeConfig {
     pub fn with_det_type(self, det_type: DeterminizeType) -> Self {
         Self { det_type, ..self }
     }
+
+    pub fn with_subsequential_label(self, subsequential_label: Label) -> Self {
+        Self {
+            subsequential_label,
+            ..self
+        }
+    }
+
+    pub fn with_increment_subsequential_label(self, increment_subsequential_label: bool) -> Self {
+        Self {
+            increment_subsequential_label,
+            ..self
+        }
+    }
 }
 
 impl Default for DeterminizeConfig {
     fn default() -> Self {
-        Self {
-            delta: KDELTA,
-            det_type: DeterminizeType::DeterminizeFunctional,
-        }
+        Self::new(KDELTA, DeterminizeType::DeterminizeFunctional)
     }
 }
 
@@ -169,7 +236,31 @@ where
 ///
 /// ![determinize_out](https://raw.githubusercontent.com/Garvys/rustfst-images-doc/master/images/determinize_out.svg?sanitize=true)
 ///
+/// # Subsequential labels
+///
+/// When a transducer is not subsequential, e.g. a lexicon where a word has several
+/// pronunciations, the output strings that can't be written before the end of the input are
+/// written on transitions leaving the final states. By default these transitions have an
+/// epsilon input label. Setting `subsequential_label` to a label unused by the input, and
+/// `increment_subsequential_label` to true, writes them on transitions with distinct
+/// non-epsilon input labels instead, so that the result is a usable deterministic transducer:
+/// the n-th output of an input string is read by appending `subsequential_label + n` to it.
+///
 pub fn determinize_with_config<W, F1, F2>(fst_in: &F1, config: DeterminizeConfig) -> Result<F2>
+where
+    W: WeaklyDivisibleSemiring + WeightQuantize,
+    F1: ExpandedFst<W>,
+    F2: MutableFst<W> + AllocableFst<W>,
+{
+    Ok(determinize_with_config_and_dump(fst_in, config, false)?.0)
+}
+
+/// Determinizes the FST and, if `dump` is true, dumps the subsets built.
+pub(crate) fn determinize_with_config_and_dump<W, F1, F2>(
+    fst_in: &F1,
+    config: DeterminizeConfig,
+    dump: bool,
+) -> Result<(F2, Option<DeterminizeDump>)>
 where
     W: WeaklyDivisibleSemiring + WeightQuantize,
     F1: ExpandedFst<W>,
@@ -178,29 +269,33 @@ where
     let delta = config.delta;
     let det_type = config.det_type;
     let iprops = fst_in.borrow().properties();
-    let mut fst_res: F2 = if iprops.contains(FstProperties::ACCEPTOR) {
-        determinize_fsa::<_, F1, _, DefaultCommonDivisor>(fst_in, delta)?
+    let (mut fst_res, dump): (F2, _) = if iprops.contains(FstProperties::ACCEPTOR) {
+        determinize_fsa_with_dump::<_, F1, _, DefaultCommonDivisor>(fst_in, delta, dump)?
     } else {
-        determinize_fst(fst_in, det_type, delta)?
+        determinize_fst_with_dump(fst_in, config, dump)?
     };
 
-    let distinct_psubsequential_labels = !(det_type == DeterminizeType::DeterminizeNonFunctional);
+    let distinct_psubsequential_labels = !(det_type == DeterminizeType::DeterminizeNonFunctional)
+        || config.increment_subsequential_label;
     fst_res.set_properties(determinize_properties(
         iprops,
-        false,
+        config.subsequential_label != EPS_LABEL,
         distinct_psubsequential_labels,
     ));
     fst_res.set_symts_from_fst(fst_in.borrow());
-    Ok(fst_res)
+    Ok((fst_res, dump))
 }
 
 #[cfg(test)]
 mod tests {
+    use crate::algorithms::shortest_distance;
     use crate::fst_impls::VectorFst;
-    use crate::semirings::TropicalWeight;
+    use crate::fst_traits::CoreFst;
+    use crate::semirings::{LogWeight, TropicalWeight, WeightValidation};
     use crate::tr::Tr;
     use crate::Semiring;
     use crate::SymbolTable;
+    use crate::Trs;
     use proptest::prelude::any;
     use proptest::proptest;
     use std::sync::Arc;
@@ -269,6 +364,70 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_determinize_increment_subsequential_label() -> Result<()> {
+        // Lexicon with two homophones : 1 -> 10 and 1 -> 11.
+        let mut input_fst = VectorFst::<TropicalWeight>::new();
+        let s0 = input_fst.add_state();
+        let s1 = input_fst.add_state();
+        input_fst.set_start(s0)?;
+        input_fst.set_final(s1, TropicalWeight::one())?;
+        input_fst.add_tr(s0, Tr::new(1, 10, 1.0, s1))?;
+        input_fst.add_tr(s0, Tr::new(1, 11, 2.0, s1))?;
+
+        let config = DeterminizeConfig::default()
+            .with_det_type(DeterminizeType::DeterminizeNonFunctional)
+            .with_subsequential_label(100)
+            .with_increment_subsequential_label(true);
+        let determinized_fst: VectorFst<TropicalWeight> =
+            determinize_with_config(&input_fst, config)?;
+
+        let props = determinized_fst.properties_check(FstProperties::I_DETERMINISTIC)?;
+        assert!(props.contains(FstProperties::I_DETERMINISTIC));
+
+        let mut ilabels = vec![];
+        let mut olabels = vec![];
+        for state in determinized_fst.states_range() {
+            for tr in determinized_fst.get_trs(state)?.trs() {
+                if tr.ilabel >= 100 {
+                    ilabels.push(tr.ilabel);
+                    olabels.push(tr.olabel);
+                }
+            }
+        }
+        ilabels.sort();
+        olabels.sort();
+        assert_eq!(ilabels, vec![100, 101]);
+        assert_eq!(olabels, vec![10, 11]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_determinize_log() -> Result<()> {
+        // Two paths "a b" of weights 1 + 1 and 2 + 3 sum up in the log semiring.
+        let mut input_fst = VectorFst::<LogWeight>::new();
+        input_fst.add_states(5);
+        input_fst.set_start(0)?;
+        input_fst.add_tr(0, Tr::new(1, 1, 1.0, 1))?;
+        input_fst.add_tr(0, Tr::new(1, 1, 2.0, 2))?;
+        input_fst.add_tr(1, Tr::new(2, 2, 1.0, 3))?;
+        input_fst.add_tr(2, Tr::new(2, 2, 3.0, 3))?;
+        input_fst.set_final(3, LogWeight::one())?;
+        // Zero weight tr, whose residuals used to be NaN.
+        input_fst.add_tr(0, Tr::new(3, 3, LogWeight::zero(), 4))?;
+        input_fst.set_final(4, LogWeight::one())?;
+
+        let determinized_fst: VectorFst<LogWeight> = determinize(&input_fst)?;
+
+        let props = determinized_fst.properties_check(FstProperties::I_DETERMINISTIC)?;
+        assert!(props.contains(FstProperties::I_DETERMINISTIC));
+        determinized_fst.validate_weights(WeightValidation::Lenient)?;
+        let expected = LogWeight::new(2.0).plus(LogWeight::new(5.0))?;
+        let distance = shortest_distance(&determinized_fst, true)?;
+        assert!(distance[0].approx_equal(expected, KDELTA));
+        Ok(())
+    }
+
     proptest! {
         #[test]
         fn test_proptest_determinize_keeps_symts(mut fst in any::<VectorFst::<TropicalWeight>>()) {
@@ -0,0 +1,148 @@
+use std::collections::BTreeMap;
+use std::fmt;
+
+use crate::algorithms::determinize::DeterminizeDump;
+use crate::StateId;
+
+/// Statistics on the weighted subsets built by a determinization, to find out which states of
+/// the input FST make it blow up. Computed from the dump of the subsets with
+/// [`DeterminizeDump::stats`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct DeterminizeStats {
+    /// Number of subsets, i.e. of states of the result, of each size.
+    pub subset_sizes: BTreeMap<usize, usize>,
+    /// Greatest residual weight of the subsets w.r.t. `PartialOrd`, i.e. the largest cost in
+    /// the tropical and log semirings, formatted with `Debug` as in `DeterminizeDump`. `None`
+    /// if the result is empty.
+    pub max_residual: Option<String>,
+    /// Largest subsets, by decreasing size, as the state of the result and the states of the
+    /// input FST it gathers.
+    pub largest_subsets: Vec<(StateId, Vec<StateId>)>,
+}
+
+impl DeterminizeStats {
+    pub(crate) fn from_dump(dump: &DeterminizeDump, num_largest_subsets: usize) -> Self {
+        let mut subset_sizes = BTreeMap::new();
+        let mut largest_subsets = vec![];
+        for (state, subset) in dump.subsets.iter() {
+            *subset_sizes.entry(subset.elements.len()).or_insert(0) += 1;
+            let states = subset.elements.iter().map(|e| e.0).collect::<Vec<_>>();
+            largest_subsets.push((*state, states));
+        }
+        largest_subsets.sort_by(|a, b| b.1.len().cmp(&a.1.len()).then(a.0.cmp(&b.0)));
+        largest_subsets.truncate(num_largest_subsets);
+        Self {
+            subset_sizes,
+            max_residual: dump.max_residual.clone(),
+            largest_subsets,
+        }
+    }
+
+    pub fn num_subsets(&self) -> usize {
+        self.subset_sizes.values().sum()
+    }
+
+    pub fn max_subset_size(&self) -> usize {
+        self.subset_sizes.keys().next_back().cloned().unwrap_or(0)
+    }
+
+    /// Mean number of states of the input FST per subset, 0 if the result is empty.
+    pub fn mean_subset_size(&self) -> f32 {
+        let num_subsets = self.num_subsets();
+        if num_subsets == 0 {
+            return 0.0;
+        }
+        let total: usize = self.subset_sizes.iter().map(|(size, n)| size * n).sum();
+        total as f32 / num_subsets as f32
+    }
+}
+
+impl fmt::Display for DeterminizeStats {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "# of subsets\t\t{}", self.num_subsets())?;
+        writeln!(f, "max subset size\t\t{}", self.max_subset_size())?;
+        writeln!(f, "mean subset size\t{:.2}", self.mean_subset_size())?;
+        match &self.max_residual {
+            Some(w) => writeln!(f, "max residual weight\t{}", w)?,
+            None => writeln!(f, "max residual weight\tnone")?,
+        };
+        writeln!(f, "subset sizes")?;
+        for (size, n) in self.subset_sizes.iter() {
+            writeln!(f, "  {}\t{}", size, n)?;
+        }
+        write!(f, "largest subsets")?;
+        for (state, states) in self.largest_subsets.iter() {
+            let states: Vec<_> = states.iter().map(|s| s.to_string()).collect();
+            write!(f, "\n  {}\t{}", state, states.join(" "))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+
+    use crate::algorithms::determinize::{
+        determinize, determinize_with_config, determinize_with_dump, DeterminizeConfig,
+        DeterminizeType,
+    };
+    use crate::fst_impls::VectorFst;
+    use crate::fst_traits::MutableFst;
+    use crate::semirings::{Semiring, TropicalWeight};
+    use crate::Tr;
+
+    #[test]
+    fn test_determinize_stats() -> Result<()> {
+        // Each label i opens a subset of the states 1..=i.
+        let mut fst = VectorFst::<TropicalWeight>::new();
+        fst.add_states(5);
+        fst.set_start(0)?;
+        for i in 1..5 {
+            for s in 1..=i {
+                fst.add_tr(0, Tr::new(i, i, s as f32, s))?;
+            }
+            fst.add_tr(i, Tr::new(10, 10, 0.0, i))?;
+            fst.set_final(i, 0.0)?;
+        }
+
+        let (det, dump): (VectorFst<TropicalWeight>, _) =
+            determinize_with_dump(&fst, DeterminizeConfig::default())?;
+        let stats = dump.stats(2);
+        let det_ref: VectorFst<TropicalWeight> = determinize(&fst)?;
+        assert_eq!(det, det_ref);
+
+        assert_eq!(stats.num_subsets(), 5);
+        let sizes: Vec<_> = stats.subset_sizes.clone().into_iter().collect();
+        assert_eq!(sizes, vec![(1, 2), (2, 1), (3, 1), (4, 1)]);
+        assert!((stats.mean_subset_size() - 11.0 / 5.0).abs() < 1e-6);
+        assert_eq!(
+            stats.max_residual,
+            Some(format!("{:?}", TropicalWeight::new(3.0)))
+        );
+        assert_eq!(stats.largest_subsets.len(), 2);
+        assert_eq!(stats.largest_subsets[0].1, vec![1, 2, 3, 4]);
+        assert_eq!(stats.largest_subsets[1].1, vec![1, 2, 3]);
+
+        // The label 1 reaches the states 1 and 2 with the pending output labels 1 and 3.
+        let mut transducer = VectorFst::<TropicalWeight>::new();
+        transducer.add_states(4);
+        transducer.set_start(0)?;
+        transducer.add_tr(0, Tr::new(1, 1, 1.0, 1))?;
+        transducer.add_tr(0, Tr::new(1, 3, 0.5, 2))?;
+        transducer.add_tr(1, Tr::new(2, 2, 0.0, 3))?;
+        transducer.add_tr(2, Tr::new(2, 4, 0.0, 3))?;
+        transducer.set_final(3, 0.0)?;
+        let config =
+            DeterminizeConfig::default().with_det_type(DeterminizeType::DeterminizeNonFunctional);
+        let (det, dump): (VectorFst<TropicalWeight>, _) =
+            determinize_with_dump(&transducer, config)?;
+        let stats = dump.stats(1);
+        let det_ref: VectorFst<TropicalWeight> = determinize_with_config(&transducer, config)?;
+        assert_eq!(det, det_ref);
+        let sizes: Vec<_> = stats.subset_sizes.clone().into_iter().collect();
+        assert_eq!(sizes, vec![(1, 2), (2, 1)]);
+        assert_eq!(stats.largest_subsets[0].1, vec![1, 2]);
+        Ok(())
+    }
+}
@@ -8,10 +8,22 @@ use crate::semirings::{
 };
 use crate::Semiring;
 
-pub trait CommonDivisor<W: Semiring>: PartialEq + Debug + Sync {
+mod private {
+    pub trait Sealed {}
+
+    impl Sealed for super::DefaultCommonDivisor {}
+    impl Sealed for super::LabelCommonDivisor {}
+    impl Sealed for super::GallicCommonDivisor {}
+}
+
+/// Computes the weight factored out of the residual weights of a subset, and put on the
+/// transition leading to it, by the weighted subset construction. The trait is sealed : the
+/// determinization relies on the properties of the implementations provided.
+pub trait CommonDivisor<W: Semiring>: PartialEq + Debug + Sync + private::Sealed {
     fn common_divisor(w1: &W, w2: &W) -> Result<W>;
 }
 
+/// Sum of the weights, for the weights of an acceptor.
 #[derive(PartialEq, Debug)]
 pub struct DefaultCommonDivisor {}
 
@@ -21,6 +33,7 @@ impl<W: Semiring> CommonDivisor<W> for DefaultCommonDivisor {
     }
 }
 
+/// First label of the string weights if they share it, the empty string otherwise.
 #[derive(PartialEq, Debug)]
 pub struct LabelCommonDivisor {}
 
@@ -56,6 +69,8 @@ macro_rules! impl_label_common_divisor {
 impl_label_common_divisor!(StringWeightLeft);
 impl_label_common_divisor!(StringWeightRestrict);
 
+/// `LabelCommonDivisor` on the string part and sum on the weight part of the gallic weights,
+/// for the determinization of transducers.
 #[derive(Debug, PartialEq)]
 pub struct GallicCommonDivisor {}
 
@@ -1,5 +1,14 @@
-use crate::{Label, Semiring, StateId, Tr};
+use std::collections::btree_map::Entry;
+use std::collections::BTreeMap;
 
+use anyhow::Result;
+
+use crate::algorithms::determinize::CommonDivisor;
+use crate::fst_traits::Fst;
+use crate::semirings::{DivideType, WeaklyDivisibleSemiring, WeightQuantize};
+use crate::{Label, Semiring, StateId, Trs};
+
+/// State of the input FST in a subset, with its residual weight.
 #[derive(PartialEq, Eq, Clone, Hash, PartialOrd, Debug)]
 pub struct DeterminizeElement<W: Semiring> {
     pub state: StateId,
@@ -12,6 +21,16 @@ impl<W: Semiring> DeterminizeElement<W> {
     }
 }
 
+/// Weighted subset of the states of the input FST, i.e. a state of the weighted subset
+/// construction. Along with `CommonDivisor`, it is the building block of the determinization
+/// and can be used to implement similar constructions, e.g. an item-set construction :
+///
+/// - `label_map` gathers the states reached from the subset by each label.
+/// - `normalize` turns such a set of states into a subset, returning the weight of the
+///   transition leading to it.
+/// - `final_weight` is the final weight of the subset.
+///
+/// The subsets are hashable, so that they can be mapped to the states of the output FST.
 #[derive(Default, PartialEq, Eq, Clone, Hash, PartialOrd, Debug)]
 pub struct WeightedSubset<W: Semiring> {
     pub pairs: Vec<DeterminizeElement<W>>,
@@ -25,6 +44,79 @@ impl<W: Semiring> WeightedSubset<W> {
     pub fn iter(&self) -> impl Iterator<Item = &DeterminizeElement<W>> {
         self.pairs.iter()
     }
+
+    /// States reached from the subset by each input label, with the weight of the path
+    /// from the subset. The sets returned are not normalized : a state may appear several times.
+    pub fn label_map<F: Fst<W>>(&self, fst: &F) -> Result<BTreeMap<Label, WeightedSubset<W>>> {
+        let mut label_map: BTreeMap<Label, WeightedSubset<W>> = BTreeMap::new();
+        for src_elt in self.iter() {
+            for tr in fst.get_trs(src_elt.state)?.trs() {
+                let dest_elt =
+                    DeterminizeElement::new(tr.nextstate, src_elt.weight.times(&tr.weight)?);
+                label_map
+                    .entry(tr.ilabel)
+                    .or_insert_with(|| WeightedSubset::from_vec(vec![]))
+                    .pairs
+                    .push(dest_elt);
+            }
+        }
+        Ok(label_map)
+    }
+
+    /// Sum over the states of the subset of their residual weight times their final weight.
+    pub fn final_weight<F: Fst<W>>(&self, fst: &F) -> Result<Option<W>> {
+        let mut final_weight = W::zero();
+        for elt in self.iter() {
+            if let Some(state_final_weight) = fst.final_weight(elt.state)? {
+                final_weight.plus_assign(elt.weight.times(state_final_weight)?)?;
+            }
+        }
+        if final_weight.is_zero() {
+            Ok(None)
+        } else {
+            Ok(Some(final_weight))
+        }
+    }
+}
+
+impl<W: WeaklyDivisibleSemiring + WeightQuantize> WeightedSubset<W> {
+    /// Sorts the subset by state and merges the duplicated states, then divides the weights
+    /// by their common divisor, as computed by `CD`, which is returned. The residual weights
+    /// are quantized with `delta`, so that the subsets equal up to `delta` are identical.
+    pub fn normalize<CD: CommonDivisor<W>>(&mut self, delta: f32) -> Result<W> {
+        self.pairs.sort_by_key(|elt| elt.state);
+
+        let mut divisor = W::zero();
+        for elt in self.pairs.iter() {
+            divisor = CD::common_divisor(&divisor, &elt.weight)?;
+        }
+
+        // A BTreeMap keeps the subset sorted by state : the same subset always maps to the
+        // same tuple, whatever the platform or the run.
+        let mut new_pairs = BTreeMap::new();
+        for x in self.pairs.iter() {
+            match new_pairs.entry(x.state) {
+                Entry::Vacant(e) => {
+                    e.insert(x.clone());
+                }
+                Entry::Occupied(mut e) => {
+                    e.get_mut().weight.plus_assign(&x.weight)?;
+                }
+            };
+        }
+        self.pairs = new_pairs.into_values().collect();
+
+        // The residuals of a zero divisor are all zero, while dividing them would give NaN in
+        // the log and tropical semirings.
+        if divisor.is_zero() {
+            return Ok(divisor);
+        }
+        for elt in self.pairs.iter_mut() {
+            elt.weight = elt.weight.divide(&divisor, DivideType::DivideLeft)?;
+            elt.weight.quantize_assign(delta)?;
+        }
+        Ok(divisor)
+    }
 }
 
 #[derive(Default, PartialEq, Eq, Clone, Hash, PartialOrd, Debug)]
@@ -33,22 +125,58 @@ pub struct DeterminizeStateTuple<W: Semiring> {
     pub filter_state: StateId,
 }
 
-#[derive(Default, PartialEq, Eq, Clone, Hash, PartialOrd, Debug)]
-pub struct DeterminizeTr<W: Semiring> {
-    pub label: Label,
-    pub weight: W,
-    pub dest_tuple: DeterminizeStateTuple<W>,
-}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::collections::HashMap;
 
-impl<W: Semiring> DeterminizeTr<W> {
-    pub fn from_tr(tr: &Tr<W>, filter_state: StateId) -> Self {
-        Self {
-            label: tr.ilabel,
-            weight: W::zero(),
-            dest_tuple: DeterminizeStateTuple {
-                subset: WeightedSubset::from_vec(vec![]),
-                filter_state,
-            },
+    use crate::algorithms::determinize::{determinize, DefaultCommonDivisor};
+    use crate::fst_impls::VectorFst;
+    use crate::fst_traits::{ExpandedFst, MutableFst};
+    use crate::semirings::TropicalWeight;
+    use crate::{Tr, KDELTA};
+
+    #[test]
+    fn test_weighted_subset_construction() -> Result<()> {
+        let mut fst = VectorFst::<TropicalWeight>::new();
+        fst.add_states(4);
+        fst.set_start(0)?;
+        fst.add_tr(0, Tr::new(1, 1, 1.0, 1))?;
+        fst.add_tr(0, Tr::new(1, 1, 2.0, 2))?;
+        fst.add_tr(1, Tr::new(2, 2, 3.0, 3))?;
+        fst.add_tr(2, Tr::new(2, 2, 1.0, 3))?;
+        fst.set_final(3, 0.5)?;
+
+        // Subset construction written with the public building blocks.
+        let start =
+            WeightedSubset::from_vec(vec![DeterminizeElement::new(0, TropicalWeight::one())]);
+        let mut subsets = vec![start.clone()];
+        let mut ids = HashMap::new();
+        ids.insert(start, 0);
+        let mut det = VectorFst::<TropicalWeight>::new();
+        det.add_state();
+        det.set_start(0)?;
+        let mut s = 0;
+        while s < subsets.len() {
+            let subset = subsets[s].clone();
+            if let Some(final_weight) = subset.final_weight(&fst)? {
+                det.set_final(s as StateId, final_weight)?;
+            }
+            for (label, mut next) in subset.label_map(&fst)? {
+                let weight = next.normalize::<DefaultCommonDivisor>(KDELTA)?;
+                let nextstate = *ids.entry(next.clone()).or_insert_with(|| {
+                    subsets.push(next);
+                    det.add_state()
+                });
+                det.add_tr(s as StateId, Tr::new(label, label, weight, nextstate))?;
+            }
+            s += 1;
         }
+
+        let expected: VectorFst<TropicalWeight> = determinize(&fst)?;
+        assert_eq!(det, expected);
+        assert_eq!(det.num_states(), 3);
+        Ok(())
     }
 }
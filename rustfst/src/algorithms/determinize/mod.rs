@@ -1,15 +1,24 @@
+pub use determinize_dump::{determinize_with_dump, DeterminizeDump, DumpedSubset};
 pub(self) use determinize_fsa::DeterminizeFsa;
 pub(self) use determinize_fsa_op::DeterminizeFsaOp;
+pub use determinize_fst::DeterminizeFst;
+pub use determinize_partial::determinize_partial;
 pub use determinize_static::{
     determinize, determinize_with_config, determinize_with_distance, DeterminizeConfig,
 };
-pub(self) use divisors::{DefaultCommonDivisor, GallicCommonDivisor};
-pub(self) use element::{DeterminizeElement, DeterminizeStateTuple, DeterminizeTr, WeightedSubset};
+pub use determinize_stats::DeterminizeStats;
+pub use divisors::{CommonDivisor, DefaultCommonDivisor, GallicCommonDivisor, LabelCommonDivisor};
+pub(self) use element::DeterminizeStateTuple;
+pub use element::{DeterminizeElement, WeightedSubset};
 pub(self) use state_table::DeterminizeStateTable;
 
+mod determinize_dump;
 mod determinize_fsa;
 mod determinize_fsa_op;
+mod determinize_fst;
+mod determinize_partial;
 mod determinize_static;
+mod determinize_stats;
 mod divisors;
 mod element;
 mod state_table;
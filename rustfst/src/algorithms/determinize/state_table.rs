@@ -4,7 +4,7 @@ use std::sync::Mutex;
 
 use bimap::BiHashMap;
 
-use crate::algorithms::determinize::{DeterminizeStateTuple, WeightedSubset};
+use crate::algorithms::determinize::{DeterminizeDump, DeterminizeStateTuple, WeightedSubset};
 use crate::{Semiring, StateId};
 use anyhow::Result;
 
@@ -66,6 +66,11 @@ impl<W: Semiring, B: Borrow<[W]>> DeterminizeStateTable<W, B> {
         inner.table.get_by_left(&tuple_id).unwrap().clone()
     }
 
+    pub fn dump(&self) -> DeterminizeDump {
+        let inner = self.0.lock().unwrap();
+        DeterminizeDump::from_subsets(inner.table.iter().map(|(s, tuple)| (*s, tuple)))
+    }
+
     pub fn out_dist(self) -> Vec<Option<W>> {
         let inner = self.0.into_inner().unwrap();
         inner.out_dist
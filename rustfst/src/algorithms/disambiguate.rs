@@ -0,0 +1,293 @@
+use std::collections::btree_map::Entry as BTreeEntry;
+use std::collections::hash_map::Entry;
+use std::collections::{BTreeMap, HashMap, VecDeque};
+
+use anyhow::Result;
+
+use crate::algorithms::encode::{decode, encode, EncodeType};
+use crate::algorithms::queues::natural_less;
+use crate::algorithms::{connect, fst_convert_from_ref};
+use crate::fst_impls::VectorFst;
+use crate::fst_properties::FstProperties;
+use crate::fst_traits::{ExpandedFst, MutableFst};
+use crate::semirings::{DivideType, SemiringProperties, WeaklyDivisibleSemiring, WeightQuantize};
+use crate::{Label, StateId, Trs, KDELTA};
+
+/// Weighted subset of the states reached by a prefix, sorted by state. As in the determinization,
+/// the residual weights are divided by their ⊕-sum and quantized.
+type Subset<W> = Vec<(StateId, W)>;
+
+/// Best weight into each state reached by a label, with the tr reaching it as in `Successor`.
+type Candidates<W> = BTreeMap<StateId, (W, (StateId, usize))>;
+
+/// Subset reached by a label, and the single tr kept into each of its states, identified by its
+/// source state and its index among the trs of that state.
+struct Successor {
+    subset_id: usize,
+    kept_trs: HashMap<StateId, (StateId, usize)>,
+}
+
+/// Subset construction of the determinization, where the trs of the input FST are kept instead
+/// of being merged : for each subset and each state in it, only the best tr into that state is
+/// kept, and only the best final state of each subset stays final.
+struct Disambiguator<'a, W: WeaklyDivisibleSemiring + WeightQuantize, F: ExpandedFst<W>> {
+    fst: &'a F,
+    delta: f32,
+    subsets: Vec<Subset<W>>,
+    subset_ids: HashMap<Subset<W>, usize>,
+    successors: Vec<Option<BTreeMap<Label, Successor>>>,
+}
+
+impl<'a, W, F> Disambiguator<'a, W, F>
+where
+    W: WeaklyDivisibleSemiring + WeightQuantize,
+    F: ExpandedFst<W>,
+{
+    fn new(fst: &'a F, delta: f32) -> Self {
+        Self {
+            fst,
+            delta,
+            subsets: vec![],
+            subset_ids: HashMap::new(),
+            successors: vec![],
+        }
+    }
+
+    fn subset_id(&mut self, subset: Subset<W>) -> usize {
+        match self.subset_ids.entry(subset) {
+            Entry::Occupied(e) => *e.get(),
+            Entry::Vacant(e) => {
+                let id = self.subsets.len();
+                self.subsets.push(e.key().clone());
+                self.successors.push(None);
+                e.insert(id);
+                id
+            }
+        }
+    }
+
+    /// The state of the subset with the best final weight, the first one on ties.
+    fn best_final_state(&self, subset_id: usize) -> Result<Option<StateId>> {
+        let mut best: Option<(StateId, W)> = None;
+        for (state, residual) in self.subsets[subset_id].iter() {
+            if let Some(final_weight) = self.fst.final_weight(*state)? {
+                let weight = residual.times(&final_weight)?;
+                match &best {
+                    Some((_, best_weight)) if !natural_less(&weight, best_weight)? => {}
+                    _ => best = Some((*state, weight)),
+                }
+            }
+        }
+        Ok(best.map(|(state, _)| state))
+    }
+
+    /// Subsets reached from the subset by each label, computed on the first call.
+    fn successors(&mut self, subset_id: usize) -> Result<&BTreeMap<Label, Successor>> {
+        if self.successors[subset_id].is_none() {
+            // Best weight into each nextstate with the tr reaching it, the first one on ties.
+            let mut candidates: BTreeMap<Label, Candidates<W>> = BTreeMap::new();
+            for (state, residual) in self.subsets[subset_id].iter() {
+                for (idx, tr) in self.fst.get_trs(*state)?.trs().iter().enumerate() {
+                    if tr.weight.is_zero() {
+                        continue;
+                    }
+                    let weight = residual.times(&tr.weight)?;
+                    let best = candidates.entry(tr.ilabel).or_default().entry(tr.nextstate);
+                    match best {
+                        BTreeEntry::Vacant(e) => {
+                            e.insert((weight, (*state, idx)));
+                        }
+                        BTreeEntry::Occupied(mut e) => {
+                            if natural_less(&weight, &e.get().0)? {
+                                e.insert((weight, (*state, idx)));
+                            }
+                        }
+                    }
+                }
+            }
+
+            let mut successors = BTreeMap::new();
+            for (label, nextstates) in candidates {
+                let mut norm = W::zero();
+                for (weight, _) in nextstates.values() {
+                    norm.plus_assign(weight)?;
+                }
+                let mut subset = Vec::with_capacity(nextstates.len());
+                let mut kept_trs = HashMap::with_capacity(nextstates.len());
+                for (nextstate, (weight, kept_tr)) in nextstates {
+                    let residual = weight
+                        .divide(&norm, DivideType::DivideLeft)?
+                        .quantize(self.delta)?;
+                    subset.push((nextstate, residual));
+                    kept_trs.insert(nextstate, kept_tr);
+                }
+                let subset_id = self.subset_id(subset);
+                successors.insert(
+                    label,
+                    Successor {
+                        subset_id,
+                        kept_trs,
+                    },
+                );
+            }
+            self.successors[subset_id] = Some(successors);
+        }
+        Ok(self.successors[subset_id].as_ref().unwrap())
+    }
+}
+
+fn disambiguate_fsa<W, F1, F2>(ifst: &F1, delta: f32) -> Result<F2>
+where
+    W: WeaklyDivisibleSemiring + WeightQuantize,
+    F1: ExpandedFst<W>,
+    F2: MutableFst<W>,
+{
+    let mut ofst = F2::new();
+    let start = match ifst.start() {
+        Some(start) => start,
+        None => return Ok(ofst),
+    };
+    let mut disambiguator = Disambiguator::new(ifst, delta);
+    let start_subset = disambiguator.subset_id(vec![(start, W::one())]);
+
+    // States of the result : a state of the input FST within a subset.
+    let mut state_ids: HashMap<(StateId, usize), StateId> = HashMap::new();
+    let mut queue = VecDeque::new();
+    let ostart = ofst.add_state();
+    ofst.set_start(ostart)?;
+    state_ids.insert((start, start_subset), ostart);
+    queue.push_back((start, start_subset, ostart));
+
+    while let Some((state, subset_id, ostate)) = queue.pop_front() {
+        if disambiguator.best_final_state(subset_id)? == Some(state) {
+            ofst.set_final(ostate, ifst.final_weight(state)?.unwrap())?;
+        }
+        let successors = disambiguator.successors(subset_id)?;
+        for (idx, tr) in ifst.get_trs(state)?.trs().iter().enumerate() {
+            let successor = match successors.get(&tr.ilabel) {
+                Some(successor) => successor,
+                None => continue,
+            };
+            if successor.kept_trs.get(&tr.nextstate) != Some(&(state, idx)) {
+                continue;
+            }
+            let key = (tr.nextstate, successor.subset_id);
+            let onextstate = match state_ids.entry(key) {
+                Entry::Occupied(e) => *e.get(),
+                Entry::Vacant(e) => {
+                    let onextstate = ofst.add_state();
+                    e.insert(onextstate);
+                    queue.push_back((key.0, key.1, onextstate));
+                    onextstate
+                }
+            };
+            let mut tr = tr.clone();
+            tr.nextstate = onextstate;
+            ofst.add_tr(ostate, tr)?;
+        }
+    }
+    Ok(ofst)
+}
+
+/// Disambiguates an FST : the result is equivalent to the input FST and no two of its
+/// successful paths have the same labels, while it stays non-deterministic, as the
+/// `Disambiguate` of OpenFst. For a transducer, the pairs of input and output labels are
+/// disambiguated.
+///
+/// Among the paths with the same labels, only one of the best paths is kept. The weight must
+/// then have the path property, e.g. `TropicalWeight`, for the ⊕-sum of the paths to be kept.
+/// The epsilon transitions are treated as regular symbols. As the result is built over the
+/// subsets of the determinization, the FST must be determinizable (twins property), otherwise
+/// the algorithm doesn't terminate.
+///
+/// # Example
+/// ```
+/// # use rustfst::algorithms::disambiguate;
+/// # use rustfst::fst_impls::VectorFst;
+/// # use rustfst::fst_traits::{CoreFst, Fst, MutableFst};
+/// # use rustfst::semirings::{Semiring, TropicalWeight};
+/// # use rustfst::{FstPath, Tr};
+/// # use anyhow::Result;
+/// # fn main() -> Result<()> {
+/// let mut fst = VectorFst::<TropicalWeight>::new();
+/// fst.add_states(3);
+/// fst.set_start(0)?;
+/// fst.add_tr(0, Tr::new(1, 1, 1.0, 1))?;
+/// fst.add_tr(0, Tr::new(1, 1, 2.0, 2))?;
+/// fst.set_final(1, TropicalWeight::one())?;
+/// fst.set_final(2, TropicalWeight::one())?;
+///
+/// let disamb: VectorFst<_> = disambiguate(&fst)?;
+/// let paths: Vec<_> = disamb.paths_iter().collect();
+/// assert_eq!(paths, vec![FstPath::new(vec![1], vec![1], TropicalWeight::new(1.0))]);
+/// # Ok(())
+/// # }
+/// ```
+pub fn disambiguate<W, F1, F2>(ifst: &F1) -> Result<F2>
+where
+    W: WeaklyDivisibleSemiring + WeightQuantize,
+    F1: ExpandedFst<W>,
+    F2: MutableFst<W>,
+{
+    W::properties().check(
+        SemiringProperties::LEFT_SEMIRING | SemiringProperties::PATH,
+        "Disambiguate",
+    )?;
+    let mut ofst: F2 = if ifst.properties().contains(FstProperties::ACCEPTOR) {
+        disambiguate_fsa(ifst, KDELTA)?
+    } else {
+        let mut fsa: VectorFst<W> = fst_convert_from_ref(ifst);
+        let encode_table = encode(&mut fsa, EncodeType::EncodeLabels)?;
+        let mut ofst = disambiguate_fsa(&fsa, KDELTA)?;
+        decode(&mut ofst, encode_table)?;
+        ofst
+    };
+    // The states whose subset is completed by another state don't lead to a final state.
+    connect(&mut ofst)?;
+    ofst.set_symts_from_fst(ifst);
+    Ok(ofst)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::fst_traits::{CoreFst, Fst};
+    use crate::semirings::{Semiring, TropicalWeight};
+    use crate::{FstPath, Tr};
+
+    #[test]
+    fn test_disambiguate() -> Result<()> {
+        // 1 2 is read through the states 1 and 2, 1 3 only through the state 2.
+        let mut fst = VectorFst::<TropicalWeight>::new();
+        fst.add_states(4);
+        fst.set_start(0)?;
+        fst.add_tr(0, Tr::new(1, 1, 1.0, 1))?;
+        fst.add_tr(0, Tr::new(1, 1, 2.0, 2))?;
+        fst.add_tr(1, Tr::new(2, 2, 1.0, 3))?;
+        fst.add_tr(2, Tr::new(2, 2, 0.5, 3))?;
+        fst.add_tr(2, Tr::new(3, 3, 0.0, 3))?;
+        fst.set_final(3, TropicalWeight::one())?;
+
+        let disamb: VectorFst<TropicalWeight> = disambiguate(&fst)?;
+        let mut paths: Vec<_> = disamb.paths_iter().collect();
+        paths.sort_by(|a, b| a.ilabels.cmp(&b.ilabels));
+        assert_eq!(
+            paths,
+            vec![
+                FstPath::new(vec![1, 2], vec![1, 2], TropicalWeight::new(2.0)),
+                FstPath::new(vec![1, 3], vec![1, 3], TropicalWeight::new(2.0)),
+            ]
+        );
+        // Both trs on 1 are still needed.
+        assert_eq!(disamb.num_trs(disamb.start().unwrap())?, 2);
+        assert_eq!(disamb.num_states(), 4);
+
+        // The paths of a transducer with other output labels aren't ambiguous.
+        let mut transducer = fst.clone();
+        transducer.add_tr(1, Tr::new(3, 4, 0.0, 3))?;
+        let disamb: VectorFst<TropicalWeight> = disambiguate(&transducer)?;
+        assert_eq!(disamb.paths_iter().count(), 3);
+        Ok(())
+    }
+}
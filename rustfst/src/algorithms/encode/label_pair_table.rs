@@ -0,0 +1,203 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::fs::{read_to_string, File};
+use std::io::{BufWriter, LineWriter, Write};
+use std::path::Path;
+
+use anyhow::{Context, Result};
+
+use crate::fst_traits::MutableFst;
+use crate::semirings::Semiring;
+use crate::{Label, EPS_LABEL};
+
+/// Bijection between the `(ilabel, olabel)` pairs and the labels of a single label space,
+/// to temporarily treat a transducer as an acceptor without going through `encode` and an
+/// `EncodeTable`.
+///
+/// The pair `(EPS_LABEL, EPS_LABEL)` is always encoded as `EPS_LABEL` and the other pairs get
+/// the labels 1, 2, ... in order of insertion. The table can be kept and extended across
+/// several FSTs, and written to and read from a text file with one `label ilabel olabel` line
+/// per pair.
+///
+/// # Example
+/// ```
+/// # use rustfst::algorithms::encode::LabelPairTable;
+/// let mut table = LabelPairTable::new();
+/// let label = table.encode(3, 5);
+/// assert_eq!(table.encode(3, 5), label);
+/// assert_eq!(table.decode(label), Some((3, 5)));
+/// assert_eq!(table.encode(0, 0), 0);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LabelPairTable {
+    /// Pair of the label `i + 1`.
+    pairs: Vec<(Label, Label)>,
+    labels: HashMap<(Label, Label), Label>,
+}
+
+impl LabelPairTable {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the label of the pair, adding it to the table if it isn't there yet.
+    pub fn encode(&mut self, ilabel: Label, olabel: Label) -> Label {
+        if ilabel == EPS_LABEL && olabel == EPS_LABEL {
+            return EPS_LABEL;
+        }
+        let pairs = &mut self.pairs;
+        *self.labels.entry((ilabel, olabel)).or_insert_with(|| {
+            pairs.push((ilabel, olabel));
+            pairs.len() as Label
+        })
+    }
+
+    /// Returns the label of the pair without modifying the table.
+    pub fn get(&self, ilabel: Label, olabel: Label) -> Option<Label> {
+        if ilabel == EPS_LABEL && olabel == EPS_LABEL {
+            return Some(EPS_LABEL);
+        }
+        self.labels.get(&(ilabel, olabel)).cloned()
+    }
+
+    /// Returns the pair encoded as `label`.
+    pub fn decode(&self, label: Label) -> Option<(Label, Label)> {
+        if label == EPS_LABEL {
+            return Some((EPS_LABEL, EPS_LABEL));
+        }
+        self.pairs.get(label as usize - 1).cloned()
+    }
+
+    /// Number of pairs in the table, `(EPS_LABEL, EPS_LABEL)` excluded.
+    pub fn len(&self) -> usize {
+        self.pairs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pairs.is_empty()
+    }
+
+    /// Relabels each tr of the FST with the label of its pair on both sides, adding the new
+    /// pairs to the table. The result is an acceptor.
+    pub fn encode_fst<W: Semiring, F: MutableFst<W>>(&mut self, fst: &mut F) -> Result<()> {
+        for state in fst.states_range() {
+            let mut it_tr = fst.tr_iter_mut(state)?;
+            for idx_tr in 0..it_tr.len() {
+                let tr = unsafe { it_tr.get_unchecked(idx_tr) };
+                let label = self.encode(tr.ilabel, tr.olabel);
+                unsafe { it_tr.set_labels_unchecked(idx_tr, label, label) };
+            }
+        }
+        Ok(())
+    }
+
+    /// Restores the pairs of an FST encoded with `encode_fst`, using the input labels.
+    pub fn decode_fst<W: Semiring, F: MutableFst<W>>(&self, fst: &mut F) -> Result<()> {
+        for state in fst.states_range() {
+            let mut it_tr = fst.tr_iter_mut(state)?;
+            for idx_tr in 0..it_tr.len() {
+                let label = unsafe { it_tr.get_unchecked(idx_tr) }.ilabel;
+                let (ilabel, olabel) = self
+                    .decode(label)
+                    .ok_or_else(|| format_err!("Label {} not in the LabelPairTable", label))?;
+                unsafe { it_tr.set_labels_unchecked(idx_tr, ilabel, olabel) };
+            }
+        }
+        Ok(())
+    }
+
+    pub fn from_text_string(text: &str) -> Result<Self> {
+        let mut table = Self::new();
+        for (idx, line) in text.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let fields = line
+                .split_whitespace()
+                .map(|f| f.parse::<Label>())
+                .collect::<std::result::Result<Vec<_>, _>>()
+                .with_context(|| format!("Invalid label on line {} : {:?}", idx + 1, line))?;
+            if fields.len() != 3 {
+                bail!("Expected 3 labels on line {} : {:?}", idx + 1, line);
+            }
+            let label = table.encode(fields[1], fields[2]);
+            if label != fields[0] {
+                bail!(
+                    "Line {} : expected the label {} for the pair ({}, {}), got {}",
+                    idx + 1,
+                    label,
+                    fields[1],
+                    fields[2],
+                    fields[0]
+                );
+            }
+        }
+        Ok(table)
+    }
+
+    pub fn read_text<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let text = read_to_string(path.as_ref())
+            .with_context(|| format!("Can't open LabelPairTable file : {:?}", path.as_ref()))?;
+        Self::from_text_string(&text)
+    }
+
+    pub fn write_text<P: AsRef<Path>>(&self, path_output: P) -> Result<()> {
+        let buffer = File::create(path_output.as_ref())?;
+        let mut writer = BufWriter::new(LineWriter::new(buffer));
+
+        write!(writer, "{}", self)?;
+
+        Ok(())
+    }
+}
+
+impl fmt::Display for LabelPairTable {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (idx, (ilabel, olabel)) in self.pairs.iter().enumerate() {
+            writeln!(f, "{}\t{}\t{}", idx + 1, ilabel, olabel)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::algorithms::accepts;
+    use crate::fst_impls::VectorFst;
+    use crate::fst_properties::FstProperties;
+    use crate::semirings::TropicalWeight;
+    use crate::utils::acceptor;
+    use crate::{fst, Tr};
+
+    #[test]
+    fn test_label_pair_table() -> Result<()> {
+        let mut fst = VectorFst::<TropicalWeight>::new();
+        fst.add_states(3);
+        fst.set_start(0)?;
+        fst.add_tr(0, Tr::new(1, 2, 1.0, 1))?;
+        fst.add_tr(1, Tr::new(1, 2, 1.0, 2))?;
+        fst.add_tr(1, Tr::new(EPS_LABEL, 3, 1.0, 2))?;
+        fst.set_final(2, 0.0)?;
+        let original = fst.clone();
+
+        let mut table = LabelPairTable::new();
+        table.encode_fst(&mut fst)?;
+        assert_eq!(table.len(), 2);
+        assert!(fst
+            .compute_and_update_properties(FstProperties::ACCEPTOR)?
+            .contains(FstProperties::ACCEPTOR));
+        assert!(accepts(&fst, &[1, 2])?);
+
+        let table = LabelPairTable::from_text_string(&table.to_string())?;
+        assert_eq!(table.get(EPS_LABEL, 3), Some(2));
+        table.decode_fst(&mut fst)?;
+        assert_eq!(fst, original);
+
+        let mut unknown: VectorFst<TropicalWeight> = fst![5];
+        assert!(table.decode_fst(&mut unknown).is_err());
+        assert!(LabelPairTable::from_text_string("2\t1\t1\n").is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,160 @@
+use anyhow::Result;
+
+use crate::algorithms::dfs_visit::dfs_visit;
+use crate::algorithms::tr_filters::EpsilonTrFilter;
+use crate::algorithms::visitors::SccVisitor;
+use crate::fst_traits::{ExpandedFst, MutableFst};
+use crate::semirings::Semiring;
+use crate::{StateId, Tr, Trs, EPS_LABEL};
+
+/// Strongly connected component of the epsilon trs (input and output epsilon) containing a
+/// cycle.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EpsilonCycle {
+    /// States of the component, sorted.
+    pub states: Vec<StateId>,
+    /// Whether an epsilon tr of the component has a weight other than `one`.
+    pub weighted: bool,
+}
+
+fn is_epsilon<W: Semiring>(tr: &Tr<W>) -> bool {
+    tr.ilabel == EPS_LABEL && tr.olabel == EPS_LABEL
+}
+
+/// Returns the epsilon cycles of the FST, grouped by strongly connected component.
+///
+/// In a non-idempotent semiring, e.g. the log semiring, the weights of the paths through an
+/// epsilon cycle sum up without converging, so that `rm_epsilon` and `shortest_distance` on
+/// such an FST loop until the delta is reached or diverge.
+pub fn epsilon_cycles<W: Semiring, F: ExpandedFst<W>>(fst: &F) -> Result<Vec<EpsilonCycle>> {
+    let mut visitor = SccVisitor::new(fst, true, false);
+    dfs_visit(fst, &mut visitor, &EpsilonTrFilter {}, false);
+    let scc = visitor.scc.unwrap_or_default();
+    let num_scc = scc.iter().max().map_or(0, |m| *m as usize + 1);
+
+    let mut components = vec![vec![]; num_scc];
+    for (s, c) in scc.iter().enumerate() {
+        components[*c as usize].push(s as StateId);
+    }
+    let mut cyclic = vec![false; num_scc];
+    let mut weighted = vec![false; num_scc];
+    for state in fst.states_range() {
+        let c = scc[state as usize] as usize;
+        for tr in fst.get_trs(state)?.trs() {
+            if is_epsilon(tr) && scc[tr.nextstate as usize] as usize == c {
+                // Self-loops are the cycles of the single state components.
+                cyclic[c] = true;
+                weighted[c] |= !tr.weight.is_one();
+            }
+        }
+    }
+    Ok(components
+        .into_iter()
+        .enumerate()
+        .filter(|(c, _)| cyclic[*c])
+        .map(|(c, states)| EpsilonCycle {
+            states,
+            weighted: weighted[c],
+        })
+        .collect())
+}
+
+/// Collapses each epsilon cycle whose trs all have a weight of `one` into its smallest state,
+/// which gets the trs and the sum of the final weights of the whole component. The epsilon
+/// trs inside of the component are removed, so that the algorithms on the result converge
+/// in any semiring.
+///
+/// The weights of the paths are preserved but not the number of times a path goes around a
+/// cycle. Returns an error listing the states of the first weighted epsilon cycle, which can't
+/// be collapsed, leaving the FST unchanged.
+pub fn collapse_epsilon_cycles<W: Semiring, F: MutableFst<W>>(fst: &mut F) -> Result<()> {
+    let cycles = epsilon_cycles(fst)?;
+    if let Some(cycle) = cycles.iter().find(|c| c.weighted) {
+        bail!(
+            "Weighted epsilon cycle through the states {:?} : rm_epsilon and shortest_distance may diverge in a non-idempotent semiring",
+            cycle.states
+        );
+    }
+    let mut rep: Vec<StateId> = fst.states_range().collect();
+    let mut removed = vec![];
+    for cycle in cycles.iter() {
+        for s in cycle.states.iter() {
+            rep[*s as usize] = cycle.states[0];
+        }
+        removed.extend_from_slice(&cycle.states[1..]);
+    }
+
+    for state in fst.states_range() {
+        let r = rep[state as usize];
+        let mut trs = fst.pop_trs(state)?;
+        trs.retain(|tr| !is_epsilon(tr) || rep[tr.nextstate as usize] != r);
+        for tr in trs.iter_mut() {
+            tr.nextstate = rep[tr.nextstate as usize];
+        }
+        if r == state {
+            for tr in trs {
+                fst.add_tr(state, tr)?;
+            }
+        } else {
+            for tr in trs {
+                fst.add_tr(r, tr)?;
+            }
+            if let Some(final_weight) = fst.take_final_weight(state)? {
+                let final_weight = match fst.final_weight(r)? {
+                    Some(w) => w.plus(final_weight)?,
+                    None => final_weight,
+                };
+                fst.set_final(r, final_weight)?;
+            }
+        }
+    }
+    if let Some(start) = fst.start() {
+        fst.set_start(rep[start as usize])?;
+    }
+    fst.del_states(removed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::fst_impls::VectorFst;
+    use crate::fst_traits::CoreFst;
+    use crate::semirings::LogWeight;
+
+    #[test]
+    fn test_collapse_epsilon_cycles() -> Result<()> {
+        // 0 -a-> 1 -eps-> 2 -eps-> 1, 2 -b-> 3 (final), 2 final.
+        let mut fst = VectorFst::<LogWeight>::new();
+        fst.add_states(4);
+        fst.set_start(0)?;
+        fst.add_tr(0, Tr::new(1, 1, 0.5, 1))?;
+        fst.add_tr(1, Tr::new(EPS_LABEL, EPS_LABEL, LogWeight::one(), 2))?;
+        fst.add_tr(2, Tr::new(EPS_LABEL, EPS_LABEL, LogWeight::one(), 1))?;
+        fst.add_tr(2, Tr::new(2, 2, 1.0, 3))?;
+        fst.set_final(2, 2.0)?;
+        fst.set_final(3, LogWeight::one())?;
+
+        let cycles = epsilon_cycles(&fst)?;
+        assert_eq!(
+            cycles,
+            vec![EpsilonCycle {
+                states: vec![1, 2],
+                weighted: false
+            }]
+        );
+
+        collapse_epsilon_cycles(&mut fst)?;
+        assert_eq!(fst.num_states(), 3);
+        assert!(epsilon_cycles(&fst)?.is_empty());
+        assert_eq!(fst.final_weight(1)?, Some(LogWeight::new(2.0)));
+        let trs = fst.get_trs(1)?;
+        assert_eq!(trs.trs(), &[Tr::<LogWeight>::new(2, 2, 1.0, 2)]);
+
+        let mut weighted = VectorFst::<LogWeight>::new();
+        let s = weighted.add_state();
+        weighted.add_tr(s, Tr::new(EPS_LABEL, EPS_LABEL, 1.0, s))?;
+        assert!(collapse_epsilon_cycles(&mut weighted).is_err());
+        Ok(())
+    }
+}
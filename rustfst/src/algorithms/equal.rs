@@ -0,0 +1,172 @@
+use anyhow::Result;
+
+use crate::fst_traits::ExpandedFst;
+use crate::semirings::Semiring;
+use crate::{Trs, KDELTA};
+
+/// Configuration of [`equal_with_config`].
+#[derive(Debug, Clone, Copy, PartialOrd, PartialEq)]
+pub struct EqualConfig {
+    delta: f32,
+    compare_symbols: bool,
+}
+
+impl Default for EqualConfig {
+    fn default() -> Self {
+        Self {
+            delta: KDELTA,
+            compare_symbols: true,
+        }
+    }
+}
+
+impl EqualConfig {
+    pub fn new(delta: f32, compare_symbols: bool) -> Self {
+        Self {
+            delta,
+            compare_symbols,
+        }
+    }
+
+    pub fn with_delta(self, delta: f32) -> Self {
+        Self { delta, ..self }
+    }
+
+    /// Whether the symbol tables attached to the FSTs must be equal too.
+    pub fn with_compare_symbols(self, compare_symbols: bool) -> Self {
+        Self {
+            compare_symbols,
+            ..self
+        }
+    }
+}
+
+/// Determines if two FSTs are equal, with the same start state, the same states and the same
+/// trs in the same order, the weights being compared with `KDELTA`. The symbol tables must be
+/// equal too. See [`equal_with_config`].
+pub fn equal<W, F1, F2>(fst_1: &F1, fst_2: &F2) -> Result<bool>
+where
+    W: Semiring,
+    F1: ExpandedFst<W>,
+    F2: ExpandedFst<W>,
+{
+    equal_with_config(fst_1, fst_2, EqualConfig::default())
+}
+
+/// Determines if two FSTs are equal, as `fstequal` of OpenFst : they have the same start
+/// state, the same number of states and each state has the same final weight and the same trs
+/// in the same order, the weights being compared with the `delta` of `config`. Unlike
+/// `isomorphic`, the states aren't renumbered nor the trs reordered.
+///
+/// # Example
+/// ```
+/// # use rustfst::algorithms::{equal, equal_with_config, EqualConfig};
+/// # use rustfst::fst_impls::VectorFst;
+/// # use rustfst::fst_traits::MutableFst;
+/// # use rustfst::semirings::{Semiring, TropicalWeight};
+/// # use rustfst::Tr;
+/// # use anyhow::Result;
+/// # fn main() -> Result<()> {
+/// let mut fst_1 = VectorFst::<TropicalWeight>::new();
+/// fst_1.add_states(2);
+/// fst_1.set_start(0)?;
+/// fst_1.add_tr(0, Tr::new(1, 1, 1.0, 1))?;
+/// fst_1.set_final(1, TropicalWeight::one())?;
+///
+/// let mut fst_2 = fst_1.clone();
+/// fst_2.set_final(1, 0.01)?;
+/// assert!(!equal(&fst_1, &fst_2)?);
+/// assert!(equal_with_config(&fst_1, &fst_2, EqualConfig::default().with_delta(0.1))?);
+/// # Ok(())
+/// # }
+/// ```
+pub fn equal_with_config<W, F1, F2>(fst_1: &F1, fst_2: &F2, config: EqualConfig) -> Result<bool>
+where
+    W: Semiring,
+    F1: ExpandedFst<W>,
+    F2: ExpandedFst<W>,
+{
+    if fst_1.start() != fst_2.start() || fst_1.num_states() != fst_2.num_states() {
+        return Ok(false);
+    }
+    if config.compare_symbols
+        && (fst_1.input_symbols() != fst_2.input_symbols()
+            || fst_1.output_symbols() != fst_2.output_symbols())
+    {
+        return Ok(false);
+    }
+    for state in fst_1.states_range() {
+        let final_equal = match (fst_1.final_weight(state)?, fst_2.final_weight(state)?) {
+            (Some(w1), Some(w2)) => w1.approx_equal(w2, config.delta),
+            (None, None) => true,
+            _ => false,
+        };
+        if !final_equal {
+            return Ok(false);
+        }
+        let trs_1 = fst_1.get_trs(state)?;
+        let trs_2 = fst_2.get_trs(state)?;
+        if trs_1.len() != trs_2.len() {
+            return Ok(false);
+        }
+        for (tr_1, tr_2) in trs_1.trs().iter().zip(trs_2.trs().iter()) {
+            if tr_1.ilabel != tr_2.ilabel
+                || tr_1.olabel != tr_2.olabel
+                || tr_1.nextstate != tr_2.nextstate
+                || !tr_1.weight.approx_equal(&tr_2.weight, config.delta)
+            {
+                return Ok(false);
+            }
+        }
+    }
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::sync::Arc;
+
+    use crate::algorithms::isomorphic;
+    use crate::fst_impls::VectorFst;
+    use crate::fst_traits::{Fst, MutableFst};
+    use crate::semirings::TropicalWeight;
+    use crate::{SymbolTable, Tr};
+
+    #[test]
+    fn test_equal() -> Result<()> {
+        let mut fst_1 = VectorFst::<TropicalWeight>::new();
+        fst_1.add_states(3);
+        fst_1.set_start(0)?;
+        fst_1.add_tr(0, Tr::new(1, 1, 1.0, 1))?;
+        fst_1.add_tr(0, Tr::new(2, 2, 2.0, 2))?;
+        fst_1.set_final(1, TropicalWeight::one())?;
+        fst_1.set_final(2, TropicalWeight::one())?;
+        assert!(equal(&fst_1, &fst_1.clone())?);
+
+        // The same FST with the states 1 and 2 swapped is isomorphic but not equal.
+        let mut fst_2 = VectorFst::<TropicalWeight>::new();
+        fst_2.add_states(3);
+        fst_2.set_start(0)?;
+        fst_2.add_tr(0, Tr::new(1, 1, 1.0, 2))?;
+        fst_2.add_tr(0, Tr::new(2, 2, 2.0, 1))?;
+        fst_2.set_final(1, TropicalWeight::one())?;
+        fst_2.set_final(2, TropicalWeight::one())?;
+        assert!(isomorphic(&fst_1, &fst_2)?);
+        assert!(!equal(&fst_1, &fst_2)?);
+
+        let mut noisy = fst_1.clone();
+        noisy.set_final(2, 0.001)?;
+        assert!(!equal(&fst_1, &noisy)?);
+        let config = EqualConfig::default().with_delta(0.01);
+        assert!(equal_with_config(&fst_1, &noisy, config)?);
+
+        let mut with_symbols = fst_1.clone();
+        with_symbols.set_input_symbols(Arc::new(SymbolTable::new()));
+        assert!(!equal(&fst_1, &with_symbols)?);
+        let config = EqualConfig::default().with_compare_symbols(false);
+        assert!(equal_with_config(&fst_1, &with_symbols, config)?);
+        Ok(())
+    }
+}
@@ -0,0 +1,280 @@
+use std::collections::HashMap;
+
+use anyhow::Result;
+
+use crate::algorithms::{
+    connect, fst_convert_from_ref, push_weights_with_config, shortest_distance_with_config,
+    PushWeightsConfig, ReweightType, ShortestDistanceConfig,
+};
+use crate::fst_impls::VectorFst;
+use crate::fst_properties::FstProperties;
+use crate::fst_traits::{CoreFst, ExpandedFst, MutableFst};
+use crate::semirings::WeaklyDivisibleSemiring;
+use crate::{Label, Tr, Trs, KDELTA};
+
+/// Configuration of [`equivalent_with_config`].
+#[derive(Debug, Clone, Copy, PartialOrd, PartialEq)]
+pub struct EquivalentConfig {
+    delta: f32,
+}
+
+impl Default for EquivalentConfig {
+    fn default() -> Self {
+        Self { delta: KDELTA }
+    }
+}
+
+impl EquivalentConfig {
+    pub fn new(delta: f32) -> Self {
+        Self { delta }
+    }
+
+    pub fn with_delta(self, delta: f32) -> Self {
+        Self { delta }
+    }
+}
+
+/// Classes of the states of both FSTs, merged as the states are found equivalent.
+struct UnionFind(Vec<usize>);
+
+impl UnionFind {
+    fn new(num_elements: usize) -> Self {
+        Self((0..num_elements).collect())
+    }
+
+    fn find(&mut self, mut element: usize) -> usize {
+        while self.0[element] != element {
+            self.0[element] = self.0[self.0[element]];
+            element = self.0[element];
+        }
+        element
+    }
+}
+
+/// Trimmed copy of `fst` with the weights pushed towards the initial state and its total
+/// weight removed, so that two equivalent deterministic acceptors get the same weights on the
+/// same trs, along with this total weight.
+fn pushed_fsa<W, F>(fst: &F, delta: f32) -> Result<(VectorFst<W>, W)>
+where
+    W: WeaklyDivisibleSemiring,
+    F: ExpandedFst<W>,
+{
+    let mut fsa: VectorFst<W> = fst_convert_from_ref(fst);
+    let required =
+        FstProperties::ACCEPTOR | FstProperties::I_DETERMINISTIC | FstProperties::NO_EPSILONS;
+    if !fsa
+        .compute_and_update_properties(required)?
+        .contains(required)
+    {
+        bail!("Equivalent : the FSTs must be epsilon-free deterministic acceptors");
+    }
+    // Pushing towards a start state that is re-entered adds an epsilon tr before it : it is
+    // copied to a new start state that never is, whether the cycles through it are unrolled or
+    // not.
+    if let Some(start) = fsa.start() {
+        let new_start = fsa.add_state();
+        let trs = fsa.get_trs(start)?.trs().to_vec();
+        for tr in trs {
+            fsa.add_tr(new_start, tr)?;
+        }
+        if let Some(final_weight) = fsa.final_weight(start)? {
+            fsa.set_final(new_start, final_weight)?;
+        }
+        fsa.set_start(new_start)?;
+    }
+    connect(&mut fsa)?;
+    let total_weight = match fsa.start() {
+        Some(start) => {
+            shortest_distance_with_config(&fsa, true, ShortestDistanceConfig::new(delta))?
+                .swap_remove(start as usize)
+        }
+        None => W::zero(),
+    };
+    push_weights_with_config(
+        &mut fsa,
+        ReweightType::ReweightToInitial,
+        PushWeightsConfig::default()
+            .with_delta(delta)
+            .with_remove_total_weight(true),
+    )?;
+    Ok((fsa, total_weight))
+}
+
+/// Determines if two epsilon-free deterministic acceptors are equivalent, i.e. accept the same
+/// sequences with the same weights, the weights being compared with `KDELTA`. See
+/// [`equivalent_with_config`].
+pub fn equivalent<W, F1, F2>(fst_1: &F1, fst_2: &F2) -> Result<bool>
+where
+    W: WeaklyDivisibleSemiring,
+    F1: ExpandedFst<W>,
+    F2: ExpandedFst<W>,
+{
+    equivalent_with_config(fst_1, fst_2, EquivalentConfig::default())
+}
+
+/// Determines if two epsilon-free deterministic acceptors are equivalent, i.e. accept the same
+/// sequences with the same weights, as `fstequivalent` of OpenFst. An error is returned if one
+/// of the FSTs isn't an epsilon-free deterministic acceptor : a transducer can first be encoded,
+/// and a non-deterministic FST determinized.
+///
+/// Both FSTs are trimmed and their weights pushed towards the initial state, their total
+/// weights being removed and compared separately. The pairs of states reached by the same
+/// prefixes are then merged with a union-find, and the FSTs are equivalent if all of them have
+/// the same final weights and the same labels and weights on their trs, the weights being
+/// compared with the `delta` of `config`.
+///
+/// # Example
+/// ```
+/// # use rustfst::algorithms::equivalent;
+/// # use rustfst::fst_impls::VectorFst;
+/// # use rustfst::fst_traits::MutableFst;
+/// # use rustfst::semirings::{Semiring, TropicalWeight};
+/// # use rustfst::Tr;
+/// # use anyhow::Result;
+/// # fn main() -> Result<()> {
+/// // 1 2 with the weights on different trs.
+/// let mut fst_1 = VectorFst::<TropicalWeight>::new();
+/// fst_1.add_states(3);
+/// fst_1.set_start(0)?;
+/// fst_1.add_tr(0, Tr::new(1, 1, 1.0, 1))?;
+/// fst_1.add_tr(1, Tr::new(2, 2, 0.0, 2))?;
+/// fst_1.set_final(2, TropicalWeight::one())?;
+///
+/// let mut fst_2 = VectorFst::<TropicalWeight>::new();
+/// fst_2.add_states(3);
+/// fst_2.set_start(0)?;
+/// fst_2.add_tr(0, Tr::new(1, 1, 0.0, 1))?;
+/// fst_2.add_tr(1, Tr::new(2, 2, 0.0, 2))?;
+/// fst_2.set_final(2, 1.0)?;
+/// assert!(equivalent(&fst_1, &fst_2)?);
+/// # Ok(())
+/// # }
+/// ```
+pub fn equivalent_with_config<W, F1, F2>(
+    fst_1: &F1,
+    fst_2: &F2,
+    config: EquivalentConfig,
+) -> Result<bool>
+where
+    W: WeaklyDivisibleSemiring,
+    F1: ExpandedFst<W>,
+    F2: ExpandedFst<W>,
+{
+    let (fsa_1, total_weight_1) = pushed_fsa(fst_1, config.delta)?;
+    let (fsa_2, total_weight_2) = pushed_fsa(fst_2, config.delta)?;
+    let (start_1, start_2) = match (fsa_1.start(), fsa_2.start()) {
+        (Some(start_1), Some(start_2)) => (start_1, start_2),
+        (None, None) => return Ok(true),
+        _ => return Ok(false),
+    };
+    if !total_weight_1.approx_equal(&total_weight_2, config.delta) {
+        return Ok(false);
+    }
+
+    // The states of `fsa_2` follow those of `fsa_1` in the union-find.
+    let offset = fsa_1.num_states();
+    let mut classes = UnionFind::new(offset + fsa_2.num_states());
+    classes.0[start_1 as usize] = offset + start_2 as usize;
+    let mut queue = vec![(start_1, start_2)];
+    while let Some((state_1, state_2)) = queue.pop() {
+        let final_equal = match (fsa_1.final_weight(state_1)?, fsa_2.final_weight(state_2)?) {
+            (Some(w1), Some(w2)) => w1.approx_equal(w2, config.delta),
+            (None, None) => true,
+            _ => false,
+        };
+        let trs_1 = fsa_1.get_trs(state_1)?;
+        let trs_2 = fsa_2.get_trs(state_2)?;
+        if !final_equal || trs_1.len() != trs_2.len() {
+            return Ok(false);
+        }
+        let trs_2: HashMap<Label, &Tr<W>> = trs_2.trs().iter().map(|tr| (tr.ilabel, tr)).collect();
+        for tr_1 in trs_1.trs() {
+            let tr_2 = match trs_2.get(&tr_1.ilabel) {
+                Some(tr_2) if tr_1.weight.approx_equal(&tr_2.weight, config.delta) => tr_2,
+                _ => return Ok(false),
+            };
+            let class_1 = classes.find(tr_1.nextstate as usize);
+            let class_2 = classes.find(offset + tr_2.nextstate as usize);
+            if class_1 != class_2 {
+                classes.0[class_1] = class_2;
+                queue.push((tr_1.nextstate, tr_2.nextstate));
+            }
+        }
+    }
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::algorithms::determinize::determinize;
+    use crate::semirings::{Semiring, TropicalWeight};
+
+    #[test]
+    fn test_equivalent() -> Result<()> {
+        // (1 2)* with the weight on 1 or on 2, and unrolled once in the second FST.
+        let mut fst_1 = VectorFst::<TropicalWeight>::new();
+        fst_1.add_states(2);
+        fst_1.set_start(0)?;
+        fst_1.add_tr(0, Tr::new(1, 1, 1.0, 1))?;
+        fst_1.add_tr(1, Tr::new(2, 2, 0.0, 0))?;
+        fst_1.set_final(0, TropicalWeight::one())?;
+
+        let mut fst_2 = VectorFst::<TropicalWeight>::new();
+        fst_2.add_states(4);
+        fst_2.set_start(0)?;
+        fst_2.add_tr(0, Tr::new(1, 1, 0.0, 1))?;
+        fst_2.add_tr(1, Tr::new(2, 2, 1.0, 2))?;
+        fst_2.add_tr(2, Tr::new(1, 1, 0.5, 3))?;
+        fst_2.add_tr(3, Tr::new(2, 2, 0.5, 0))?;
+        fst_2.set_final(0, TropicalWeight::one())?;
+        fst_2.set_final(2, TropicalWeight::one())?;
+        assert!(equivalent(&fst_1, &fst_2)?);
+
+        let mut heavier = fst_2.clone();
+        heavier.set_final(2, 0.001)?;
+        assert!(!equivalent(&fst_1, &heavier)?);
+        let config = EquivalentConfig::default().with_delta(0.01);
+        assert!(equivalent_with_config(&fst_1, &heavier, config)?);
+
+        let mut longer = fst_2.clone();
+        longer.add_tr(2, Tr::new(3, 3, 0.0, 0))?;
+        assert!(!equivalent(&fst_1, &longer)?);
+
+        // A non-deterministic acceptor must first be determinized.
+        let mut non_det = fst_1.clone();
+        non_det.add_tr(0, Tr::new(1, 1, 2.0, 1))?;
+        assert!(equivalent(&fst_1, &non_det).is_err());
+        let det: VectorFst<TropicalWeight> = determinize(&non_det)?;
+        assert!(equivalent(&fst_1, &det)?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_equivalent_start_reentered() -> Result<()> {
+        // 1* with a weight of 1 per label : the start state is re-entered by its self-loop.
+        let mut fst_1 = VectorFst::<TropicalWeight>::new();
+        fst_1.add_states(1);
+        fst_1.set_start(0)?;
+        fst_1.add_tr(0, Tr::new(1, 1, 1.0, 0))?;
+        fst_1.set_final(0, TropicalWeight::one())?;
+
+        // Unrolled once, the start state being never re-entered.
+        let mut fst_2 = VectorFst::<TropicalWeight>::new();
+        fst_2.add_states(2);
+        fst_2.set_start(0)?;
+        fst_2.add_tr(0, Tr::new(1, 1, 1.0, 1))?;
+        fst_2.add_tr(1, Tr::new(1, 1, 1.0, 1))?;
+        fst_2.set_final(0, TropicalWeight::one())?;
+        fst_2.set_final(1, TropicalWeight::one())?;
+        assert!(equivalent(&fst_1, &fst_2)?);
+        assert!(equivalent(&fst_2, &fst_1)?);
+
+        // Same trs once pushed, but a different total weight.
+        let mut heavier = fst_1.clone();
+        heavier.set_final(0, 0.5)?;
+        assert!(!equivalent(&heavier, &fst_2)?);
+        Ok(())
+    }
+}
@@ -5,9 +5,9 @@ use crate::{Label, KDELTA};
 bitflags! {
     /// What kind of weight should be factored ? Tr weight ? Final weights ?
     pub struct FactorWeightType: u32 {
-        /// Factor weights located on the Trs.
-        const FACTOR_FINAL_WEIGHTS = 0b01;
         /// Factor weights located in the final states.
+        const FACTOR_FINAL_WEIGHTS = 0b01;
+        /// Factor weights located on the Trs.
         const FACTOR_ARC_WEIGHTS = 0b10;
     }
 }
@@ -44,7 +44,6 @@ pub struct FactorWeightOptions {
 }
 
 impl FactorWeightOptions {
-    #[allow(unused)]
     pub fn new(mode: FactorWeightType) -> FactorWeightOptions {
         FactorWeightOptions {
             delta: KDELTA,
@@ -55,4 +54,36 @@ impl FactorWeightOptions {
             increment_final_olabel: false,
         }
     }
+
+    pub fn with_delta(self, delta: f32) -> Self {
+        Self { delta, ..self }
+    }
+
+    pub fn with_final_ilabel(self, final_ilabel: Label) -> Self {
+        Self {
+            final_ilabel,
+            ..self
+        }
+    }
+
+    pub fn with_final_olabel(self, final_olabel: Label) -> Self {
+        Self {
+            final_olabel,
+            ..self
+        }
+    }
+
+    pub fn with_increment_final_ilabel(self, increment_final_ilabel: bool) -> Self {
+        Self {
+            increment_final_ilabel,
+            ..self
+        }
+    }
+
+    pub fn with_increment_final_olabel(self, increment_final_olabel: bool) -> Self {
+        Self {
+            increment_final_olabel,
+            ..self
+        }
+    }
 }
@@ -162,6 +162,13 @@ where
     pub fn compute<F2: MutableFst<W> + AllocableFst<W>>(&self) -> Result<F2> {
         self.0.compute()
     }
+
+    /// States expanded so far that stand for a state of the input FST, i.e. all the states but
+    /// those added to factor the weights, as the pairs of their id and of the id of the state
+    /// of the input FST.
+    pub(crate) fn input_states(&self) -> Vec<(StateId, StateId)> {
+        self.0.op.input_states()
+    }
 }
 
 #[cfg(test)]
@@ -166,4 +166,8 @@ where
             .mode
             .intersects(FactorWeightType::FACTOR_FINAL_WEIGHTS)
     }
+
+    pub(crate) fn input_states(&self) -> Vec<(StateId, StateId)> {
+        self.fw_state_table.input_states()
+    }
 }
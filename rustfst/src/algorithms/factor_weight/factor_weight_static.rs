@@ -11,6 +11,40 @@ use crate::semirings::WeightQuantize;
 /// States and transitions will be added as necessary. The algorithm is a
 /// generalization to arbitrary weights of the second step of the input
 /// epsilon-normalization algorithm.
+///
+/// For instance, the gallic weights left on the final states by the determinization of a
+/// transducer are expanded with `GallicFactor`, and the string weights are expanded into
+/// transitions of at most one label each with `StringFactorLeft`.
+///
+/// # Example
+/// ```
+/// # use rustfst::algorithms::factor_weight::factor_iterators::StringFactorLeft;
+/// # use rustfst::algorithms::factor_weight::{factor_weight, FactorWeightOptions, FactorWeightType};
+/// # use rustfst::fst_impls::VectorFst;
+/// # use rustfst::fst_traits::{ExpandedFst, Fst, MutableFst};
+/// # use rustfst::semirings::{Semiring, StringWeightLeft};
+/// # use rustfst::Tr;
+/// # use anyhow::Result;
+/// # fn main() -> Result<()> {
+/// let mut fst = VectorFst::<StringWeightLeft>::new();
+/// fst.add_states(2);
+/// fst.set_start(0)?;
+/// fst.add_tr(0, Tr::new(1, 1, StringWeightLeft::from(vec![1, 2, 3]), 1))?;
+/// fst.set_final(1, StringWeightLeft::one())?;
+///
+/// let opts = FactorWeightOptions::new(
+///     FactorWeightType::FACTOR_ARC_WEIGHTS | FactorWeightType::FACTOR_FINAL_WEIGHTS,
+/// );
+/// let factored: VectorFst<_> =
+///     factor_weight::<_, VectorFst<_>, _, _, StringFactorLeft>(&fst, opts)?;
+///
+/// // The labels 2 and 3 are moved to two epsilon transitions leaving the final state.
+/// assert_eq!(factored.num_states(), 4);
+/// let path = factored.paths_iter().next().unwrap();
+/// assert_eq!(path.weight, StringWeightLeft::from(vec![1, 2, 3]));
+/// # Ok(())
+/// # }
+/// ```
 pub fn factor_weight<W, F1, B, F2, FI>(fst_in: B, opts: FactorWeightOptions) -> Result<F2>
 where
     F1: Fst<W>,
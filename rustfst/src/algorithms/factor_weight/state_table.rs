@@ -60,6 +60,18 @@ impl<W: Semiring> FactorWeightStateTable<W> {
         inner_state_table.find_tuple_bimap(tuple_id)
     }
 
+    /// States standing for a state of the input FST with a weight of one, as the pairs of
+    /// their id and of the id of the state of the input FST.
+    pub fn input_states(&self) -> Vec<(StateId, StateId)> {
+        let inner_state_table = self.inner_state_table.lock().unwrap();
+        inner_state_table
+            .bimap
+            .iter()
+            .filter(|(_, elt)| elt.weight.is_one())
+            .filter_map(|(s, elt)| elt.state.map(|old_state| (*s, old_state)))
+            .collect()
+    }
+
     pub fn find_state(&self, elt: &Element<W>) -> StateId {
         let mut inner_state_table = self.inner_state_table.lock().unwrap();
         if !self.factor_tr_weights && elt.weight.is_one() && elt.state.is_some() {
@@ -0,0 +1,395 @@
+use std::cmp::Ordering;
+use std::hash::{Hash, Hasher};
+use std::sync::Arc;
+
+use anyhow::Result;
+
+use crate::algorithms::canonicalize::bfs_state_order;
+use crate::fst_traits::ExpandedFst;
+use crate::semirings::{Semiring, SerializableSemiring, WeightQuantize};
+use crate::{StateId, SymbolTable, Tr, Trs, KDELTA};
+
+const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0100_0000_01b3;
+const FNV_128_OFFSET_BASIS: u128 = 0x6c62_272e_07bb_0142_62b8_2175_6295_c58d;
+const FNV_128_PRIME: u128 = 0x0000_0000_0100_0000_0000_0000_0000_013b;
+
+/// Writes the integers in little endian, and the `usize` as `u64`, instead of the native
+/// encoding of the default implementations, so that the hashes are the same on every platform.
+macro_rules! write_integers_le {
+    () => {
+        fn write_u16(&mut self, i: u16) {
+            self.write(&i.to_le_bytes())
+        }
+
+        fn write_u32(&mut self, i: u32) {
+            self.write(&i.to_le_bytes())
+        }
+
+        fn write_u64(&mut self, i: u64) {
+            self.write(&i.to_le_bytes())
+        }
+
+        fn write_u128(&mut self, i: u128) {
+            self.write(&i.to_le_bytes())
+        }
+
+        fn write_usize(&mut self, i: usize) {
+            self.write_u64(i as u64)
+        }
+    };
+}
+
+/// 64 bits FNV-1a hasher. Contrary to the `DefaultHasher` of the standard library, its output
+/// is guaranteed to be the same across Rust versions, runs and platforms.
+struct FnvHasher(u64);
+
+impl Hasher for FnvHasher {
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 ^= u64::from(*byte);
+            self.0 = self.0.wrapping_mul(FNV_PRIME);
+        }
+    }
+
+    write_integers_le!();
+}
+
+/// 128 bits FNV-1a hasher, used when the fingerprint serves as a deduplication key and 64 bits
+/// would make collisions likely.
+struct Fnv128Hasher(u128);
+
+impl Fnv128Hasher {
+    fn finish_128(&self) -> u128 {
+        self.0
+    }
+}
+
+impl Hasher for Fnv128Hasher {
+    fn finish(&self) -> u64 {
+        self.0 as u64
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 ^= u128::from(*byte);
+            self.0 = self.0.wrapping_mul(FNV_128_PRIME);
+        }
+    }
+
+    write_integers_le!();
+}
+
+/// Computes a hash of the content of the FST: start state, final weights and transitions,
+/// in the order in which they are stored, and symbol tables.
+///
+/// The fingerprint only depends on the content of the FST, encoded in little endian with the
+/// labels and states ids as `u64` and the weights through their binary serialization, so it
+/// is preserved by a serialization followed by a deserialization, is the same on every
+/// platform, and can be used as a key to cache the
+/// results of algorithms. Two FSTs that are equal but store the transitions of a state in a
+/// different order have different fingerprints. Being a 64 bits hash, different FSTs may have
+/// the same fingerprint : a cache must compare the FSTs when the fingerprints match.
+///
+/// # Example
+/// ```
+/// # #[macro_use] extern crate rustfst;
+/// # use rustfst::utils::{acceptor, transducer};
+/// # use rustfst::algorithms::fingerprint;
+/// # use rustfst::fst_impls::{ConstFst, VectorFst};
+/// # use rustfst::semirings::{Semiring, TropicalWeight};
+/// # use anyhow::Result;
+/// # fn main() -> Result<()> {
+/// let fst : VectorFst<TropicalWeight> = fst![1, 2 => 3, 4; 0.5];
+/// let const_fst : ConstFst<TropicalWeight> = fst.clone().into();
+/// assert_eq!(fingerprint(&fst)?, fingerprint(&const_fst)?);
+///
+/// let other : VectorFst<TropicalWeight> = fst![1, 2 => 3, 4; 0.6];
+/// assert_ne!(fingerprint(&fst)?, fingerprint(&other)?);
+/// # Ok(())
+/// # }
+/// ```
+pub fn fingerprint<W: SerializableSemiring, F: ExpandedFst<W>>(fst: &F) -> Result<u64> {
+    let mut hasher = FnvHasher(FNV_OFFSET_BASIS);
+    match fst.start() {
+        None => 0u8.hash(&mut hasher),
+        Some(start) => {
+            1u8.hash(&mut hasher);
+            (start as u64).hash(&mut hasher);
+        }
+    }
+    (fst.num_states() as u64).hash(&mut hasher);
+    for state in fst.states_range() {
+        match fst.final_weight(state)? {
+            None => 0u8.hash(&mut hasher),
+            Some(final_weight) => {
+                1u8.hash(&mut hasher);
+                hash_weight(&final_weight, &mut hasher)?;
+            }
+        }
+        let trs = fst.get_trs(state)?;
+        (trs.len() as u64).hash(&mut hasher);
+        for tr in trs.trs() {
+            (tr.ilabel as u64).hash(&mut hasher);
+            (tr.olabel as u64).hash(&mut hasher);
+            hash_weight(&tr.weight, &mut hasher)?;
+            (tr.nextstate as u64).hash(&mut hasher);
+        }
+    }
+    hash_symt(fst.input_symbols(), &mut hasher);
+    hash_symt(fst.output_symbols(), &mut hasher);
+    Ok(hasher.finish())
+}
+
+/// Configuration for [`content_hash_with_config`].
+#[derive(Clone, Copy, Debug, PartialOrd, PartialEq)]
+pub struct ContentHashConfig {
+    /// Quantization delta applied to the weights before hashing them.
+    pub delta: f32,
+}
+
+impl Default for ContentHashConfig {
+    fn default() -> Self {
+        Self { delta: KDELTA }
+    }
+}
+
+impl ContentHashConfig {
+    pub fn new(delta: f32) -> Self {
+        Self { delta }
+    }
+
+    pub fn with_delta(self, delta: f32) -> Self {
+        Self { delta }
+    }
+}
+
+fn compare_trs<W: Semiring>(tr_1: &Tr<W>, tr_2: &Tr<W>) -> Ordering {
+    tr_1.ilabel
+        .cmp(&tr_2.ilabel)
+        .then(tr_1.olabel.cmp(&tr_2.olabel))
+        .then(
+            tr_1.weight
+                .partial_cmp(&tr_2.weight)
+                .unwrap_or(Ordering::Equal),
+        )
+}
+
+fn hash_symt<H: Hasher>(symt: Option<&Arc<SymbolTable>>, hasher: &mut H) {
+    match symt {
+        None => 0u8.hash(hasher),
+        Some(symt) => {
+            1u8.hash(hasher);
+            (symt.len() as u64).hash(hasher);
+            for (label, symbol) in symt.iter() {
+                (label as u64).hash(hasher);
+                symbol.hash(hasher);
+            }
+        }
+    }
+}
+
+/// Hashes the binary serialization of the weight, which is in little endian, instead of its
+/// `Hash` implementation which writes the labels of the string weights in the native encoding.
+fn hash_weight<W: SerializableSemiring, H: Hasher>(weight: &W, hasher: &mut H) -> Result<()> {
+    let mut bytes = vec![];
+    weight.write_binary(&mut bytes)?;
+    (bytes.len() as u64).hash(hasher);
+    hasher.write(&bytes);
+    Ok(())
+}
+
+/// Computes a 128 bits hash of the content of the FST which doesn't depend on the numbering of
+/// the states nor on the order of the transitions: the states are renumbered in the BFS order
+/// from the start state, the transitions of each state being visited sorted by input label,
+/// output label and weight. The weights are quantized and the symbol tables are hashed as well.
+///
+/// It is meant to be used as a deduplication or build cache key. The integers are hashed in
+/// little endian and the weights through their binary serialization, so the hash is the same on
+/// every platform. The states not accessible from the start state are numbered after the
+/// accessible ones, in their original order. The renumbering is not canonical when a state has
+/// several transitions with the same labels and weights towards different states : isomorphic
+/// FSTs may then have different hashes.
+///
+/// # Example
+/// ```
+/// # #[macro_use] extern crate rustfst;
+/// # use rustfst::utils::transducer;
+/// # use rustfst::algorithms::{content_hash, state_sort};
+/// # use rustfst::fst_impls::VectorFst;
+/// # use rustfst::semirings::{Semiring, TropicalWeight};
+/// # use anyhow::Result;
+/// # fn main() -> Result<()> {
+/// let fst : VectorFst<TropicalWeight> = fst![1, 2 => 3, 4; 0.5];
+/// let mut renumbered = fst.clone();
+/// state_sort(&mut renumbered, &[2, 0, 1])?;
+/// assert_eq!(content_hash(&fst)?, content_hash(&renumbered)?);
+/// # Ok(())
+/// # }
+/// ```
+pub fn content_hash<W, F>(fst: &F) -> Result<u128>
+where
+    W: WeightQuantize + SerializableSemiring,
+    F: ExpandedFst<W>,
+{
+    content_hash_with_config(fst, ContentHashConfig::default())
+}
+
+/// Computes a 128 bits hash of the content of the FST with the given configuration. See
+/// [`content_hash`].
+pub fn content_hash_with_config<W, F>(fst: &F, config: ContentHashConfig) -> Result<u128>
+where
+    W: WeightQuantize + SerializableSemiring,
+    F: ExpandedFst<W>,
+{
+    let num_states = fst.num_states();
+
+    let mut sorted_trs = Vec::with_capacity(num_states);
+    for state in fst.states_range() {
+        let mut trs = fst
+            .get_trs(state)?
+            .trs()
+            .iter()
+            .map(|tr| {
+                let mut tr = tr.clone();
+                tr.weight.quantize_assign(config.delta)?;
+                Ok(tr)
+            })
+            .collect::<Result<Vec<_>>>()?;
+        trs.sort_by(compare_trs);
+        sorted_trs.push(trs);
+    }
+
+    let new_ids = bfs_state_order(fst.start(), &sorted_trs);
+    let mut order = vec![0; num_states];
+    for (state, new_id) in new_ids.iter().enumerate() {
+        order[*new_id as usize] = state as StateId;
+    }
+
+    let mut hasher = Fnv128Hasher(FNV_128_OFFSET_BASIS);
+    fst.start().is_some().hash(&mut hasher);
+    (num_states as u64).hash(&mut hasher);
+    for state in order {
+        match fst.final_weight(state)? {
+            None => 0u8.hash(&mut hasher),
+            Some(final_weight) => {
+                1u8.hash(&mut hasher);
+                hash_weight(&final_weight.quantize(config.delta)?, &mut hasher)?;
+            }
+        }
+        let mut trs: Vec<_> = sorted_trs[state as usize]
+            .iter()
+            .map(|tr| (tr, new_ids[tr.nextstate as usize]))
+            .collect();
+        trs.sort_by(|(tr_1, n_1), (tr_2, n_2)| compare_trs(tr_1, tr_2).then(n_1.cmp(n_2)));
+        (trs.len() as u64).hash(&mut hasher);
+        for (tr, nextstate) in trs {
+            (tr.ilabel as u64).hash(&mut hasher);
+            (tr.olabel as u64).hash(&mut hasher);
+            hash_weight(&tr.weight, &mut hasher)?;
+            (nextstate as u64).hash(&mut hasher);
+        }
+    }
+    hash_symt(fst.input_symbols(), &mut hasher);
+    hash_symt(fst.output_symbols(), &mut hasher);
+    Ok(hasher.finish_128())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::fst_impls::VectorFst;
+    use crate::fst_traits::{Fst, MutableFst};
+    use crate::semirings::{StringWeightLeft, TropicalWeight};
+
+    #[test]
+    fn test_content_hash_independent_of_numbering() -> Result<()> {
+        let mut fst_1 = VectorFst::<TropicalWeight>::new();
+        fst_1.add_states(3);
+        fst_1.set_start(0)?;
+        fst_1.add_tr(0, Tr::new(2, 2, 1.0, 2))?;
+        fst_1.add_tr(0, Tr::new(1, 1, 1.0, 1))?;
+        fst_1.set_final(1, 0.5)?;
+        fst_1.set_final(2, 0.0)?;
+
+        // Same FST with the states 1 and 2 swapped and the transitions reordered.
+        let mut fst_2 = VectorFst::<TropicalWeight>::new();
+        fst_2.add_states(3);
+        fst_2.set_start(0)?;
+        fst_2.add_tr(0, Tr::new(1, 1, 1.0, 2))?;
+        fst_2.add_tr(0, Tr::new(2, 2, 1.0001, 1))?;
+        fst_2.set_final(2, 0.5)?;
+        fst_2.set_final(1, 0.0)?;
+
+        assert_eq!(content_hash(&fst_1)?, content_hash(&fst_2)?);
+
+        fst_2.set_final(1, 0.2)?;
+        assert_ne!(content_hash(&fst_1)?, content_hash(&fst_2)?);
+
+        let mut symt = SymbolTable::new();
+        symt.add_symbol("a");
+        fst_1.set_input_symbols(Arc::new(symt));
+        fst_2.set_final(1, 0.0)?;
+        assert_ne!(content_hash(&fst_1)?, content_hash(&fst_2)?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_content_hash_is_stable() -> Result<()> {
+        let mut fst = VectorFst::<TropicalWeight>::new();
+        fst.add_states(2);
+        fst.set_start(0)?;
+        fst.add_tr(0, Tr::new(1, 2, 0.5, 1))?;
+        fst.set_final(1, 0.0)?;
+
+        // Computed from the little endian encoding, it must not change across platforms
+        // nor releases.
+        assert_eq!(
+            content_hash(&fst)?,
+            0x3618_9e5d_80c8_18da_297e_4d15_0b9c_bc77
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_fingerprint_string_weight_is_stable() -> Result<()> {
+        let mut fst = VectorFst::<StringWeightLeft>::new();
+        fst.add_states(2);
+        fst.set_start(0)?;
+        fst.add_tr(0, Tr::new(1, 2, StringWeightLeft::from(vec![3, 4]), 1))?;
+        fst.set_final(1, StringWeightLeft::from(vec![5]))?;
+
+        // The labels of the string weights are hashed in little endian as well, the
+        // fingerprint must not change across platforms nor releases.
+        assert_eq!(fingerprint(&fst)?, 0xd512_a2e7_716b_61a7);
+        Ok(())
+    }
+
+    #[test]
+    fn test_fingerprint_symbol_tables_and_encoding() -> Result<()> {
+        let mut fst = VectorFst::<TropicalWeight>::new();
+        fst.add_states(2);
+        fst.set_start(0)?;
+        fst.add_tr(0, Tr::new(1, 2, 1.0, 1))?;
+        fst.set_final(1, 0.5)?;
+
+        let mut with_symt = fst.clone();
+        let mut symt = SymbolTable::new();
+        symt.add_symbol("a");
+        with_symt.set_input_symbols(Arc::new(symt));
+        assert_ne!(fingerprint(&fst)?, fingerprint(&with_symt)?);
+
+        // The integers are encoded in little endian whatever the platform.
+        let mut hasher = FnvHasher(FNV_OFFSET_BASIS);
+        hasher.write_u32(0x0102_0304);
+        let mut expected = FnvHasher(FNV_OFFSET_BASIS);
+        expected.write(&[4, 3, 2, 1]);
+        assert_eq!(hasher.finish(), expected.finish());
+        Ok(())
+    }
+}
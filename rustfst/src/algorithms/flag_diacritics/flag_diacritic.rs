@@ -0,0 +1,93 @@
+/// Operator of a flag diacritic, as used by foma, HFST and Xerox tools.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FlagDiacriticOp {
+    /// `@P.F.V@` : sets the feature `F` to `V`.
+    Positive,
+    /// `@N.F.V@` : sets the feature `F` to anything but `V`.
+    Negative,
+    /// `@R.F.V@` : requires `F` to be set to `V`. `@R.F@` requires `F` to be set.
+    Require,
+    /// `@D.F.V@` : disallows `F` being set to `V`. `@D.F@` disallows `F` being set.
+    Disallow,
+    /// `@C.F@` : clears the feature `F`.
+    Clear,
+    /// `@U.F.V@` : sets `F` to `V` if it is compatible with its current value, fails otherwise.
+    Unify,
+}
+
+/// Flag diacritic parsed from a symbol such as `@U.CASE.NOM@`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct FlagDiacritic {
+    pub op: FlagDiacriticOp,
+    pub feature: String,
+    pub value: Option<String>,
+}
+
+impl FlagDiacritic {
+    /// Parses a symbol, returning `None` if it isn't a well-formed flag diacritic.
+    ///
+    /// # Example
+    /// ```
+    /// # use rustfst::algorithms::flag_diacritics::{FlagDiacritic, FlagDiacriticOp};
+    /// let flag = FlagDiacritic::parse("@U.CASE.NOM@").unwrap();
+    /// assert_eq!(flag.op, FlagDiacriticOp::Unify);
+    /// assert_eq!(flag.feature, "CASE");
+    /// assert_eq!(flag.value, Some("NOM".to_string()));
+    ///
+    /// assert!(FlagDiacritic::parse("@C.CASE@").is_some());
+    /// assert!(FlagDiacritic::parse("@P.CASE@").is_none());
+    /// assert!(FlagDiacritic::parse("cat").is_none());
+    /// ```
+    pub fn parse(symbol: &str) -> Option<Self> {
+        if symbol.len() < 5 || !symbol.starts_with('@') || !symbol.ends_with('@') {
+            return None;
+        }
+        let parts: Vec<&str> = symbol[1..symbol.len() - 1].split('.').collect();
+        if parts.len() < 2 || parts.len() > 3 || parts.iter().any(|p| p.is_empty()) {
+            return None;
+        }
+        let op = match parts[0] {
+            "P" => FlagDiacriticOp::Positive,
+            "N" => FlagDiacriticOp::Negative,
+            "R" => FlagDiacriticOp::Require,
+            "D" => FlagDiacriticOp::Disallow,
+            "C" => FlagDiacriticOp::Clear,
+            "U" => FlagDiacriticOp::Unify,
+            _ => return None,
+        };
+        let value = parts.get(2).map(|v| v.to_string());
+        let value_allowed = match op {
+            FlagDiacriticOp::Positive | FlagDiacriticOp::Negative | FlagDiacriticOp::Unify => {
+                value.is_some()
+            }
+            FlagDiacriticOp::Clear => value.is_none(),
+            FlagDiacriticOp::Require | FlagDiacriticOp::Disallow => true,
+        };
+        if !value_allowed {
+            return None;
+        }
+        Some(Self {
+            op,
+            feature: parts[1].to_string(),
+            value,
+        })
+    }
+}
+
+/// Value of a feature along a path.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub(super) enum FeatureValue {
+    Unset,
+    Positive(usize),
+    Negative(usize),
+}
+
+/// Flag diacritic whose feature and value have been interned.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(super) struct CompiledFlag {
+    pub(super) op: FlagDiacriticOp,
+    pub(super) feature: usize,
+    pub(super) value: Option<usize>,
+}
+
+pub(super) type FeatureValues = Vec<FeatureValue>;
@@ -0,0 +1,195 @@
+use std::borrow::Borrow;
+use std::fmt::Debug;
+use std::sync::Arc;
+
+use anyhow::Result;
+
+use crate::algorithms::flag_diacritics::flag_diacritics_op::FlagDiacriticsOp;
+use crate::algorithms::flag_diacritics::FlagDiacritics;
+use crate::algorithms::lazy::{LazyFst, SimpleHashMapCache};
+use crate::fst_properties::FstProperties;
+use crate::fst_traits::{AllocableFst, CoreFst, Fst, FstIterator, MutableFst, StateIterator};
+use crate::semirings::Semiring;
+use crate::{StateId, SymbolTable, TrsVec};
+
+type InnerLazyFst<W, F, B> = LazyFst<W, FlagDiacriticsOp<W, F, B>, SimpleHashMapCache<W>>;
+
+/// Enforces the flag diacritics of a transducer, as `enforce_flag_diacritics` does. This
+/// version is a Delayed FST : the values of the features reaching a state are only explored
+/// when its transitions or its final weight are requested.
+///
+/// # Example
+/// ```
+/// # use rustfst::algorithms::flag_diacritics::{FlagDiacritics, FlagDiacriticsFst};
+/// # use rustfst::fst_impls::VectorFst;
+/// # use rustfst::fst_traits::{CoreFst, MutableFst};
+/// # use rustfst::semirings::{Semiring, TropicalWeight};
+/// # use rustfst::{SymbolTable, Tr, Trs, EPS_LABEL};
+/// # use anyhow::Result;
+/// # fn main() -> Result<()> {
+/// let mut symt = SymbolTable::new();
+/// let a = symt.add_symbol("a");
+/// let set_a = symt.add_symbol("@P.X.A@");
+/// let require_b = symt.add_symbol("@R.X.B@");
+///
+/// // a @P.X.A@ (@R.X.B@ a)? : the optional part can never be read.
+/// let mut fst = VectorFst::<TropicalWeight>::new();
+/// fst.add_states(4);
+/// fst.set_start(0)?;
+/// fst.add_tr(0, Tr::new(a, a, 0.0, 1))?;
+/// fst.add_tr(1, Tr::new(set_a, set_a, 0.0, 2))?;
+/// fst.add_tr(2, Tr::new(require_b, require_b, 0.0, 3))?;
+/// fst.add_tr(3, Tr::new(a, a, 0.0, 2))?;
+/// fst.set_final(2, TropicalWeight::one())?;
+///
+/// let flags = FlagDiacritics::from_symbol_table(&symt);
+/// let lazy_fst = FlagDiacriticsFst::<_, VectorFst<_>, _>::new(fst, flags)?;
+/// let start = lazy_fst.start().unwrap();
+/// let s1 = lazy_fst.get_trs(start)?.trs()[0].nextstate;
+/// let trs = lazy_fst.get_trs(s1)?;
+/// assert_eq!((trs.trs()[0].ilabel, trs.trs()[0].olabel), (EPS_LABEL, EPS_LABEL));
+/// let s2 = trs.trs()[0].nextstate;
+/// assert!(lazy_fst.get_trs(s2)?.trs().is_empty());
+/// assert!(lazy_fst.is_final(s2)?);
+/// # Ok(())
+/// # }
+/// ```
+pub struct FlagDiacriticsFst<W: Semiring, F: Fst<W>, B: Borrow<F>>(InnerLazyFst<W, F, B>);
+
+impl<W, F, B> CoreFst<W> for FlagDiacriticsFst<W, F, B>
+where
+    W: Semiring,
+    F: Fst<W>,
+    B: Borrow<F>,
+{
+    type TRS = TrsVec<W>;
+
+    fn start(&self) -> Option<StateId> {
+        self.0.start()
+    }
+
+    fn final_weight(&self, state_id: StateId) -> Result<Option<W>> {
+        self.0.final_weight(state_id)
+    }
+
+    unsafe fn final_weight_unchecked(&self, state_id: StateId) -> Option<W> {
+        self.0.final_weight_unchecked(state_id)
+    }
+
+    fn num_trs(&self, s: StateId) -> Result<usize> {
+        self.0.num_trs(s)
+    }
+
+    unsafe fn num_trs_unchecked(&self, s: StateId) -> usize {
+        self.0.num_trs_unchecked(s)
+    }
+
+    fn get_trs(&self, state_id: StateId) -> Result<Self::TRS> {
+        self.0.get_trs(state_id)
+    }
+
+    unsafe fn get_trs_unchecked(&self, state_id: StateId) -> Self::TRS {
+        self.0.get_trs_unchecked(state_id)
+    }
+
+    fn properties(&self) -> FstProperties {
+        self.0.properties()
+    }
+
+    fn num_input_epsilons(&self, state: StateId) -> Result<usize> {
+        self.0.num_input_epsilons(state)
+    }
+
+    fn num_output_epsilons(&self, state: StateId) -> Result<usize> {
+        self.0.num_output_epsilons(state)
+    }
+}
+
+impl<'a, W, F, B> StateIterator<'a> for FlagDiacriticsFst<W, F, B>
+where
+    W: Semiring,
+    F: Fst<W> + 'a,
+    B: Borrow<F> + 'a,
+{
+    type Iter = <InnerLazyFst<W, F, B> as StateIterator<'a>>::Iter;
+
+    fn states_iter(&'a self) -> Self::Iter {
+        self.0.states_iter()
+    }
+}
+
+impl<'a, W, F, B> FstIterator<'a, W> for FlagDiacriticsFst<W, F, B>
+where
+    W: Semiring,
+    F: Fst<W> + 'a,
+    B: Borrow<F> + 'a,
+{
+    type FstIter = <InnerLazyFst<W, F, B> as FstIterator<'a, W>>::FstIter;
+
+    fn fst_iter(&'a self) -> Self::FstIter {
+        self.0.fst_iter()
+    }
+}
+
+impl<W, F, B> Fst<W> for FlagDiacriticsFst<W, F, B>
+where
+    W: Semiring,
+    F: Fst<W> + 'static,
+    B: Borrow<F> + 'static,
+{
+    fn input_symbols(&self) -> Option<&Arc<SymbolTable>> {
+        self.0.input_symbols()
+    }
+
+    fn output_symbols(&self) -> Option<&Arc<SymbolTable>> {
+        self.0.output_symbols()
+    }
+
+    fn set_input_symbols(&mut self, symt: Arc<SymbolTable>) {
+        self.0.set_input_symbols(symt)
+    }
+
+    fn set_output_symbols(&mut self, symt: Arc<SymbolTable>) {
+        self.0.set_output_symbols(symt)
+    }
+
+    fn take_input_symbols(&mut self) -> Option<Arc<SymbolTable>> {
+        self.0.take_input_symbols()
+    }
+
+    fn take_output_symbols(&mut self) -> Option<Arc<SymbolTable>> {
+        self.0.take_output_symbols()
+    }
+}
+
+impl<W, F, B> Debug for FlagDiacriticsFst<W, F, B>
+where
+    W: Semiring,
+    F: Fst<W>,
+    B: Borrow<F>,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl<W, F, B> FlagDiacriticsFst<W, F, B>
+where
+    W: Semiring,
+    F: Fst<W>,
+    B: Borrow<F>,
+{
+    pub fn new(fst: B, flags: FlagDiacritics) -> Result<Self> {
+        let isymt = fst.borrow().input_symbols().cloned();
+        let osymt = fst.borrow().output_symbols().cloned();
+        let fst_op = FlagDiacriticsOp::new(fst, flags);
+        let fst_cache = SimpleHashMapCache::default();
+        let lazy_fst = LazyFst::from_op_and_cache(fst_op, fst_cache, isymt, osymt);
+        Ok(FlagDiacriticsFst(lazy_fst))
+    }
+
+    /// Turns the Lazy FST into a static one.
+    pub fn compute<F2: MutableFst<W> + AllocableFst<W>>(&self) -> Result<F2> {
+        self.0.compute()
+    }
+}
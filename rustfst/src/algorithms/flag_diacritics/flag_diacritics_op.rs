@@ -0,0 +1,92 @@
+use std::borrow::Borrow;
+use std::marker::PhantomData;
+use std::sync::Arc;
+
+use anyhow::Result;
+
+use crate::algorithms::flag_diacritics::flag_diacritic::FeatureValues;
+use crate::algorithms::flag_diacritics::FlagDiacritics;
+use crate::algorithms::lazy::{FstOp, StateTable};
+use crate::fst_properties::FstProperties;
+use crate::fst_traits::Fst;
+use crate::semirings::Semiring;
+use crate::{StateId, Tr, Trs, TrsVec, EPS_LABEL};
+
+pub struct FlagDiacriticsOp<W: Semiring, F: Fst<W>, B: Borrow<F>> {
+    fst: B,
+    flags: FlagDiacritics,
+    state_table: StateTable<(StateId, FeatureValues)>,
+    properties: FstProperties,
+    w: PhantomData<W>,
+    f: PhantomData<F>,
+}
+
+impl<W: Semiring, F: Fst<W>, B: Borrow<F>> std::fmt::Debug for FlagDiacriticsOp<W, F, B> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "FlagDiacriticsOp {{ flags : {:?}, state_table : {:?} }}",
+            self.flags, self.state_table
+        )
+    }
+}
+
+impl<W: Semiring, F: Fst<W>, B: Borrow<F>> FlagDiacriticsOp<W, F, B> {
+    pub fn new(fst: B, flags: FlagDiacritics) -> Self {
+        // The transitions carrying a flag diacritic become epsilon transitions or are removed,
+        // and the states are split according to the values of the features.
+        let properties = fst.borrow().properties()
+            & (FstProperties::ACCEPTOR
+                | FstProperties::EPSILONS
+                | FstProperties::I_EPSILONS
+                | FstProperties::O_EPSILONS
+                | FstProperties::WEIGHTED
+                | FstProperties::UNWEIGHTED
+                | FstProperties::ACYCLIC
+                | FstProperties::INITIAL_ACYCLIC);
+        Self {
+            fst,
+            flags,
+            state_table: StateTable::new(),
+            properties,
+            w: PhantomData,
+            f: PhantomData,
+        }
+    }
+}
+
+impl<W: Semiring, F: Fst<W>, B: Borrow<F>> FstOp<W> for FlagDiacriticsOp<W, F, B> {
+    fn compute_start(&self) -> Result<Option<StateId>> {
+        Ok(self.fst.borrow().start().map(|start| {
+            self.state_table
+                .find_id((start, self.flags.initial_values()))
+        }))
+    }
+
+    fn compute_trs(&self, id: StateId) -> Result<TrsVec<W>> {
+        let (state, values) = self.state_table.find_tuple(id);
+        let mut trs = vec![];
+        for tr in self.fst.borrow().get_trs(state)?.trs() {
+            let (ilabel, olabel, next_values) = match self.flags.tr_flag(tr)? {
+                // The transitions whose constraint fails are removed.
+                Some(flag) => match self.flags.apply(flag, &values) {
+                    Some(v) => (EPS_LABEL, EPS_LABEL, v),
+                    None => continue,
+                },
+                None => (tr.ilabel, tr.olabel, values.clone()),
+            };
+            let nextstate = self.state_table.find_id((tr.nextstate, next_values));
+            trs.push(Tr::new(ilabel, olabel, tr.weight.clone(), nextstate));
+        }
+        Ok(TrsVec(Arc::new(trs)))
+    }
+
+    fn compute_final_weight(&self, id: StateId) -> Result<Option<W>> {
+        let (state, _) = self.state_table.find_tuple(id);
+        self.fst.borrow().final_weight(state)
+    }
+
+    fn properties(&self) -> FstProperties {
+        self.properties
+    }
+}
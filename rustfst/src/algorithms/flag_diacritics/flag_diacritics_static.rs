@@ -0,0 +1,143 @@
+use anyhow::Result;
+
+use crate::algorithms::flag_diacritics::{FlagDiacritics, FlagDiacriticsFst};
+use crate::fst_traits::{AllocableFst, Fst, MutableFst};
+use crate::semirings::Semiring;
+
+/// Enforces the flag diacritics of a transducer in its topology : each state is split
+/// according to the values of the features reaching it, the transitions whose constraint
+/// fails are removed and the other flag diacritics are replaced by epsilons.
+///
+/// The result accepts the same paths as [`FlagDiacritics::lookup`] and can be composed or
+/// applied with [`transduce`](crate::algorithms::transduce) like any transducer. The number
+/// of states grows with the number of combinations of feature values that can be reached.
+/// An error is returned if a transition mixes a flag diacritic with a different label.
+///
+/// # Example
+/// ```
+/// # use rustfst::algorithms::flag_diacritics::{enforce_flag_diacritics, FlagDiacritics};
+/// # use rustfst::algorithms::transduce;
+/// # use rustfst::fst_impls::VectorFst;
+/// # use rustfst::fst_traits::MutableFst;
+/// # use rustfst::semirings::{Semiring, TropicalWeight};
+/// # use rustfst::{SymbolTable, Tr};
+/// # use anyhow::Result;
+/// # fn main() -> Result<()> {
+/// let mut symt = SymbolTable::new();
+/// let a = symt.add_symbol("a");
+/// let b = symt.add_symbol("b");
+/// let set_a = symt.add_symbol("@P.X.A@");
+/// let require_a = symt.add_symbol("@R.X.A@");
+///
+/// // (a @P.X.A@ | b) @R.X.A@ : only "a" is accepted.
+/// let mut fst = VectorFst::<TropicalWeight>::new();
+/// fst.add_states(4);
+/// fst.set_start(0)?;
+/// fst.add_tr(0, Tr::new(a, a, 0.0, 1))?;
+/// fst.add_tr(1, Tr::new(set_a, set_a, 0.0, 2))?;
+/// fst.add_tr(0, Tr::new(b, b, 0.0, 2))?;
+/// fst.add_tr(2, Tr::new(require_a, require_a, 0.0, 3))?;
+/// fst.set_final(3, TropicalWeight::one())?;
+///
+/// let flags = FlagDiacritics::from_symbol_table(&symt);
+/// let enforced: VectorFst<_> = enforce_flag_diacritics(&fst, &flags)?;
+/// assert_eq!(transduce(&enforced, &[a], 1)?, vec![(vec![a], TropicalWeight::one())]);
+/// assert!(transduce(&enforced, &[b], 1)?.is_empty());
+/// # Ok(())
+/// # }
+/// ```
+pub fn enforce_flag_diacritics<W, F1, F2>(fst: &F1, flags: &FlagDiacritics) -> Result<F2>
+where
+    W: Semiring,
+    F1: Fst<W>,
+    F2: MutableFst<W> + AllocableFst<W>,
+{
+    let fst = FlagDiacriticsFst::<W, F1, _>::new(fst, flags.clone())?;
+    fst.compute()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::algorithms::compose::compose;
+    use crate::algorithms::transduce;
+    use crate::fst_impls::VectorFst;
+    use crate::fst_traits::MutableFst;
+    use crate::semirings::TropicalWeight;
+    use crate::{SymbolTable, Tr, EPS_LABEL};
+
+    #[test]
+    fn test_enforce_flag_diacritics_compose() -> Result<()> {
+        let mut symt = SymbolTable::new();
+        let pre = symt.add_symbol("pre");
+        let stem = symt.add_symbol("stem");
+        let suf1 = symt.add_symbol("suf1");
+        let suf2 = symt.add_symbol("suf2");
+        let u1 = symt.add_symbol("@U.CLASS.ONE@");
+        let u2 = symt.add_symbol("@U.CLASS.TWO@");
+        let flags = FlagDiacritics::from_symbol_table(&symt);
+
+        // (pre @U.CLASS.ONE@)? stem (@U.CLASS.ONE@ suf1 | @U.CLASS.TWO@ suf2), the flags
+        // being written on the input side only.
+        let mut fst = VectorFst::<TropicalWeight>::new();
+        fst.add_states(7);
+        fst.set_start(0)?;
+        fst.add_tr(0, Tr::new(pre, pre, 1.0, 1))?;
+        fst.add_tr(1, Tr::new(u1, EPS_LABEL, 0.0, 2))?;
+        fst.add_tr(0, Tr::new(EPS_LABEL, EPS_LABEL, 0.0, 2))?;
+        fst.add_tr(2, Tr::new(stem, stem, 0.0, 3))?;
+        fst.add_tr(3, Tr::new(u1, EPS_LABEL, 0.0, 4))?;
+        fst.add_tr(4, Tr::new(suf1, suf1, 0.0, 5))?;
+        fst.add_tr(3, Tr::new(u2, EPS_LABEL, 0.0, 6))?;
+        fst.add_tr(6, Tr::new(suf2, suf2, 0.0, 5))?;
+        fst.set_final(5, TropicalWeight::one())?;
+
+        let enforced: VectorFst<_> = enforce_flag_diacritics(&fst, &flags)?;
+        for input in [
+            vec![pre, stem, suf1],
+            vec![pre, stem, suf2],
+            vec![stem, suf2],
+        ]
+        .iter()
+        {
+            assert_eq!(transduce(&enforced, input, 10)?, flags.lookup(&fst, input)?);
+        }
+        assert!(transduce(&enforced, &[pre, stem, suf2], 1)?.is_empty());
+
+        // Composition with a transducer rewriting the suffixes.
+        let suffix = symt.add_symbol("SUF");
+        let mut rewrite = VectorFst::<TropicalWeight>::new();
+        let s = rewrite.add_state();
+        rewrite.set_start(s)?;
+        rewrite.set_final(s, TropicalWeight::one())?;
+        for (ilabel, olabel) in [(pre, pre), (stem, stem), (suf1, suffix), (suf2, suffix)].iter() {
+            rewrite.add_tr(s, Tr::new(*ilabel, *olabel, 0.0, s))?;
+        }
+        let composed: VectorFst<_> =
+            compose::<_, VectorFst<_>, VectorFst<_>, _, _, _>(&enforced, &rewrite)?;
+        assert_eq!(
+            transduce(&composed, &[stem, suf2], 1)?,
+            vec![(vec![stem, suffix], TropicalWeight::one())]
+        );
+        assert!(transduce(&composed, &[pre, stem, suf2], 1)?.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_enforce_flag_diacritics_mismatched_flags() -> Result<()> {
+        let mut symt = SymbolTable::new();
+        let set_a = symt.add_symbol("@P.X.A@");
+        let set_b = symt.add_symbol("@P.X.B@");
+        let flags = FlagDiacritics::from_symbol_table(&symt);
+
+        let mut fst = VectorFst::<TropicalWeight>::new();
+        fst.add_states(2);
+        fst.set_start(0)?;
+        fst.add_tr(0, Tr::new(set_a, set_b, 0.0, 1))?;
+        fst.set_final(1, TropicalWeight::one())?;
+        assert!(enforce_flag_diacritics::<_, _, VectorFst<_>>(&fst, &flags).is_err());
+        assert!(flags.lookup(&fst, &[]).is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,370 @@
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, HashMap, VecDeque};
+
+use anyhow::Result;
+
+use crate::algorithms::flag_diacritics::flag_diacritic::{
+    CompiledFlag, FeatureValue, FeatureValues,
+};
+use crate::algorithms::flag_diacritics::{FlagDiacritic, FlagDiacriticOp};
+use crate::algorithms::queues::natural_less;
+use crate::fst_traits::Fst;
+use crate::semirings::Semiring;
+use crate::{Label, StateId, SymbolTable, Tr, Trs, EPS_LABEL, KSHORTESTDELTA};
+
+/// Active hypotheses: a state of the FST, the values of the features and the output labels
+/// produced so far, mapped to the ⊕-sum of the weights of the paths leading to it.
+type Hypotheses<W> = BTreeMap<(StateId, FeatureValues, Vec<Label>), W>;
+
+/// Set of labels of a symbol table that are flag diacritics.
+///
+/// Morphological transducers compiled with foma or HFST use flag diacritics to express
+/// long-distance constraints (e.g. between a prefix and a suffix) without expanding them
+/// in the topology of the machine. When looking up a sequence with [`lookup`](FlagDiacritics::lookup),
+/// a transition whose input label is a flag diacritic doesn't consume any input but is only
+/// followed if its constraint is satisfied by the features set earlier on the path.
+/// Flag diacritics are removed from the outputs.
+///
+/// A flag diacritic is a single symbol written on both tapes : the other label of a
+/// transition carrying a flag diacritic must be the same flag or an epsilon, an error is
+/// returned otherwise. To compose the transducer or to apply it with
+/// [`transduce`](crate::algorithms::transduce), the constraints are first enforced in the
+/// topology of the machine with [`enforce_flag_diacritics`] or lazily with
+/// [`FlagDiacriticsFst`].
+///
+/// [`enforce_flag_diacritics`]: crate::algorithms::flag_diacritics::enforce_flag_diacritics
+/// [`FlagDiacriticsFst`]: crate::algorithms::flag_diacritics::FlagDiacriticsFst
+///
+/// # Example
+/// ```
+/// # use rustfst::algorithms::flag_diacritics::FlagDiacritics;
+/// # use rustfst::fst_impls::VectorFst;
+/// # use rustfst::fst_traits::MutableFst;
+/// # use rustfst::semirings::{Semiring, TropicalWeight};
+/// # use rustfst::{SymbolTable, Tr};
+/// # use anyhow::Result;
+/// # fn main() -> Result<()> {
+/// let mut symt = SymbolTable::new();
+/// let a = symt.add_symbol("a");
+/// let b = symt.add_symbol("b");
+/// let set_a = symt.add_symbol("@P.X.A@");
+/// let require_a = symt.add_symbol("@R.X.A@");
+///
+/// // (a @P.X.A@ | b) @R.X.A@ : only "a" is accepted.
+/// let mut fst = VectorFst::<TropicalWeight>::new();
+/// let s0 = fst.add_state();
+/// let s1 = fst.add_state();
+/// let s2 = fst.add_state();
+/// let s3 = fst.add_state();
+/// fst.set_start(s0)?;
+/// fst.add_tr(s0, Tr::new(a, a, 0.0, s1))?;
+/// fst.add_tr(s1, Tr::new(set_a, set_a, 0.0, s2))?;
+/// fst.add_tr(s0, Tr::new(b, b, 0.0, s2))?;
+/// fst.add_tr(s2, Tr::new(require_a, require_a, 0.0, s3))?;
+/// fst.set_final(s3, TropicalWeight::one())?;
+///
+/// let flags = FlagDiacritics::from_symbol_table(&symt);
+/// assert_eq!(flags.lookup(&fst, &[a])?, vec![(vec![a], TropicalWeight::one())]);
+/// assert!(flags.lookup(&fst, &[b])?.is_empty());
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct FlagDiacritics {
+    flags: HashMap<Label, CompiledFlag>,
+    num_features: usize,
+    max_active: usize,
+}
+
+impl FlagDiacritics {
+    /// Default maximum number of active hypotheses during a lookup.
+    pub const DEFAULT_MAX_ACTIVE: usize = 100_000;
+
+    /// Collects the symbols of the table that are flag diacritics.
+    pub fn from_symbol_table(symt: &SymbolTable) -> Self {
+        let mut features: HashMap<String, usize> = HashMap::new();
+        let mut values: HashMap<String, usize> = HashMap::new();
+        let mut flags = HashMap::new();
+        for (label, symbol) in symt.iter() {
+            if let Some(flag) = FlagDiacritic::parse(symbol) {
+                let n_features = features.len();
+                let feature = *features.entry(flag.feature).or_insert(n_features);
+                let value = flag.value.map(|v| {
+                    let n_values = values.len();
+                    *values.entry(v).or_insert(n_values)
+                });
+                flags.insert(
+                    label,
+                    CompiledFlag {
+                        op: flag.op,
+                        feature,
+                        value,
+                    },
+                );
+            }
+        }
+        Self {
+            flags,
+            num_features: features.len(),
+            max_active: Self::DEFAULT_MAX_ACTIVE,
+        }
+    }
+
+    /// Sets the maximum number of hypotheses alive at the same time during a lookup.
+    pub fn with_max_active(self, max_active: usize) -> Self {
+        Self { max_active, ..self }
+    }
+
+    /// Returns true if the label is a flag diacritic.
+    pub fn is_flag(&self, label: Label) -> bool {
+        self.flags.contains_key(&label)
+    }
+
+    /// Number of labels that are flag diacritics.
+    pub fn len(&self) -> usize {
+        self.flags.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.flags.is_empty()
+    }
+
+    /// Values of the features at the start of a path.
+    pub(super) fn initial_values(&self) -> FeatureValues {
+        vec![FeatureValue::Unset; self.num_features]
+    }
+
+    /// Flag diacritic carried by a transition, if any. Returns an error if the transition
+    /// mixes a flag diacritic with a different label.
+    pub(super) fn tr_flag<W: Semiring>(&self, tr: &Tr<W>) -> Result<Option<&CompiledFlag>> {
+        match (self.flags.get(&tr.ilabel), self.flags.get(&tr.olabel)) {
+            (None, None) => Ok(None),
+            (Some(flag), _) if tr.olabel == tr.ilabel || tr.olabel == EPS_LABEL => Ok(Some(flag)),
+            (None, Some(flag)) if tr.ilabel == EPS_LABEL => Ok(Some(flag)),
+            _ => bail!(
+                "FlagDiacritics : the transition {}:{} mixes a flag diacritic with another label",
+                tr.ilabel,
+                tr.olabel
+            ),
+        }
+    }
+
+    /// Applies the transducer to a single input sequence, enforcing the flag diacritics
+    /// constraints. Returns all the output sequences along with the ⊕-sum of the weights of
+    /// their paths, best first w.r.t. the natural order of the semiring.
+    ///
+    /// An error is returned if more than `max_active` hypotheses are alive at the same time,
+    /// which happens when the FST has cycles with an input epsilon and a non-epsilon output.
+    pub fn lookup<W: Semiring, F: Fst<W>>(
+        &self,
+        fst: &F,
+        ilabels: &[Label],
+    ) -> Result<Vec<(Vec<Label>, W)>> {
+        let start = match fst.start() {
+            Some(s) => s,
+            None => return Ok(vec![]),
+        };
+        let mut hyps = Hypotheses::new();
+        hyps.insert((start, self.initial_values(), vec![]), W::one());
+        self.epsilon_closure(fst, &mut hyps)?;
+
+        for label in ilabels {
+            if hyps.is_empty() {
+                return Ok(vec![]);
+            }
+            let mut next = Hypotheses::new();
+            for ((state, values, output), weight) in hyps.iter() {
+                for tr in fst.get_trs(*state)?.trs() {
+                    // The flag diacritics never consume the input.
+                    if tr.ilabel != *label || self.tr_flag(tr)?.is_some() {
+                        continue;
+                    }
+                    let w = weight.times(&tr.weight)?;
+                    if w.is_zero() {
+                        continue;
+                    }
+                    let mut output = output.clone();
+                    if tr.olabel != EPS_LABEL {
+                        output.push(tr.olabel);
+                    }
+                    next.entry((tr.nextstate, values.clone(), output))
+                        .or_insert_with(W::zero)
+                        .plus_assign(w)?;
+                }
+            }
+            hyps = next;
+            self.epsilon_closure(fst, &mut hyps)?;
+        }
+
+        let mut outputs: BTreeMap<Vec<Label>, W> = BTreeMap::new();
+        for ((state, _, output), weight) in hyps.into_iter() {
+            if let Some(final_weight) = fst.final_weight(state)? {
+                outputs
+                    .entry(output)
+                    .or_insert_with(W::zero)
+                    .plus_assign(weight.times(final_weight)?)?;
+            }
+        }
+        let mut outputs: Vec<_> = outputs.into_iter().collect();
+        outputs.sort_by(|(_, w1), (_, w2)| {
+            if natural_less(w1, w2).unwrap_or(false) {
+                Ordering::Less
+            } else if natural_less(w2, w1).unwrap_or(false) {
+                Ordering::Greater
+            } else {
+                Ordering::Equal
+            }
+        });
+        Ok(outputs)
+    }
+
+    /// Applies a flag diacritic to the values of the features, returning `None` if it fails.
+    pub(super) fn apply(
+        &self,
+        flag: &CompiledFlag,
+        values: &[FeatureValue],
+    ) -> Option<FeatureValues> {
+        let current = values[flag.feature];
+        let new_value = match (flag.op, flag.value) {
+            (FlagDiacriticOp::Positive, Some(v)) => FeatureValue::Positive(v),
+            (FlagDiacriticOp::Negative, Some(v)) => FeatureValue::Negative(v),
+            (FlagDiacriticOp::Require, Some(v)) if current == FeatureValue::Positive(v) => current,
+            (FlagDiacriticOp::Require, None) if current != FeatureValue::Unset => current,
+            (FlagDiacriticOp::Disallow, Some(v)) if current != FeatureValue::Positive(v) => current,
+            (FlagDiacriticOp::Disallow, None) if current == FeatureValue::Unset => current,
+            (FlagDiacriticOp::Clear, _) => FeatureValue::Unset,
+            (FlagDiacriticOp::Unify, Some(v)) => match current {
+                FeatureValue::Unset => FeatureValue::Positive(v),
+                FeatureValue::Positive(c) if c == v => current,
+                FeatureValue::Negative(c) if c != v => FeatureValue::Positive(v),
+                _ => return None,
+            },
+            _ => return None,
+        };
+        let mut values = values.to_vec();
+        values[flag.feature] = new_value;
+        Some(values)
+    }
+
+    /// Follows the transitions whose input label is an epsilon or a flag diacritic.
+    fn epsilon_closure<W: Semiring, F: Fst<W>>(
+        &self,
+        fst: &F,
+        hyps: &mut Hypotheses<W>,
+    ) -> Result<()> {
+        let mut residuals = hyps.clone();
+        let mut queue: VecDeque<_> = hyps.keys().cloned().collect();
+        while let Some(key) = queue.pop_front() {
+            let residual = match residuals.insert(key.clone(), W::zero()) {
+                Some(r) if !r.is_zero() => r,
+                _ => continue,
+            };
+            let (state, values, output) = &key;
+            for tr in fst.get_trs(*state)?.trs() {
+                let (values, output) = match self.tr_flag(tr)? {
+                    Some(flag) => match self.apply(flag, values) {
+                        Some(v) => (v, output.clone()),
+                        None => continue,
+                    },
+                    None if tr.ilabel == EPS_LABEL => {
+                        let mut output = output.clone();
+                        if tr.olabel != EPS_LABEL {
+                            output.push(tr.olabel);
+                        }
+                        (values.clone(), output)
+                    }
+                    None => continue,
+                };
+                let weight = residual.times(&tr.weight)?;
+                if weight.is_zero() {
+                    continue;
+                }
+                let next_key = (tr.nextstate, values, output);
+                let distance = hyps.entry(next_key.clone()).or_insert_with(W::zero);
+                let new_distance = distance.plus(&weight)?;
+                if !distance.approx_equal(&new_distance, KSHORTESTDELTA) {
+                    *distance = new_distance;
+                    let r = residuals.entry(next_key.clone()).or_insert_with(W::zero);
+                    let was_pending = !r.is_zero();
+                    r.plus_assign(&weight)?;
+                    if !was_pending {
+                        queue.push_back(next_key);
+                    }
+                }
+                if hyps.len() > self.max_active {
+                    bail!(
+                        "FlagDiacritics : more than {} active hypotheses during the lookup",
+                        self.max_active
+                    );
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::fst_impls::VectorFst;
+    use crate::fst_traits::MutableFst;
+    use crate::semirings::TropicalWeight;
+
+    #[test]
+    fn test_flag_diacritics_unify() -> Result<()> {
+        let mut symt = SymbolTable::new();
+        let pre = symt.add_symbol("pre");
+        let stem = symt.add_symbol("stem");
+        let suf1 = symt.add_symbol("suf1");
+        let suf2 = symt.add_symbol("suf2");
+        let u1 = symt.add_symbol("@U.CLASS.ONE@");
+        let u2 = symt.add_symbol("@U.CLASS.TWO@");
+        let clear = symt.add_symbol("@C.CLASS@");
+        let flags = FlagDiacritics::from_symbol_table(&symt);
+        assert_eq!(flags.len(), 3);
+        assert!(flags.is_flag(clear));
+        assert!(!flags.is_flag(stem));
+
+        // (pre @U.CLASS.ONE@)? stem (@U.CLASS.ONE@ suf1 | @U.CLASS.TWO@ suf2) (@C.CLASS@ stem)?
+        let mut fst = VectorFst::<TropicalWeight>::new();
+        let s0 = fst.add_state();
+        let s1 = fst.add_state();
+        let s2 = fst.add_state();
+        let s3 = fst.add_state();
+        let s4 = fst.add_state();
+        let s5 = fst.add_state();
+        let s6 = fst.add_state();
+        let s7 = fst.add_state();
+        let s8 = fst.add_state();
+        fst.set_start(s0)?;
+        fst.add_tr(s0, Tr::new(pre, pre, 1.0, s1))?;
+        fst.add_tr(s1, Tr::new(u1, u1, 0.0, s2))?;
+        fst.add_tr(s0, Tr::new(EPS_LABEL, EPS_LABEL, 0.0, s2))?;
+        fst.add_tr(s2, Tr::new(stem, stem, 0.0, s3))?;
+        fst.add_tr(s3, Tr::new(u1, u1, 0.0, s4))?;
+        fst.add_tr(s4, Tr::new(suf1, suf1, 0.0, s6))?;
+        fst.add_tr(s3, Tr::new(u2, u2, 0.0, s5))?;
+        fst.add_tr(s5, Tr::new(suf2, suf2, 0.0, s6))?;
+        fst.add_tr(s6, Tr::new(clear, clear, 0.0, s7))?;
+        fst.add_tr(s7, Tr::new(stem, stem, 2.0, s8))?;
+        fst.set_final(s6, TropicalWeight::one())?;
+        fst.set_final(s8, TropicalWeight::one())?;
+
+        let one = TropicalWeight::one();
+        assert_eq!(
+            flags.lookup(&fst, &[pre, stem, suf1])?,
+            vec![(vec![pre, stem, suf1], TropicalWeight::new(1.0))]
+        );
+        assert!(flags.lookup(&fst, &[pre, stem, suf2])?.is_empty());
+        assert_eq!(
+            flags.lookup(&fst, &[stem, suf2])?,
+            vec![(vec![stem, suf2], one)]
+        );
+        assert_eq!(
+            flags.lookup(&fst, &[stem, suf2, stem])?,
+            vec![(vec![stem, suf2, stem], TropicalWeight::new(2.0))]
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,10 @@
+mod flag_diacritic;
+mod flag_diacritics_fst;
+mod flag_diacritics_op;
+mod flag_diacritics_static;
+mod flag_table;
+
+pub use flag_diacritic::{FlagDiacritic, FlagDiacriticOp};
+pub use flag_diacritics_fst::FlagDiacriticsFst;
+pub use flag_diacritics_static::enforce_flag_diacritics;
+pub use flag_table::FlagDiacritics;
@@ -0,0 +1,93 @@
+use std::cmp::Reverse;
+
+use anyhow::Result;
+
+use crate::fst_properties::FstProperties;
+use crate::fst_traits::MutableFst;
+use crate::semirings::Semiring;
+use crate::StateId;
+
+/// Reorders the trs leaving each state so that the most traversed ones come first, which
+/// shortens the linear scans of the matchers over unsorted trs (e.g. during decoding).
+///
+/// `counts[s][i]` is the number of times the i-th tr of the state `s` has been taken during
+/// a profiling run. Trs with equal counts keep their relative order and the states past the
+/// end of `counts` are left unchanged. The FST loses its label-sorted properties.
+pub fn hot_tr_sort<W, F>(fst: &mut F, counts: &[Vec<u64>]) -> Result<()>
+where
+    W: Semiring,
+    F: MutableFst<W>,
+{
+    if counts.len() > fst.num_states() {
+        bail!(
+            "Traversal counts given for {} states but the FST has only {} states",
+            counts.len(),
+            fst.num_states()
+        );
+    }
+    for (state, state_counts) in counts.iter().enumerate() {
+        let num_trs = fst.num_trs(state as StateId)?;
+        if state_counts.len() != num_trs {
+            bail!(
+                "State {} has {} trs but {} traversal counts",
+                state,
+                num_trs,
+                state_counts.len()
+            );
+        }
+    }
+
+    let props = fst.properties();
+    for (state, state_counts) in counts.iter().enumerate() {
+        let state = state as StateId;
+        let mut order: Vec<_> = (0..state_counts.len()).collect();
+        order.sort_by_key(|i| Reverse(state_counts[*i]));
+        if order.iter().enumerate().all(|(pos, i)| pos == *i) {
+            continue;
+        }
+        let mut trs: Vec<_> = fst.pop_trs(state)?.into_iter().map(Some).collect();
+        // Safe because the state exists.
+        unsafe {
+            fst.set_trs_unchecked(
+                state,
+                order.iter().map(|i| trs[*i].take().unwrap()).collect(),
+            )
+        };
+    }
+    fst.set_properties_with_mask(
+        props & FstProperties::arcsort_properties(),
+        FstProperties::all_properties(),
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::fst_impls::VectorFst;
+    use crate::fst_traits::CoreFst;
+    use crate::semirings::TropicalWeight;
+    use crate::{Tr, Trs};
+
+    #[test]
+    fn test_hot_tr_sort() -> Result<()> {
+        let mut fst = VectorFst::<TropicalWeight>::new();
+        fst.add_states(3);
+        fst.set_start(0)?;
+        fst.add_tr(0, Tr::new(1, 1, 1.0, 1))?;
+        fst.add_tr(0, Tr::new(2, 2, 1.0, 1))?;
+        fst.add_tr(0, Tr::new(3, 3, 1.0, 2))?;
+        fst.add_tr(1, Tr::new(4, 4, 1.0, 2))?;
+        fst.set_final(2, 0.0)?;
+
+        hot_tr_sort(&mut fst, &[vec![2, 10, 2]])?;
+        let ilabels: Vec<_> = fst.get_trs(0)?.trs().iter().map(|tr| tr.ilabel).collect();
+        assert_eq!(ilabels, vec![2, 1, 3]);
+        assert!(!fst.properties().contains(FstProperties::I_LABEL_SORTED));
+        assert_eq!(fst.num_trs(1)?, 1);
+
+        assert!(hot_tr_sort(&mut fst, &[vec![1, 2]]).is_err());
+        Ok(())
+    }
+}
@@ -159,6 +159,7 @@ impl<'a, W: Semiring, F1: ExpandedFst<W>, F2: ExpandedFst<W>> Isomorphism<'a, W,
 }
 
 /// Configuration for isomorphic comparison.
+#[derive(Debug, Clone, Copy, PartialOrd, PartialEq)]
 pub struct IsomorphicConfig {
     delta: f32,
 }
@@ -173,6 +174,10 @@ impl IsomorphicConfig {
     pub fn new(delta: f32) -> Self {
         Self { delta }
     }
+
+    pub fn with_delta(self, delta: f32) -> Self {
+        Self { delta }
+    }
 }
 
 /// Determine if two transducers with a certain required determinism
@@ -0,0 +1,283 @@
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, VecDeque};
+
+use anyhow::{bail, format_err, Result};
+
+use crate::algorithms::connect;
+use crate::fst_impls::VectorFst;
+use crate::fst_traits::{ExpandedFst, Fst, MutableFst};
+use crate::semirings::Semiring;
+use crate::{Label, StateId, SymbolTable, Tr, Trs, EPS_LABEL};
+
+/// Position of a phone in the words, as in the `word_boundary.int` files of Kaldi.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PhonePosition {
+    /// First phone of a word of several phones.
+    Begin,
+    /// Last phone of a word of several phones.
+    End,
+    /// Phone inside a word, neither the first nor the last one.
+    Internal,
+    /// Phone making up a whole word.
+    Singleton,
+    /// Phone outside of the words, such as silence or noise.
+    Nonword,
+}
+
+/// Positions of the phones in the words, used to find the word boundaries in a lattice.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct WordBoundaryInfo {
+    positions: HashMap<Label, PhonePosition>,
+}
+
+impl WordBoundaryInfo {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_phone(mut self, phone: Label, position: PhonePosition) -> Self {
+        self.set_position(phone, position);
+        self
+    }
+
+    pub fn set_position(&mut self, phone: Label, position: PhonePosition) {
+        self.positions.insert(phone, position);
+    }
+
+    /// Positions given by the position-dependent phones convention : the phones whose
+    /// symbols end with `_B`, `_E`, `_I` and `_S` are respectively the begin, end, internal
+    /// and singleton phones. The other phones are nonword phones.
+    pub fn from_symbol_table(phones: &SymbolTable) -> Self {
+        let mut info = Self::new();
+        for (label, symbol) in phones.iter() {
+            if label == EPS_LABEL {
+                continue;
+            }
+            let position = if symbol.ends_with("_B") {
+                PhonePosition::Begin
+            } else if symbol.ends_with("_E") {
+                PhonePosition::End
+            } else if symbol.ends_with("_I") {
+                PhonePosition::Internal
+            } else if symbol.ends_with("_S") {
+                PhonePosition::Singleton
+            } else {
+                PhonePosition::Nonword
+            };
+            info.set_position(label, position);
+        }
+        info
+    }
+
+    pub fn position(&self, phone: Label) -> Option<PhonePosition> {
+        self.positions.get(&phone).cloned()
+    }
+}
+
+/// State of the aligned lattice : a state of the input lattice, the words read but not yet
+/// placed on their first phone and whether the path is inside a word.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct AlignTuple {
+    state: StateId,
+    pending: Vec<Label>,
+    inside_word: bool,
+}
+
+/// Re-aligns the words of a lattice on its phones, as `lattice-align-words` from Kaldi does.
+///
+/// In the input lattice, the words may be output anywhere before their first phone, which is
+/// what determinization and epsilon removal usually produce. In the result, each word is the
+/// output label of the transition of its first phone and the other transitions have epsilon
+/// output labels, so that the word boundaries of each path are known from its transitions.
+/// The states of the result are pairs of a state of the input and of the words that are
+/// pending, the weights are left unchanged.
+///
+/// The paths that can't be aligned are removed : the ones where a word is output after its
+/// first phone, where the phone positions are not consistent or which end inside a word.
+/// The result is empty when no path can be aligned. An error is returned if a phone has no
+/// position in `info`.
+///
+/// # Example
+/// ```
+/// # use rustfst::algorithms::lattice::{lattice_align_words, PhonePosition, WordBoundaryInfo};
+/// # use rustfst::fst_impls::VectorFst;
+/// # use rustfst::fst_traits::{Fst, MutableFst};
+/// # use rustfst::semirings::{Semiring, TropicalWeight};
+/// # use rustfst::{Tr, EPS_LABEL};
+/// # use anyhow::Result;
+/// # fn main() -> Result<()> {
+/// let info = WordBoundaryInfo::new()
+///     .with_phone(1, PhonePosition::Singleton)
+///     .with_phone(2, PhonePosition::Singleton);
+/// // Both words are output on the first phone.
+/// let mut lattice = VectorFst::<TropicalWeight>::new();
+/// let s0 = lattice.add_state();
+/// let s1 = lattice.add_state();
+/// let s2 = lattice.add_state();
+/// let s3 = lattice.add_state();
+/// lattice.set_start(s0)?;
+/// lattice.add_tr(s0, Tr::new(EPS_LABEL, 10, 0.0, s1))?;
+/// lattice.add_tr(s1, Tr::new(1, 20, 0.0, s2))?;
+/// lattice.add_tr(s2, Tr::new(2, EPS_LABEL, 0.0, s3))?;
+/// lattice.set_final(s3, TropicalWeight::one())?;
+///
+/// let aligned = lattice_align_words(&lattice, &info)?;
+/// let path = aligned.paths_iter().next().unwrap();
+/// assert_eq!(path.ilabels, vec![1, 2]);
+/// assert_eq!(path.olabels, vec![10, 20]);
+/// # Ok(())
+/// # }
+/// ```
+pub fn lattice_align_words<W, F>(lattice: &F, info: &WordBoundaryInfo) -> Result<VectorFst<W>>
+where
+    W: Semiring,
+    F: ExpandedFst<W>,
+{
+    let mut ofst = VectorFst::new();
+    if let Some(symt) = lattice.input_symbols() {
+        ofst.set_input_symbols(symt.clone());
+    }
+    if let Some(symt) = lattice.output_symbols() {
+        ofst.set_output_symbols(symt.clone());
+    }
+    let start = match lattice.start() {
+        Some(start) => start,
+        None => return Ok(ofst),
+    };
+    // In an acyclic lattice, no more words than states can be pending.
+    let max_pending = lattice.num_states();
+
+    let mut tuples: HashMap<AlignTuple, StateId> = HashMap::new();
+    let mut queue = VecDeque::new();
+    let start_tuple = AlignTuple {
+        state: start,
+        pending: vec![],
+        inside_word: false,
+    };
+    let ostart = ofst.add_state();
+    ofst.set_start(ostart)?;
+    tuples.insert(start_tuple.clone(), ostart);
+    queue.push_back((start_tuple, ostart));
+
+    while let Some((tuple, ostate)) = queue.pop_front() {
+        if let Some(final_weight) = lattice.final_weight(tuple.state)? {
+            if tuple.pending.is_empty() && !tuple.inside_word {
+                ofst.set_final(ostate, final_weight)?;
+            }
+        }
+        for tr in lattice.get_trs(tuple.state)?.trs() {
+            let mut pending = tuple.pending.clone();
+            if tr.olabel != EPS_LABEL {
+                pending.push(tr.olabel);
+                if pending.len() > max_pending {
+                    bail!("lattice_align_words : words are output on a cycle without phones");
+                }
+            }
+            let mut inside_word = tuple.inside_word;
+            let mut olabel = EPS_LABEL;
+            if tr.ilabel != EPS_LABEL {
+                let position = info.position(tr.ilabel).ok_or_else(|| {
+                    format_err!("No word boundary information for the phone {}", tr.ilabel)
+                })?;
+                let consistent = match position {
+                    PhonePosition::Begin | PhonePosition::Singleton => {
+                        if inside_word || pending.is_empty() {
+                            false
+                        } else {
+                            olabel = pending.remove(0);
+                            inside_word = position == PhonePosition::Begin;
+                            true
+                        }
+                    }
+                    PhonePosition::Internal => inside_word,
+                    PhonePosition::End => {
+                        let consistent = inside_word;
+                        inside_word = false;
+                        consistent
+                    }
+                    PhonePosition::Nonword => !inside_word,
+                };
+                if !consistent {
+                    continue;
+                }
+            }
+
+            let next_tuple = AlignTuple {
+                state: tr.nextstate,
+                pending,
+                inside_word,
+            };
+            let next_ostate = match tuples.entry(next_tuple) {
+                Entry::Occupied(e) => *e.get(),
+                Entry::Vacant(e) => {
+                    let s = ofst.add_state();
+                    queue.push_back((e.key().clone(), s));
+                    e.insert(s);
+                    s
+                }
+            };
+            ofst.add_tr(
+                ostate,
+                Tr::new(tr.ilabel, olabel, tr.weight.clone(), next_ostate),
+            )?;
+        }
+    }
+
+    connect(&mut ofst)?;
+    Ok(ofst)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::fst_traits::CoreFst;
+    use crate::semirings::TropicalWeight;
+
+    #[test]
+    fn test_lattice_align_words() -> Result<()> {
+        let mut phones = SymbolTable::new();
+        phones.add_symbols(vec!["sil", "a_B", "a_E", "b_S"]);
+        let info = WordBoundaryInfo::from_symbol_table(&phones);
+        assert_eq!(info.position(1), Some(PhonePosition::Nonword));
+        assert_eq!(info.position(3), Some(PhonePosition::End));
+
+        // a_B:w1 a_E:w2 sil b_S : w2 is output too early, on the phones of w1.
+        let mut lattice = VectorFst::<TropicalWeight>::new();
+        let s0 = lattice.add_state();
+        let s1 = lattice.add_state();
+        let s2 = lattice.add_state();
+        let s3 = lattice.add_state();
+        let s4 = lattice.add_state();
+        lattice.set_start(s0)?;
+        lattice.add_tr(s0, Tr::new(2, 10, 1.0, s1))?;
+        lattice.add_tr(s1, Tr::new(3, 20, 2.0, s2))?;
+        lattice.add_tr(s2, Tr::new(1, EPS_LABEL, 0.0, s3))?;
+        lattice.add_tr(s3, Tr::new(4, EPS_LABEL, 0.0, s4))?;
+        lattice.set_final(s4, TropicalWeight::new(0.5))?;
+        // b_S then w1 : the word comes after its phone and the path is removed.
+        let s5 = lattice.add_state();
+        lattice.add_tr(s0, Tr::new(4, EPS_LABEL, 0.0, s5))?;
+        lattice.add_tr(s5, Tr::new(EPS_LABEL, 10, 0.0, s4))?;
+
+        let aligned = lattice_align_words(&lattice, &info)?;
+        let paths: Vec<_> = aligned.paths_iter().collect();
+        assert_eq!(paths.len(), 1);
+        assert_eq!(paths[0].ilabels, vec![2, 3, 1, 4]);
+        assert_eq!(paths[0].olabels, vec![10, 20]);
+        assert_eq!(paths[0].weight, TropicalWeight::new(3.5));
+        for state in aligned.states_range() {
+            for tr in aligned.get_trs(state)?.trs() {
+                let expected = match tr.ilabel {
+                    2 => 10,
+                    4 => 20,
+                    _ => EPS_LABEL,
+                };
+                assert_eq!(tr.olabel, expected);
+            }
+        }
+
+        assert!(lattice_align_words(&lattice, &WordBoundaryInfo::new()).is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,517 @@
+use std::collections::{BTreeMap, HashMap};
+
+use anyhow::{bail, Result};
+use ordered_float::OrderedFloat;
+
+use crate::algorithms::determinize::{determinize_with_config, DeterminizeConfig, DeterminizeType};
+use crate::fst_impls::VectorFst;
+use crate::fst_traits::{CoreFst, ExpandedFst, MutableFst};
+use crate::semirings::{LogWeight, Semiring};
+use crate::{Label, StateId, Tr, Trs, EPS_LABEL};
+
+/// Quantization delta of the determinization of the factors.
+const KWS_DELTA: f32 = 1e-6;
+
+/// Configuration of the construction of a keyword search index.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct KwsIndexConfig {
+    /// Maximum number of words of the indexed factors.
+    pub max_factor_length: usize,
+    /// Occurrences whose posterior probability is below this threshold are not indexed.
+    pub min_posterior: f64,
+}
+
+impl Default for KwsIndexConfig {
+    fn default() -> Self {
+        Self {
+            max_factor_length: 5,
+            min_posterior: 1e-4,
+        }
+    }
+}
+
+impl KwsIndexConfig {
+    pub fn new(max_factor_length: usize, min_posterior: f64) -> Self {
+        Self {
+            max_factor_length,
+            min_posterior,
+        }
+    }
+
+    pub fn with_max_factor_length(self, max_factor_length: usize) -> Self {
+        Self {
+            max_factor_length,
+            ..self
+        }
+    }
+
+    pub fn with_min_posterior(self, min_posterior: f64) -> Self {
+        Self {
+            min_posterior,
+            ..self
+        }
+    }
+}
+
+/// Occurrence of a factor in a lattice : the index of the lattice and the time interval.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct KwsEntry {
+    pub utterance: usize,
+    pub start: usize,
+    pub end: usize,
+}
+
+/// Occurrence of a keyword found in the index, with its posterior probability.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct KwsHit {
+    pub utterance: usize,
+    pub start: usize,
+    pub end: usize,
+    pub posterior: f64,
+}
+
+/// Timed factor transducer index of a set of lattices, built with [`kws_index`].
+///
+/// The input labels of the FST are words. Reading the words of a factor from the start state
+/// reaches a state whose epsilon-input transitions lead to the final state : their output
+/// labels identify the occurrences of the factor (the label `l` is the entry `l - 1`) and their
+/// weights are the negative logarithms of the posterior probabilities of the occurrences.
+#[derive(Debug, Clone, PartialEq)]
+pub struct KwsIndex {
+    fst: VectorFst<LogWeight>,
+    entries: Vec<KwsEntry>,
+}
+
+impl KwsIndex {
+    pub fn fst(&self) -> &VectorFst<LogWeight> {
+        &self.fst
+    }
+
+    pub fn entries(&self) -> &[KwsEntry] {
+        &self.entries
+    }
+
+    /// Occurrences of the keyword, sorted by decreasing posterior probability.
+    pub fn search(&self, keyword: &[Label]) -> Result<Vec<KwsHit>> {
+        let mut hits = vec![];
+        if keyword.is_empty() {
+            return Ok(hits);
+        }
+        let mut state = match self.fst.start() {
+            Some(start) => start,
+            None => return Ok(hits),
+        };
+        for word in keyword {
+            match self
+                .fst
+                .get_trs(state)?
+                .trs()
+                .iter()
+                .find(|tr| tr.ilabel == *word)
+            {
+                Some(tr) => state = tr.nextstate,
+                None => return Ok(hits),
+            }
+        }
+        for tr in self.fst.get_trs(state)?.trs() {
+            if tr.ilabel != EPS_LABEL {
+                continue;
+            }
+            let entry = &self.entries[tr.olabel as usize - 1];
+            hits.push(KwsHit {
+                utterance: entry.utterance,
+                start: entry.start,
+                end: entry.end,
+                posterior: f64::from(-*tr.weight.value()).exp(),
+            });
+        }
+        hits.sort_by_key(|hit| std::cmp::Reverse(OrderedFloat(hit.posterior)));
+        Ok(hits)
+    }
+}
+
+/// ⊕-sum of the log semiring on negative logarithms of probabilities.
+fn log_plus(a: f64, b: f64) -> f64 {
+    if a == f64::INFINITY {
+        return b;
+    }
+    if b == f64::INFINITY {
+        return a;
+    }
+    a.min(b) - (-(a - b).abs()).exp().ln_1p()
+}
+
+fn weight_of<W: Semiring<Type = f32>>(w: &W) -> f64 {
+    f64::from(*w.value())
+}
+
+/// Topological order of the states of an acyclic FST.
+fn topological_order<W: Semiring, F: ExpandedFst<W>>(fst: &F) -> Result<Vec<StateId>> {
+    let mut in_degrees = vec![0; fst.num_states()];
+    for state in fst.states_range() {
+        for tr in fst.get_trs(state)?.trs() {
+            in_degrees[tr.nextstate as usize] += 1;
+        }
+    }
+    let mut order = vec![];
+    let mut stack: Vec<StateId> = fst
+        .states_range()
+        .filter(|s| in_degrees[*s as usize] == 0)
+        .collect();
+    while let Some(state) = stack.pop() {
+        order.push(state);
+        for tr in fst.get_trs(state)?.trs() {
+            in_degrees[tr.nextstate as usize] -= 1;
+            if in_degrees[tr.nextstate as usize] == 0 {
+                stack.push(tr.nextstate);
+            }
+        }
+    }
+    if order.len() != fst.num_states() {
+        bail!("kws_index : the lattices must be acyclic");
+    }
+    Ok(order)
+}
+
+/// Transition of the word graph of a lattice : a word, the state ending its segment and the
+/// weight of the segment.
+struct WordEdge {
+    word: Label,
+    nextstate: StateId,
+    weight: f64,
+}
+
+/// Adds the occurrences of the factors of a lattice to `occurrences`, indexed by the words of
+/// the factors.
+fn index_lattice<W, F>(
+    lattice: &F,
+    utterance: usize,
+    config: &KwsIndexConfig,
+    occurrences: &mut BTreeMap<Vec<Label>, BTreeMap<KwsEntry, f64>>,
+) -> Result<()>
+where
+    W: Semiring<Type = f32>,
+    F: ExpandedFst<W>,
+{
+    let start = match lattice.start() {
+        Some(start) => start,
+        None => return Ok(()),
+    };
+    let order = topological_order(lattice)?;
+    let num_states = lattice.num_states();
+
+    // Times of the states : number of non-epsilon input labels read from the start state.
+    let mut times: Vec<Option<usize>> = vec![None; num_states];
+    times[start as usize] = Some(0);
+    let mut alpha = vec![f64::INFINITY; num_states];
+    alpha[start as usize] = 0.0;
+    for state in order.iter() {
+        let time = match times[*state as usize] {
+            Some(time) => time,
+            None => continue,
+        };
+        for tr in lattice.get_trs(*state)?.trs() {
+            let next_time = if tr.ilabel == EPS_LABEL {
+                time
+            } else {
+                time + 1
+            };
+            let next = tr.nextstate as usize;
+            match times[next] {
+                Some(t) if t != next_time => bail!(
+                    "kws_index : the state {} of the lattice {} is reached at several times",
+                    tr.nextstate,
+                    utterance
+                ),
+                _ => times[next] = Some(next_time),
+            }
+            alpha[next] = log_plus(alpha[next], alpha[*state as usize] + weight_of(&tr.weight));
+        }
+    }
+
+    // beta is the weight of all the paths leaving a state, word_beta the weight of the paths
+    // whose first transition has a word or which end at the state. epsilon_closures maps each
+    // state to the states reachable with epsilon output labels and the weights of the paths.
+    let mut beta = vec![f64::INFINITY; num_states];
+    let mut word_beta = vec![f64::INFINITY; num_states];
+    let mut epsilon_closures: Vec<BTreeMap<StateId, f64>> = vec![BTreeMap::new(); num_states];
+    for state in order.iter().rev() {
+        let idx = *state as usize;
+        if let Some(final_weight) = lattice.final_weight(*state)? {
+            beta[idx] = weight_of(&final_weight);
+            word_beta[idx] = beta[idx];
+        }
+        let mut closure = BTreeMap::new();
+        closure.insert(*state, 0.0);
+        for tr in lattice.get_trs(*state)?.trs() {
+            let w = weight_of(&tr.weight);
+            let next = tr.nextstate as usize;
+            beta[idx] = log_plus(beta[idx], w + beta[next]);
+            if tr.olabel == EPS_LABEL {
+                for (s, d) in epsilon_closures[next].iter() {
+                    let e = closure.entry(*s).or_insert(f64::INFINITY);
+                    *e = log_plus(*e, w + d);
+                }
+            } else {
+                word_beta[idx] = log_plus(word_beta[idx], w + beta[next]);
+            }
+        }
+        epsilon_closures[idx] = closure;
+    }
+    let total = beta[start as usize];
+    if total == f64::INFINITY {
+        return Ok(());
+    }
+
+    let mut word_edges: Vec<Vec<WordEdge>> = Vec::with_capacity(num_states);
+    let mut max_word = 0;
+    for state in lattice.states_range() {
+        let mut edges = vec![];
+        for tr in lattice.get_trs(state)?.trs() {
+            if tr.olabel == EPS_LABEL {
+                continue;
+            }
+            max_word = max_word.max(tr.olabel);
+            for (s, d) in epsilon_closures[tr.nextstate as usize].iter() {
+                if word_beta[*s as usize] < f64::INFINITY {
+                    edges.push(WordEdge {
+                        word: tr.olabel,
+                        nextstate: *s,
+                        weight: weight_of(&tr.weight) + d,
+                    });
+                }
+            }
+        }
+        word_edges.push(edges);
+    }
+
+    // Factor acceptor of the lattice : its strings are a start time marker, the words of a
+    // factor and an end time marker, weighted by the paths containing the factor at this
+    // interval divided by the total weight. Determinizing it sums the weights of the paths
+    // sharing a string without enumerating them. The state (s, k) is the state s of the
+    // lattice reached after k words of the factor.
+    let max_time = times.iter().filter_map(|t| *t).max().unwrap_or(0);
+    let start_marker = |time: usize| max_word + 1 + time as Label;
+    let end_marker = |time: usize| max_word + 2 + (max_time + time) as Label;
+    if u64::from(max_word) + 2 * (max_time as u64 + 1) >= u64::from(Label::MAX) {
+        bail!(
+            "kws_index : not enough labels to mark the times of the lattice {}",
+            utterance
+        );
+    }
+    let max_length = config.max_factor_length;
+    let factor_state = |s: StateId, k: usize| (2 + s as usize * (max_length + 1) + k) as StateId;
+    let mut factors = VectorFst::<LogWeight>::new();
+    factors.add_states(2 + num_states * (max_length + 1));
+    factors.set_start(0)?;
+    factors.set_final(1, LogWeight::new(-total as f32))?;
+    for state in lattice.states_range() {
+        let idx = state as usize;
+        let time = match times[idx] {
+            Some(time) => time,
+            None => continue,
+        };
+        if alpha[idx] < f64::INFINITY && !word_edges[idx].is_empty() {
+            let weight = LogWeight::new(alpha[idx] as f32);
+            factors.emplace_tr(
+                0,
+                start_marker(time),
+                start_marker(time),
+                weight,
+                factor_state(state, 0),
+            )?;
+        }
+        for k in 0..=max_length {
+            if k > 0 && word_beta[idx] < f64::INFINITY {
+                let weight = LogWeight::new(word_beta[idx] as f32);
+                factors.emplace_tr(
+                    factor_state(state, k),
+                    end_marker(time),
+                    end_marker(time),
+                    weight,
+                    1,
+                )?;
+            }
+            if k == max_length {
+                continue;
+            }
+            for edge in word_edges[idx].iter() {
+                let weight = LogWeight::new(edge.weight as f32);
+                factors.emplace_tr(
+                    factor_state(state, k),
+                    edge.word,
+                    edge.word,
+                    weight,
+                    factor_state(edge.nextstate, k + 1),
+                )?;
+            }
+        }
+    }
+    // The residual weights are quantized by the determinization : a small delta keeps the
+    // posteriors accurate, the default one would shift them by up to 0.05%.
+    let det_config = DeterminizeConfig::new(KWS_DELTA, DeterminizeType::DeterminizeFunctional);
+    let factors: VectorFst<LogWeight> = determinize_with_config(&factors, det_config)?;
+
+    // Each string is read by a single path of the deterministic acceptor.
+    let det_start = match factors.start() {
+        Some(start) => start,
+        None => return Ok(()),
+    };
+    let mut stack = vec![(det_start, vec![], 0.0)];
+    while let Some((state, labels, weight)) = stack.pop() {
+        if let Some(final_weight) = factors.final_weight(state)? {
+            let posterior = (-(weight + weight_of(&final_weight))).exp();
+            let entry = KwsEntry {
+                utterance,
+                start: (labels[0] - start_marker(0)) as usize,
+                end: (labels[labels.len() - 1] - end_marker(0)) as usize,
+            };
+            let p = occurrences
+                .entry(labels[1..labels.len() - 1].to_vec())
+                .or_default()
+                .entry(entry)
+                .or_insert(0.0);
+            *p += posterior;
+        }
+        for tr in factors.get_trs(state)?.trs() {
+            let mut next_labels = labels.clone();
+            next_labels.push(tr.ilabel);
+            stack.push((tr.nextstate, next_labels, weight + weight_of(&tr.weight)));
+        }
+    }
+    Ok(())
+}
+
+/// Builds a timed factor transducer index of a set of lattices for keyword search, with the
+/// default configuration.
+pub fn kws_index<W, F>(lattices: &[F]) -> Result<KwsIndex>
+where
+    W: Semiring<Type = f32>,
+    F: ExpandedFst<W>,
+{
+    kws_index_with_config(lattices, KwsIndexConfig::default())
+}
+
+/// Builds a timed factor transducer index of a set of lattices for keyword search.
+///
+/// The lattices must be acyclic, their output labels are the words and their weights are
+/// negative logarithms of probabilities, as in the tropical and log semirings. The times are
+/// counted in transitions with a non-epsilon input label, frames or phones, and must be the
+/// same on all the paths reaching a state.
+///
+/// An occurrence of a factor starts at the source state of the transition of its first word
+/// and ends at the state from which the next word is output, or at the end of the path. The
+/// lattices should then be aligned with [`lattice_align_words`](super::lattice_align_words)
+/// first. The posterior probability of an occurrence is the sum of the weights of all the
+/// paths containing it at this time interval divided by the total weight of the lattice. The
+/// posteriors of the same factor at the same time interval are summed, by determinizing in the
+/// log semiring an acceptor of the factors of each lattice marked with their time intervals :
+/// the paths of the lattices are never enumerated.
+///
+/// # Example
+/// ```
+/// # #[macro_use] extern crate rustfst;
+/// # use rustfst::algorithms::lattice::kws_index;
+/// # use rustfst::fst_impls::VectorFst;
+/// # use rustfst::semirings::{Semiring, TropicalWeight};
+/// # use rustfst::utils::acceptor;
+/// # use anyhow::Result;
+/// # fn main() -> Result<()> {
+/// let lattices : Vec<VectorFst<TropicalWeight>> = vec![fst![1, 2, 3], fst![3, 2]];
+/// let index = kws_index(&lattices)?;
+/// let hits = index.search(&[2, 3])?;
+/// assert_eq!(hits.len(), 1);
+/// assert_eq!((hits[0].utterance, hits[0].start, hits[0].end), (0, 1, 3));
+/// assert_eq!(index.search(&[3])?.len(), 2);
+/// # Ok(())
+/// # }
+/// ```
+pub fn kws_index_with_config<W, F>(lattices: &[F], config: KwsIndexConfig) -> Result<KwsIndex>
+where
+    W: Semiring<Type = f32>,
+    F: ExpandedFst<W>,
+{
+    let mut occurrences = BTreeMap::new();
+    for (utterance, lattice) in lattices.iter().enumerate() {
+        index_lattice(lattice, utterance, &config, &mut occurrences)?;
+    }
+
+    let mut fst = VectorFst::<LogWeight>::new();
+    let mut entries = vec![];
+    let start = fst.add_state();
+    let final_state = fst.add_state();
+    fst.set_start(start)?;
+    fst.set_final(final_state, LogWeight::one())?;
+    // State of the trie reached by reading each factor.
+    let mut factor_states: HashMap<Vec<Label>, StateId> = HashMap::new();
+    factor_states.insert(vec![], start);
+    for (factor, factor_occurrences) in occurrences.into_iter() {
+        let mut state = start;
+        for (idx, word) in factor.iter().enumerate() {
+            state = match factor_states.get(&factor[..=idx]) {
+                Some(s) => *s,
+                None => {
+                    let s = fst.add_state();
+                    fst.add_tr(state, Tr::new(*word, *word, LogWeight::one(), s))?;
+                    factor_states.insert(factor[..=idx].to_vec(), s);
+                    s
+                }
+            };
+        }
+        for (entry, posterior) in factor_occurrences.into_iter() {
+            if posterior < config.min_posterior {
+                continue;
+            }
+            entries.push(entry);
+            let weight = LogWeight::new(-(posterior.min(1.0).ln() as f32));
+            fst.add_tr(
+                state,
+                Tr::new(EPS_LABEL, entries.len() as Label, weight, final_state),
+            )?;
+        }
+    }
+    Ok(KwsIndex { fst, entries })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::semirings::TropicalWeight;
+
+    #[test]
+    fn test_kws_index_posteriors() -> Result<()> {
+        // a (b | c) with p(b) = 0.6 and p(c) = 0.4, a silence frame before b.
+        let mut lattice = VectorFst::<TropicalWeight>::new();
+        let s0 = lattice.add_state();
+        let s1 = lattice.add_state();
+        let s2 = lattice.add_state();
+        let s3 = lattice.add_state();
+        let s4 = lattice.add_state();
+        lattice.set_start(s0)?;
+        lattice.add_tr(s0, Tr::new(1, 1, 0.0, s1))?;
+        lattice.add_tr(s1, Tr::new(100, EPS_LABEL, -(0.6f32.ln()), s2))?;
+        lattice.add_tr(s2, Tr::new(2, 2, 0.0, s3))?;
+        lattice.add_tr(s1, Tr::new(3, 3, -(0.4f32.ln()), s4))?;
+        lattice.add_tr(s4, Tr::new(101, EPS_LABEL, 0.0, s3))?;
+        lattice.set_final(s3, TropicalWeight::one())?;
+
+        let index = kws_index(&[lattice])?;
+        let check = |keyword: &[Label], expected: &[(usize, usize, f64)]| -> Result<()> {
+            let hits = index.search(keyword)?;
+            assert_eq!(hits.len(), expected.len());
+            for (hit, (start, end, posterior)) in hits.iter().zip(expected.iter()) {
+                assert_eq!((hit.utterance, hit.start, hit.end), (0, *start, *end));
+                assert!((hit.posterior - posterior).abs() < 1e-5);
+            }
+            Ok(())
+        };
+        // The silence following a belongs to its interval on the path a b.
+        check(&[1], &[(0, 2, 0.6), (0, 1, 0.4)])?;
+        check(&[1, 2], &[(0, 3, 0.6)])?;
+        check(&[2], &[(2, 3, 0.6)])?;
+        check(&[3], &[(1, 3, 0.4)])?;
+        check(&[2, 1], &[])?;
+        Ok(())
+    }
+}
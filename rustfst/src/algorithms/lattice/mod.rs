@@ -0,0 +1,13 @@
+//! Algorithms on speech recognition lattices, with the conventions of Kaldi : the input
+//! labels of a lattice are phones and its output labels are words.
+//!
+//! Rustfst has no lattice weight : the acoustic and language model scores of the lattices
+//! are expected to be combined in a single weight beforehand.
+
+pub use self::align_words::{lattice_align_words, PhonePosition, WordBoundaryInfo};
+pub use self::kws_index::{
+    kws_index, kws_index_with_config, KwsEntry, KwsHit, KwsIndex, KwsIndexConfig,
+};
+
+mod align_words;
+mod kws_index;
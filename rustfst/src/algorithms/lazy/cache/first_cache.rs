@@ -87,4 +87,21 @@ impl<W: Semiring, Cache: FstCache<W>> FstCache<W> for FirstCache<W, Cache> {
     fn len_final_weights(&self) -> usize {
         self.cache.len_final_weights()
     }
+
+    fn evict(&self, id: StateId) {
+        let mut data = self.last_trs.lock().unwrap();
+        if matches!(&*data, Some((last_id_trs, _)) if *last_id_trs == id) {
+            *data = None;
+        }
+        let mut data = self.last_final_weight.lock().unwrap();
+        if matches!(&*data, Some((last_id_final_weight, _)) if *last_id_final_weight == id) {
+            *data = None;
+        }
+        self.cache.evict(id)
+    }
+
+    fn cached_states(&self) -> Vec<StateId> {
+        // The last trs and final weight are also inserted in the underlying cache.
+        self.cache.cached_states()
+    }
 }
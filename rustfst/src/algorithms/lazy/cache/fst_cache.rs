@@ -25,6 +25,18 @@ pub trait FstCache<W: Semiring>: Debug {
 
     fn len_trs(&self) -> usize;
     fn len_final_weights(&self) -> usize;
+
+    /// Drops the trs and the final weight cached for the state, which are computed again on
+    /// the next access. The state remains known to the cache.
+    ///
+    /// No-op by default, for the caches that keep everything they computed.
+    fn evict(&self, _id: StateId) {}
+
+    /// States whose trs or final weight are cached, i.e. those `evict` would drop, in
+    /// increasing order. Empty by default, for the caches that don't support eviction.
+    fn cached_states(&self) -> Vec<StateId> {
+        vec![]
+    }
 }
 
 impl<W: Semiring, C: FstCache<W>, CP: Deref<Target = C> + Debug> FstCache<W> for CP {
@@ -79,4 +91,12 @@ impl<W: Semiring, C: FstCache<W>, CP: Deref<Target = C> + Debug> FstCache<W> for
     fn len_final_weights(&self) -> usize {
         self.deref().len_final_weights()
     }
+
+    fn evict(&self, id: StateId) {
+        self.deref().evict(id)
+    }
+
+    fn cached_states(&self) -> Vec<StateId> {
+        self.deref().cached_states()
+    }
 }
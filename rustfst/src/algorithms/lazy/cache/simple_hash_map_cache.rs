@@ -163,6 +163,19 @@ impl<W: Semiring> FstCache<W> for SimpleHashMapCache<W> {
         let cached_data = self.final_weights.lock().unwrap();
         cached_data.data.len()
     }
+
+    fn evict(&self, id: StateId) {
+        self.trs.lock().unwrap().data.remove(&id);
+        self.final_weights.lock().unwrap().data.remove(&id);
+    }
+
+    fn cached_states(&self) -> Vec<StateId> {
+        let mut states: Vec<_> = self.trs.lock().unwrap().data.keys().cloned().collect();
+        states.extend(self.final_weights.lock().unwrap().data.keys());
+        states.sort_unstable();
+        states.dedup();
+        states
+    }
 }
 
 impl<W: SerializableSemiring> SerializableCache for SimpleHashMapCache<W> {
@@ -227,14 +240,11 @@ pub fn write_simple_hashmap_cache<F: Write, W: SerializableSemiring>(
     let num_visited_states = cache.len_trs();
     write_bin_u64(writter, num_visited_states as u64)?;
 
-    // Computed states serialization
-    for (state, cache_trs) in cache
-        .trs
-        .lock()
-        .map_err(|err| anyhow!("{}", err))?
-        .data
-        .iter()
-    {
+    // Computed states serialization, sorted by state so that the output is deterministic
+    let trs = cache.trs.lock().map_err(|err| anyhow!("{}", err))?;
+    let mut trs_data: Vec<_> = trs.data.iter().collect();
+    trs_data.sort_by_key(|(state, _)| **state);
+    for (state, cache_trs) in trs_data {
         // Write CacheTrs trs
         write_hashmap_cache_trs(writter, cache_trs, state)?;
     }
@@ -251,14 +261,14 @@ pub fn write_simple_hashmap_cache<F: Write, W: SerializableSemiring>(
     let num_final_weights = cache.len_final_weights();
     write_bin_u64(writter, num_final_weights as u64)?;
 
-    // Final weights serialization
-    for (state, final_weight) in cache
+    // Final weights serialization, sorted by state as well
+    let final_weights = cache
         .final_weights
         .lock()
-        .map_err(|err| anyhow!("{}", err))?
-        .data
-        .iter()
-    {
+        .map_err(|err| anyhow!("{}", err))?;
+    let mut final_weights_data: Vec<_> = final_weights.data.iter().collect();
+    final_weights_data.sort_by_key(|(state, _)| **state);
+    for (state, final_weight) in final_weights_data {
         // Write final weight for state
         write_hashmap_cache_final_weight(writter, final_weight, state)?;
     }
@@ -434,4 +444,27 @@ mod tests {
         assert_eq!(cache, parsed_cache);
         Ok(())
     }
+
+    #[test]
+    fn test_write_simple_hashmap_cache_deterministic() -> Result<()> {
+        // Each cache has its own random hasher : the output must not depend on it.
+        let build_cache = || {
+            let cache = SimpleHashMapCache::default();
+            cache.insert_start(Some(0));
+            for s in 0..50 {
+                let mut trs = TrsVec::<TropicalWeight>::default();
+                trs.push(Tr::new(1, 1, TropicalWeight::new(s as f32), s + 1));
+                cache.insert_trs(s, trs);
+                cache.insert_final_weight(s, Some(TropicalWeight::new(s as f32)));
+            }
+            cache
+        };
+
+        let mut buffer_a = Vec::new();
+        write_simple_hashmap_cache(&mut buffer_a, &build_cache())?;
+        let mut buffer_b = Vec::new();
+        write_simple_hashmap_cache(&mut buffer_b, &build_cache())?;
+        assert_eq!(buffer_a, buffer_b);
+        Ok(())
+    }
 }
@@ -174,6 +174,25 @@ impl<W: Semiring> FstCache<W> for SimpleVecCache<W> {
         let cached_data = self.final_weights.lock().unwrap();
         cached_data.data.len()
     }
+
+    fn evict(&self, id: StateId) {
+        let id = id as usize;
+        if let Some(e) = self.trs.lock().unwrap().data.get_mut(id) {
+            *e = CacheStatus::NotComputed;
+        }
+        if let Some(e) = self.final_weights.lock().unwrap().data.get_mut(id) {
+            *e = CacheStatus::NotComputed;
+        }
+    }
+
+    fn cached_states(&self) -> Vec<StateId> {
+        let trs = self.trs.lock().unwrap();
+        let final_weights = self.final_weights.lock().unwrap();
+        let len = std::cmp::max(trs.data.len(), final_weights.data.len());
+        (0..len as StateId)
+            .filter(|s| trs.get(*s).is_computed() || final_weights.get(*s).is_computed())
+            .collect()
+    }
 }
 
 impl<W: SerializableSemiring> SerializableCache for SimpleVecCache<W> {
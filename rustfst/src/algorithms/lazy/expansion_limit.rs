@@ -0,0 +1,24 @@
+use std::error::Error;
+use std::fmt;
+
+/// Error returned by a lazy FST when expanding a state would make the number of states known
+/// to its cache exceed the limit set with `with_max_expanded_states`.
+///
+/// It can be retrieved from an `anyhow::Error` with `downcast_ref::<ExpansionLimitError>()`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExpansionLimitError {
+    /// Limit on the number of states.
+    pub max_expanded_states: usize,
+}
+
+impl fmt::Display for ExpansionLimitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Lazy FST expansion stopped after {} states : the FST is probably infinite",
+            self.max_expanded_states
+        )
+    }
+}
+
+impl Error for ExpansionLimitError {}
@@ -0,0 +1,109 @@
+use std::fmt;
+use std::mem::size_of;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use crate::{StateId, Tr};
+
+/// Statistics reported to an [`ExpansionObserver`] each time a lazy FST expands a state.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ExpansionEvent {
+    /// State that has just been expanded.
+    pub state: StateId,
+    /// Number of transitions leaving this state.
+    pub num_trs: usize,
+    /// Number of states expanded since the observer has been set, this one included.
+    pub num_expanded_states: usize,
+    /// Number of transitions computed since the observer has been set.
+    pub num_expanded_trs: usize,
+    /// Number of states known to the cache, i.e. the size of the cache.
+    pub num_known_states: usize,
+    /// Time elapsed since the observer has been set.
+    pub elapsed: Duration,
+    /// Approximate memory used by the transitions computed since the observer has been set.
+    pub expanded_trs_bytes: usize,
+}
+
+impl ExpansionEvent {
+    /// Average number of states expanded per second since the observer has been set.
+    pub fn states_per_sec(&self) -> f64 {
+        let secs = self.elapsed.as_secs_f64();
+        if secs > 0.0 {
+            self.num_expanded_states as f64 / secs
+        } else {
+            0.0
+        }
+    }
+}
+
+/// Sink of the expansion events of a lazy FST, set with `with_expansion_observer`, for
+/// instance to export the growth of an on-the-fly decoding graph to a metrics system.
+///
+/// It is called synchronously during the expansion, which it slows down : expensive
+/// processing should be sampled or deferred. Any `Fn(&ExpansionEvent) + Send + Sync` closure
+/// is an observer.
+pub trait ExpansionObserver: Send + Sync {
+    fn on_expansion(&self, event: &ExpansionEvent);
+}
+
+impl<F: Fn(&ExpansionEvent) + Send + Sync> ExpansionObserver for F {
+    fn on_expansion(&self, event: &ExpansionEvent) {
+        self(event)
+    }
+}
+
+/// Observer of a lazy FST along with the counters of the expansion.
+pub(crate) struct ObserverState {
+    observer: Arc<dyn ExpansionObserver>,
+    start: Instant,
+    num_expanded_states: AtomicUsize,
+    num_expanded_trs: AtomicUsize,
+}
+
+impl ObserverState {
+    pub(crate) fn new(observer: Arc<dyn ExpansionObserver>) -> Self {
+        Self {
+            observer,
+            start: Instant::now(),
+            num_expanded_states: AtomicUsize::new(0),
+            num_expanded_trs: AtomicUsize::new(0),
+        }
+    }
+
+    pub(crate) fn report<W>(&self, state: StateId, num_trs: usize, num_known_states: usize) {
+        let num_expanded_states = self.num_expanded_states.fetch_add(1, Ordering::Relaxed) + 1;
+        let num_expanded_trs =
+            self.num_expanded_trs.fetch_add(num_trs, Ordering::Relaxed) + num_trs;
+        self.observer.on_expansion(&ExpansionEvent {
+            state,
+            num_trs,
+            num_expanded_states,
+            num_expanded_trs,
+            num_known_states,
+            elapsed: self.start.elapsed(),
+            expanded_trs_bytes: num_expanded_trs * size_of::<Tr<W>>(),
+        });
+    }
+}
+
+impl Clone for ObserverState {
+    fn clone(&self) -> Self {
+        Self {
+            observer: Arc::clone(&self.observer),
+            start: self.start,
+            num_expanded_states: AtomicUsize::new(self.num_expanded_states.load(Ordering::Relaxed)),
+            num_expanded_trs: AtomicUsize::new(self.num_expanded_trs.load(Ordering::Relaxed)),
+        }
+    }
+}
+
+impl fmt::Debug for ObserverState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ObserverState")
+            .field("start", &self.start)
+            .field("num_expanded_states", &self.num_expanded_states)
+            .field("num_expanded_trs", &self.num_expanded_trs)
+            .finish()
+    }
+}
@@ -1,8 +1,8 @@
-use std::collections::VecDeque;
+use std::collections::{HashSet, VecDeque};
 use std::fmt::Debug;
 use std::iter::{repeat, Map, Repeat, Zip};
 use std::marker::PhantomData;
-use std::ops::Deref;
+use std::ops::{Deref, Range};
 use std::path::Path;
 use std::sync::Arc;
 
@@ -12,6 +12,8 @@ use unsafe_unwrap::UnsafeUnwrap;
 
 use crate::algorithms::lazy::cache::CacheStatus;
 use crate::algorithms::lazy::fst_op::{AccessibleOpState, FstOp, SerializableOpState};
+use crate::algorithms::lazy::lazy_fst_utils::{self, ExpansionControl};
+use crate::algorithms::lazy::ExpansionObserver;
 use crate::algorithms::lazy::{FstCache, SerializableCache};
 use crate::fst_properties::FstProperties;
 use crate::fst_traits::{
@@ -27,6 +29,7 @@ pub struct LazyFst<W: Semiring, Op: FstOp<W>, Cache> {
     w: PhantomData<W>,
     isymt: Option<Arc<SymbolTable>>,
     osymt: Option<Arc<SymbolTable>>,
+    expansion: ExpansionControl,
 }
 
 impl<W: Semiring, Op: FstOp<W>, Cache: FstCache<W>> CoreFst<W> for LazyFst<W, Op, Cache> {
@@ -45,19 +48,19 @@ impl<W: Semiring, Op: FstOp<W>, Cache: FstCache<W>> CoreFst<W> for LazyFst<W, Op
     }
 
     fn final_weight(&self, state_id: StateId) -> Result<Option<W>> {
-        match self.cache.get_final_weight(state_id) {
-            CacheStatus::Computed(final_weight) => Ok(final_weight),
-            CacheStatus::NotComputed => {
-                let final_weight = self.op.compute_final_weight(state_id)?;
-                self.cache
-                    .insert_final_weight(state_id, final_weight.clone());
-                Ok(final_weight)
-            }
-        }
+        self.compute_final_weight(state_id, true)
     }
 
+    /// Not subject to the limit set with `with_max_expanded_states`. Panics if the
+    /// computation of the final weight fails.
     unsafe fn final_weight_unchecked(&self, state_id: StateId) -> Option<W> {
-        self.final_weight(state_id).unsafe_unwrap()
+        self.compute_final_weight(state_id, false)
+            .unwrap_or_else(|e| {
+                panic!(
+                    "Failed to compute the final weight of state {} : {}",
+                    state_id, e
+                )
+            })
     }
 
     fn num_trs(&self, s: StateId) -> Result<usize> {
@@ -71,18 +74,14 @@ impl<W: Semiring, Op: FstOp<W>, Cache: FstCache<W>> CoreFst<W> for LazyFst<W, Op
     }
 
     fn get_trs(&self, state_id: StateId) -> Result<Self::TRS> {
-        match self.cache.get_trs(state_id) {
-            CacheStatus::Computed(trs) => Ok(trs),
-            CacheStatus::NotComputed => {
-                let trs = self.op.compute_trs(state_id)?;
-                self.cache.insert_trs(state_id, trs.shallow_clone());
-                Ok(trs)
-            }
-        }
+        self.compute_trs(state_id, true)
     }
 
+    /// Not subject to the limit set with `with_max_expanded_states`. Panics if the
+    /// computation of the trs fails.
     unsafe fn get_trs_unchecked(&self, state_id: StateId) -> Self::TRS {
-        self.get_trs(state_id).unsafe_unwrap()
+        self.compute_trs(state_id, false)
+            .unwrap_or_else(|e| panic!("Failed to compute the trs of state {} : {}", state_id, e))
     }
 
     fn properties(&self) -> FstProperties {
@@ -110,6 +109,9 @@ where
 {
     type Iter = StatesIteratorLazyFst<'a, Self>;
 
+    /// Expands each state when it is reached : the iteration walks the whole FST and never
+    /// ends if it is infinite. Use `known_states_range` to walk only the states already known
+    /// to the cache.
     fn states_iter(&'a self) -> Self::Iter {
         self.start();
         StatesIteratorLazyFst { fst: self, s: 0 }
@@ -158,6 +160,7 @@ where
 {
     type FstIter = MapIter<'a, W, Op, Cache, Self, Self::TRS>;
 
+    /// Panics if the expansion of a state fails. Use `try_fst_iter` to get the errors.
     fn fst_iter(&'a self) -> Self::FstIter {
         let it = repeat(self);
         izip!(self.states_iter(), it).map(Box::new(|(state_id, p): (StateId, &'a Self)| {
@@ -220,9 +223,147 @@ where
             isymt,
             osymt,
             w: PhantomData,
+            expansion: ExpansionControl::default(),
         }
     }
 
+    /// Limits the number of states known to the cache. Once the limit is exceeded, the
+    /// expansion of a new state fails with an [`ExpansionLimitError`] instead of running
+    /// forever on an infinite FST. No limit is set by default.
+    ///
+    /// [`ExpansionLimitError`]: crate::algorithms::lazy::ExpansionLimitError
+    pub fn with_max_expanded_states(mut self, max_expanded_states: usize) -> Self {
+        self.expansion.max_expanded_states = Some(max_expanded_states);
+        self
+    }
+
+    pub fn set_max_expanded_states(&mut self, max_expanded_states: Option<usize>) {
+        self.expansion.max_expanded_states = max_expanded_states;
+    }
+
+    pub fn max_expanded_states(&self) -> Option<usize> {
+        self.expansion.max_expanded_states
+    }
+
+    /// Reports each expansion of a state to `observer`, along with the number of states and
+    /// transitions expanded so far and the size of the cache. See [`ExpansionObserver`].
+    pub fn with_expansion_observer<O: ExpansionObserver + 'static>(mut self, observer: O) -> Self {
+        self.expansion.set_observer(Some(Arc::new(observer)));
+        self
+    }
+
+    /// Sets or removes the observer. The statistics restart from zero.
+    pub fn set_expansion_observer(&mut self, observer: Option<Arc<dyn ExpansionObserver>>) {
+        self.expansion.set_observer(observer);
+    }
+
+    /// Range of the states known to the cache : the start state and the destinations of the
+    /// transitions already computed. Contrary to `states_iter`, which expands each state when
+    /// it is reached and thus walks the whole FST, nothing is expanded : the range ends on the
+    /// frontier of the expansion.
+    pub fn known_states_range(&self) -> Range<StateId> {
+        lazy_fst_utils::known_states_range(&self.cache)
+    }
+
+    /// Iterates over all the states, expanding each of them when it is reached, as `fst_iter`
+    /// does, but returns the error of the expansion of a state instead of panicking. The
+    /// iteration ends after the first error.
+    pub fn try_fst_iter(&self) -> impl Iterator<Item = Result<FstIterData<W, TrsVec<W>>>> + '_ {
+        lazy_fst_utils::try_fst_iter(self, &self.cache)
+    }
+
+    /// Expands all the states reachable from the start state, after which
+    /// `known_states_range` covers the whole FST. Never returns if the FST is infinite and no
+    /// limit has been set with `with_max_expanded_states`.
+    pub fn expand_all_states(&self) -> Result<()> {
+        lazy_fst_utils::expand_all_states(self)
+    }
+
+    /// Turns the part of the Lazy FST expanded so far into a static FST, without expanding any
+    /// new state, e.g. to keep the subgraph explored by a search.
+    ///
+    /// The state ids are preserved : all the states known to the cache are created, the
+    /// transitions and final weights not computed yet being left empty. Those dead ends can be
+    /// removed with `connect`, at the cost of renumbering the states.
+    pub fn freeze_visited<F2: MutableFst<W> + AllocableFst<W>>(&self) -> Result<F2> {
+        lazy_fst_utils::freeze_visited(&self.cache, self.isymt.as_ref(), self.osymt.as_ref())
+    }
+
+    /// Evicts from the cache the states that can't be reached from the `live_states` through
+    /// the transitions already expanded, e.g. the states behind the active frontier of a
+    /// streaming search. Returns the number of states evicted. Nothing is expanded.
+    ///
+    /// The state ids are preserved : an evicted state is expanded again if it is accessed
+    /// afterwards. Only the transitions and final weights are dropped, the state table of the
+    /// operation keeps growing, as does the number of states counted by
+    /// `with_max_expanded_states`. Nothing is evicted if the cache doesn't support it, see
+    /// [`FstCache::cached_states`].
+    pub fn trim_cache(&self, live_states: &[StateId]) -> usize {
+        let mut live = HashSet::new();
+        let mut queue = vec![];
+        for &s in live_states {
+            if live.insert(s) {
+                queue.push(s);
+            }
+        }
+        while let Some(s) = queue.pop() {
+            if let CacheStatus::Computed(trs) = self.cache.get_trs(s) {
+                for tr in trs.trs() {
+                    if live.insert(tr.nextstate) {
+                        queue.push(tr.nextstate);
+                    }
+                }
+            }
+        }
+        let mut num_evicted = 0;
+        for s in self.cache.cached_states() {
+            if !live.contains(&s) {
+                self.cache.evict(s);
+                num_evicted += 1;
+            }
+        }
+        num_evicted
+    }
+
+    fn compute_final_weight(&self, state_id: StateId, check_limit: bool) -> Result<Option<W>> {
+        match self.cache.get_final_weight(state_id) {
+            CacheStatus::Computed(final_weight) => Ok(final_weight),
+            CacheStatus::NotComputed => {
+                if check_limit {
+                    self.check_expansion_limit()?;
+                }
+                let final_weight = self.op.compute_final_weight(state_id)?;
+                self.cache
+                    .insert_final_weight(state_id, final_weight.clone());
+                Ok(final_weight)
+            }
+        }
+    }
+
+    fn compute_trs(&self, state_id: StateId, check_limit: bool) -> Result<TrsVec<W>> {
+        match self.cache.get_trs(state_id) {
+            CacheStatus::Computed(trs) => Ok(trs),
+            CacheStatus::NotComputed => {
+                if check_limit {
+                    self.check_expansion_limit()?;
+                }
+                let trs = self.op.compute_trs(state_id)?;
+                self.cache.insert_trs(state_id, trs.shallow_clone());
+                self.report_expansion(state_id, trs.len());
+                Ok(trs)
+            }
+        }
+    }
+
+    fn report_expansion(&self, state: StateId, num_trs: usize) {
+        self.expansion
+            .report::<W>(state, num_trs, self.cache.num_known_states());
+    }
+
+    fn check_expansion_limit(&self) -> Result<()> {
+        self.expansion.check_limit(self.cache.num_known_states())
+    }
+
     /// Turns the Lazy FST into a static one.
     pub fn compute<F2: MutableFst<W> + AllocableFst<W>>(&self) -> Result<F2> {
         let start_state = self.start();
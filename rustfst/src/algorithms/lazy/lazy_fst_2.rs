@@ -1,5 +1,7 @@
+use std::collections::{HashSet, VecDeque};
 use std::iter::{repeat, Map, Repeat, Zip};
 use std::marker::PhantomData;
+use std::ops::Range;
 use std::sync::Arc;
 
 use anyhow::Result;
@@ -7,12 +9,12 @@ use itertools::izip;
 use unsafe_unwrap::UnsafeUnwrap;
 
 use crate::algorithms::lazy::fst_op_2::FstOp2;
-use crate::algorithms::lazy::{CacheStatus, FstCache};
+use crate::algorithms::lazy::lazy_fst_utils::{self, ExpansionControl};
+use crate::algorithms::lazy::{CacheStatus, ExpansionObserver, FstCache};
 use crate::fst_properties::FstProperties;
 use crate::fst_traits::{CoreFst, Fst, FstIterData, FstIterator, MutableFst, StateIterator};
 use crate::semirings::Semiring;
 use crate::{StateId, SymbolTable, Trs, TrsVec};
-use std::collections::{HashSet, VecDeque};
 
 #[derive(Debug)]
 pub struct LazyFst2<W: Semiring, Op: FstOp2<W>, Cache: FstCache<W>> {
@@ -21,6 +23,7 @@ pub struct LazyFst2<W: Semiring, Op: FstOp2<W>, Cache: FstCache<W>> {
     w: PhantomData<W>,
     isymt: Option<Arc<SymbolTable>>,
     osymt: Option<Arc<SymbolTable>>,
+    expansion: ExpansionControl,
 }
 
 impl<W: Semiring, Op: FstOp2<W>, Cache: FstCache<W>> CoreFst<W> for LazyFst2<W, Op, Cache> {
@@ -39,20 +42,19 @@ impl<W: Semiring, Op: FstOp2<W>, Cache: FstCache<W>> CoreFst<W> for LazyFst2<W,
     }
 
     fn final_weight(&self, state_id: StateId) -> Result<Option<W>> {
-        match self.cache.get_final_weight(state_id) {
-            CacheStatus::Computed(final_weight) => Ok(final_weight),
-            CacheStatus::NotComputed => {
-                let (trs, final_weight) = self.op.compute_trs_and_final_weight(state_id)?;
-                self.cache.insert_trs(state_id, trs);
-                self.cache
-                    .insert_final_weight(state_id, final_weight.clone());
-                Ok(final_weight)
-            }
-        }
+        self.compute_final_weight(state_id, true)
     }
 
+    /// Not subject to the limit set with `with_max_expanded_states`. Panics if the
+    /// computation of the final weight fails.
     unsafe fn final_weight_unchecked(&self, state_id: StateId) -> Option<W> {
-        self.final_weight(state_id).unsafe_unwrap()
+        self.compute_final_weight(state_id, false)
+            .unwrap_or_else(|e| {
+                panic!(
+                    "Failed to compute the final weight of state {} : {}",
+                    state_id, e
+                )
+            })
     }
 
     fn num_trs(&self, s: StateId) -> Result<usize> {
@@ -66,19 +68,14 @@ impl<W: Semiring, Op: FstOp2<W>, Cache: FstCache<W>> CoreFst<W> for LazyFst2<W,
     }
 
     fn get_trs(&self, state_id: StateId) -> Result<Self::TRS> {
-        match self.cache.get_trs(state_id) {
-            CacheStatus::Computed(trs) => Ok(trs),
-            CacheStatus::NotComputed => {
-                let (trs, final_weight) = self.op.compute_trs_and_final_weight(state_id)?;
-                self.cache.insert_trs(state_id, trs.shallow_clone());
-                self.cache.insert_final_weight(state_id, final_weight);
-                Ok(trs)
-            }
-        }
+        self.compute_trs(state_id, true)
     }
 
+    /// Not subject to the limit set with `with_max_expanded_states`. Panics if the
+    /// computation of the trs fails.
     unsafe fn get_trs_unchecked(&self, state_id: StateId) -> Self::TRS {
-        self.get_trs(state_id).unsafe_unwrap()
+        self.compute_trs(state_id, false)
+            .unwrap_or_else(|e| panic!("Failed to compute the trs of state {} : {}", state_id, e))
     }
 
     fn properties(&self) -> FstProperties {
@@ -106,6 +103,9 @@ where
 {
     type Iter = StatesIteratorLazyFst<'a, Self>;
 
+    /// Expands each state when it is reached : the iteration walks the whole FST and never
+    /// ends if it is infinite. Use `known_states_range` to walk only the states already known
+    /// to the cache.
     fn states_iter(&'a self) -> Self::Iter {
         self.start();
         StatesIteratorLazyFst { fst: self, s: 0 }
@@ -154,6 +154,7 @@ where
 {
     type FstIter = MapIter<'a, W, Op, Cache, Self, Self::TRS>;
 
+    /// Panics if the expansion of a state fails. Use `try_fst_iter` to get the errors.
     fn fst_iter(&'a self) -> Self::FstIter {
         let it = repeat(self);
         izip!(self.states_iter(), it).map(Box::new(|(state_id, p): (StateId, &'a Self)| {
@@ -216,9 +217,114 @@ where
             isymt,
             osymt,
             w: PhantomData,
+            expansion: ExpansionControl::default(),
+        }
+    }
+
+    /// Limits the number of states known to the cache. Once the limit is exceeded, the
+    /// expansion of a new state fails with an [`ExpansionLimitError`] instead of running
+    /// forever on an infinite FST. No limit is set by default.
+    ///
+    /// [`ExpansionLimitError`]: crate::algorithms::lazy::ExpansionLimitError
+    pub fn with_max_expanded_states(mut self, max_expanded_states: usize) -> Self {
+        self.expansion.max_expanded_states = Some(max_expanded_states);
+        self
+    }
+
+    pub fn set_max_expanded_states(&mut self, max_expanded_states: Option<usize>) {
+        self.expansion.max_expanded_states = max_expanded_states;
+    }
+
+    pub fn max_expanded_states(&self) -> Option<usize> {
+        self.expansion.max_expanded_states
+    }
+
+    /// Reports each expansion of a state to `observer`, along with the number of states and
+    /// transitions expanded so far and the size of the cache. See [`ExpansionObserver`].
+    pub fn with_expansion_observer<O: ExpansionObserver + 'static>(mut self, observer: O) -> Self {
+        self.expansion.set_observer(Some(Arc::new(observer)));
+        self
+    }
+
+    /// Sets or removes the observer. The statistics restart from zero.
+    pub fn set_expansion_observer(&mut self, observer: Option<Arc<dyn ExpansionObserver>>) {
+        self.expansion.set_observer(observer);
+    }
+
+    /// Range of the states known to the cache : the start state and the destinations of the
+    /// transitions already computed. Contrary to `states_iter`, which expands each state when
+    /// it is reached and thus walks the whole FST, nothing is expanded : the range ends on the
+    /// frontier of the expansion.
+    pub fn known_states_range(&self) -> Range<StateId> {
+        lazy_fst_utils::known_states_range(&self.cache)
+    }
+
+    /// Iterates over all the states, expanding each of them when it is reached, as `fst_iter`
+    /// does, but returns the error of the expansion of a state instead of panicking. The
+    /// iteration ends after the first error.
+    pub fn try_fst_iter(&self) -> impl Iterator<Item = Result<FstIterData<W, TrsVec<W>>>> + '_ {
+        lazy_fst_utils::try_fst_iter(self, &self.cache)
+    }
+
+    /// Expands all the states reachable from the start state, after which
+    /// `known_states_range` covers the whole FST. Never returns if the FST is infinite and no
+    /// limit has been set with `with_max_expanded_states`.
+    pub fn expand_all_states(&self) -> Result<()> {
+        lazy_fst_utils::expand_all_states(self)
+    }
+
+    /// Turns the part of the Lazy FST expanded so far into a static FST, without expanding any
+    /// new state, e.g. to keep the subgraph explored by a search.
+    ///
+    /// The state ids are preserved : all the states known to the cache are created, the
+    /// transitions and final weights not computed yet being left empty. Those dead ends can be
+    /// removed with `connect`, at the cost of renumbering the states.
+    pub fn freeze_visited<F2: MutableFst<W>>(&self) -> Result<F2> {
+        lazy_fst_utils::freeze_visited(&self.cache, self.isymt.as_ref(), self.osymt.as_ref())
+    }
+
+    fn compute_final_weight(&self, state_id: StateId, check_limit: bool) -> Result<Option<W>> {
+        match self.cache.get_final_weight(state_id) {
+            CacheStatus::Computed(final_weight) => Ok(final_weight),
+            CacheStatus::NotComputed => {
+                if check_limit {
+                    self.check_expansion_limit()?;
+                }
+                let (trs, final_weight) = self.op.compute_trs_and_final_weight(state_id)?;
+                self.report_expansion(state_id, trs.len());
+                self.cache.insert_trs(state_id, trs);
+                self.cache
+                    .insert_final_weight(state_id, final_weight.clone());
+                Ok(final_weight)
+            }
         }
     }
 
+    fn compute_trs(&self, state_id: StateId, check_limit: bool) -> Result<TrsVec<W>> {
+        match self.cache.get_trs(state_id) {
+            CacheStatus::Computed(trs) => Ok(trs),
+            CacheStatus::NotComputed => {
+                if check_limit {
+                    self.check_expansion_limit()?;
+                }
+                let (trs, final_weight) = self.op.compute_trs_and_final_weight(state_id)?;
+                self.cache.insert_trs(state_id, trs.shallow_clone());
+                self.cache.insert_final_weight(state_id, final_weight);
+                self.report_expansion(state_id, trs.len());
+                Ok(trs)
+            }
+        }
+    }
+
+    fn report_expansion(&self, state: StateId, num_trs: usize) {
+        self.expansion
+            .report::<W>(state, num_trs, self.cache.num_known_states());
+    }
+
+    fn check_expansion_limit(&self) -> Result<()> {
+        self.expansion.check_limit(self.cache.num_known_states())
+    }
+
     /// Turns the Lazy FST into a static one.
     pub fn compute<F2: MutableFst<W>>(&self) -> Result<F2> {
         let start_state = self.start();
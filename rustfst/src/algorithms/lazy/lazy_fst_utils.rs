@@ -0,0 +1,138 @@
+use std::collections::{HashSet, VecDeque};
+use std::ops::Range;
+use std::sync::Arc;
+
+use anyhow::Result;
+
+use crate::algorithms::lazy::expansion_observer::ObserverState;
+use crate::algorithms::lazy::{CacheStatus, ExpansionLimitError, ExpansionObserver, FstCache};
+use crate::fst_traits::{CoreFst, FstIterData, MutableFst};
+use crate::semirings::Semiring;
+use crate::{StateId, SymbolTable, Trs, TrsVec};
+
+/// Limit on the number of states and observer of the expansion of a lazy FST.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct ExpansionControl {
+    pub(crate) max_expanded_states: Option<usize>,
+    observer: Option<ObserverState>,
+}
+
+impl ExpansionControl {
+    /// Sets or removes the observer. The statistics restart from zero.
+    pub(crate) fn set_observer(&mut self, observer: Option<Arc<dyn ExpansionObserver>>) {
+        self.observer = observer.map(ObserverState::new);
+    }
+
+    pub(crate) fn check_limit(&self, num_known_states: usize) -> Result<()> {
+        match self.max_expanded_states {
+            Some(max_expanded_states) if num_known_states > max_expanded_states => {
+                Err(ExpansionLimitError {
+                    max_expanded_states,
+                }
+                .into())
+            }
+            _ => Ok(()),
+        }
+    }
+
+    pub(crate) fn report<W>(&self, state: StateId, num_trs: usize, num_known_states: usize) {
+        if let Some(observer) = &self.observer {
+            observer.report::<W>(state, num_trs, num_known_states);
+        }
+    }
+}
+
+/// Range of the states known to the cache, see `LazyFst::known_states_range`.
+pub(crate) fn known_states_range<W: Semiring, C: FstCache<W>>(cache: &C) -> Range<StateId> {
+    0..(cache.num_known_states() as StateId)
+}
+
+/// Iterates over all the states of a lazy FST, see `LazyFst::try_fst_iter`.
+pub(crate) fn try_fst_iter<'a, W, F, C>(
+    fst: &'a F,
+    cache: &'a C,
+) -> impl Iterator<Item = Result<FstIterData<W, TrsVec<W>>>> + 'a
+where
+    W: Semiring,
+    F: CoreFst<W, TRS = TrsVec<W>>,
+    C: FstCache<W>,
+{
+    fst.start();
+    let mut state_id: StateId = 0;
+    let mut failed = false;
+    std::iter::from_fn(move || {
+        if failed || state_id as usize >= cache.num_known_states() {
+            return None;
+        }
+        let data = fst.get_trs(state_id).and_then(|trs| {
+            Ok(FstIterData {
+                state_id,
+                num_trs: trs.len(),
+                trs,
+                final_weight: fst.final_weight(state_id)?,
+            })
+        });
+        failed = data.is_err();
+        state_id += 1;
+        Some(data)
+    })
+}
+
+/// Expands all the states reachable from the start state, see `LazyFst::expand_all_states`.
+pub(crate) fn expand_all_states<W: Semiring, F: CoreFst<W>>(fst: &F) -> Result<()> {
+    let start = match fst.start() {
+        Some(s) => s,
+        None => return Ok(()),
+    };
+    let mut visited_states = HashSet::new();
+    visited_states.insert(start);
+    let mut queue = VecDeque::new();
+    queue.push_back(start);
+    while let Some(s) = queue.pop_front() {
+        for tr in fst.get_trs(s)?.trs() {
+            if visited_states.insert(tr.nextstate) {
+                queue.push_back(tr.nextstate);
+            }
+        }
+        fst.final_weight(s)?;
+    }
+    Ok(())
+}
+
+/// Turns the part of a lazy FST held by its cache into a static FST, see
+/// `LazyFst::freeze_visited`.
+pub(crate) fn freeze_visited<W, C, F2>(
+    cache: &C,
+    isymt: Option<&Arc<SymbolTable>>,
+    osymt: Option<&Arc<SymbolTable>>,
+) -> Result<F2>
+where
+    W: Semiring,
+    C: FstCache<W>,
+    F2: MutableFst<W>,
+{
+    let mut fst_out = F2::new();
+    let start_state = match cache.get_start() {
+        CacheStatus::Computed(Some(s)) => s,
+        _ => return Ok(fst_out),
+    };
+    fst_out.add_states(cache.num_known_states());
+    fst_out.set_start(start_state)?;
+    for s in 0..(fst_out.num_states() as StateId) {
+        if let CacheStatus::Computed(trs) = cache.get_trs(s) {
+            unsafe { fst_out.set_trs_unchecked(s, trs.trs().to_vec()) };
+        }
+        if let CacheStatus::Computed(Some(f_w)) = cache.get_final_weight(s) {
+            fst_out.set_final(s, f_w)?;
+        }
+    }
+    fst_out.compute_and_update_properties_all()?;
+
+    if let Some(isymt) = isymt {
+        fst_out.set_input_symbols(Arc::clone(isymt));
+    }
+    if let Some(osymt) = osymt {
+        fst_out.set_output_symbols(Arc::clone(osymt));
+    }
+    Ok(fst_out)
+}
@@ -1,14 +1,19 @@
 pub use self::cache::*;
+pub use expansion_limit::ExpansionLimitError;
+pub use expansion_observer::{ExpansionEvent, ExpansionObserver};
 pub use fst_op::{AccessibleOpState, FstOp, SerializableOpState};
 pub use fst_op_2::FstOp2;
 pub use lazy_fst::{LazyFst, SerializableLazyFst};
 pub use lazy_fst_2::LazyFst2;
 pub use state_table::StateTable;
 
+mod expansion_limit;
+mod expansion_observer;
 mod fst_op;
 mod fst_op_2;
 mod lazy_fst;
 mod lazy_fst_2;
+mod lazy_fst_utils;
 mod state_table;
 
 pub mod cache;
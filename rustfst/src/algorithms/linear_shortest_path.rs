@@ -0,0 +1,324 @@
+use std::collections::VecDeque;
+
+use anyhow::Result;
+
+use crate::algorithms::compose::compose;
+use crate::algorithms::shortest_path;
+use crate::algorithms::shortest_path::natural_less;
+use crate::fst_impls::VectorFst;
+use crate::fst_properties::mutable_properties::shortest_path_properties;
+use crate::fst_properties::FstProperties;
+use crate::fst_traits::{ExpandedFst, MutableFst};
+use crate::semirings::{Semiring, SemiringProperties, WeaklyDivisibleSemiring, WeightQuantize};
+use crate::{Label, StateId, Tr, Trs, EPS_LABEL};
+
+/// State of `fst` reached after reading a prefix of the linear chain, along with the best
+/// path reaching it.
+struct Token<W> {
+    state: StateId,
+    weight: W,
+    /// Previous token, its position in the chain and the index of the transition followed.
+    parent: Option<(usize, usize, usize)>,
+}
+
+/// Labels and weights of the transitions of a linear chain acceptor, and its final weight.
+type LinearChain<W> = (Vec<(Label, W)>, W);
+
+/// Labels and weights of the transitions of a linear chain acceptor without epsilons, and its
+/// final weight. `None` if the FST is not such a chain.
+fn linear_chain<W: Semiring, F: ExpandedFst<W>>(fst: &F) -> Result<Option<LinearChain<W>>> {
+    let props = fst.properties();
+    if props.intersects(FstProperties::NOT_STRING | FstProperties::NOT_ACCEPTOR) {
+        return Ok(None);
+    }
+    let mut state = match fst.start() {
+        Some(s) => s,
+        None => return Ok(None),
+    };
+    let mut chain = vec![];
+    loop {
+        let trs = fst.get_trs(state)?;
+        match (trs.trs(), fst.final_weight(state)?) {
+            ([], Some(final_weight)) => return Ok(Some((chain, final_weight))),
+            ([tr], None)
+                if tr.ilabel == tr.olabel
+                    && tr.ilabel != EPS_LABEL
+                    && chain.len() < fst.num_states() =>
+            {
+                chain.push((tr.ilabel, tr.weight.clone()));
+                state = tr.nextstate;
+            }
+            _ => return Ok(None),
+        }
+    }
+}
+
+/// Computes the single shortest path of the composition of `linear` with `fst`, for instance
+/// to align one utterance with a decoding graph.
+///
+/// When `linear` is a linear chain acceptor without epsilons, the composition is never built :
+/// the paths of `fst` are searched position by position along the chain, the states reached at
+/// each position being tracked with arrays indexed by the states of `fst` rather than with the
+/// hash maps of the composition. Otherwise, or if the semiring isn't commutative, this falls
+/// back to `shortest_path(compose(linear, fst))`, which requires `fst` to be sorted on its
+/// input labels.
+///
+/// The result is the same as the one of the composition followed by `shortest_path`, up to the
+/// choice between paths of equal weights : the input labels are those of the chain, or
+/// epsilon on the input epsilon transitions of `fst`.
+///
+/// # Example
+/// ```
+/// # #[macro_use] extern crate rustfst;
+/// # use rustfst::utils::{acceptor, transducer};
+/// # use rustfst::semirings::{Semiring, TropicalWeight};
+/// # use rustfst::fst_impls::VectorFst;
+/// # use rustfst::fst_traits::{CoreFst, MutableFst, Fst};
+/// # use rustfst::algorithms::linear_compose_shortest_path;
+/// # use rustfst::Tr;
+/// # use anyhow::Result;
+/// # fn main() -> Result<()> {
+/// let linear: VectorFst<TropicalWeight> = acceptor(&[1, 1], TropicalWeight::one());
+///
+/// let mut fst = VectorFst::<TropicalWeight>::new();
+/// let s0 = fst.add_state();
+/// fst.set_start(s0)?;
+/// fst.set_final(s0, TropicalWeight::one())?;
+/// fst.add_tr(s0, Tr::new(1, 2, 1.0, s0))?;
+/// fst.add_tr(s0, Tr::new(1, 3, 0.5, s0))?;
+///
+/// let path: VectorFst<TropicalWeight> = linear_compose_shortest_path(&linear, &fst)?;
+/// let path = path.paths_iter().next().unwrap();
+/// assert_eq!(path.olabels, vec![3, 3]);
+/// assert_eq!(path.weight, TropicalWeight::new(1.0));
+/// # Ok(())
+/// # }
+/// ```
+pub fn linear_compose_shortest_path<W, F1, F2, FO>(linear: &F1, fst: &F2) -> Result<FO>
+where
+    F1: ExpandedFst<W>,
+    F2: ExpandedFst<W>,
+    FO: MutableFst<W>,
+    W: Semiring
+        + WeightQuantize
+        + Into<<W as Semiring>::ReverseWeight>
+        + From<<W as Semiring>::ReverseWeight>,
+    <W as Semiring>::ReverseWeight: WeightQuantize + WeaklyDivisibleSemiring,
+{
+    let chain = if W::properties().contains(SemiringProperties::COMMUTATIVE) {
+        linear_chain(linear)?
+    } else {
+        None
+    };
+    let (chain, linear_final_weight) = match chain {
+        Some(c) => c,
+        None => {
+            let composed: VectorFst<W> = compose::<W, F1, F2, VectorFst<W>, _, _>(linear, fst)?;
+            return shortest_path(&composed);
+        }
+    };
+    if !W::properties().contains(SemiringProperties::PATH | SemiringProperties::RIGHT_SEMIRING) {
+        bail!(
+            "linear_compose_shortest_path : Weight needs to have the path property and be right \
+             distributive"
+        )
+    }
+
+    let mut ofst = FO::new();
+    let start = match fst.start() {
+        Some(s) => s,
+        None => return Ok(ofst),
+    };
+
+    // Index of the token of each state in the current position, valid if the stamp of the
+    // state is the position.
+    let mut slot = vec![0; fst.num_states()];
+    let mut stamp = vec![usize::MAX; fst.num_states()];
+    let mut positions: Vec<Vec<Token<W>>> = Vec::with_capacity(chain.len() + 1);
+
+    let mut tokens = vec![Token {
+        state: start,
+        weight: W::one(),
+        parent: None,
+    }];
+    slot[start as usize] = 0;
+    stamp[start as usize] = 0;
+
+    for pos in 0..=chain.len() {
+        // Input epsilon transitions, the weights of the states being improved until no
+        // improvement is left.
+        let mut queue: VecDeque<usize> = (0..tokens.len()).collect();
+        while let Some(idx) = queue.pop_front() {
+            let state = tokens[idx].state;
+            let weight = tokens[idx].weight.clone();
+            for (tr_idx, tr) in fst.get_trs(state)?.trs().iter().enumerate() {
+                if tr.ilabel != EPS_LABEL {
+                    continue;
+                }
+                let w = weight.times(&tr.weight)?;
+                let next = tr.nextstate as usize;
+                if stamp[next] == pos {
+                    let next_idx = slot[next];
+                    if natural_less(&w, &tokens[next_idx].weight)? {
+                        tokens[next_idx].weight = w;
+                        tokens[next_idx].parent = Some((pos, idx, tr_idx));
+                        queue.push_back(next_idx);
+                    }
+                } else if !w.is_zero() {
+                    stamp[next] = pos;
+                    slot[next] = tokens.len();
+                    queue.push_back(tokens.len());
+                    tokens.push(Token {
+                        state: tr.nextstate,
+                        weight: w,
+                        parent: Some((pos, idx, tr_idx)),
+                    });
+                }
+            }
+        }
+
+        let mut next_tokens = vec![];
+        if let Some((label, _)) = chain.get(pos) {
+            for (idx, token) in tokens.iter().enumerate() {
+                for (tr_idx, tr) in fst.get_trs(token.state)?.trs().iter().enumerate() {
+                    if tr.ilabel != *label {
+                        continue;
+                    }
+                    let w = token.weight.times(&tr.weight)?;
+                    let next = tr.nextstate as usize;
+                    if stamp[next] == pos + 1 {
+                        let next_token: &mut Token<W> = &mut next_tokens[slot[next]];
+                        if natural_less(&w, &next_token.weight)? {
+                            next_token.weight = w;
+                            next_token.parent = Some((pos, idx, tr_idx));
+                        }
+                    } else if !w.is_zero() {
+                        stamp[next] = pos + 1;
+                        slot[next] = next_tokens.len();
+                        next_tokens.push(Token {
+                            state: tr.nextstate,
+                            weight: w,
+                            parent: Some((pos, idx, tr_idx)),
+                        });
+                    }
+                }
+            }
+        }
+        positions.push(tokens);
+        if next_tokens.is_empty() && pos < chain.len() {
+            return Ok(ofst);
+        }
+        tokens = next_tokens;
+    }
+
+    // Best final token of the last position.
+    let last = chain.len();
+    let mut best: Option<(usize, W)> = None;
+    for (idx, token) in positions[last].iter().enumerate() {
+        if let Some(final_weight) = fst.final_weight(token.state)? {
+            let w = token.weight.times(final_weight)?;
+            let better = match &best {
+                Some((_, best_weight)) => natural_less(&w, best_weight)?,
+                None => !w.is_zero(),
+            };
+            if better {
+                best = Some((idx, w));
+            }
+        }
+    }
+    let (best_idx, _) = match best {
+        Some(b) => b,
+        None => return Ok(ofst),
+    };
+
+    // Backtrace, the transitions being collected from the end of the path.
+    let final_token = &positions[last][best_idx];
+    let final_weight = fst
+        .final_weight(final_token.state)?
+        .unwrap()
+        .times(&linear_final_weight)?;
+    let mut trs = vec![];
+    let (mut pos, mut idx) = (last, best_idx);
+    while let Some((parent_pos, parent_idx, tr_idx)) = positions[pos][idx].parent {
+        let parent_state = positions[parent_pos][parent_idx].state;
+        let tr = fst.get_trs(parent_state)?.trs()[tr_idx].clone();
+        if parent_pos == pos {
+            trs.push(Tr::<W>::new(EPS_LABEL, tr.olabel, tr.weight, 0));
+        } else {
+            let (label, weight) = &chain[parent_pos];
+            trs.push(Tr::<W>::new(
+                *label,
+                tr.olabel,
+                weight.times(&tr.weight)?,
+                0,
+            ));
+        }
+        pos = parent_pos;
+        idx = parent_idx;
+    }
+
+    let mut state = ofst.add_state();
+    ofst.set_start(state)?;
+    for mut tr in trs.into_iter().rev() {
+        let next = ofst.add_state();
+        tr.nextstate = next;
+        ofst.add_tr(state, tr)?;
+        state = next;
+    }
+    ofst.set_final(state, final_weight)?;
+    ofst.set_properties_with_mask(
+        shortest_path_properties(ofst.properties(), true),
+        FstProperties::all_properties(),
+    );
+    if let Some(symt) = linear.input_symbols() {
+        ofst.set_input_symbols(symt.clone());
+    }
+    if let Some(symt) = fst.output_symbols() {
+        ofst.set_output_symbols(symt.clone());
+    }
+    Ok(ofst)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::algorithms::tr_compares::ILabelCompare;
+    use crate::algorithms::tr_sort;
+    use crate::fst_traits::{CoreFst, Fst};
+    use crate::semirings::TropicalWeight;
+    use crate::utils::acceptor;
+
+    #[test]
+    fn test_linear_compose_shortest_path_epsilons() -> Result<()> {
+        // 0 -a:x/1-> 1 -eps:y/0.5-> 2 -b:z/1-> 3, and 0 -a:w/3-> 2 -b:z/1-> 3.
+        let mut fst = VectorFst::<TropicalWeight>::new();
+        let s0 = fst.add_state();
+        let s1 = fst.add_state();
+        let s2 = fst.add_state();
+        let s3 = fst.add_state();
+        fst.set_start(s0)?;
+        fst.set_final(s3, 0.25)?;
+        fst.add_tr(s0, Tr::new(1, 10, 1.0, s1))?;
+        fst.add_tr(s0, Tr::new(1, 11, 3.0, s2))?;
+        fst.add_tr(s1, Tr::new(EPS_LABEL, 12, 0.5, s2))?;
+        fst.add_tr(s2, Tr::new(2, 13, 1.0, s3))?;
+        tr_sort(&mut fst, ILabelCompare {});
+
+        let linear: VectorFst<TropicalWeight> = acceptor(&[1, 2], TropicalWeight::new(0.125));
+        let fast: VectorFst<TropicalWeight> = linear_compose_shortest_path(&linear, &fst)?;
+        let composed: VectorFst<TropicalWeight> =
+            compose::<_, VectorFst<_>, VectorFst<_>, _, _, _>(&linear, &fst)?;
+        let generic: VectorFst<TropicalWeight> = shortest_path(&composed)?;
+        let path = fast.paths_iter().next().unwrap();
+        assert_eq!(Some(&path), generic.paths_iter().next().as_ref());
+        assert_eq!(path.olabels, vec![10, 12, 13]);
+        assert_eq!(path.weight, TropicalWeight::new(2.875));
+
+        // Not accepted.
+        let linear: VectorFst<TropicalWeight> = acceptor(&[2], TropicalWeight::one());
+        let fast: VectorFst<TropicalWeight> = linear_compose_shortest_path(&linear, &fst)?;
+        assert_eq!(fast.start(), None);
+        Ok(())
+    }
+}
@@ -9,20 +9,23 @@ use anyhow::Result;
 use binary_heap_plus::BinaryHeap;
 use stable_bst::TreeMap;
 
+use crate::algorithms::dfs_visit::dfs_visit;
 use crate::algorithms::encode::EncodeType;
 use crate::algorithms::factor_weight::factor_iterators::GallicFactorLeft;
 use crate::algorithms::factor_weight::{factor_weight, FactorWeightOptions, FactorWeightType};
 use crate::algorithms::partition::Partition;
 use crate::algorithms::queues::LifoQueue;
 use crate::algorithms::tr_compares::ILabelCompare;
+use crate::algorithms::tr_filters::AnyTrFilter;
 use crate::algorithms::tr_mappers::QuantizeMapper;
 use crate::algorithms::tr_unique;
+use crate::algorithms::visitors::SccVisitor;
 use crate::algorithms::weight_converters::{FromGallicConverter, ToGallicConverter};
 use crate::algorithms::Queue;
 use crate::algorithms::{
     connect,
     encode::{decode, encode},
-    tr_map, tr_sort, weight_convert, ReweightType,
+    fst_convert_from_ref, tr_map, tr_sort, weight_convert, ReweightType,
 };
 use crate::algorithms::{push_weights_with_config, reverse, PushWeightsConfig};
 use crate::fst_impls::VectorFst;
@@ -90,6 +93,12 @@ where
 /// In place minimization of deterministic weighted automata and transducers,
 /// and also non-deterministic ones if they use an idempotent semiring.
 /// For transducers, the algorithm produces a compact factorization of the minimal transducer.
+///
+/// The weights are pushed towards the initial state and the FST is encoded as an unweighted
+/// acceptor whose states are merged by partition refinement, Revuz's algorithm if it is
+/// acyclic and Hopcroft's otherwise. A non-deterministic FST is only minimized if
+/// `allow_nondet` is set : its equivalent states, in the sense of bisimulation, are merged,
+/// but the result is not guaranteed to be minimal.
 pub fn minimize_with_config<W, F>(ifst: &mut F, config: MinimizeConfig) -> Result<()>
 where
     F: MutableFst<W> + ExpandedFst<W> + AllocableFst<W>,
@@ -97,26 +106,7 @@ where
     W::ReverseWeight: WeightQuantize,
 {
     let delta = config.delta;
-    let allow_nondet = config.allow_nondet;
-
-    let props = ifst.compute_and_update_properties(
-        FstProperties::ACCEPTOR
-            | FstProperties::I_DETERMINISTIC
-            | FstProperties::WEIGHTED
-            | FstProperties::UNWEIGHTED,
-    )?;
-
-    let allow_acyclic_minimization = if props.contains(FstProperties::I_DETERMINISTIC) {
-        true
-    } else {
-        if !W::properties().contains(SemiringProperties::IDEMPOTENT) {
-            bail!("Cannot minimize a non-deterministic FST over a non-idempotent semiring")
-        } else if !allow_nondet {
-            bail!("Refusing to minimize a non-deterministic FST with allow_nondet = false")
-        }
-
-        false
-    };
+    let (props, allow_acyclic_minimization) = minimize_properties(ifst, config.allow_nondet)?;
 
     if !props.contains(FstProperties::ACCEPTOR) {
         // Weighted transducer
@@ -174,6 +164,133 @@ where
     }
 }
 
+/// Properties of the FST to minimize and whether the acyclic minimization can be applied.
+fn minimize_properties<W, F>(ifst: &mut F, allow_nondet: bool) -> Result<(FstProperties, bool)>
+where
+    W: Semiring,
+    F: MutableFst<W>,
+{
+    let props = ifst.compute_and_update_properties(
+        FstProperties::ACCEPTOR
+            | FstProperties::I_DETERMINISTIC
+            | FstProperties::WEIGHTED
+            | FstProperties::UNWEIGHTED,
+    )?;
+
+    let allow_acyclic_minimization = if props.contains(FstProperties::I_DETERMINISTIC) {
+        true
+    } else {
+        if !W::properties().contains(SemiringProperties::IDEMPOTENT) {
+            bail!("Cannot minimize a non-deterministic FST over a non-idempotent semiring")
+        } else if !allow_nondet {
+            bail!("Refusing to minimize a non-deterministic FST with allow_nondet = false")
+        }
+
+        false
+    };
+    Ok((props, allow_acyclic_minimization))
+}
+
+/// Computes the partition of the states of `ifst` into the classes of equivalent states merged
+/// by `minimize_with_config`, without modifying `ifst`.
+///
+/// The class of each state is returned, the classes being numbered in the order of their first
+/// state. The states on no successful path, removed by the minimization, have no class. As in
+/// `minimize_with_config`, the weights are pushed towards the initial state before comparing
+/// the states : two states are equivalent if their futures are equal up to a weight, which is
+/// left to the caller when merging them.
+///
+/// # Example
+/// ```
+/// # use rustfst::algorithms::minimize_partition;
+/// # use rustfst::algorithms::MinimizeConfig;
+/// # use rustfst::fst_impls::VectorFst;
+/// # use rustfst::fst_traits::MutableFst;
+/// # use rustfst::semirings::{Semiring, TropicalWeight};
+/// # use rustfst::Tr;
+/// # use anyhow::Result;
+/// # fn main() -> Result<()> {
+/// let mut fst = VectorFst::<TropicalWeight>::new();
+/// fst.add_states(4);
+/// fst.set_start(0)?;
+/// fst.add_tr(0, Tr::new(1, 1, 1.0, 1))?;
+/// fst.add_tr(0, Tr::new(2, 2, 1.0, 2))?;
+/// fst.add_tr(1, Tr::new(3, 3, 1.0, 3))?;
+/// fst.add_tr(2, Tr::new(3, 3, 1.0, 3))?;
+/// fst.set_final(3, TropicalWeight::one())?;
+///
+/// let classes = minimize_partition(&fst, MinimizeConfig::default())?;
+/// assert_eq!(classes, vec![Some(0), Some(1), Some(1), Some(2)]);
+/// # Ok(())
+/// # }
+/// ```
+pub fn minimize_partition<W, F>(ifst: &F, config: MinimizeConfig) -> Result<Vec<Option<usize>>>
+where
+    F: ExpandedFst<W>,
+    W: WeaklyDivisibleSemiring + WeightQuantize,
+    W::ReverseWeight: WeightQuantize,
+{
+    let delta = config.delta;
+    let mut fst: VectorFst<W> = fst_convert_from_ref(ifst);
+    let (props, allow_acyclic_minimization) = minimize_properties(&mut fst, config.allow_nondet)?;
+
+    let mut visitor = SccVisitor::new(&fst, false, true);
+    dfs_visit(&fst, &mut visitor, &AnyTrFilter {}, false);
+    // Can't fail as the accessibility has been computed.
+    let access = visitor.access.unwrap();
+    let coaccess = visitor.coaccess;
+    // The states kept by connect keep their order.
+    connect(&mut fst)?;
+    if fst.num_states() == 0 {
+        return Ok(vec![None; access.len()]);
+    }
+
+    let push_weights_config = PushWeightsConfig::default().with_delta(delta);
+    let quantize_mapper = QuantizeMapper::new(delta);
+    let partition = if !props.contains(FstProperties::ACCEPTOR) {
+        let mut to_gallic = ToGallicConverter {};
+        let mut gfst: VectorFst<GallicWeightLeft<W>> = weight_convert(&fst, &mut to_gallic)?;
+        push_weights_with_config(
+            &mut gfst,
+            ReweightType::ReweightToInitial,
+            push_weights_config,
+        )?;
+        tr_map(&mut gfst, &quantize_mapper)?;
+        encode(&mut gfst, EncodeType::EncodeWeightsAndLabels)?;
+        acceptor_partition(&mut gfst, allow_acyclic_minimization)?
+    } else if props.contains(FstProperties::WEIGHTED) {
+        push_weights_with_config(
+            &mut fst,
+            ReweightType::ReweightToInitial,
+            push_weights_config,
+        )?;
+        tr_map(&mut fst, &quantize_mapper)?;
+        encode(&mut fst, EncodeType::EncodeWeightsAndLabels)?;
+        acceptor_partition(&mut fst, allow_acyclic_minimization)?
+    } else {
+        acceptor_partition(&mut fst, allow_acyclic_minimization)?
+    };
+    let partition = partition.borrow();
+
+    let mut class_ids = vec![None; partition.num_classes()];
+    let mut num_classes = 0;
+    let mut state = 0;
+    let mut classes = Vec::with_capacity(access.len());
+    for (accessible, coaccessible) in access.into_iter().zip(coaccess) {
+        if !accessible || !coaccessible {
+            classes.push(None);
+            continue;
+        }
+        let class_id = class_ids[partition.get_class_id(state)].get_or_insert_with(|| {
+            num_classes += 1;
+            num_classes - 1
+        });
+        classes.push(Some(*class_id));
+        state += 1;
+    }
+    Ok(classes)
+}
+
 /// In place minimization for weighted final state acceptor.
 /// If `allow_acyclic_minimization` is true and the input is acyclic, then a specific
 /// minimization is applied.
@@ -183,9 +300,8 @@ pub fn acceptor_minimize<W: Semiring, F: MutableFst<W> + ExpandedFst<W>>(
     ifst: &mut F,
     allow_acyclic_minimization: bool,
 ) -> Result<()> {
-    let props = ifst.compute_and_update_properties(
-        FstProperties::ACCEPTOR | FstProperties::UNWEIGHTED | FstProperties::ACYCLIC,
-    )?;
+    let props =
+        ifst.compute_and_update_properties(FstProperties::ACCEPTOR | FstProperties::UNWEIGHTED)?;
     if !props.contains(FstProperties::ACCEPTOR | FstProperties::UNWEIGHTED) {
         bail!("FST is not an unweighted acceptor");
     }
@@ -196,19 +312,29 @@ pub fn acceptor_minimize<W: Semiring, F: MutableFst<W> + ExpandedFst<W>>(
         return Ok(());
     }
 
+    let partition = acceptor_partition(ifst, allow_acyclic_minimization)?;
+    merge_states(partition, ifst)?;
+
+    tr_unique(ifst);
+
+    Ok(())
+}
+
+/// Partition of the states of a connected unweighted acceptor into equivalent states.
+fn acceptor_partition<W: Semiring, F: MutableFst<W>>(
+    ifst: &mut F,
+    allow_acyclic_minimization: bool,
+) -> Result<Rc<RefCell<Partition>>> {
+    let props = ifst
+        .compute_and_update_properties(FstProperties::ACYCLIC | FstProperties::I_DETERMINISTIC)?;
     if allow_acyclic_minimization && props.contains(FstProperties::ACYCLIC) {
         // Acyclic minimization
         tr_sort(ifst, ILabelCompare {});
         let minimizer = AcyclicMinimizer::new(ifst)?;
-        merge_states(minimizer.get_partition(), ifst)?;
+        Ok(minimizer.get_partition())
     } else {
-        let p = cyclic_minimize(ifst)?;
-        merge_states(p, ifst)?;
+        cyclic_minimize(ifst, props.contains(FstProperties::I_DETERMINISTIC))
     }
-
-    tr_unique(ifst);
-
-    Ok(())
 }
 
 fn merge_states<W: Semiring, F: MutableFst<W>>(
@@ -512,7 +638,12 @@ fn pre_partition<W: Semiring, F: MutableFst<W>>(
     }
 }
 
-fn cyclic_minimize<W: Semiring, F: MutableFst<W>>(fst: &mut F) -> Result<Rc<RefCell<Partition>>> {
+/// Hopcroft's partition refinement. For a non-deterministic acceptor, both parts of a split
+/// class are refined against, so that the states merged are bisimilar.
+fn cyclic_minimize<W: Semiring, F: MutableFst<W>>(
+    fst: &mut F,
+    deterministic: bool,
+) -> Result<Rc<RefCell<Partition>>> {
     // Initialize
     let mut tr: VectorFst<W::ReverseWeight> = reverse(fst)?;
     tr_sort(&mut tr, ILabelCompare {});
@@ -522,6 +653,13 @@ fn cyclic_minimize<W: Semiring, F: MutableFst<W>>(fst: &mut F) -> Result<Rc<RefC
     pre_partition(fst, &partition, &mut queue);
 
     let comp = TrIterCompare {};
+    let finalize_split = |partition: &mut Partition, queue: &mut LifoQueue| {
+        if deterministic {
+            partition.finalize_split(&mut Some(queue));
+        } else {
+            partition.finalize_split_enqueue_all(queue);
+        }
+    };
 
     let mut aiter_queue = BinaryHeap::new_by(|v1, v2| {
         if comp.compare(v1, v2) {
@@ -556,7 +694,7 @@ fn cyclic_minimize<W: Semiring, F: MutableFst<W>>(fst: &mut F) -> Result<Rc<RefC
             let from_state = tr.nextstate - 1;
             let from_label = tr.ilabel;
             if prev_label != from_label as i32 {
-                partition.borrow_mut().finalize_split(&mut Some(&mut queue));
+                finalize_split(&mut partition.borrow_mut(), &mut queue);
             }
             let from_class = partition.borrow().get_class_id(from_state as usize);
             if partition.borrow().get_class_size(from_class) > 1 {
@@ -569,7 +707,7 @@ fn cyclic_minimize<W: Semiring, F: MutableFst<W>>(fst: &mut F) -> Result<Rc<RefC
             }
         }
 
-        partition.borrow_mut().finalize_split(&mut Some(&mut queue));
+        finalize_split(&mut partition.borrow_mut(), &mut queue);
     }
 
     // Get Partition
@@ -616,9 +754,12 @@ impl TrIterCompare {
 
 #[cfg(test)]
 mod tests {
+    use crate::algorithms::determinize::*;
+    use crate::algorithms::{isomorphic, minimize_partition, minimize_with_config, MinimizeConfig};
     use crate::prelude::*;
+    use crate::{check_path_in_fst, KDELTA};
     use ::proptest::prelude::*;
-    use algorithms::determinize::*;
+    use anyhow::Result;
     use std::sync::Arc;
 
     #[test]
@@ -683,6 +824,85 @@ mod tests {
         assert_eq!(accept1, accept2);
     }
 
+    #[test]
+    fn test_minimize_log() -> Result<()> {
+        // The suffixes "c" of weights 2 and 1 become equal once the weights are pushed in the
+        // log semiring.
+        let mut fst = VectorFst::<LogWeight>::new();
+        fst.add_states(4);
+        fst.set_start(0)?;
+        fst.add_tr(0, Tr::new(1, 1, 1.0, 1))?;
+        fst.add_tr(0, Tr::new(2, 2, 2.0, 2))?;
+        fst.add_tr(1, Tr::new(3, 3, 2.0, 3))?;
+        fst.add_tr(2, Tr::new(3, 3, 1.0, 3))?;
+        fst.set_final(3, LogWeight::one())?;
+        let expected = shortest_distance(&fst, true)?;
+
+        minimize(&mut fst)?;
+
+        assert_eq!(fst.num_states(), 3);
+        let start = fst.start().unwrap() as usize;
+        let distance = shortest_distance(&fst, true)?;
+        assert!(distance[start].approx_equal(expected[0], KDELTA));
+        Ok(())
+    }
+
+    #[test]
+    fn test_minimize_partition() -> Result<()> {
+        // Transducer with two equivalent branches, up to their weights, and a dead-end state.
+        let mut fst = VectorFst::<TropicalWeight>::new();
+        fst.add_states(5);
+        fst.set_start(0)?;
+        fst.add_tr(0, Tr::new(1, 1, 1.0, 1))?;
+        fst.add_tr(0, Tr::new(2, 2, 1.0, 2))?;
+        fst.add_tr(0, Tr::new(3, 3, 1.0, 4))?;
+        fst.add_tr(1, Tr::new(3, 4, 1.0, 3))?;
+        fst.add_tr(2, Tr::new(3, 4, 2.0, 3))?;
+        fst.set_final(3, TropicalWeight::one())?;
+
+        let config = MinimizeConfig::default();
+        let classes = minimize_partition(&fst, config)?;
+        assert_eq!(classes, vec![Some(0), Some(1), Some(1), Some(2), None]);
+
+        minimize_with_config(&mut fst, config)?;
+        let num_classes = classes.iter().flatten().max().map_or(0, |c| c + 1);
+        assert_eq!(fst.num_states(), num_classes);
+        Ok(())
+    }
+
+    #[test]
+    fn test_minimize_nondet() -> Result<()> {
+        // p reads a to x1 and x2, q only to x2 : p reads "ab" and "abe", q only "abe". The
+        // class {x1, x2} is split after being used to refine {p, q}, which must be refined
+        // again against both parts.
+        let (s, f, h, p, q, x1, x2) = (0, 1, 2, 3, 4, 5, 6);
+        let mut fst = VectorFst::<TropicalWeight>::new();
+        fst.add_states(7);
+        fst.set_start(s)?;
+        fst.add_tr(s, Tr::new(3, 3, 0.0, p))?;
+        fst.add_tr(s, Tr::new(4, 4, 0.0, q))?;
+        fst.add_tr(p, Tr::new(1, 1, 0.0, x1))?;
+        fst.add_tr(p, Tr::new(1, 1, 0.0, x2))?;
+        fst.add_tr(q, Tr::new(1, 1, 0.0, x2))?;
+        fst.add_tr(x1, Tr::new(2, 2, 0.0, f))?;
+        fst.add_tr(x2, Tr::new(2, 2, 0.0, h))?;
+        fst.add_tr(h, Tr::new(5, 5, 0.0, f))?;
+        fst.set_final(f, TropicalWeight::one())?;
+
+        let paths = |fst: &VectorFst<TropicalWeight>| {
+            let mut paths: Vec<_> = fst.paths_iter().map(|path| path.ilabels).collect();
+            paths.sort();
+            paths
+        };
+        let expected = paths(&fst);
+
+        assert!(minimize(&mut fst).is_err());
+        minimize_with_config(&mut fst, MinimizeConfig::default().with_allow_nondet(true))?;
+        assert_eq!(paths(&fst), expected);
+        assert_eq!(fst.num_states(), 7);
+        Ok(())
+    }
+
     proptest! {
         #[test]
         fn test_proptest_minimize_timeout(mut fst in any::<VectorFst::<TropicalWeight>>()) {
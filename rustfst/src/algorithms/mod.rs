@@ -1,38 +1,78 @@
 pub use self::{
     add_super_final_state::add_super_final_state,
-    all_pairs_shortest_distance::all_pairs_shortest_distance,
+    all_pairs_shortest_distance::{
+        all_pairs_shortest_distance, all_pairs_shortest_distance_with_filter,
+    },
+    approx_minimize::{approx_minimize, approx_minimize_with_config, ApproxMinimizeConfig},
+    canonicalize::canonicalize,
+    complement::complement,
     condense::condense,
     connect::connect,
+    constraints::{contains, not_followed_by, optionalize, sigma_star},
+    coverage::{coverage, string_coverage, CoverageReport, StringCoverage},
+    dafsa::{dafsa_update, weighted_dafsa, DafsaUpdater},
+    disambiguate::disambiguate,
+    epsilon_cycles::{collapse_epsilon_cycles, epsilon_cycles, EpsilonCycle},
+    equal::{equal, equal_with_config, EqualConfig},
+    equivalent::{equivalent, equivalent_with_config, EquivalentConfig},
+    fingerprint::{content_hash, content_hash_with_config, fingerprint, ContentHashConfig},
     fst_convert::{fst_convert, fst_convert_from_ref},
+    hot_tr_sort::hot_tr_sort,
     inversion::invert,
     isomorphic::{isomorphic, isomorphic_with_config, IsomorphicConfig},
-    minimize::{acceptor_minimize, minimize, minimize_with_config, MinimizeConfig},
+    linear_shortest_path::linear_compose_shortest_path,
+    minimize::{
+        acceptor_minimize, minimize, minimize_partition, minimize_with_config, MinimizeConfig,
+    },
     optimize::optimize,
+    priority_union::priority_union,
     projection::{project, ProjectType},
+    prune::{prune, prune_fst, prune_with_config, PruneConfig},
+    prune_trs::prune_trs,
     push::{
         push, push_weights, push_weights_with_config, push_with_config, PushConfig, PushType,
         PushWeightsConfig,
     },
+    quantize_sweep::{quantize_sweep, QuantizeSweepPoint, QuantizeSweepReport},
     queue::{Queue, QueueType},
     relabel_pairs::relabel_pairs,
     reverse::reverse,
     reweight::{reweight, ReweightType},
+    rewrite::{longest_match, rewrite_leftmost_longest},
     rm_final_epsilon::rm_final_epsilon,
-    shortest_distance::{shortest_distance, shortest_distance_with_config, ShortestDistanceConfig},
-    shortest_path::{shortest_path, shortest_path_with_config, ShortestPathConfig},
+    score_sequence::{accepts, score_sequence},
+    sequentialize::{sequentialize, SequentializeConfig},
+    shortest_distance::{
+        shortest_distance, shortest_distance_with_config, shortest_distance_with_queue,
+        ShortestDistanceConfig,
+    },
+    shortest_path::{
+        shortest_path, shortest_path_with_config, shortest_path_with_queue, ShortestPathConfig,
+    },
+    shortest_paths_iterator::ShortestPathsIterator,
     state_sort::state_sort,
+    streaming_applier::StreamingApplier,
     top_sort::top_sort,
     tr_map::{tr_map, FinalTr, MapFinalAction, TrMapper},
     tr_sort::tr_sort,
     tr_sum::tr_sum,
     tr_unique::tr_unique,
+    transduce::transduce,
+    transition_matrix::{LinearRepresentation, TransitionMatrix},
     weight_convert::{weight_convert, WeightConverter},
+    weight_graph::WeightGraph,
 };
 
 mod add_super_final_state;
 mod all_pairs_shortest_distance;
+mod approx_minimize;
+/// Builders of the transducers of speech recognition decoding graphs.
+#[cfg(feature = "asr")]
+pub mod asr;
+mod canonicalize;
 /// Functions to compute Kleene closure (star or plus) of an FST.
 pub mod closure;
+mod complement;
 #[allow(clippy::type_complexity)]
 /// Functions to compose FSTs.
 pub mod compose;
@@ -40,21 +80,44 @@ pub mod compose;
 pub mod concat;
 mod condense;
 mod connect;
+mod constraints;
+mod coverage;
+mod dafsa;
 /// Functions to determinize FSTs.
 pub mod determinize;
 pub(crate) mod dfs_visit;
+mod disambiguate;
 /// Functions to encode FSTs as FSAs and vice versa.
 pub mod encode;
+mod epsilon_cycles;
+mod equal;
+mod equivalent;
 /// Functions to factor various weight types.
 pub mod factor_weight;
+mod fingerprint;
+/// Functions to apply morphological transducers containing flag diacritics.
+pub mod flag_diacritics;
 mod fst_convert;
+mod hot_tr_sort;
 mod inversion;
 mod isomorphic;
+/// Algorithms on speech recognition lattices.
+#[cfg(feature = "lattice")]
+pub mod lattice;
+mod linear_shortest_path;
 mod minimize;
+/// Functions to count n-grams and build n-gram language models.
+#[cfg(feature = "ngram")]
+pub mod ngram;
 mod optimize;
 mod partition;
+mod phi_shortest;
+mod priority_union;
 mod projection;
+mod prune;
+mod prune_trs;
 mod push;
+mod quantize_sweep;
 mod queue;
 
 /// Functions to randomly generate paths through an Fst. A static and a delayed version are available.
@@ -64,21 +127,31 @@ mod relabel_pairs;
 pub mod replace;
 mod reverse;
 mod reweight;
+mod rewrite;
 
 /// Functions to remove epsilon transitions from an Fst. A static and a delayed version are available.
 pub mod rm_epsilon;
 mod rm_final_epsilon;
+mod score_sequence;
+mod sequentialize;
 mod shortest_distance;
 mod shortest_path;
+mod shortest_paths_iterator;
 mod state_sort;
+mod streaming_applier;
+/// Functions to synchronize transducers. A static and a delayed version are available.
+pub mod synchronize;
 mod top_sort;
 mod tr_map;
 mod tr_sort;
 mod tr_sum;
 pub(crate) mod tr_unique;
+mod transduce;
+mod transition_matrix;
 /// Functions to compute the union of FSTs.
 pub mod union;
 mod weight_convert;
+mod weight_graph;
 
 /// Module providing different structures implementing the `Queue` trait.
 pub mod queues;
@@ -96,6 +169,7 @@ pub mod weight_converters;
 
 /// Functions to compare / sort the Trs of an FST.
 pub mod tr_compares {
+    pub use super::canonicalize::CanonicalTrCompare;
     pub use super::isomorphic::tr_compare;
     pub use super::tr_sort::{ILabelCompare, OLabelCompare, TrCompare};
 }
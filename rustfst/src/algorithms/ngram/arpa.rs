@@ -0,0 +1,178 @@
+use std::fmt::Write as FmtWrite;
+use std::fs::File;
+use std::io::{BufWriter, LineWriter, Write};
+use std::path::Path;
+
+use anyhow::Result;
+
+use crate::algorithms::ngram::model::weight_to_prob;
+use crate::algorithms::ngram::table::{NGramTable, BOS, EOS};
+use crate::fst_traits::ExpandedFst;
+use crate::semirings::Semiring;
+use crate::{Label, SymbolTable};
+
+/// Log10 probability written for the impossible events, as SRILM does.
+const ARPA_LOG_ZERO: f64 = -99.0;
+
+/// N-gram with its log10 probability and its log10 backoff weight, if it is a history.
+type ArpaEntry = (Vec<Label>, f64, Option<f64>);
+
+fn log10(p: f64) -> f64 {
+    if p <= 0.0 {
+        ARPA_LOG_ZERO
+    } else {
+        p.log10()
+    }
+}
+
+fn symbol(symt: Option<&SymbolTable>, label: Label) -> Result<String> {
+    if label == BOS {
+        return Ok("<s>".to_string());
+    }
+    if label == EOS {
+        return Ok("</s>".to_string());
+    }
+    match symt {
+        Some(symt) => symt
+            .get_symbol(label)
+            .map(|s| s.to_string())
+            .ok_or_else(|| format_err!("Label {} is missing from the symbol table", label)),
+        None => Ok(label.to_string()),
+    }
+}
+
+fn ngram_string(symt: Option<&SymbolTable>, ngram: &[Label]) -> Result<String> {
+    Ok(ngram
+        .iter()
+        .map(|l| symbol(symt, *l))
+        .collect::<Result<Vec<_>>>()?
+        .join(" "))
+}
+
+/// Converts a backoff n-gram model to the ARPA text format.
+///
+/// The model follows the conventions of the [ngram module](self) : weights are negative
+/// natural logarithms of the probabilities and backoff weights are on the epsilon
+/// transitions, as produced by [`ngram_make`](super::ngram_make) or by OpenGrm. The words are
+/// written with the input symbol table of the model, or as labels if it has none. The
+/// probabilities and backoff weights are converted to base 10 logarithms.
+///
+/// # Example
+/// ```
+/// # #[macro_use] extern crate rustfst;
+/// # use rustfst::utils::acceptor;
+/// # use rustfst::algorithms::ngram::{ngram_count, ngram_make, ngram_to_arpa, NGramSmoothing};
+/// # use rustfst::fst_impls::VectorFst;
+/// # use rustfst::semirings::{Semiring, TropicalWeight};
+/// # use anyhow::Result;
+/// # fn main() -> Result<()> {
+/// let corpus : Vec<VectorFst<TropicalWeight>> = vec![fst![1, 2], fst![2]];
+/// let model = ngram_make(&ngram_count(&corpus, 2)?, NGramSmoothing::WittenBell)?;
+/// let arpa = ngram_to_arpa(&model)?;
+/// assert!(arpa.starts_with("\n\\data\\\nngram 1=4\nngram 2=4\n"));
+/// # Ok(())
+/// # }
+/// ```
+pub fn ngram_to_arpa<W, F>(model: &F) -> Result<String>
+where
+    W: Semiring<Type = f32>,
+    F: ExpandedFst<W>,
+{
+    let table = NGramTable::from_fst(model, weight_to_prob)?;
+    let symt = model.input_symbols().map(|s| s.as_ref());
+
+    // N-grams of each order.
+    let mut ngrams: Vec<Vec<ArpaEntry>> = vec![vec![]; table.order];
+    if let Some(entry) = table.histories.get(&[BOS][..]) {
+        ngrams[0].push((vec![BOS], ARPA_LOG_ZERO, Some(log10(entry.backoff))));
+    }
+    for (history, entry) in table.histories.iter() {
+        for (word, p) in entry.next.iter() {
+            let mut ngram = history.clone();
+            ngram.push(*word);
+            let backoff = table
+                .histories
+                .get(&ngram)
+                .map(|ngram_entry| log10(ngram_entry.backoff));
+            ngrams[history.len()].push((ngram, log10(*p), backoff));
+        }
+    }
+    for ngrams_order in ngrams.iter_mut() {
+        ngrams_order.sort_by(|a, b| a.0.cmp(&b.0));
+    }
+
+    let mut arpa = String::new();
+    writeln!(arpa)?;
+    writeln!(arpa, "\\data\\")?;
+    for (idx, ngrams_order) in ngrams.iter().enumerate() {
+        writeln!(arpa, "ngram {}={}", idx + 1, ngrams_order.len())?;
+    }
+    for (idx, ngrams_order) in ngrams.iter().enumerate() {
+        writeln!(arpa)?;
+        writeln!(arpa, "\\{}-grams:", idx + 1)?;
+        for (ngram, logp, backoff) in ngrams_order.iter() {
+            write!(arpa, "{:.6}\t{}", logp, ngram_string(symt, ngram)?)?;
+            if let Some(backoff) = backoff {
+                write!(arpa, "\t{:.6}", backoff)?;
+            }
+            writeln!(arpa)?;
+        }
+    }
+    writeln!(arpa)?;
+    writeln!(arpa, "\\end\\")?;
+    Ok(arpa)
+}
+
+/// Writes a backoff n-gram model to a file in the ARPA text format. See [`ngram_to_arpa`].
+pub fn write_arpa<W, F, P>(model: &F, path: P) -> Result<()>
+where
+    W: Semiring<Type = f32>,
+    F: ExpandedFst<W>,
+    P: AsRef<Path>,
+{
+    let buffer = File::create(path.as_ref())?;
+    let mut writer = BufWriter::new(LineWriter::new(buffer));
+    write!(writer, "{}", ngram_to_arpa(model)?)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::sync::Arc;
+
+    use crate::algorithms::ngram::{ngram_count, ngram_make, NGramSmoothing};
+    use crate::fst_impls::VectorFst;
+    use crate::fst_traits::Fst;
+    use crate::semirings::TropicalWeight;
+    use crate::utils::acceptor;
+
+    #[test]
+    fn test_ngram_to_arpa() -> Result<()> {
+        let mut symt = SymbolTable::new();
+        symt.add_symbols(vec!["a", "b"]);
+        let symt = Arc::new(symt);
+        let corpus: Vec<VectorFst<TropicalWeight>> = vec![
+            acceptor(&[1, 2], TropicalWeight::one()),
+            acceptor(&[2], TropicalWeight::one()),
+        ];
+        let mut model = ngram_make(&ngram_count(&corpus, 2)?, NGramSmoothing::WittenBell)?;
+        model.set_input_symbols(Arc::clone(&symt));
+
+        let arpa = ngram_to_arpa(&model)?;
+        let lines: Vec<&str> = arpa.lines().collect();
+        assert_eq!(lines[1], "\\data\\");
+        assert_eq!(lines[2], "ngram 1=4");
+        assert_eq!(lines[3], "ngram 2=4");
+        assert_eq!(lines[5], "\\1-grams:");
+        // p(a) = 1/5, with a backoff weight since `a` is a history.
+        assert!(lines[6].starts_with("-0.698970\ta\t"));
+        assert!(lines[8].starts_with("-99.000000\t<s>\t"));
+        assert_eq!(lines[9], "-0.397940\t</s>");
+        // p(b|a) = 0.5 * 1 + 0.5 * p(b) with Witten-Bell.
+        assert!(lines.contains(&"-0.154902\ta b"));
+        assert_eq!(lines.last(), Some(&"\\end\\"));
+        Ok(())
+    }
+}
@@ -0,0 +1,249 @@
+use std::collections::BTreeMap;
+
+use anyhow::{bail, Result};
+
+use crate::algorithms::dfs_visit::dfs_visit;
+use crate::algorithms::ngram::table::{NGramTable, BOS, EOS};
+use crate::algorithms::top_sort::TopOrderVisitor;
+use crate::algorithms::tr_filters::AnyTrFilter;
+use crate::fst_impls::VectorFst;
+use crate::fst_traits::{ExpandedFst, Fst};
+use crate::semirings::{LogWeight, Semiring};
+use crate::{Label, StateId, Trs, EPS_LABEL};
+
+/// Counts the n-grams of order up to `order` of a corpus and returns the count FST, as
+/// `ngramcount` from OpenGrm does.
+///
+/// Each FST of the corpus is an acyclic acceptor, a single sentence or a lattice. Each path
+/// contributes to the counts with its probability, `exp(-weight)`, so that an unweighted
+/// sentence counts for one. Epsilon transitions are skipped.
+///
+/// The counts are the expected counts computed by forward-backward over the lattice, without
+/// enumerating its paths : the forward probabilities are computed for each state and each of
+/// the last `order - 1` labels read to reach it, so the cost is linear in the number of
+/// transitions times the number of such contexts.
+///
+/// The count FST has the topology described in the [module documentation](self). Its weights
+/// are the negative logarithms of the counts in the log semiring. The counts of the n-grams
+/// of all the orders are kept, so the count of `hw` is on the transition `w` leaving the state
+/// of the history `h`. The weights of the backoff transitions are `LogWeight::one()`. The
+/// symbol tables of the first FST of the corpus are copied to the count FST.
+///
+/// # Example
+/// ```
+/// # #[macro_use] extern crate rustfst;
+/// # use rustfst::utils::acceptor;
+/// # use rustfst::algorithms::ngram::ngram_count;
+/// # use rustfst::fst_impls::VectorFst;
+/// # use rustfst::fst_traits::CoreFst;
+/// # use rustfst::Trs;
+/// # use rustfst::semirings::{LogWeight, Semiring, TropicalWeight};
+/// # use anyhow::Result;
+/// # fn main() -> Result<()> {
+/// let corpus : Vec<VectorFst<TropicalWeight>> = vec![fst![1, 2], fst![1]];
+/// let counts = ngram_count(&corpus, 2)?;
+///
+/// // The start state is the history `<s>`, followed twice by the word 1.
+/// let start = counts.start().unwrap();
+/// let tr = counts.get_trs(start)?.trs()[0].clone();
+/// assert_eq!(tr.ilabel, 1);
+/// assert!((tr.weight.value() + 2f32.ln()).abs() < 1e-6);
+/// # Ok(())
+/// # }
+/// ```
+pub fn ngram_count<'a, W, F, I>(corpus: I, order: usize) -> Result<VectorFst<LogWeight>>
+where
+    W: Semiring<Type = f32>,
+    F: ExpandedFst<W> + 'a,
+    I: IntoIterator<Item = &'a F>,
+{
+    if order == 0 {
+        bail!("ngram_count : the order must be at least 1");
+    }
+    let mut table = NGramTable::new(order);
+    let mut symts = None;
+    for fst in corpus {
+        if symts.is_none() {
+            symts = Some((fst.input_symbols().cloned(), fst.output_symbols().cloned()));
+        }
+        count_lattice(&mut table, fst)?;
+    }
+
+    let mut fst = table.to_fst(|count| LogWeight::new(-count.ln() as f32))?;
+    if let Some((isymt, osymt)) = symts {
+        if let Some(symt) = isymt {
+            fst.set_input_symbols(symt);
+        }
+        if let Some(symt) = osymt {
+            fst.set_output_symbols(symt);
+        }
+    }
+    Ok(fst)
+}
+
+/// Adds the expected counts of the n-grams of the lattice to the table.
+fn count_lattice<W, F>(table: &mut NGramTable, fst: &F) -> Result<()>
+where
+    W: Semiring<Type = f32>,
+    F: ExpandedFst<W>,
+{
+    let start = match fst.start() {
+        Some(start) => start,
+        None => return Ok(()),
+    };
+    let mut visitor = TopOrderVisitor::new();
+    dfs_visit(fst, &mut visitor, &AnyTrFilter {}, false);
+    if !visitor.acyclic {
+        bail!("ngram_count : the FSTs of the corpus must be acyclic");
+    }
+    let mut states = vec![0; visitor.order.len()];
+    for (state, rank) in visitor.order.iter().enumerate() {
+        states[*rank as usize] = state as StateId;
+    }
+    let prob = |weight: &W| f64::from(-*weight.value()).exp();
+
+    // Sum of the probabilities of the paths from each state to a final state.
+    let mut backward = vec![0.0; states.len()];
+    for state in states.iter().rev() {
+        let mut beta = match fst.final_weight(*state)? {
+            Some(final_weight) => prob(&final_weight),
+            None => 0.0,
+        };
+        for tr in fst.get_trs(*state)?.trs() {
+            if tr.ilabel != tr.olabel {
+                bail!("ngram_count : the FSTs of the corpus must be acceptors");
+            }
+            beta += prob(&tr.weight) * backward[tr.nextstate as usize];
+        }
+        backward[*state as usize] = beta;
+    }
+
+    // Sum of the probabilities of the paths from the start state to each state, split by
+    // the last labels read, which are the histories of the n-grams ending after the state.
+    let context = table.order - 1;
+    let mut forward: Vec<BTreeMap<Vec<Label>, f64>> = vec![BTreeMap::new(); states.len()];
+    forward[start as usize].insert([BOS][..context.min(1)].to_vec(), 1.0);
+    for state in states {
+        let histories = std::mem::take(&mut forward[state as usize]);
+        let final_weight = fst.final_weight(state)?;
+        let trs = fst.get_trs(state)?;
+        for (history, alpha) in histories {
+            if let Some(final_weight) = &final_weight {
+                count_ngrams(table, &history, EOS, alpha * prob(final_weight));
+            }
+            for tr in trs.trs() {
+                let beta = backward[tr.nextstate as usize];
+                if beta == 0.0 {
+                    continue;
+                }
+                let alpha_next = alpha * prob(&tr.weight);
+                let mut next_history = history.clone();
+                if tr.ilabel != EPS_LABEL {
+                    count_ngrams(table, &history, tr.ilabel, alpha_next * beta);
+                    next_history.push(tr.ilabel);
+                    if next_history.len() > context {
+                        next_history.remove(0);
+                    }
+                }
+                *forward[tr.nextstate as usize]
+                    .entry(next_history)
+                    .or_insert(0.0) += alpha_next;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Adds `count` to the n-grams made of each suffix of `history` followed by `word`.
+fn count_ngrams(table: &mut NGramTable, history: &[Label], word: Label, count: f64) {
+    if count == 0.0 {
+        return;
+    }
+    for begin in 0..=history.len() {
+        table.add(&history[begin..], word, count);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::fst_traits::{CoreFst, MutableFst};
+    use crate::semirings::TropicalWeight;
+    use crate::utils::acceptor;
+
+    fn count_of(fst: &VectorFst<LogWeight>, state: StateId, label: Label) -> Result<f32> {
+        let trs = fst.get_trs(state)?;
+        let tr = trs
+            .trs()
+            .iter()
+            .find(|tr| tr.ilabel == label)
+            .ok_or_else(|| format_err!("Missing label {} in state {}", label, state))?;
+        Ok((-tr.weight.value()).exp())
+    }
+
+    #[test]
+    fn test_ngram_count_bigrams() -> Result<()> {
+        let corpus: Vec<VectorFst<TropicalWeight>> = vec![
+            acceptor(&[1, 2], TropicalWeight::one()),
+            acceptor(&[1], TropicalWeight::one()),
+        ];
+        let counts = ngram_count(&corpus, 2)?;
+
+        // States : unigram, then the histories [1], [2] and [<s>].
+        assert_eq!(counts.num_states(), 4);
+        let start = counts.start().unwrap();
+        assert_eq!(start, 3);
+        assert!((count_of(&counts, start, 1)? - 2.0).abs() < 1e-5);
+        assert!((count_of(&counts, 0, 1)? - 2.0).abs() < 1e-5);
+        assert!((count_of(&counts, 0, 2)? - 1.0).abs() < 1e-5);
+        assert!((count_of(&counts, 1, 2)? - 1.0).abs() < 1e-5);
+
+        // Counts of the end of sentence.
+        let final_count = |s| (-counts.final_weight(s).unwrap().unwrap().value()).exp();
+        assert!((final_count(0) - 2.0).abs() < 1e-5);
+        assert!((final_count(1) - 1.0).abs() < 1e-5);
+        assert!((final_count(2) - 1.0).abs() < 1e-5);
+
+        // Backoff transitions.
+        let trs = counts.get_trs(start)?;
+        let backoff = trs.trs().iter().find(|tr| tr.ilabel == EPS_LABEL).unwrap();
+        assert_eq!(backoff.nextstate, 0);
+        Ok(())
+    }
+
+    #[test]
+    fn test_ngram_count_weighted_paths() -> Result<()> {
+        let corpus: Vec<VectorFst<TropicalWeight>> =
+            vec![acceptor(&[1], TropicalWeight::new(-(0.5f32.ln())))];
+        let counts = ngram_count(&corpus, 1)?;
+        assert_eq!(counts.num_states(), 1);
+        assert!((count_of(&counts, 0, 1)? - 0.5).abs() < 1e-5);
+        Ok(())
+    }
+
+    #[test]
+    fn test_ngram_count_lattice() -> Result<()> {
+        // Lattice with 2^20 paths : each position reads 1 or 2 with the probability 0.5.
+        let length = 20;
+        let mut lattice = VectorFst::<TropicalWeight>::new();
+        lattice.add_states(length + 1);
+        lattice.set_start(0)?;
+        let half = TropicalWeight::new(-(0.5f32.ln()));
+        for state in 0..length as StateId {
+            lattice.emplace_tr(state, 1, 1, half, state + 1)?;
+            lattice.emplace_tr(state, 2, 2, half, state + 1)?;
+        }
+        lattice.set_final(length as StateId, TropicalWeight::one())?;
+        let counts = ngram_count(&[lattice], 2)?;
+
+        // States : unigram, then the histories [1], [2] and [<s>].
+        assert_eq!(counts.num_states(), 4);
+        assert!((count_of(&counts, 0, 1)? - 10.0).abs() < 1e-4);
+        assert!((count_of(&counts, 1, 2)? - 19.0 / 4.0).abs() < 1e-4);
+        assert!((count_of(&counts, 3, 2)? - 0.5).abs() < 1e-4);
+        let final_count = (-counts.final_weight(2)?.unwrap().value()).exp();
+        assert!((final_count - 0.5).abs() < 1e-4);
+        Ok(())
+    }
+}
@@ -0,0 +1,198 @@
+use std::collections::BTreeMap;
+
+use anyhow::Result;
+
+use crate::algorithms::ngram::model::{
+    backoff_prob, backoff_weight, prob_to_weight, weight_to_prob,
+};
+use crate::algorithms::ngram::table::{HistoryEntry, NGramTable};
+use crate::fst_impls::VectorFst;
+use crate::fst_traits::{ExpandedFst, Fst};
+use crate::semirings::{Semiring, TropicalWeight};
+
+/// Counts up to which the Katz smoothing discounts the n-grams.
+const KATZ_MAX_DISCOUNTED_COUNT: usize = 5;
+
+/// Smoothing method used to estimate an n-gram model from counts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NGramSmoothing {
+    /// Witten-Bell smoothing : the mass given to the lower order model at a history grows with
+    /// the number of distinct words observed after it. The interpolated model is then
+    /// represented as a backoff model.
+    WittenBell,
+    /// Katz smoothing : the counts up to 5 are discounted with the Good-Turing estimates of
+    /// each order and the mass left is given to the lower order model. Fractional counts are
+    /// rounded to compute the counts of counts.
+    Katz,
+}
+
+/// Estimates a backoff n-gram model from a count FST built by [`ngram_count`](super::ngram_count).
+///
+/// The model has the same topology as the count FST. Its weights are the negative natural
+/// logarithms of the probabilities in the tropical semiring and the backoff transitions hold
+/// the backoff weights, so that the model is normalized at each state. The unigram
+/// probabilities are the relative frequencies of the words.
+///
+/// # Example
+/// ```
+/// # #[macro_use] extern crate rustfst;
+/// # use rustfst::utils::acceptor;
+/// # use rustfst::algorithms::ngram::{ngram_count, ngram_make, NGramSmoothing};
+/// # use rustfst::fst_impls::VectorFst;
+/// # use rustfst::fst_traits::CoreFst;
+/// # use rustfst::semirings::{Semiring, TropicalWeight};
+/// # use anyhow::Result;
+/// # fn main() -> Result<()> {
+/// let corpus : Vec<VectorFst<TropicalWeight>> = vec![fst![1, 2], fst![1, 3], fst![2]];
+/// let counts = ngram_count(&corpus, 2)?;
+/// let model = ngram_make(&counts, NGramSmoothing::WittenBell)?;
+/// assert_eq!(model.start(), counts.start());
+/// # Ok(())
+/// # }
+/// ```
+pub fn ngram_make<W, F>(counts: &F, smoothing: NGramSmoothing) -> Result<VectorFst<TropicalWeight>>
+where
+    W: Semiring<Type = f32>,
+    F: ExpandedFst<W>,
+{
+    let counts_table = NGramTable::from_fst(counts, weight_to_prob)?;
+    let discounts = match smoothing {
+        NGramSmoothing::Katz => katz_discounts(&counts_table),
+        NGramSmoothing::WittenBell => BTreeMap::new(),
+    };
+
+    let mut model = NGramTable::new(counts_table.order);
+    for history in counts_table.histories_by_length() {
+        let counts_entry = &counts_table.histories[&history];
+        let total: f64 = counts_entry.next.values().sum();
+        let mut entry = HistoryEntry::default();
+        if history.is_empty() || total <= 0.0 {
+            for (word, count) in counts_entry.next.iter() {
+                let p = if total > 0.0 { count / total } else { 0.0 };
+                entry.next.insert(*word, p);
+            }
+            if !history.is_empty() {
+                // Nothing observed after the history : all the mass goes to the backoff.
+                entry.backoff = 1.0;
+            }
+            model.histories.insert(history, entry);
+            continue;
+        }
+
+        let lower = model.backoff_history(&history).unwrap().to_vec();
+        match smoothing {
+            NGramSmoothing::WittenBell => {
+                let num_types = counts_entry.next.len() as f64;
+                let lambda = total / (total + num_types);
+                for (word, count) in counts_entry.next.iter() {
+                    let p = lambda * count / total
+                        + (1.0 - lambda) * backoff_prob(&model, &lower, *word);
+                    entry.next.insert(*word, p);
+                }
+            }
+            NGramSmoothing::Katz => {
+                let order_discounts = discounts.get(&(history.len() + 1));
+                for (word, count) in counts_entry.next.iter() {
+                    let r = count.round() as usize;
+                    let discount = order_discounts
+                        .and_then(|d| d.get(&r))
+                        .cloned()
+                        .unwrap_or(1.0);
+                    entry.next.insert(*word, discount * count / total);
+                }
+            }
+        }
+        model.histories.insert(history.clone(), entry);
+        let backoff = backoff_weight(&model, &history);
+        model.histories.get_mut(&history).unwrap().backoff = backoff;
+    }
+
+    let mut fst = model.to_fst(|p| TropicalWeight::new(prob_to_weight(p)))?;
+    if let Some(symt) = counts.input_symbols() {
+        fst.set_input_symbols(symt.clone());
+    }
+    if let Some(symt) = counts.output_symbols() {
+        fst.set_output_symbols(symt.clone());
+    }
+    Ok(fst)
+}
+
+/// Good-Turing discounts of the counts up to `KATZ_MAX_DISCOUNTED_COUNT`, for each order
+/// above 1. The discounts that can't be estimated are left out, the count is then not
+/// discounted.
+fn katz_discounts(counts: &NGramTable) -> BTreeMap<usize, BTreeMap<usize, f64>> {
+    let k = KATZ_MAX_DISCOUNTED_COUNT;
+    let mut counts_of_counts: BTreeMap<usize, Vec<f64>> = BTreeMap::new();
+    for (history, entry) in counts.histories.iter() {
+        if history.is_empty() {
+            continue;
+        }
+        let n = counts_of_counts
+            .entry(history.len() + 1)
+            .or_insert_with(|| vec![0.0; k + 2]);
+        for count in entry.next.values() {
+            let r = count.round() as usize;
+            if (1..=k + 1).contains(&r) {
+                n[r] += 1.0;
+            }
+        }
+    }
+
+    let mut discounts = BTreeMap::new();
+    for (order, n) in counts_of_counts.into_iter() {
+        let mut order_discounts = BTreeMap::new();
+        if n[1] > 0.0 {
+            let common = (k + 1) as f64 * n[k + 1] / n[1];
+            for r in 1..=k {
+                if n[r] <= 0.0 || common >= 1.0 {
+                    continue;
+                }
+                let d = ((r + 1) as f64 * n[r + 1] / (r as f64 * n[r]) - common) / (1.0 - common);
+                if d > 0.0 && d < 1.0 {
+                    order_discounts.insert(r, d);
+                }
+            }
+        }
+        discounts.insert(order, order_discounts);
+    }
+    discounts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::algorithms::ngram::model::tests::check_normalized;
+    use crate::algorithms::ngram::ngram_count;
+    use crate::utils::acceptor;
+
+    fn corpus() -> Vec<VectorFst<TropicalWeight>> {
+        let sentences: Vec<Vec<crate::Label>> = vec![
+            vec![1, 2, 3],
+            vec![1, 2],
+            vec![2, 3, 3],
+            vec![1, 3],
+            vec![3, 2, 1],
+            vec![1, 2, 3, 2],
+        ];
+        sentences
+            .iter()
+            .map(|s| acceptor(s, TropicalWeight::one()))
+            .collect()
+    }
+
+    #[test]
+    fn test_ngram_make_witten_bell() -> Result<()> {
+        let counts = ngram_count(&corpus(), 3)?;
+        let model = ngram_make(&counts, NGramSmoothing::WittenBell)?;
+        assert_eq!(model.num_states(), counts.num_states());
+        check_normalized(&model)
+    }
+
+    #[test]
+    fn test_ngram_make_katz() -> Result<()> {
+        let counts = ngram_count(&corpus(), 2)?;
+        let model = ngram_make(&counts, NGramSmoothing::Katz)?;
+        check_normalized(&model)
+    }
+}
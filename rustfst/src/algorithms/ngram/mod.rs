@@ -0,0 +1,23 @@
+//! N-gram language modeling on top of FSTs, following the conventions of OpenGrm :
+//!
+//! - Each state of an n-gram FST corresponds to a history, the start state being the history
+//!   made of the beginning of sentence.
+//! - A transition labeled `w` leaving the state of the history `h` goes to the state of the
+//!   longest suffix of `hw` which is a history.
+//! - The end of sentence is represented by the final weight of the states.
+//! - Each state except the unigram one (the empty history) has an epsilon transition towards
+//!   the state of its history without its first word : the backoff transition.
+
+pub use self::arpa::{ngram_to_arpa, write_arpa};
+pub use self::count::ngram_count;
+pub use self::make::{ngram_make, NGramSmoothing};
+pub use self::perplexity::{ngram_perplexity, NGramPerplexity};
+pub use self::shrink::{ngram_shrink, NGramShrinkMethod};
+
+mod arpa;
+mod count;
+mod make;
+mod model;
+mod perplexity;
+mod shrink;
+mod table;
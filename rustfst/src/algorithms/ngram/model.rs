@@ -0,0 +1,87 @@
+use crate::algorithms::ngram::table::NGramTable;
+use crate::Label;
+
+/// Probability of `word` after `history` in a backoff model whose values are probabilities.
+pub(crate) fn backoff_prob(model: &NGramTable, history: &[Label], word: Label) -> f64 {
+    let entry = match model.histories.get(history) {
+        Some(entry) => entry,
+        None => {
+            return match model.backoff_history(history) {
+                Some(lower) => backoff_prob(model, lower, word),
+                None => 0.0,
+            }
+        }
+    };
+    if let Some(p) = entry.next.get(&word) {
+        return *p;
+    }
+    match model.backoff_history(history) {
+        Some(lower) => entry.backoff * backoff_prob(model, lower, word),
+        None => 0.0,
+    }
+}
+
+/// Backoff weight of `history` normalizing the model : the mass left by the words observed
+/// after the history divided by the mass of the other words in the lower order model.
+pub(crate) fn backoff_weight(model: &NGramTable, history: &[Label]) -> f64 {
+    let lower = match model.backoff_history(history) {
+        Some(lower) => lower,
+        None => return 1.0,
+    };
+    let entry = &model.histories[history];
+    let mut numerator = 1.0;
+    let mut denominator = 1.0;
+    for (word, p) in entry.next.iter() {
+        numerator -= p;
+        denominator -= backoff_prob(model, lower, *word);
+    }
+    if numerator <= 1e-12 {
+        // All the mass is already taken by the observed words.
+        0.0
+    } else if denominator <= 1e-12 {
+        1.0
+    } else {
+        numerator / denominator
+    }
+}
+
+/// Converts a probability to a weight of a model FST, its negative natural logarithm.
+pub(crate) fn prob_to_weight(p: f64) -> f32 {
+    -(p.ln() as f32)
+}
+
+/// Converts a weight of a model FST back to a probability.
+pub(crate) fn weight_to_prob(w: f32) -> f64 {
+    (-f64::from(w)).exp()
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    use anyhow::Result;
+
+    use crate::algorithms::ngram::table::EOS;
+    use crate::fst_impls::VectorFst;
+    use crate::semirings::TropicalWeight;
+
+    /// Checks that the probabilities of the words after each history of the model sum to one.
+    pub(crate) fn check_normalized(model: &VectorFst<TropicalWeight>) -> Result<()> {
+        let table = NGramTable::from_fst(model, weight_to_prob)?;
+        let mut vocabulary: Vec<_> = table.histories[&Vec::<Label>::new()]
+            .next
+            .keys()
+            .cloned()
+            .collect();
+        vocabulary.push(EOS);
+        vocabulary.dedup();
+        for history in table.histories.keys() {
+            let sum: f64 = vocabulary
+                .iter()
+                .map(|w| backoff_prob(&table, history, *w))
+                .sum();
+            ensure!((sum - 1.0).abs() < 1e-4, "{:?} sums to {}", history, sum);
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,174 @@
+use anyhow::{bail, Result};
+
+use crate::fst_traits::Fst;
+use crate::semirings::Semiring;
+use crate::{Label, StateId, Trs, EPS_LABEL};
+
+/// Result of the evaluation of an n-gram model on a set of sentences.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct NGramPerplexity {
+    /// Number of sentences scored.
+    pub num_sentences: usize,
+    /// Number of words of the sentences, OOVs included.
+    pub num_words: usize,
+    /// Number of words which are not in the vocabulary of the model. They are not scored.
+    pub num_oovs: usize,
+    /// Sum of the natural log probabilities of the scored words and of the ends of sentence.
+    pub log_prob: f64,
+    /// `exp(-log_prob / (num_words - num_oovs + num_sentences))`.
+    pub perplexity: f64,
+}
+
+/// Follows the backoff transitions from `state` until a transition labeled `label` (or a
+/// final weight when `label` is `None`) is found. Returns its weight, with the weights of the
+/// backoff transitions, and its nextstate. Returns `None` if the label is unknown to the model.
+fn next_state<W, F>(
+    lm: &F,
+    mut state: StateId,
+    label: Option<Label>,
+) -> Result<Option<(f64, Option<StateId>)>>
+where
+    W: Semiring<Type = f32>,
+    F: Fst<W>,
+{
+    let mut weight = 0.0;
+    loop {
+        let trs = lm.get_trs(state)?;
+        let mut backoff = None;
+        for tr in trs.trs() {
+            if tr.ilabel == EPS_LABEL {
+                backoff = Some(tr);
+            } else if Some(tr.ilabel) == label {
+                return Ok(Some((
+                    weight + f64::from(*tr.weight.value()),
+                    Some(tr.nextstate),
+                )));
+            }
+        }
+        if label.is_none() {
+            if let Some(final_weight) = lm.final_weight(state)? {
+                return Ok(Some((weight + f64::from(*final_weight.value()), None)));
+            }
+        }
+        match backoff {
+            Some(tr) => {
+                weight += f64::from(*tr.weight.value());
+                state = tr.nextstate;
+            }
+            None => return Ok(None),
+        }
+    }
+}
+
+/// Computes the perplexity of an n-gram model on a set of sentences, as `ngramperplexity`
+/// from OpenGrm does.
+///
+/// The model is a backoff model such as the ones built by [`ngram_make`](super::ngram_make).
+/// Its epsilon transitions are backoff transitions : they are only followed when the current
+/// state has no transition with the next word, as failure (phi) transitions. Each sentence is
+/// scored from the start state, which stands for `<s>`, and ends with the final weight, which
+/// stands for `</s>`. The words missing from the model are counted as OOVs and not scored,
+/// the following word being scored from the unigram state.
+///
+/// # Example
+/// ```
+/// # #[macro_use] extern crate rustfst;
+/// # use rustfst::algorithms::ngram::{ngram_count, ngram_make, ngram_perplexity, NGramSmoothing};
+/// # use rustfst::fst_impls::VectorFst;
+/// # use rustfst::semirings::{Semiring, TropicalWeight};
+/// # use rustfst::utils::acceptor;
+/// # use anyhow::Result;
+/// # fn main() -> Result<()> {
+/// let corpus : Vec<VectorFst<TropicalWeight>> = vec![fst![1, 2], fst![2]];
+/// let model = ngram_make(&ngram_count(&corpus, 2)?, NGramSmoothing::WittenBell)?;
+/// let res = ngram_perplexity(&model, &[vec![1, 2], vec![3, 2]])?;
+/// assert_eq!(res.num_words, 4);
+/// assert_eq!(res.num_oovs, 1);
+/// # Ok(())
+/// # }
+/// ```
+pub fn ngram_perplexity<W, F, S>(lm: &F, sentences: &[S]) -> Result<NGramPerplexity>
+where
+    W: Semiring<Type = f32>,
+    F: Fst<W>,
+    S: AsRef<[Label]>,
+{
+    let start = match lm.start() {
+        Some(start) => start,
+        None => bail!("ngram_perplexity : the model has no start state"),
+    };
+    let mut unigram = start;
+    while let Some(tr) = lm
+        .get_trs(unigram)?
+        .trs()
+        .iter()
+        .find(|tr| tr.ilabel == EPS_LABEL)
+    {
+        unigram = tr.nextstate;
+    }
+
+    let mut res = NGramPerplexity::default();
+    let mut neg_log_prob = 0.0;
+    for sentence in sentences {
+        res.num_sentences += 1;
+        let mut state = start;
+        for word in sentence.as_ref() {
+            res.num_words += 1;
+            match next_state(lm, state, Some(*word))? {
+                Some((weight, nextstate)) => {
+                    neg_log_prob += weight;
+                    state = nextstate.unwrap();
+                }
+                None => {
+                    res.num_oovs += 1;
+                    state = unigram;
+                }
+            }
+        }
+        match next_state(lm, state, None)? {
+            Some((weight, _)) => neg_log_prob += weight,
+            None => bail!("ngram_perplexity : the model can't end a sentence"),
+        }
+    }
+
+    res.log_prob = -neg_log_prob;
+    let num_scored = res.num_words - res.num_oovs + res.num_sentences;
+    res.perplexity = if num_scored > 0 {
+        (neg_log_prob / num_scored as f64).exp()
+    } else {
+        1.0
+    };
+    Ok(res)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::algorithms::ngram::{ngram_count, ngram_make, NGramSmoothing};
+    use crate::fst_impls::VectorFst;
+    use crate::semirings::TropicalWeight;
+    use crate::utils::acceptor;
+
+    #[test]
+    fn test_ngram_perplexity() -> Result<()> {
+        let corpus: Vec<VectorFst<TropicalWeight>> = vec![
+            acceptor(&[1, 2], TropicalWeight::one()),
+            acceptor(&[2], TropicalWeight::one()),
+        ];
+        let model = ngram_make(&ngram_count(&corpus, 2)?, NGramSmoothing::WittenBell)?;
+
+        // p(a|<s>) = 0.35, p(b|a) = 0.7 and p(</s>|b) = 0.8.
+        let res = ngram_perplexity(&model, &[vec![1, 2]])?;
+        let expected = 0.35f64.ln() + 0.7f64.ln() + 0.8f64.ln();
+        assert!((res.log_prob - expected).abs() < 1e-5);
+        assert!((res.perplexity - (-expected / 3.0).exp()).abs() < 1e-4);
+
+        // b is not seen after b : backoff to the unigram state, then p(b) = 0.4.
+        let res = ngram_perplexity(&model, &[vec![2, 2]])?;
+        let backoff_b = (1.0 - 0.8) / (1.0 - 0.4);
+        let expected = 0.45f64.ln() + (backoff_b * 0.4f64).ln() + 0.8f64.ln();
+        assert!((res.log_prob - expected).abs() < 1e-5);
+        Ok(())
+    }
+}
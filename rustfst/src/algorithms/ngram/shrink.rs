@@ -0,0 +1,188 @@
+use anyhow::Result;
+
+use crate::algorithms::ngram::model::{
+    backoff_prob, backoff_weight, prob_to_weight, weight_to_prob,
+};
+use crate::algorithms::ngram::table::{NGramTable, BOS};
+use crate::fst_impls::VectorFst;
+use crate::fst_traits::{ExpandedFst, Fst};
+use crate::semirings::{LogWeight, Semiring, TropicalWeight};
+use crate::Label;
+
+/// Criterion used to select the n-grams removed by [`ngram_shrink`].
+#[derive(Debug, Clone)]
+pub enum NGramShrinkMethod {
+    /// Relative entropy pruning (Stolcke, 1998) : removes the n-grams whose removal increases
+    /// the relative entropy between the original model and the pruned one by less than
+    /// `theta`.
+    RelativeEntropy { theta: f64 },
+    /// Removes the n-grams whose count is below `min_count`, the counts being the ones of the
+    /// count FST the model was estimated from.
+    Count {
+        counts: VectorFst<LogWeight>,
+        min_count: f64,
+    },
+}
+
+/// Shrinks a backoff n-gram model built by [`ngram_make`](super::ngram_make), as
+/// `ngramshrink` from OpenGrm does.
+///
+/// The unigrams and the n-grams which are the history of a state are never removed. All the
+/// n-grams are scored against the original model, then the backoff weights are recomputed so
+/// that the pruned model is normalized.
+pub fn ngram_shrink<F>(model: &F, method: NGramShrinkMethod) -> Result<VectorFst<TropicalWeight>>
+where
+    F: ExpandedFst<TropicalWeight>,
+{
+    let mut table = NGramTable::from_fst(model, weight_to_prob)?;
+    let counts = match &method {
+        NGramShrinkMethod::Count { counts, .. } => {
+            Some(NGramTable::from_fst(counts, weight_to_prob)?)
+        }
+        NGramShrinkMethod::RelativeEntropy { .. } => None,
+    };
+
+    let mut pruned = vec![];
+    for (history, entry) in table.histories.iter() {
+        let lower = match table.backoff_history(history) {
+            Some(lower) => lower,
+            None => continue,
+        };
+        let sum_p: f64 = entry.next.values().sum();
+        let sum_lower: f64 = entry
+            .next
+            .keys()
+            .map(|w| backoff_prob(&table, lower, *w))
+            .sum();
+        let history_prob = match &method {
+            NGramShrinkMethod::RelativeEntropy { .. } => history_prob(&table, history),
+            NGramShrinkMethod::Count { .. } => 0.0,
+        };
+
+        let mut ngram = history.clone();
+        for (word, p) in entry.next.iter() {
+            ngram.push(*word);
+            let is_history = table.histories.contains_key(&ngram);
+            ngram.pop();
+            if is_history {
+                continue;
+            }
+            let prune = match &method {
+                NGramShrinkMethod::Count { min_count, .. } => {
+                    let count = counts
+                        .as_ref()
+                        .and_then(|c| c.histories.get(history))
+                        .and_then(|e| e.next.get(word))
+                        .cloned()
+                        .unwrap_or(0.0);
+                    count < *min_count
+                }
+                NGramShrinkMethod::RelativeEntropy { theta } => {
+                    let q = backoff_prob(&table, lower, *word);
+                    if entry.backoff <= 0.0 || q <= 0.0 {
+                        false
+                    } else {
+                        let new_backoff = (1.0 - sum_p + p) / (1.0 - sum_lower + q);
+                        let delta = -history_prob
+                            * (p * ((new_backoff * q).ln() - p.ln())
+                                + (1.0 - sum_p) * (new_backoff.ln() - entry.backoff.ln()));
+                        delta < *theta
+                    }
+                }
+            };
+            if prune {
+                pruned.push((history.clone(), *word));
+            }
+        }
+    }
+
+    for (history, word) in pruned {
+        table
+            .histories
+            .get_mut(&history)
+            .unwrap()
+            .next
+            .remove(&word);
+    }
+    for history in table.histories_by_length() {
+        let backoff = backoff_weight(&table, &history);
+        table.histories.get_mut(&history).unwrap().backoff = backoff;
+    }
+
+    let mut fst = table.to_fst(|p| TropicalWeight::new(prob_to_weight(p)))?;
+    if let Some(symt) = model.input_symbols() {
+        fst.set_input_symbols(symt.clone());
+    }
+    if let Some(symt) = model.output_symbols() {
+        fst.set_output_symbols(symt.clone());
+    }
+    Ok(fst)
+}
+
+/// Probability of the history according to the model. The histories which don't start with
+/// the beginning of sentence are approximated with the unigram probability of their first
+/// word.
+fn history_prob(table: &NGramTable, history: &[Label]) -> f64 {
+    let mut prob = match history.first() {
+        Some(w) if *w == BOS => 1.0,
+        Some(w) => backoff_prob(table, &[], *w),
+        None => return 1.0,
+    };
+    for (i, word) in history.iter().enumerate().skip(1) {
+        prob *= backoff_prob(table, &history[..i], *word);
+    }
+    prob
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::algorithms::ngram::model::tests::check_normalized;
+    use crate::algorithms::ngram::{ngram_count, ngram_make, NGramSmoothing};
+    use crate::fst_traits::CoreFst;
+    use crate::semirings::Semiring;
+    use crate::utils::acceptor;
+
+    fn num_trs(fst: &VectorFst<TropicalWeight>) -> usize {
+        fst.states_range().map(|s| fst.num_trs(s).unwrap()).sum()
+    }
+
+    fn counts() -> Result<VectorFst<LogWeight>> {
+        let corpus: Vec<VectorFst<TropicalWeight>> = vec![
+            acceptor(&[1, 2, 3], TropicalWeight::one()),
+            acceptor(&[1, 2], TropicalWeight::one()),
+            acceptor(&[2, 3, 1], TropicalWeight::one()),
+            acceptor(&[3, 3], TropicalWeight::one()),
+        ];
+        ngram_count(&corpus, 2)
+    }
+
+    #[test]
+    fn test_ngram_shrink_count() -> Result<()> {
+        let counts = counts()?;
+        let model = ngram_make(&counts, NGramSmoothing::WittenBell)?;
+        let shrunk = ngram_shrink(
+            &model,
+            NGramShrinkMethod::Count {
+                counts,
+                min_count: 2.0,
+            },
+        )?;
+        assert!(num_trs(&shrunk) < num_trs(&model));
+        assert_eq!(shrunk.num_states(), model.num_states());
+        check_normalized(&shrunk)
+    }
+
+    #[test]
+    fn test_ngram_shrink_relative_entropy() -> Result<()> {
+        let model = ngram_make(&counts()?, NGramSmoothing::WittenBell)?;
+
+        let same = ngram_shrink(&model, NGramShrinkMethod::RelativeEntropy { theta: -1.0 })?;
+        assert_eq!(num_trs(&same), num_trs(&model));
+
+        let shrunk = ngram_shrink(&model, NGramShrinkMethod::RelativeEntropy { theta: 1.0 })?;
+        assert!(num_trs(&shrunk) < num_trs(&model));
+        check_normalized(&shrunk)
+    }
+}
@@ -0,0 +1,227 @@
+use std::collections::{BTreeMap, VecDeque};
+
+use anyhow::{bail, Result};
+
+use crate::fst_impls::VectorFst;
+use crate::fst_traits::{ExpandedFst, MutableFst};
+use crate::semirings::Semiring;
+use crate::{Label, StateId, Tr, Trs, EPS_LABEL};
+
+/// Marker of the beginning of sentence in the histories. It is never used as a label.
+pub(crate) const BOS: Label = Label::MAX - 1;
+/// Marker of the end of sentence. It is represented by the final weights in the FSTs.
+pub(crate) const EOS: Label = Label::MAX;
+
+/// Values attached to a history : one per word following it and the backoff value.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub(crate) struct HistoryEntry {
+    pub(crate) next: BTreeMap<Label, f64>,
+    pub(crate) backoff: f64,
+}
+
+/// N-gram table indexed by history. Depending on the step of the pipeline, the values are
+/// counts or probabilities.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub(crate) struct NGramTable {
+    pub(crate) order: usize,
+    pub(crate) histories: BTreeMap<Vec<Label>, HistoryEntry>,
+}
+
+impl NGramTable {
+    pub(crate) fn new(order: usize) -> Self {
+        Self {
+            order,
+            histories: BTreeMap::new(),
+        }
+    }
+
+    /// Adds `value` to the value of the n-gram made of `history` followed by `word`.
+    pub(crate) fn add(&mut self, history: &[Label], word: Label, value: f64) {
+        *self
+            .histories
+            .entry(history.to_vec())
+            .or_default()
+            .next
+            .entry(word)
+            .or_insert(0.0) += value;
+    }
+
+    /// Longest suffix of `ngram` which is a history of the table.
+    pub(crate) fn longest_history_suffix<'a>(&self, ngram: &'a [Label]) -> &'a [Label] {
+        let mut suffix = ngram;
+        while !suffix.is_empty() && !self.histories.contains_key(suffix) {
+            suffix = &suffix[1..];
+        }
+        suffix
+    }
+
+    /// History the given history backs off to.
+    pub(crate) fn backoff_history<'a>(&self, history: &'a [Label]) -> Option<&'a [Label]> {
+        if history.is_empty() {
+            None
+        } else {
+            Some(self.longest_history_suffix(&history[1..]))
+        }
+    }
+
+    /// Histories of the table, shortest first.
+    pub(crate) fn histories_by_length(&self) -> Vec<Vec<Label>> {
+        let mut histories: Vec<_> = self.histories.keys().cloned().collect();
+        histories.sort_by_key(|h| h.len());
+        histories
+    }
+
+    /// Reads an n-gram FST. `value` converts the weights back to the values of the table.
+    ///
+    /// The history of each state is recovered from the number of backoff transitions between
+    /// the state and the unigram state and from the words read to reach it.
+    pub(crate) fn from_fst<W, F, C>(fst: &F, value: C) -> Result<Self>
+    where
+        W: Semiring<Type = f32>,
+        F: ExpandedFst<W>,
+        C: Fn(f32) -> f64,
+    {
+        let num_states = fst.num_states();
+        let start = match fst.start() {
+            Some(start) => start,
+            None => bail!("The n-gram FST has no start state"),
+        };
+
+        let mut backoffs = vec![None; num_states];
+        for state in fst.states_range() {
+            for tr in fst.get_trs(state)?.trs() {
+                if tr.ilabel == EPS_LABEL {
+                    if backoffs[state as usize].is_some() {
+                        bail!("State {} has several backoff transitions", state);
+                    }
+                    backoffs[state as usize] = Some(tr.nextstate);
+                }
+            }
+        }
+
+        // Length of the history of each state : number of backoffs to reach the unigram state.
+        let mut lengths: Vec<Option<usize>> = vec![None; num_states];
+        for state in 0..num_states {
+            let mut chain = vec![];
+            let mut cur = state;
+            while lengths[cur].is_none() {
+                if chain.len() > num_states {
+                    bail!("The backoff transitions of the n-gram FST are cyclic");
+                }
+                chain.push(cur);
+                match backoffs[cur] {
+                    Some(next) => cur = next as usize,
+                    None => {
+                        lengths[cur] = Some(0);
+                        chain.pop();
+                    }
+                }
+            }
+            let mut length = lengths[cur].unwrap();
+            for s in chain.into_iter().rev() {
+                length += 1;
+                lengths[s] = Some(length);
+            }
+        }
+        let lengths: Vec<usize> = lengths.into_iter().map(|l| l.unwrap()).collect();
+
+        let mut unigram = start;
+        while let Some(next) = backoffs[unigram as usize] {
+            unigram = next;
+        }
+
+        let mut histories: Vec<Option<Vec<Label>>> = vec![None; num_states];
+        histories[unigram as usize] = Some(vec![]);
+        if start != unigram {
+            if lengths[start as usize] != 1 {
+                bail!("The start state of the n-gram FST must be the history of <s>");
+            }
+            histories[start as usize] = Some(vec![BOS]);
+        }
+        let mut queue: VecDeque<StateId> = vec![start, unigram].into_iter().collect();
+        while let Some(state) = queue.pop_front() {
+            let history = histories[state as usize].clone().unwrap();
+            for tr in fst.get_trs(state)?.trs() {
+                if tr.ilabel == EPS_LABEL || histories[tr.nextstate as usize].is_some() {
+                    continue;
+                }
+                let mut ngram = history.clone();
+                ngram.push(tr.ilabel);
+                let length = lengths[tr.nextstate as usize];
+                if length > ngram.len() {
+                    bail!("Inconsistent history length for state {}", tr.nextstate);
+                }
+                histories[tr.nextstate as usize] = Some(ngram[ngram.len() - length..].to_vec());
+                queue.push_back(tr.nextstate);
+            }
+        }
+
+        let order = lengths.iter().max().cloned().unwrap_or(0) + 1;
+        let mut table = Self::new(order);
+        for state in fst.states_range() {
+            let history = match &histories[state as usize] {
+                Some(history) => history.clone(),
+                None => bail!("State {} of the n-gram FST is not reachable", state),
+            };
+            let entry = table
+                .histories
+                .entry(history)
+                .or_insert_with(HistoryEntry::default);
+            for tr in fst.get_trs(state)?.trs() {
+                if tr.ilabel == EPS_LABEL {
+                    entry.backoff = value(*tr.weight.value());
+                } else {
+                    entry.next.insert(tr.ilabel, value(*tr.weight.value()));
+                }
+            }
+            if let Some(final_weight) = fst.final_weight(state)? {
+                entry.next.insert(EOS, value(*final_weight.value()));
+            }
+        }
+        Ok(table)
+    }
+
+    /// Builds the FST of the table. `weight` converts the values of the n-grams and of the
+    /// backoffs to weights.
+    pub(crate) fn to_fst<W: Semiring, C: Fn(f64) -> W>(&self, weight: C) -> Result<VectorFst<W>> {
+        let mut fst = VectorFst::new();
+        // Makes sure the unigram state exists.
+        let mut states: BTreeMap<Vec<Label>, StateId> = BTreeMap::new();
+        states.insert(vec![], fst.add_state());
+        for history in self.histories.keys() {
+            if !history.is_empty() {
+                states.insert(history.clone(), fst.add_state());
+            }
+        }
+
+        let start = states.get(&[BOS][..]).cloned().unwrap_or(states[&[][..]]);
+        fst.set_start(start)?;
+
+        for (history, entry) in self.histories.iter() {
+            let state = states[history];
+            let mut ngram = history.clone();
+            for (word, value) in entry.next.iter() {
+                if *word == EOS {
+                    fst.set_final(state, weight(*value))?;
+                    continue;
+                }
+                ngram.push(*word);
+                let nextstate = states[self.longest_history_suffix(&ngram)];
+                fst.add_tr(state, Tr::new(*word, *word, weight(*value), nextstate))?;
+                ngram.pop();
+            }
+            if let Some(backoff_history) = self.backoff_history(history) {
+                fst.add_tr(
+                    state,
+                    Tr::new(
+                        EPS_LABEL,
+                        EPS_LABEL,
+                        weight(entry.backoff),
+                        states[backoff_history],
+                    ),
+                )?;
+            }
+        }
+        Ok(fst)
+    }
+}
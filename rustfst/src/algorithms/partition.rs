@@ -176,6 +176,23 @@ impl Partition {
         self.yes_counter += 1;
     }
 
+    /// As `finalize_split`, but the classes split are enqueued along with the new classes.
+    /// Only the new class, the smaller part, needs to be processed again when refining the
+    /// partition of a deterministic automaton, but both parts do for a non-deterministic one:
+    /// a state may have transitions with the same label into both.
+    pub fn finalize_split_enqueue_all<Q: Queue>(&mut self, queue: &mut Q) {
+        let visited_classes = self.visited_classes.clone();
+        for visited_class in visited_classes {
+            let new_class = self.split_refine(visited_class);
+            if new_class != -1 {
+                queue.enqueue(visited_class as StateId);
+                queue.enqueue(new_class as StateId);
+            }
+        }
+        self.visited_classes.clear();
+        self.yes_counter += 1;
+    }
+
     pub fn get_class_id(&self, element_id: usize) -> usize {
         self.elements[element_id].class_id
     }
@@ -0,0 +1,285 @@
+use std::collections::HashSet;
+use std::marker::PhantomData;
+
+use anyhow::Result;
+
+use crate::algorithms::queues::AutoQueue;
+use crate::algorithms::tr_filters::AnyTrFilter;
+use crate::algorithms::Queue;
+use crate::fst_properties::mutable_properties::shortest_path_properties;
+use crate::fst_properties::FstProperties;
+use crate::fst_traits::{ExpandedFst, MutableFst};
+use crate::semirings::{Semiring, SemiringProperties};
+use crate::{Label, StateId, Tr, Trs, EPS_LABEL};
+
+/// Transitions of the states of an FST whose `phi_label` transitions are failure transitions,
+/// as if they had been replaced by the transitions they stand for.
+struct PhiExpander<'a, W: Semiring, F: ExpandedFst<W>> {
+    fst: &'a F,
+    phi_label: Label,
+    w: PhantomData<W>,
+}
+
+impl<'a, W: Semiring, F: ExpandedFst<W>> PhiExpander<'a, W, F> {
+    fn new(fst: &'a F, phi_label: Label) -> Self {
+        Self {
+            fst,
+            phi_label,
+            w: PhantomData,
+        }
+    }
+
+    /// Failure transition of `state`, if any.
+    fn phi_tr(&self, state: StateId) -> Result<Option<Tr<W>>> {
+        let trs = self.fst.get_trs(state)?;
+        let mut phi_trs = trs.trs().iter().filter(|tr| tr.ilabel == self.phi_label);
+        let phi_tr = phi_trs.next().cloned();
+        if phi_trs.next().is_some() {
+            bail!("State {} has several failure transitions", state);
+        }
+        Ok(phi_tr)
+    }
+
+    /// Trs of `state` followed by the trs of the states reached by its failure transitions on
+    /// the labels not matched before, their weights multiplied by the weights of the failure
+    /// transitions taken. The epsilon transitions of these states are not followed.
+    fn trs(&self, state: StateId) -> Result<Vec<Tr<W>>> {
+        let mut res = vec![];
+        let mut matched = HashSet::new();
+        let mut visited = HashSet::new();
+        let mut current = state;
+        let mut weight = W::one();
+        loop {
+            visited.insert(current);
+            let mut labels = vec![];
+            for tr in self.fst.get_trs(current)?.trs() {
+                if tr.ilabel == self.phi_label || (current != state && tr.ilabel == EPS_LABEL) {
+                    continue;
+                }
+                if !matched.contains(&tr.ilabel) {
+                    let mut tr = tr.clone();
+                    tr.weight = weight.times(&tr.weight)?;
+                    res.push(tr);
+                }
+                if tr.ilabel != EPS_LABEL {
+                    labels.push(tr.ilabel);
+                }
+            }
+            matched.extend(labels);
+            match self.phi_tr(current)? {
+                Some(phi_tr) if !visited.contains(&phi_tr.nextstate) => {
+                    weight.times_assign(&phi_tr.weight)?;
+                    current = phi_tr.nextstate;
+                }
+                _ => break,
+            }
+        }
+        Ok(res)
+    }
+
+    /// Final weight of `state`, or of the first final state reached by its failure transitions
+    /// multiplied by their weights.
+    fn final_weight(&self, state: StateId) -> Result<Option<W>> {
+        let mut visited = HashSet::new();
+        let mut current = state;
+        let mut weight = W::one();
+        loop {
+            if let Some(final_weight) = self.fst.final_weight(current)? {
+                return Ok(Some(weight.times(&final_weight)?));
+            }
+            visited.insert(current);
+            match self.phi_tr(current)? {
+                Some(phi_tr) if !visited.contains(&phi_tr.nextstate) => {
+                    weight.times_assign(&phi_tr.weight)?;
+                    current = phi_tr.nextstate;
+                }
+                _ => return Ok(None),
+            }
+        }
+    }
+}
+
+/// Shortest distance from the initial state to every state, the `phi_label` transitions being
+/// failure transitions. See `ShortestDistanceConfig::with_phi_label`.
+pub(crate) fn phi_shortest_distance<W: Semiring, F: ExpandedFst<W>>(
+    fst: &F,
+    phi_label: Label,
+    delta: f32,
+) -> Result<Vec<W>> {
+    if !W::properties().contains(SemiringProperties::RIGHT_SEMIRING) {
+        bail!("ShortestDistance: Weight needs to be right distributive")
+    }
+    let start = match fst.start() {
+        Some(start) => start,
+        None => return Ok(vec![]),
+    };
+    let expander = PhiExpander::new(fst, phi_label);
+    // The trs of the expanded states follow paths of the FST, so its order suits them too.
+    let mut queue = AutoQueue::new(fst, None, &AnyTrFilter {})?;
+    let mut distance = vec![W::zero(); fst.num_states()];
+    let mut radder = vec![W::zero(); fst.num_states()];
+    let mut enqueued = vec![false; fst.num_states()];
+    distance[start as usize] = W::one();
+    radder[start as usize] = W::one();
+    enqueued[start as usize] = true;
+    queue.enqueue(start);
+    while !queue.is_empty() {
+        let state = queue.head().unwrap();
+        queue.dequeue();
+        enqueued[state as usize] = false;
+        let r = std::mem::replace(&mut radder[state as usize], W::zero());
+        for tr in expander.trs(state)? {
+            let nextstate = tr.nextstate as usize;
+            let weight = r.times(&tr.weight)?;
+            let nd = &mut distance[nextstate];
+            if !nd.approx_equal(nd.plus(&weight)?, delta) {
+                nd.plus_assign(&weight)?;
+                radder[nextstate].plus_assign(&weight)?;
+                if !enqueued[nextstate] {
+                    queue.enqueue(tr.nextstate);
+                    enqueued[nextstate] = true;
+                } else {
+                    queue.update(tr.nextstate);
+                }
+            }
+        }
+    }
+    Ok(distance)
+}
+
+/// Single shortest path, the `phi_label` transitions being failure transitions. Each failure
+/// transition taken is merged with the transition it leads to. See `ShortestPathConfig::phi_label`.
+pub(crate) fn phi_shortest_path<W, FI, FO>(ifst: &FI, phi_label: Label) -> Result<FO>
+where
+    W: Semiring,
+    FI: ExpandedFst<W>,
+    FO: MutableFst<W>,
+{
+    if !W::properties().contains(SemiringProperties::PATH | SemiringProperties::RIGHT_SEMIRING) {
+        bail!(
+            "SingleShortestPath: Weight needs to have the path property and be right distributive"
+        )
+    }
+    let mut ofst = FO::new();
+    let start = match ifst.start() {
+        Some(start) => start,
+        None => return Ok(ofst),
+    };
+    let expander = PhiExpander::new(ifst, phi_label);
+    let mut queue = AutoQueue::new(ifst, None, &AnyTrFilter {})?;
+    let mut distance = vec![W::zero(); ifst.num_states()];
+    let mut parent: Vec<Option<(StateId, Tr<W>)>> = vec![None; ifst.num_states()];
+    let mut enqueued = vec![false; ifst.num_states()];
+    let mut f_distance = W::zero();
+    let mut f_parent = None;
+    distance[start as usize] = W::one();
+    enqueued[start as usize] = true;
+    queue.enqueue(start);
+    while !queue.is_empty() {
+        let s = queue.head().unwrap();
+        queue.dequeue();
+        enqueued[s as usize] = false;
+        let sd = distance[s as usize].clone();
+        if let Some(final_weight) = expander.final_weight(s)? {
+            let plus = f_distance.plus(&sd.times(&final_weight)?)?;
+            if f_distance != plus {
+                f_distance = plus;
+                f_parent = Some((s, final_weight));
+            }
+        }
+        for tr in expander.trs(s)? {
+            let nextstate = tr.nextstate as usize;
+            let nd = &mut distance[nextstate];
+            let weight = sd.times(&tr.weight)?;
+            if *nd != nd.plus(&weight)? {
+                *nd = nd.plus(&weight)?;
+                parent[nextstate] = Some((s, tr));
+                if !enqueued[nextstate] {
+                    queue.enqueue(nextstate as StateId);
+                    enqueued[nextstate] = true;
+                } else {
+                    queue.update(nextstate as StateId);
+                }
+            }
+        }
+    }
+
+    if let Some((final_state, final_weight)) = f_parent {
+        let mut ostate = ofst.add_state();
+        ofst.set_final(ostate, final_weight)?;
+        let mut state = final_state;
+        while let Some((prevstate, tr)) = &parent[state as usize] {
+            let prev_ostate = ofst.add_state();
+            let mut tr = tr.clone();
+            tr.nextstate = ostate;
+            ofst.add_tr(prev_ostate, tr)?;
+            ostate = prev_ostate;
+            state = *prevstate;
+        }
+        ofst.set_start(ostate)?;
+    }
+    ofst.set_properties_with_mask(
+        shortest_path_properties(ofst.properties(), true),
+        FstProperties::all_properties(),
+    );
+    ofst.set_symts_from_fst(ifst);
+    Ok(ofst)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::algorithms::{
+        shortest_distance_with_config, shortest_path_with_config, ShortestDistanceConfig,
+        ShortestPathConfig,
+    };
+    use crate::fst_impls::VectorFst;
+    use crate::fst_traits::Fst;
+    use crate::semirings::TropicalWeight;
+    use crate::FstPath;
+
+    #[test]
+    fn test_phi_shortest() -> Result<()> {
+        // Backoff model : the history 1 backs off to the unigram state 0, where 1 is cheaper.
+        let phi = 100;
+        let mut fst = VectorFst::<TropicalWeight>::new();
+        fst.add_states(3);
+        fst.set_start(1)?;
+        fst.add_tr(1, Tr::new(1, 1, 1.0, 2))?;
+        fst.add_tr(1, Tr::new(phi, phi, 0.5, 0))?;
+        fst.add_tr(0, Tr::new(1, 1, 0.1, 2))?;
+        fst.add_tr(0, Tr::new(2, 2, 2.0, 2))?;
+        fst.set_final(2, TropicalWeight::one())?;
+
+        let config = ShortestDistanceConfig::default().with_phi_label(phi);
+        let distance = shortest_distance_with_config(&fst, false, config)?;
+        assert_eq!(
+            distance,
+            vec![
+                TropicalWeight::zero(),
+                TropicalWeight::one(),
+                TropicalWeight::new(1.0)
+            ]
+        );
+        // As a regular transition, the failure transition would lead to a cheaper 1.
+        let distance =
+            shortest_distance_with_config(&fst, false, ShortestDistanceConfig::default())?;
+        assert_eq!(distance[2], TropicalWeight::new(0.6));
+
+        let config = ShortestPathConfig::default().with_phi_label(phi);
+        let path: VectorFst<TropicalWeight> = shortest_path_with_config(&fst, config)?;
+        let paths: Vec<_> = path.paths_iter().collect();
+        assert_eq!(
+            paths,
+            vec![FstPath::new(vec![1], vec![1], TropicalWeight::new(1.0))]
+        );
+
+        // The label 2 is only read through the failure transition.
+        let expander = PhiExpander::new(&fst, phi);
+        let trs = expander.trs(1)?;
+        assert_eq!(trs, vec![Tr::new(1, 1, 1.0, 2), Tr::new(2, 2, 2.5, 2)]);
+        assert_eq!(expander.final_weight(0)?, None);
+        Ok(())
+    }
+}
@@ -0,0 +1,187 @@
+use std::collections::hash_map::Entry;
+use std::collections::{BTreeSet, HashMap, VecDeque};
+
+use anyhow::Result;
+
+use crate::algorithms::complement::complement_over_alphabet;
+use crate::algorithms::compose::compose;
+use crate::algorithms::fst_convert_from_ref;
+use crate::algorithms::tr_compares::{ILabelCompare, OLabelCompare};
+use crate::algorithms::tr_sort;
+use crate::algorithms::union::union;
+use crate::fst_impls::VectorFst;
+use crate::fst_traits::{AllocableFst, ExpandedFst, Fst, MutableFst};
+use crate::semirings::Semiring;
+use crate::{Label, StateId, Tr, Trs, EPS_LABEL};
+
+/// Set of states of the input reached by reading a string, closed under input epsilons.
+type StateSubset = BTreeSet<StateId>;
+
+fn input_epsilon_closure<W: Semiring, F: Fst<W>>(fst: &F, subset: &mut StateSubset) -> Result<()> {
+    let mut queue: Vec<StateId> = subset.iter().cloned().collect();
+    while let Some(state) = queue.pop() {
+        for tr in fst.get_trs(state)?.trs() {
+            if tr.ilabel == EPS_LABEL && subset.insert(tr.nextstate) {
+                queue.push(tr.nextstate);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Builds the unweighted deterministic acceptor of the domain of `fst`, restricted to the
+/// strings over `alphabet`, using a subset construction.
+fn domain_acceptor<W, F>(fst: &F, alphabet: &BTreeSet<Label>) -> Result<VectorFst<W>>
+where
+    W: Semiring,
+    F: Fst<W>,
+{
+    let mut ofst = VectorFst::new();
+    let mut subsets: HashMap<StateSubset, StateId> = HashMap::new();
+    let mut queue = VecDeque::new();
+
+    let mut start_subset = StateSubset::new();
+    if let Some(start) = fst.start() {
+        start_subset.insert(start);
+        input_epsilon_closure(fst, &mut start_subset)?;
+    }
+    let ostart = ofst.add_state();
+    ofst.set_start(ostart)?;
+    subsets.insert(start_subset.clone(), ostart);
+    queue.push_back((start_subset, ostart));
+
+    while let Some((subset, ostate)) = queue.pop_front() {
+        for state in subset.iter() {
+            if fst.is_final(*state)? {
+                ofst.set_final(ostate, W::one())?;
+                break;
+            }
+        }
+        for label in alphabet.iter() {
+            let mut next_subset = StateSubset::new();
+            for state in subset.iter() {
+                for tr in fst.get_trs(*state)?.trs() {
+                    if tr.ilabel == *label && !tr.weight.is_zero() {
+                        next_subset.insert(tr.nextstate);
+                    }
+                }
+            }
+            if next_subset.is_empty() {
+                continue;
+            }
+            input_epsilon_closure(fst, &mut next_subset)?;
+            let next_ostate = match subsets.entry(next_subset) {
+                Entry::Occupied(e) => *e.get(),
+                Entry::Vacant(e) => {
+                    let s = ofst.add_state();
+                    queue.push_back((e.key().clone(), s));
+                    e.insert(s);
+                    s
+                }
+            };
+            ofst.add_tr(ostate, Tr::new(*label, *label, W::one(), next_ostate))?;
+        }
+    }
+    Ok(ofst)
+}
+
+/// Computes the priority union of two FSTs: the strings in the domain of `fst_1` are
+/// transduced by `fst_1` only, the other ones by `fst_2`. This is the usual way to layer
+/// exceptions (`fst_1`) over default rules (`fst_2`).
+///
+/// The result is the union of `fst_1` with the composition of the complement of the domain
+/// of `fst_1` with `fst_2`. The complement is computed over the non-epsilon input labels of
+/// both FSTs, through a determinization of the domain of `fst_1`, which can be exponential
+/// in the number of its states.
+///
+/// # Example
+/// ```
+/// # #[macro_use] extern crate rustfst;
+/// # use rustfst::utils::{acceptor, transducer};
+/// # use rustfst::algorithms::{priority_union, transduce};
+/// # use rustfst::algorithms::union::union;
+/// # use rustfst::fst_impls::VectorFst;
+/// # use rustfst::semirings::{Semiring, TropicalWeight};
+/// # use anyhow::Result;
+/// # fn main() -> Result<()> {
+/// // Exception : 1 -> 10.
+/// let exception : VectorFst<TropicalWeight> = fst![1 => 10];
+/// // Default rule : 1 -> 20 and 2 -> 20.
+/// let mut rule : VectorFst<TropicalWeight> = fst![1 => 20];
+/// let rule_2 : VectorFst<TropicalWeight> = fst![2 => 20];
+/// union(&mut rule, &rule_2)?;
+///
+/// let fst : VectorFst<TropicalWeight> = priority_union(&exception, &rule)?;
+/// assert_eq!(transduce(&fst, &[1], 10)?, vec![(vec![10], TropicalWeight::one())]);
+/// assert_eq!(transduce(&fst, &[2], 10)?, vec![(vec![20], TropicalWeight::one())]);
+/// # Ok(())
+/// # }
+/// ```
+pub fn priority_union<W, F1, F2, F3>(fst_1: &F1, fst_2: &F2) -> Result<F3>
+where
+    W: Semiring,
+    F1: ExpandedFst<W>,
+    F2: ExpandedFst<W>,
+    F3: MutableFst<W> + AllocableFst<W> + ExpandedFst<W>,
+{
+    let mut alphabet = BTreeSet::new();
+    for state in fst_1.states_range() {
+        alphabet.extend(fst_1.get_trs(state)?.trs().iter().map(|tr| tr.ilabel));
+    }
+    for state in fst_2.states_range() {
+        alphabet.extend(fst_2.get_trs(state)?.trs().iter().map(|tr| tr.ilabel));
+    }
+    alphabet.remove(&EPS_LABEL);
+
+    let mut complement = domain_acceptor(fst_1, &alphabet)?;
+    complement_over_alphabet(&mut complement, &alphabet)?;
+    tr_sort(&mut complement, OLabelCompare {});
+    let mut sorted_fst_2: VectorFst<W> = fst_convert_from_ref(fst_2);
+    tr_sort(&mut sorted_fst_2, ILabelCompare {});
+    let fallback: F3 =
+        compose::<W, VectorFst<W>, VectorFst<W>, _, _, _>(&complement, &sorted_fst_2)?;
+
+    let mut res: F3 = fst_convert_from_ref(fst_1);
+    union(&mut res, &fallback)?;
+    Ok(res)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::algorithms::transduce;
+    use crate::semirings::TropicalWeight;
+
+    #[test]
+    fn test_priority_union_with_epsilons() -> Result<()> {
+        // Exception : 1 2 -> 5 (with an input epsilon) and the empty string -> 6.
+        let mut exception = VectorFst::<TropicalWeight>::new();
+        let s0 = exception.add_state();
+        let s1 = exception.add_state();
+        let s2 = exception.add_state();
+        let s3 = exception.add_state();
+        exception.set_start(s0)?;
+        exception.add_tr(s0, Tr::new(1, 5, 1.0, s1))?;
+        exception.add_tr(s1, Tr::new(EPS_LABEL, EPS_LABEL, 0.0, s2))?;
+        exception.add_tr(s2, Tr::new(2, EPS_LABEL, 0.0, s3))?;
+        exception.set_final(s3, TropicalWeight::one())?;
+        exception.add_tr(s0, Tr::new(EPS_LABEL, 6, 0.0, s3))?;
+
+        // Default : (1:7 | 2:8)*
+        let mut rule = VectorFst::<TropicalWeight>::new();
+        let s0 = rule.add_state();
+        rule.set_start(s0)?;
+        rule.set_final(s0, TropicalWeight::one())?;
+        rule.add_tr(s0, Tr::new(1, 7, 2.0, s0))?;
+        rule.add_tr(s0, Tr::new(2, 8, 2.0, s0))?;
+
+        let fst: VectorFst<_> = priority_union(&exception, &rule)?;
+        let t = |input: &[Label]| transduce(&fst, input, 10);
+        assert_eq!(t(&[1, 2])?, vec![(vec![5], TropicalWeight::new(1.0))]);
+        assert_eq!(t(&[])?, vec![(vec![6], TropicalWeight::one())]);
+        assert_eq!(t(&[2, 1])?, vec![(vec![8, 7], TropicalWeight::new(4.0))]);
+        assert_eq!(t(&[1])?, vec![(vec![7], TropicalWeight::new(2.0))]);
+        Ok(())
+    }
+}
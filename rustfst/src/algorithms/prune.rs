@@ -0,0 +1,277 @@
+use std::cmp::Ordering;
+
+use anyhow::Result;
+
+use crate::algorithms::shortest_path::natural_less;
+use crate::algorithms::{
+    connect, fst_convert_from_ref, shortest_distance_with_config, ShortestDistanceConfig,
+};
+use crate::fst_traits::{AllocableFst, ExpandedFst, MutableFst};
+use crate::semirings::{Semiring, SemiringProperties};
+use crate::{StateId, Trs, KDELTA};
+
+/// Configuration of the pruning.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PruneConfig<W: Semiring> {
+    /// Paths whose weight exceeds the weight of the shortest path times this threshold are
+    /// removed. `W::zero()` disables the pruning by weight.
+    pub weight_threshold: W,
+    /// Maximum number of states kept, the best ones being kept. `None` for no limit.
+    pub state_threshold: Option<StateId>,
+    /// Convergence delta of the shortest distance computations.
+    pub delta: f32,
+}
+
+impl<W: Semiring> Default for PruneConfig<W> {
+    fn default() -> Self {
+        Self {
+            weight_threshold: W::zero(),
+            state_threshold: None,
+            delta: KDELTA,
+        }
+    }
+}
+
+impl<W: Semiring> PruneConfig<W> {
+    pub fn new(weight_threshold: W, state_threshold: Option<StateId>, delta: f32) -> Self {
+        Self {
+            weight_threshold,
+            state_threshold,
+            delta,
+        }
+    }
+
+    pub fn with_weight_threshold(self, weight_threshold: W) -> Self {
+        Self {
+            weight_threshold,
+            ..self
+        }
+    }
+
+    pub fn with_state_threshold(self, state_threshold: StateId) -> Self {
+        Self {
+            state_threshold: Some(state_threshold),
+            ..self
+        }
+    }
+
+    pub fn with_delta(self, delta: f32) -> Self {
+        Self { delta, ..self }
+    }
+}
+
+/// Removes the states, trs and final weights that are on no successful path whose weight is
+/// within `weight_threshold` of the weight of the shortest path. For instance in the tropical
+/// semiring, a threshold of `5.0` keeps the paths costing at most 5 more than the best path.
+///
+/// The weights must have the path property and be commutative.
+///
+/// # Example
+/// ```
+/// # use anyhow::Result;
+/// # fn main() -> Result<()> {
+/// # use rustfst::algorithms::prune;
+/// # use rustfst::fst_impls::VectorFst;
+/// # use rustfst::fst_traits::{CoreFst, ExpandedFst, MutableFst};
+/// # use rustfst::semirings::{Semiring, TropicalWeight};
+/// # use rustfst::Tr;
+/// let mut fst = VectorFst::<TropicalWeight>::new();
+/// fst.add_states(3);
+/// fst.set_start(0)?;
+/// fst.add_tr(0, Tr::new(1, 1, 1.0, 1))?;
+/// fst.add_tr(0, Tr::new(2, 2, 10.0, 2))?;
+/// fst.set_final(1, TropicalWeight::one())?;
+/// fst.set_final(2, TropicalWeight::one())?;
+///
+/// prune(&mut fst, TropicalWeight::new(5.0))?;
+/// assert_eq!(fst.num_states(), 2);
+/// assert_eq!(fst.num_trs(0)?, 1);
+/// # Ok(())
+/// # }
+/// ```
+pub fn prune<W, F>(fst: &mut F, weight_threshold: W) -> Result<()>
+where
+    W: Semiring,
+    F: MutableFst<W>,
+{
+    prune_with_config(
+        fst,
+        PruneConfig::default().with_weight_threshold(weight_threshold),
+    )
+}
+
+/// Prunes the FST by weight, as `prune`, and keeps at most `state_threshold` states if it is
+/// set.
+///
+/// The states are kept in the order of the weight of the best successful path through them,
+/// along with the trs between them that are on a path within the weight threshold. A tr from
+/// `p` to `q` of weight `w` is kept iff `d[p] ⊗ w ⊗ r[q] ≤ best ⊗ weight_threshold`, with `d`
+/// and `r` the shortest distances from the start state and to the final states.
+pub fn prune_with_config<W, F>(fst: &mut F, config: PruneConfig<W>) -> Result<()>
+where
+    W: Semiring,
+    F: MutableFst<W>,
+{
+    if !W::properties().contains(SemiringProperties::PATH | SemiringProperties::COMMUTATIVE) {
+        bail!("prune: Weight needs to have the path property and be commutative")
+    }
+    let start = match fst.start() {
+        Some(start) => start,
+        None => return Ok(()),
+    };
+    if config.state_threshold == Some(0) {
+        fst.del_all_states();
+        return Ok(());
+    }
+    let sd_config = ShortestDistanceConfig::new(config.delta);
+    let distance = shortest_distance_with_config(fst, false, sd_config)?;
+    let rdistance = shortest_distance_with_config(fst, true, sd_config)?;
+    let get = |d: &[W], s: StateId| d.get(s as usize).cloned().unwrap_or_else(W::zero);
+
+    let best = get(&rdistance, start);
+    if best.is_zero() {
+        // No successful path : everything is removed by the connection.
+        return connect(fst);
+    }
+    let limit = best.times(&config.weight_threshold)?;
+
+    // States on a successful path within the threshold, from the best one.
+    let mut kept_states = vec![];
+    let mut priorities = vec![W::zero(); fst.num_states()];
+    for state in 0..(fst.num_states() as StateId) {
+        let priority = get(&distance, state).times(get(&rdistance, state))?;
+        if !priority.is_zero() && !natural_less(&limit, &priority)? {
+            kept_states.push(state);
+        }
+        priorities[state as usize] = priority;
+    }
+    if let Some(state_threshold) = config.state_threshold {
+        let mut error = None;
+        kept_states.sort_by(|s1, s2| {
+            let (p1, p2) = (&priorities[*s1 as usize], &priorities[*s2 as usize]);
+            match (natural_less(p1, p2), natural_less(p2, p1)) {
+                (Ok(true), _) => Ordering::Less,
+                (_, Ok(true)) => Ordering::Greater,
+                (Err(e), _) | (_, Err(e)) => {
+                    error.get_or_insert(e);
+                    Ordering::Equal
+                }
+                _ => Ordering::Equal,
+            }
+        });
+        if let Some(e) = error {
+            return Err(e);
+        }
+        kept_states.truncate(state_threshold as usize);
+    }
+    let mut kept = vec![false; fst.num_states()];
+    for state in kept_states.iter() {
+        kept[*state as usize] = true;
+    }
+
+    for state in 0..(fst.num_states() as StateId) {
+        if !kept[state as usize] {
+            fst.delete_trs(state)?;
+            fst.delete_final_weight(state)?;
+            continue;
+        }
+        let d = get(&distance, state);
+        // Safe because the state exists.
+        if let Some(final_weight) = unsafe { fst.final_weight_unchecked(state) } {
+            if natural_less(&limit, &d.times(&final_weight)?)? {
+                unsafe { fst.delete_final_weight_unchecked(state) };
+            }
+        }
+        let trs = fst.get_trs(state)?;
+        let mut kept_trs = Vec::with_capacity(trs.len());
+        for tr in trs.trs() {
+            if !kept[tr.nextstate as usize] {
+                continue;
+            }
+            let r = get(&rdistance, tr.nextstate);
+            if !natural_less(&limit, &d.times(&tr.weight)?.times(&r)?)? {
+                kept_trs.push(tr.clone());
+            }
+        }
+        if kept_trs.len() != trs.len() {
+            drop(trs);
+            unsafe { fst.set_trs_unchecked(state, kept_trs) };
+        }
+    }
+    connect(fst)
+}
+
+/// Copying version of `prune_with_config` : the input FST is left untouched.
+pub fn prune_fst<W, F1, F2>(fst_in: &F1, config: PruneConfig<W>) -> Result<F2>
+where
+    W: Semiring,
+    F1: ExpandedFst<W>,
+    F2: MutableFst<W> + AllocableFst<W>,
+{
+    let mut fst_out: F2 = fst_convert_from_ref(fst_in);
+    prune_with_config(&mut fst_out, config)?;
+    Ok(fst_out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::algorithms::prune_trs;
+    use crate::fst_impls::VectorFst;
+    use crate::fst_traits::CoreFst;
+    use crate::semirings::TropicalWeight;
+    use crate::Tr;
+
+    #[test]
+    fn test_prune() -> Result<()> {
+        // 0 -a/1-> 1 -c/1-> 2 (final)
+        // 0 -b/4-> 1
+        // 1 -d/9-> 2
+        // 0 -e/2-> 3 (final 7)
+        // 0 -f/3-> 4 (final 0)
+        let mut fst = VectorFst::<TropicalWeight>::new();
+        fst.add_states(5);
+        fst.set_start(0)?;
+        fst.add_tr(0, Tr::new(1, 1, 1.0, 1))?;
+        fst.add_tr(0, Tr::new(2, 2, 4.0, 1))?;
+        fst.add_tr(1, Tr::new(3, 3, 1.0, 2))?;
+        fst.add_tr(1, Tr::new(4, 4, 9.0, 2))?;
+        fst.add_tr(0, Tr::new(5, 5, 2.0, 3))?;
+        fst.add_tr(0, Tr::new(6, 6, 3.0, 4))?;
+        fst.set_final(2, 0.0)?;
+        fst.set_final(3, 7.0)?;
+        fst.set_final(4, 0.0)?;
+
+        // Same result as `prune_trs` without a state threshold.
+        let pruned: VectorFst<_> = prune_fst(
+            &fst,
+            PruneConfig::default().with_weight_threshold(TropicalWeight::new(3.0)),
+        )?;
+        let mut expected = fst.clone();
+        prune_trs(&mut expected, TropicalWeight::new(3.0))?;
+        assert_eq!(pruned, expected);
+        assert_eq!(pruned.num_states(), 4);
+
+        // The states 0, 1 and 2 are on the best path, the state 4 comes next.
+        let config = PruneConfig::default().with_state_threshold(3);
+        let pruned: VectorFst<_> = prune_fst(&fst, config.clone())?;
+        assert_eq!(pruned.num_states(), 3);
+        let ilabels: Vec<_> = pruned
+            .get_trs(0)?
+            .trs()
+            .iter()
+            .map(|tr| tr.ilabel)
+            .collect();
+        assert_eq!(ilabels, vec![1, 2]);
+
+        let pruned: VectorFst<_> = prune_fst(&fst, config.with_state_threshold(4))?;
+        assert_eq!(pruned.num_states(), 4);
+        assert_eq!(pruned.num_trs(0)?, 3);
+
+        prune(&mut fst, TropicalWeight::new(1.0))?;
+        assert_eq!(fst.num_states(), 4);
+        assert_eq!(fst.num_trs(0)?, 2);
+        Ok(())
+    }
+}
@@ -0,0 +1,130 @@
+use anyhow::Result;
+
+use crate::algorithms::shortest_path::natural_less;
+use crate::algorithms::{connect, shortest_distance};
+use crate::fst_traits::MutableFst;
+use crate::semirings::{Semiring, SemiringProperties};
+use crate::{StateId, Trs};
+
+/// Removes the trs and final weights that are on no successful path whose weight is within
+/// `threshold` of the weight of the shortest path, then the states left inaccessible or not
+/// coaccessible.
+///
+/// A tr from `p` to `q` of weight `w` is kept iff `d[p] ⊗ w ⊗ r[q] ≤ best ⊗ threshold`, with
+/// `d` and `r` the shortest distances from the start state and to the final states, i.e. the
+/// best completion through this tr. Unlike the pruning of the states, the trs of a state on
+/// a good path are removed individually. For instance in the tropical semiring, a threshold of
+/// `5.0` keeps the trs on the paths costing at most 5 more than the best path.
+///
+/// The weights must have the path property and be commutative.
+///
+/// # Example
+/// ```
+/// # use anyhow::Result;
+/// # fn main() -> Result<()> {
+/// # use rustfst::algorithms::prune_trs;
+/// # use rustfst::fst_impls::VectorFst;
+/// # use rustfst::fst_traits::{CoreFst, MutableFst};
+/// # use rustfst::semirings::{Semiring, TropicalWeight};
+/// # use rustfst::Tr;
+/// let mut fst = VectorFst::<TropicalWeight>::new();
+/// let s0 = fst.add_state();
+/// let s1 = fst.add_state();
+/// fst.set_start(s0)?;
+/// fst.set_final(s1, TropicalWeight::one())?;
+/// fst.add_tr(s0, Tr::new(1, 1, 1.0, s1))?;
+/// fst.add_tr(s0, Tr::new(2, 2, 3.0, s1))?;
+/// fst.add_tr(s0, Tr::new(3, 3, 10.0, s1))?;
+///
+/// prune_trs(&mut fst, TropicalWeight::new(5.0))?;
+/// assert_eq!(fst.num_trs(s0)?, 2);
+/// # Ok(())
+/// # }
+/// ```
+pub fn prune_trs<W, F>(fst: &mut F, threshold: W) -> Result<()>
+where
+    W: Semiring,
+    F: MutableFst<W>,
+{
+    if !W::properties().contains(SemiringProperties::PATH | SemiringProperties::COMMUTATIVE) {
+        bail!("prune_trs: Weight needs to have the path property and be commutative")
+    }
+    let start = match fst.start() {
+        Some(start) => start,
+        None => return Ok(()),
+    };
+    let distance = shortest_distance(fst, false)?;
+    let rdistance = shortest_distance(fst, true)?;
+    let zero = W::zero();
+    let get = |d: &[W], s: StateId| d.get(s as usize).cloned().unwrap_or_else(W::zero);
+
+    let best = get(&rdistance, start);
+    if best.is_zero() {
+        // No successful path : everything is removed by the connection.
+        connect(fst)?;
+        return Ok(());
+    }
+    let limit = best.times(&threshold)?;
+
+    for state in 0..(fst.num_states() as StateId) {
+        let d = get(&distance, state);
+        if d.is_zero() {
+            continue;
+        }
+        // Safe because the state exists.
+        if let Some(final_weight) = unsafe { fst.final_weight_unchecked(state) } {
+            if natural_less(&limit, &d.times(&final_weight)?)? {
+                unsafe { fst.delete_final_weight_unchecked(state) };
+            }
+        }
+        let trs = fst.get_trs(state)?;
+        let mut kept = Vec::with_capacity(trs.len());
+        for tr in trs.trs() {
+            let r = get(&rdistance, tr.nextstate);
+            if r != zero && !natural_less(&limit, &d.times(&tr.weight)?.times(&r)?)? {
+                kept.push(tr.clone());
+            }
+        }
+        if kept.len() != trs.len() {
+            drop(trs);
+            unsafe { fst.set_trs_unchecked(state, kept) };
+        }
+    }
+    connect(fst)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::fst_impls::VectorFst;
+    use crate::fst_traits::{CoreFst, ExpandedFst};
+    use crate::semirings::TropicalWeight;
+    use crate::Tr;
+
+    #[test]
+    fn test_prune_trs() -> Result<()> {
+        // 0 -a/1-> 1 -c/1-> 2 (final)
+        // 0 -b/4-> 1
+        // 1 -d/9-> 2
+        // 0 -e/2-> 3 (final 7)
+        let mut fst = VectorFst::<TropicalWeight>::new();
+        fst.add_states(4);
+        fst.set_start(0)?;
+        fst.add_tr(0, Tr::new(1, 1, 1.0, 1))?;
+        fst.add_tr(0, Tr::new(2, 2, 4.0, 1))?;
+        fst.add_tr(1, Tr::new(3, 3, 1.0, 2))?;
+        fst.add_tr(1, Tr::new(4, 4, 9.0, 2))?;
+        fst.add_tr(0, Tr::new(5, 5, 2.0, 3))?;
+        fst.set_final(2, 0.0)?;
+        fst.set_final(3, 7.0)?;
+
+        // Best path costs 2 : the limit is 5.
+        prune_trs(&mut fst, TropicalWeight::new(3.0))?;
+        assert_eq!(fst.num_states(), 3);
+        let ilabels: Vec<_> = fst.get_trs(0)?.trs().iter().map(|tr| tr.ilabel).collect();
+        assert_eq!(ilabels, vec![1, 2]);
+        assert_eq!(fst.num_trs(1)?, 1);
+        Ok(())
+    }
+}
@@ -0,0 +1,205 @@
+use std::fmt;
+
+use anyhow::Result;
+
+use crate::algorithms::tr_mappers::QuantizeMapper;
+use crate::algorithms::{
+    fst_convert_from_ref, minimize_with_config, shortest_distance, tr_sum, MinimizeConfig,
+};
+use crate::fst_impls::VectorFst;
+use crate::fst_traits::{ExpandedFst, MutableFst};
+use crate::semirings::{Semiring, WeaklyDivisibleSemiring, WeightQuantize};
+
+/// Outcome of the quantization of the weights of an FST at one `delta`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct QuantizeSweepPoint {
+    pub delta: f32,
+    /// Number of states once quantized, the trs summed and minimized.
+    pub num_states: usize,
+    /// Number of trs once quantized, the trs summed and minimized.
+    pub num_trs: usize,
+    /// Greatest absolute difference, over all the states, between the ⊕-sum of the weights of
+    /// the paths to the final states before and after the quantization, i.e. between the
+    /// weights of the best paths in the tropical semiring.
+    pub max_deviation: f32,
+}
+
+/// Sizes and weight deviations of an FST quantized at several `delta`, to choose the coarsest
+/// quantization preserving the scores well enough.
+#[derive(Debug, Clone, PartialEq)]
+pub struct QuantizeSweepReport {
+    /// Number of states of the original FST.
+    pub num_states: usize,
+    /// Number of trs of the original FST.
+    pub num_trs: usize,
+    /// Outcome of each `delta`, in the order of the sweep.
+    pub points: Vec<QuantizeSweepPoint>,
+}
+
+impl QuantizeSweepReport {
+    /// Fraction of the states of the original FST removed at `point`.
+    pub fn states_reduction(&self, point: &QuantizeSweepPoint) -> f32 {
+        reduction(self.num_states, point.num_states)
+    }
+
+    /// Fraction of the trs of the original FST removed at `point`.
+    pub fn trs_reduction(&self, point: &QuantizeSweepPoint) -> f32 {
+        reduction(self.num_trs, point.num_trs)
+    }
+}
+
+fn reduction(before: usize, after: usize) -> f32 {
+    if before == 0 {
+        return 0.0;
+    }
+    (before as f32 - after as f32) / before as f32
+}
+
+impl fmt::Display for QuantizeSweepReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "delta\t\t# of states\t# of trs\treduction\tmax deviation\n\
+             original\t{}\t\t{}",
+            self.num_states, self.num_trs
+        )?;
+        for point in self.points.iter() {
+            write!(
+                f,
+                "\n{}\t{}\t\t{}\t\t{:.1}%\t\t{}",
+                point.delta,
+                point.num_states,
+                point.num_trs,
+                100.0 * self.trs_reduction(point),
+                point.max_deviation
+            )?;
+        }
+        Ok(())
+    }
+}
+
+fn num_trs<W: Semiring, F: ExpandedFst<W>>(fst: &F) -> usize {
+    fst.states_range()
+        .map(|s| unsafe { fst.num_trs_unchecked(s) })
+        .sum()
+}
+
+/// Greatest absolute difference between the shortest distances of the same states, infinite
+/// if a state only reaches the final states in one of the FSTs.
+fn max_deviation<W: Semiring<Type = f32>>(distances1: &[W], distances2: &[W]) -> f32 {
+    distances1
+        .iter()
+        .zip(distances2.iter())
+        .filter(|(w1, w2)| w1.value() != w2.value())
+        .map(|(w1, w2)| (w1.value() - w2.value()).abs())
+        .fold(0.0, f32::max)
+}
+
+/// Quantizes the weights of `fst` at each of the `deltas`, sums the trs and minimizes with
+/// `config`, reporting the size of the result and the deviation of the weights of the paths
+/// w.r.t. the original FST.
+///
+/// The deviation is measured on the shortest distances to the final states, computed on the
+/// quantized FST before its minimization so that its states are those of `fst`. As the
+/// minimization preserves the weights of the paths, it is also the deviation of the minimized
+/// FST. The quantization of each weight being off by at most `delta / 2`, the deviation of the
+/// long paths can be much larger than `delta`.
+///
+/// # Example
+/// ```
+/// # use rustfst::algorithms::{quantize_sweep, MinimizeConfig};
+/// # use rustfst::fst_impls::VectorFst;
+/// # use rustfst::fst_traits::MutableFst;
+/// # use rustfst::semirings::{Semiring, TropicalWeight};
+/// # use rustfst::Tr;
+/// # use anyhow::Result;
+/// # fn main() -> Result<()> {
+/// let mut fst = VectorFst::<TropicalWeight>::new();
+/// fst.add_states(2);
+/// fst.set_start(0)?;
+/// fst.add_tr(0, Tr::new(1, 1, 0.33, 1))?;
+/// fst.set_final(1, TropicalWeight::one())?;
+///
+/// let report = quantize_sweep(&fst, &[0.1, 1.0], MinimizeConfig::default())?;
+/// assert!((report.points[0].max_deviation - 0.03).abs() < 1e-4);
+/// assert!((report.points[1].max_deviation - 0.33).abs() < 1e-4);
+/// println!("{}", report);
+/// # Ok(())
+/// # }
+/// ```
+pub fn quantize_sweep<W, F>(
+    fst: &F,
+    deltas: &[f32],
+    config: MinimizeConfig,
+) -> Result<QuantizeSweepReport>
+where
+    W: WeaklyDivisibleSemiring + WeightQuantize + Semiring<Type = f32>,
+    W::ReverseWeight: WeightQuantize,
+    F: ExpandedFst<W>,
+{
+    let distances = shortest_distance(fst, true)?;
+    let mut points = Vec::with_capacity(deltas.len());
+    for delta in deltas.iter().cloned() {
+        let mut quantized: VectorFst<W> = fst_convert_from_ref(fst);
+        quantized.tr_map(&mut QuantizeMapper::new(delta))?;
+        let quantized_distances = shortest_distance(&quantized, true)?;
+
+        tr_sum(&mut quantized);
+        minimize_with_config(&mut quantized, config)?;
+        points.push(QuantizeSweepPoint {
+            delta,
+            num_states: quantized.num_states(),
+            num_trs: num_trs(&quantized),
+            max_deviation: max_deviation(&distances, &quantized_distances),
+        });
+    }
+    Ok(QuantizeSweepReport {
+        num_states: fst.num_states(),
+        num_trs: num_trs(fst),
+        points,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::semirings::TropicalWeight;
+    use crate::{Tr, KDELTA};
+
+    #[test]
+    fn test_quantize_sweep() -> Result<()> {
+        // The states 1 and 2 only differ by the noise on the weights of their trs on 4.
+        let mut fst = VectorFst::<TropicalWeight>::new();
+        fst.add_states(4);
+        fst.set_start(0)?;
+        fst.add_tr(0, Tr::new(1, 1, 0.33, 1))?;
+        fst.add_tr(0, Tr::new(2, 2, 1.0, 2))?;
+        fst.add_tr(1, Tr::new(3, 3, 0.5, 3))?;
+        fst.add_tr(1, Tr::new(4, 4, 1.0, 3))?;
+        fst.add_tr(2, Tr::new(3, 3, 0.5, 3))?;
+        fst.add_tr(2, Tr::new(4, 4, 1.02, 3))?;
+        fst.set_final(3, TropicalWeight::one())?;
+
+        let report = quantize_sweep(&fst, &[KDELTA, 0.1], MinimizeConfig::default())?;
+        assert_eq!(report.num_states, 4);
+        assert_eq!(report.num_trs, 6);
+        assert_eq!(report.points.len(), 2);
+
+        let fine = &report.points[0];
+        assert_eq!((fine.num_states, fine.num_trs), (4, 6));
+        assert!(fine.max_deviation <= KDELTA / 2.0);
+
+        let coarse = &report.points[1];
+        assert_eq!(coarse.delta, 0.1);
+        assert_eq!((coarse.num_states, coarse.num_trs), (3, 4));
+        assert!((report.trs_reduction(coarse) - 2.0 / 6.0).abs() < 1e-6);
+        assert!((report.states_reduction(coarse) - 0.25).abs() < 1e-6);
+        // 0.33 is quantized to 0.3, on the best paths from 0.
+        assert!((coarse.max_deviation - 0.03).abs() < 1e-4);
+
+        // The original FST is left untouched.
+        assert_eq!(fst.num_states(), 4);
+        Ok(())
+    }
+}
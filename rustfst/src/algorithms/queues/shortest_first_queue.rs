@@ -29,6 +29,13 @@ pub fn natural_less<W: Semiring>(w1: &W, w2: &W) -> Result<bool> {
     Ok((&w1.plus(w2)? == w1) && (w1 != w2))
 }
 
+/// Queue popping the states in the order of a comparator, e.g. on their distances.
+///
+/// The comparator is expected to order the states on weights that don't change while the
+/// states are in the queue, e.g. the distances of a previous computation : `update` is a no-op
+/// and doesn't move a state whose weight has decreased. Used with weights updated on the fly,
+/// such a state is popped at its old position, which still gives the right distances in
+/// `shortest_distance` but may visit the states more often than in shortest-first order.
 #[derive(Clone)]
 pub struct ShortestFirstQueue<C: Clone + FnMut(&StateId, &StateId) -> Ordering> {
     heap: BinaryHeap<StateId, FnComparator<C>>,
@@ -61,9 +68,9 @@ impl<C: Clone + FnMut(&StateId, &StateId) -> Ordering> Queue for ShortestFirstQu
         self.heap.pop();
     }
 
-    fn update(&mut self, _state: StateId) {
-        unimplemented!()
-    }
+    // The comparator orders the states on weights given beforehand, e.g. the distances of a
+    // previous computation, which are not changed by the algorithm using the queue.
+    fn update(&mut self, _state: StateId) {}
 
     fn is_empty(&self) -> bool {
         self.heap.is_empty()
@@ -5,13 +5,13 @@ use crate::fst_impls::VectorFst;
 pub use randgen_config::RandGenConfig;
 pub use randgen_fst::RandGenFst;
 use tr_sampler::TrSampler;
-pub use tr_selector::{TrSelector, UniformTrSelector};
+pub use tr_selector::{LogProbTrSelector, TrSelector, UniformTrSelector};
 
+use crate::algorithms::dfs_visit::dfs_visit;
+use crate::algorithms::randgen::randgen_visitor::RandGenVisitor;
 use crate::fst_traits::Fst;
-use crate::prelude::dfs_visit::dfs_visit;
-use crate::prelude::randgen::randgen_visitor::RandGenVisitor;
 use crate::prelude::MutableFst;
-use crate::Semiring;
+use crate::{FstPath, Semiring};
 
 mod rand_state;
 mod randgen_config;
@@ -51,6 +51,35 @@ pub fn randgen_with_config<
     }
 }
 
+/// Randomly generate paths through an Fst, as `randgen_with_config`, and return them as a list
+/// instead of an Fst. In the unweighted case, a path sampled several times is repeated, while
+/// in the weighted case each distinct path is listed once, weighted by the negative log of its
+/// number of occurrences (or of its frequency if `remove_total_weight` is set).
+///
+/// # Example
+/// ```
+/// # use rustfst::algorithms::randgen::{randgen_paths_with_config, LogProbTrSelector, RandGenConfig};
+/// # use rustfst::fst_impls::VectorFst;
+/// # use rustfst::semirings::{Semiring, TropicalWeight};
+/// # use rustfst::utils::acceptor;
+/// # use anyhow::Result;
+/// # fn main() -> Result<()> {
+/// let fst: VectorFst<TropicalWeight> = acceptor(&[1, 2, 3], TropicalWeight::one());
+/// let config = RandGenConfig::new(LogProbTrSelector::from_seed(42)).with_npath(3);
+/// let paths = randgen_paths_with_config(&fst, config)?;
+/// assert_eq!(paths.len(), 3);
+/// assert!(paths.iter().all(|p| p.ilabels == vec![1, 2, 3]));
+/// # Ok(())
+/// # }
+/// ```
+pub fn randgen_paths_with_config<W: Semiring<Type = f32>, FI: Fst<W>, S: TrSelector>(
+    ifst: &FI,
+    config: RandGenConfig<S>,
+) -> Result<Vec<FstPath<W>>> {
+    let ofst: VectorFst<W> = randgen_with_config(ifst, config)?;
+    Ok(ofst.paths_iter().collect())
+}
+
 /// Randomly generate a path through an Fst with the uniform distribution
 /// over the transitions.
 pub fn randgen<W: Semiring<Type = f32>, FI: Fst<W>, FO: MutableFst<W>>(ifst: &FI) -> Result<FO> {
@@ -65,6 +94,7 @@ mod tests {
     use crate::algorithms::union::union;
     use crate::semirings::TropicalWeight;
     use crate::utils::acceptor;
+    use crate::Tr;
 
     #[test]
     fn test_randgen_weighted() -> Result<()> {
@@ -113,4 +143,40 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_randgen_log_prob() -> Result<()> {
+        // The path 4 5 is e^10 times less likely than the path 1 2 3, the weight being on the
+        // tr where the selector chooses between them.
+        let mut fst = VectorFst::<TropicalWeight>::new();
+        fst.add_states(5);
+        fst.set_start(0)?;
+        fst.add_tr(0, Tr::new(1, 1, TropicalWeight::one(), 1))?;
+        fst.add_tr(1, Tr::new(2, 2, TropicalWeight::one(), 2))?;
+        fst.add_tr(2, Tr::new(3, 3, TropicalWeight::one(), 4))?;
+        fst.add_tr(0, Tr::new(4, 4, TropicalWeight::new(10.0), 3))?;
+        fst.add_tr(3, Tr::new(5, 5, TropicalWeight::one(), 4))?;
+        fst.set_final(4, TropicalWeight::one())?;
+
+        let config = RandGenConfig::new(LogProbTrSelector::from_seed(2022)).with_npath(100);
+        let paths = randgen_paths_with_config(&fst, config)?;
+        assert_eq!(paths.len(), 100);
+        assert!(paths.iter().all(|p| p.ilabels == vec![1, 2, 3]));
+
+        // No path is longer than the max length, so none is generated.
+        let config = RandGenConfig::new(LogProbTrSelector::from_seed(2022))
+            .with_npath(10)
+            .with_max_length(1);
+        assert!(randgen_paths_with_config(&fst, config)?.is_empty());
+
+        let config = RandGenConfig::new(LogProbTrSelector::from_seed(2022))
+            .with_npath(100)
+            .with_weighted(true);
+        let paths = randgen_paths_with_config(&fst, config)?;
+        assert_eq!(paths.len(), 1);
+        assert!(paths[0]
+            .weight
+            .approx_equal(TropicalWeight::new(-(100.0f32).ln()), 1e-4));
+        Ok(())
+    }
 }
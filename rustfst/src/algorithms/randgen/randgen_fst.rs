@@ -7,9 +7,9 @@ use anyhow::Result;
 use crate::algorithms::lazy::{LazyFst2, SimpleHashMapCache};
 use crate::algorithms::randgen::randgen_fst_op::RandGenFstOp;
 use crate::algorithms::randgen::tr_sampler::TrSampler;
+use crate::algorithms::randgen::TrSelector;
 use crate::fst_properties::FstProperties;
 use crate::fst_traits::{CoreFst, Fst, FstIterator, MutableFst, StateIterator};
-use crate::prelude::randgen::TrSelector;
 use crate::{Semiring, StateId, SymbolTable, TrsVec};
 
 type InnerLazyFst<W, F, B, S> = LazyFst2<W, RandGenFstOp<W, F, B, S>, SimpleHashMapCache<W>>;
@@ -43,7 +43,7 @@ where
 
 impl<W, F, B, S> TrSampler<W, F, B, S>
 where
-    W: Semiring,
+    W: Semiring<Type = f32>,
     F: Fst<W>,
     B: Borrow<F>,
     S: TrSelector,
@@ -1,7 +1,8 @@
 use crate::prelude::Fst;
-use crate::{Semiring, StateId};
+use crate::{Semiring, StateId, Trs};
 use anyhow::Result;
 use rand::distributions::{Distribution, Uniform};
+use rand::Rng;
 use rand_chacha::rand_core::SeedableRng;
 use rand_chacha::ChaCha8Rng;
 use std::fmt::Debug;
@@ -10,9 +11,13 @@ use std::fmt::Debug;
 /// state `s`, returning a number `N` such that 0 <= `N` <= `fst.num_trs(s)`. If `N` is
 /// `fst.num_trs(s)`, then the final weight is selected; otherwise the `N`-th transition is
 /// selected. It is assumed these are not applied to any state which is neither
-/// final nor has any arcs leaving it.
+/// final nor has any arcs leaving it. The weights hold an `f32` value, as required by `randgen`.
 pub trait TrSelector: Debug {
-    fn select_tr<W: Semiring, F: Fst<W>>(&mut self, fst: &F, state: StateId) -> Result<usize>;
+    fn select_tr<W: Semiring<Type = f32>, F: Fst<W>>(
+        &mut self,
+        fst: &F,
+        state: StateId,
+    ) -> Result<usize>;
 }
 
 /// Randomly selects a transition using the uniform distribution.
@@ -41,7 +46,11 @@ impl UniformTrSelector {
 }
 
 impl TrSelector for UniformTrSelector {
-    fn select_tr<W: Semiring, F: Fst<W>>(&mut self, fst: &F, state: StateId) -> Result<usize> {
+    fn select_tr<W: Semiring<Type = f32>, F: Fst<W>>(
+        &mut self,
+        fst: &F,
+        state: StateId,
+    ) -> Result<usize> {
         let mut n = fst.num_trs(state)?;
         if fst.is_final(state)? {
             n += 1;
@@ -51,3 +60,69 @@ impl TrSelector for UniformTrSelector {
         Ok(res)
     }
 }
+
+/// Randomly selects a transition w.r.t. the weights treated as negative log probabilities, as
+/// in the tropical and the log semirings, after normalizing for the total weight leaving the
+/// state. The final weight is treated as a transition to a super-final state.
+#[derive(Debug, Clone)]
+pub struct LogProbTrSelector {
+    rng: ChaCha8Rng,
+}
+
+impl Default for LogProbTrSelector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LogProbTrSelector {
+    pub fn new() -> Self {
+        Self {
+            rng: ChaCha8Rng::from_entropy(),
+        }
+    }
+    pub fn from_seed(seed: u64) -> Self {
+        Self {
+            rng: ChaCha8Rng::seed_from_u64(seed),
+        }
+    }
+}
+
+impl TrSelector for LogProbTrSelector {
+    fn select_tr<W: Semiring<Type = f32>, F: Fst<W>>(
+        &mut self,
+        fst: &F,
+        state: StateId,
+    ) -> Result<usize> {
+        let mut weights: Vec<f32> = fst
+            .get_trs(state)?
+            .trs()
+            .iter()
+            .map(|tr| *tr.weight.value())
+            .collect();
+        if let Some(final_weight) = fst.final_weight(state)? {
+            weights.push(*final_weight.value());
+        }
+        // The probabilities are relative to the most probable choice, not to underflow.
+        let min_weight = weights.iter().cloned().fold(f32::INFINITY, f32::min);
+        if !min_weight.is_finite() {
+            bail!(
+                "LogProbTrSelector : no transition with a finite weight at state {}",
+                state
+            );
+        }
+        let probs: Vec<f64> = weights
+            .iter()
+            .map(|w| (-f64::from(w - min_weight)).exp())
+            .collect();
+        let mut r = self.rng.gen::<f64>() * probs.iter().sum::<f64>();
+        for (idx, prob) in probs.iter().enumerate() {
+            if r < *prob {
+                return Ok(idx);
+            }
+            r -= prob;
+        }
+        // Only reached through rounding errors.
+        Ok(probs.iter().rposition(|p| *p > 0.0).unwrap())
+    }
+}
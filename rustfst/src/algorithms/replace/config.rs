@@ -9,7 +9,6 @@ pub enum ReplaceLabelType {
     Input,
     /// Epsilon on input and non-epsilon on output.
     Output,
-    #[allow(unused)]
     /// Non-epsilon labels on both input and output.
     Both,
 }
@@ -43,4 +42,17 @@ impl ReplaceFstOptions {
             return_label: 0,
         }
     }
+
+    /// Options tagging the outputs with the rules applied : the call transitions keep their
+    /// input label and output the non-terminal label, and the return transitions output
+    /// `return_label` on an epsilon input, unless it is an epsilon.
+    pub fn new_traced(root: Label, return_label: Label) -> Self {
+        Self {
+            root,
+            call_label_type: ReplaceLabelType::Both,
+            return_label_type: ReplaceLabelType::Output,
+            call_output_label: None,
+            return_label,
+        }
+    }
 }
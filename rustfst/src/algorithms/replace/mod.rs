@@ -2,8 +2,13 @@ pub(crate) mod config;
 mod replace_fst;
 pub(crate) mod replace_fst_op;
 mod replace_static;
+mod rule_trace;
 pub(crate) mod state_table;
+mod symbols;
 pub(crate) mod utils;
 
 pub use replace_fst::ReplaceFst;
-pub use replace_static::replace;
+pub use replace_static::{replace, replace_with_config, ReplaceConfig};
+pub use rule_trace::{trace_rules, RuleSpan};
+pub use symbols::merge_symbol_tables;
+pub(crate) use symbols::merge_table;
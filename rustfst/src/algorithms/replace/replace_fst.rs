@@ -1,14 +1,17 @@
 use std::borrow::Borrow;
 use std::fmt::Debug;
+use std::ops::Range;
 use std::sync::Arc;
 
 use anyhow::Result;
 
-use crate::algorithms::lazy::{LazyFst, SimpleHashMapCache};
+use crate::algorithms::lazy::{ExpansionObserver, LazyFst, SimpleHashMapCache};
 use crate::algorithms::replace::config::ReplaceFstOptions;
 use crate::algorithms::replace::replace_fst_op::ReplaceFstOp;
 use crate::fst_properties::FstProperties;
-use crate::fst_traits::{AllocableFst, CoreFst, Fst, FstIterator, MutableFst, StateIterator};
+use crate::fst_traits::{
+    AllocableFst, CoreFst, Fst, FstIterData, FstIterator, MutableFst, StateIterator,
+};
 use crate::semirings::Semiring;
 use crate::{Label, StateId, SymbolTable, TrsVec};
 
@@ -25,14 +28,54 @@ where
     F: Fst<W>,
     B: Borrow<F>,
 {
+    /// Lazy version of [`replace`](crate::algorithms::replace::replace) : `fst_list` pairs each
+    /// non-terminal label with its FST, `root` is the label of the FST to start from. The
+    /// transitions whose output label is a non-terminal are replaced by the FST of this label.
+    /// Their input label is kept on the call transitions unless `epsilon_on_replace` is set.
+    ///
+    /// The states are expanded on demand, which allows to explore the expansion of a recursive
+    /// transition network that isn't finite.
+    ///
+    /// # Example
+    /// ```
+    /// # use rustfst::algorithms::replace::ReplaceFst;
+    /// # use rustfst::fst_impls::VectorFst;
+    /// # use rustfst::fst_traits::{Fst, MutableFst};
+    /// # use rustfst::semirings::{Semiring, TropicalWeight};
+    /// # use rustfst::utils::transducer;
+    /// # use rustfst::{FstPath, Tr, EPS_LABEL};
+    /// # use anyhow::Result;
+    /// # fn main() -> Result<()> {
+    /// // 1 followed by the non-terminal 10.
+    /// let mut root = VectorFst::<TropicalWeight>::new();
+    /// root.add_states(3);
+    /// root.set_start(0)?;
+    /// root.add_tr(0, Tr::new(1, 1, 1.0, 1))?;
+    /// root.add_tr(1, Tr::new(EPS_LABEL, 10, 0.0, 2))?;
+    /// root.set_final(2, TropicalWeight::one())?;
+    /// let rule: VectorFst<TropicalWeight> = transducer(&[2], &[3], TropicalWeight::new(2.0));
+    ///
+    /// let fst = ReplaceFst::<_, VectorFst<_>, _>::new(vec![(0, root), (10, rule)], 0, true)?;
+    /// let replaced: VectorFst<TropicalWeight> = fst.compute()?;
+    /// let paths: Vec<_> = replaced.paths_iter().collect();
+    /// assert_eq!(paths, vec![FstPath::new(vec![1, 2], vec![1, 3], TropicalWeight::new(3.0))]);
+    /// # Ok(())
+    /// # }
+    /// ```
     pub fn new(fst_list: Vec<(Label, B)>, root: Label, epsilon_on_replace: bool) -> Result<Self> {
+        Self::new_with_options(fst_list, ReplaceFstOptions::new(root, epsilon_on_replace))
+    }
+
+    pub(crate) fn new_with_options(
+        fst_list: Vec<(Label, B)>,
+        opts: ReplaceFstOptions,
+    ) -> Result<Self> {
         let mut isymt = None;
         let mut osymt = None;
         if let Some(first_elt) = fst_list.first() {
             isymt = first_elt.1.borrow().input_symbols().cloned();
             osymt = first_elt.1.borrow().output_symbols().cloned();
         }
-        let opts = ReplaceFstOptions::new(root, epsilon_on_replace);
         let fst_op = ReplaceFstOp::new(fst_list, opts)?;
         let fst_cache = SimpleHashMapCache::default();
         Ok(ReplaceFst(LazyFst::from_op_and_cache(
@@ -44,6 +87,41 @@ where
     pub fn compute<F2: MutableFst<W> + AllocableFst<W>>(&self) -> Result<F2> {
         self.0.compute()
     }
+
+    /// Limits the number of states expanded by the Lazy FST, which is useful as the
+    /// replacement of a non-tail recursion yields an infinite FST.
+    /// See [`LazyFst::with_max_expanded_states`].
+    pub fn with_max_expanded_states(self, max_expanded_states: usize) -> Self {
+        ReplaceFst(self.0.with_max_expanded_states(max_expanded_states))
+    }
+
+    /// Reports the expansions of the Lazy FST to `observer`.
+    /// See [`LazyFst::with_expansion_observer`].
+    pub fn with_expansion_observer<O: ExpansionObserver + 'static>(self, observer: O) -> Self {
+        ReplaceFst(self.0.with_expansion_observer(observer))
+    }
+
+    /// Turns the part of the Lazy FST expanded so far into a static one, preserving the state
+    /// ids. See [`LazyFst::freeze_visited`].
+    pub fn freeze_visited<F2: MutableFst<W> + AllocableFst<W>>(&self) -> Result<F2> {
+        self.0.freeze_visited()
+    }
+
+    /// Expands all the states of the Lazy FST. See [`LazyFst::expand_all_states`].
+    pub fn expand_all_states(&self) -> Result<()> {
+        self.0.expand_all_states()
+    }
+
+    /// Range of the states known to the cache. See [`LazyFst::known_states_range`].
+    pub fn known_states_range(&self) -> Range<StateId> {
+        self.0.known_states_range()
+    }
+
+    /// Iterates over all the states, returning the expansion errors. See
+    /// [`LazyFst::try_fst_iter`].
+    pub fn try_fst_iter(&self) -> impl Iterator<Item = Result<FstIterData<W, TrsVec<W>>>> + '_ {
+        self.0.try_fst_iter()
+    }
 }
 
 impl<W, F, B> CoreFst<W> for ReplaceFst<W, F, B>
@@ -165,8 +243,12 @@ where
 
 #[cfg(test)]
 mod test {
+    use std::sync::Mutex;
+
+    use crate::algorithms::lazy::{ExpansionEvent, ExpansionLimitError};
     use crate::fst_impls::VectorFst;
     use crate::semirings::TropicalWeight;
+    use crate::Tr;
 
     use super::*;
 
@@ -175,4 +257,100 @@ mod test {
         fn is_sync<T: Sync>() {}
         is_sync::<ReplaceFst<TropicalWeight, VectorFst<_>, VectorFst<_>>>();
     }
+
+    #[test]
+    fn test_replace_fst_known_states_range_does_not_expand() -> Result<()> {
+        let mut fst = VectorFst::<TropicalWeight>::new();
+        let s0 = fst.add_state();
+        let s1 = fst.add_state();
+        let s2 = fst.add_state();
+        fst.set_start(s0)?;
+        fst.set_final(s2, TropicalWeight::one())?;
+        fst.add_tr(s0, Tr::new(1, 1, TropicalWeight::one(), s1))?;
+        fst.add_tr(s1, Tr::new(2, 2, TropicalWeight::one(), s2))?;
+
+        let replace_fst =
+            ReplaceFst::<_, VectorFst<_>, VectorFst<_>>::new(vec![(10, fst.clone())], 10, false)?;
+        assert_eq!(replace_fst.known_states_range(), 0..0);
+        replace_fst.start();
+        assert_eq!(replace_fst.known_states_range(), 0..1);
+        replace_fst.expand_all_states()?;
+        assert_eq!(replace_fst.known_states_range(), 0..3);
+
+        let replace_fst =
+            ReplaceFst::<_, VectorFst<_>, VectorFst<_>>::new(vec![(10, fst)], 10, false)?;
+        assert_eq!(replace_fst.states_iter().count(), 3);
+        let data = replace_fst.try_fst_iter().collect::<Result<Vec<_>>>()?;
+        assert_eq!(data.len(), 3);
+        Ok(())
+    }
+
+    #[test]
+    fn test_replace_fst_expansion_limit() -> Result<()> {
+        // Non-tail recursion : 10 -> 1 10 2 | eps, which accepts 1^n 2^n.
+        let mut fst = VectorFst::<TropicalWeight>::new();
+        let s0 = fst.add_state();
+        let s1 = fst.add_state();
+        let s2 = fst.add_state();
+        let s3 = fst.add_state();
+        fst.set_start(s0)?;
+        fst.set_final(s0, TropicalWeight::one())?;
+        fst.set_final(s3, TropicalWeight::one())?;
+        fst.add_tr(s0, Tr::new(1, 1, TropicalWeight::one(), s1))?;
+        fst.add_tr(s1, Tr::new(10, 10, TropicalWeight::one(), s2))?;
+        fst.add_tr(s2, Tr::new(2, 2, TropicalWeight::one(), s3))?;
+
+        let replace_fst =
+            ReplaceFst::<_, VectorFst<_>, VectorFst<_>>::new(vec![(10, fst)], 10, false)?
+                .with_max_expanded_states(100);
+        let err = replace_fst.compute::<VectorFst<_>>().unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<ExpansionLimitError>(),
+            Some(&ExpansionLimitError {
+                max_expanded_states: 100
+            })
+        );
+
+        // The unchecked accessors are not subject to the limit.
+        let frontier = (0..).find(|s| replace_fst.get_trs(*s).is_err()).unwrap();
+        let trs = unsafe { replace_fst.get_trs_unchecked(frontier) };
+        assert_eq!(replace_fst.get_trs(frontier)?, trs);
+        let frontier = (0..)
+            .find(|s| replace_fst.final_weight(*s).is_err())
+            .unwrap();
+        let final_weight = unsafe { replace_fst.final_weight_unchecked(frontier) };
+        assert_eq!(replace_fst.final_weight(frontier)?, final_weight);
+        Ok(())
+    }
+
+    #[test]
+    fn test_replace_fst_expansion_observer() -> Result<()> {
+        let mut fst = VectorFst::<TropicalWeight>::new();
+        let s0 = fst.add_state();
+        let s1 = fst.add_state();
+        let s2 = fst.add_state();
+        fst.set_start(s0)?;
+        fst.set_final(s2, TropicalWeight::one())?;
+        fst.add_tr(s0, Tr::new(1, 1, TropicalWeight::one(), s1))?;
+        fst.add_tr(s1, Tr::new(2, 2, TropicalWeight::one(), s2))?;
+
+        let events = Arc::new(Mutex::new(vec![]));
+        let events_sink = Arc::clone(&events);
+        let replace_fst =
+            ReplaceFst::<_, VectorFst<_>, VectorFst<_>>::new(vec![(10, fst)], 10, false)?
+                .with_expansion_observer(move |e: &ExpansionEvent| {
+                    events_sink.lock().unwrap().push(*e)
+                });
+        replace_fst.expand_all_states()?;
+        replace_fst.expand_all_states()?;
+
+        let events = events.lock().unwrap();
+        assert_eq!(events.len(), 3);
+        let last = events.last().unwrap();
+        assert_eq!(last.num_expanded_states, 3);
+        assert_eq!(last.num_expanded_trs, 2);
+        assert_eq!(last.num_known_states, 3);
+        assert_eq!(last.num_trs, 0);
+        Ok(())
+    }
 }
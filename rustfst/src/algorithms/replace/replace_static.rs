@@ -2,6 +2,7 @@ use std::borrow::Borrow;
 
 use anyhow::Result;
 
+use crate::algorithms::replace::config::ReplaceFstOptions;
 use crate::algorithms::replace::ReplaceFst;
 use crate::fst_traits::{AllocableFst, Fst, MutableFst};
 use crate::semirings::Semiring;
@@ -53,6 +54,70 @@ where
     F2: MutableFst<W> + AllocableFst<W>,
     B: Borrow<F1>,
 {
-    let fst = ReplaceFst::new(fst_list, root, epsilon_on_replace)?;
+    replace_with_config(
+        fst_list,
+        ReplaceConfig::new(root).with_epsilon_on_replace(epsilon_on_replace),
+    )
+}
+
+/// Configuration of [`replace_with_config`].
+#[derive(Debug, Clone, Copy, PartialOrd, PartialEq, Eq)]
+pub struct ReplaceConfig {
+    /// Non-terminal label of the root FST.
+    pub root: Label,
+    /// If true, the call transitions are labeled with epsilons instead of the
+    /// non-terminal labels.
+    pub epsilon_on_replace: bool,
+    /// If set, the outputs are tagged with the rules applied : each call transition keeps its
+    /// input label and outputs the non-terminal label of the rule, and each return transition
+    /// outputs this label on an epsilon input, unless it is an epsilon. Takes precedence over `epsilon_on_replace`.
+    /// See [`trace_rules`](crate::algorithms::replace::trace_rules).
+    pub trace_return_label: Option<Label>,
+}
+
+impl ReplaceConfig {
+    pub fn new(root: Label) -> Self {
+        Self {
+            root,
+            epsilon_on_replace: false,
+            trace_return_label: None,
+        }
+    }
+
+    pub fn with_root(self, root: Label) -> Self {
+        Self { root, ..self }
+    }
+
+    pub fn with_epsilon_on_replace(self, epsilon_on_replace: bool) -> Self {
+        Self {
+            epsilon_on_replace,
+            ..self
+        }
+    }
+
+    pub fn with_trace_return_label(self, trace_return_label: Option<Label>) -> Self {
+        Self {
+            trace_return_label,
+            ..self
+        }
+    }
+}
+
+/// Same as [`replace`] with the options grouped in a [`ReplaceConfig`].
+pub fn replace_with_config<W, F1, F2, B>(
+    fst_list: Vec<(Label, B)>,
+    config: ReplaceConfig,
+) -> Result<F2>
+where
+    F1: Fst<W>,
+    W: Semiring,
+    F2: MutableFst<W> + AllocableFst<W>,
+    B: Borrow<F1>,
+{
+    let opts = match config.trace_return_label {
+        Some(return_label) => ReplaceFstOptions::new_traced(config.root, return_label),
+        None => ReplaceFstOptions::new(config.root, config.epsilon_on_replace),
+    };
+    let fst = ReplaceFst::new_with_options(fst_list, opts)?;
     fst.compute()
 }
@@ -0,0 +1,141 @@
+use anyhow::Result;
+
+use crate::{Label, EPS_LABEL};
+
+/// Span of the outputs produced by an application of a rule.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RuleSpan {
+    /// Label tagging the rule, i.e. its non-terminal label.
+    pub rule: Label,
+    /// Start of the span in the outputs stripped of the tags.
+    pub start: usize,
+    /// End (excluded) of the span in the outputs stripped of the tags.
+    pub end: usize,
+    /// Number of rules applied around this one.
+    pub depth: usize,
+}
+
+/// Splits the output labels of a traced FST into the outputs themselves and the spans of the
+/// rules that produced them, to find out which rule fired on an input.
+///
+/// `rule_labels` are the labels tagging the rules : the non-terminal labels of a `replace`
+/// with `ReplaceConfig::with_trace_return_label`, or the rule labels of `far_to_union`. Each
+/// rule label opens a span, closed by the next `return_label`. If `return_label` is an
+/// epsilon, as with `far_to_union`, the spans run until the end of the outputs.
+///
+/// The spans are sorted by start, the outer rules coming first.
+///
+/// # Example
+/// ```
+/// # use rustfst::algorithms::replace::{trace_rules, RuleSpan};
+/// # use anyhow::Result;
+/// # fn main() -> Result<()> {
+/// // Rule 10 outputs 1 then calls rule 11, which outputs 2 3.
+/// let (olabels, spans) = trace_rules(&[10, 1, 11, 2, 3, 100, 100], &[10, 11], 100)?;
+/// assert_eq!(olabels, vec![1, 2, 3]);
+/// assert_eq!(
+///     spans,
+///     vec![
+///         RuleSpan { rule: 10, start: 0, end: 3, depth: 0 },
+///         RuleSpan { rule: 11, start: 1, end: 3, depth: 1 },
+///     ]
+/// );
+/// # Ok(())
+/// # }
+/// ```
+pub fn trace_rules(
+    olabels: &[Label],
+    rule_labels: &[Label],
+    return_label: Label,
+) -> Result<(Vec<Label>, Vec<RuleSpan>)> {
+    let mut outputs = Vec::with_capacity(olabels.len());
+    let mut spans = vec![];
+    // Index in `spans` of the rules not closed yet.
+    let mut open = vec![];
+    for label in olabels.iter().cloned() {
+        if rule_labels.contains(&label) {
+            open.push(spans.len());
+            spans.push(RuleSpan {
+                rule: label,
+                start: outputs.len(),
+                end: outputs.len(),
+                depth: open.len() - 1,
+            });
+        } else if return_label != EPS_LABEL && label == return_label {
+            let idx = open
+                .pop()
+                .ok_or_else(|| format_err!("trace_rules : return label without a rule"))?;
+            spans[idx].end = outputs.len();
+        } else if label != EPS_LABEL {
+            outputs.push(label);
+        }
+    }
+    if return_label != EPS_LABEL && !open.is_empty() {
+        bail!("trace_rules : {} rules not returned from", open.len());
+    }
+    for idx in open {
+        spans[idx].end = outputs.len();
+    }
+    Ok((outputs, spans))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::algorithms::replace::{replace_with_config, ReplaceConfig};
+    use crate::fst_impls::VectorFst;
+    use crate::fst_traits::{Fst, MutableFst};
+    use crate::semirings::{Semiring, TropicalWeight};
+    use crate::utils::transducer;
+    use crate::Tr;
+
+    #[test]
+    fn test_trace_rules_replace() -> Result<()> {
+        // Root : 1 then the rule 10 or the rule 11, the calls reading 7.
+        let mut root = VectorFst::<TropicalWeight>::new();
+        root.add_states(3);
+        root.set_start(0)?;
+        root.add_tr(0, Tr::new(1, 1, 0.0, 1))?;
+        root.add_tr(1, Tr::new(7, 10, 0.0, 2))?;
+        root.add_tr(1, Tr::new(7, 11, 0.0, 2))?;
+        root.set_final(2, 0.0)?;
+        let rule_10: VectorFst<TropicalWeight> = transducer(&[4], &[5], TropicalWeight::one());
+        let rule_11: VectorFst<TropicalWeight> = transducer(&[4], &[6], TropicalWeight::one());
+        let fst_list = vec![(0, root), (10, rule_10), (11, rule_11)];
+
+        let config = ReplaceConfig::new(0).with_trace_return_label(Some(100));
+        let fst: VectorFst<TropicalWeight> = replace_with_config(fst_list.clone(), config)?;
+        let mut traces: Vec<_> = fst
+            .paths_iter()
+            .map(|p| {
+                // The input label of the call transition is kept.
+                assert_eq!(p.ilabels, vec![1, 7, 4]);
+                trace_rules(&p.olabels, &[10, 11], 100)
+            })
+            .collect::<Result<Vec<_>>>()?;
+        traces.sort_by_key(|(_, spans)| spans[0].rule);
+        assert_eq!(traces[0].0, vec![1, 5]);
+        assert_eq!(
+            traces[1].1,
+            vec![RuleSpan {
+                rule: 11,
+                start: 1,
+                end: 2,
+                depth: 0
+            }]
+        );
+
+        // Without a return label, the spans run until the end.
+        let config = ReplaceConfig::new(0).with_trace_return_label(Some(EPS_LABEL));
+        let fst: VectorFst<TropicalWeight> = replace_with_config(fst_list, config)?;
+        for path in fst.paths_iter() {
+            let (olabels, spans) = trace_rules(&path.olabels, &[10, 11], EPS_LABEL)?;
+            assert_eq!(spans[0].end, olabels.len());
+        }
+
+        assert!(trace_rules(&[1, 100], &[10], 100).is_err());
+        assert!(trace_rules(&[10, 1], &[10], 100).is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,184 @@
+use std::sync::Arc;
+
+use anyhow::Result;
+
+use crate::algorithms::relabel_pairs;
+use crate::fst_traits::{ExpandedFst, MutableFst};
+use crate::semirings::Semiring;
+use crate::{Label, SymbolTable, Trs};
+
+/// Side of the trs whose labels are mapped by a symbol table.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Side {
+    Input,
+    Output,
+}
+
+/// Adds the symbols of `symt` to `merged` and returns the labels of `symt` whose symbol has
+/// another label in `merged`, with their new label.
+pub(crate) fn merge_table(merged: &mut SymbolTable, symt: &SymbolTable) -> Vec<(Label, Label)> {
+    symt.iter()
+        .map(|(label, symbol)| (label, merged.add_symbol(symbol)))
+        .filter(|(label, new_label)| label != new_label)
+        .collect()
+}
+
+fn check_labels<W: Semiring, F: ExpandedFst<W>>(
+    fst: &F,
+    symt: &SymbolTable,
+    side: Side,
+    nonterminal: Label,
+) -> Result<()> {
+    for state in fst.states_range() {
+        for tr in fst.get_trs(state)?.trs() {
+            let label = match side {
+                Side::Input => tr.ilabel,
+                Side::Output => tr.olabel,
+            };
+            if !symt.contains_label(label) {
+                bail!(
+                    "The {:?} label {} of the FST of the non-terminal {} has no symbol in its table",
+                    side,
+                    label,
+                    nonterminal
+                );
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Merges the symbol tables of the FSTs passed to [`replace`](super::replace) into a single
+/// pair of tables, so that the expanded FST has coherent symbols.
+///
+/// The merged tables start from the tables of the root FST, whose labels are left unchanged.
+/// The symbols of the other FSTs are added to them and their trs are relabeled when a label
+/// stands for another symbol in the merged table. The non-terminal labels are those of the
+/// output table of the root FST : a replacement FST calls a non-terminal through its symbol.
+///
+/// All the FSTs must have both symbol tables and all their labels must be in them. Returns the
+/// merged tables, which are also set on each FST.
+pub fn merge_symbol_tables<W, F>(
+    fst_list: &mut [(Label, F)],
+    root: Label,
+) -> Result<(Arc<SymbolTable>, Arc<SymbolTable>)>
+where
+    W: Semiring,
+    F: MutableFst<W>,
+{
+    let root_idx = fst_list
+        .iter()
+        .position(|(label, _)| *label == root)
+        .ok_or_else(|| format_err!("No FST for the root non-terminal {}", root))?;
+    let root_fst = &fst_list[root_idx].1;
+    let mut isymt: SymbolTable = root_fst
+        .input_symbols()
+        .map(|symt| (**symt).clone())
+        .ok_or_else(|| format_err!("The root FST has no input symbol table"))?;
+    let mut osymt: SymbolTable = root_fst
+        .output_symbols()
+        .map(|symt| (**symt).clone())
+        .ok_or_else(|| format_err!("The root FST has no output symbol table"))?;
+
+    // The relabelings are computed for all the FSTs before modifying any of them.
+    let mut pairs = Vec::with_capacity(fst_list.len());
+    for (nonterminal, fst) in fst_list.iter() {
+        let fst_isymt = fst.input_symbols().ok_or_else(|| {
+            format_err!(
+                "The FST of the non-terminal {} has no input symbol table",
+                nonterminal
+            )
+        })?;
+        let fst_osymt = fst.output_symbols().ok_or_else(|| {
+            format_err!(
+                "The FST of the non-terminal {} has no output symbol table",
+                nonterminal
+            )
+        })?;
+        check_labels(fst, fst_isymt, Side::Input, *nonterminal)?;
+        check_labels(fst, fst_osymt, Side::Output, *nonterminal)?;
+        pairs.push((
+            merge_table(&mut isymt, fst_isymt),
+            merge_table(&mut osymt, fst_osymt),
+        ));
+    }
+
+    let isymt = Arc::new(isymt);
+    let osymt = Arc::new(osymt);
+    for ((_, fst), (ipairs, opairs)) in fst_list.iter_mut().zip(pairs) {
+        if !ipairs.is_empty() || !opairs.is_empty() {
+            relabel_pairs(fst, ipairs, opairs)?;
+        }
+        fst.set_input_symbols(Arc::clone(&isymt));
+        fst.set_output_symbols(Arc::clone(&osymt));
+    }
+    Ok((isymt, osymt))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::algorithms::replace::replace;
+    use crate::fst_impls::VectorFst;
+    use crate::fst_traits::{CoreFst, Fst};
+    use crate::semirings::TropicalWeight;
+    use crate::Tr;
+
+    fn symt(symbols: &[&str]) -> Arc<SymbolTable> {
+        let mut symt = SymbolTable::new();
+        symt.add_symbols(symbols.iter().cloned());
+        Arc::new(symt)
+    }
+
+    fn linear_fst(
+        labels: &[(Label, Label)],
+        symt: &Arc<SymbolTable>,
+    ) -> Result<VectorFst<TropicalWeight>> {
+        let mut fst = VectorFst::new();
+        fst.add_states(labels.len() + 1);
+        fst.set_start(0)?;
+        for (i, (ilabel, olabel)) in labels.iter().enumerate() {
+            fst.add_tr(i as Label, Tr::new(*ilabel, *olabel, 1.0, i as Label + 1))?;
+        }
+        fst.set_final(labels.len() as Label, 0.0)?;
+        fst.set_input_symbols(Arc::clone(symt));
+        fst.set_output_symbols(Arc::clone(symt));
+        Ok(fst)
+    }
+
+    #[test]
+    fn test_merge_symbol_tables() -> Result<()> {
+        // Root : "hello #NAME", #NAME : "alice". The label 1 is "hello" in the root table
+        // and "alice" in the table of #NAME.
+        let root_symt = symt(&["hello", "#NAME"]);
+        let name_symt = symt(&["alice", "#NAME"]);
+        let root = linear_fst(&[(1, 1), (2, 2)], &root_symt)?;
+        let name = linear_fst(&[(1, 1)], &name_symt)?;
+
+        let mut fst_list = vec![(100, root), (2, name)];
+        let (isymt, osymt) = merge_symbol_tables(&mut fst_list, 100)?;
+        assert_eq!(isymt.get_label("hello"), Some(1));
+        assert_eq!(isymt.get_label("#NAME"), Some(2));
+        let alice = isymt.get_label("alice").unwrap();
+        assert_eq!(alice, 3);
+        assert_eq!(osymt, isymt);
+        let trs = fst_list[1].1.get_trs(0)?;
+        assert_eq!(
+            trs.trs(),
+            &[Tr::<TropicalWeight>::new(alice, alice, 1.0, 1)]
+        );
+        drop(trs);
+
+        let expanded: VectorFst<TropicalWeight> = replace::<_, VectorFst<_>, _, _>(
+            fst_list.iter().map(|(l, f)| (*l, f)).collect::<Vec<_>>(),
+            100,
+            true,
+        )?;
+        assert_eq!(expanded.input_symbols(), Some(&isymt));
+
+        let mut no_symbols = vec![(100, VectorFst::<TropicalWeight>::new())];
+        assert!(merge_symbol_tables(&mut no_symbols, 100).is_err());
+        Ok(())
+    }
+}
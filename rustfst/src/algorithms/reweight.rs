@@ -3,7 +3,7 @@ use anyhow::Result;
 use crate::fst_properties::mutable_properties::reweight_properties;
 use crate::fst_properties::FstProperties;
 use crate::fst_traits::MutableFst;
-use crate::semirings::{DivideType, WeaklyDivisibleSemiring};
+use crate::semirings::{DivideType, SemiringProperties, WeaklyDivisibleSemiring};
 use crate::{StateId, Tr, EPS_LABEL};
 
 /// Different types of reweighting.
@@ -29,6 +29,15 @@ where
     F: MutableFst<W>,
     W: WeaklyDivisibleSemiring,
 {
+    match reweight_type {
+        ReweightType::ReweightToInitial => {
+            W::properties().check(SemiringProperties::LEFT_SEMIRING, "reweight to initial")?
+        }
+        ReweightType::ReweightToFinal => {
+            W::properties().check(SemiringProperties::RIGHT_SEMIRING, "reweight to final")?
+        }
+    };
+
     let zero = W::zero();
     let num_states = fst.num_states();
 
@@ -152,3 +161,35 @@ where
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::fst_impls::VectorFst;
+    use crate::fst_traits::CoreFst;
+    use crate::semirings::{Semiring, StringWeightLeft, TropicalWeight};
+
+    #[test]
+    fn test_reweight_semiring_properties() -> Result<()> {
+        let mut fst = VectorFst::<TropicalWeight>::new();
+        fst.add_states(2);
+        fst.set_start(0)?;
+        fst.add_tr(0, Tr::new(1, 1, 2.0, 1))?;
+        fst.set_final(1, 0.0)?;
+        let potentials = vec![TropicalWeight::new(2.0), TropicalWeight::new(0.0)];
+        reweight(&mut fst, &potentials, ReweightType::ReweightToInitial)?;
+        assert_eq!(fst.final_weight(1)?, Some(TropicalWeight::new(0.0)));
+
+        // The left string semiring is not right distributive.
+        let mut fst = VectorFst::<StringWeightLeft>::new();
+        fst.add_states(2);
+        fst.set_start(0)?;
+        fst.add_tr(0, Tr::new(1, 1, StringWeightLeft::from(vec![1]), 1))?;
+        fst.set_final(1, StringWeightLeft::one())?;
+        let potentials = vec![StringWeightLeft::from(vec![1]), StringWeightLeft::one()];
+        assert!(reweight(&mut fst, &potentials, ReweightType::ReweightToFinal).is_err());
+        reweight(&mut fst, &potentials, ReweightType::ReweightToInitial)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,135 @@
+use anyhow::Result;
+
+use crate::algorithms::score_sequence::{advance_frontier, input_epsilon_closure, Frontier};
+use crate::algorithms::transduce;
+use crate::fst_traits::{ExpandedFst, Fst};
+use crate::semirings::{Semiring, WeaklyDivisibleSemiring, WeightQuantize};
+use crate::{Label, KSHORTESTDELTA};
+
+/// Returns the length of the longest non-empty prefix of `ilabels` accepted by the input side
+/// of the FST, if any.
+pub fn longest_match<W: Semiring, F: Fst<W>>(fst: &F, ilabels: &[Label]) -> Result<Option<usize>> {
+    let start = match fst.start() {
+        Some(s) => s,
+        None => return Ok(None),
+    };
+    let mut frontier = Frontier::new();
+    frontier.insert(start, W::one());
+    input_epsilon_closure(fst, &mut frontier, KSHORTESTDELTA)?;
+
+    let mut longest = None;
+    for (idx, label) in ilabels.iter().enumerate() {
+        frontier = advance_frontier(fst, &frontier, *label)?;
+        if frontier.is_empty() {
+            break;
+        }
+        input_epsilon_closure(fst, &mut frontier, KSHORTESTDELTA)?;
+        for state in frontier.keys() {
+            if fst.is_final(*state)? {
+                longest = Some(idx + 1);
+                break;
+            }
+        }
+    }
+    Ok(longest)
+}
+
+/// Applies a rewrite transducer to an input sequence with leftmost-longest match semantics.
+///
+/// The input is scanned from left to right. At each position, the longest non-empty
+/// sub-sequence accepted by the input side of `rule` is rewritten into the best output of
+/// `rule` for it, and the scan resumes right after it. Positions where no match starts are
+/// copied unchanged to the output. Returns the rewritten sequence and the ⊗-product of the
+/// weights of the applied rewrites.
+///
+/// Contrary to the composition with `sigma* rule sigma*`, matches never overlap and only one
+/// output is produced.
+///
+/// # Example
+/// ```
+/// # #[macro_use] extern crate rustfst;
+/// # use rustfst::utils::{acceptor, transducer};
+/// # use rustfst::algorithms::{rewrite_leftmost_longest, union::union};
+/// # use rustfst::fst_impls::VectorFst;
+/// # use rustfst::semirings::{Semiring, TropicalWeight};
+/// # use anyhow::Result;
+/// # fn main() -> Result<()> {
+/// // Rewrites "1" into "10" and "1 2" into "20".
+/// let mut rule : VectorFst<TropicalWeight> = fst![1 => 10];
+/// let rule_2 : VectorFst<TropicalWeight> = fst![1, 2 => 20];
+/// union(&mut rule, &rule_2)?;
+///
+/// let (output, _) = rewrite_leftmost_longest(&rule, &[3, 1, 2, 1, 4])?;
+/// assert_eq!(output, vec![3, 20, 10, 4]);
+/// # Ok(())
+/// # }
+/// ```
+pub fn rewrite_leftmost_longest<W, F>(rule: &F, ilabels: &[Label]) -> Result<(Vec<Label>, W)>
+where
+    F: ExpandedFst<W>,
+    W: Semiring
+        + WeightQuantize
+        + Into<<W as Semiring>::ReverseWeight>
+        + From<<W as Semiring>::ReverseWeight>,
+    <W as Semiring>::ReverseWeight: WeightQuantize + WeaklyDivisibleSemiring,
+{
+    let mut output = Vec::with_capacity(ilabels.len());
+    let mut weight = W::one();
+    let mut pos = 0;
+    while pos < ilabels.len() {
+        let rewrite = match longest_match(rule, &ilabels[pos..])? {
+            Some(len) => transduce(rule, &ilabels[pos..pos + len], 1)?
+                .into_iter()
+                .next()
+                .map(|r| (len, r)),
+            None => None,
+        };
+        match rewrite {
+            Some((len, (olabels, w))) => {
+                output.extend(olabels);
+                weight.times_assign(w)?;
+                pos += len;
+            }
+            None => {
+                output.push(ilabels[pos]);
+                pos += 1;
+            }
+        }
+    }
+    Ok((output, weight))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::fst_impls::VectorFst;
+    use crate::fst_traits::MutableFst;
+    use crate::semirings::TropicalWeight;
+    use crate::{Tr, EPS_LABEL};
+
+    #[test]
+    fn test_rewrite_leftmost_longest_deletion() -> Result<()> {
+        // Deletes "5 5" and rewrites "5" into "6 6".
+        let mut rule = VectorFst::<TropicalWeight>::new();
+        let s0 = rule.add_state();
+        let s1 = rule.add_state();
+        let s2 = rule.add_state();
+        let s3 = rule.add_state();
+        rule.set_start(s0)?;
+        rule.add_tr(s0, Tr::new(5, 6, 1.0, s1))?;
+        rule.add_tr(s1, Tr::new(EPS_LABEL, 6, 0.0, s2))?;
+        rule.add_tr(s1, Tr::new(5, EPS_LABEL, 0.0, s3))?;
+        rule.set_final(s2, TropicalWeight::one())?;
+        rule.set_final(s3, TropicalWeight::one())?;
+
+        assert_eq!(longest_match(&rule, &[5, 5, 5])?, Some(2));
+        assert_eq!(longest_match(&rule, &[4, 5])?, None);
+
+        let (output, weight) = rewrite_leftmost_longest(&rule, &[1, 5, 5, 5, 2])?;
+        // "5 5" is rewritten into "6" by the path 5:6 5:<eps>.
+        assert_eq!(output, vec![1, 6, 6, 6, 2]);
+        assert_eq!(weight, TropicalWeight::new(2.0));
+        Ok(())
+    }
+}
@@ -12,11 +12,34 @@ use crate::{Semiring, StateId, SymbolTable, TrsVec};
 
 type InnerLazyFst<W, F, B> = LazyFst2<W, RmEpsilonOp<W, F, B>, SimpleHashMapCache<W>>;
 
-/// The result of weight factoring is a transducer equivalent to the
-/// input whose path weights have been factored according to the FactorIterator.
-/// States and transitions will be added as necessary. The algorithm is a
-/// generalization to arbitrary weights of the second step of the input
-/// epsilon-normalization algorithm. This version is a Delayed FST.
+/// Removes the epsilon-transitions (when both the input and output labels are an epsilon)
+/// of a transducer, as `rm_epsilon` does. This version is a Delayed FST : the epsilon closure
+/// of a state is only computed when its transitions or its final weight are requested, e.g.
+/// during a decoding exploring a small part of the result.
+///
+/// # Example
+/// ```
+/// # use rustfst::algorithms::rm_epsilon::RmEpsilonFst;
+/// # use rustfst::fst_impls::VectorFst;
+/// # use rustfst::fst_traits::{CoreFst, MutableFst};
+/// # use rustfst::semirings::{Semiring, TropicalWeight};
+/// # use rustfst::{Tr, Trs, EPS_LABEL};
+/// # use anyhow::Result;
+/// # fn main() -> Result<()> {
+/// let mut fst = VectorFst::<TropicalWeight>::new();
+/// fst.add_states(3);
+/// fst.set_start(0)?;
+/// fst.add_tr(0, Tr::new(EPS_LABEL, EPS_LABEL, 1.0, 1))?;
+/// fst.add_tr(1, Tr::new(2, 3, 2.0, 2))?;
+/// fst.set_final(2, TropicalWeight::one())?;
+///
+/// let lazy_fst = RmEpsilonFst::<_, VectorFst<_>, _>::new(fst)?;
+/// let start = lazy_fst.start().unwrap();
+/// assert_eq!(lazy_fst.get_trs(start)?.trs()[0].ilabel, 2);
+/// assert_eq!(lazy_fst.get_trs(start)?.trs()[0].weight, TropicalWeight::new(3.0));
+/// # Ok(())
+/// # }
+/// ```
 pub struct RmEpsilonFst<W: Semiring, F: MutableFst<W>, B: Borrow<F>>(InnerLazyFst<W, F, B>);
 
 impl<W, F, B> CoreFst<W> for RmEpsilonFst<W, F, B>
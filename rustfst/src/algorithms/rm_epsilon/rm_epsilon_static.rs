@@ -6,7 +6,7 @@ use crate::algorithms::rm_epsilon::{RmEpsilonInternalConfig, RmEpsilonState};
 use crate::algorithms::top_sort::TopOrderVisitor;
 use crate::algorithms::tr_filters::EpsilonTrFilter;
 use crate::algorithms::visitors::SccVisitor;
-use crate::algorithms::Queue;
+use crate::algorithms::{prune_with_config, PruneConfig, Queue};
 use crate::fst_properties::mutable_properties::rmepsilon_properties;
 use crate::fst_properties::FstProperties;
 use crate::fst_traits::MutableFst;
@@ -75,6 +75,7 @@ pub(crate) fn rm_epsilon_with_internal_config<W: Semiring, F: MutableFst<W>, Q:
     let connect = opts.connect;
     let weight_threshold = opts.weight_threshold.clone();
     let state_threshold = opts.state_threshold;
+    let delta = opts.sd_opts.delta;
 
     let start_state = match fst.start() {
         None => return Ok(()),
@@ -167,7 +168,8 @@ pub(crate) fn rm_epsilon_with_internal_config<W: Semiring, F: MutableFst<W>, Q:
     fst.set_properties(rmepsilon_properties(fst.properties(), false));
 
     if weight_threshold != W::zero() || state_threshold != None {
-        todo!("Implement Prune!")
+        let config = PruneConfig::new(weight_threshold.clone(), state_threshold, delta);
+        prune_with_config(fst, config)?;
     }
 
     if connect && weight_threshold == W::zero() && state_threshold == None {
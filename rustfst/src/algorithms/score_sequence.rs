@@ -0,0 +1,195 @@
+use std::collections::{BTreeMap, HashSet, VecDeque};
+
+use anyhow::Result;
+
+use crate::fst_traits::Fst;
+use crate::semirings::Semiring;
+use crate::{Label, StateId, Trs, EPS_LABEL, KSHORTESTDELTA};
+
+/// Weighted set of states reached after reading a prefix of the input.
+pub(crate) type Frontier<W> = BTreeMap<StateId, W>;
+
+/// Extends the frontier with all the states reachable through input epsilon transitions.
+/// The weights are ⊕-summed over all the epsilon paths using the generic single-source
+/// shortest-distance algorithm, which converges for k-closed semirings.
+pub(crate) fn input_epsilon_closure<W: Semiring, F: Fst<W>>(
+    fst: &F,
+    frontier: &mut Frontier<W>,
+    delta: f32,
+) -> Result<()> {
+    let mut residuals = frontier.clone();
+    let mut queue: VecDeque<StateId> = frontier.keys().cloned().collect();
+    let mut enqueued: HashSet<StateId> = queue.iter().cloned().collect();
+
+    while let Some(state) = queue.pop_front() {
+        enqueued.remove(&state);
+        let residual = match residuals.insert(state, W::zero()) {
+            Some(r) if !r.is_zero() => r,
+            _ => continue,
+        };
+        for tr in fst.get_trs(state)?.trs() {
+            if tr.ilabel != EPS_LABEL {
+                continue;
+            }
+            let weight = residual.times(&tr.weight)?;
+            if weight.is_zero() {
+                continue;
+            }
+            let distance = frontier.entry(tr.nextstate).or_insert_with(W::zero);
+            let new_distance = distance.plus(&weight)?;
+            if !distance.approx_equal(&new_distance, delta) {
+                *distance = new_distance;
+                residuals
+                    .entry(tr.nextstate)
+                    .or_insert_with(W::zero)
+                    .plus_assign(&weight)?;
+                if enqueued.insert(tr.nextstate) {
+                    queue.push_back(tr.nextstate);
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Follows all the transitions with input label `label` leaving the states of the frontier.
+pub(crate) fn advance_frontier<W: Semiring, F: Fst<W>>(
+    fst: &F,
+    frontier: &Frontier<W>,
+    label: Label,
+) -> Result<Frontier<W>> {
+    let mut next = Frontier::new();
+    for (state, weight) in frontier.iter() {
+        for tr in fst.get_trs(*state)?.trs() {
+            if tr.ilabel != label {
+                continue;
+            }
+            let w = weight.times(&tr.weight)?;
+            if w.is_zero() {
+                continue;
+            }
+            next.entry(tr.nextstate)
+                .or_insert_with(W::zero)
+                .plus_assign(&w)?;
+        }
+    }
+    Ok(next)
+}
+
+/// Computes the ⊕-sum of the weights of all the successful paths of the FST whose
+/// input string is `ilabels`. Returns `None` if no such path exists.
+///
+/// Contrary to the building of an acceptor for the sequence followed by a composition,
+/// the FST is walked directly, keeping track of the weighted set of active states.
+/// Input epsilon transitions are followed at every position of the sequence.
+///
+/// # Example
+/// ```
+/// # #[macro_use] extern crate rustfst;
+/// # use rustfst::utils::{acceptor, transducer};
+/// # use rustfst::semirings::{Semiring, TropicalWeight};
+/// # use rustfst::fst_impls::VectorFst;
+/// # use rustfst::algorithms::score_sequence;
+/// # use anyhow::Result;
+/// # fn main() -> Result<()> {
+/// let fst : VectorFst<TropicalWeight> = fst![1, 2 => 3, 4; 0.5];
+///
+/// assert_eq!(score_sequence(&fst, &[1, 2])?, Some(TropicalWeight::new(0.5)));
+/// assert_eq!(score_sequence(&fst, &[1])?, None);
+/// # Ok(())
+/// # }
+/// ```
+pub fn score_sequence<W: Semiring, F: Fst<W>>(fst: &F, ilabels: &[Label]) -> Result<Option<W>> {
+    let start = match fst.start() {
+        Some(s) => s,
+        None => return Ok(None),
+    };
+    let mut frontier = Frontier::new();
+    frontier.insert(start, W::one());
+    input_epsilon_closure(fst, &mut frontier, KSHORTESTDELTA)?;
+
+    for label in ilabels {
+        if frontier.is_empty() {
+            return Ok(None);
+        }
+        frontier = advance_frontier(fst, &frontier, *label)?;
+        input_epsilon_closure(fst, &mut frontier, KSHORTESTDELTA)?;
+    }
+
+    let mut total = None;
+    for (state, weight) in frontier.iter() {
+        if let Some(final_weight) = fst.final_weight(*state)? {
+            total
+                .get_or_insert_with(W::zero)
+                .plus_assign(weight.times(final_weight)?)?;
+        }
+    }
+    Ok(total)
+}
+
+/// Returns true if the FST has at least one successful path whose input string is `ilabels`.
+///
+/// # Example
+/// ```
+/// # #[macro_use] extern crate rustfst;
+/// # use rustfst::utils::{acceptor, transducer};
+/// # use rustfst::semirings::{Semiring, BooleanWeight};
+/// # use rustfst::fst_impls::VectorFst;
+/// # use rustfst::algorithms::accepts;
+/// # use anyhow::Result;
+/// # fn main() -> Result<()> {
+/// let fst : VectorFst<BooleanWeight> = fst![1, 2, 3];
+///
+/// assert!(accepts(&fst, &[1, 2, 3])?);
+/// assert!(!accepts(&fst, &[1, 2])?);
+/// # Ok(())
+/// # }
+/// ```
+pub fn accepts<W: Semiring, F: Fst<W>>(fst: &F, ilabels: &[Label]) -> Result<bool> {
+    Ok(score_sequence(fst, ilabels)?.is_some())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::fst_impls::VectorFst;
+    use crate::fst_traits::MutableFst;
+    use crate::semirings::{LogWeight, TropicalWeight};
+    use crate::Tr;
+
+    #[test]
+    fn test_score_sequence_with_epsilons() -> Result<()> {
+        let mut fst = VectorFst::<TropicalWeight>::new();
+        let s0 = fst.add_state();
+        let s1 = fst.add_state();
+        let s2 = fst.add_state();
+        fst.set_start(s0)?;
+        fst.add_tr(s0, Tr::new(1, 1, 1.0, s1))?;
+        fst.add_tr(s0, Tr::new(1, 2, 3.0, s2))?;
+        fst.add_tr(s1, Tr::new(EPS_LABEL, 5, 0.5, s2))?;
+        fst.set_final(s2, 2.0)?;
+
+        assert_eq!(score_sequence(&fst, &[1])?, Some(TropicalWeight::new(3.5)));
+        assert_eq!(score_sequence(&fst, &[2])?, None);
+        assert_eq!(score_sequence(&fst, &[])?, None);
+        assert!(accepts(&fst, &[1])?);
+        assert!(!accepts(&fst, &[1, 1])?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_score_sequence_sums_paths() -> Result<()> {
+        let mut fst = VectorFst::<LogWeight>::new();
+        let s0 = fst.add_state();
+        let s1 = fst.add_state();
+        fst.set_start(s0)?;
+        fst.add_tr(s0, Tr::new(1, 1, 1.0, s1))?;
+        fst.add_tr(s0, Tr::new(1, 2, 1.0, s1))?;
+        fst.set_final(s1, LogWeight::one())?;
+
+        let score = score_sequence(&fst, &[1])?.unwrap();
+        assert!(score.approx_equal(LogWeight::new(1.0 - 2f32.ln()), 1e-3));
+        Ok(())
+    }
+}
@@ -0,0 +1,332 @@
+use std::collections::hash_map::Entry;
+use std::collections::{BTreeMap, HashMap, VecDeque};
+
+use anyhow::Result;
+
+use crate::fst_traits::{AllocableFst, ExpandedFst, MutableFst};
+use crate::semirings::{DivideType, WeaklyDivisibleSemiring, WeightQuantize};
+use crate::{Label, StateId, Tr, Trs, EPS_LABEL, KDELTA};
+
+/// Configuration of the sequentialization.
+#[derive(Debug, Clone, Copy, PartialOrd, PartialEq)]
+pub struct SequentializeConfig {
+    /// Quantization delta applied to the residual weights of the subsets.
+    pub delta: f32,
+    /// Maximum number of output labels that can be delayed.
+    /// The input is considered as not sequentializable beyond it.
+    pub max_delay: usize,
+    /// Optional maximum number of states of the output.
+    pub max_states: Option<usize>,
+}
+
+impl Default for SequentializeConfig {
+    fn default() -> Self {
+        Self {
+            delta: KDELTA,
+            max_delay: 64,
+            max_states: None,
+        }
+    }
+}
+
+impl SequentializeConfig {
+    pub fn new(delta: f32, max_delay: usize, max_states: Option<usize>) -> Self {
+        Self {
+            delta,
+            max_delay,
+            max_states,
+        }
+    }
+
+    pub fn with_delta(self, delta: f32) -> Self {
+        Self { delta, ..self }
+    }
+
+    pub fn with_max_delay(self, max_delay: usize) -> Self {
+        Self { max_delay, ..self }
+    }
+
+    pub fn with_max_states(self, max_states: Option<usize>) -> Self {
+        Self { max_states, ..self }
+    }
+}
+
+/// Element of a subset: a state of the input along with the output labels and the weight
+/// that have been read but not emitted yet.
+type Subset<W> = Vec<(StateId, Vec<Label>, W)>;
+
+fn common_prefix_len(strings: &[&[Label]]) -> usize {
+    let first = match strings.first() {
+        Some(f) => f,
+        None => return 0,
+    };
+    let mut len = first.len();
+    for s in strings.iter().skip(1) {
+        len = first
+            .iter()
+            .zip(s.iter())
+            .take(len)
+            .take_while(|(a, b)| a == b)
+            .count();
+    }
+    len
+}
+
+/// Adds a path emitting `olabels` (possibly with several transitions, the extra ones having
+/// an input epsilon) from `source` to `target`.
+fn add_output_path<W: WeaklyDivisibleSemiring, F: MutableFst<W>>(
+    ofst: &mut F,
+    source: StateId,
+    ilabel: Label,
+    olabels: &[Label],
+    weight: W,
+    target: StateId,
+) -> Result<()> {
+    if olabels.len() <= 1 {
+        let olabel = olabels.first().cloned().unwrap_or(EPS_LABEL);
+        return ofst.add_tr(source, Tr::new(ilabel, olabel, weight, target));
+    }
+    let mut current = source;
+    for (idx, olabel) in olabels.iter().enumerate() {
+        let (i, w) = if idx == 0 {
+            (ilabel, weight.clone())
+        } else {
+            (EPS_LABEL, W::one())
+        };
+        let next = if idx == olabels.len() - 1 {
+            target
+        } else {
+            ofst.add_state()
+        };
+        ofst.add_tr(current, Tr::new(i, *olabel, w, next))?;
+        current = next;
+    }
+    Ok(())
+}
+
+/// Computes a sequential transducer equivalent to a functional transducer with bounded
+/// delay. The output strings are emitted as early as possible and the remaining ones are
+/// emitted at the end of the input (final output function).
+///
+/// The result is not epsilon free : when more than one output label must be emitted at
+/// once, the labels after the first one are emitted by a chain of transitions with an input
+/// epsilon, and a final output function is emitted by a chain of transitions with an input
+/// epsilon towards a new final state. Each state built for a subset has at most one
+/// transition per non epsilon input label, and at most one input epsilon transition, so the
+/// result is input deterministic in the sense of `FstProperties::I_DETERMINISTIC`, but it
+/// isn't subsequential in the strict sense : applying it to an input may have to follow the
+/// input epsilon transitions, and `rm_epsilon` would break the determinism.
+///
+/// The input must not contain input epsilons. An error is returned if the delay between
+/// the input and the output exceeds `config.max_delay` (which happens when the input is
+/// not sequentializable), or if the input is not functional.
+///
+/// # Example
+/// ```
+/// # use rustfst::algorithms::{sequentialize, SequentializeConfig};
+/// # use rustfst::fst_impls::VectorFst;
+/// # use rustfst::fst_properties::FstProperties;
+/// # use rustfst::fst_traits::{CoreFst, MutableFst};
+/// # use rustfst::semirings::{Semiring, TropicalWeight};
+/// # use rustfst::Tr;
+/// # use anyhow::Result;
+/// # fn main() -> Result<()> {
+/// // 1 2 -> 3 4 and 1 5 -> 6 4: the first output label depends on the second input label.
+/// let mut fst = VectorFst::<TropicalWeight>::new();
+/// let s0 = fst.add_state();
+/// let s1 = fst.add_state();
+/// let s2 = fst.add_state();
+/// let s3 = fst.add_state();
+/// fst.set_start(s0)?;
+/// fst.add_tr(s0, Tr::new(1, 3, 1.0, s1))?;
+/// fst.add_tr(s0, Tr::new(1, 6, 2.0, s2))?;
+/// fst.add_tr(s1, Tr::new(2, 4, 1.0, s3))?;
+/// fst.add_tr(s2, Tr::new(5, 4, 1.0, s3))?;
+/// fst.set_final(s3, TropicalWeight::one())?;
+///
+/// let mut seq: VectorFst<_> = sequentialize(&fst, SequentializeConfig::default())?;
+/// let props = seq.compute_and_update_properties(FstProperties::I_DETERMINISTIC)?;
+/// assert!(props.contains(FstProperties::I_DETERMINISTIC));
+/// # Ok(())
+/// # }
+/// ```
+pub fn sequentialize<W, F1, F2>(ifst: &F1, config: SequentializeConfig) -> Result<F2>
+where
+    W: WeaklyDivisibleSemiring + WeightQuantize,
+    F1: ExpandedFst<W>,
+    F2: MutableFst<W> + AllocableFst<W>,
+{
+    let mut ofst = F2::new();
+    let start = match ifst.start() {
+        Some(s) => s,
+        None => return Ok(ofst),
+    };
+
+    let mut subsets: HashMap<Subset<W>, StateId> = HashMap::new();
+    let mut queue = VecDeque::new();
+
+    let start_subset: Subset<W> = vec![(start, vec![], W::one())];
+    let ostart = ofst.add_state();
+    ofst.set_start(ostart)?;
+    subsets.insert(start_subset.clone(), ostart);
+    queue.push_back((start_subset, ostart));
+
+    while let Some((subset, ostate)) = queue.pop_front() {
+        // Final output function.
+        let mut final_output: Option<(Vec<Label>, W)> = None;
+        for (state, residual_olabels, residual_weight) in subset.iter() {
+            if let Some(final_weight) = ifst.final_weight(*state)? {
+                let w = residual_weight.times(final_weight)?;
+                match &mut final_output {
+                    None => final_output = Some((residual_olabels.clone(), w)),
+                    Some((olabels, weight)) => {
+                        if olabels != residual_olabels {
+                            bail!("sequentialize : the input FST is not functional");
+                        }
+                        weight.plus_assign(w)?;
+                    }
+                }
+            }
+        }
+        if let Some((olabels, weight)) = final_output {
+            if olabels.is_empty() {
+                ofst.set_final(ostate, weight)?;
+            } else {
+                let ofinal = ofst.add_state();
+                ofst.set_final(ofinal, W::one())?;
+                add_output_path(&mut ofst, ostate, EPS_LABEL, &olabels, weight, ofinal)?;
+            }
+        }
+
+        // Group the transitions leaving the subset by input label.
+        let mut by_label: BTreeMap<Label, BTreeMap<(StateId, Vec<Label>), W>> = BTreeMap::new();
+        for (state, residual_olabels, residual_weight) in subset.iter() {
+            for tr in ifst.get_trs(*state)?.trs() {
+                if tr.ilabel == EPS_LABEL {
+                    bail!("sequentialize : input epsilons are not supported, remove them first");
+                }
+                let mut olabels = residual_olabels.clone();
+                if tr.olabel != EPS_LABEL {
+                    olabels.push(tr.olabel);
+                }
+                let weight = residual_weight.times(&tr.weight)?;
+                by_label
+                    .entry(tr.ilabel)
+                    .or_default()
+                    .entry((tr.nextstate, olabels))
+                    .or_insert_with(W::zero)
+                    .plus_assign(weight)?;
+            }
+        }
+
+        for (ilabel, elements) in by_label.into_iter() {
+            let strings: Vec<&[Label]> = elements.keys().map(|(_, o)| o.as_slice()).collect();
+            let prefix_len = common_prefix_len(&strings);
+            let prefix = strings[0][..prefix_len].to_vec();
+
+            let mut common_weight = W::zero();
+            for w in elements.values() {
+                common_weight.plus_assign(w)?;
+            }
+
+            let mut next_subset = Subset::with_capacity(elements.len());
+            for ((state, olabels), weight) in elements.into_iter() {
+                let residual_olabels = olabels[prefix_len..].to_vec();
+                if residual_olabels.len() > config.max_delay {
+                    bail!(
+                        "sequentialize : delay exceeds {} labels, the input FST is likely not sequentializable",
+                        config.max_delay
+                    );
+                }
+                let residual_weight = weight
+                    .divide(&common_weight, DivideType::DivideLeft)?
+                    .quantize(config.delta)?;
+                next_subset.push((state, residual_olabels, residual_weight));
+            }
+
+            let next_ostate = match subsets.entry(next_subset) {
+                Entry::Occupied(e) => *e.get(),
+                Entry::Vacant(e) => {
+                    let s = ofst.add_state();
+                    queue.push_back((e.key().clone(), s));
+                    e.insert(s);
+                    s
+                }
+            };
+            if let Some(max_states) = config.max_states {
+                if ofst.num_states() > max_states {
+                    bail!("sequentialize : more than {} states created", max_states);
+                }
+            }
+            add_output_path(
+                &mut ofst,
+                ostate,
+                ilabel,
+                &prefix,
+                common_weight,
+                next_ostate,
+            )?;
+        }
+    }
+
+    ofst.set_symts_from_fst(ifst);
+    Ok(ofst)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::algorithms::transduce;
+    use crate::fst_impls::VectorFst;
+    use crate::fst_properties::FstProperties;
+    use crate::semirings::{Semiring, TropicalWeight};
+
+    #[test]
+    fn test_sequentialize_bounded_delay() -> Result<()> {
+        let mut fst = VectorFst::<TropicalWeight>::new();
+        let s0 = fst.add_state();
+        let s1 = fst.add_state();
+        let s2 = fst.add_state();
+        let s3 = fst.add_state();
+        fst.set_start(s0)?;
+        fst.add_tr(s0, Tr::new(1, 3, 1.0, s1))?;
+        fst.add_tr(s0, Tr::new(1, 6, 2.0, s2))?;
+        fst.add_tr(s1, Tr::new(2, 4, 1.0, s3))?;
+        fst.add_tr(s2, Tr::new(5, 4, 1.0, s3))?;
+        fst.set_final(s3, TropicalWeight::one())?;
+        fst.set_final(s2, TropicalWeight::new(0.5))?;
+
+        let mut seq: VectorFst<_> = sequentialize(&fst, SequentializeConfig::default())?;
+        let props = seq.compute_and_update_properties(FstProperties::I_DETERMINISTIC)?;
+        assert!(props.contains(FstProperties::I_DETERMINISTIC));
+
+        for input in &[vec![1, 2], vec![1, 5], vec![1, 5, 5], vec![1], vec![]] {
+            assert_eq!(transduce(&seq, input, 1)?, transduce(&fst, input, 1)?);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_sequentialize_unbounded_delay() -> Result<()> {
+        // (1:3)* 2:2 | (1:4)* 5:5 can't be sequentialized.
+        let mut fst = VectorFst::<TropicalWeight>::new();
+        let s0 = fst.add_state();
+        let s1 = fst.add_state();
+        let s2 = fst.add_state();
+        let s3 = fst.add_state();
+        fst.set_start(s0)?;
+        fst.add_tr(s0, Tr::new(1, 3, 0.0, s1))?;
+        fst.add_tr(s0, Tr::new(1, 4, 0.0, s2))?;
+        fst.add_tr(s1, Tr::new(1, 3, 0.0, s1))?;
+        fst.add_tr(s2, Tr::new(1, 4, 0.0, s2))?;
+        fst.add_tr(s1, Tr::new(2, 2, 0.0, s3))?;
+        fst.add_tr(s2, Tr::new(5, 5, 0.0, s3))?;
+        fst.set_final(s3, TropicalWeight::one())?;
+
+        let config = SequentializeConfig::default().with_max_delay(10);
+        let res: Result<VectorFst<_>> = sequentialize(&fst, config);
+        assert!(res.is_err());
+        Ok(())
+    }
+}
@@ -2,13 +2,14 @@ use std::marker::PhantomData;
 
 use anyhow::Result;
 
+use crate::algorithms::phi_shortest::phi_shortest_distance;
 use crate::algorithms::queues::AutoQueue;
 use crate::algorithms::tr_filters::{AnyTrFilter, TrFilter};
-use crate::algorithms::Queue;
+use crate::algorithms::{Queue, WeightGraph};
 use crate::fst_impls::VectorFst;
 use crate::fst_traits::{ExpandedFst, MutableFst};
 use crate::semirings::{ReverseBack, Semiring, SemiringProperties, WeightQuantize};
-use crate::{StateId, Trs, KSHORTESTDELTA};
+use crate::{Label, StateId, Trs, KSHORTESTDELTA};
 use std::borrow::Borrow;
 
 pub(crate) struct ShortestDistanceInternalConfig<W: Semiring, Q: Queue, A: TrFilter<W>> {
@@ -222,7 +223,7 @@ impl<W: Semiring, Q: Queue, A: TrFilter<W>> ShortestDistanceState<W, Q, A> {
                     na.plus_assign(&weight)?;
                     *nd = na.clone();
                     nr.plus_assign(&weight)?;
-                    if !self.enqueued[state] {
+                    if !self.enqueued[nextstate] {
                         self.state_queue.enqueue(nextstate as StateId);
                         self.enqueued[nextstate] = true;
                     } else {
@@ -256,19 +257,54 @@ pub(crate) fn shortest_distance_with_internal_config<
 #[derive(Debug, Clone, Copy, PartialOrd, PartialEq)]
 pub struct ShortestDistanceConfig {
     delta: f32,
+    weights_only: bool,
+    phi_label: Option<Label>,
 }
 
 impl Default for ShortestDistanceConfig {
     fn default() -> Self {
         Self {
             delta: KSHORTESTDELTA,
+            weights_only: false,
+            phi_label: None,
         }
     }
 }
 
 impl ShortestDistanceConfig {
     pub fn new(delta: f32) -> Self {
-        Self { delta }
+        Self {
+            delta,
+            weights_only: false,
+            phi_label: None,
+        }
+    }
+
+    pub fn with_delta(self, delta: f32) -> Self {
+        Self { delta, ..self }
+    }
+
+    /// Computes the distances on a [`WeightGraph`] of the FST, which drops the labels, instead
+    /// of on the FST itself. The states are then visited in FIFO order when the FST is cyclic
+    /// and one weight is returned per state.
+    pub fn with_weights_only(self, weights_only: bool) -> Self {
+        Self {
+            weights_only,
+            ..self
+        }
+    }
+
+    /// Interprets the transitions whose input label is `phi_label` as failure transitions, as
+    /// the backoff transitions of an n-gram model : one is only taken to read the labels that
+    /// its state can't read, and merged with the transition reading them, or to reach a final
+    /// state if its state is not final. The distances are then those of the FST where the
+    /// failure transitions would be replaced by the transitions they stand for, without
+    /// building it. Only supported in the forward direction.
+    pub fn with_phi_label(self, phi_label: Label) -> Self {
+        Self {
+            phi_label: Some(phi_label),
+            ..self
+        }
     }
 }
 
@@ -317,6 +353,23 @@ pub fn shortest_distance_with_config<W: Semiring, F: ExpandedFst<W>>(
     config: ShortestDistanceConfig,
 ) -> Result<Vec<W>> {
     let delta = config.delta;
+    if let Some(phi_label) = config.phi_label {
+        if reverse || config.weights_only {
+            bail!("shortest_distance : the phi label is only supported in the forward direction and with the labels");
+        }
+        return phi_shortest_distance(fst, phi_label, delta);
+    }
+    if config.weights_only {
+        return if !reverse {
+            WeightGraph::new(fst)?.shortest_distance(delta)
+        } else {
+            WeightGraph::new_reversed(fst)?
+                .shortest_distance(delta)?
+                .iter()
+                .map(|w| w.reverse_back())
+                .collect()
+        };
+    }
     let tr_filter = AnyTrFilter {};
     if !reverse {
         let queue = AutoQueue::new(fst, None, &tr_filter)?;
@@ -335,6 +388,52 @@ pub fn shortest_distance_with_config<W: Semiring, F: ExpandedFst<W>>(
     }
 }
 
+/// Compute the shortest distance from the initial state to every state, visiting the states
+/// in the order of `queue` instead of the one chosen by `AutoQueue`, e.g. a `TopOrderQueue`
+/// built once for an acyclic FST whose distances are computed several times, or a
+/// `FifoQueue`. The distances don't depend on the queue, only the number of times each state
+/// is visited does. The queue is cleared before the computation.
+///
+/// Only the forward distances are computed, and neither `with_weights_only` nor
+/// `with_phi_label` is supported.
+///
+/// # Example
+/// ```
+/// # use rustfst::algorithms::queues::FifoQueue;
+/// # use rustfst::algorithms::{shortest_distance, shortest_distance_with_queue, ShortestDistanceConfig};
+/// # use rustfst::fst_impls::VectorFst;
+/// # use rustfst::fst_traits::MutableFst;
+/// # use rustfst::semirings::{Semiring, TropicalWeight};
+/// # use rustfst::Tr;
+/// # use anyhow::Result;
+/// # fn main() -> Result<()> {
+/// let mut fst = VectorFst::<TropicalWeight>::new();
+/// fst.add_states(3);
+/// fst.set_start(0)?;
+/// fst.add_tr(0, Tr::new(1, 1, 1.0, 1))?;
+/// fst.add_tr(0, Tr::new(2, 2, 3.0, 2))?;
+/// fst.add_tr(1, Tr::new(3, 3, 1.0, 2))?;
+///
+/// let dists =
+///     shortest_distance_with_queue(&fst, FifoQueue::default(), ShortestDistanceConfig::default())?;
+/// assert_eq!(dists, shortest_distance(&fst, false)?);
+/// assert_eq!(dists[2], TropicalWeight::new(2.0));
+/// # Ok(())
+/// # }
+/// ```
+pub fn shortest_distance_with_queue<W: Semiring, F: ExpandedFst<W>, Q: Queue>(
+    fst: &F,
+    queue: Q,
+    config: ShortestDistanceConfig,
+) -> Result<Vec<W>> {
+    if config.phi_label.is_some() || config.weights_only {
+        bail!("shortest_distance_with_queue : the phi label and the weights only options are not supported");
+    }
+    let config =
+        ShortestDistanceInternalConfig::new_with_default(AnyTrFilter {}, queue, config.delta);
+    shortest_distance_with_internal_config(fst, config)
+}
+
 #[allow(unused)]
 /// Return the sum of the weight of all successful paths in an FST, i.e., the
 /// shortest-distance from the initial state to the final states..
@@ -372,3 +471,138 @@ where
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::cell::Cell;
+    use std::collections::VecDeque;
+    use std::rc::Rc;
+
+    use crate::algorithms::queues::{
+        FifoQueue, LifoQueue, NaturalShortestFirstQueue, StateOrderQueue, TopOrderQueue,
+    };
+    use crate::algorithms::QueueType;
+    use crate::algorithms::{
+        shortest_path_with_config, shortest_path_with_queue, ShortestPathConfig,
+    };
+    use crate::semirings::TropicalWeight;
+    use crate::Tr;
+
+    /// FIFO queue counting the states enqueued while already in the queue.
+    #[derive(Debug, Default)]
+    struct CountingFifoQueue {
+        queue: VecDeque<StateId>,
+        duplicates: Rc<Cell<usize>>,
+    }
+
+    impl Queue for CountingFifoQueue {
+        fn head(&mut self) -> Option<StateId> {
+            self.queue.front().cloned()
+        }
+
+        fn enqueue(&mut self, state: StateId) {
+            if self.queue.contains(&state) {
+                self.duplicates.set(self.duplicates.get() + 1);
+            }
+            self.queue.push_back(state);
+        }
+
+        fn dequeue(&mut self) {
+            self.queue.pop_front();
+        }
+
+        fn update(&mut self, _state: StateId) {}
+
+        fn is_empty(&self) -> bool {
+            self.queue.is_empty()
+        }
+
+        fn clear(&mut self) {
+            self.queue.clear()
+        }
+
+        fn queue_type(&self) -> QueueType {
+            QueueType::FifoQueue
+        }
+    }
+
+    #[test]
+    fn test_shortest_distance_no_duplicate_enqueue() -> Result<()> {
+        // The state 2 is improved through the state 1 while it is still in the queue.
+        let mut fst = VectorFst::<TropicalWeight>::new();
+        fst.add_states(3);
+        fst.set_start(0)?;
+        fst.add_tr(0, Tr::new(1, 1, 1.0, 1))?;
+        fst.add_tr(0, Tr::new(2, 2, 5.0, 2))?;
+        fst.add_tr(1, Tr::new(3, 3, 1.0, 2))?;
+
+        let queue = CountingFifoQueue::default();
+        let duplicates = Rc::clone(&queue.duplicates);
+        let dists = shortest_distance_with_queue(&fst, queue, ShortestDistanceConfig::default())?;
+        assert_eq!(dists[2], TropicalWeight::new(2.0));
+        assert_eq!(duplicates.get(), 0);
+        Ok(())
+    }
+
+    #[test]
+    fn test_shortest_distance_with_queue() -> Result<()> {
+        // The state 2 is first reached through a costly tr, then through the state 1.
+        let mut fst = VectorFst::<TropicalWeight>::new();
+        fst.add_states(4);
+        fst.set_start(0)?;
+        fst.add_tr(0, Tr::new(1, 1, 5.0, 2))?;
+        fst.add_tr(0, Tr::new(2, 2, 1.0, 1))?;
+        fst.add_tr(1, Tr::new(3, 3, 1.0, 2))?;
+        fst.add_tr(2, Tr::new(4, 4, 1.0, 3))?;
+        fst.set_final(3, TropicalWeight::one())?;
+        let expected = vec![
+            TropicalWeight::one(),
+            TropicalWeight::new(1.0),
+            TropicalWeight::new(2.0),
+            TropicalWeight::new(3.0),
+        ];
+        let config = ShortestDistanceConfig::default();
+        assert_eq!(shortest_distance(&fst, false)?, expected);
+        let filter = AnyTrFilter {};
+        assert_eq!(
+            shortest_distance_with_queue(&fst, FifoQueue::default(), config)?,
+            expected
+        );
+        assert_eq!(
+            shortest_distance_with_queue(&fst, LifoQueue::default(), config)?,
+            expected
+        );
+        assert_eq!(
+            shortest_distance_with_queue(&fst, StateOrderQueue::default(), config)?,
+            expected
+        );
+        assert_eq!(
+            shortest_distance_with_queue(&fst, TopOrderQueue::new(&fst, &filter), config)?,
+            expected
+        );
+        let queue = NaturalShortestFirstQueue::new(expected.clone());
+        assert_eq!(shortest_distance_with_queue(&fst, queue, config)?, expected);
+
+        // With a cycle, whatever the queue.
+        fst.add_tr(3, Tr::new(5, 5, 1.0, 1))?;
+        assert_eq!(
+            shortest_distance_with_queue(&fst, FifoQueue::default(), config)?,
+            expected
+        );
+        assert!(shortest_distance_with_queue(
+            &fst,
+            FifoQueue::default(),
+            config.with_weights_only(true)
+        )
+        .is_err());
+
+        let path_config = ShortestPathConfig::default().with_nshortest(2);
+        let paths: VectorFst<TropicalWeight> =
+            shortest_path_with_queue(&fst, LifoQueue::default(), path_config)?;
+        let ref_paths: VectorFst<TropicalWeight> = shortest_path_with_config(&fst, path_config)?;
+        assert_eq!(paths, ref_paths);
+        Ok(())
+    }
+}
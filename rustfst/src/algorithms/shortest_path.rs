@@ -4,12 +4,13 @@ use anyhow::Result;
 use unsafe_unwrap::UnsafeUnwrap;
 
 use crate::algorithms::determinize::determinize_with_distance;
+use crate::algorithms::phi_shortest::phi_shortest_path;
 use crate::algorithms::queues::AutoQueue;
 use crate::algorithms::tr_filters::AnyTrFilter;
 use crate::algorithms::{
-    connect, reverse, shortest_distance_with_config, Queue, ShortestDistanceConfig,
+    connect, reverse, shortest_distance_with_queue, Queue, ShortestDistanceConfig,
 };
-use crate::fst_impls::VectorFst;
+use crate::fst_impls::{FinalStates, VectorFst};
 use crate::fst_properties::mutable_properties::shortest_path_properties;
 use crate::fst_properties::FstProperties;
 use crate::fst_traits::{CoreFst, ExpandedFst, MutableFst};
@@ -17,7 +18,7 @@ use crate::semirings::{
     ReverseBack, Semiring, SemiringProperties, WeaklyDivisibleSemiring, WeightQuantize,
 };
 use crate::Tr;
-use crate::{StateId, Trs, KSHORTESTDELTA};
+use crate::{Label, StateId, Trs, KSHORTESTDELTA};
 use bitflags::_core::fmt::Formatter;
 use std::fmt::Debug;
 
@@ -25,8 +26,13 @@ use std::fmt::Debug;
 #[derive(Debug, Clone, Copy, PartialOrd, PartialEq)]
 pub struct ShortestPathConfig {
     pub delta: f32,
+    /// Number of paths to return, the paths being ordered by increasing weights.
     pub nshortest: usize,
+    /// Whether the paths returned must have distinct strings. The input FST, which must then
+    /// be an acceptor, is determinized on the fly, which requires the weights to be weakly
+    /// divisible.
     pub unique: bool,
+    phi_label: Option<Label>,
 }
 
 impl Default for ShortestPathConfig {
@@ -35,6 +41,7 @@ impl Default for ShortestPathConfig {
             delta: KSHORTESTDELTA,
             nshortest: 1,
             unique: false,
+            phi_label: None,
         }
     }
 }
@@ -45,6 +52,7 @@ impl ShortestPathConfig {
             delta,
             nshortest,
             unique,
+            phi_label: None,
         }
     }
 
@@ -59,6 +67,18 @@ impl ShortestPathConfig {
     pub fn with_unique(self, unique: bool) -> Self {
         Self { unique, ..self }
     }
+
+    /// Interprets the transitions whose input label is `phi_label` as failure transitions, as
+    /// the backoff transitions of an n-gram model : one is only taken to read the labels that
+    /// its state can't read, or to reach a final state if its state is not final. The failure
+    /// transitions of the shortest path are merged with the transitions they lead to. Only
+    /// supported for the single shortest path.
+    pub fn with_phi_label(self, phi_label: Label) -> Self {
+        Self {
+            phi_label: Some(phi_label),
+            ..self
+        }
+    }
 }
 
 /// Create an FST containing the single shortest path in the input
@@ -106,6 +126,9 @@ where
 ///
 /// ![shortestpath_out_n_2](https://raw.githubusercontent.com/Garvys/rustfst-images-doc/master/images/shortestpath_out_n_2.svg?sanitize=true)
 ///
+/// With `unique`, the n-best paths are computed on the determinized FST so that two paths
+/// reading the same string are never both returned. The FST must then be an acceptor.
+///
 pub fn shortest_path_with_config<W, FI, FO>(ifst: &FI, config: ShortestPathConfig) -> Result<FO>
 where
     FI: ExpandedFst<W>,
@@ -115,6 +138,65 @@ where
         + Into<<W as Semiring>::ReverseWeight>
         + From<<W as Semiring>::ReverseWeight>,
     <W as Semiring>::ReverseWeight: WeightQuantize + WeaklyDivisibleSemiring,
+{
+    if config.nshortest == 0 {
+        return Ok(FO::new());
+    }
+
+    if let Some(phi_label) = config.phi_label {
+        if config.nshortest != 1 {
+            bail!("ShortestPath : the phi label is only supported for the single shortest path");
+        }
+        return phi_shortest_path(ifst, phi_label);
+    }
+
+    let queue = AutoQueue::new(ifst, None, &AnyTrFilter {})?;
+    shortest_path_with_queue(ifst, queue, config)
+}
+
+/// Create an FST containing the n-shortest paths in the input FST as
+/// `shortest_path_with_config`, visiting the states in the order of `queue` instead of the one
+/// chosen by `AutoQueue` when computing the distances from the initial state. The queue is
+/// cleared before being used. `ShortestPathConfig::phi_label` is not supported.
+///
+/// # Example
+/// ```
+/// # use rustfst::algorithms::queues::LifoQueue;
+/// # use rustfst::algorithms::{shortest_path_with_queue, ShortestPathConfig};
+/// # use rustfst::fst_impls::VectorFst;
+/// # use rustfst::fst_traits::{Fst, MutableFst};
+/// # use rustfst::semirings::{Semiring, TropicalWeight};
+/// # use rustfst::{FstPath, Tr};
+/// # use anyhow::Result;
+/// # fn main() -> Result<()> {
+/// let mut fst = VectorFst::<TropicalWeight>::new();
+/// fst.add_states(2);
+/// fst.set_start(0)?;
+/// fst.add_tr(0, Tr::new(1, 1, 1.0, 1))?;
+/// fst.add_tr(0, Tr::new(2, 2, 3.0, 1))?;
+/// fst.set_final(1, TropicalWeight::one())?;
+///
+/// let config = ShortestPathConfig::default();
+/// let best: VectorFst<_> = shortest_path_with_queue(&fst, LifoQueue::default(), config)?;
+/// let paths: Vec<_> = best.paths_iter().collect();
+/// assert_eq!(paths, vec![FstPath::new(vec![1], vec![1], TropicalWeight::new(1.0))]);
+/// # Ok(())
+/// # }
+/// ```
+pub fn shortest_path_with_queue<W, FI, FO, Q>(
+    ifst: &FI,
+    mut queue: Q,
+    config: ShortestPathConfig,
+) -> Result<FO>
+where
+    FI: ExpandedFst<W>,
+    FO: MutableFst<W>,
+    W: Semiring
+        + WeightQuantize
+        + Into<<W as Semiring>::ReverseWeight>
+        + From<<W as Semiring>::ReverseWeight>,
+    <W as Semiring>::ReverseWeight: WeightQuantize + WeaklyDivisibleSemiring,
+    Q: Queue,
 {
     let nshortest = config.nshortest;
     let unique = config.unique;
@@ -123,12 +205,16 @@ where
         return Ok(FO::new());
     }
 
+    if config.phi_label.is_some() {
+        bail!("shortest_path_with_queue : the phi label is not supported");
+    }
+
     if nshortest == 1 {
         let mut parent = vec![];
         let mut f_parent = None;
         let mut distance = vec![];
 
-        single_shortest_path(ifst, &mut distance, &mut f_parent, &mut parent)?;
+        single_shortest_path(ifst, &mut queue, &mut distance, &mut f_parent, &mut parent)?;
         let mut fst_res: FO = single_shortest_path_backtrace(ifst, &f_parent, &parent)?;
         fst_res.set_symts_from_fst(ifst);
         return Ok(fst_res);
@@ -139,7 +225,7 @@ where
     }
 
     let mut distance =
-        shortest_distance_with_config(ifst, false, ShortestDistanceConfig::new(delta))?;
+        shortest_distance_with_queue(ifst, queue, ShortestDistanceConfig::new(delta))?;
 
     let rfst: VectorFst<_> = reverse(ifst)?;
     let mut d = W::zero();
@@ -172,8 +258,9 @@ where
     Ok(fst_res)
 }
 
-fn single_shortest_path<W, F>(
+fn single_shortest_path<W, F, Q>(
     ifst: &F,
+    queue: &mut Q,
     distance: &mut Vec<W>,
     f_parent: &mut Option<StateId>,
     parent: &mut Vec<Option<(StateId, usize)>>,
@@ -181,6 +268,7 @@ fn single_shortest_path<W, F>(
 where
     W: Semiring,
     F: ExpandedFst<W>,
+    Q: Queue,
 {
     parent.clear();
     *f_parent = None;
@@ -189,7 +277,6 @@ where
         return Ok(());
     }
     let mut enqueued = vec![];
-    let mut queue = AutoQueue::new(ifst, None, &AnyTrFilter {})?;
     let source = unsafe { start.unsafe_unwrap() };
     let mut f_distance = W::zero();
     distance.clear();
@@ -209,6 +296,7 @@ where
 
     queue.enqueue(source);
 
+    let final_states = FinalStates::new(ifst);
     while !queue.is_empty() {
         // Safe because non empty
         let s = unsafe { queue.head().unsafe_unwrap() };
@@ -216,7 +304,7 @@ where
         enqueued[s as usize] = false;
         let sd = distance[s as usize].clone();
 
-        if let Some(final_weight) = unsafe { ifst.final_weight_unchecked(s) } {
+        if let Some(final_weight) = final_states.final_weight(s) {
             let plus = f_distance.plus(&sd.times(final_weight)?)?;
             if f_distance != plus {
                 f_distance = plus;
@@ -524,3 +612,37 @@ where
     );
     Ok(ofst)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::fst_impls::VectorFst;
+    use crate::fst_traits::Fst;
+    use crate::semirings::TropicalWeight;
+
+    #[test]
+    fn test_shortest_path_unique() -> Result<()> {
+        // The string 1 is read by two paths, both lighter than the path reading 2.
+        let mut fst = VectorFst::<TropicalWeight>::new();
+        fst.add_states(3);
+        fst.set_start(0)?;
+        fst.add_tr(0, Tr::new(1, 1, 1.0, 1))?;
+        fst.add_tr(0, Tr::new(1, 1, 2.0, 2))?;
+        fst.add_tr(0, Tr::new(2, 2, 3.0, 1))?;
+        fst.set_final(2, TropicalWeight::one())?;
+        fst.set_final(1, TropicalWeight::one())?;
+
+        let config = ShortestPathConfig::default().with_nshortest(2);
+        let nbest: VectorFst<_> = shortest_path_with_config(&fst, config)?;
+        let mut ilabels: Vec<_> = nbest.paths_iter().map(|p| p.ilabels).collect();
+        ilabels.sort();
+        assert_eq!(ilabels, vec![vec![1], vec![1]]);
+
+        let nbest: VectorFst<_> = shortest_path_with_config(&fst, config.with_unique(true))?;
+        let mut ilabels: Vec<_> = nbest.paths_iter().map(|p| p.ilabels).collect();
+        ilabels.sort();
+        assert_eq!(ilabels, vec![vec![1], vec![2]]);
+        Ok(())
+    }
+}
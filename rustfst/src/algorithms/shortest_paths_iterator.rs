@@ -0,0 +1,220 @@
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+
+use anyhow::Result;
+
+use crate::algorithms::shortest_distance;
+use crate::algorithms::shortest_path::natural_less;
+use crate::fst_traits::ExpandedFst;
+use crate::semirings::{Semiring, SemiringProperties};
+use crate::{TrPath, Trs};
+
+/// Partial path in the queue, complete once its final weight has been set.
+struct Candidate<W: Semiring> {
+    /// Weight of the best successful path extending this one.
+    priority: W,
+    /// Weight of the path, final weight included once complete.
+    weight: W,
+    complete: bool,
+    path: TrPath<W>,
+    /// Insertion number, the older candidates coming first among the ones of equal priority.
+    seq: usize,
+}
+
+impl<W: Semiring> PartialEq for Candidate<W> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<W: Semiring> Eq for Candidate<W> {}
+
+impl<W: Semiring> PartialOrd for Candidate<W> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<W: Semiring> Ord for Candidate<W> {
+    // The greatest candidate for the BinaryHeap is the one with the smallest priority.
+    fn cmp(&self, other: &Self) -> Ordering {
+        if natural_less(&self.priority, &other.priority).unwrap() {
+            Ordering::Greater
+        } else if natural_less(&other.priority, &self.priority).unwrap() {
+            Ordering::Less
+        } else {
+            other.seq.cmp(&self.seq)
+        }
+    }
+}
+
+/// Iterator on the successful paths of an FST by increasing weight w.r.t. the natural order
+/// of the semiring, computing the next path only when it is requested.
+///
+/// Unlike `shortest_path_with_config`, the number of paths doesn't have to be chosen in
+/// advance : the iteration can be stopped on any condition, e.g. a cost gap with the best
+/// path. The paths are distinct as sequences of transitions, hence an FST with several paths
+/// over the same strings yields these strings several times. Each `TrPath` keeps its states and
+/// the weights of its transitions.
+///
+/// The candidates are ordered by the weight of their prefix times the shortest distance to
+/// the final states, so that a path is yielded as soon as it is the best remaining one. The
+/// weights must have the path property.
+///
+/// # Example
+/// ```
+/// # use rustfst::algorithms::ShortestPathsIterator;
+/// # use rustfst::fst_impls::VectorFst;
+/// # use rustfst::fst_traits::MutableFst;
+/// # use rustfst::semirings::{Semiring, TropicalWeight};
+/// # use rustfst::Tr;
+/// # use anyhow::Result;
+/// # fn main() -> Result<()> {
+/// let mut fst = VectorFst::<TropicalWeight>::new();
+/// let s0 = fst.add_state();
+/// let s1 = fst.add_state();
+/// fst.set_start(s0)?;
+/// fst.set_final(s1, TropicalWeight::one())?;
+/// fst.add_tr(s0, Tr::new(1, 1, 3.0, s1))?;
+/// fst.add_tr(s0, Tr::new(2, 2, 1.0, s1))?;
+/// fst.add_tr(s1, Tr::new(3, 3, 1.5, s1))?;
+///
+/// // Stops at the first path costing more than 2 more than the best one.
+/// let mut paths = ShortestPathsIterator::new(&fst)?;
+/// let best = paths.next().unwrap().weight()?;
+/// let mut costs = vec![best.clone()];
+/// for path in paths {
+///     let weight = path.weight()?;
+///     if *weight.value() > *best.value() + 2.0 {
+///         break;
+///     }
+///     costs.push(weight);
+/// }
+/// assert_eq!(
+///     costs,
+///     vec![TropicalWeight::new(1.0), TropicalWeight::new(2.5), TropicalWeight::new(3.0)]
+/// );
+/// # Ok(())
+/// # }
+/// ```
+pub struct ShortestPathsIterator<'a, W: Semiring, F: ExpandedFst<W>> {
+    fst: &'a F,
+    /// Shortest distance from each state to the final states.
+    rdistance: Vec<W>,
+    heap: BinaryHeap<Candidate<W>>,
+    num_candidates: usize,
+}
+
+impl<'a, W: Semiring, F: ExpandedFst<W>> ShortestPathsIterator<'a, W, F> {
+    pub fn new(fst: &'a F) -> Result<Self> {
+        W::properties().check(
+            SemiringProperties::PATH | SemiringProperties::SEMIRING,
+            "ShortestPathsIterator",
+        )?;
+        let mut it = Self {
+            fst,
+            rdistance: shortest_distance(fst, true)?,
+            heap: BinaryHeap::new(),
+            num_candidates: 0,
+        };
+        if let Some(start) = fst.start() {
+            it.push(TrPath::new(start), W::one(), false);
+        }
+        Ok(it)
+    }
+
+    fn push(&mut self, path: TrPath<W>, weight: W, complete: bool) {
+        let priority = if complete {
+            weight.clone()
+        } else {
+            match self.rdistance.get(path.last_state() as usize) {
+                Some(r) if !r.is_zero() => weight.times(r).unwrap(),
+                // No successful path goes through this state.
+                _ => return,
+            }
+        };
+        self.heap.push(Candidate {
+            priority,
+            weight,
+            complete,
+            path,
+            seq: self.num_candidates,
+        });
+        self.num_candidates += 1;
+    }
+}
+
+impl<'a, W: Semiring, F: ExpandedFst<W>> Iterator for ShortestPathsIterator<'a, W, F> {
+    type Item = TrPath<W>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some(candidate) = self.heap.pop() {
+            if candidate.complete {
+                return Some(candidate.path);
+            }
+            let state = candidate.path.last_state();
+            let fst = self.fst;
+            for tr in unsafe { fst.get_trs_unchecked(state).trs() } {
+                let weight = candidate
+                    .weight
+                    .times(&tr.weight)
+                    .expect("Error times in ShortestPathsIterator");
+                let mut path = candidate.path.clone();
+                path.push(tr.clone());
+                self.push(path, weight, false);
+            }
+            if let Some(final_weight) = unsafe { fst.final_weight_unchecked(state) } {
+                let weight = candidate
+                    .weight
+                    .times(&final_weight)
+                    .expect("Error times in ShortestPathsIterator");
+                let mut path = candidate.path;
+                path.final_weight = final_weight;
+                self.push(path, weight, true);
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::algorithms::{shortest_path_with_config, ShortestPathConfig};
+    use crate::fst_impls::VectorFst;
+    use crate::fst_traits::{Fst, MutableFst};
+    use crate::semirings::TropicalWeight;
+    use crate::Tr;
+
+    #[test]
+    fn test_shortest_paths_iterator() -> Result<()> {
+        let mut fst = VectorFst::<TropicalWeight>::new();
+        fst.add_states(4);
+        fst.set_start(0)?;
+        fst.add_tr(0, Tr::new(1, 1, 1.0, 1))?;
+        fst.add_tr(0, Tr::new(2, 2, 4.0, 2))?;
+        fst.add_tr(1, Tr::new(3, 3, 2.0, 2))?;
+        fst.add_tr(1, Tr::new(4, 4, 6.0, 3))?;
+        fst.add_tr(2, Tr::new(5, 5, 0.5, 3))?;
+        fst.set_final(2, 4.0)?;
+        fst.set_final(3, 0.0)?;
+
+        let paths: Vec<_> = ShortestPathsIterator::new(&fst)?.collect();
+        assert_eq!(paths.len(), 5);
+        let weights = paths
+            .iter()
+            .map(|p| p.weight())
+            .collect::<Result<Vec<_>>>()?;
+        for pair in weights.windows(2) {
+            assert!(!natural_less(&pair[1], &pair[0])?);
+        }
+
+        let nbest: VectorFst<TropicalWeight> =
+            shortest_path_with_config(&fst, ShortestPathConfig::default().with_nshortest(3))?;
+        let mut expected: Vec<_> = nbest.paths_iter().map(|p| p.weight).collect();
+        expected.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        assert_eq!(&weights[..3], expected.as_slice());
+        Ok(())
+    }
+}
@@ -0,0 +1,254 @@
+use std::collections::{BTreeMap, VecDeque};
+
+use anyhow::Result;
+
+use crate::algorithms::queues::natural_less;
+use crate::fst_traits::Fst;
+use crate::semirings::Semiring;
+use crate::{Label, StateId, Trs, EPS_LABEL, KSHORTESTDELTA};
+
+/// Active hypotheses: a state of the FST along with the output labels produced since the last
+/// commit, mapped to the ⊕-sum of the weights of the paths leading to it.
+type Hypotheses<W> = BTreeMap<(StateId, Vec<Label>), W>;
+
+/// Applies a transducer to an unbounded input stream, chunk by chunk.
+///
+/// The applier maintains the set of active states of the FST reached by the input consumed
+/// so far, each one along with its pending output. After each chunk, the longest common
+/// prefix of the pending outputs is committed: it is returned to the caller and will never
+/// be revised, whatever the rest of the input. Memory usage is therefore bounded for
+/// transducers with a bounded output delay (e.g. sequential ones).
+///
+/// # Example
+/// ```
+/// # #[macro_use] extern crate rustfst;
+/// # use rustfst::utils::{acceptor, transducer};
+/// # use rustfst::algorithms::StreamingApplier;
+/// # use rustfst::algorithms::closure::{closure, ClosureType};
+/// # use rustfst::fst_impls::VectorFst;
+/// # use rustfst::semirings::{Semiring, TropicalWeight};
+/// # use anyhow::Result;
+/// # fn main() -> Result<()> {
+/// let mut fst : VectorFst<TropicalWeight> = fst![1, 2 => 3, 4];
+/// closure(&mut fst, ClosureType::ClosureStar);
+///
+/// let mut applier = StreamingApplier::new(&fst)?;
+/// assert_eq!(applier.feed(&[1, 2, 1])?, vec![3, 4, 3]);
+/// assert_eq!(applier.feed(&[2])?, vec![4]);
+/// let (remaining, _) = applier.finish()?.unwrap();
+/// assert!(remaining.is_empty());
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct StreamingApplier<'a, W: Semiring, F: Fst<W>> {
+    fst: &'a F,
+    hyps: Hypotheses<W>,
+    max_active: usize,
+    delta: f32,
+}
+
+impl<'a, W: Semiring, F: Fst<W>> StreamingApplier<'a, W, F> {
+    /// Default maximum number of active hypotheses.
+    pub const DEFAULT_MAX_ACTIVE: usize = 100_000;
+
+    pub fn new(fst: &'a F) -> Result<Self> {
+        Self::new_with_max_active(fst, Self::DEFAULT_MAX_ACTIVE)
+    }
+
+    /// Creates an applier that fails when more than `max_active` hypotheses are alive at the
+    /// same time, which happens with transducers whose output delay is not bounded.
+    pub fn new_with_max_active(fst: &'a F, max_active: usize) -> Result<Self> {
+        let mut applier = Self {
+            fst,
+            hyps: Hypotheses::new(),
+            max_active,
+            delta: KSHORTESTDELTA,
+        };
+        if let Some(start) = fst.start() {
+            applier.hyps.insert((start, vec![]), W::one());
+            applier.epsilon_closure()?;
+        }
+        Ok(applier)
+    }
+
+    /// Returns true if no path of the FST matches the input consumed so far.
+    pub fn is_dead(&self) -> bool {
+        self.hyps.is_empty()
+    }
+
+    /// Number of hypotheses currently alive.
+    pub fn num_active(&self) -> usize {
+        self.hyps.len()
+    }
+
+    /// Consumes a chunk of input labels and returns the output labels that are now unambiguous.
+    pub fn feed(&mut self, ilabels: &[Label]) -> Result<Vec<Label>> {
+        for label in ilabels {
+            let mut next = Hypotheses::new();
+            for ((state, output), weight) in self.hyps.iter() {
+                for tr in self.fst.get_trs(*state)?.trs() {
+                    if tr.ilabel != *label {
+                        continue;
+                    }
+                    let w = weight.times(&tr.weight)?;
+                    if w.is_zero() {
+                        continue;
+                    }
+                    let mut new_output = output.clone();
+                    if tr.olabel != EPS_LABEL {
+                        new_output.push(tr.olabel);
+                    }
+                    next.entry((tr.nextstate, new_output))
+                        .or_insert_with(W::zero)
+                        .plus_assign(w)?;
+                }
+            }
+            self.hyps = next;
+            self.epsilon_closure()?;
+        }
+        Ok(self.commit())
+    }
+
+    /// Ends the stream: returns the pending output of the best successful hypothesis
+    /// w.r.t. the natural order of the semiring, along with its weight including the final
+    /// weight. Returns `None` if the consumed input isn't accepted by the FST.
+    pub fn finish(self) -> Result<Option<(Vec<Label>, W)>> {
+        let mut best: Option<(Vec<Label>, W)> = None;
+        for ((state, output), weight) in self.hyps.into_iter() {
+            if let Some(final_weight) = self.fst.final_weight(state)? {
+                let w = weight.times(final_weight)?;
+                let better = match &best {
+                    Some((_, best_weight)) => natural_less(&w, best_weight)?,
+                    None => true,
+                };
+                if better {
+                    best = Some((output, w));
+                }
+            }
+        }
+        Ok(best)
+    }
+
+    fn commit(&mut self) -> Vec<Label> {
+        let mut iter = self.hyps.keys();
+        let mut prefix_len = match iter.next() {
+            Some((_, output)) => output.len(),
+            None => return vec![],
+        };
+        let first = self.hyps.keys().next().unwrap().1.clone();
+        for (_, output) in iter {
+            prefix_len = first
+                .iter()
+                .zip(output.iter())
+                .take(prefix_len)
+                .take_while(|(a, b)| a == b)
+                .count();
+        }
+        if prefix_len == 0 {
+            return vec![];
+        }
+        let hyps = std::mem::take(&mut self.hyps);
+        self.hyps = hyps
+            .into_iter()
+            .map(|((state, output), weight)| ((state, output[prefix_len..].to_vec()), weight))
+            .collect();
+        first[..prefix_len].to_vec()
+    }
+
+    fn epsilon_closure(&mut self) -> Result<()> {
+        let mut residuals = self.hyps.clone();
+        let mut queue: VecDeque<(StateId, Vec<Label>)> = self.hyps.keys().cloned().collect();
+        while let Some(key) = queue.pop_front() {
+            let residual = match residuals.insert(key.clone(), W::zero()) {
+                Some(r) if !r.is_zero() => r,
+                _ => continue,
+            };
+            for tr in self.fst.get_trs(key.0)?.trs() {
+                if tr.ilabel != EPS_LABEL {
+                    continue;
+                }
+                let weight = residual.times(&tr.weight)?;
+                if weight.is_zero() {
+                    continue;
+                }
+                let mut output = key.1.clone();
+                if tr.olabel != EPS_LABEL {
+                    output.push(tr.olabel);
+                }
+                let next_key = (tr.nextstate, output);
+                let distance = self.hyps.entry(next_key.clone()).or_insert_with(W::zero);
+                let new_distance = distance.plus(&weight)?;
+                if !distance.approx_equal(&new_distance, self.delta) {
+                    *distance = new_distance;
+                    let r = residuals.entry(next_key.clone()).or_insert_with(W::zero);
+                    let was_pending = !r.is_zero();
+                    r.plus_assign(&weight)?;
+                    if !was_pending {
+                        queue.push_back(next_key);
+                    }
+                }
+                if self.hyps.len() > self.max_active {
+                    bail!(
+                        "StreamingApplier : more than {} active hypotheses, the output delay of the FST is likely unbounded",
+                        self.max_active
+                    );
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::fst_impls::VectorFst;
+    use crate::fst_traits::MutableFst;
+    use crate::semirings::TropicalWeight;
+    use crate::Tr;
+
+    #[test]
+    fn test_streaming_applier_delayed_output() -> Result<()> {
+        // 1 1 -> 5 and 1 2 -> 6: the output is only known after the second label.
+        let mut fst = VectorFst::<TropicalWeight>::new();
+        let s0 = fst.add_state();
+        let s1 = fst.add_state();
+        let s2 = fst.add_state();
+        let s3 = fst.add_state();
+        fst.set_start(s0)?;
+        fst.add_tr(s0, Tr::new(1, 5, 1.0, s1))?;
+        fst.add_tr(s0, Tr::new(1, 6, 2.0, s2))?;
+        fst.add_tr(s1, Tr::new(1, 7, 0.0, s3))?;
+        fst.add_tr(s2, Tr::new(2, 7, 0.0, s3))?;
+        fst.add_tr(s3, Tr::new(EPS_LABEL, EPS_LABEL, 0.0, s0))?;
+        fst.set_final(s3, TropicalWeight::one())?;
+
+        let mut applier = StreamingApplier::new(&fst)?;
+        assert_eq!(applier.feed(&[1])?, Vec::<Label>::new());
+        assert_eq!(applier.num_active(), 2);
+        assert_eq!(applier.feed(&[2])?, vec![6, 7]);
+        assert_eq!(applier.feed(&[1, 1])?, vec![5, 7]);
+        let (remaining, weight) = applier.finish()?.unwrap();
+        assert!(remaining.is_empty());
+        assert_eq!(weight, TropicalWeight::new(3.0));
+        Ok(())
+    }
+
+    #[test]
+    fn test_streaming_applier_rejects() -> Result<()> {
+        let mut fst = VectorFst::<TropicalWeight>::new();
+        let s0 = fst.add_state();
+        let s1 = fst.add_state();
+        fst.set_start(s0)?;
+        fst.add_tr(s0, Tr::new(1, 2, 1.0, s1))?;
+        fst.set_final(s1, TropicalWeight::one())?;
+
+        let mut applier = StreamingApplier::new(&fst)?;
+        assert_eq!(applier.feed(&[3])?, Vec::<Label>::new());
+        assert!(applier.is_dead());
+        assert!(applier.finish()?.is_none());
+        Ok(())
+    }
+}
@@ -0,0 +1,155 @@
+use std::borrow::Borrow;
+use std::marker::PhantomData;
+use std::sync::Arc;
+
+use anyhow::Result;
+
+use crate::algorithms::lazy::{FstOp, StateTable};
+use crate::fst_properties::mutable_properties::synchronization_properties;
+use crate::fst_properties::FstProperties;
+use crate::fst_traits::Fst;
+use crate::semirings::Semiring;
+use crate::{Label, StateId, Tr, Trs, TrsVec, EPS_LABEL};
+
+/// State of the synchronized FST : a state of the input FST, or none once its final weight has
+/// been read, with the input and output labels read but not emitted yet.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+struct SynchronizeElement {
+    state: Option<StateId>,
+    istring: Vec<Label>,
+    ostring: Vec<Label>,
+}
+
+/// First label of `string` followed by `label`.
+fn car(string: &[Label], label: Label) -> Label {
+    string.first().cloned().unwrap_or(label)
+}
+
+/// Labels of `string` followed by `label` but the first one.
+fn cdr(string: &[Label], label: Label) -> Vec<Label> {
+    match string.split_first() {
+        Some((_, tail)) => concat(tail, label),
+        None => vec![],
+    }
+}
+
+/// Labels of `string` followed by `label`.
+fn concat(string: &[Label], label: Label) -> Vec<Label> {
+    let mut res = string.to_vec();
+    if label != EPS_LABEL {
+        res.push(label);
+    }
+    res
+}
+
+pub struct SynchronizeOp<W: Semiring, F: Fst<W>, B: Borrow<F>> {
+    fst: B,
+    state_table: StateTable<SynchronizeElement>,
+    properties: FstProperties,
+    w: PhantomData<W>,
+    f: PhantomData<F>,
+}
+
+impl<W: Semiring, F: Fst<W>, B: Borrow<F>> std::fmt::Debug for SynchronizeOp<W, F, B> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "SynchronizeOp {{ state_table : {:?} }}",
+            self.state_table
+        )
+    }
+}
+
+impl<W: Semiring, F: Fst<W>, B: Borrow<F>> SynchronizeOp<W, F, B> {
+    pub fn new(fst: B) -> Self {
+        let properties = synchronization_properties(fst.borrow().properties());
+        Self {
+            fst,
+            state_table: StateTable::new(),
+            properties,
+            w: PhantomData,
+            f: PhantomData,
+        }
+    }
+}
+
+impl<W: Semiring, F: Fst<W>, B: Borrow<F>> FstOp<W> for SynchronizeOp<W, F, B> {
+    fn compute_start(&self) -> Result<Option<StateId>> {
+        Ok(self.fst.borrow().start().map(|start| {
+            self.state_table.find_id(SynchronizeElement {
+                state: Some(start),
+                istring: vec![],
+                ostring: vec![],
+            })
+        }))
+    }
+
+    fn compute_trs(&self, id: StateId) -> Result<TrsVec<W>> {
+        let elt = self.state_table.find_tuple(id);
+        let mut trs = vec![];
+        if let Some(state) = elt.state {
+            for tr in self.fst.borrow().get_trs(state)?.trs() {
+                let istring_empty = elt.istring.is_empty() && tr.ilabel == EPS_LABEL;
+                let ostring_empty = elt.ostring.is_empty() && tr.olabel == EPS_LABEL;
+                let (ilabel, olabel, istring, ostring) = if !istring_empty && !ostring_empty {
+                    // A label can be emitted on both sides.
+                    (
+                        car(&elt.istring, tr.ilabel),
+                        car(&elt.ostring, tr.olabel),
+                        cdr(&elt.istring, tr.ilabel),
+                        cdr(&elt.ostring, tr.olabel),
+                    )
+                } else {
+                    (
+                        EPS_LABEL,
+                        EPS_LABEL,
+                        concat(&elt.istring, tr.ilabel),
+                        concat(&elt.ostring, tr.olabel),
+                    )
+                };
+                let nextstate = self.state_table.find_id(SynchronizeElement {
+                    state: Some(tr.nextstate),
+                    istring,
+                    ostring,
+                });
+                trs.push(Tr::new(ilabel, olabel, tr.weight.clone(), nextstate));
+            }
+        }
+        // The labels left at a final state are emitted on a path ending in a new final state.
+        let final_weight = match elt.state {
+            Some(state) => self.fst.borrow().final_weight(state)?,
+            None => Some(W::one()),
+        };
+        if let Some(final_weight) = final_weight {
+            if !elt.istring.is_empty() || !elt.ostring.is_empty() {
+                let nextstate = self.state_table.find_id(SynchronizeElement {
+                    state: None,
+                    istring: cdr(&elt.istring, EPS_LABEL),
+                    ostring: cdr(&elt.ostring, EPS_LABEL),
+                });
+                trs.push(Tr::new(
+                    car(&elt.istring, EPS_LABEL),
+                    car(&elt.ostring, EPS_LABEL),
+                    final_weight,
+                    nextstate,
+                ));
+            }
+        }
+        Ok(TrsVec(Arc::new(trs)))
+    }
+
+    fn compute_final_weight(&self, id: StateId) -> Result<Option<W>> {
+        let elt = self.state_table.find_tuple(id);
+        if !elt.istring.is_empty() || !elt.ostring.is_empty() {
+            return Ok(None);
+        }
+        match elt.state {
+            Some(state) => self.fst.borrow().final_weight(state),
+            None => Ok(Some(W::one())),
+        }
+    }
+
+    fn properties(&self) -> FstProperties {
+        self.properties
+    }
+}
@@ -0,0 +1,101 @@
+use anyhow::Result;
+
+use crate::algorithms::synchronize::SynchronizeFst;
+use crate::fst_traits::{AllocableFst, Fst, MutableFst};
+use crate::semirings::Semiring;
+
+/// Synchronizes a transducer : the result is an equivalent transducer whose paths emit their
+/// input and output labels together, the epsilons being pushed to the end of the paths. Each
+/// state remembers the labels read on one side and not yet emitted, so that the transducer must
+/// have a bounded delay, i.e. the difference between the lengths of the input and the output
+/// labels of the paths reaching a state must be bounded, otherwise the algorithm doesn't
+/// terminate. This is the case of the acyclic transducers and of those whose cycles have as
+/// many non-epsilon input labels as output labels.
+///
+/// The synchronized transducer can then be composed with transducers expecting a label on
+/// each side, or determinized as an acceptor on the pairs of labels.
+///
+/// # Example
+/// ```
+/// # use rustfst::algorithms::synchronize::synchronize;
+/// # use rustfst::fst_impls::VectorFst;
+/// # use rustfst::fst_traits::{Fst, MutableFst};
+/// # use rustfst::semirings::{Semiring, TropicalWeight};
+/// # use rustfst::{FstPath, Tr, EPS_LABEL};
+/// # use anyhow::Result;
+/// # fn main() -> Result<()> {
+/// // 1 2 is rewritten as 3, the output being delayed by one label.
+/// let mut fst = VectorFst::<TropicalWeight>::new();
+/// fst.add_states(3);
+/// fst.set_start(0)?;
+/// fst.add_tr(0, Tr::new(1, EPS_LABEL, 1.0, 1))?;
+/// fst.add_tr(1, Tr::new(2, 3, 1.0, 2))?;
+/// fst.set_final(2, TropicalWeight::one())?;
+///
+/// let sync: VectorFst<_> = synchronize(&fst)?;
+/// let paths: Vec<_> = sync.paths_iter().collect();
+/// assert_eq!(paths, vec![FstPath::new(vec![1, 2], vec![3], TropicalWeight::new(2.0))]);
+/// # Ok(())
+/// # }
+/// ```
+pub fn synchronize<W, F1, F2>(fst: &F1) -> Result<F2>
+where
+    W: Semiring,
+    F1: Fst<W>,
+    F2: MutableFst<W> + AllocableFst<W>,
+{
+    let fst = SynchronizeFst::<W, F1, _>::new(fst)?;
+    fst.compute()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::fst_impls::VectorFst;
+    use crate::fst_traits::{CoreFst, StateIterator};
+    use crate::semirings::TropicalWeight;
+    use crate::{FstPath, Tr, Trs, EPS_LABEL};
+
+    #[test]
+    fn test_synchronize() -> Result<()> {
+        // 1 2 -> 3 with the output delayed, and 4 -> 5 6 with the input delayed.
+        let mut fst = VectorFst::<TropicalWeight>::new();
+        fst.add_states(5);
+        fst.set_start(0)?;
+        fst.add_tr(0, Tr::new(1, EPS_LABEL, 1.0, 1))?;
+        fst.add_tr(1, Tr::new(2, 3, 1.0, 2))?;
+        fst.add_tr(0, Tr::new(EPS_LABEL, 5, 0.5, 3))?;
+        fst.add_tr(3, Tr::new(4, 6, 0.5, 4))?;
+        fst.set_final(2, TropicalWeight::one())?;
+        fst.set_final(4, TropicalWeight::new(2.0))?;
+
+        let sync: VectorFst<TropicalWeight> = synchronize(&fst)?;
+        let mut paths: Vec<_> = sync.paths_iter().collect();
+        paths.sort_by(|a, b| a.ilabels.cmp(&b.ilabels));
+        assert_eq!(
+            paths,
+            vec![
+                FstPath::new(vec![1, 2], vec![3], TropicalWeight::new(2.0)),
+                FstPath::new(vec![4], vec![5, 6], TropicalWeight::new(3.0)),
+            ]
+        );
+
+        // The labels are emitted together, the epsilons coming last.
+        for state in sync.states_iter() {
+            for tr in sync.get_trs(state)?.trs() {
+                if tr.ilabel == EPS_LABEL && tr.olabel != EPS_LABEL {
+                    assert!(sync
+                        .get_trs(tr.nextstate)?
+                        .trs()
+                        .iter()
+                        .all(|tr| tr.ilabel == EPS_LABEL));
+                }
+            }
+        }
+        let trs = sync.get_trs(sync.start().unwrap())?;
+        let tr = &trs.trs()[0];
+        assert_eq!((tr.ilabel, tr.olabel), (EPS_LABEL, EPS_LABEL));
+        Ok(())
+    }
+}
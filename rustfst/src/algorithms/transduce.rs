@@ -0,0 +1,137 @@
+use std::cmp::Ordering;
+
+use anyhow::Result;
+
+use crate::algorithms::compose::compose;
+use crate::algorithms::queues::natural_less;
+use crate::algorithms::rm_epsilon::rm_epsilon;
+use crate::algorithms::tr_compares::ILabelCompare;
+use crate::algorithms::{
+    fst_convert_from_ref, project, shortest_path_with_config, tr_sort, ProjectType,
+    ShortestPathConfig,
+};
+use crate::fst_impls::VectorFst;
+use crate::fst_properties::FstProperties;
+use crate::fst_traits::{CoreFst, ExpandedFst, Fst};
+use crate::semirings::{Semiring, WeaklyDivisibleSemiring, WeightQuantize};
+use crate::utils::acceptor;
+use crate::Label;
+
+/// Applies the transducer to a single input sequence and returns the `nshortest` best
+/// distinct output sequences along with their weights, best first.
+///
+/// Input and output epsilons are handled transparently: the input sequence doesn't contain
+/// epsilons and the returned output sequences are stripped of them. If the FST is not sorted
+/// on its input labels, a sorted copy is created before the composition.
+///
+/// # Example
+/// ```
+/// # use rustfst::algorithms::transduce;
+/// # use rustfst::fst_impls::VectorFst;
+/// # use rustfst::fst_traits::MutableFst;
+/// # use rustfst::semirings::{Semiring, TropicalWeight};
+/// # use rustfst::Tr;
+/// # use anyhow::Result;
+/// # fn main() -> Result<()> {
+/// let mut fst = VectorFst::<TropicalWeight>::new();
+/// let s0 = fst.add_state();
+/// let s1 = fst.add_state();
+/// fst.set_start(s0)?;
+/// fst.add_tr(s0, Tr::new(1, 10, 1.0, s1))?;
+/// fst.add_tr(s0, Tr::new(1, 20, 2.0, s1))?;
+/// fst.set_final(s1, TropicalWeight::one())?;
+///
+/// let outputs = transduce(&fst, &[1], 2)?;
+/// assert_eq!(
+///     outputs,
+///     vec![
+///         (vec![10], TropicalWeight::new(1.0)),
+///         (vec![20], TropicalWeight::new(2.0))
+///     ]
+/// );
+/// # Ok(())
+/// # }
+/// ```
+pub fn transduce<W, F>(fst: &F, ilabels: &[Label], nshortest: usize) -> Result<Vec<(Vec<Label>, W)>>
+where
+    F: ExpandedFst<W>,
+    W: Semiring
+        + WeightQuantize
+        + Into<<W as Semiring>::ReverseWeight>
+        + From<<W as Semiring>::ReverseWeight>,
+    <W as Semiring>::ReverseWeight: WeightQuantize + WeaklyDivisibleSemiring,
+{
+    let input: VectorFst<W> = acceptor(ilabels, W::one());
+    let mut lattice: VectorFst<W> = if fst.properties().contains(FstProperties::I_LABEL_SORTED) {
+        compose::<W, VectorFst<W>, F, _, _, _>(&input, fst)?
+    } else {
+        let mut sorted: VectorFst<W> = fst_convert_from_ref(fst);
+        tr_sort(&mut sorted, ILabelCompare {});
+        compose::<W, VectorFst<W>, VectorFst<W>, _, _, _>(&input, &sorted)?
+    };
+    project(&mut lattice, ProjectType::ProjectOutput);
+    rm_epsilon(&mut lattice)?;
+    if lattice.start().is_none() {
+        return Ok(vec![]);
+    }
+
+    let config = ShortestPathConfig::default()
+        .with_nshortest(nshortest)
+        .with_unique(true);
+    let best: VectorFst<W> = shortest_path_with_config(&lattice, config)?;
+
+    let mut outputs: Vec<_> = best
+        .paths_iter()
+        .map(|path| (path.olabels, path.weight))
+        .collect();
+    outputs.sort_by(|(_, w1), (_, w2)| {
+        if natural_less(w1, w2).unwrap_or(false) {
+            Ordering::Less
+        } else if natural_less(w2, w1).unwrap_or(false) {
+            Ordering::Greater
+        } else {
+            Ordering::Equal
+        }
+    });
+    Ok(outputs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::fst_traits::MutableFst;
+    use crate::semirings::TropicalWeight;
+    use crate::{Tr, EPS_LABEL};
+
+    #[test]
+    fn test_transduce_with_epsilons() -> Result<()> {
+        // 1:<eps> <eps>:5 2:6 | 1:7 2:<eps>
+        let mut fst = VectorFst::<TropicalWeight>::new();
+        let s0 = fst.add_state();
+        let s1 = fst.add_state();
+        let s2 = fst.add_state();
+        let s3 = fst.add_state();
+        fst.set_start(s0)?;
+        fst.add_tr(s0, Tr::new(1, EPS_LABEL, 0.5, s1))?;
+        fst.add_tr(s1, Tr::new(EPS_LABEL, 5, 0.5, s2))?;
+        fst.add_tr(s2, Tr::new(2, 6, 0.5, s3))?;
+        fst.add_tr(s0, Tr::new(1, 7, 3.0, s2))?;
+        fst.add_tr(s2, Tr::new(2, EPS_LABEL, 1.0, s3))?;
+        fst.set_final(s3, TropicalWeight::one())?;
+
+        let outputs = transduce(&fst, &[1, 2], 10)?;
+        assert_eq!(
+            outputs,
+            vec![
+                (vec![5, 6], TropicalWeight::new(1.5)),
+                (vec![5], TropicalWeight::new(2.0)),
+                (vec![7, 6], TropicalWeight::new(3.5)),
+                (vec![7], TropicalWeight::new(4.0)),
+            ]
+        );
+
+        assert!(transduce(&fst, &[2], 10)?.is_empty());
+        Ok(())
+    }
+}
@@ -0,0 +1,333 @@
+use std::collections::BTreeMap;
+
+use anyhow::Result;
+
+use crate::fst_traits::ExpandedFst;
+use crate::semirings::Semiring;
+use crate::{Label, StateId, Tr, Trs};
+
+/// Sparse square matrix of the weights of the transitions of an FST, the entry `(i, j)` being
+/// the ⊕-sum of the weights of the transitions from the state `i` to the state `j`. The rows
+/// are stored in flat arrays (compressed sparse rows), the columns of a row being sorted.
+///
+/// The vectors are indexed by the states : `vec_mul` propagates weights forward along the
+/// transitions, e.g. the distribution over the states of a Markov chain in the
+/// `ProbabilityWeight` semiring, and `mul_vec` propagates them backward.
+///
+/// # Example
+/// ```
+/// # use rustfst::algorithms::TransitionMatrix;
+/// # use rustfst::fst_impls::VectorFst;
+/// # use rustfst::fst_traits::MutableFst;
+/// # use rustfst::semirings::{ProbabilityWeight, Semiring};
+/// # use rustfst::Tr;
+/// # use anyhow::Result;
+/// # fn main() -> Result<()> {
+/// // Stationary distribution of a Markov chain.
+/// let mut fst = VectorFst::<ProbabilityWeight>::new();
+/// fst.add_states(2);
+/// fst.add_tr(0, Tr::new(1, 1, 0.5, 0))?;
+/// fst.add_tr(0, Tr::new(2, 2, 0.5, 1))?;
+/// fst.add_tr(1, Tr::new(1, 1, 1.0, 0))?;
+///
+/// let matrix = TransitionMatrix::new(&fst)?;
+/// let mut distribution = vec![ProbabilityWeight::one(), ProbabilityWeight::zero()];
+/// for _ in 0..50 {
+///     distribution = matrix.vec_mul(&distribution)?;
+/// }
+/// assert!((*distribution[0].value() - 2.0 / 3.0).abs() < 1e-4);
+/// assert!((*distribution[1].value() - 1.0 / 3.0).abs() < 1e-4);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct TransitionMatrix<W> {
+    /// Position of the entries of each row in `columns` and `weights`.
+    offsets: Vec<usize>,
+    columns: Vec<StateId>,
+    weights: Vec<W>,
+}
+
+impl<W: Semiring> TransitionMatrix<W> {
+    /// Matrix of all the transitions of `fst`, whatever their labels.
+    pub fn new<F: ExpandedFst<W>>(fst: &F) -> Result<Self> {
+        Self::from_trs(fst, |_| true)
+    }
+
+    /// Matrix of the transitions of `fst` with the input label `label`.
+    pub fn new_with_label<F: ExpandedFst<W>>(fst: &F, label: Label) -> Result<Self> {
+        Self::from_trs(fst, |tr| tr.ilabel == label)
+    }
+
+    fn from_trs<F: ExpandedFst<W>, P: Fn(&Tr<W>) -> bool>(fst: &F, predicate: P) -> Result<Self> {
+        let mut rows = Vec::with_capacity(fst.num_states());
+        for state in fst.states_range() {
+            let mut row = BTreeMap::new();
+            for tr in fst.get_trs(state)?.trs().iter().filter(|tr| predicate(tr)) {
+                row.entry(tr.nextstate)
+                    .or_insert_with(W::zero)
+                    .plus_assign(&tr.weight)?;
+            }
+            rows.push(row);
+        }
+        Ok(Self::from_rows(rows))
+    }
+
+    fn from_rows(rows: Vec<BTreeMap<StateId, W>>) -> Self {
+        let mut offsets = Vec::with_capacity(rows.len() + 1);
+        let mut columns = vec![];
+        let mut weights = vec![];
+        offsets.push(0);
+        for row in rows {
+            for (column, weight) in row {
+                if !weight.is_zero() {
+                    columns.push(column);
+                    weights.push(weight);
+                }
+            }
+            offsets.push(columns.len());
+        }
+        Self {
+            offsets,
+            columns,
+            weights,
+        }
+    }
+
+    /// Identity matrix of size `num_states`.
+    pub fn identity(num_states: usize) -> Self {
+        Self {
+            offsets: (0..=num_states).collect(),
+            columns: (0..num_states).map(|s| s as StateId).collect(),
+            weights: vec![W::one(); num_states],
+        }
+    }
+
+    /// Number of rows, i.e. of states.
+    pub fn num_states(&self) -> usize {
+        self.offsets.len() - 1
+    }
+
+    /// Number of entries different from zero.
+    pub fn num_entries(&self) -> usize {
+        self.columns.len()
+    }
+
+    /// Columns and weights of the entries of the row `state` different from zero.
+    pub fn row(&self, state: StateId) -> impl Iterator<Item = (StateId, &W)> {
+        let range = self.offsets[state as usize]..self.offsets[state as usize + 1];
+        self.columns[range.clone()]
+            .iter()
+            .cloned()
+            .zip(self.weights[range].iter())
+    }
+
+    /// Entry `(row, column)`, zero if there is no transition from `row` to `column`.
+    pub fn get(&self, row: StateId, column: StateId) -> W {
+        self.row(row)
+            .find(|(c, _)| *c == column)
+            .map_or_else(W::zero, |(_, w)| w.clone())
+    }
+
+    fn check_size(&self, size: usize) -> Result<()> {
+        if size != self.num_states() {
+            bail!(
+                "TransitionMatrix : size mismatch, expected {} but got {}",
+                self.num_states(),
+                size
+            );
+        }
+        Ok(())
+    }
+
+    /// Product of the row vector `vector` by the matrix : the entry `j` of the result is the
+    /// ⊕-sum over `i` of `vector[i] ⊗ m[i][j]`.
+    pub fn vec_mul(&self, vector: &[W]) -> Result<Vec<W>> {
+        self.check_size(vector.len())?;
+        let mut res = vec![W::zero(); self.num_states()];
+        for (state, v) in vector.iter().enumerate() {
+            if v.is_zero() {
+                continue;
+            }
+            for (column, weight) in self.row(state as StateId) {
+                res[column as usize].plus_assign(v.times(weight)?)?;
+            }
+        }
+        Ok(res)
+    }
+
+    /// Product of the matrix by the column vector `vector` : the entry `i` of the result is
+    /// the ⊕-sum over `j` of `m[i][j] ⊗ vector[j]`.
+    pub fn mul_vec(&self, vector: &[W]) -> Result<Vec<W>> {
+        self.check_size(vector.len())?;
+        let mut res = Vec::with_capacity(self.num_states());
+        for state in 0..self.num_states() {
+            let mut sum = W::zero();
+            for (column, weight) in self.row(state as StateId) {
+                sum.plus_assign(weight.times(&vector[column as usize])?)?;
+            }
+            res.push(sum);
+        }
+        Ok(res)
+    }
+
+    /// Product of the matrix by `other`.
+    pub fn mul(&self, other: &Self) -> Result<Self> {
+        self.check_size(other.num_states())?;
+        let mut rows = Vec::with_capacity(self.num_states());
+        for state in 0..self.num_states() {
+            let mut row = BTreeMap::new();
+            for (k, weight) in self.row(state as StateId) {
+                for (column, other_weight) in other.row(k) {
+                    row.entry(column)
+                        .or_insert_with(W::zero)
+                        .plus_assign(weight.times(other_weight)?)?;
+                }
+            }
+            rows.push(row);
+        }
+        Ok(Self::from_rows(rows))
+    }
+
+    /// Matrix raised to the power `n` by repeated squaring, the entry `(i, j)` being the
+    /// ⊕-sum of the weights of the paths of `n` transitions from `i` to `j`.
+    pub fn pow(&self, mut n: usize) -> Result<Self> {
+        let mut res = Self::identity(self.num_states());
+        let mut square = self.clone();
+        while n > 0 {
+            if n % 2 == 1 {
+                res = res.mul(&square)?;
+            }
+            n /= 2;
+            if n > 0 {
+                square = square.mul(&square)?;
+            }
+        }
+        Ok(res)
+    }
+}
+
+/// Linear representation of a weighted automaton : the vector of the initial weights, a
+/// transition matrix per input label and the vector of the final weights. The weight of a
+/// sequence of labels `x1 ... xn` is `initial ⊗ m[x1] ⊗ ... ⊗ m[xn] ⊗ finals`, e.g. the
+/// entries of the Hankel matrices of the spectral learning methods.
+///
+/// The epsilon transitions are kept in the matrix of `EPS_LABEL` but not followed by `weight`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LinearRepresentation<W> {
+    /// `one` for the start state, `zero` for the others.
+    pub initial: Vec<W>,
+    /// Final weight of each state, `zero` for the non-final states.
+    pub finals: Vec<W>,
+    /// Transition matrix of each input label.
+    pub matrices: BTreeMap<Label, TransitionMatrix<W>>,
+}
+
+impl<W: Semiring> LinearRepresentation<W> {
+    pub fn new<F: ExpandedFst<W>>(fst: &F) -> Result<Self> {
+        let mut initial = vec![W::zero(); fst.num_states()];
+        if let Some(start) = fst.start() {
+            initial[start as usize] = W::one();
+        }
+        let mut finals = Vec::with_capacity(fst.num_states());
+        let mut labels = vec![];
+        for state in fst.states_range() {
+            finals.push(fst.final_weight(state)?.unwrap_or_else(W::zero));
+            labels.extend(fst.get_trs(state)?.trs().iter().map(|tr| tr.ilabel));
+        }
+        labels.sort_unstable();
+        labels.dedup();
+        let mut matrices = BTreeMap::new();
+        for label in labels {
+            matrices.insert(label, TransitionMatrix::new_with_label(fst, label)?);
+        }
+        Ok(Self {
+            initial,
+            finals,
+            matrices,
+        })
+    }
+
+    /// Weights of the states reached from the initial vector by reading `labels`.
+    pub fn forward(&self, labels: &[Label]) -> Result<Vec<W>> {
+        let mut vector = self.initial.clone();
+        for label in labels {
+            vector = match self.matrices.get(label) {
+                Some(matrix) => matrix.vec_mul(&vector)?,
+                None => vec![W::zero(); vector.len()],
+            };
+        }
+        Ok(vector)
+    }
+
+    /// ⊕-sum of the weights of the paths reading `labels`.
+    pub fn weight(&self, labels: &[Label]) -> Result<W> {
+        let mut sum = W::zero();
+        for (v, final_weight) in self.forward(labels)?.iter().zip(self.finals.iter()) {
+            sum.plus_assign(v.times(final_weight)?)?;
+        }
+        Ok(sum)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::fst_impls::VectorFst;
+    use crate::fst_traits::MutableFst;
+    use crate::semirings::TropicalWeight;
+
+    #[test]
+    fn test_transition_matrix() -> Result<()> {
+        let mut fst = VectorFst::<TropicalWeight>::new();
+        fst.add_states(3);
+        fst.set_start(0)?;
+        fst.add_tr(0, Tr::new(1, 1, 1.0, 1))?;
+        fst.add_tr(0, Tr::new(2, 2, 3.0, 1))?;
+        fst.add_tr(0, Tr::new(2, 2, 5.0, 2))?;
+        fst.add_tr(1, Tr::new(1, 1, 2.0, 2))?;
+        fst.add_tr(2, Tr::new(2, 2, 0.5, 0))?;
+        fst.set_final(2, 0.25)?;
+
+        let matrix = TransitionMatrix::new(&fst)?;
+        assert_eq!(matrix.num_states(), 3);
+        // The two trs from 0 to 1 are summed.
+        assert_eq!(matrix.num_entries(), 4);
+        assert_eq!(matrix.get(0, 1), TropicalWeight::new(1.0));
+        assert_eq!(matrix.get(1, 0), TropicalWeight::zero());
+
+        let vector = vec![
+            TropicalWeight::one(),
+            TropicalWeight::zero(),
+            TropicalWeight::zero(),
+        ];
+        let forward = matrix.vec_mul(&vector)?;
+        assert_eq!(
+            forward,
+            vec![
+                TropicalWeight::zero(),
+                TropicalWeight::new(1.0),
+                TropicalWeight::new(5.0)
+            ]
+        );
+        let backward = matrix.mul_vec(&vector)?;
+        assert_eq!(backward[2], TropicalWeight::new(0.5));
+
+        // Paths of two trs from 0 to 2 : min(1 + 2, ...).
+        let square = matrix.pow(2)?;
+        assert_eq!(square.get(0, 2), TropicalWeight::new(3.0));
+        assert_eq!(square.vec_mul(&vector)?, matrix.vec_mul(&forward)?);
+        assert_eq!(matrix.pow(0)?, TransitionMatrix::identity(3));
+        assert!(matrix.vec_mul(&vector[1..]).is_err());
+
+        let linear = LinearRepresentation::new(&fst)?;
+        assert_eq!(linear.matrices.len(), 2);
+        assert_eq!(linear.matrices[&2].get(0, 1), TropicalWeight::new(3.0));
+        assert_eq!(linear.weight(&[1, 1])?, TropicalWeight::new(3.25));
+        assert_eq!(linear.weight(&[2])?, TropicalWeight::new(5.25));
+        assert_eq!(linear.weight(&[1])?, TropicalWeight::zero());
+        assert_eq!(linear.weight(&[3])?, TropicalWeight::zero());
+        Ok(())
+    }
+}
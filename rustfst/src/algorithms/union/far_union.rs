@@ -0,0 +1,186 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use anyhow::Result;
+
+use crate::algorithms::replace::merge_table;
+use crate::fst_impls::VectorFst;
+use crate::fst_traits::{ExpandedFst, Fst, MutableFst};
+use crate::semirings::Semiring;
+use crate::tr::Tr;
+use crate::{Label, StateId, SymbolTable, Trs, EPS_LABEL};
+
+/// Merges the symbol tables of one side of the rules, starting from the table of the first
+/// rule. Returns the merged table and, for each rule, its labels whose symbol has another label
+/// in the merged table. Fails if only some of the rules have a table.
+#[allow(clippy::type_complexity)]
+fn merge_rule_tables<'a, W, F, S>(
+    rules: &[(S, &'a F)],
+    symt: impl Fn(&'a F) -> Option<&'a Arc<SymbolTable>>,
+    side: &str,
+) -> Result<(Option<SymbolTable>, Vec<HashMap<Label, Label>>)>
+where
+    W: Semiring,
+    F: ExpandedFst<W> + 'a,
+    S: AsRef<str>,
+{
+    if rules.iter().all(|(_, f)| symt(*f).is_none()) {
+        return Ok((None, vec![HashMap::new(); rules.len()]));
+    }
+    let mut merged: Option<SymbolTable> = None;
+    let mut relabelings = Vec::with_capacity(rules.len());
+    for (name, f) in rules.iter() {
+        let fst_symt = symt(*f).ok_or_else(|| {
+            format_err!(
+                "far_to_union : the rule {} has no {} symbol table unlike other rules",
+                name.as_ref(),
+                side
+            )
+        })?;
+        let merged = merged.get_or_insert_with(|| SymbolTable::clone(fst_symt));
+        relabelings.push(merge_table(merged, fst_symt).into_iter().collect());
+    }
+    Ok((merged, relabelings))
+}
+
+/// Unions all the FSTs of an archive, given as pairs of a rule name and an FST, into a single
+/// FST applying any of the rules.
+///
+/// A new start state has an epsilon transition to the start state of each FST. If
+/// `add_rule_labels` is true, this transition outputs a label distinguishing the rule, so that
+/// the outputs of the union tell which rule has been applied :
+/// - If the FSTs have output symbol tables, the rule names are added to the merged output
+///   table. A rule name must not already be one of its symbols.
+/// - Otherwise, the rules are labeled in order starting from the largest output label of the
+///   archive plus one.
+///
+/// On each side, either all the FSTs or none of them must have a symbol table. The tables are
+/// merged into the table of the union, starting from the table of the first FST : the trs of
+/// the other FSTs are relabeled when a label stands for another symbol in the merged table.
+/// The labels without symbol are kept.
+pub fn far_to_union<'a, W, F, I, S>(far: I, add_rule_labels: bool) -> Result<VectorFst<W>>
+where
+    W: Semiring,
+    F: ExpandedFst<W> + 'a,
+    I: IntoIterator<Item = (S, &'a F)>,
+    S: AsRef<str>,
+{
+    let rules: Vec<_> = far.into_iter().collect();
+    let (isymt, irelabelings) = merge_rule_tables(&rules, |f| f.input_symbols(), "input")?;
+    let (mut osymt, orelabelings) = merge_rule_tables(&rules, |f| f.output_symbols(), "output")?;
+
+    let rule_labels = if !add_rule_labels {
+        vec![EPS_LABEL; rules.len()]
+    } else if let Some(symt) = osymt.as_mut() {
+        let mut labels = Vec::with_capacity(rules.len());
+        for (name, _) in rules.iter() {
+            if symt.contains_symbol(name.as_ref()) {
+                bail!(
+                    "far_to_union : the rule {} is already an output symbol",
+                    name.as_ref()
+                );
+            }
+            labels.push(symt.add_symbol(name.as_ref()));
+        }
+        labels
+    } else {
+        let mut max_label = EPS_LABEL;
+        for (_, f) in rules.iter() {
+            for state in f.states_range() {
+                for tr in f.get_trs(state)?.trs() {
+                    max_label = max_label.max(tr.olabel);
+                }
+            }
+        }
+        (1..=rules.len() as Label).map(|i| max_label + i).collect()
+    };
+
+    let mut fst_out = VectorFst::new();
+    let start = fst_out.add_state();
+    fst_out.set_start(start)?;
+    for (i, (_, f)) in rules.iter().enumerate() {
+        let offset = fst_out.num_states() as StateId;
+        fst_out.add_states(f.num_states());
+        for state in f.states_range() {
+            if let Some(final_weight) = f.final_weight(state)? {
+                fst_out.set_final(state + offset, final_weight)?;
+            }
+            for tr in f.get_trs(state)?.trs() {
+                let mut tr = tr.clone();
+                if let Some(ilabel) = irelabelings[i].get(&tr.ilabel) {
+                    tr.ilabel = *ilabel;
+                }
+                if let Some(olabel) = orelabelings[i].get(&tr.olabel) {
+                    tr.olabel = *olabel;
+                }
+                tr.nextstate += offset;
+                fst_out.add_tr(state + offset, tr)?;
+            }
+        }
+        if let Some(f_start) = f.start() {
+            fst_out.add_tr(
+                start,
+                Tr::new(EPS_LABEL, rule_labels[i], W::one(), f_start + offset),
+            )?;
+        }
+    }
+    if let Some(symt) = isymt {
+        fst_out.set_input_symbols(Arc::new(symt));
+    }
+    if let Some(symt) = osymt {
+        fst_out.set_output_symbols(Arc::new(symt));
+    }
+    Ok(fst_out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::fst_traits::{CoreFst, Fst};
+    use crate::semirings::TropicalWeight;
+    use crate::utils::transducer;
+
+    #[test]
+    fn test_far_to_union() -> Result<()> {
+        let rule_a: VectorFst<TropicalWeight> = transducer(&[1, 2], &[3], TropicalWeight::one());
+        let rule_b: VectorFst<TropicalWeight> = transducer(&[4], &[5, 6], TropicalWeight::one());
+        let far = vec![("rule_a", &rule_a), ("rule_b", &rule_b)];
+
+        let fst = far_to_union(far.clone(), false)?;
+        assert_eq!(
+            fst.num_states(),
+            1 + rule_a.num_states() + rule_b.num_states()
+        );
+        let olabels: Vec<_> = fst.get_trs(0)?.trs().iter().map(|tr| tr.olabel).collect();
+        assert_eq!(olabels, vec![EPS_LABEL, EPS_LABEL]);
+
+        let fst = far_to_union(far.clone(), true)?;
+        let olabels: Vec<_> = fst.get_trs(0)?.trs().iter().map(|tr| tr.olabel).collect();
+        assert_eq!(olabels, vec![7, 8]);
+
+        let mut symt = SymbolTable::new();
+        symt.add_symbols(vec!["a", "b", "c", "d", "e", "f"]);
+        let mut rule_a = rule_a.clone();
+        rule_a.set_output_symbols(Arc::new(symt));
+        // Only some of the rules have an output symbol table.
+        assert!(far_to_union(vec![("rule_a", &rule_a), ("rule_b", &rule_b)], true).is_err());
+
+        let mut symt = SymbolTable::new();
+        symt.add_symbols(vec!["f", "e", "d", "c", "b", "a"]);
+        let mut rule_b = rule_b.clone();
+        rule_b.set_output_symbols(Arc::new(symt));
+        let fst = far_to_union(vec![("rule_a", &rule_a), ("rule_b", &rule_b)], true)?;
+        let osymt = fst.output_symbols().unwrap();
+        let olabels: Vec<_> = fst.get_trs(0)?.trs().iter().map(|tr| tr.olabel).collect();
+        assert_eq!(olabels, vec![7, 8]);
+        assert_eq!(osymt.get_symbol(8), Some("rule_b"));
+        // The outputs "b" and "a" of rule_b are relabeled with the labels of the first table.
+        assert_eq!(fst.get_trs(4)?.trs()[0].olabel, 2);
+        assert_eq!(fst.get_trs(5)?.trs()[0].olabel, 1);
+        assert!(fst.input_symbols().is_none());
+
+        assert!(far_to_union(vec![("a", &rule_a)], true).is_err());
+        Ok(())
+    }
+}
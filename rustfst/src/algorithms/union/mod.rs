@@ -1,5 +1,7 @@
+mod far_union;
 mod union_fst;
 mod union_static;
 
+pub use far_union::far_to_union;
 pub use union_fst::UnionFst;
 pub use union_static::union;
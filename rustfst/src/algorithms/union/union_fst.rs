@@ -13,6 +13,31 @@ use crate::{StateId, SymbolTable, Tr, TrsVec, EPS_LABEL, NO_LABEL};
 /// transduces string x to y with weight a and B transduces string w to v with
 /// weight b, then their union transduces x to y with weight a and w to v with
 /// weight b.
+///
+/// The states are expanded on demand, without copying the input FSTs upfront, e.g. when the
+/// lazy FST is read by another algorithm. `compute` turns it into a static FST.
+///
+/// # Example
+/// ```
+/// # use anyhow::Result;
+/// # fn main() -> Result<()> {
+/// # use rustfst::algorithms::accepts;
+/// # use rustfst::algorithms::union::UnionFst;
+/// # use rustfst::fst_impls::VectorFst;
+/// # use rustfst::semirings::{Semiring, TropicalWeight};
+/// # use rustfst::utils::acceptor;
+/// let fst1: VectorFst<TropicalWeight> = acceptor(&[1, 2], TropicalWeight::one());
+/// let fst2: VectorFst<TropicalWeight> = acceptor(&[3], TropicalWeight::one());
+/// let union_fst = UnionFst::new(fst1, fst2)?;
+/// assert!(accepts(&union_fst, &[1, 2])?);
+/// assert!(accepts(&union_fst, &[3])?);
+///
+/// let computed: VectorFst<_> = union_fst.compute()?;
+/// assert!(accepts(&computed, &[3])?);
+/// assert!(!accepts(&computed, &[1, 2, 3])?);
+/// # Ok(())
+/// # }
+/// ```
 #[derive(Debug)]
 pub struct UnionFst<W: Semiring, F: Fst<W> + 'static>(ReplaceFst<W, F, F>, FstProperties);
 
@@ -0,0 +1,257 @@
+use std::collections::VecDeque;
+use std::mem::replace;
+
+use anyhow::Result;
+
+use crate::fst_traits::ExpandedFst;
+use crate::semirings::{Semiring, SemiringProperties};
+use crate::{StateId, Trs};
+
+/// Weight-only view of an FST : the destination states and the weights of the transitions of
+/// each state stored in flat arrays, without their labels.
+///
+/// The distance computations (`shortest_distance`, the potentials passed to `reweight`) only
+/// read these two fields, hence don't need to move the labels through the memory.
+/// The distances are computed from a set of initial states and weights, i.e. the start state
+/// for a graph built with `new` and the final states for a graph built with `new_reversed`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WeightGraph<W> {
+    /// Position of the transitions of each state in `nextstates` and `weights`.
+    offsets: Vec<usize>,
+    nextstates: Vec<StateId>,
+    weights: Vec<W>,
+    initial_weights: Vec<(StateId, W)>,
+}
+
+impl<W: Semiring> WeightGraph<W> {
+    /// Weight-only view of `fst`, whose initial state is the start state of `fst`.
+    pub fn new<F: ExpandedFst<W>>(fst: &F) -> Result<Self> {
+        let num_states = fst.num_states();
+        let mut offsets = Vec::with_capacity(num_states + 1);
+        let mut nextstates = vec![];
+        let mut weights = vec![];
+        offsets.push(0);
+        for state in fst.states_range() {
+            for tr in fst.get_trs(state)?.trs() {
+                nextstates.push(tr.nextstate);
+                weights.push(tr.weight.clone());
+            }
+            offsets.push(nextstates.len());
+        }
+        Ok(Self {
+            offsets,
+            nextstates,
+            weights,
+            initial_weights: fst.start().map(|s| (s, W::one())).into_iter().collect(),
+        })
+    }
+
+    /// Weight-only view of the reversal of `fst`, whose initial states are the final states
+    /// of `fst` with their reversed final weights. Unlike `reverse`, no super-initial state is
+    /// added : the states keep their ids.
+    pub fn new_reversed<F: ExpandedFst<V>, V: Semiring<ReverseWeight = W>>(
+        fst: &F,
+    ) -> Result<Self> {
+        let num_states = fst.num_states();
+        // Counting sort of the transitions by destination state.
+        let mut offsets = vec![0; num_states + 1];
+        for state in fst.states_range() {
+            for tr in fst.get_trs(state)?.trs() {
+                offsets[tr.nextstate as usize + 1] += 1;
+            }
+        }
+        for i in 0..num_states {
+            offsets[i + 1] += offsets[i];
+        }
+        let num_trs = offsets[num_states];
+        let mut positions = offsets.clone();
+        let mut nextstates = vec![0; num_trs];
+        let mut weights = vec![W::zero(); num_trs];
+        let mut initial_weights = vec![];
+        for state in fst.states_range() {
+            for tr in fst.get_trs(state)?.trs() {
+                let pos = &mut positions[tr.nextstate as usize];
+                nextstates[*pos] = state;
+                weights[*pos] = tr.weight.reverse()?;
+                *pos += 1;
+            }
+            if let Some(final_weight) = fst.final_weight(state)? {
+                initial_weights.push((state, final_weight.reverse()?));
+            }
+        }
+        Ok(Self {
+            offsets,
+            nextstates,
+            weights,
+            initial_weights,
+        })
+    }
+
+    pub fn num_states(&self) -> usize {
+        self.offsets.len() - 1
+    }
+
+    pub fn num_trs(&self) -> usize {
+        self.nextstates.len()
+    }
+
+    /// Destination states and weights of the transitions leaving `state`.
+    pub fn trs(&self, state: StateId) -> impl Iterator<Item = (StateId, &W)> {
+        let range = self.offsets[state as usize]..self.offsets[state as usize + 1];
+        self.nextstates[range.clone()]
+            .iter()
+            .cloned()
+            .zip(self.weights[range].iter())
+    }
+
+    /// Topological order of the states, `None` if the graph is cyclic.
+    fn top_order(&self) -> Option<Vec<StateId>> {
+        let num_states = self.num_states();
+        let mut in_degrees = vec![0usize; num_states];
+        for n in self.nextstates.iter() {
+            in_degrees[*n as usize] += 1;
+        }
+        let mut order: Vec<StateId> = (0..num_states)
+            .filter(|s| in_degrees[*s] == 0)
+            .map(|s| s as StateId)
+            .collect();
+        let mut i = 0;
+        while i < order.len() {
+            let state = order[i];
+            for (nextstate, _) in self.trs(state) {
+                in_degrees[nextstate as usize] -= 1;
+                if in_degrees[nextstate as usize] == 0 {
+                    order.push(nextstate);
+                }
+            }
+            i += 1;
+        }
+        if order.len() == num_states {
+            Some(order)
+        } else {
+            None
+        }
+    }
+
+    /// Shortest distance from the initial states to every state, with the generic algorithm
+    /// of `shortest_distance`. The states are visited in topological order if the graph is
+    /// acyclic and in FIFO order otherwise. The result has one weight per state.
+    pub fn shortest_distance(&self, delta: f32) -> Result<Vec<W>> {
+        if !W::properties().contains(SemiringProperties::RIGHT_SEMIRING) {
+            bail!("ShortestDistance: Weight needs to be right distributive")
+        }
+        let num_states = self.num_states();
+        let mut distance = vec![W::zero(); num_states];
+        let mut adder = vec![W::zero(); num_states];
+        let mut radder = vec![W::zero(); num_states];
+        let mut enqueued = vec![false; num_states];
+        let mut queue = VecDeque::new();
+        for (state, weight) in self.initial_weights.iter() {
+            let s = *state as usize;
+            distance[s].plus_assign(weight)?;
+            adder[s].plus_assign(weight)?;
+            radder[s].plus_assign(weight)?;
+            if !enqueued[s] {
+                enqueued[s] = true;
+                queue.push_back(*state);
+            }
+        }
+
+        let top_order = self.top_order();
+        let mut top_order_iter = top_order.iter().flatten();
+        loop {
+            // In topological order, each state is visited once, after all its predecessors.
+            let state = match &top_order {
+                Some(_) => match top_order_iter.next() {
+                    Some(s) => *s,
+                    None => break,
+                },
+                None => match queue.pop_front() {
+                    Some(s) => s,
+                    None => break,
+                },
+            };
+            enqueued[state as usize] = false;
+            let r = replace(&mut radder[state as usize], W::zero());
+            if r.is_zero() {
+                continue;
+            }
+            for (nextstate, tr_weight) in self.trs(state) {
+                let n = nextstate as usize;
+                let weight = r.times(tr_weight)?;
+                if !distance[n].approx_equal(distance[n].plus(&weight)?, delta) {
+                    adder[n].plus_assign(&weight)?;
+                    distance[n] = adder[n].clone();
+                    radder[n].plus_assign(&weight)?;
+                    if top_order.is_none() && !enqueued[n] {
+                        enqueued[n] = true;
+                        queue.push_back(nextstate);
+                    }
+                }
+            }
+        }
+        Ok(distance)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::algorithms::{
+        shortest_distance, shortest_distance_with_config, ShortestDistanceConfig,
+    };
+    use crate::fst_impls::VectorFst;
+    use crate::fst_traits::MutableFst;
+    use crate::semirings::{LogWeight, ReverseBack, TropicalWeight};
+    use crate::{Tr, KSHORTESTDELTA};
+
+    #[test]
+    fn test_weight_graph_shortest_distance() -> Result<()> {
+        let mut fst = VectorFst::<TropicalWeight>::new();
+        fst.add_states(4);
+        fst.set_start(0)?;
+        fst.add_tr(0, Tr::new(1, 1, 1.0, 1))?;
+        fst.add_tr(0, Tr::new(2, 2, 4.0, 2))?;
+        fst.add_tr(1, Tr::new(3, 3, 1.0, 2))?;
+        fst.add_tr(2, Tr::new(4, 4, 2.0, 1))?;
+        fst.add_tr(2, Tr::new(5, 5, 3.0, 3))?;
+        fst.set_final(3, 0.5)?;
+
+        let graph = WeightGraph::new(&fst)?;
+        assert_eq!(graph.num_trs(), 5);
+        assert_eq!(
+            graph.shortest_distance(KSHORTESTDELTA)?,
+            shortest_distance(&fst, false)?
+        );
+
+        let rgraph = WeightGraph::new_reversed(&fst)?;
+        let distance = rgraph
+            .shortest_distance(KSHORTESTDELTA)?
+            .iter()
+            .map(|w| w.reverse_back())
+            .collect::<Result<Vec<TropicalWeight>>>()?;
+        assert_eq!(distance, shortest_distance(&fst, true)?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_weight_graph_shortest_distance_acyclic_log() -> Result<()> {
+        let mut fst = VectorFst::<LogWeight>::new();
+        fst.add_states(3);
+        fst.set_start(0)?;
+        fst.add_tr(0, Tr::new(1, 1, 1.0, 1))?;
+        fst.add_tr(0, Tr::new(2, 2, 2.0, 1))?;
+        fst.add_tr(1, Tr::new(3, 3, 0.5, 2))?;
+        fst.set_final(2, 0.0)?;
+
+        let expected =
+            shortest_distance_with_config(&fst, false, ShortestDistanceConfig::default())?;
+        let config = ShortestDistanceConfig::default().with_weights_only(true);
+        let distance = shortest_distance_with_config(&fst, false, config)?;
+        for (d, e) in distance.iter().zip(expected.iter()) {
+            assert!(d.approx_equal(e, KSHORTESTDELTA));
+        }
+        Ok(())
+    }
+}
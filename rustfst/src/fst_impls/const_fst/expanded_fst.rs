@@ -1,9 +1,27 @@
 use crate::fst_impls::ConstFst;
-use crate::fst_traits::ExpandedFst;
+use anyhow::Result;
+
+use crate::fst_traits::{ExpandedFst, TrsSliceFst};
 use crate::semirings::Semiring;
+use crate::{StateId, Tr};
 
 impl<W: 'static + Semiring> ExpandedFst<W> for ConstFst<W> {
     fn num_states(&self) -> usize {
         self.states.len()
     }
 }
+
+impl<W: 'static + Semiring> TrsSliceFst<W> for ConstFst<W> {
+    fn get_trs_slice(&self, state_id: StateId) -> Result<&[Tr<W>]> {
+        let state = self
+            .states
+            .get(state_id as usize)
+            .ok_or_else(|| format_err!("State {:?} doesn't exist", state_id))?;
+        Ok(&self.trs[state.pos..state.pos + state.ntrs])
+    }
+
+    unsafe fn get_trs_slice_unchecked(&self, state_id: StateId) -> &[Tr<W>] {
+        let state = self.states.get_unchecked(state_id as usize);
+        self.trs.get_unchecked(state.pos..state.pos + state.ntrs)
+    }
+}
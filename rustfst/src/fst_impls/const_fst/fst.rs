@@ -56,6 +56,24 @@ impl<W: Semiring> CoreFst<W> for ConstFst<W> {
             .clone()
     }
 
+    // Overridden to avoid cloning the final weight.
+    #[inline]
+    fn is_final(&self, state_id: StateId) -> Result<bool> {
+        let s = self
+            .states
+            .get(state_id as usize)
+            .ok_or_else(|| format_err!("State {:?} doesn't exist", state_id))?;
+        Ok(matches!(&s.final_weight, Some(w) if !w.is_zero()))
+    }
+
+    #[inline]
+    unsafe fn is_final_unchecked(&self, state: StateId) -> bool {
+        self.states
+            .get_unchecked(state as usize)
+            .final_weight
+            .is_some()
+    }
+
     fn num_trs(&self, s: StateId) -> Result<usize> {
         Ok(self
             .states
@@ -0,0 +1,130 @@
+use crate::fst_traits::ExpandedFst;
+use crate::semirings::Semiring;
+use crate::StateId;
+
+const WORD_BITS: usize = 64;
+
+/// Compact read-only view of the final states of an FST : a bitset of the final states and
+/// their final weights packed in the order of the states.
+///
+/// Checking whether a state is final reads a single bit and fetching its final weight costs a
+/// popcount, without cloning the weight. It is meant to be built once before a search whose
+/// inner loop queries the final states, the FSTs themselves storing an `Option<W>` per state.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FinalStates<W> {
+    bits: Vec<u64>,
+    /// Number of final states before each word of `bits`.
+    ranks: Vec<u32>,
+    weights: Vec<W>,
+    num_states: usize,
+}
+
+impl<W: Semiring> FinalStates<W> {
+    pub fn new<F: ExpandedFst<W>>(fst: &F) -> Self {
+        let num_states = fst.num_states();
+        let mut bits = vec![];
+        let mut ranks = vec![];
+        let mut weights = vec![];
+        for state in 0..num_states {
+            if state % WORD_BITS == 0 {
+                bits.push(0u64);
+                ranks.push(weights.len() as u32);
+            }
+            // Safe because the state exists.
+            if let Some(final_weight) = unsafe { fst.final_weight_unchecked(state as StateId) } {
+                if !final_weight.is_zero() {
+                    bits[state / WORD_BITS] |= 1 << (state % WORD_BITS);
+                    weights.push(final_weight);
+                }
+            }
+        }
+        Self {
+            bits,
+            ranks,
+            weights,
+            num_states,
+        }
+    }
+
+    /// Whether the state is final. `false` for the states not in the FST.
+    #[inline]
+    pub fn is_final(&self, state: StateId) -> bool {
+        let state = state as usize;
+        state < self.num_states && self.bits[state / WORD_BITS] & (1 << (state % WORD_BITS)) != 0
+    }
+
+    /// Final weight of the state, `None` if it is not final.
+    #[inline]
+    pub fn final_weight(&self, state: StateId) -> Option<&W> {
+        if !self.is_final(state) {
+            return None;
+        }
+        let state = state as usize;
+        let word = state / WORD_BITS;
+        let below = self.bits[word] & ((1 << (state % WORD_BITS)) - 1);
+        Some(&self.weights[self.ranks[word] as usize + below.count_ones() as usize])
+    }
+
+    pub fn num_final_states(&self) -> usize {
+        self.weights.len()
+    }
+
+    pub fn num_states(&self) -> usize {
+        self.num_states
+    }
+
+    /// Iterates over the final states, in increasing order, with their final weights.
+    pub fn iter(&self) -> impl Iterator<Item = (StateId, &W)> + '_ {
+        self.bits
+            .iter()
+            .enumerate()
+            .flat_map(|(word, bits)| {
+                let mut bits = *bits;
+                std::iter::from_fn(move || {
+                    if bits == 0 {
+                        return None;
+                    }
+                    let offset = bits.trailing_zeros() as usize;
+                    bits &= bits - 1;
+                    Some((word * WORD_BITS + offset) as StateId)
+                })
+            })
+            .zip(self.weights.iter())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use anyhow::Result;
+
+    use crate::fst_impls::VectorFst;
+    use crate::fst_traits::{CoreFst, MutableFst};
+    use crate::semirings::TropicalWeight;
+
+    #[test]
+    fn test_final_states() -> Result<()> {
+        let mut fst = VectorFst::<TropicalWeight>::new();
+        fst.add_states(150);
+        fst.set_final(0, 1.0)?;
+        fst.set_final(63, 2.0)?;
+        fst.set_final(64, 3.0)?;
+        fst.set_final(100, TropicalWeight::zero())?;
+        fst.set_final(149, 4.0)?;
+
+        let final_states = FinalStates::new(&fst);
+        assert_eq!(final_states.num_final_states(), 4);
+        for state in 0..152 {
+            let expected = fst
+                .final_weight(state)
+                .unwrap_or(None)
+                .filter(|w| !w.is_zero());
+            assert_eq!(final_states.final_weight(state), expected.as_ref());
+            assert_eq!(final_states.is_final(state), expected.is_some());
+        }
+        let states: Vec<_> = final_states.iter().map(|(s, _)| s).collect();
+        assert_eq!(states, vec![0, 63, 64, 149]);
+        Ok(())
+    }
+}
@@ -1,6 +1,10 @@
 mod arc;
 pub(crate) mod const_fst;
+mod final_states;
+mod score_matrix_fst;
 pub(crate) mod vector_fst;
 
 pub use self::const_fst::ConstFst;
+pub use self::final_states::FinalStates;
+pub use self::score_matrix_fst::ScoreMatrixFst;
 pub use self::vector_fst::VectorFst;
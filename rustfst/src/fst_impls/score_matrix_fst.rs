@@ -0,0 +1,359 @@
+use std::iter::Map;
+use std::marker::PhantomData;
+use std::ops::Range;
+use std::sync::Arc;
+
+use anyhow::{bail, format_err, Result};
+
+use crate::fst_properties::FstProperties;
+use crate::fst_traits::{
+    CoreFst, ExpandedFst, Fst, FstIntoIterator, FstIterData, FstIterator, StateIterator,
+};
+use crate::semirings::Semiring;
+use crate::{Label, StateId, SymbolTable, Tr, TrsVec, EPS_LABEL};
+
+/// Immutable acceptor whose transitions are read from a dense matrix of scores of `T` frames
+/// by `L` labels, such as the posteriors of a CTC acoustic model.
+///
+/// The state `t` is the frame `t` and the state `T` is the only final state. For each label
+/// `l`, the state `t` has a transition to the state `t + 1` with the weight `scores[t][l]`,
+/// unless it is infinite. The transitions are built on demand, the matrix is never turned into
+/// a `VectorFst`, so that the acceptor can be given directly to the composition with a
+/// decoding graph or to a search.
+///
+/// By default the column `l` holds the scores of the label `l + 1`. Other labels can be set
+/// with [`with_labels`](ScoreMatrixFst::with_labels), for instance to map the CTC blank to
+/// `EPS_LABEL`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScoreMatrixFst<W> {
+    scores: Vec<f32>,
+    num_frames: usize,
+    labels: Vec<Label>,
+    isymt: Option<Arc<SymbolTable>>,
+    osymt: Option<Arc<SymbolTable>>,
+    properties: FstProperties,
+    w: PhantomData<W>,
+}
+
+impl<W: Semiring<Type = f32>> ScoreMatrixFst<W> {
+    /// Builds the acceptor from the scores of each frame, stored frame after frame. The scores
+    /// are the values of the weights, for instance negative log posteriors in the tropical
+    /// and log semirings.
+    pub fn new(scores: Vec<f32>, num_labels: usize) -> Result<Self> {
+        if num_labels == 0 || !scores.chunks_exact(num_labels).remainder().is_empty() {
+            bail!(
+                "ScoreMatrixFst : {} scores can't be split in frames of {} labels",
+                scores.len(),
+                num_labels
+            );
+        }
+        let labels = (1..=num_labels).map(|l| l as Label).collect();
+        let mut fst = Self {
+            num_frames: scores.len() / num_labels,
+            scores,
+            labels,
+            isymt: None,
+            osymt: None,
+            properties: FstProperties::empty(),
+            w: PhantomData,
+        };
+        fst.properties = fst.compute_properties();
+        Ok(fst)
+    }
+
+    /// Sets the label of each column of the matrix.
+    pub fn with_labels(mut self, labels: Vec<Label>) -> Result<Self> {
+        if labels.len() != self.labels.len() {
+            bail!(
+                "ScoreMatrixFst : expected {} labels, got {}",
+                self.labels.len(),
+                labels.len()
+            );
+        }
+        self.labels = labels;
+        self.properties = self.compute_properties();
+        Ok(self)
+    }
+
+    pub fn num_frames(&self) -> usize {
+        self.num_frames
+    }
+
+    pub fn labels(&self) -> &[Label] {
+        &self.labels
+    }
+
+    /// Scores of the frame `t`, one per label.
+    pub fn frame_scores(&self, t: usize) -> &[f32] {
+        let n = self.labels.len();
+        &self.scores[t * n..(t + 1) * n]
+    }
+
+    fn compute_properties(&self) -> FstProperties {
+        let mut props = FstProperties::ACCEPTOR
+            | FstProperties::ACYCLIC
+            | FstProperties::INITIAL_ACYCLIC
+            | FstProperties::TOP_SORTED;
+        if self.labels.contains(&EPS_LABEL) {
+            props |=
+                FstProperties::EPSILONS | FstProperties::I_EPSILONS | FstProperties::O_EPSILONS;
+        } else {
+            props |= FstProperties::NO_EPSILONS
+                | FstProperties::NO_I_EPSILONS
+                | FstProperties::NO_O_EPSILONS;
+            let mut sorted_labels = self.labels.clone();
+            sorted_labels.sort_unstable();
+            sorted_labels.dedup();
+            if sorted_labels.len() == self.labels.len() {
+                props |= FstProperties::I_DETERMINISTIC | FstProperties::O_DETERMINISTIC;
+            }
+        }
+        if self.labels.windows(2).all(|w| w[0] <= w[1]) {
+            props |= FstProperties::I_LABEL_SORTED | FstProperties::O_LABEL_SORTED;
+        }
+        if (0..self.num_frames).all(|t| self.frame_scores(t).iter().any(|s| s.is_finite())) {
+            props |= FstProperties::ACCESSIBLE | FstProperties::COACCESSIBLE;
+        }
+        props
+    }
+
+    fn check_state(&self, state: StateId) -> Result<usize> {
+        let t = state as usize;
+        if t > self.num_frames {
+            return Err(format_err!("State {:?} doesn't exist", state));
+        }
+        Ok(t)
+    }
+
+    fn frame_trs(&self, t: usize) -> Vec<Tr<W>> {
+        if t == self.num_frames {
+            return vec![];
+        }
+        self.labels
+            .iter()
+            .zip(self.frame_scores(t).iter())
+            .filter(|(_, score)| score.is_finite())
+            .map(|(label, score)| Tr::new(*label, *label, W::new(*score), (t + 1) as StateId))
+            .collect()
+    }
+}
+
+impl<W: Semiring<Type = f32>> CoreFst<W> for ScoreMatrixFst<W> {
+    type TRS = TrsVec<W>;
+
+    fn start(&self) -> Option<StateId> {
+        Some(0)
+    }
+
+    fn final_weight(&self, state_id: StateId) -> Result<Option<W>> {
+        let t = self.check_state(state_id)?;
+        Ok(unsafe { self.final_weight_unchecked(t as StateId) })
+    }
+
+    unsafe fn final_weight_unchecked(&self, state_id: StateId) -> Option<W> {
+        if state_id as usize == self.num_frames {
+            Some(W::one())
+        } else {
+            None
+        }
+    }
+
+    fn num_trs(&self, s: StateId) -> Result<usize> {
+        let t = self.check_state(s)?;
+        Ok(unsafe { self.num_trs_unchecked(t as StateId) })
+    }
+
+    unsafe fn num_trs_unchecked(&self, s: StateId) -> usize {
+        let t = s as usize;
+        if t == self.num_frames {
+            0
+        } else {
+            self.frame_scores(t)
+                .iter()
+                .filter(|s| s.is_finite())
+                .count()
+        }
+    }
+
+    fn get_trs(&self, state_id: StateId) -> Result<Self::TRS> {
+        let t = self.check_state(state_id)?;
+        Ok(TrsVec(Arc::new(self.frame_trs(t))))
+    }
+
+    unsafe fn get_trs_unchecked(&self, state_id: StateId) -> Self::TRS {
+        TrsVec(Arc::new(self.frame_trs(state_id as usize)))
+    }
+
+    fn properties(&self) -> FstProperties {
+        self.properties
+    }
+
+    fn num_input_epsilons(&self, state: StateId) -> Result<usize> {
+        let t = self.check_state(state)?;
+        Ok(self
+            .frame_trs(t)
+            .iter()
+            .filter(|tr| tr.ilabel == EPS_LABEL)
+            .count())
+    }
+
+    fn num_output_epsilons(&self, state: StateId) -> Result<usize> {
+        self.num_input_epsilons(state)
+    }
+}
+
+impl<'a, W> StateIterator<'a> for ScoreMatrixFst<W> {
+    type Iter = Range<StateId>;
+    fn states_iter(&'a self) -> Self::Iter {
+        0..((self.num_frames + 1) as StateId)
+    }
+}
+
+type StateToData<'a, W> = Box<dyn FnMut(StateId) -> FstIterData<W, TrsVec<W>> + 'a>;
+
+impl<'a, W: Semiring<Type = f32>> FstIterator<'a, W> for ScoreMatrixFst<W> {
+    type FstIter = Map<Range<StateId>, StateToData<'a, W>>;
+    fn fst_iter(&'a self) -> Self::FstIter {
+        self.states_iter().map(Box::new(move |state_id| {
+            let trs = TrsVec(Arc::new(self.frame_trs(state_id as usize)));
+            FstIterData {
+                state_id,
+                final_weight: unsafe { self.final_weight_unchecked(state_id) },
+                num_trs: trs.0.len(),
+                trs,
+            }
+        }))
+    }
+}
+
+impl<W: Semiring<Type = f32> + 'static> FstIntoIterator<W> for ScoreMatrixFst<W> {
+    type TrsIter = std::vec::IntoIter<Tr<W>>;
+    type FstIter = Box<dyn Iterator<Item = FstIterData<W, Self::TrsIter>>>;
+
+    fn fst_into_iter(self) -> Self::FstIter {
+        Box::new((0..=self.num_frames).map(move |t| {
+            let trs = self.frame_trs(t);
+            FstIterData {
+                state_id: t as StateId,
+                final_weight: unsafe { self.final_weight_unchecked(t as StateId) },
+                num_trs: trs.len(),
+                trs: trs.into_iter(),
+            }
+        }))
+    }
+}
+
+impl<W: Semiring<Type = f32>> Fst<W> for ScoreMatrixFst<W> {
+    fn input_symbols(&self) -> Option<&Arc<SymbolTable>> {
+        self.isymt.as_ref()
+    }
+
+    fn output_symbols(&self) -> Option<&Arc<SymbolTable>> {
+        self.osymt.as_ref()
+    }
+
+    fn set_input_symbols(&mut self, symt: Arc<SymbolTable>) {
+        self.isymt = Some(symt)
+    }
+
+    fn set_output_symbols(&mut self, symt: Arc<SymbolTable>) {
+        self.osymt = Some(symt);
+    }
+
+    fn take_input_symbols(&mut self) -> Option<Arc<SymbolTable>> {
+        self.isymt.take()
+    }
+
+    fn take_output_symbols(&mut self) -> Option<Arc<SymbolTable>> {
+        self.osymt.take()
+    }
+}
+
+impl<W: Semiring<Type = f32> + 'static> ExpandedFst<W> for ScoreMatrixFst<W> {
+    fn num_states(&self) -> usize {
+        self.num_frames + 1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::algorithms::compose::compose;
+    use crate::algorithms::tr_compares::ILabelCompare;
+    use crate::algorithms::{fst_convert_from_ref, shortest_path, tr_sort};
+    use crate::fst_impls::VectorFst;
+    use crate::fst_traits::MutableFst;
+    use crate::semirings::TropicalWeight;
+    use crate::Trs;
+
+    #[test]
+    fn test_score_matrix_fst_compose() -> Result<()> {
+        // Columns : blank, a, b.
+        let scores = vec![
+            0.1,
+            1.0,
+            2.0, //
+            1.0,
+            0.2,
+            3.0, //
+            f32::INFINITY,
+            2.0,
+            0.1,
+        ];
+        let matrix =
+            ScoreMatrixFst::<TropicalWeight>::new(scores, 3)?.with_labels(vec![EPS_LABEL, 1, 2])?;
+        assert_eq!(matrix.num_states(), 4);
+        assert_eq!(matrix.num_trs(2)?, 2);
+        assert_eq!(matrix.final_weight(3)?, Some(TropicalWeight::one()));
+        assert!(matrix.get_trs(4).is_err());
+
+        // Graph accepting "a b" or "b".
+        let mut graph = VectorFst::<TropicalWeight>::new();
+        let s0 = graph.add_state();
+        let s1 = graph.add_state();
+        let s2 = graph.add_state();
+        graph.set_start(s0)?;
+        graph.add_tr(s0, Tr::new(1, 1, 0.0, s1))?;
+        graph.add_tr(s1, Tr::new(2, 2, 0.0, s2))?;
+        graph.add_tr(s0, Tr::new(2, 2, 0.0, s2))?;
+        graph.set_final(s2, TropicalWeight::one())?;
+        tr_sort(&mut graph, ILabelCompare {});
+
+        let lattice: VectorFst<TropicalWeight> =
+            compose::<_, ScoreMatrixFst<_>, VectorFst<_>, _, _, _>(&matrix, &graph)?;
+        let best: VectorFst<TropicalWeight> = shortest_path(&lattice)?;
+        let path = best.paths_iter().next().unwrap();
+        // blank a b is the best alignment of "a b".
+        assert_eq!(path.olabels, vec![1, 2]);
+        assert!((path.weight.value() - 0.4).abs() < 1e-5);
+        Ok(())
+    }
+
+    #[test]
+    fn test_score_matrix_fst_into_iter() -> Result<()> {
+        let matrix = ScoreMatrixFst::<TropicalWeight>::new(vec![0.5, f32::INFINITY, 1.0, 2.0], 2)?;
+        let expected: Vec<_> = matrix
+            .fst_iter()
+            .map(|data| (data.state_id, data.final_weight, data.trs.trs().to_vec()))
+            .collect();
+        let data: Vec<_> = matrix
+            .clone()
+            .fst_into_iter()
+            .map(|data| {
+                (
+                    data.state_id,
+                    data.final_weight,
+                    data.trs.collect::<Vec<_>>(),
+                )
+            })
+            .collect();
+        assert_eq!(data, expected);
+        assert_eq!(data[0].2.len(), 1);
+
+        let fst: VectorFst<TropicalWeight> = fst_convert_from_ref(&matrix);
+        assert_eq!(fst.num_states(), 3);
+        assert_eq!(fst.num_trs(1)?, 2);
+        Ok(())
+    }
+}
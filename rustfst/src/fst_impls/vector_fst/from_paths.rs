@@ -0,0 +1,210 @@
+use std::collections::HashMap;
+
+use anyhow::Result;
+
+use crate::fst_impls::VectorFst;
+use crate::fst_traits::{CoreFst, MutableFst};
+use crate::semirings::Semiring;
+use crate::{Label, StateId, Tr, EPS_LABEL};
+
+/// State of the path being inserted, whose transitions to the states of the paths already
+/// inserted are final.
+struct PendingState<W> {
+    labels: (Label, Label),
+    final_weight: Option<W>,
+    trs: Vec<(Label, Label, StateId)>,
+}
+
+impl<W> PendingState<W> {
+    fn new(labels: (Label, Label)) -> Self {
+        Self {
+            labels,
+            final_weight: None,
+            trs: vec![],
+        }
+    }
+}
+
+type Signature<W> = (Option<W>, Vec<(Label, Label, StateId)>);
+
+/// Builder of the paths sharing their suffixes, registering the states whose suffixes are all
+/// known.
+struct SuffixSharingBuilder<W: Semiring> {
+    fst: VectorFst<W>,
+    register: HashMap<Signature<W>, StateId>,
+}
+
+impl<W: Semiring> SuffixSharingBuilder<W> {
+    /// Returns the registered state equivalent to `state`, created if there is none.
+    fn register(&mut self, state: PendingState<W>) -> Result<StateId> {
+        let signature = (state.final_weight, state.trs);
+        if let Some(s) = self.register.get(&signature) {
+            return Ok(*s);
+        }
+        let s = self.fst.add_state();
+        if let Some(final_weight) = &signature.0 {
+            self.fst.set_final(s, final_weight.clone())?;
+        }
+        for (ilabel, olabel, nextstate) in signature.1.iter() {
+            self.fst
+                .add_tr(s, Tr::new(*ilabel, *olabel, W::one(), *nextstate))?;
+        }
+        self.register.insert(signature, s);
+        Ok(s)
+    }
+
+    /// Registers the states of `path` past the first `len` ones.
+    fn collapse(&mut self, path: &mut Vec<PendingState<W>>, len: usize) -> Result<()> {
+        while path.len() > len {
+            // Can't fail as path is longer than len >= 1.
+            let state = path.pop().unwrap();
+            let (ilabel, olabel) = state.labels;
+            let s = self.register(state)?;
+            path.last_mut().unwrap().trs.push((ilabel, olabel, s));
+        }
+        Ok(())
+    }
+}
+
+/// Labels of the transitions of a path, the shorter sequence being padded with epsilons.
+fn path_labels(ilabels: &[Label], olabels: &[Label]) -> Vec<(Label, Label)> {
+    (0..ilabels.len().max(olabels.len()))
+        .map(|i| {
+            (
+                ilabels.get(i).cloned().unwrap_or(EPS_LABEL),
+                olabels.get(i).cloned().unwrap_or(EPS_LABEL),
+            )
+        })
+        .collect()
+}
+
+impl<W: Semiring> VectorFst<W> {
+    /// Builds the prefix tree of a set of paths given as `(ilabels, olabels, weight)`.
+    ///
+    /// The i-th transition of a path is labeled with the i-th input and output labels, the
+    /// shorter sequence being padded with epsilons. The paths sharing a prefix share the
+    /// corresponding states, the weight of each path is its final weight and the weights of the
+    /// duplicated paths are summed up. The construction is linear in the total length of the
+    /// paths, whereas `union` copies its first argument each time.
+    ///
+    /// If `share_suffixes` is true, the states having the same final weight and the same
+    /// transitions are also merged, so that the common suffixes of the paths are shared. The
+    /// paths must then be sorted in increasing lexicographic order of their pairs of labels :
+    /// the states of a path are merged as soon as all the paths going through them have been
+    /// inserted, so that the memory used is proportional to the size of the result rather than
+    /// to the size of the prefix tree.
+    ///
+    /// # Example
+    /// ```
+    /// # use rustfst::fst_impls::VectorFst;
+    /// # use rustfst::fst_traits::ExpandedFst;
+    /// # use rustfst::semirings::{Semiring, TropicalWeight};
+    /// # use anyhow::Result;
+    /// # fn main() -> Result<()> {
+    /// let paths = vec![
+    ///     (vec![1, 2], vec![3], TropicalWeight::new(1.0)),
+    ///     (vec![1, 3], vec![3], TropicalWeight::new(1.0)),
+    /// ];
+    /// let trie = VectorFst::from_paths(paths.clone(), false)?;
+    /// assert_eq!(trie.num_states(), 4);
+    ///
+    /// let fst = VectorFst::from_paths(paths, true)?;
+    /// assert_eq!(fst.num_states(), 3);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn from_paths<I, L1, L2>(paths: I, share_suffixes: bool) -> Result<Self>
+    where
+        I: IntoIterator<Item = (L1, L2, W)>,
+        L1: AsRef<[Label]>,
+        L2: AsRef<[Label]>,
+    {
+        if share_suffixes {
+            return Self::from_sorted_paths(paths);
+        }
+        let mut fst = Self::new();
+        let start = fst.add_state();
+        fst.set_start(start)?;
+        let mut children: Vec<HashMap<(Label, Label), StateId>> = vec![HashMap::new()];
+        for (ilabels, olabels, weight) in paths {
+            let mut state = start;
+            for labels in path_labels(ilabels.as_ref(), olabels.as_ref()) {
+                state = match children[state as usize].get(&labels) {
+                    Some(nextstate) => *nextstate,
+                    None => {
+                        let nextstate = fst.add_state();
+                        children.push(HashMap::new());
+                        children[state as usize].insert(labels, nextstate);
+                        fst.add_tr(state, Tr::new(labels.0, labels.1, W::one(), nextstate))?;
+                        nextstate
+                    }
+                };
+            }
+            let weight = match fst.final_weight(state)? {
+                Some(w) => w.plus(weight)?,
+                None => weight,
+            };
+            fst.set_final(state, weight)?;
+        }
+        Ok(fst)
+    }
+
+    /// `from_paths` sharing the suffixes of the paths, which must be sorted.
+    fn from_sorted_paths<I, L1, L2>(paths: I) -> Result<Self>
+    where
+        I: IntoIterator<Item = (L1, L2, W)>,
+        L1: AsRef<[Label]>,
+        L2: AsRef<[Label]>,
+    {
+        let mut builder = SuffixSharingBuilder {
+            fst: Self::new(),
+            register: HashMap::new(),
+        };
+        // The start state is pending until the end, the path of the previous path after it.
+        let start = builder.fst.add_state();
+        builder.fst.set_start(start)?;
+        let mut path = vec![PendingState::new((EPS_LABEL, EPS_LABEL))];
+        let mut previous: Option<Vec<(Label, Label)>> = None;
+        for (ilabels, olabels, weight) in paths {
+            let labels = path_labels(ilabels.as_ref(), olabels.as_ref());
+            let prefix_len = match &previous {
+                Some(previous) => {
+                    if previous.as_slice() > labels.as_slice() {
+                        bail!(
+                            "from_paths : the paths must be sorted to share their suffixes, {:?} is after {:?}",
+                            labels,
+                            previous
+                        );
+                    }
+                    previous
+                        .iter()
+                        .zip(labels.iter())
+                        .take_while(|(a, b)| a == b)
+                        .count()
+                }
+                None => 0,
+            };
+            builder.collapse(&mut path, prefix_len + 1)?;
+            path.extend(labels[prefix_len..].iter().map(|l| PendingState::new(*l)));
+            // Can't fail as path contains the start state.
+            let last = path.last_mut().unwrap();
+            last.final_weight = Some(match last.final_weight.take() {
+                Some(w) => w.plus(weight)?,
+                None => weight,
+            });
+            previous = Some(labels);
+        }
+        builder.collapse(&mut path, 1)?;
+
+        // The start state is never merged, to keep its id.
+        let start_state = path.pop().unwrap();
+        let mut fst = builder.fst;
+        if let Some(final_weight) = start_state.final_weight {
+            fst.set_final(start, final_weight)?;
+        }
+        for (ilabel, olabel, nextstate) in start_state.trs {
+            fst.add_tr(start, Tr::new(ilabel, olabel, W::one(), nextstate))?;
+        }
+        Ok(fst)
+    }
+}
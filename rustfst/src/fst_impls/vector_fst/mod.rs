@@ -4,10 +4,12 @@ pub(crate) use data_structure::VectorFstState;
 mod allocable_fst;
 mod data_structure;
 mod expanded_fst;
+mod from_paths;
 mod fst;
 mod iterators;
 mod misc;
 mod mutable_fst;
 mod parse_const;
 mod serializable_fst;
+mod snapshot;
 mod test;
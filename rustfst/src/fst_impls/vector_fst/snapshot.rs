@@ -0,0 +1,65 @@
+use std::sync::Arc;
+
+use crate::fst_impls::vector_fst::VectorFstState;
+use crate::fst_impls::VectorFst;
+use crate::semirings::Semiring;
+use crate::TrsVec;
+
+impl<W: Semiring> VectorFst<W> {
+    /// Takes an immutable view of the FST in its current state, which can be shared between
+    /// threads and queried while the FST keeps being modified.
+    ///
+    /// Contrary to `clone`, which copies all the trs, the snapshot shares them with the FST :
+    /// - the array of the states is copied in full, i.e. an allocation of `num_states` entries,
+    ///   each one cloning its final weight and bumping the reference count of its trs. Taking a
+    ///   snapshot is linear in the number of states whatever the number of modifications made
+    ///   since the previous one, there is no chunking of the states array.
+    /// - the trs of each state are stored behind an `Arc` and are not copied : the first
+    ///   modification of the trs of a state after a snapshot copies all the trs of this state,
+    ///   the states that are not modified keep sharing their trs with the snapshot.
+    ///
+    /// The snapshots don't see the modifications made afterwards. For large FSTs snapshotted
+    /// often, the copy of the states array dominates.
+    ///
+    /// # Example
+    /// ```
+    /// # use rustfst::fst_impls::VectorFst;
+    /// # use rustfst::fst_traits::{CoreFst, ExpandedFst, MutableFst};
+    /// # use rustfst::semirings::TropicalWeight;
+    /// # use rustfst::Tr;
+    /// # use anyhow::Result;
+    /// # fn main() -> Result<()> {
+    /// let mut fst = VectorFst::<TropicalWeight>::new();
+    /// let s0 = fst.add_state();
+    /// fst.set_start(s0)?;
+    /// let snapshot = fst.snapshot();
+    ///
+    /// let handle = std::thread::spawn(move || snapshot.num_states());
+    /// let s1 = fst.add_state();
+    /// fst.add_tr(s0, Tr::new(1, 1, 1.0, s1))?;
+    ///
+    /// assert_eq!(handle.join().unwrap(), 1);
+    /// assert_eq!(fst.num_states(), 2);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn snapshot(&self) -> Arc<VectorFst<W>> {
+        let states = self
+            .states
+            .iter()
+            .map(|state| VectorFstState {
+                final_weight: state.final_weight.clone(),
+                trs: TrsVec(Arc::clone(&state.trs.0)),
+                niepsilons: state.niepsilons,
+                noepsilons: state.noepsilons,
+            })
+            .collect();
+        Arc::new(VectorFst {
+            states,
+            start_state: self.start_state,
+            isymt: self.isymt.clone(),
+            osymt: self.osymt.clone(),
+            properties: self.properties,
+        })
+    }
+}
@@ -8,9 +8,12 @@ mod tests {
     use crate::fst_traits::{
         CoreFst, ExpandedFst, Fst, MutableFst, SerializableFst, StateIterator,
     };
-    use crate::semirings::{ProbabilityWeight, Semiring, TropicalWeight};
+    use crate::semirings::{
+        GallicWeight, ProbabilityWeight, Semiring, StringWeightLeft, TropicalWeight,
+        WeightValidation,
+    };
     use crate::tr::Tr;
-    use crate::{SymbolTable, Trs};
+    use crate::{SymbolTable, Trs, NO_LABEL};
     use rand::seq::SliceRandom;
     use std::sync::Arc;
 
@@ -329,4 +332,176 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_text_with_symts() -> Result<()> {
+        let mut isymt = SymbolTable::new();
+        isymt.add_symbols(vec!["a", "b"]);
+        let isymt = Arc::new(isymt);
+        let text = "0\t1\ta\t3\n1\t2\tb\t0\t0.5\n2\n";
+
+        let fst = VectorFst::<TropicalWeight>::from_text_string_with_symts(
+            text,
+            Some(Arc::clone(&isymt)),
+            None,
+        )?;
+        assert_eq!(fst.input_symbols(), Some(&isymt));
+        assert_eq!(
+            fst.get_trs(0)?.trs()[0],
+            Tr::new(1, 3, TropicalWeight::one(), 1)
+        );
+        let text_ref = "0\t1\ta\t3\t0\n1\t2\tb\t0\t0.5\n2\t0\n";
+        assert_eq!(fst.text_with_symts()?, text_ref);
+        assert_eq!(
+            VectorFst::from_text_string_with_symts(&fst.text_with_symts()?, Some(isymt), None)?,
+            fst
+        );
+
+        let mut unknown = fst.clone();
+        unknown.add_tr(2, Tr::new(3, 3, TropicalWeight::one(), 2))?;
+        assert!(unknown.text_with_symts().is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_display_truncated() -> Result<()> {
+        let mut fst = VectorFst::<TropicalWeight>::new();
+        let s0 = fst.add_state();
+        let s1 = fst.add_state();
+        let s2 = fst.add_state();
+        fst.set_start(s0)?;
+        fst.set_final(s2, TropicalWeight::one())?;
+        fst.add_tr(s0, Tr::new(1, 1, TropicalWeight::one(), s1))?;
+        fst.add_tr(s1, Tr::new(2, 2, TropicalWeight::one(), s2))?;
+        let mut symt = SymbolTable::new();
+        symt.add_symbol("a");
+        symt.add_symbol("b");
+        fst.set_input_symbols(Arc::new(symt));
+
+        let full = format!("{}", fst);
+        assert_eq!(full.lines().count(), 3);
+        assert!(full.lines().next().unwrap().starts_with("0\t1\t\"a\"\t1"));
+
+        assert_eq!(format!("{:.2}", fst), full);
+
+        let truncated = format!("{}", fst.display_truncated(2));
+        let lines: Vec<_> = truncated.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert!(lines[1].starts_with("1\t2\t\"b\"\t2"));
+        assert_eq!(lines[2], "... (1 more states)");
+        assert_eq!(format!("{}", fst.display_truncated(3)), full);
+
+        assert_eq!(
+            fst.summary(),
+            "3 states, 2 transitions, 1 final states, start state 0, 3 input symbols, no output symbols"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_validate_weights() -> Result<()> {
+        let mut fst = VectorFst::<TropicalWeight>::new();
+        let s0 = fst.add_state();
+        let s1 = fst.add_state();
+        fst.set_start(s0)?;
+        fst.set_final(s1, TropicalWeight::one())?;
+        fst.add_tr(s0, Tr::new(1, 1, f32::NEG_INFINITY, s1))?;
+        fst.validate_weights(WeightValidation::Lenient)?;
+        assert!(fst.validate_weights(WeightValidation::Strict).is_err());
+
+        fst.add_tr(s0, Tr::new(2, 2, f32::NAN, s1))?;
+        assert!(fst.validate_weights(WeightValidation::Lenient).is_err());
+        assert!(TropicalWeight::new_checked(f32::NAN, WeightValidation::Lenient).is_err());
+        assert!(TropicalWeight::new_checked(1.0, WeightValidation::Strict).is_ok());
+        Ok(())
+    }
+
+    #[test]
+    fn test_validate_composite_weights() -> Result<()> {
+        let nan = GallicWeight::from((vec![1], TropicalWeight::new(f32::NAN)));
+        assert!(nan.is_nan());
+        let neg_inf = GallicWeight::from((vec![1, 2], TropicalWeight::new(f32::NEG_INFINITY)));
+        assert!(!neg_inf.is_nan());
+        assert!(!neg_inf.is_member());
+        assert!(!StringWeightLeft::from(vec![1, NO_LABEL]).is_member());
+
+        let mut fst = VectorFst::<GallicWeight<TropicalWeight>>::new();
+        let s0 = fst.add_state();
+        let s1 = fst.add_state();
+        fst.set_start(s0)?;
+        fst.set_final(s1, GallicWeight::one())?;
+        fst.add_tr(s0, Tr::new(1, 1, neg_inf, s1))?;
+        fst.validate_weights(WeightValidation::Lenient)?;
+        assert!(fst.validate_weights(WeightValidation::Strict).is_err());
+
+        fst.add_tr(s0, Tr::new(2, 2, nan, s1))?;
+        assert!(fst.validate_weights(WeightValidation::Lenient).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_from_paths() -> Result<()> {
+        let paths = vec![
+            (vec![1, 2, 3], vec![4], TropicalWeight::new(1.0)),
+            (vec![1, 2], vec![4], TropicalWeight::new(2.0)),
+            (vec![5, 2, 3], vec![6], TropicalWeight::new(1.0)),
+            (vec![1, 2], vec![4], TropicalWeight::new(0.5)),
+        ];
+        let trie = VectorFst::from_paths(paths.clone(), false)?;
+        assert_eq!(trie.num_states(), 7);
+        assert_eq!(trie.final_weight(2)?, Some(TropicalWeight::new(0.5)));
+        assert_eq!(
+            trie.get_trs(1)?.trs(),
+            &[Tr::<TropicalWeight>::new(2, 0, TropicalWeight::one(), 2)]
+        );
+
+        // The suffixes can only be shared on the fly if the paths are sorted.
+        assert!(VectorFst::from_paths(paths, true).is_err());
+        let sorted_paths = vec![
+            (vec![1, 2], vec![4], TropicalWeight::new(2.0)),
+            (vec![1, 2], vec![4], TropicalWeight::new(0.5)),
+            (vec![1, 2, 3], vec![4], TropicalWeight::new(1.0)),
+            (vec![5, 2, 3], vec![6], TropicalWeight::new(1.0)),
+        ];
+        // The suffix "3" of the last two paths is shared.
+        let fst = VectorFst::from_paths(sorted_paths, true)?;
+        assert_eq!(fst.num_states(), 6);
+        assert_eq!(fst.start(), Some(0));
+        let finals: Vec<_> = fst
+            .states_iter()
+            .filter_map(|s| fst.final_weight(s).unwrap())
+            .collect();
+        assert_eq!(
+            finals,
+            vec![TropicalWeight::new(1.0), TropicalWeight::new(0.5)]
+        );
+        Ok(())
+    }
+    #[test]
+    fn test_snapshot() -> Result<()> {
+        let mut fst = VectorFst::<TropicalWeight>::new();
+        fst.add_states(3);
+        fst.set_start(0)?;
+        fst.add_tr(0, Tr::new(1, 1, 1.0, 1))?;
+        fst.add_tr(1, Tr::new(2, 2, 1.0, 2))?;
+        fst.set_final(2, 0.0)?;
+        let fst_ref = fst.clone();
+
+        let snapshot = fst.snapshot();
+        // The trs are shared until they are modified.
+        assert!(Arc::ptr_eq(&fst.states[1].trs.0, &snapshot.states[1].trs.0));
+
+        fst.add_tr(0, Tr::new(3, 3, 1.0, 2))?;
+        fst.set_final(1, 2.0)?;
+        fst.add_state();
+        assert!(Arc::ptr_eq(&fst.states[1].trs.0, &snapshot.states[1].trs.0));
+        assert!(!Arc::ptr_eq(
+            &fst.states[0].trs.0,
+            &snapshot.states[0].trs.0
+        ));
+        assert_eq!(*snapshot, fst_ref);
+        assert_eq!(snapshot.num_trs(0)?, 1);
+        assert_eq!(fst.num_trs(0)?, 2);
+        Ok(())
+    }
 }
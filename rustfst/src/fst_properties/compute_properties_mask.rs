@@ -0,0 +1,322 @@
+use std::collections::HashSet;
+
+use anyhow::Result;
+
+use crate::fst_properties::{compute_fst_properties, known_properties, FstProperties};
+use crate::fst_traits::ExpandedFst;
+use crate::semirings::Semiring;
+use crate::{StateId, Tr, Trs, EPS_LABEL};
+
+/// Properties checked transition by transition, as the property holding until a transition
+/// contradicts it and its negation.
+const LOCAL_PROPERTIES: [(FstProperties, FstProperties); 10] = [
+    (FstProperties::ACCEPTOR, FstProperties::NOT_ACCEPTOR),
+    (
+        FstProperties::I_DETERMINISTIC,
+        FstProperties::NOT_I_DETERMINISTIC,
+    ),
+    (
+        FstProperties::O_DETERMINISTIC,
+        FstProperties::NOT_O_DETERMINISTIC,
+    ),
+    (FstProperties::NO_EPSILONS, FstProperties::EPSILONS),
+    (FstProperties::NO_I_EPSILONS, FstProperties::I_EPSILONS),
+    (FstProperties::NO_O_EPSILONS, FstProperties::O_EPSILONS),
+    (
+        FstProperties::I_LABEL_SORTED,
+        FstProperties::NOT_I_LABEL_SORTED,
+    ),
+    (
+        FstProperties::O_LABEL_SORTED,
+        FstProperties::NOT_O_LABEL_SORTED,
+    ),
+    (FstProperties::UNWEIGHTED, FstProperties::WEIGHTED),
+    (FstProperties::TOP_SORTED, FstProperties::NOT_TOP_SORTED),
+];
+
+/// Computes only the properties of the FST in `mask`, without attaching them to the FST.
+/// Every property whose positive or negative bit is in `mask` is known in the result, and
+/// no other property is set.
+///
+/// Unlike `compute_fst_properties`, which computes all the properties as soon as one of them
+/// needs a pass over the transitions, each property is computed by its own algorithm :
+/// the properties local to the transitions, e.g. `I_LABEL_SORTED`, stop the pass as soon as
+/// they are all contradicted, and `ACYCLIC` is computed by a plain depth-first search instead
+/// of the computation of the strongly connected components. The stored properties are used
+/// if they already know `mask`.
+///
+/// # Example
+/// ```
+/// # use rustfst::fst_impls::VectorFst;
+/// # use rustfst::fst_properties::{compute_properties_mask, FstProperties};
+/// # use rustfst::fst_traits::MutableFst;
+/// # use rustfst::semirings::{Semiring, TropicalWeight};
+/// # use rustfst::Tr;
+/// # use anyhow::Result;
+/// # fn main() -> Result<()> {
+/// let mut fst = VectorFst::<TropicalWeight>::new();
+/// fst.add_states(2);
+/// fst.set_start(0)?;
+/// fst.add_tr(0, Tr::new(2, 2, 1.0, 1))?;
+/// fst.add_tr(0, Tr::new(1, 1, 1.0, 1))?;
+/// fst.add_tr(1, Tr::new(3, 3, 1.0, 0))?;
+///
+/// let props = compute_properties_mask(
+///     &fst,
+///     FstProperties::I_LABEL_SORTED | FstProperties::ACYCLIC,
+/// )?;
+/// assert_eq!(props, FstProperties::NOT_I_LABEL_SORTED | FstProperties::CYCLIC);
+/// # Ok(())
+/// # }
+/// ```
+pub fn compute_properties_mask<W: Semiring, F: ExpandedFst<W>>(
+    fst: &F,
+    mask: FstProperties,
+) -> Result<FstProperties> {
+    let requested = known_properties(mask) & FstProperties::trinary_properties();
+    let fst_props = fst.properties();
+    if fst_props.knows(requested) {
+        return Ok(fst_props & requested);
+    }
+
+    let local = LOCAL_PROPERTIES
+        .iter()
+        .fold(FstProperties::empty(), |acc, (holds, fails)| {
+            acc | *holds | *fails
+        });
+    let cyclic = FstProperties::CYCLIC | FstProperties::ACYCLIC;
+    let mut others = requested & !local & !cyclic;
+
+    let mut props = FstProperties::empty();
+    if !others.is_empty() {
+        // The acyclicity comes for free with the other properties computed by a traversal.
+        others |= requested & cyclic;
+        let mut known = FstProperties::empty();
+        props |= compute_fst_properties(fst, others, &mut known, false)? & others;
+    } else if requested.intersects(cyclic) {
+        props |= if is_cyclic(fst)? {
+            FstProperties::CYCLIC
+        } else {
+            FstProperties::ACYCLIC
+        };
+    }
+    if requested.intersects(local) {
+        props |= compute_local_properties(fst, requested)?;
+    }
+    Ok(props)
+}
+
+/// Records that the property `holds` is contradicted by the FST.
+fn contradict(
+    props: &mut FstProperties,
+    undecided: &mut FstProperties,
+    holds: FstProperties,
+    fails: FstProperties,
+) {
+    props.remove(holds);
+    props.insert(fails);
+    undecided.remove(holds);
+}
+
+fn compute_local_properties<W: Semiring, F: ExpandedFst<W>>(
+    fst: &F,
+    requested: FstProperties,
+) -> Result<FstProperties> {
+    let mut props = FstProperties::empty();
+    for (holds, fails) in LOCAL_PROPERTIES.iter() {
+        if requested.intersects(*holds | *fails) {
+            props |= *holds;
+        }
+    }
+    // Properties that no transition has contradicted yet.
+    let mut undecided = props;
+
+    let mut ilabels = HashSet::new();
+    let mut olabels = HashSet::new();
+    for state in 0..(fst.num_states() as StateId) {
+        if undecided.is_empty() {
+            break;
+        }
+        ilabels.clear();
+        olabels.clear();
+        let trs = fst.get_trs(state)?;
+        let mut prev_tr: Option<&Tr<W>> = None;
+        for tr in trs.trs() {
+            if undecided.contains(FstProperties::ACCEPTOR) && tr.ilabel != tr.olabel {
+                contradict(
+                    &mut props,
+                    &mut undecided,
+                    FstProperties::ACCEPTOR,
+                    FstProperties::NOT_ACCEPTOR,
+                );
+            }
+            if undecided.contains(FstProperties::I_DETERMINISTIC) && !ilabels.insert(tr.ilabel) {
+                contradict(
+                    &mut props,
+                    &mut undecided,
+                    FstProperties::I_DETERMINISTIC,
+                    FstProperties::NOT_I_DETERMINISTIC,
+                );
+            }
+            if undecided.contains(FstProperties::O_DETERMINISTIC) && !olabels.insert(tr.olabel) {
+                contradict(
+                    &mut props,
+                    &mut undecided,
+                    FstProperties::O_DETERMINISTIC,
+                    FstProperties::NOT_O_DETERMINISTIC,
+                );
+            }
+            if undecided.contains(FstProperties::NO_EPSILONS)
+                && tr.ilabel == EPS_LABEL
+                && tr.olabel == EPS_LABEL
+            {
+                contradict(
+                    &mut props,
+                    &mut undecided,
+                    FstProperties::NO_EPSILONS,
+                    FstProperties::EPSILONS,
+                );
+            }
+            if undecided.contains(FstProperties::NO_I_EPSILONS) && tr.ilabel == EPS_LABEL {
+                contradict(
+                    &mut props,
+                    &mut undecided,
+                    FstProperties::NO_I_EPSILONS,
+                    FstProperties::I_EPSILONS,
+                );
+            }
+            if undecided.contains(FstProperties::NO_O_EPSILONS) && tr.olabel == EPS_LABEL {
+                contradict(
+                    &mut props,
+                    &mut undecided,
+                    FstProperties::NO_O_EPSILONS,
+                    FstProperties::O_EPSILONS,
+                );
+            }
+            if let Some(prev_tr) = prev_tr {
+                if undecided.contains(FstProperties::I_LABEL_SORTED) && tr.ilabel < prev_tr.ilabel {
+                    contradict(
+                        &mut props,
+                        &mut undecided,
+                        FstProperties::I_LABEL_SORTED,
+                        FstProperties::NOT_I_LABEL_SORTED,
+                    );
+                }
+                if undecided.contains(FstProperties::O_LABEL_SORTED) && tr.olabel < prev_tr.olabel {
+                    contradict(
+                        &mut props,
+                        &mut undecided,
+                        FstProperties::O_LABEL_SORTED,
+                        FstProperties::NOT_O_LABEL_SORTED,
+                    );
+                }
+            }
+            if undecided.contains(FstProperties::UNWEIGHTED)
+                && !tr.weight.is_one()
+                && !tr.weight.is_zero()
+            {
+                contradict(
+                    &mut props,
+                    &mut undecided,
+                    FstProperties::UNWEIGHTED,
+                    FstProperties::WEIGHTED,
+                );
+            }
+            if undecided.contains(FstProperties::TOP_SORTED) && tr.nextstate <= state {
+                contradict(
+                    &mut props,
+                    &mut undecided,
+                    FstProperties::TOP_SORTED,
+                    FstProperties::NOT_TOP_SORTED,
+                );
+            }
+            prev_tr = Some(tr);
+        }
+        if undecided.contains(FstProperties::UNWEIGHTED) {
+            if let Some(final_weight) = fst.final_weight(state)? {
+                if !final_weight.is_one() {
+                    contradict(
+                        &mut props,
+                        &mut undecided,
+                        FstProperties::UNWEIGHTED,
+                        FstProperties::WEIGHTED,
+                    );
+                }
+            }
+        }
+    }
+    Ok(props)
+}
+
+/// Whether the FST has a cycle, found by an iterative depth-first search from every state.
+fn is_cyclic<W: Semiring, F: ExpandedFst<W>>(fst: &F) -> Result<bool> {
+    const WHITE: u8 = 0;
+    const GREY: u8 = 1;
+    const BLACK: u8 = 2;
+    let mut colors = vec![WHITE; fst.num_states()];
+    let mut stack = vec![];
+    for root in 0..(fst.num_states() as StateId) {
+        if colors[root as usize] != WHITE {
+            continue;
+        }
+        colors[root as usize] = GREY;
+        stack.push((root, fst.get_trs(root)?, 0));
+        while let Some((state, trs, pos)) = stack.last_mut() {
+            let nextstate = trs.trs().get(*pos).map(|tr| tr.nextstate);
+            *pos += 1;
+            match nextstate {
+                Some(nextstate) => match colors[nextstate as usize] {
+                    WHITE => {
+                        colors[nextstate as usize] = GREY;
+                        let trs = fst.get_trs(nextstate)?;
+                        stack.push((nextstate, trs, 0));
+                    }
+                    GREY => return Ok(true),
+                    _ => {}
+                },
+                None => {
+                    colors[*state as usize] = BLACK;
+                    stack.pop();
+                }
+            }
+        }
+    }
+    Ok(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use proptest::prelude::*;
+
+    use crate::fst_impls::VectorFst;
+    use crate::semirings::TropicalWeight;
+
+    fn masks() -> Vec<FstProperties> {
+        let mut masks: Vec<_> = LOCAL_PROPERTIES.iter().map(|(holds, _)| *holds).collect();
+        masks.push(FstProperties::ACYCLIC);
+        masks.push(FstProperties::I_LABEL_SORTED | FstProperties::ACYCLIC);
+        masks.push(FstProperties::COACCESSIBLE | FstProperties::NOT_STRING);
+        masks.push(FstProperties::trinary_properties());
+        masks
+    }
+
+    proptest! {
+        #[test]
+        fn test_proptest_compute_properties_mask(fst in any::<VectorFst::<TropicalWeight>>()) {
+            let mut known = FstProperties::empty();
+            let all_props = compute_fst_properties(
+                &fst,
+                FstProperties::all_properties(),
+                &mut known,
+                false,
+            ).unwrap();
+            for mask in masks() {
+                let requested = known_properties(mask) & FstProperties::trinary_properties();
+                let props = compute_properties_mask(&fst, mask).unwrap();
+                prop_assert_eq!(props, all_props & requested);
+            }
+        }
+    }
+}
@@ -1,4 +1,5 @@
 mod compute_fst_properties;
+mod compute_properties_mask;
 mod mutate_properties;
 pub(crate) mod properties;
 mod utils;
@@ -9,5 +10,6 @@ pub mod mutable_properties {
 }
 
 pub use self::compute_fst_properties::compute_fst_properties;
+pub use self::compute_properties_mask::compute_properties_mask;
 pub use self::properties::FstProperties;
 pub use self::utils::{compat_properties, known_properties};
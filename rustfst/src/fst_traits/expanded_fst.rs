@@ -1,11 +1,12 @@
-use anyhow::Result;
+use anyhow::{Context, Result};
 use std::ops::Range;
+use std::sync::Arc;
 
-use crate::algorithms::fst_convert_from_ref;
 use crate::algorithms::tr_mappers::QuantizeMapper;
+use crate::algorithms::{content_hash, fst_convert_from_ref};
 use crate::fst_traits::{AllocableFst, Fst, FstIntoIterator, MutableFst};
-use crate::semirings::{Semiring, WeightQuantize};
-use crate::{StateId, Trs};
+use crate::semirings::{Semiring, SerializableSemiring, WeightQuantize, WeightValidation};
+use crate::{StateId, SymbolTable, Trs};
 
 /// Trait defining the necessary methods that should implement an ExpandedFST e.g
 /// a FST where all the states are already computed and not computed on the fly.
@@ -32,6 +33,56 @@ pub trait ExpandedFst<W: Semiring>: Fst<W> + Clone + PartialEq + FstIntoIterator
     /// ```
     fn num_states(&self) -> usize;
 
+    /// One line description of the FST : its numbers of states, transitions and final states,
+    /// its start state and its symbol tables. Useful to log big FSTs, whose `Display` is
+    /// unreadable.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use rustfst::fst_traits::{MutableFst, ExpandedFst};
+    /// # use rustfst::fst_impls::VectorFst;
+    /// # use rustfst::semirings::{Semiring, TropicalWeight};
+    /// # use rustfst::Tr;
+    /// # use anyhow::Result;
+    /// # fn main() -> Result<()> {
+    /// let mut fst = VectorFst::<TropicalWeight>::new();
+    /// let s0 = fst.add_state();
+    /// let s1 = fst.add_state();
+    /// fst.set_start(s0)?;
+    /// fst.set_final(s1, TropicalWeight::one())?;
+    /// fst.add_tr(s0, Tr::new(1, 2, 0.5, s1))?;
+    /// assert_eq!(
+    ///     fst.summary(),
+    ///     "2 states, 1 transitions, 1 final states, start state 0, no input symbols, no output symbols"
+    /// );
+    /// # Ok(())
+    /// # }
+    /// ```
+    fn summary(&self) -> String {
+        let num_trs: usize = self
+            .states_range()
+            .map(|s| unsafe { self.num_trs_unchecked(s) })
+            .sum();
+        let start = match self.start() {
+            Some(s) => format!("start state {}", s),
+            None => "no start state".to_string(),
+        };
+        let symt = |symt: Option<&Arc<SymbolTable>>, name: &str| match symt {
+            Some(symt) => format!("{} {} symbols", symt.len(), name),
+            None => format!("no {} symbols", name),
+        };
+        format!(
+            "{} states, {} transitions, {} final states, {}, {}, {}",
+            self.num_states(),
+            num_trs,
+            self.final_states_iter().count(),
+            start,
+            symt(self.input_symbols(), "input"),
+            symt(self.output_symbols(), "output"),
+        )
+    }
+
     fn states_range(&self) -> Range<StateId> {
         0..(self.num_states() as StateId)
     }
@@ -86,6 +137,25 @@ pub trait ExpandedFst<W: Semiring>: Fst<W> + Clone + PartialEq + FstIntoIterator
         true
     }
 
+    /// Returns an error on the first final weight or tr weight which is not valid according
+    /// to `validation`, e.g. a NaN which would silently ruin the shortest paths.
+    fn validate_weights(&self, validation: WeightValidation) -> Result<()> {
+        for state in self.states_range() {
+            if let Some(final_weight) = unsafe { self.final_weight_unchecked(state) } {
+                final_weight
+                    .validate(validation)
+                    .with_context(|| format!("Invalid final weight of state {}", state))?;
+            }
+            let trs = unsafe { self.get_trs_unchecked(state) };
+            for (i, tr) in trs.trs().iter().enumerate() {
+                tr.weight
+                    .validate(validation)
+                    .with_context(|| format!("Invalid weight of tr {} of state {}", i, state))?;
+            }
+        }
+        Ok(())
+    }
+
     fn quantize<F2: MutableFst<W> + AllocableFst<W>>(&self) -> Result<F2>
     where
         W: WeightQuantize,
@@ -95,4 +165,14 @@ pub trait ExpandedFst<W: Semiring>: Fst<W> + Clone + PartialEq + FstIntoIterator
         fst_tr_map.tr_map(&mut mapper)?;
         Ok(fst_tr_map)
     }
+
+    /// Stable 128 bits fingerprint of the structure, the quantized weights and the symbol
+    /// tables of the FST, independent of the numbering of the states. See
+    /// [`content_hash`](crate::algorithms::content_hash).
+    fn content_hash(&self) -> Result<u128>
+    where
+        W: WeightQuantize + SerializableSemiring,
+    {
+        content_hash(self)
+    }
 }
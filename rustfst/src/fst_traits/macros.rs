@@ -1,15 +1,25 @@
 macro_rules! display_single_state {
-    ($fst:expr, $state_id:expr, $f: expr, $show_weight_one: expr, $use_symt: expr) => {
+    (
+        $fst:expr,
+        $state_id:expr,
+        $f: expr,
+        $show_weight_one: expr,
+        $use_symt: expr,
+        $quote_symt: expr
+    ) => {
         for tr in $fst.get_trs($state_id).unwrap().trs() {
             let s_ilabel = if !$use_symt {
                 format!("{}", tr.ilabel)
             } else if let Some(symt) = $fst.input_symbols() {
-                format!(
-                    "{:?}",
-                    symt.get_symbol(tr.ilabel)
-                        .map(|v| v.to_string())
-                        .unwrap_or_else(|| format!("{}", tr.ilabel))
-                )
+                let symbol = symt
+                    .get_symbol(tr.ilabel)
+                    .map(|v| v.to_string())
+                    .unwrap_or_else(|| format!("{}", tr.ilabel));
+                if $quote_symt {
+                    format!("{:?}", symbol)
+                } else {
+                    symbol
+                }
             } else {
                 format!("{}", tr.ilabel)
             };
@@ -17,12 +27,15 @@ macro_rules! display_single_state {
             let s_olabel = if !$use_symt {
                 format!("{}", tr.olabel)
             } else if let Some(symt) = $fst.output_symbols() {
-                format!(
-                    "{:?}",
-                    symt.get_symbol(tr.olabel)
-                        .map(|v| v.to_string())
-                        .unwrap_or_else(|| format!("{}", tr.olabel))
-                )
+                let symbol = symt
+                    .get_symbol(tr.olabel)
+                    .map(|v| v.to_string())
+                    .unwrap_or_else(|| format!("{}", tr.olabel));
+                if $quote_symt {
+                    format!("{:?}", symbol)
+                } else {
+                    symbol
+                }
             } else {
                 format!("{}", tr.olabel)
             };
@@ -45,19 +58,66 @@ macro_rules! display_single_state {
 
 macro_rules! write_fst {
     ($fst:expr, $f:expr, $show_weight_one: expr, $use_symt: expr) => {
+        write_fst!($fst, $f, $show_weight_one, $use_symt, None, false)
+    };
+    (
+        $fst:expr,
+        $f:expr,
+        $show_weight_one: expr,
+        $use_symt: expr,
+        $max_states: expr,
+        $quote_symt: expr
+    ) => {
         if let Some(start_state) = $fst.start() {
+            let max_states: Option<usize> = $max_states;
+            let max_states = max_states.unwrap_or(usize::MAX);
+            let mut num_displayed_states = 0;
+            // First state not displayed. The states are expected to be iterated in increasing
+            // order, so that all the states above it are hidden.
+            let mut first_hidden_state = None;
+
             // Firstly print the trs leaving the start state
-            display_single_state!($fst, start_state, $f, $show_weight_one, $use_symt);
+            if max_states > 0 {
+                display_single_state!(
+                    $fst,
+                    start_state,
+                    $f,
+                    $show_weight_one,
+                    $use_symt,
+                    $quote_symt
+                );
+                num_displayed_states += 1;
+            }
 
             // Secondly, print the trs leaving all the other states
             for state_id in $fst.states_iter() {
                 if state_id != start_state {
-                    display_single_state!($fst, state_id, $f, $show_weight_one, $use_symt);
+                    if num_displayed_states >= max_states {
+                        first_hidden_state = Some(state_id);
+                        break;
+                    }
+                    display_single_state!(
+                        $fst,
+                        state_id,
+                        $f,
+                        $show_weight_one,
+                        $use_symt,
+                        $quote_symt
+                    );
+                    num_displayed_states += 1;
                 }
             }
 
             // Finally, print the final states with their weight
             for final_state in $fst.final_states_iter() {
+                let hidden = match first_hidden_state {
+                    _ if max_states == 0 => true,
+                    Some(s) => final_state != start_state && final_state >= s,
+                    None => false,
+                };
+                if hidden {
+                    continue;
+                }
                 let final_weight =
                     unsafe { $fst.final_weight_unchecked(final_state).unsafe_unwrap() };
                 if final_weight.is_one() && !$show_weight_one {
@@ -66,15 +126,27 @@ macro_rules! write_fst {
                     writeln!($f, "{}\t{}", &final_state, &final_weight)?;
                 }
             }
+
+            if first_hidden_state.is_some() || max_states == 0 {
+                let num_states = $fst.states_iter().count();
+                writeln!(
+                    $f,
+                    "... ({} more states)",
+                    num_states - num_displayed_states
+                )?;
+            }
         }
     };
 }
 
 macro_rules! display_fst_trait {
     ($semiring:tt, $fst_type:ty) => {
+        /// Writes the FST in the text format of OpenFST, the labels being resolved with the
+        /// symbol tables when they are attached and quoted. Use
+        /// `SerializableFst::display_truncated` to only display the first states of big FSTs.
         impl<$semiring: 'static + SerializableSemiring> fmt::Display for $fst_type {
             fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-                write_fst!(self, f, true, true);
+                write_fst!(self, f, true, true, None, true);
                 Ok(())
             }
         }
@@ -4,8 +4,9 @@ pub use self::fst::{CoreFst, Fst};
 pub use self::iterators::{FstIntoIterator, FstIterData, FstIterator, StateIterator};
 pub use self::mutable_fst::MutableFst;
 pub use self::paths_iterator::PathsIterator;
-pub use self::serializable_fst::SerializableFst;
+pub use self::serializable_fst::{SerializableFst, TruncatedDisplay};
 pub use self::string_paths_iterator::StringPathsIterator;
+pub use self::trs_slice_fst::TrsSliceFst;
 
 #[macro_use]
 mod macros;
@@ -18,3 +19,4 @@ mod mutable_fst;
 mod paths_iterator;
 mod serializable_fst;
 mod string_paths_iterator;
+mod trs_slice_fst;
@@ -459,6 +459,49 @@ pub trait MutableFst<W: Semiring>: ExpandedFst<W> {
         }
     }
 
+    /// Applies a sequence of edits to the Fst as a whole : if `edits` fails, the Fst is
+    /// restored to its state before the transaction and the error is returned, so that a
+    /// failing step of a graph surgery doesn't leave the Fst half-mutated.
+    ///
+    /// The edits are applied directly to the Fst, hence they can read the result of the
+    /// previous ones. To be rolled back to, the whole Fst is cloned before the edits, whatever
+    /// their number : a transaction costs a copy of all the states, transitions and final
+    /// weights of the Fst, even for a single edit, the symbol tables being shared.
+    ///
+    /// # Example
+    /// ```
+    /// # use rustfst::fst_impls::VectorFst;
+    /// # use rustfst::fst_traits::{CoreFst, ExpandedFst, MutableFst};
+    /// # use rustfst::semirings::TropicalWeight;
+    /// # use rustfst::Tr;
+    /// # use anyhow::Result;
+    /// # fn main() -> Result<()> {
+    /// let mut fst = VectorFst::<TropicalWeight>::new();
+    /// let s0 = fst.add_state();
+    /// fst.set_start(s0)?;
+    ///
+    /// // Setting a state that doesn't exist as final fails : the new state and tr are
+    /// // rolled back.
+    /// let res = fst.transaction(|tx| {
+    ///     let s1 = tx.add_state();
+    ///     tx.add_tr(s0, Tr::new(1, 1, 1.0, s1))?;
+    ///     tx.set_final(10, 1.0)
+    /// });
+    /// assert!(res.is_err());
+    /// assert_eq!(fst.num_states(), 1);
+    /// assert_eq!(fst.num_trs(s0)?, 0);
+    /// # Ok(())
+    /// # }
+    /// ```
+    fn transaction<T, E: FnOnce(&mut Self) -> Result<T>>(&mut self, edits: E) -> Result<T> {
+        let backup = self.clone();
+        let res = edits(self);
+        if res.is_err() {
+            *self = backup;
+        }
+        res
+    }
+
     /// Destructively relabel the Fst with new Symbol Tables.
     ///
     /// Relabelling refers to the operation where all the labels of an Fst are mapped to the equivalent labels
@@ -549,6 +592,7 @@ pub trait MutableFst<W: Semiring>: ExpandedFst<W> {
 mod tests {
     use super::*;
     use crate::fst;
+    use crate::fst_traits::CoreFst;
     use crate::prelude::{TropicalWeight, VectorFst};
     use crate::symt;
     use crate::utils::transducer;
@@ -609,4 +653,31 @@ mod tests {
 
         Ok(())
     }
+    #[test]
+    fn test_transaction() -> Result<()> {
+        let fst: VectorFst<TropicalWeight> = fst![1, 2 => 3, 4; 0.5];
+
+        let mut fst_1 = fst.clone();
+        let state = fst_1.transaction(|tx| {
+            let state = tx.add_state();
+            tx.set_final(state, 1.0)?;
+            tx.add_tr(0, Tr::new(5, 5, 1.0, state))?;
+            Ok(state)
+        })?;
+        assert_eq!(fst_1.num_states(), fst.num_states() + 1);
+        assert!(fst_1.is_final(state)?);
+
+        let mut fst_2 = fst.clone();
+        fst_2.set_input_symbols(Arc::new(symt!["a", "b"]));
+        let fst_ref = fst_2.clone();
+        let res = fst_2.transaction(|tx| {
+            tx.take_input_symbols();
+            tx.del_state(0)?;
+            tx.set_start(10)
+        });
+        assert!(res.is_err());
+        assert_eq!(fst_2, fst_ref);
+        assert!(fst_2.input_symbols().is_some());
+        Ok(())
+    }
 }
@@ -1,15 +1,18 @@
-use std::fs::File;
+use std::fmt;
+use std::fs::{read_to_string, File};
 use std::io::{BufWriter, LineWriter, Write};
+use std::marker::PhantomData;
 use std::path::Path;
+use std::sync::Arc;
 
 use anyhow::{Context, Result};
 use unsafe_unwrap::UnsafeUnwrap;
 
 use crate::fst_traits::ExpandedFst;
 use crate::parsers::text_fst::ParsedTextFst;
-use crate::semirings::SerializableSemiring;
+use crate::semirings::{SerializableSemiring, WeightValidation};
 use crate::Trs;
-use crate::{DrawingConfig, StateId};
+use crate::{DrawingConfig, StateId, SymbolTable};
 
 /// Trait definining the methods an Fst must implement to be serialized and deserialized.
 pub trait SerializableFst<W: SerializableSemiring>: ExpandedFst<W> {
@@ -36,6 +39,18 @@ pub trait SerializableFst<W: SerializableSemiring>: ExpandedFst<W> {
         })?;
         Self::load(&data)
     }
+    /// Loads an FST from a file in binary format and checks its weights with
+    /// `ExpandedFst::validate_weights`.
+    fn read_with_validation<P: AsRef<Path>>(
+        path_bin_fst: P,
+        validation: WeightValidation,
+    ) -> Result<Self> {
+        let fst = Self::read(path_bin_fst.as_ref())?;
+        fst.validate_weights(validation)
+            .with_context(|| format!("Invalid FST : {:?}", path_bin_fst.as_ref()))?;
+        Ok(fst)
+    }
+
     /// Writes the FST to a file in binary format.
     fn write<P: AsRef<Path>>(&self, path_bin_fst: P) -> Result<()> {
         let output = std::fs::File::create(path_bin_fst.as_ref()).with_context(|| {
@@ -65,6 +80,50 @@ pub trait SerializableFst<W: SerializableSemiring>: ExpandedFst<W> {
         Self::from_parsed_fst_text(parsed_text_fst)
     }
 
+    /// Deserializes a wFST in text whose labels are the symbols of `isymt` and `osymt`, as
+    /// compiled by `fstcompile --isymbols --osymbols`, and attaches these symbol tables. The
+    /// labels of a side without symbol table are integers.
+    fn from_text_string_with_symts(
+        fst_string: &str,
+        isymt: Option<Arc<SymbolTable>>,
+        osymt: Option<Arc<SymbolTable>>,
+    ) -> Result<Self> {
+        let parsed_text_fst =
+            ParsedTextFst::from_string_with_symts(fst_string, isymt.as_deref(), osymt.as_deref())?;
+        let mut fst = Self::from_parsed_fst_text(parsed_text_fst)?;
+        if let Some(isymt) = isymt {
+            fst.set_input_symbols(isymt);
+        }
+        if let Some(osymt) = osymt {
+            fst.set_output_symbols(osymt);
+        }
+        Ok(fst)
+    }
+
+    /// Deserializes a wFST in text from a path, its labels being the symbols of `isymt` and
+    /// `osymt`. See `from_text_string_with_symts`.
+    fn read_text_with_symts<P: AsRef<Path>>(
+        path_text_fst: P,
+        isymt: Option<Arc<SymbolTable>>,
+        osymt: Option<Arc<SymbolTable>>,
+    ) -> Result<Self> {
+        let fst_string = read_to_string(path_text_fst.as_ref())
+            .with_context(|| format!("Can't open text FST file : {:?}", path_text_fst.as_ref()))?;
+        Self::from_text_string_with_symts(&fst_string, isymt, osymt)
+    }
+
+    /// Deserializes a wFST in text from a path and checks its weights with
+    /// `ExpandedFst::validate_weights`.
+    fn read_text_with_validation<P: AsRef<Path>>(
+        path_text_fst: P,
+        validation: WeightValidation,
+    ) -> Result<Self> {
+        let fst = Self::read_text(path_text_fst.as_ref())?;
+        fst.validate_weights(validation)
+            .with_context(|| format!("Invalid FST : {:?}", path_text_fst.as_ref()))?;
+        Ok(fst)
+    }
+
     /// Serializes the FST as a text file in a format compatible with OpenFST.
     fn write_text<P: AsRef<Path>>(&self, path_output: P) -> Result<()> {
         let buffer = File::create(path_output.as_ref())?;
@@ -81,6 +140,75 @@ pub trait SerializableFst<W: SerializableSemiring>: ExpandedFst<W> {
         Ok(String::from_utf8(line_writer.into_inner()?)?)
     }
 
+    /// Serializes the FST as a text file whose labels are the symbols of the attached symbol
+    /// tables, as printed by `fstprint --isymbols --osymbols`. The labels of a side without
+    /// symbol table are integers. Fails if a label is missing from its symbol table.
+    fn write_text_with_symts<P: AsRef<Path>>(&self, path_output: P) -> Result<()> {
+        let text = self.text_with_symts()?;
+        std::fs::write(path_output.as_ref(), text)
+            .with_context(|| format!("Cannot create text FST file : {:?}", path_output.as_ref()))
+    }
+
+    /// Writes the text representation of the FST into a String, the labels being the symbols
+    /// of the attached symbol tables. See `write_text_with_symts`.
+    fn text_with_symts(&self) -> Result<String> {
+        for state in self.states_iter() {
+            for tr in self.get_trs(state)?.trs() {
+                if let Some(symt) = self.input_symbols() {
+                    if symt.get_symbol(tr.ilabel).is_none() {
+                        bail!("Input label {} is missing from the symbol table", tr.ilabel);
+                    }
+                }
+                if let Some(symt) = self.output_symbols() {
+                    if symt.get_symbol(tr.olabel).is_none() {
+                        bail!(
+                            "Output label {} is missing from the symbol table",
+                            tr.olabel
+                        );
+                    }
+                }
+            }
+        }
+        let buffer = Vec::<u8>::new();
+        let mut line_writer = LineWriter::new(buffer);
+        write_fst!(self, line_writer, true, true);
+        Ok(String::from_utf8(line_writer.into_inner()?)?)
+    }
+
+    /// Wraps the FST so that its `Display` only writes the trs and final weights of the first
+    /// `max_states` states, followed by an ellipsis with the number of hidden states. The
+    /// output is otherwise the same as the `Display` of the FST.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use rustfst::fst_traits::{MutableFst, SerializableFst};
+    /// # use rustfst::fst_impls::VectorFst;
+    /// # use rustfst::semirings::{Semiring, TropicalWeight};
+    /// # use rustfst::Tr;
+    /// # use anyhow::Result;
+    /// # fn main() -> Result<()> {
+    /// let mut fst = VectorFst::<TropicalWeight>::new();
+    /// let s0 = fst.add_state();
+    /// let s1 = fst.add_state();
+    /// fst.set_start(s0)?;
+    /// fst.set_final(s1, TropicalWeight::one())?;
+    /// fst.add_tr(s0, Tr::new(1, 2, 0.5, s1))?;
+    /// assert_eq!(
+    ///     format!("{}", fst.display_truncated(1)),
+    ///     "0\t1\t1\t2\t0.5\n... (1 more states)\n"
+    /// );
+    /// # Ok(())
+    /// # }
+    /// ```
+    fn display_truncated(&self, max_states: usize) -> TruncatedDisplay<'_, W, Self> {
+        TruncatedDisplay {
+            fst: self,
+            max_states,
+            w: PhantomData,
+        }
+    }
+
     /// Serializes the FST as a DOT file compatible with GraphViz binaries.
     fn draw<P: AsRef<Path>>(&self, path_output: P, config: &DrawingConfig) -> Result<()> {
         let buffer = File::create(path_output.as_ref())?;
@@ -131,6 +259,22 @@ pub trait SerializableFst<W: SerializableSemiring>: ExpandedFst<W> {
     }
 }
 
+/// `Display` of an FST limited to its first states. See `SerializableFst::display_truncated`.
+pub struct TruncatedDisplay<'a, W: SerializableSemiring, F: SerializableFst<W>> {
+    fst: &'a F,
+    max_states: usize,
+    w: PhantomData<W>,
+}
+
+impl<'a, W: SerializableSemiring, F: SerializableFst<W>> fmt::Display
+    for TruncatedDisplay<'a, W, F>
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write_fst!(self.fst, f, true, true, Some(self.max_states), true);
+        Ok(())
+    }
+}
+
 fn draw_single_fst_state<S: SerializableSemiring, F: SerializableFst<S>, W: Write>(
     fst: &F,
     writer: &mut W,
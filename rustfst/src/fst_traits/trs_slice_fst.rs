@@ -0,0 +1,93 @@
+use anyhow::Result;
+
+use crate::fst_traits::ExpandedFst;
+use crate::semirings::Semiring;
+use crate::{StateId, Tr};
+
+/// Trait implemented by the FSTs storing the trs leaving each state contiguously, so that they
+/// can be borrowed as a slice. Unlike `CoreFst::get_trs`, which returns a `Trs` owning a
+/// reference to the trs, e.g. an `Arc` to clone, nothing is allocated or reference counted.
+///
+/// # Example
+///
+/// ```
+/// # use rustfst::fst_traits::{MutableFst, TrsSliceFst};
+/// # use rustfst::fst_impls::VectorFst;
+/// # use rustfst::semirings::{Semiring, TropicalWeight};
+/// # use rustfst::{Label, Tr};
+/// # use anyhow::Result;
+/// # fn main() -> Result<()> {
+/// fn ilabels<W: Semiring, F: TrsSliceFst<W>>(fst: &F) -> Result<Vec<Label>> {
+///     let mut ilabels = vec![];
+///     for state in fst.states_range() {
+///         for tr in fst.get_trs_slice(state)? {
+///             ilabels.push(tr.ilabel);
+///         }
+///     }
+///     Ok(ilabels)
+/// }
+///
+/// let mut fst = VectorFst::<TropicalWeight>::new();
+/// fst.add_states(2);
+/// fst.add_tr(0, Tr::new(1, 2, 1.0, 1))?;
+/// fst.add_tr(0, Tr::new(3, 4, 1.0, 1))?;
+/// assert_eq!(fst.get_trs_slice(0)?[1].ilabel, 3);
+/// assert_eq!(ilabels(&fst)?, vec![1, 3]);
+/// # Ok(())
+/// # }
+/// ```
+pub trait TrsSliceFst<W: Semiring>: ExpandedFst<W> {
+    /// Borrows the trs leaving the state `state_id`.
+    fn get_trs_slice(&self, state_id: StateId) -> Result<&[Tr<W>]>;
+
+    /// # Safety
+    ///
+    /// Unsafe behaviour if `state_id` is not present in Fst.
+    ///
+    unsafe fn get_trs_slice_unchecked(&self, state_id: StateId) -> &[Tr<W>];
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::fst_impls::{ConstFst, VectorFst};
+    use crate::fst_traits::{CoreFst, MutableFst};
+    use crate::semirings::TropicalWeight;
+    use crate::Trs;
+
+    fn check_trs_slice<W: Semiring, F: TrsSliceFst<W>>(fst: &F) -> Result<()> {
+        for state in fst.states_range() {
+            let trs = fst.get_trs(state)?;
+            let slice = fst.get_trs_slice(state)?;
+            assert_eq!(slice, trs.trs());
+            assert_eq!(unsafe { fst.get_trs_slice_unchecked(state) }, slice);
+            for (i, tr) in slice.iter().enumerate() {
+                assert_eq!(tr, &trs[i]);
+            }
+        }
+        assert!(fst.get_trs_slice(fst.num_states() as StateId).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_trs_slice_fst() -> Result<()> {
+        let mut fst = VectorFst::<TropicalWeight>::new();
+        fst.add_states(3);
+        fst.set_start(0)?;
+        fst.add_tr(0, Tr::new(1, 1, 1.0, 1))?;
+        fst.add_tr(0, Tr::new(2, 3, 2.0, 2))?;
+        fst.add_tr(2, Tr::new(4, 4, 0.5, 1))?;
+        fst.set_final(1, TropicalWeight::one())?;
+        check_trs_slice(&fst)?;
+
+        let const_fst: ConstFst<_> = fst.clone().into();
+        check_trs_slice(&const_fst)?;
+        assert_eq!(const_fst.get_trs_slice(2)?, fst.get_trs_slice(2)?);
+
+        let trs = fst.get_trs(0)?;
+        let olabels: Vec<_> = (&trs).into_iter().map(|tr| tr.olabel).collect();
+        assert_eq!(olabels, vec![1, 3]);
+        Ok(())
+    }
+}
@@ -0,0 +1,9 @@
+//! Reading and writing of FSTs and symbol tables : the binary and text formats of OpenFST,
+//! the DOT format of GraphViz and the importers of the morphology toolkits.
+
+pub use crate::drawing_config::DrawingConfig;
+pub use crate::fst_traits::SerializableFst;
+pub use crate::parsers::morphology;
+pub use crate::parsers::text_fst::{FinalState, ParsedTextFst, Transition};
+pub use crate::parsers::{NomCustomError, SerializeBinary};
+pub use crate::symbol_table::SymbolTable;
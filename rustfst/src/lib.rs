@@ -201,6 +201,7 @@ pub use crate::drawing_config::DrawingConfig;
 pub use crate::fst_path::{check_path_in_fst, FstPath};
 pub use crate::string_path::StringPath;
 pub use crate::symbol_table::SymbolTable;
+pub use crate::tr_path::{acyclic_paths, paths_to_fst, TrPath};
 
 pub use self::tr::Tr;
 pub use self::trs::{Trs, TrsConst, TrsVec};
@@ -243,6 +244,10 @@ pub mod utils;
 /// Provides algorithms that are generic to all Fst.
 pub mod algorithms;
 
+pub mod algo;
+
+pub mod io;
+
 /// Provides the `FstProperties` struct and some utils functions around it.
 /// Useful to assert some properties on a Fst.
 pub mod fst_properties;
@@ -257,31 +262,65 @@ pub mod fst_impls;
 /// Provides a trait that shall be implemented for all weights stored inside a wFST.
 pub mod semirings;
 
+/// Multi-tape machines, joined on selected tapes and projected on pairs of tapes.
+#[cfg(feature = "multi-tape")]
+pub mod multi_tape;
+
+/// Harness comparing rustfst with the OpenFst command line tools.
+#[cfg(feature = "openfst-compat")]
+pub mod openfst_compat;
+
 mod drawing_config;
 /// Implementation of a successful path inside a wFST.
 mod fst_path;
 mod parsers;
 mod string_path;
+/// Successful path keeping its states and the weights of its transitions.
+mod tr_path;
 
 pub use crate::parsers::nom_utils::NomCustomError;
 
+/// Importers for the transducers of the morphology toolkits (foma, HFST).
+pub use crate::parsers::morphology;
+
 /// A representable float near .001. (Used in Quantize)
 pub const KDELTA: f32 = 1.0f32 / 1024.0f32;
 /// Default tolerance value used in floating-point comparisons.
 pub const KSHORTESTDELTA: f32 = 1e-6;
 
-/// Module re-exporting most of the objects from this crate.
+/// Module re-exporting the objects most commonly needed to build and transform FSTs.
+///
+/// The algorithms are reachable through the namespaces of [`algo`], e.g.
+/// `algo::shortest::shortest_path`, the most common ones being also re-exported directly.
 pub mod prelude {
-    pub use crate::algorithms::tr_compares::*;
-    pub use crate::algorithms::tr_mappers::*;
-    pub use crate::algorithms::*;
+    pub use crate::algo;
+    pub use crate::algo::compare::tr_compares::{ILabelCompare, OLabelCompare};
+    pub use crate::algo::compose::compose;
+    pub use crate::algo::determinize::determinize;
+    pub use crate::algo::map::tr_mappers::*;
+    pub use crate::algo::minimize::{minimize, optimize};
+    pub use crate::algo::shortest::{shortest_distance, shortest_path};
+    pub use crate::algo::structure::{connect, invert, project, reverse, tr_sort, ProjectType};
     pub use crate::fst_impls::*;
+    pub use crate::fst_properties::FstProperties;
     pub use crate::fst_traits::*;
-    pub use crate::parsers::SerializeBinary;
+    pub use crate::io::{DrawingConfig, SerializableFst, SerializeBinary};
     pub use crate::semirings::*;
     pub use crate::tr::Tr;
     pub use crate::trs::{Trs, TrsConst, TrsVec};
-    pub use crate::*;
+    pub use crate::{Label, StateId, SymbolTable, EPS_LABEL, EPS_SYMBOL};
+
+    #[allow(deprecated)]
+    pub use self::flat::*;
+
+    /// Flat re-exports of the algorithms, the comparators and the root of the crate, kept in
+    /// the prelude for one release.
+    #[deprecated(note = "Use the namespaces of `algo` or the modules of the crate")]
+    pub mod flat {
+        pub use crate::algorithms::tr_compares::*;
+        pub use crate::algorithms::*;
+        pub use crate::*;
+    }
 }
 
 #[cfg(test)]
@@ -0,0 +1,229 @@
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, VecDeque};
+
+use anyhow::Result;
+
+use crate::multi_tape::{MTr, MultiTapeFst};
+use crate::semirings::Semiring;
+use crate::{Label, StateId, EPS_LABEL};
+
+/// State of the epsilon filter, preventing redundant epsilon paths as in the composition.
+/// In `FirstMove`, both machines may move alone on an epsilon of the joined tape. Once the
+/// second machine has moved alone, the first one has to wait for a matching transition.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum JoinFilterState {
+    FirstMove,
+    SecondMove,
+}
+
+/// Labels of a transition of the join: the ones of the first machine followed by the ones
+/// of the second machine except its joined tape.
+fn merge_labels(labels1: &[Label], labels2: &[Label], tape2: usize) -> Vec<Label> {
+    let mut labels = labels1.to_vec();
+    labels.extend(
+        labels2
+            .iter()
+            .enumerate()
+            .filter(|(t, _)| *t != tape2)
+            .map(|(_, l)| *l),
+    );
+    labels
+}
+
+/// Joins two multi-tape machines on a selected tape of each: a path of the result exists
+/// iff the two machines have paths agreeing on the strings of the joined tapes.
+///
+/// The result has the tapes of `fst1` followed by the tapes of `fst2` except `tape2`, whose
+/// content is the one of `tape1`. The join of two 2-tape machines on the output tape of the
+/// first one and the input tape of the second one is a composition keeping the intermediate
+/// tape. Epsilons on the joined tapes are handled with a sequence filter so that each
+/// alignment is only produced once.
+///
+/// # Example
+///
+/// ```
+/// # use rustfst::multi_tape::{join, MultiTapeFst, MTr};
+/// # use rustfst::semirings::{Semiring, TropicalWeight};
+/// # use anyhow::Result;
+/// # fn main() -> Result<()> {
+/// let mut fst1 = MultiTapeFst::<TropicalWeight>::new(2);
+/// let s0 = fst1.add_state();
+/// let s1 = fst1.add_state();
+/// fst1.set_start(s0)?;
+/// fst1.add_tr(s0, MTr::new(vec![1, 2], 1.0, s1))?;
+/// fst1.set_final(s1, TropicalWeight::one())?;
+///
+/// let mut fst2 = MultiTapeFst::<TropicalWeight>::new(2);
+/// let s0 = fst2.add_state();
+/// let s1 = fst2.add_state();
+/// fst2.set_start(s0)?;
+/// fst2.add_tr(s0, MTr::new(vec![2, 3], 2.0, s1))?;
+/// fst2.set_final(s1, TropicalWeight::one())?;
+///
+/// let joined = join(&fst1, 1, &fst2, 0)?;
+/// assert_eq!(joined.num_tapes(), 3);
+/// let start = joined.start().unwrap();
+/// assert_eq!(joined.get_trs(start)?[0].labels, vec![1, 2, 3]);
+/// assert_eq!(joined.get_trs(start)?[0].weight, TropicalWeight::new(3.0));
+/// # Ok(())
+/// # }
+/// ```
+pub fn join<W: Semiring>(
+    fst1: &MultiTapeFst<W>,
+    tape1: usize,
+    fst2: &MultiTapeFst<W>,
+    tape2: usize,
+) -> Result<MultiTapeFst<W>> {
+    ensure!(
+        tape1 < fst1.num_tapes(),
+        "join : tape {} doesn't exist in the first machine",
+        tape1
+    );
+    ensure!(
+        tape2 < fst2.num_tapes(),
+        "join : tape {} doesn't exist in the second machine",
+        tape2
+    );
+    let mut ofst = MultiTapeFst::new(fst1.num_tapes() + fst2.num_tapes() - 1);
+    let (start1, start2) = match (fst1.start(), fst2.start()) {
+        (Some(s1), Some(s2)) => (s1, s2),
+        _ => return Ok(ofst),
+    };
+
+    let mut tuples: HashMap<(StateId, StateId, JoinFilterState), StateId> = HashMap::new();
+    let mut queue = VecDeque::new();
+    let start_tuple = (start1, start2, JoinFilterState::FirstMove);
+    let ostart = ofst.add_state();
+    ofst.set_start(ostart)?;
+    tuples.insert(start_tuple, ostart);
+    queue.push_back((start_tuple, ostart));
+
+    while let Some(((s1, s2, filter), ostate)) = queue.pop_front() {
+        if let (Some(w1), Some(w2)) = (fst1.final_weight(s1)?, fst2.final_weight(s2)?) {
+            ofst.set_final(ostate, w1.times(w2)?)?;
+        }
+
+        // Transitions of the result before being mapped to states of the result.
+        let mut candidates = vec![];
+        for tr1 in fst1.get_trs(s1)? {
+            let l1 = tr1.labels[tape1];
+            if l1 == EPS_LABEL && filter == JoinFilterState::FirstMove {
+                let epsilons2: Vec<Label> = vec![EPS_LABEL; fst2.num_tapes()];
+                candidates.push((
+                    merge_labels(&tr1.labels, &epsilons2, tape2),
+                    tr1.weight.clone(),
+                    (tr1.nextstate, s2, JoinFilterState::FirstMove),
+                ));
+            }
+            if l1 == EPS_LABEL {
+                continue;
+            }
+            for tr2 in fst2.get_trs(s2)? {
+                if tr2.labels[tape2] == l1 {
+                    candidates.push((
+                        merge_labels(&tr1.labels, &tr2.labels, tape2),
+                        tr1.weight.times(&tr2.weight)?,
+                        (tr1.nextstate, tr2.nextstate, JoinFilterState::FirstMove),
+                    ));
+                }
+            }
+        }
+        for tr2 in fst2.get_trs(s2)? {
+            if tr2.labels[tape2] == EPS_LABEL {
+                let epsilons1: Vec<Label> = vec![EPS_LABEL; fst1.num_tapes()];
+                candidates.push((
+                    merge_labels(&epsilons1, &tr2.labels, tape2),
+                    tr2.weight.clone(),
+                    (s1, tr2.nextstate, JoinFilterState::SecondMove),
+                ));
+            }
+        }
+
+        for (labels, weight, tuple) in candidates.into_iter() {
+            let nextstate = match tuples.entry(tuple) {
+                Entry::Occupied(e) => *e.get(),
+                Entry::Vacant(e) => {
+                    let s = ofst.add_state();
+                    queue.push_back((tuple, s));
+                    e.insert(s);
+                    s
+                }
+            };
+            ofst.add_tr(ostate, MTr::new(labels, weight, nextstate))?;
+        }
+    }
+    Ok(ofst)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::fst_impls::VectorFst;
+    use crate::fst_traits::{ExpandedFst, MutableFst};
+    use crate::semirings::TropicalWeight;
+    use crate::Tr;
+
+    #[test]
+    fn test_join_with_epsilons() -> Result<()> {
+        // 1:<eps> 2:5
+        let mut fst1 = VectorFst::<TropicalWeight>::new();
+        let s0 = fst1.add_state();
+        let s1 = fst1.add_state();
+        let s2 = fst1.add_state();
+        fst1.set_start(s0)?;
+        fst1.add_tr(s0, Tr::new(1, EPS_LABEL, 1.0, s1))?;
+        fst1.add_tr(s1, Tr::new(2, 5, 1.0, s2))?;
+        fst1.set_final(s2, TropicalWeight::one())?;
+
+        // <eps>:7 5:8
+        let mut fst2 = VectorFst::<TropicalWeight>::new();
+        let s0 = fst2.add_state();
+        let s1 = fst2.add_state();
+        let s2 = fst2.add_state();
+        fst2.set_start(s0)?;
+        fst2.add_tr(s0, Tr::new(EPS_LABEL, 7, 2.0, s1))?;
+        fst2.add_tr(s1, Tr::new(5, 8, 2.0, s2))?;
+        fst2.set_final(s2, TropicalWeight::one())?;
+
+        let joined = join(
+            &MultiTapeFst::from_fst(&fst1)?,
+            1,
+            &MultiTapeFst::from_fst(&fst2)?,
+            0,
+        )?;
+        assert_eq!(joined.num_tapes(), 3);
+
+        // Enumerates the successful paths.
+        let mut paths = vec![];
+        let mut stack = vec![(joined.start().unwrap(), vec![], TropicalWeight::one())];
+        while let Some((state, labels, weight)) = stack.pop() {
+            if let Some(final_weight) = joined.final_weight(state)? {
+                paths.push((labels.clone(), weight.times(final_weight)?));
+            }
+            for tr in joined.get_trs(state)? {
+                let mut l = labels.clone();
+                l.push(tr.labels.clone());
+                stack.push((tr.nextstate, l, weight.times(tr.weight)?));
+            }
+        }
+        // The two epsilon moves can be interleaved in two ways but only one is kept.
+        assert_eq!(paths.len(), 1);
+        let (labels, weight) = &paths[0];
+        assert_eq!(weight, &TropicalWeight::new(6.0));
+        let tape = |t: usize| -> Vec<Label> {
+            labels
+                .iter()
+                .map(|l: &Vec<Label>| l[t])
+                .filter(|l| *l != EPS_LABEL)
+                .collect()
+        };
+        assert_eq!(tape(0), vec![1, 2]);
+        assert_eq!(tape(1), vec![5]);
+        assert_eq!(tape(2), vec![7, 8]);
+
+        let composed: VectorFst<TropicalWeight> = joined.select_tapes(&[0, 2])?.project(0, 1)?;
+        assert_eq!(composed.num_states(), joined.num_states());
+        Ok(())
+    }
+}
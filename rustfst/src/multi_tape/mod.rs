@@ -0,0 +1,14 @@
+//! Weighted finite-state machines with more than two tapes.
+//!
+//! A k-tape machine reads or writes k strings at the same time: each transition carries one
+//! label per tape. The usual two-tape transducers can be obtained by projecting a k-tape
+//! machine on a pair of tapes and k-tape machines can be combined by joining them on
+//! selected tapes, which generalizes composition.
+
+pub use self::join::join;
+pub use self::mtr::MTr;
+pub use self::multi_tape_fst::MultiTapeFst;
+
+mod join;
+mod mtr;
+mod multi_tape_fst;
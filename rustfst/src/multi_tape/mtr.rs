@@ -0,0 +1,40 @@
+use crate::{Label, StateId};
+
+/// Transition of a multi-tape machine: one label per tape.
+#[derive(Debug, Clone, PartialEq, PartialOrd, Eq, Hash)]
+pub struct MTr<W> {
+    /// Labels of the transition, one per tape. `EPS_LABEL` means nothing is read on the tape.
+    pub labels: Vec<Label>,
+    /// Weight.
+    pub weight: W,
+    /// ID of the next state.
+    pub nextstate: StateId,
+}
+
+impl<W> MTr<W> {
+    /// Creates a new MTr.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use rustfst::multi_tape::MTr;
+    /// # use rustfst::semirings::{TropicalWeight, Semiring};
+    /// let transition = MTr::<TropicalWeight>::new(vec![1, 2, 3], 1.3, 2);
+    ///
+    /// assert_eq!(transition.labels, vec![1, 2, 3]);
+    /// assert_eq!(transition.weight, TropicalWeight::new(1.3));
+    /// assert_eq!(transition.nextstate, 2);
+    /// ```
+    pub fn new<S: Into<W>>(labels: Vec<Label>, weight: S, nextstate: StateId) -> Self {
+        MTr {
+            labels,
+            weight: weight.into(),
+            nextstate,
+        }
+    }
+
+    /// Number of tapes of the transition.
+    pub fn num_tapes(&self) -> usize {
+        self.labels.len()
+    }
+}
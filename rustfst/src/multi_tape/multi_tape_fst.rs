@@ -0,0 +1,218 @@
+use anyhow::Result;
+
+use crate::fst_traits::{ExpandedFst, MutableFst};
+use crate::multi_tape::MTr;
+use crate::semirings::Semiring;
+use crate::{StateId, Tr, Trs};
+
+#[derive(Debug, Clone, PartialEq)]
+struct MultiTapeFstState<W: Semiring> {
+    final_weight: Option<W>,
+    trs: Vec<MTr<W>>,
+}
+
+/// Mutable multi-tape machine whose states and transitions are stored in vectors.
+///
+/// # Example
+///
+/// ```
+/// # use rustfst::multi_tape::{MultiTapeFst, MTr};
+/// # use rustfst::fst_impls::VectorFst;
+/// # use rustfst::fst_traits::MutableFst;
+/// # use rustfst::semirings::{Semiring, TropicalWeight};
+/// # use rustfst::Tr;
+/// # use anyhow::Result;
+/// # fn main() -> Result<()> {
+/// // Surface form, lemma and part-of-speech tag.
+/// let mut fst = MultiTapeFst::<TropicalWeight>::new(3);
+/// let s0 = fst.add_state();
+/// let s1 = fst.add_state();
+/// fst.set_start(s0)?;
+/// fst.add_tr(s0, MTr::new(vec![1, 2, 3], 0.5, s1))?;
+/// fst.set_final(s1, TropicalWeight::one())?;
+///
+/// let lemmatizer: VectorFst<TropicalWeight> = fst.project(0, 1)?;
+/// let mut ref_fst = VectorFst::new();
+/// let s0 = ref_fst.add_state();
+/// let s1 = ref_fst.add_state();
+/// ref_fst.set_start(s0)?;
+/// ref_fst.add_tr(s0, Tr::new(1, 2, 0.5, s1))?;
+/// ref_fst.set_final(s1, TropicalWeight::one())?;
+/// assert_eq!(lemmatizer, ref_fst);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct MultiTapeFst<W: Semiring> {
+    num_tapes: usize,
+    states: Vec<MultiTapeFstState<W>>,
+    start_state: Option<StateId>,
+}
+
+impl<W: Semiring> MultiTapeFst<W> {
+    /// Creates an empty machine with `num_tapes` tapes.
+    pub fn new(num_tapes: usize) -> Self {
+        Self {
+            num_tapes,
+            states: vec![],
+            start_state: None,
+        }
+    }
+
+    /// Converts a two-tape FST: the input labels go on tape 0 and the output labels on tape 1.
+    pub fn from_fst<F: ExpandedFst<W>>(fst: &F) -> Result<Self> {
+        let mut res = Self::new(2);
+        for _ in 0..fst.num_states() {
+            res.add_state();
+        }
+        if let Some(start) = fst.start() {
+            res.set_start(start)?;
+        }
+        for state in fst.states_range() {
+            for tr in fst.get_trs(state)?.trs() {
+                res.add_tr(
+                    state,
+                    MTr::new(vec![tr.ilabel, tr.olabel], tr.weight.clone(), tr.nextstate),
+                )?;
+            }
+            if let Some(final_weight) = fst.final_weight(state)? {
+                res.set_final(state, final_weight)?;
+            }
+        }
+        Ok(res)
+    }
+
+    pub fn num_tapes(&self) -> usize {
+        self.num_tapes
+    }
+
+    pub fn num_states(&self) -> usize {
+        self.states.len()
+    }
+
+    pub fn start(&self) -> Option<StateId> {
+        self.start_state
+    }
+
+    pub fn add_state(&mut self) -> StateId {
+        let id = self.states.len() as StateId;
+        self.states.push(MultiTapeFstState {
+            final_weight: None,
+            trs: vec![],
+        });
+        id
+    }
+
+    pub fn set_start(&mut self, state: StateId) -> Result<()> {
+        self.check_state(state)?;
+        self.start_state = Some(state);
+        Ok(())
+    }
+
+    pub fn set_final<S: Into<W>>(&mut self, state: StateId, final_weight: S) -> Result<()> {
+        self.check_state(state)?;
+        self.states[state as usize].final_weight = Some(final_weight.into());
+        Ok(())
+    }
+
+    pub fn final_weight(&self, state: StateId) -> Result<Option<W>> {
+        self.check_state(state)?;
+        Ok(self.states[state as usize].final_weight.clone())
+    }
+
+    /// Adds a transition leaving `source`. The transition must have one label per tape.
+    pub fn add_tr(&mut self, source: StateId, tr: MTr<W>) -> Result<()> {
+        self.check_state(source)?;
+        self.check_state(tr.nextstate)?;
+        ensure!(
+            tr.num_tapes() == self.num_tapes,
+            "Transition has {} labels but the machine has {} tapes",
+            tr.num_tapes(),
+            self.num_tapes
+        );
+        self.states[source as usize].trs.push(tr);
+        Ok(())
+    }
+
+    pub fn get_trs(&self, state: StateId) -> Result<&[MTr<W>]> {
+        self.check_state(state)?;
+        Ok(self.states[state as usize].trs.as_slice())
+    }
+
+    /// Keeps only the tapes listed in `tapes`, in this order.
+    pub fn select_tapes(&self, tapes: &[usize]) -> Result<Self> {
+        for tape in tapes {
+            self.check_tape(*tape)?;
+        }
+        let states = self
+            .states
+            .iter()
+            .map(|state| MultiTapeFstState {
+                final_weight: state.final_weight.clone(),
+                trs: state
+                    .trs
+                    .iter()
+                    .map(|tr| MTr {
+                        labels: tapes.iter().map(|t| tr.labels[*t]).collect(),
+                        weight: tr.weight.clone(),
+                        nextstate: tr.nextstate,
+                    })
+                    .collect(),
+            })
+            .collect();
+        Ok(Self {
+            num_tapes: tapes.len(),
+            states,
+            start_state: self.start_state,
+        })
+    }
+
+    /// Projects the machine on a pair of tapes, returning a regular transducer reading
+    /// `input_tape` and writing `output_tape`.
+    pub fn project<F: MutableFst<W>>(&self, input_tape: usize, output_tape: usize) -> Result<F> {
+        self.check_tape(input_tape)?;
+        self.check_tape(output_tape)?;
+        let mut fst = F::new();
+        fst.add_states(self.states.len());
+        if let Some(start) = self.start_state {
+            fst.set_start(start)?;
+        }
+        for (state, data) in self.states.iter().enumerate() {
+            let state = state as StateId;
+            for tr in data.trs.iter() {
+                fst.add_tr(
+                    state,
+                    Tr::new(
+                        tr.labels[input_tape],
+                        tr.labels[output_tape],
+                        tr.weight.clone(),
+                        tr.nextstate,
+                    ),
+                )?;
+            }
+            if let Some(final_weight) = &data.final_weight {
+                fst.set_final(state, final_weight.clone())?;
+            }
+        }
+        Ok(fst)
+    }
+
+    fn check_state(&self, state: StateId) -> Result<()> {
+        ensure!(
+            (state as usize) < self.states.len(),
+            "The state {:?} doesn't exist",
+            state
+        );
+        Ok(())
+    }
+
+    fn check_tape(&self, tape: usize) -> Result<()> {
+        ensure!(
+            tape < self.num_tapes,
+            "Tape {} doesn't exist, the machine has {} tapes",
+            tape,
+            self.num_tapes
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,339 @@
+//! Harness comparing the algorithms of rustfst with the ones of the OpenFst command line tools.
+//!
+//! Random FSTs are generated, written in the OpenFst binary format and run through both
+//! implementations. The outputs are then compared up to a renumbering of the states. This is
+//! useful to validate rustfst on your own data, provided that the OpenFst binaries
+//! (`fstconnect`, `fstdeterminize`...) are installed.
+//!
+//! # Example
+//! ```no_run
+//! # use rustfst::openfst_compat::{CompatCheck, OpenFstHarness, RandomFstConfig};
+//! # use anyhow::Result;
+//! # fn main() -> Result<()> {
+//! let harness = OpenFstHarness::new();
+//! if harness.is_available() {
+//!     harness.run_random(&CompatCheck::defaults(), RandomFstConfig::default(), 100)?;
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use anyhow::{Context, Result};
+use rand::distributions::{Distribution, Uniform};
+use rand::Rng;
+use rand_chacha::rand_core::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+
+use crate::algorithms::determinize::determinize;
+use crate::algorithms::rm_epsilon::rm_epsilon;
+use crate::algorithms::tr_compares::{ILabelCompare, OLabelCompare};
+use crate::algorithms::{
+    connect, invert, isomorphic_with_config, minimize, project, reverse, top_sort, tr_sort,
+    IsomorphicConfig, ProjectType,
+};
+use crate::fst_impls::VectorFst;
+use crate::fst_traits::{MutableFst, SerializableFst};
+use crate::semirings::TropicalWeight;
+use crate::{Label, StateId, Tr, EPS_LABEL, KDELTA};
+
+type CompatFst = VectorFst<TropicalWeight>;
+
+/// Counter used to create unique temporary files.
+static TMP_FILE_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+/// Algorithm run through both implementations.
+pub struct CompatCheck {
+    /// Name of the OpenFst binary, e.g. `fstconnect`.
+    pub binary: String,
+    /// Extra command line arguments of the OpenFst binary.
+    pub args: Vec<String>,
+    /// Same algorithm implemented with rustfst.
+    pub run: fn(&CompatFst) -> Result<CompatFst>,
+}
+
+impl CompatCheck {
+    pub fn new(binary: &str, args: &[&str], run: fn(&CompatFst) -> Result<CompatFst>) -> Self {
+        Self {
+            binary: binary.to_string(),
+            args: args.iter().map(|a| a.to_string()).collect(),
+            run,
+        }
+    }
+
+    /// Checks of the algorithms whose output is canonical enough to be compared.
+    pub fn defaults() -> Vec<Self> {
+        vec![
+            Self::new("fstconnect", &[], |fst| {
+                let mut fst = fst.clone();
+                connect(&mut fst)?;
+                Ok(fst)
+            }),
+            Self::new("fstinvert", &[], |fst| {
+                let mut fst = fst.clone();
+                invert(&mut fst);
+                Ok(fst)
+            }),
+            Self::new("fstproject", &["--project_type=output"], |fst| {
+                let mut fst = fst.clone();
+                project(&mut fst, ProjectType::ProjectOutput);
+                Ok(fst)
+            }),
+            Self::new("fstreverse", &[], reverse),
+            Self::new("fstrmepsilon", &[], |fst| {
+                let mut fst = fst.clone();
+                rm_epsilon(&mut fst)?;
+                Ok(fst)
+            }),
+            Self::new("fsttopsort", &[], |fst| {
+                let mut fst = fst.clone();
+                top_sort(&mut fst)?;
+                Ok(fst)
+            }),
+            Self::new("fstarcsort", &["--sort_type=olabel"], |fst| {
+                let mut fst = fst.clone();
+                tr_sort(&mut fst, OLabelCompare {});
+                Ok(fst)
+            }),
+            Self::new("fstdeterminize", &[], determinize),
+            Self::new("fstminimize", &[], |fst| {
+                let mut fst = fst.clone();
+                minimize(&mut fst)?;
+                Ok(fst)
+            }),
+        ]
+    }
+
+    /// Restricts the input to the domain of the algorithm: determinization needs an acceptor
+    /// and minimization a deterministic acceptor.
+    fn prepare_input(&self, fst: &CompatFst) -> Result<CompatFst> {
+        let mut fst = fst.clone();
+        match self.binary.as_str() {
+            "fstdeterminize" => project(&mut fst, ProjectType::ProjectInput),
+            "fstminimize" => {
+                project(&mut fst, ProjectType::ProjectInput);
+                fst = determinize(&fst)?;
+            }
+            _ => {}
+        }
+        Ok(fst)
+    }
+}
+
+/// Parameters of the random FSTs. The generated FSTs are acyclic so that all the algorithms
+/// terminate.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RandomFstConfig {
+    pub max_states: usize,
+    pub max_trs_per_state: usize,
+    /// Labels are drawn between 0 (epsilon) and `max_label`.
+    pub max_label: Label,
+    pub seed: u64,
+}
+
+impl Default for RandomFstConfig {
+    fn default() -> Self {
+        Self {
+            max_states: 10,
+            max_trs_per_state: 3,
+            max_label: 5,
+            seed: 42,
+        }
+    }
+}
+
+/// Generates an acyclic FST where each transition goes to a state with a greater id.
+pub fn random_acyclic_fst<R: Rng>(rng: &mut R, config: &RandomFstConfig) -> Result<CompatFst> {
+    let mut fst = CompatFst::new();
+    let num_states = rng.gen_range(1..=config.max_states.max(1));
+    fst.add_states(num_states);
+    fst.set_start(0)?;
+    let labels = Uniform::new_inclusive(EPS_LABEL, config.max_label);
+    for state in 0..num_states {
+        let state = state as StateId;
+        if state as usize + 1 < num_states {
+            let targets = Uniform::new(state + 1, num_states as StateId);
+            for _ in 0..rng.gen_range(0..=config.max_trs_per_state) {
+                // Weights with two decimals to avoid rounding differences.
+                let weight = rng.gen_range(0..500) as f32 / 100.0;
+                fst.add_tr(
+                    state,
+                    Tr::new(
+                        labels.sample(rng),
+                        labels.sample(rng),
+                        weight,
+                        targets.sample(rng),
+                    ),
+                )?;
+            }
+        }
+        if state as usize + 1 == num_states || rng.gen_bool(0.3) {
+            fst.set_final(state, rng.gen_range(0..500) as f32 / 100.0)?;
+        }
+    }
+    Ok(fst)
+}
+
+/// Runs the OpenFst binaries and compares their outputs with the ones of rustfst.
+#[derive(Debug, Clone)]
+pub struct OpenFstHarness {
+    bin_dir: Option<PathBuf>,
+    tmp_dir: PathBuf,
+    delta: f32,
+}
+
+impl Default for OpenFstHarness {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl OpenFstHarness {
+    /// Uses the OpenFst binaries found in the `PATH`.
+    pub fn new() -> Self {
+        Self {
+            bin_dir: None,
+            tmp_dir: std::env::temp_dir(),
+            delta: KDELTA,
+        }
+    }
+
+    /// Uses the OpenFst binaries of a given directory.
+    pub fn with_bin_dir<P: AsRef<Path>>(self, bin_dir: P) -> Self {
+        Self {
+            bin_dir: Some(bin_dir.as_ref().to_path_buf()),
+            ..self
+        }
+    }
+
+    /// Sets the tolerance used when comparing the weights.
+    pub fn with_delta(self, delta: f32) -> Self {
+        Self { delta, ..self }
+    }
+
+    fn binary_path(&self, binary: &str) -> PathBuf {
+        match &self.bin_dir {
+            Some(dir) => dir.join(binary),
+            None => PathBuf::from(binary),
+        }
+    }
+
+    fn tmp_path(&self, name: &str) -> PathBuf {
+        let idx = TMP_FILE_COUNTER.fetch_add(1, Ordering::SeqCst);
+        self.tmp_dir.join(format!(
+            "rustfst-compat-{}-{}-{}.fst",
+            std::process::id(),
+            idx,
+            name
+        ))
+    }
+
+    /// Returns true if the OpenFst binaries can be run.
+    pub fn is_available(&self) -> bool {
+        Command::new(self.binary_path("fstinfo"))
+            .arg("--help")
+            .output()
+            .is_ok()
+    }
+
+    /// Runs an OpenFst binary on the FST and returns its output.
+    pub fn run_openfst(&self, check: &CompatCheck, fst: &CompatFst) -> Result<CompatFst> {
+        let input = self.tmp_path("in");
+        let output = self.tmp_path("out");
+        fst.write(&input)?;
+        let status = Command::new(self.binary_path(&check.binary))
+            .args(&check.args)
+            .arg(&input)
+            .arg(&output)
+            .status()
+            .with_context(|| format!("Can't run {}", check.binary));
+        let res = match status {
+            Ok(s) if s.success() => CompatFst::read(&output),
+            Ok(s) => Err(format_err!("{} failed with status {}", check.binary, s)),
+            Err(e) => Err(e),
+        };
+        let _ = std::fs::remove_file(&input);
+        let _ = std::fs::remove_file(&output);
+        res
+    }
+
+    /// Runs the check on a single FST and fails if the outputs of the two implementations
+    /// are not isomorphic.
+    pub fn check(&self, check: &CompatCheck, fst: &CompatFst) -> Result<()> {
+        let fst = check.prepare_input(fst)?;
+        let mut openfst_output = self.run_openfst(check, &fst)?;
+        let mut rustfst_output = (check.run)(&fst)?;
+
+        let config = IsomorphicConfig::new(self.delta);
+        let same = match isomorphic_with_config(&rustfst_output, &openfst_output, config) {
+            Ok(same) => same,
+            // Isomorphism can't be checked on non-deterministic FSTs : the states are then
+            // expected to be numbered the same way by both implementations.
+            Err(_) => {
+                tr_sort(&mut rustfst_output, ILabelCompare {});
+                tr_sort(&mut openfst_output, ILabelCompare {});
+                rustfst_output == openfst_output
+            }
+        };
+        if !same {
+            bail!(
+                "{} : outputs differ for the input\n{}\nrustfst :\n{}\nOpenFst :\n{}",
+                check.binary,
+                fst,
+                rustfst_output,
+                openfst_output
+            );
+        }
+        Ok(())
+    }
+
+    /// Runs all the checks on `num_fsts` random FSTs.
+    pub fn run_random(
+        &self,
+        checks: &[CompatCheck],
+        config: RandomFstConfig,
+        num_fsts: usize,
+    ) -> Result<()> {
+        let mut rng = ChaCha8Rng::seed_from_u64(config.seed);
+        for _ in 0..num_fsts {
+            let fst = random_acyclic_fst(&mut rng, &config)?;
+            for check in checks {
+                self.check(check, &fst)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::fst_properties::FstProperties;
+    use crate::fst_traits::CoreFst;
+
+    #[test]
+    fn test_random_acyclic_fst() -> Result<()> {
+        let mut rng = ChaCha8Rng::seed_from_u64(7);
+        let config = RandomFstConfig::default();
+        for _ in 0..20 {
+            let fst = random_acyclic_fst(&mut rng, &config)?;
+            let props = fst.properties_check(FstProperties::ACYCLIC)?;
+            assert!(props.contains(FstProperties::ACYCLIC));
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_openfst_compat_if_available() -> Result<()> {
+        let harness = OpenFstHarness::new();
+        if !harness.is_available() {
+            return Ok(());
+        }
+        let config = RandomFstConfig::default();
+        harness.run_random(&CompatCheck::defaults(), config, 20)
+    }
+}
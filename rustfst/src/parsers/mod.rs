@@ -1,5 +1,6 @@
 pub(crate) mod bin_fst;
 pub(crate) mod bin_symt;
+pub mod morphology;
 pub mod nom_utils;
 pub mod text_fst;
 pub(crate) mod text_symt;
@@ -0,0 +1,331 @@
+//! Importers for the transducers produced by the morphology toolkits (foma, HFST).
+//!
+//! The symbols of the imported transducers are stored in a single `SymbolTable` set as both
+//! the input and the output symbol table of the FST. The special symbols of these toolkits
+//! (`@_UNKNOWN_SYMBOL_@`, `@_IDENTITY_SYMBOL_@`, flag diacritics...) are kept as regular
+//! symbols and are not expanded.
+
+use std::collections::HashMap;
+use std::fs::read_to_string;
+use std::path::Path;
+use std::sync::Arc;
+
+use anyhow::{Context, Result};
+
+use crate::fst_traits::MutableFst;
+use crate::semirings::{Semiring, SerializableSemiring};
+use crate::{Label, StateId, SymbolTable, Tr, EPS_LABEL, EPS_SYMBOL};
+
+/// Symbols used by foma and HFST for the epsilon.
+const EPSILON_SYMBOLS: &[&str] = &["@_EPSILON_SYMBOL_@", "@0@", EPS_SYMBOL];
+
+fn symbol_to_label(symt: &mut SymbolTable, symbol: &str) -> Label {
+    if EPSILON_SYMBOLS.contains(&symbol) {
+        EPS_LABEL
+    } else {
+        symt.add_symbol(symbol)
+    }
+}
+
+fn ensure_state<W: Semiring, F: MutableFst<W>>(fst: &mut F, state: StateId) {
+    let num_states = fst.num_states();
+    if state as usize >= num_states {
+        fst.add_states(state as usize + 1 - num_states);
+    }
+}
+
+fn parse_field<T: std::str::FromStr>(field: &str, line_idx: usize) -> Result<T> {
+    field
+        .parse()
+        .map_err(|_| format_err!("Line {} : can't parse {:?}", line_idx + 1, field))
+}
+
+/// Loads the first network of a foma file in text format (the content of a `.foma` file
+/// once decompressed with `gunzip`).
+///
+/// foma networks are unweighted: all the transitions and final states have a one weight.
+/// The start state is the state 0.
+///
+/// # Example
+/// ```
+/// # use rustfst::morphology::parse_foma_text;
+/// # use rustfst::fst_impls::VectorFst;
+/// # use rustfst::fst_traits::{CoreFst, Fst};
+/// # use rustfst::semirings::TropicalWeight;
+/// # use rustfst::Trs;
+/// # use anyhow::Result;
+/// # fn main() -> Result<()> {
+/// let text = concat!(
+///     "##foma-net 1.0##\n",
+///     "##props##\n",
+///     "2 2 3 4 1 1 1 1 1 1 1 2 0 0 cat\n",
+///     "##sigma##\n",
+///     "3 c\n",
+///     "4 k\n",
+///     "5 a\n",
+///     "##states##\n",
+///     "0 3 4 1 0\n",
+///     "1 5 2 0\n",
+///     "2 -1 -1 1\n",
+///     "-1 -1 -1 -1 -1\n",
+///     "##end##\n",
+/// );
+/// let fst: VectorFst<TropicalWeight> = parse_foma_text(text)?;
+/// let symt = fst.input_symbols().unwrap();
+/// assert_eq!(fst.num_trs(0)?, 1);
+/// assert!(fst.is_final(2)?);
+/// assert_eq!(symt.get_symbol(fst.get_trs(0)?.trs()[0].ilabel), Some("c"));
+/// assert_eq!(symt.get_symbol(fst.get_trs(0)?.trs()[0].olabel), Some("k"));
+/// # Ok(())
+/// # }
+/// ```
+pub fn parse_foma_text<W: Semiring, F: MutableFst<W>>(text: &str) -> Result<F> {
+    #[derive(PartialEq)]
+    enum Section {
+        Header,
+        Props,
+        Sigma,
+        States,
+    }
+
+    let mut fst = F::new();
+    let mut symt = SymbolTable::new();
+    let mut sigma: HashMap<i64, Label> = HashMap::new();
+    let mut section = Section::Header;
+    let mut current_state: Option<StateId> = None;
+
+    for (line_idx, line) in text.lines().enumerate() {
+        let line = line.trim_end_matches('\r');
+        match line {
+            "##foma-net 1.0##" => continue,
+            "##props##" => {
+                section = Section::Props;
+                continue;
+            }
+            "##sigma##" => {
+                section = Section::Sigma;
+                continue;
+            }
+            "##states##" => {
+                section = Section::States;
+                continue;
+            }
+            "##end##" => break,
+            _ => {}
+        }
+        match section {
+            Section::Header => bail!("Line {} : not a foma network", line_idx + 1),
+            Section::Props => {}
+            Section::Sigma => {
+                let mut fields = line.splitn(2, ' ');
+                let id: i64 = parse_field(fields.next().unwrap_or(""), line_idx)?;
+                let symbol = fields
+                    .next()
+                    .ok_or_else(|| format_err!("Line {} : missing symbol", line_idx + 1))?;
+                sigma.insert(id, symbol_to_label(&mut symt, symbol));
+            }
+            Section::States => {
+                let fields = line
+                    .split_whitespace()
+                    .map(|f| parse_field::<i64>(f, line_idx))
+                    .collect::<Result<Vec<_>>>()?;
+                // The state and the final flag are only written on the first line of each state
+                // and the output label is omitted when it is equal to the input label.
+                let (state, ilabel, olabel, nextstate, is_final) = match fields.as_slice() {
+                    [-1, -1, -1, -1, -1] => break,
+                    [i, n] => (None, *i, *i, *n, None),
+                    [i, o, n] => (None, *i, *o, *n, None),
+                    [s, i, n, f] => (Some(*s), *i, *i, *n, Some(*f)),
+                    [s, i, o, n, f] => (Some(*s), *i, *o, *n, Some(*f)),
+                    _ => bail!("Line {} : unexpected number of fields", line_idx + 1),
+                };
+                if let Some(s) = state {
+                    let s = s as StateId;
+                    ensure_state(&mut fst, s);
+                    if s == 0 {
+                        fst.set_start(s)?;
+                    }
+                    if is_final == Some(1) {
+                        fst.set_final(s, W::one())?;
+                    }
+                    current_state = Some(s);
+                }
+                if nextstate < 0 {
+                    continue;
+                }
+                let source = current_state.ok_or_else(|| {
+                    format_err!("Line {} : transition without state", line_idx + 1)
+                })?;
+                let label = |id: i64| {
+                    sigma.get(&id).cloned().ok_or_else(|| {
+                        format_err!("Line {} : symbol {} not in sigma", line_idx + 1, id)
+                    })
+                };
+                let (ilabel, olabel) = (label(ilabel)?, label(olabel)?);
+                ensure_state(&mut fst, nextstate as StateId);
+                fst.add_tr(
+                    source,
+                    Tr::new(ilabel, olabel, W::one(), nextstate as StateId),
+                )?;
+            }
+        }
+    }
+
+    let symt = Arc::new(symt);
+    fst.set_input_symbols(Arc::clone(&symt));
+    fst.set_output_symbols(symt);
+    Ok(fst)
+}
+
+/// Loads the first network of a decompressed foma file.
+pub fn read_foma_text<W: Semiring, F: MutableFst<W>, P: AsRef<Path>>(path: P) -> Result<F> {
+    let text = read_to_string(path.as_ref())
+        .with_context(|| format!("Can't open foma file : {:?}", path.as_ref()))?;
+    parse_foma_text(&text)
+}
+
+/// Loads the first transducer of a file in the AT&T format with symbols, as written by
+/// `hfst-fst2txt` or foma's `write att`.
+///
+/// Transition format: `src dest isymbol osymbol [weight]`, final state format: `state [weight]`.
+/// The fields are separated by tabulations and the source of the first line is the start
+/// state. `@0@` and `@_EPSILON_SYMBOL_@` are mapped to the epsilon label. Transducers
+/// are separated by a line `--`.
+///
+/// # Example
+/// ```
+/// # use rustfst::morphology::parse_att_symbolic;
+/// # use rustfst::fst_impls::VectorFst;
+/// # use rustfst::fst_traits::{CoreFst, Fst};
+/// # use rustfst::semirings::{Semiring, TropicalWeight};
+/// # use rustfst::{Trs, EPS_LABEL};
+/// # use anyhow::Result;
+/// # fn main() -> Result<()> {
+/// let text = "0\t1\tc\tc\t0.5\n1\t2\ta\t@0@\n2\t1.5\n";
+/// let fst: VectorFst<TropicalWeight> = parse_att_symbolic(text)?;
+/// assert_eq!(fst.start(), Some(0));
+/// assert_eq!(fst.get_trs(1)?.trs()[0].olabel, EPS_LABEL);
+/// assert_eq!(fst.final_weight(2)?, Some(TropicalWeight::new(1.5)));
+/// # Ok(())
+/// # }
+/// ```
+pub fn parse_att_symbolic<W: SerializableSemiring, F: MutableFst<W>>(text: &str) -> Result<F> {
+    let mut fst = F::new();
+    let mut symt = SymbolTable::new();
+
+    let parse_weight = |field: Option<&&str>, line_idx: usize| -> Result<W> {
+        match field {
+            None => Ok(W::one()),
+            Some(f) => match W::parse_text(f) {
+                Ok(("", w)) => Ok(w),
+                _ => bail!("Line {} : can't parse weight {:?}", line_idx + 1, f),
+            },
+        }
+    };
+
+    for (line_idx, line) in text.lines().enumerate() {
+        let line = line.trim_end_matches('\r');
+        if line == "--" {
+            break;
+        }
+        if line.is_empty() {
+            continue;
+        }
+        let fields: Vec<&str> = line.split('\t').collect();
+        let state: StateId = parse_field(fields[0], line_idx)?;
+        ensure_state(&mut fst, state);
+        if fst.start().is_none() {
+            fst.set_start(state)?;
+        }
+        match fields.len() {
+            1 | 2 => {
+                let weight = parse_weight(fields.get(1), line_idx)?;
+                fst.set_final(state, weight)?;
+            }
+            4 | 5 => {
+                let nextstate: StateId = parse_field(fields[1], line_idx)?;
+                ensure_state(&mut fst, nextstate);
+                let ilabel = symbol_to_label(&mut symt, fields[2]);
+                let olabel = symbol_to_label(&mut symt, fields[3]);
+                let weight = parse_weight(fields.get(4), line_idx)?;
+                fst.add_tr(state, Tr::new(ilabel, olabel, weight, nextstate))?;
+            }
+            _ => bail!("Line {} : unexpected number of fields", line_idx + 1),
+        }
+    }
+
+    let symt = Arc::new(symt);
+    fst.set_input_symbols(Arc::clone(&symt));
+    fst.set_output_symbols(symt);
+    Ok(fst)
+}
+
+/// Loads the first transducer of a file in the AT&T format with symbols.
+pub fn read_att_symbolic<W: SerializableSemiring, F: MutableFst<W>, P: AsRef<Path>>(
+    path: P,
+) -> Result<F> {
+    let text = read_to_string(path.as_ref())
+        .with_context(|| format!("Can't open AT&T file : {:?}", path.as_ref()))?;
+    parse_att_symbolic(&text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::algorithms::transduce;
+    use crate::fst_impls::VectorFst;
+    use crate::fst_traits::{CoreFst, ExpandedFst, Fst};
+    use crate::semirings::TropicalWeight;
+
+    #[test]
+    fn test_parse_foma_text_compressed_lines() -> Result<()> {
+        // cat:dog with a loop on the last state reading `s`.
+        let text = "##foma-net 1.0##
+##props##
+2 4 4 6 1 1 1 1 1 1 0 2 0 0 test
+##sigma##
+0 @_EPSILON_SYMBOL_@
+3 c
+4 d
+5 a
+6 o
+7 t
+8 g
+9 s
+##states##
+0 3 4 1 0
+1 5 6 2 0
+2 7 8 3 0
+3 9 0 3 1
+-1 -1 -1 -1 -1
+##end##
+";
+        let fst: VectorFst<TropicalWeight> = parse_foma_text(text)?;
+        assert_eq!(fst.num_states(), 4);
+        assert!(fst.is_final(3)?);
+        assert!(!fst.is_final(0)?);
+
+        let symt = fst.input_symbols().unwrap();
+        let label = |s: &str| symt.get_label(s).unwrap();
+        let outputs = transduce(&fst, &[label("c"), label("a"), label("t"), label("s")], 1)?;
+        assert_eq!(
+            outputs,
+            vec![(
+                vec![label("d"), label("o"), label("g")],
+                TropicalWeight::one()
+            )]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_att_symbolic_stops_at_separator() -> Result<()> {
+        let text = "0\t1\ta\tb\n1\n--\n0\t1\tc\tc\n1\n";
+        let fst: VectorFst<TropicalWeight> = parse_att_symbolic(text)?;
+        assert_eq!(fst.num_states(), 2);
+        assert_eq!(fst.input_symbols().unwrap().get_label("c"), None);
+        assert!(parse_att_symbolic::<TropicalWeight, VectorFst<_>>("0\t1\ta\n").is_err());
+        Ok(())
+    }
+}
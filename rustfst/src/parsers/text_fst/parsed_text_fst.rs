@@ -5,7 +5,7 @@ use anyhow::Result;
 
 use crate::parsers::text_fst::nom_parser::vec_rows_parsed;
 use crate::semirings::SerializableSemiring;
-use crate::{Label, StateId};
+use crate::{Label, StateId, SymbolTable};
 
 #[derive(Debug, PartialEq)]
 pub enum RowParsed<W: SerializableSemiring> {
@@ -139,6 +139,39 @@ impl<W: SerializableSemiring> ParsedTextFst<W> {
         Self::from_string(&fst_string)
     }
 
+    /// Loads an FST from a loaded string in text format whose labels are the symbols of
+    /// `isymt` and `osymt`, as the input of `fstcompile --isymbols --osymbols`. The labels of
+    /// a side without symbol table are integers.
+    ///
+    /// ## Example:
+    /// ```text
+    /// 0   1   a   x
+    /// 1   2   b   <eps>   0.25
+    /// 2
+    /// ```
+    pub fn from_string_with_symts(
+        fst_string: &str,
+        isymt: Option<&SymbolTable>,
+        osymt: Option<&SymbolTable>,
+    ) -> Result<Self> {
+        let mut lines = Vec::new();
+        for (idx, line) in fst_string.lines().enumerate() {
+            let mut fields: Vec<String> = line.split('\t').map(|f| f.to_string()).collect();
+            if fields.len() >= 4 {
+                for (field, symt) in fields[2..4].iter_mut().zip([isymt, osymt].iter()) {
+                    if let Some(symt) = symt {
+                        let label = symt.get_label(field.as_str()).ok_or_else(|| {
+                            format_err!("Unknown symbol {:?} at line {}", field, idx + 1)
+                        })?;
+                        *field = label.to_string();
+                    }
+                }
+            }
+            lines.push(fields.join("\t"));
+        }
+        Self::from_string(&lines.join("\n"))
+    }
+
     pub fn start(&self) -> Option<StateId> {
         self.start_state
     }
@@ -232,6 +265,31 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_parse_text_fst_with_symts() -> Result<()> {
+        let mut isymt = SymbolTable::new();
+        isymt.add_symbol("a");
+        let parsed_fst = ParsedTextFst::<TropicalWeight>::from_string_with_symts(
+            "0\t1\ta\t3\t0.5\n1\n",
+            Some(&isymt),
+            None,
+        )?;
+        let parsed_fst_ref = ParsedTextFst {
+            start_state: Some(0),
+            transitions: vec![Transition::new(0, 1, 3, Some(TropicalWeight::new(0.5)), 1)],
+            final_states: vec![FinalState::new(1, None)],
+        };
+        assert_eq!(parsed_fst, parsed_fst_ref);
+
+        assert!(ParsedTextFst::<TropicalWeight>::from_string_with_symts(
+            "0\t1\tb\t3\n1\n",
+            Some(&isymt),
+            None
+        )
+        .is_err());
+        Ok(())
+    }
+
     #[test]
     fn test_parse_text_fst_infinity_final_states() -> Result<()> {
         let parsed_fst =
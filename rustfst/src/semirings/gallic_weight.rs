@@ -140,6 +140,14 @@ macro_rules! gallic_weight {
             fn properties() -> SemiringProperties {
                 ProductWeight::<$string_weight, W>::properties()
             }
+
+            fn is_nan(&self) -> bool {
+                self.0.is_nan()
+            }
+
+            fn is_member(&self) -> bool {
+                self.0.is_member()
+            }
         }
 
         impl<W> $semiring
@@ -394,6 +402,14 @@ impl<W: Semiring> Semiring for GallicWeight<W> {
     fn properties() -> SemiringProperties {
         UnionWeight::<GallicWeightRestrict<W>, GallicUnionWeightOption<GallicWeightRestrict<W>>>::properties()
     }
+
+    fn is_nan(&self) -> bool {
+        self.0.is_nan()
+    }
+
+    fn is_member(&self) -> bool {
+        self.0.is_member()
+    }
 }
 
 impl<W: Semiring> ReverseBack<GallicWeight<W>> for <GallicWeight<W> as Semiring>::ReverseWeight {
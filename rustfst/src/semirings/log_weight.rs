@@ -101,6 +101,14 @@ impl Semiring for LogWeight {
             | SemiringProperties::RIGHT_SEMIRING
             | SemiringProperties::COMMUTATIVE
     }
+
+    fn is_nan(&self) -> bool {
+        self.value.0.is_nan()
+    }
+
+    fn is_member(&self) -> bool {
+        !self.is_nan() && self.value.0 != f32::NEG_INFINITY
+    }
 }
 
 impl ReverseBack<LogWeight> for LogWeight {
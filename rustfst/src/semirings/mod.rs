@@ -27,7 +27,7 @@ pub use self::probability_weight::ProbabilityWeight;
 pub use self::product_weight::ProductWeight;
 pub use self::semiring::{
     CompleteSemiring, DivideType, ReverseBack, Semiring, SemiringProperties, SerializableSemiring,
-    StarSemiring, WeaklyDivisibleSemiring, WeightQuantize,
+    StarSemiring, WeaklyDivisibleSemiring, WeightQuantize, WeightValidation,
 };
 pub(crate) use self::string_variant::StringWeightVariant;
 pub use self::string_weight::{
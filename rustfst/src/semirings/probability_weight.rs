@@ -80,6 +80,14 @@ impl Semiring for ProbabilityWeight {
             | SemiringProperties::RIGHT_SEMIRING
             | SemiringProperties::COMMUTATIVE
     }
+
+    fn is_nan(&self) -> bool {
+        self.value.0.is_nan()
+    }
+
+    fn is_member(&self) -> bool {
+        !self.is_nan() && self.value.0 >= 0.0
+    }
 }
 
 impl ReverseBack<ProbabilityWeight> for ProbabilityWeight {
@@ -100,6 +100,14 @@ where
                 | SemiringProperties::COMMUTATIVE
                 | SemiringProperties::IDEMPOTENT)
     }
+
+    fn is_nan(&self) -> bool {
+        self.value1().is_nan() || self.value2().is_nan()
+    }
+
+    fn is_member(&self) -> bool {
+        self.value1().is_member() && self.value2().is_member()
+    }
 }
 
 impl<W1: Semiring, W2: Semiring> ReverseBack<ProductWeight<W1, W2>>
@@ -27,6 +27,34 @@ bitflags! {
     }
 }
 
+impl SemiringProperties {
+    /// Returns an error naming `algorithm` and the missing properties if these properties don't
+    /// contain all of `required`, so that an algorithm can check `W::properties()` before
+    /// running instead of returning incorrect results.
+    pub fn check(self, required: SemiringProperties, algorithm: &str) -> Result<()> {
+        if !self.contains(required) {
+            bail!(
+                "{} : the weight must have the properties {:?} but is missing {:?}",
+                algorithm,
+                required,
+                required - self
+            );
+        }
+        Ok(())
+    }
+}
+
+/// How strictly the weights are validated by `Semiring::new_checked`,
+/// `ExpandedFst::validate_weights` and the readers `SerializableFst::read_with_validation`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WeightValidation {
+    /// Rejects the NaN values only.
+    Lenient,
+    /// Also rejects the values outside of the semiring, e.g. a cost of `-inf` in the
+    /// tropical semiring.
+    Strict,
+}
+
 /// The weight on an Fst must implement the `Semiring` trait.
 /// Indeed, the weight set associated to a Fst must have the structure of a semiring.
 /// `(S, +, *, 0, 1)` is a semiring if `(S, +, 0)` is a commutative monoid with identity element 0,
@@ -72,6 +100,32 @@ pub trait Semiring: Clone + PartialEq + PartialOrd + Debug + Hash + Eq + Sync +
     }
     fn reverse(&self) -> Result<Self::ReverseWeight>;
     fn properties() -> SemiringProperties;
+
+    /// Whether the weight is or contains a NaN value.
+    fn is_nan(&self) -> bool {
+        false
+    }
+    /// Whether the weight is a valid element of the semiring. NaN values are not.
+    fn is_member(&self) -> bool {
+        !self.is_nan()
+    }
+    /// Returns an error if the weight is not valid according to `validation`.
+    fn validate(&self, validation: WeightValidation) -> Result<()> {
+        match validation {
+            WeightValidation::Lenient if self.is_nan() => bail!("NaN weight {:?}", self),
+            WeightValidation::Strict if !self.is_member() => {
+                bail!("{:?} is not a valid weight of the semiring", self)
+            }
+            _ => Ok(()),
+        }
+    }
+    /// Same as `new` but returns an error if the weight is not valid according to
+    /// `validation`, instead of letting a NaN propagate through the algorithms.
+    fn new_checked(value: Self::Type, validation: WeightValidation) -> Result<Self> {
+        let w = Self::new(value);
+        w.validate(validation)?;
+        Ok(w)
+    }
 }
 
 pub trait ReverseBack<W> {
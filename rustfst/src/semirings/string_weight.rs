@@ -16,7 +16,7 @@ use crate::semirings::{
     DivideType, ReverseBack, Semiring, SemiringProperties, SerializableSemiring,
     WeaklyDivisibleSemiring, WeightQuantize,
 };
-use crate::Label;
+use crate::{Label, NO_LABEL};
 
 /// String semiring: (identity, ., Infinity, Epsilon)
 #[derive(Clone, Debug, PartialOrd, PartialEq, Eq, Hash)]
@@ -174,6 +174,13 @@ macro_rules! string_semiring {
                     }
                 }
             }
+
+            fn is_member(&self) -> bool {
+                match &self.value {
+                    StringWeightVariant::Infinity => true,
+                    StringWeightVariant::Labels(l) => l.iter().all(|l| *l != NO_LABEL),
+                }
+            }
         }
 
         impl $semiring {
@@ -93,6 +93,14 @@ impl Semiring for TropicalWeight {
             | SemiringProperties::PATH
             | SemiringProperties::IDEMPOTENT
     }
+
+    fn is_nan(&self) -> bool {
+        self.value.0.is_nan()
+    }
+
+    fn is_member(&self) -> bool {
+        !self.is_nan() && self.value.0 != f32::NEG_INFINITY
+    }
 }
 
 impl ReverseBack<TropicalWeight> for TropicalWeight {
@@ -176,6 +176,14 @@ impl<W: Semiring, O: UnionWeightOption<W>> Semiring for UnionWeight<W, O> {
                 | SemiringProperties::COMMUTATIVE
                 | SemiringProperties::IDEMPOTENT)
     }
+
+    fn is_nan(&self) -> bool {
+        self.list.iter().any(|w| w.is_nan())
+    }
+
+    fn is_member(&self) -> bool {
+        self.list.iter().all(|w| w.is_member())
+    }
 }
 
 impl<W: Semiring, O: UnionWeightOption<W>> UnionWeight<W, O> {
@@ -105,15 +105,6 @@ impl MatcherFlagsTrait for DefaultLabelLookAheadMatcherFlags {
     }
 }
 
-#[derive(Debug, Clone, PartialOrd, PartialEq)]
-pub struct OLabelLookAheadFlags {}
-
-impl MatcherFlagsTrait for OLabelLookAheadFlags {
-    fn flags() -> MatcherFlags {
-        MatcherFlags::OLABEL_LOOKAHEAD_FLAGS
-    }
-}
-
 fn do_test_compose_lookahead<W>(
     fst_raw: &VectorFst<W>,
     compose_test_data: &ComposeTestData<W, VectorFst<W>>,
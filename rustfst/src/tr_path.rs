@@ -0,0 +1,253 @@
+use std::ops::Range;
+
+use anyhow::Result;
+
+use crate::fst_properties::FstProperties;
+use crate::fst_traits::{ExpandedFst, MutableFst};
+use crate::semirings::Semiring;
+use crate::{FstPath, Label, StateId, SymbolTable, Tr, Trs, EPS_LABEL};
+
+/// Successful path of an FST keeping its states and its transitions, with their own weights,
+/// whereas `FstPath` only keeps the labels and the total weight.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TrPath<W: Semiring> {
+    /// States of the path, one more than the transitions.
+    pub states: Vec<StateId>,
+    /// Transitions of the path, the i-th one going from the i-th state to the next one.
+    pub trs: Vec<Tr<W>>,
+    /// Final weight of the last state.
+    pub final_weight: W,
+}
+
+impl<W: Semiring> TrPath<W> {
+    /// Creates an empty path at `start`, with a final weight one.
+    pub fn new(start: StateId) -> Self {
+        Self {
+            states: vec![start],
+            trs: vec![],
+            final_weight: W::one(),
+        }
+    }
+
+    /// Appends a transition leaving the last state of the path.
+    pub fn push(&mut self, tr: Tr<W>) {
+        self.states.push(tr.nextstate);
+        self.trs.push(tr);
+    }
+
+    pub fn start(&self) -> StateId {
+        self.states[0]
+    }
+
+    pub fn last_state(&self) -> StateId {
+        self.states[self.states.len() - 1]
+    }
+
+    /// Number of transitions of the path.
+    pub fn len(&self) -> usize {
+        self.trs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.trs.is_empty()
+    }
+
+    /// Product of the weights of the transitions and of the final weight.
+    pub fn weight(&self) -> Result<W> {
+        let mut weight = W::one();
+        for tr in self.trs.iter() {
+            weight.times_assign(&tr.weight)?;
+        }
+        weight.times_assign(&self.final_weight)?;
+        Ok(weight)
+    }
+
+    /// Input labels of the path, without the epsilons.
+    pub fn ilabels(&self) -> Vec<Label> {
+        self.trs
+            .iter()
+            .map(|tr| tr.ilabel)
+            .filter(|l| *l != EPS_LABEL)
+            .collect()
+    }
+
+    /// Output labels of the path, without the epsilons.
+    pub fn olabels(&self) -> Vec<Label> {
+        self.trs
+            .iter()
+            .map(|tr| tr.olabel)
+            .filter(|l| *l != EPS_LABEL)
+            .collect()
+    }
+
+    /// Symbols of the input labels separated by spaces.
+    pub fn istring(&self, symt: &SymbolTable) -> Result<String> {
+        labels_to_string(&self.ilabels(), symt)
+    }
+
+    /// Symbols of the output labels separated by spaces.
+    pub fn ostring(&self, symt: &SymbolTable) -> Result<String> {
+        labels_to_string(&self.olabels(), symt)
+    }
+
+    /// Path made of the transitions in `range`. Its final weight is the one of this path if
+    /// it ends at the same position and one otherwise.
+    pub fn sub_path(&self, range: Range<usize>) -> Result<Self> {
+        if range.start > range.end || range.end > self.len() {
+            bail!(
+                "Range {:?} out of the {} transitions of the path",
+                range,
+                self.len()
+            );
+        }
+        let final_weight = if range.end == self.len() {
+            self.final_weight.clone()
+        } else {
+            W::one()
+        };
+        Ok(Self {
+            states: self.states[range.start..=range.end].to_vec(),
+            trs: self.trs[range].to_vec(),
+            final_weight,
+        })
+    }
+
+    /// Replaces the weight of each transition with `f(tr)`, e.g. its weight in another model.
+    pub fn rescore<M: FnMut(&Tr<W>) -> Result<W>>(&mut self, mut f: M) -> Result<()> {
+        for tr in self.trs.iter_mut() {
+            tr.weight = f(tr)?;
+        }
+        Ok(())
+    }
+
+    /// Labels and total weight of the path.
+    pub fn to_fst_path(&self) -> Result<FstPath<W>> {
+        Ok(FstPath::new(self.ilabels(), self.olabels(), self.weight()?))
+    }
+}
+
+fn labels_to_string(labels: &[Label], symt: &SymbolTable) -> Result<String> {
+    let symbols = labels
+        .iter()
+        .map(|l| {
+            symt.get_symbol(*l)
+                .ok_or_else(|| format_err!("Missing {} in symbol table", l))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    Ok(symbols.join(" "))
+}
+
+/// Returns all the successful paths of an acyclic FST, e.g. the result of `shortest_path` or
+/// of `randgen`, in depth-first order.
+pub fn acyclic_paths<W: Semiring, F: ExpandedFst<W>>(fst: &F) -> Result<Vec<TrPath<W>>> {
+    if !fst
+        .properties_check(FstProperties::ACYCLIC)?
+        .contains(FstProperties::ACYCLIC)
+    {
+        bail!("acyclic_paths : the FST must be acyclic");
+    }
+    let mut paths = vec![];
+    let mut stack = match fst.start() {
+        Some(start) => vec![TrPath::new(start)],
+        None => vec![],
+    };
+    while let Some(path) = stack.pop() {
+        let state = path.last_state();
+        if let Some(final_weight) = fst.final_weight(state)? {
+            let mut path = path.clone();
+            path.final_weight = final_weight;
+            paths.push(path);
+        }
+        // Pushed in reverse so that the first transitions are explored first.
+        for tr in fst.get_trs(state)?.trs().iter().rev() {
+            let mut next = path.clone();
+            next.push(tr.clone());
+            stack.push(next);
+        }
+    }
+    Ok(paths)
+}
+
+/// Builds an FST from a set of paths, sharing their start state only. The transitions keep
+/// their weights and the last state of each path gets its final weight.
+pub fn paths_to_fst<'a, W, F, I>(paths: I) -> Result<F>
+where
+    W: Semiring,
+    F: MutableFst<W>,
+    I: IntoIterator<Item = &'a TrPath<W>>,
+{
+    let mut fst = F::new();
+    let start = fst.add_state();
+    fst.set_start(start)?;
+    for path in paths {
+        let mut state = start;
+        for tr in path.trs.iter() {
+            let nextstate = fst.add_state();
+            fst.add_tr(
+                state,
+                Tr::new(tr.ilabel, tr.olabel, tr.weight.clone(), nextstate),
+            )?;
+            state = nextstate;
+        }
+        let final_weight = match fst.final_weight(state)? {
+            Some(w) => w.plus(&path.final_weight)?,
+            None => path.final_weight.clone(),
+        };
+        fst.set_final(state, final_weight)?;
+    }
+    Ok(fst)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::fst_impls::VectorFst;
+    use crate::fst_traits::Fst;
+    use crate::semirings::TropicalWeight;
+    use crate::symt;
+
+    #[test]
+    fn test_path() -> Result<()> {
+        let mut fst = VectorFst::<TropicalWeight>::new();
+        fst.add_states(4);
+        fst.set_start(0)?;
+        fst.add_tr(0, Tr::new(1, 4, 1.0, 1))?;
+        fst.add_tr(1, Tr::new(2, EPS_LABEL, 2.0, 2))?;
+        fst.add_tr(0, Tr::new(3, 5, 0.5, 3))?;
+        fst.set_final(2, 0.5)?;
+        fst.set_final(3, 1.0)?;
+
+        let paths = acyclic_paths(&fst)?;
+        assert_eq!(paths.len(), 2);
+        let path = &paths[0];
+        assert_eq!(path.states, vec![0, 1, 2]);
+        assert_eq!(path.ilabels(), vec![1, 2]);
+        assert_eq!(path.olabels(), vec![4]);
+        assert_eq!(path.weight()?, TropicalWeight::new(3.5));
+        assert_eq!(
+            path.to_fst_path()?,
+            FstPath::new(vec![1, 2], vec![4], TropicalWeight::new(3.5))
+        );
+
+        let symt = symt!["a", "b", "c", "d"];
+        assert_eq!(path.istring(&symt)?, "a b");
+        assert!(paths[1].ostring(&symt).is_err());
+
+        let sub_path = path.sub_path(1..2)?;
+        assert_eq!(sub_path.states, vec![1, 2]);
+        assert_eq!(sub_path.weight()?, TropicalWeight::new(2.5));
+        let mut prefix = path.sub_path(0..1)?;
+        prefix.rescore(|tr| Ok(TropicalWeight::new(*tr.weight.value() * 2.0)))?;
+        assert_eq!(prefix.weight()?, TropicalWeight::new(2.0));
+
+        let rebuilt: VectorFst<TropicalWeight> = paths_to_fst(&paths)?;
+        let mut weights: Vec<_> = rebuilt.paths_iter().map(|p| p.weight).collect();
+        weights.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        assert_eq!(
+            weights,
+            vec![TropicalWeight::new(1.5), TropicalWeight::new(3.5)]
+        );
+        Ok(())
+    }
+}
@@ -3,6 +3,10 @@ use crate::Tr;
 use std::fmt::Debug;
 use std::sync::Arc;
 
+/// Transitions leaving a state, as returned by `CoreFst::get_trs`. They dereference to a slice,
+/// so that they can be indexed and iterated over by reference in generic code, e.g.
+/// `trs[0]` or `for tr in trs.iter()`. Getting them from the FST may clone an `Arc` :
+/// `TrsSliceFst` borrows them directly from the FSTs storing them.
 pub trait Trs<W: Semiring>: std::ops::Deref<Target = [Tr<W>]> + Debug {
     fn trs(&self) -> &[Tr<W>];
     fn to_trs_vec(&self) -> TrsVec<W>;
@@ -52,6 +56,15 @@ impl<W: Semiring> std::ops::Deref for TrsVec<W> {
     }
 }
 
+impl<'a, W: Semiring> IntoIterator for &'a TrsVec<W> {
+    type Item = &'a Tr<W>;
+    type IntoIter = std::slice::Iter<'a, Tr<W>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.trs().iter()
+    }
+}
+
 impl<W: Semiring> Default for TrsVec<W> {
     fn default() -> Self {
         Self(Arc::new(vec![]))
@@ -107,6 +120,15 @@ impl<W: Semiring> std::ops::Deref for TrsConst<W> {
     }
 }
 
+impl<'a, W: Semiring> IntoIterator for &'a TrsConst<W> {
+    type Item = &'a Tr<W>;
+    type IntoIter = std::slice::Iter<'a, Tr<W>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.trs().iter()
+    }
+}
+
 impl<W: Semiring> Default for TrsConst<W> {
     fn default() -> Self {
         Self {
@@ -1,7 +1,11 @@
 mod epsilon_machine;
 mod fst_to_labels;
 mod labels_to_fst;
+pub mod number_grammars;
+mod subword_segmenter;
+pub mod unicode_transducers;
 
 pub use self::epsilon_machine::epsilon_machine;
 pub use self::fst_to_labels::decode_linear_fst;
 pub use self::labels_to_fst::{acceptor, transducer};
+pub use self::subword_segmenter::subword_segmenter;
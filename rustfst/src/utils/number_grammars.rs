@@ -0,0 +1,656 @@
+//! Builders of transducers verbalizing numbers and dates, to be used as primitives of text
+//! normalization grammars.
+//!
+//! The transducers read the digits of a number, one symbol per digit (`"0"` to `"9"`), and
+//! write its words. Both the digits and the words are added to the same `SymbolTable`, which
+//! is then used for the input and output sides. The words depend on the language through the
+//! [`NumberLexicon`] and [`DateLexicon`] traits, English implementations being provided.
+//!
+//! # Example
+//! ```
+//! # use rustfst::utils::number_grammars::{cardinal, EnglishNumberLexicon};
+//! # use rustfst::algorithms::transduce;
+//! # use rustfst::fst_impls::VectorFst;
+//! # use rustfst::semirings::TropicalWeight;
+//! # use rustfst::SymbolTable;
+//! # use anyhow::Result;
+//! # fn main() -> Result<()> {
+//! let mut symt = SymbolTable::new();
+//! let fst: VectorFst<TropicalWeight> = cardinal(&EnglishNumberLexicon, &mut symt)?;
+//!
+//! let digits: Vec<_> = "2021".chars().map(|c| symt.get_label(c.to_string()).unwrap()).collect();
+//! let (words, _) = transduce(&fst, &digits, 1)?.remove(0);
+//! let words: Vec<_> = words.iter().map(|l| symt.get_symbol(*l).unwrap()).collect();
+//! assert_eq!(words, vec!["two", "thousand", "twenty", "one"]);
+//! # Ok(())
+//! # }
+//! ```
+
+use std::collections::HashMap;
+
+use anyhow::Result;
+
+use crate::algorithms::concat::concat;
+use crate::algorithms::union::union;
+use crate::fst_traits::{AllocableFst, ExpandedFst, MutableFst};
+use crate::semirings::Semiring;
+use crate::{Label, StateId, SymbolTable, Tr, EPS_LABEL};
+
+/// Words of the numbers in a given language.
+pub trait NumberLexicon {
+    /// Word of a single digit, used for the digit sequences and for zero.
+    fn digit(&self, digit: u32) -> String;
+
+    /// Words of a number between 1 and 999.
+    fn below_thousand(&self, n: u32) -> Vec<String>;
+
+    /// Scale words, by increasing power of 1000 (e.g. `thousand`, `million`).
+    /// Their number bounds the numbers that can be verbalized.
+    fn scales(&self) -> Vec<String>;
+
+    /// Turns the words of a cardinal number into the ones of the corresponding ordinal.
+    fn ordinal(&self, cardinal_words: &[String]) -> Vec<String>;
+
+    /// Word read for the decimal separator.
+    fn decimal_point(&self) -> String;
+}
+
+/// English words for numbers up to 999,999,999.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct EnglishNumberLexicon;
+
+const ENGLISH_UNITS: [&str; 20] = [
+    "zero",
+    "one",
+    "two",
+    "three",
+    "four",
+    "five",
+    "six",
+    "seven",
+    "eight",
+    "nine",
+    "ten",
+    "eleven",
+    "twelve",
+    "thirteen",
+    "fourteen",
+    "fifteen",
+    "sixteen",
+    "seventeen",
+    "eighteen",
+    "nineteen",
+];
+
+const ENGLISH_TENS: [&str; 10] = [
+    "", "", "twenty", "thirty", "forty", "fifty", "sixty", "seventy", "eighty", "ninety",
+];
+
+impl NumberLexicon for EnglishNumberLexicon {
+    fn digit(&self, digit: u32) -> String {
+        ENGLISH_UNITS[digit as usize].to_string()
+    }
+
+    fn below_thousand(&self, n: u32) -> Vec<String> {
+        let mut words = vec![];
+        if n >= 100 {
+            words.push(ENGLISH_UNITS[(n / 100) as usize].to_string());
+            words.push("hundred".to_string());
+        }
+        let rest = n % 100;
+        if rest >= 20 {
+            words.push(ENGLISH_TENS[(rest / 10) as usize].to_string());
+            let units = rest % 10;
+            if units > 0 {
+                words.push(ENGLISH_UNITS[units as usize].to_string());
+            }
+        } else if rest > 0 {
+            words.push(ENGLISH_UNITS[rest as usize].to_string());
+        }
+        words
+    }
+
+    fn scales(&self) -> Vec<String> {
+        vec!["thousand".to_string(), "million".to_string()]
+    }
+
+    fn ordinal(&self, cardinal_words: &[String]) -> Vec<String> {
+        let mut words = cardinal_words.to_vec();
+        if let Some(last) = words.last_mut() {
+            *last = match last.as_str() {
+                "one" => "first".to_string(),
+                "two" => "second".to_string(),
+                "three" => "third".to_string(),
+                "five" => "fifth".to_string(),
+                "eight" => "eighth".to_string(),
+                "nine" => "ninth".to_string(),
+                "twelve" => "twelfth".to_string(),
+                w if w.ends_with('y') => format!("{}ieth", &w[..w.len() - 1]),
+                w => format!("{}th", w),
+            };
+        }
+        words
+    }
+
+    fn decimal_point(&self) -> String {
+        "point".to_string()
+    }
+}
+
+/// Words of the dates in a given language.
+pub trait DateLexicon: NumberLexicon {
+    /// Name of the month, between 1 and 12.
+    fn month(&self, month: u32) -> String;
+
+    /// Words of the day of the month, between 1 and 31.
+    fn day(&self, day: u32) -> Vec<String> {
+        self.ordinal(&self.below_thousand(day))
+    }
+
+    /// Words of a year between 1000 and 9999.
+    fn year(&self, year: u32) -> Vec<String>;
+}
+
+const ENGLISH_MONTHS: [&str; 12] = [
+    "january",
+    "february",
+    "march",
+    "april",
+    "may",
+    "june",
+    "july",
+    "august",
+    "september",
+    "october",
+    "november",
+    "december",
+];
+
+impl DateLexicon for EnglishNumberLexicon {
+    fn month(&self, month: u32) -> String {
+        ENGLISH_MONTHS[(month - 1) as usize].to_string()
+    }
+
+    /// Years read by pairs of digits, e.g. `nineteen oh five` or `twenty twenty one`, except
+    /// the first ten years of each millennium, e.g. `two thousand five`.
+    fn year(&self, year: u32) -> Vec<String> {
+        let (high, low) = (year / 100, year % 100);
+        if year % 1000 < 10 {
+            let mut words = self.below_thousand(year / 1000);
+            words.push("thousand".to_string());
+            words.extend(self.below_thousand(year % 1000));
+            return words;
+        }
+        let mut words = self.below_thousand(high);
+        if low == 0 {
+            words.push("hundred".to_string());
+        } else if low < 10 {
+            words.push("oh".to_string());
+            words.push(self.digit(low));
+        } else {
+            words.extend(self.below_thousand(low));
+        }
+        words
+    }
+}
+
+/// Order of the fields of a date.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DateOrder {
+    /// e.g. `12/31/1999`.
+    MonthDayYear,
+    /// e.g. `31/12/1999`.
+    DayMonthYear,
+    /// e.g. `1999-12-31`.
+    YearMonthDay,
+}
+
+/// Builds a transducer mapping each input string, read one character per symbol, to its
+/// words. The input side is a prefix tree and the words are written at the end.
+fn string_map<W, F, I>(symt: &mut SymbolTable, entries: I) -> Result<F>
+where
+    W: Semiring,
+    F: MutableFst<W>,
+    I: IntoIterator<Item = (String, Vec<String>)>,
+{
+    let mut fst = F::new();
+    let start = fst.add_state();
+    fst.set_start(start)?;
+    let end = fst.add_state();
+    fst.set_final(end, W::one())?;
+
+    let mut children: HashMap<(StateId, Label), StateId> = HashMap::new();
+    for (input, words) in entries {
+        let mut state = start;
+        for c in input.chars() {
+            let label = symt.add_symbol(c.to_string());
+            state = match children.get(&(state, label)) {
+                Some(next) => *next,
+                None => {
+                    let next = fst.add_state();
+                    fst.add_tr(state, Tr::new(label, EPS_LABEL, W::one(), next))?;
+                    children.insert((state, label), next);
+                    next
+                }
+            };
+        }
+        if words.is_empty() {
+            fst.add_tr(state, Tr::new(EPS_LABEL, EPS_LABEL, W::one(), end))?;
+        }
+        for (idx, word) in words.iter().enumerate() {
+            let next = if idx + 1 == words.len() {
+                end
+            } else {
+                fst.add_state()
+            };
+            let label = symt.add_symbol(word.as_str());
+            fst.add_tr(state, Tr::new(EPS_LABEL, label, W::one(), next))?;
+            state = next;
+        }
+    }
+    Ok(fst)
+}
+
+fn concat_all<W, F>(fsts: Vec<F>) -> Result<F>
+where
+    W: Semiring,
+    F: MutableFst<W> + ExpandedFst<W> + AllocableFst<W>,
+{
+    let mut iter = fsts.into_iter();
+    let mut res = iter.next().unwrap_or_else(F::new);
+    for fst in iter {
+        concat(&mut res, &fst)?;
+    }
+    Ok(res)
+}
+
+fn union_all<W, F>(fsts: Vec<F>) -> Result<F>
+where
+    W: Semiring,
+    F: MutableFst<W> + ExpandedFst<W> + AllocableFst<W>,
+{
+    let mut iter = fsts.into_iter();
+    let mut res = iter.next().unwrap_or_else(F::new);
+    for fst in iter {
+        union(&mut res, &fst)?;
+    }
+    Ok(res)
+}
+
+/// Words of the group of three digits `n` followed by the scale word of rank `scale`.
+fn group_words<L: NumberLexicon>(
+    lexicon: &L,
+    scales: &[String],
+    n: u32,
+    scale: usize,
+) -> Vec<String> {
+    let mut words = lexicon.below_thousand(n);
+    if scale > 0 {
+        words.push(scales[scale - 1].clone());
+    }
+    words
+}
+
+/// Leading group of a number: between 1 and 999, without leading zeros.
+fn leading_group<W, F, L>(
+    lexicon: &L,
+    symt: &mut SymbolTable,
+    scale: usize,
+    ordinal: bool,
+) -> Result<F>
+where
+    W: Semiring,
+    F: MutableFst<W>,
+    L: NumberLexicon,
+{
+    let scales = lexicon.scales();
+    let entries = (1..1000).map(|n| {
+        let words = group_words(lexicon, &scales, n, scale);
+        let words = if ordinal {
+            lexicon.ordinal(&words)
+        } else {
+            words
+        };
+        (n.to_string(), words)
+    });
+    string_map(symt, entries)
+}
+
+/// Group of exactly three digits following the leading group. `000` is verbalized as nothing
+/// and without scale word, unless `nonzero` is set in which case it is not accepted.
+fn inner_group<W, F, L>(
+    lexicon: &L,
+    symt: &mut SymbolTable,
+    scale: usize,
+    ordinal: bool,
+    nonzero: bool,
+) -> Result<F>
+where
+    W: Semiring,
+    F: MutableFst<W>,
+    L: NumberLexicon,
+{
+    let scales = lexicon.scales();
+    let first = if nonzero { 1 } else { 0 };
+    let entries = (first..1000).map(|n| {
+        let words = if n == 0 {
+            vec![]
+        } else {
+            group_words(lexicon, &scales, n, scale)
+        };
+        let words = if ordinal {
+            lexicon.ordinal(&words)
+        } else {
+            words
+        };
+        (format!("{:03}", n), words)
+    });
+    string_map(symt, entries)
+}
+
+/// Builds the transducer verbalizing the digits of a sequence one by one,
+/// e.g. `1 0 7` into `one zero seven`.
+pub fn digit_sequence<W, F, L>(lexicon: &L, symt: &mut SymbolTable) -> Result<F>
+where
+    W: Semiring,
+    F: MutableFst<W>,
+    L: NumberLexicon,
+{
+    let mut fst = F::new();
+    let start = fst.add_state();
+    let end = fst.add_state();
+    fst.set_start(start)?;
+    fst.set_final(end, W::one())?;
+    for digit in 0..10 {
+        let ilabel = symt.add_symbol(digit.to_string());
+        let olabel = symt.add_symbol(lexicon.digit(digit));
+        fst.add_tr(start, Tr::new(ilabel, olabel, W::one(), end))?;
+        fst.add_tr(end, Tr::new(ilabel, olabel, W::one(), end))?;
+    }
+    Ok(fst)
+}
+
+/// Builds the transducer verbalizing the cardinal numbers written without leading zeros,
+/// e.g. `1 0 0 5` into `one thousand five`. The largest number is limited by the scale words
+/// of the lexicon.
+pub fn cardinal<W, F, L>(lexicon: &L, symt: &mut SymbolTable) -> Result<F>
+where
+    W: Semiring,
+    F: MutableFst<W> + ExpandedFst<W> + AllocableFst<W>,
+    L: NumberLexicon,
+{
+    let num_groups = lexicon.scales().len() + 1;
+    let mut alternatives: Vec<F> = vec![string_map(
+        symt,
+        vec![("0".to_string(), vec![lexicon.digit(0)])],
+    )?];
+    for k in 1..=num_groups {
+        let mut parts = vec![leading_group(lexicon, symt, k - 1, false)?];
+        for scale in (0..k - 1).rev() {
+            parts.push(inner_group(lexicon, symt, scale, false, false)?);
+        }
+        alternatives.push(concat_all(parts)?);
+    }
+    union_all(alternatives)
+}
+
+/// Builds the transducer verbalizing the ordinal numbers, e.g. `2 1` into `twenty first`.
+/// The digits may be followed by an ordinal marker (e.g. `th` or `.`) that is read
+/// without output.
+pub fn ordinal<W, F, L>(lexicon: &L, symt: &mut SymbolTable, marker: Option<&str>) -> Result<F>
+where
+    W: Semiring,
+    F: MutableFst<W> + ExpandedFst<W> + AllocableFst<W>,
+    L: NumberLexicon,
+{
+    let num_groups = lexicon.scales().len() + 1;
+    let zero = lexicon.ordinal(&[lexicon.digit(0)]);
+    let mut alternatives: Vec<F> = vec![string_map(symt, vec![("0".to_string(), zero)])?];
+    for k in 1..=num_groups {
+        // The last word is in the last non-zero group, followed by `trailing_zeros` groups.
+        for trailing_zeros in 0..k {
+            let mut parts = vec![];
+            if trailing_zeros == k - 1 {
+                parts.push(leading_group(lexicon, symt, k - 1, true)?);
+            } else {
+                parts.push(leading_group(lexicon, symt, k - 1, false)?);
+                for scale in (trailing_zeros + 1..k - 1).rev() {
+                    parts.push(inner_group(lexicon, symt, scale, false, false)?);
+                }
+                parts.push(inner_group(lexicon, symt, trailing_zeros, true, true)?);
+            }
+            for _ in 0..trailing_zeros {
+                parts.push(string_map(symt, vec![("000".to_string(), vec![])])?);
+            }
+            alternatives.push(concat_all(parts)?);
+        }
+    }
+    let mut fst = union_all(alternatives)?;
+    if let Some(marker) = marker {
+        let mut marker_fst = F::new();
+        let s0 = marker_fst.add_state();
+        let s1 = marker_fst.add_state();
+        marker_fst.set_start(s0)?;
+        marker_fst.set_final(s1, W::one())?;
+        let label = symt.add_symbol(marker);
+        marker_fst.add_tr(s0, Tr::new(label, EPS_LABEL, W::one(), s1))?;
+        marker_fst.add_tr(s0, Tr::new(EPS_LABEL, EPS_LABEL, W::one(), s1))?;
+        concat(&mut fst, &marker_fst)?;
+    }
+    Ok(fst)
+}
+
+/// Builds the transducer verbalizing the decimal numbers: a cardinal, followed by
+/// `separator` and the digits of the fractional part, e.g. `3 . 1 4` into
+/// `three point one four`.
+pub fn decimal<W, F, L>(lexicon: &L, symt: &mut SymbolTable, separator: &str) -> Result<F>
+where
+    W: Semiring,
+    F: MutableFst<W> + ExpandedFst<W> + AllocableFst<W>,
+    L: NumberLexicon,
+{
+    let integer_part: F = cardinal(lexicon, symt)?;
+    let fractional_part: F = digit_sequence(lexicon, symt)?;
+
+    let mut separator_fst = F::new();
+    let s0 = separator_fst.add_state();
+    let s1 = separator_fst.add_state();
+    separator_fst.set_start(s0)?;
+    separator_fst.set_final(s1, W::one())?;
+    let ilabel = symt.add_symbol(separator);
+    let olabel = symt.add_symbol(lexicon.decimal_point());
+    separator_fst.add_tr(s0, Tr::new(ilabel, olabel, W::one(), s1))?;
+
+    concat_all(vec![integer_part, separator_fst, fractional_part])
+}
+
+/// Strings of the numbers between `first` and `last`, and with a leading zero for the ones
+/// below 10 if `leading_zero` is set.
+fn two_digits_entries(first: u32, last: u32, leading_zero: bool) -> Vec<(String, u32)> {
+    let mut entries: Vec<_> = (first..=last).map(|n| (n.to_string(), n)).collect();
+    if leading_zero {
+        entries.extend((first..=last.min(9)).map(|n| (format!("{:02}", n), n)));
+    }
+    entries
+}
+
+/// Builds the transducer verbalizing the months written with their number, with or without a
+/// leading zero, e.g. `0 3` or `3` into `march`.
+pub fn month<W, F, L>(lexicon: &L, symt: &mut SymbolTable) -> Result<F>
+where
+    W: Semiring,
+    F: MutableFst<W>,
+    L: DateLexicon,
+{
+    let entries = two_digits_entries(1, 12, true)
+        .into_iter()
+        .map(|(input, n)| (input, vec![lexicon.month(n)]));
+    string_map(symt, entries)
+}
+
+/// Builds the transducer verbalizing the days of the month, with or without a leading zero,
+/// e.g. `0 1` into `first`.
+pub fn day<W, F, L>(lexicon: &L, symt: &mut SymbolTable) -> Result<F>
+where
+    W: Semiring,
+    F: MutableFst<W>,
+    L: DateLexicon,
+{
+    let entries = two_digits_entries(1, 31, true)
+        .into_iter()
+        .map(|(input, n)| (input, lexicon.day(n)));
+    string_map(symt, entries)
+}
+
+/// Builds the transducer verbalizing the years written with four digits, e.g. `1 9 9 9` into
+/// `nineteen ninety nine`.
+pub fn year<W, F, L>(lexicon: &L, symt: &mut SymbolTable) -> Result<F>
+where
+    W: Semiring,
+    F: MutableFst<W>,
+    L: DateLexicon,
+{
+    let entries = (1000..10000).map(|n| (n.to_string(), lexicon.year(n)));
+    string_map(symt, entries)
+}
+
+/// Builds the transducer verbalizing the numeric dates whose fields are in the given order
+/// and separated by `separator`, e.g. `1 2 / 3 1 / 1 9 9 9` into
+/// `december thirty first nineteen ninety nine`. The fields are verbalized in the order in
+/// which they are written and the separators are read without output. The days are not
+/// checked against the months.
+pub fn date<W, F, L>(
+    lexicon: &L,
+    symt: &mut SymbolTable,
+    order: DateOrder,
+    separator: &str,
+) -> Result<F>
+where
+    W: Semiring,
+    F: MutableFst<W> + ExpandedFst<W> + AllocableFst<W>,
+    L: DateLexicon,
+{
+    let month_fst: F = month(lexicon, symt)?;
+    let day_fst: F = day(lexicon, symt)?;
+    let year_fst: F = year(lexicon, symt)?;
+    let separator_fst: F = string_map(symt, vec![(separator.to_string(), vec![])])?;
+    let fields = match order {
+        DateOrder::MonthDayYear => vec![month_fst, day_fst, year_fst],
+        DateOrder::DayMonthYear => vec![day_fst, month_fst, year_fst],
+        DateOrder::YearMonthDay => vec![year_fst, month_fst, day_fst],
+    };
+    let mut parts = vec![];
+    for (idx, field) in fields.into_iter().enumerate() {
+        if idx > 0 {
+            parts.push(separator_fst.clone());
+        }
+        parts.push(field);
+    }
+    concat_all(parts)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::algorithms::transduce;
+    use crate::fst_impls::VectorFst;
+    use crate::semirings::TropicalWeight;
+
+    fn verbalize(
+        fst: &VectorFst<TropicalWeight>,
+        symt: &SymbolTable,
+        input: &[&str],
+    ) -> Result<Vec<String>> {
+        let ilabels: Vec<Label> = input.iter().map(|s| symt.get_label(s).unwrap()).collect();
+        let outputs = transduce(fst, &ilabels, 2)?;
+        assert!(outputs.len() <= 1, "ambiguous output for {:?}", input);
+        Ok(outputs
+            .into_iter()
+            .flat_map(|(o, _)| o)
+            .map(|l| symt.get_symbol(l).unwrap().to_string())
+            .collect())
+    }
+
+    fn chars(s: &str) -> Vec<String> {
+        s.chars().map(|c| c.to_string()).collect()
+    }
+
+    #[test]
+    fn test_cardinal_and_ordinal() -> Result<()> {
+        let mut symt = SymbolTable::new();
+        let card: VectorFst<TropicalWeight> = cardinal(&EnglishNumberLexicon, &mut symt)?;
+        let ord: VectorFst<TropicalWeight> = ordinal(&EnglishNumberLexicon, &mut symt, Some("th"))?;
+
+        let c = |s: &str| -> Result<String> {
+            let input = chars(s);
+            let input: Vec<&str> = input.iter().map(|s| s.as_str()).collect();
+            Ok(verbalize(&card, &symt, &input)?.join(" "))
+        };
+        assert_eq!(c("0")?, "zero");
+        assert_eq!(c("15")?, "fifteen");
+        assert_eq!(c("1005")?, "one thousand five");
+        assert_eq!(c("2000000")?, "two million");
+        assert_eq!(c("310042")?, "three hundred ten thousand forty two");
+        assert_eq!(c("012")?, "");
+
+        let o = |s: &str| -> Result<String> {
+            let mut input = chars(s);
+            input.push("th".to_string());
+            let input: Vec<&str> = input.iter().map(|s| s.as_str()).collect();
+            Ok(verbalize(&ord, &symt, &input)?.join(" "))
+        };
+        assert_eq!(o("21")?, "twenty first");
+        assert_eq!(o("40")?, "fortieth");
+        assert_eq!(o("3000")?, "three thousandth");
+        assert_eq!(o("1000012")?, "one million twelfth");
+        Ok(())
+    }
+
+    #[test]
+    fn test_decimal() -> Result<()> {
+        let mut symt = SymbolTable::new();
+        let fst: VectorFst<TropicalWeight> = decimal(&EnglishNumberLexicon, &mut symt, ".")?;
+        assert_eq!(
+            verbalize(&fst, &symt, &["3", ".", "1", "4"])?.join(" "),
+            "three point one four"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_date() -> Result<()> {
+        let mut symt = SymbolTable::new();
+        let mdy: VectorFst<TropicalWeight> = date(
+            &EnglishNumberLexicon,
+            &mut symt,
+            DateOrder::MonthDayYear,
+            "/",
+        )?;
+        let ymd: VectorFst<TropicalWeight> = date(
+            &EnglishNumberLexicon,
+            &mut symt,
+            DateOrder::YearMonthDay,
+            "-",
+        )?;
+
+        let d = |fst: &VectorFst<TropicalWeight>, s: &str| -> Result<String> {
+            let input = chars(s);
+            let input: Vec<&str> = input.iter().map(|s| s.as_str()).collect();
+            Ok(verbalize(fst, &symt, &input)?.join(" "))
+        };
+        assert_eq!(
+            d(&mdy, "12/31/1999")?,
+            "december thirty first nineteen ninety nine"
+        );
+        assert_eq!(d(&mdy, "3/02/1905")?, "march second nineteen oh five");
+        assert_eq!(d(&ymd, "2005-07-04")?, "two thousand five july fourth");
+        assert_eq!(
+            d(&ymd, "2021-11-20")?,
+            "twenty twenty one november twentieth"
+        );
+        assert_eq!(d(&ymd, "1900-01-12")?, "nineteen hundred january twelfth");
+        assert_eq!(d(&mdy, "13/01/1999")?, "");
+        Ok(())
+    }
+}
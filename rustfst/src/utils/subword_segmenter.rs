@@ -0,0 +1,89 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use anyhow::Result;
+
+use crate::fst_traits::MutableFst;
+use crate::semirings::Semiring;
+use crate::{Label, StateId, SymbolTable, Tr, EPS_LABEL};
+
+/// Builds a transducer segmenting a sequence of characters into subword units, from the
+/// vocabulary of a BPE or unigram tokenizer.
+///
+/// Each entry of the vocabulary is a unit along with its weight (e.g. the negative log
+/// probability of the unit in a unigram model in the tropical semiring, or `W::one()` for a
+/// BPE vocabulary). The input symbols of the returned FST are the characters, one symbol per
+/// Unicode scalar value, and its output symbols are the units, both stored in the symbol
+/// tables of the FST. The units are organised in a prefix tree: the characters of a unit are
+/// read without output and the unit is emitted with its weight at the end.
+///
+/// Any segmentation of the input into units of the vocabulary is a path of the FST, the
+/// best unigram segmentation of a string being its shortest path once composed with the
+/// acceptor of the characters. As a transducer, the segmenter can be composed with other
+/// constraints (e.g. restricting the allowed units).
+///
+/// # Example
+/// ```
+/// # use rustfst::utils::{acceptor, subword_segmenter};
+/// # use rustfst::algorithms::transduce;
+/// # use rustfst::fst_impls::VectorFst;
+/// # use rustfst::fst_traits::Fst;
+/// # use rustfst::semirings::{Semiring, TropicalWeight};
+/// # use rustfst::Label;
+/// # use anyhow::Result;
+/// # fn main() -> Result<()> {
+/// let vocabulary = vec![
+///     ("un", TropicalWeight::new(2.0)),
+///     ("u", TropicalWeight::new(3.0)),
+///     ("n", TropicalWeight::new(3.0)),
+///     ("do", TropicalWeight::new(2.5)),
+///     ("d", TropicalWeight::new(3.0)),
+///     ("o", TropicalWeight::new(3.0)),
+/// ];
+/// let segmenter: VectorFst<TropicalWeight> = subword_segmenter(&vocabulary)?;
+/// let chars = segmenter.input_symbols().unwrap();
+/// let units = segmenter.output_symbols().unwrap();
+///
+/// let input: Vec<Label> = "undo".chars().map(|c| chars.get_label(c.to_string()).unwrap()).collect();
+/// let (best, weight) = transduce(&segmenter, &input, 1)?.remove(0);
+/// let best: Vec<&str> = best.iter().map(|l| units.get_symbol(*l).unwrap()).collect();
+/// assert_eq!(best, vec!["un", "do"]);
+/// assert_eq!(weight, TropicalWeight::new(4.5));
+/// # Ok(())
+/// # }
+/// ```
+pub fn subword_segmenter<W: Semiring, F: MutableFst<W>>(vocabulary: &[(&str, W)]) -> Result<F> {
+    let mut chars = SymbolTable::new();
+    let mut units = SymbolTable::new();
+    let mut fst = F::new();
+    let start = fst.add_state();
+    fst.set_start(start)?;
+    fst.set_final(start, W::one())?;
+
+    // Children of the nodes of the prefix tree.
+    let mut children: HashMap<(StateId, Label), StateId> = HashMap::new();
+    for (unit, weight) in vocabulary {
+        if unit.is_empty() {
+            bail!("subword_segmenter : empty units are not allowed");
+        }
+        let mut state = start;
+        for c in unit.chars() {
+            let label = chars.add_symbol(c.to_string());
+            state = match children.get(&(state, label)) {
+                Some(next) => *next,
+                None => {
+                    let next = fst.add_state();
+                    fst.add_tr(state, Tr::new(label, EPS_LABEL, W::one(), next))?;
+                    children.insert((state, label), next);
+                    next
+                }
+            };
+        }
+        let unit_label = units.add_symbol(*unit);
+        fst.add_tr(state, Tr::new(EPS_LABEL, unit_label, weight.clone(), start))?;
+    }
+
+    fst.set_input_symbols(Arc::new(chars));
+    fst.set_output_symbols(Arc::new(units));
+    Ok(fst)
+}
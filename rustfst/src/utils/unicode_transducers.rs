@@ -0,0 +1,355 @@
+//! Builders of transducers operating on Unicode characters, to be used as components of text
+//! normalization pipelines.
+//!
+//! The transducers are built over a codepoint symbol table: each symbol made of a single
+//! character is a character of the alphabet. The symbols produced by the transducers that are
+//! missing from the table are added to it.
+//!
+//! The canonical normalization forms require the `unicode` feature, which embeds the Unicode
+//! normalization tables.
+
+use anyhow::Result;
+
+use crate::fst_traits::MutableFst;
+use crate::semirings::Semiring;
+use crate::{Label, StateId, SymbolTable, Tr, EPS_LABEL};
+
+/// Characters of the symbol table, in the order of their labels.
+fn alphabet(symt: &SymbolTable) -> Vec<char> {
+    symt.symbols()
+        .filter_map(|s| {
+            let mut chars = s.chars();
+            match (chars.next(), chars.next()) {
+                (Some(c), None) => Some(c),
+                _ => None,
+            }
+        })
+        .collect()
+}
+
+fn char_label(symt: &mut SymbolTable, c: char) -> Label {
+    symt.add_symbol(c.to_string())
+}
+
+/// Adds a path from `source` to `target` reading `ilabel` and writing `olabels`, using
+/// transitions with an input epsilon when more than one label has to be written.
+fn add_chain<W: Semiring, F: MutableFst<W>>(
+    fst: &mut F,
+    source: StateId,
+    ilabel: Label,
+    olabels: &[Label],
+    target: StateId,
+) -> Result<()> {
+    if olabels.len() <= 1 {
+        let olabel = olabels.first().cloned().unwrap_or(EPS_LABEL);
+        return fst.add_tr(source, Tr::new(ilabel, olabel, W::one(), target));
+    }
+    let mut state = source;
+    for (idx, olabel) in olabels.iter().enumerate() {
+        let i = if idx == 0 { ilabel } else { EPS_LABEL };
+        let next = if idx + 1 == olabels.len() {
+            target
+        } else {
+            fst.add_state()
+        };
+        fst.add_tr(state, Tr::new(i, *olabel, W::one(), next))?;
+        state = next;
+    }
+    Ok(())
+}
+
+/// Builds the one-state transducer mapping each character of the alphabet to a string.
+fn char_map<W, F, M>(symt: &mut SymbolTable, map: M) -> Result<F>
+where
+    W: Semiring,
+    F: MutableFst<W>,
+    M: Fn(char) -> String,
+{
+    let mut fst = F::new();
+    let state = fst.add_state();
+    fst.set_start(state)?;
+    fst.set_final(state, W::one())?;
+    for c in alphabet(symt) {
+        let ilabel = char_label(symt, c);
+        let olabels: Vec<Label> = map(c).chars().map(|o| char_label(symt, o)).collect();
+        add_chain(&mut fst, state, ilabel, &olabels, state)?;
+    }
+    Ok(fst)
+}
+
+/// Full case folding of a character, i.e. the mappings of status `C` and `F` of the Unicode
+/// `CaseFolding.txt` file.
+///
+/// The folding is derived from the case mappings of the standard library: a character is
+/// folded into the lowercase of the uppercase of its lowercase, which handles the characters
+/// folded into several characters (`ß` into `ss`) and the variants of a same letter (`ς` and
+/// `ſ`). The exceptions are the dotless `ı`, which has no folding, and the Cherokee small
+/// letters, which are folded into their capital letters.
+fn fold_case(c: char) -> String {
+    match c {
+        '\u{131}' => c.to_string(),
+        '\u{13F8}'..='\u{13FD}' | '\u{AB70}'..='\u{ABBF}' => c.to_uppercase().collect(),
+        '\u{13A0}'..='\u{13F5}' => c.to_string(),
+        _ => c
+            .to_lowercase()
+            .flat_map(char::to_uppercase)
+            .flat_map(char::to_lowercase)
+            .collect(),
+    }
+}
+
+/// Builds the transducer case folding each character of the symbol table, using the full
+/// Unicode case folding (some characters are folded into several characters, e.g. `ß` into
+/// `ss`). Two strings that only differ by their case are mapped to the same string.
+///
+/// # Example
+/// ```
+/// # use rustfst::utils::unicode_transducers::case_folding;
+/// # use rustfst::algorithms::transduce;
+/// # use rustfst::fst_impls::VectorFst;
+/// # use rustfst::semirings::TropicalWeight;
+/// # use rustfst::SymbolTable;
+/// # use anyhow::Result;
+/// # fn main() -> Result<()> {
+/// let mut symt = SymbolTable::new();
+/// symt.add_symbols(vec!["É", "T", "é", "t", "ß"]);
+/// let fst: VectorFst<TropicalWeight> = case_folding(&mut symt)?;
+///
+/// let input: Vec<_> = "ÉtÉß".chars().map(|c| symt.get_label(c.to_string()).unwrap()).collect();
+/// let (output, _) = transduce(&fst, &input, 1)?.remove(0);
+/// let output: String = output.iter().map(|l| symt.get_symbol(*l).unwrap()).collect();
+/// assert_eq!(output, "étéss");
+/// # Ok(())
+/// # }
+/// ```
+pub fn case_folding<W: Semiring, F: MutableFst<W>>(symt: &mut SymbolTable) -> Result<F> {
+    char_map(symt, fold_case)
+}
+
+/// Builds the transducer mapping each character of the symbol table to its canonical
+/// decomposition (NFD). Contrary to the normalization of a string, the combining marks are
+/// not reordered.
+#[cfg(feature = "unicode")]
+pub fn nfd<W: Semiring, F: MutableFst<W>>(symt: &mut SymbolTable) -> Result<F> {
+    char_map(symt, |c| {
+        let mut decomposition = String::new();
+        unicode_normalization::char::decompose_canonical(c, |d| decomposition.push(d));
+        decomposition
+    })
+}
+
+/// Builds the transducer mapping a string of characters of the symbol table to its canonical
+/// composition (NFC), e.g. `e` followed by a combining acute accent to `é`.
+///
+/// The input is first decomposed as with [`nfd`], then the longest sequences of characters
+/// that are the decomposition of a character of the symbol table are composed, from left to
+/// right. As only the characters of the symbol table are produced, the symbol table should
+/// contain the composed characters of interest.
+///
+/// # Example
+/// ```
+/// # use rustfst::utils::unicode_transducers::nfc;
+/// # use rustfst::algorithms::transduce;
+/// # use rustfst::fst_impls::VectorFst;
+/// # use rustfst::semirings::TropicalWeight;
+/// # use rustfst::SymbolTable;
+/// # use anyhow::Result;
+/// # fn main() -> Result<()> {
+/// let mut symt = SymbolTable::new();
+/// symt.add_symbols(vec!["e", "\u{301}", "é", "t"]);
+/// let fst: VectorFst<TropicalWeight> = nfc(&mut symt)?;
+///
+/// let input: Vec<_> = "e\u{301}te\u{301}".chars().map(|c| symt.get_label(c.to_string()).unwrap()).collect();
+/// let (output, _) = transduce(&fst, &input, 1)?.remove(0);
+/// let output: String = output.iter().map(|l| symt.get_symbol(*l).unwrap()).collect();
+/// assert_eq!(output, "été");
+/// # Ok(())
+/// # }
+/// ```
+#[cfg(feature = "unicode")]
+pub fn nfc<W, F>(symt: &mut SymbolTable) -> Result<F>
+where
+    W: Semiring,
+    F: crate::fst_traits::ExpandedFst<W>
+        + crate::fst_traits::AllocableFst<W>
+        + MutableFst<W>
+        + std::fmt::Debug,
+{
+    use std::collections::HashMap;
+
+    use unicode_normalization::UnicodeNormalization;
+
+    use crate::algorithms::compose::compose;
+    use crate::algorithms::tr_compares::ILabelCompare;
+    use crate::algorithms::tr_sort;
+
+    let decompose: F = nfd(symt)?;
+
+    // Decompositions of the characters of the table that are restored by the composition.
+    let mut compositions: HashMap<Vec<char>, char> = HashMap::new();
+    for c in alphabet(symt) {
+        let decomposition: Vec<char> = c.to_string().nfd().collect();
+        let recomposed: String = decomposition.iter().collect::<String>().nfc().collect();
+        if decomposition.len() >= 2 && recomposed == c.to_string() {
+            compositions.insert(decomposition, c);
+        }
+    }
+
+    // Greedy composition of a string, longest match first.
+    let flush = |pending: &[char]| -> Vec<char> {
+        let mut output = vec![];
+        let mut i = 0;
+        while i < pending.len() {
+            match (i + 2..=pending.len())
+                .rev()
+                .find_map(|j| compositions.get(&pending[i..j]).map(|c| (j, *c)))
+            {
+                Some((j, c)) => {
+                    output.push(c);
+                    i = j;
+                }
+                None => {
+                    output.push(pending[i]);
+                    i += 1;
+                }
+            }
+        }
+        output
+    };
+
+    let mut recompose = F::new();
+    let root = recompose.add_state();
+    recompose.set_start(root)?;
+    recompose.set_final(root, W::one())?;
+    let end = recompose.add_state();
+    recompose.set_final(end, W::one())?;
+
+    // The states are the prefixes of the decompositions, waiting to be composed.
+    let mut prefixes: HashMap<Vec<char>, StateId> = HashMap::new();
+    prefixes.insert(vec![], root);
+    let mut sorted_decompositions: Vec<&Vec<char>> = compositions.keys().collect();
+    sorted_decompositions.sort();
+    for decomposition in sorted_decompositions {
+        for len in 1..=decomposition.len() {
+            if !prefixes.contains_key(&decomposition[..len]) {
+                let s = recompose.add_state();
+                prefixes.insert(decomposition[..len].to_vec(), s);
+            }
+        }
+    }
+
+    let input_alphabet = alphabet(symt);
+    let mut sorted_prefixes: Vec<(Vec<char>, StateId)> = prefixes.into_iter().collect();
+    sorted_prefixes.sort_by_key(|(_, s)| *s);
+    let prefix_states: HashMap<Vec<char>, StateId> = sorted_prefixes.iter().cloned().collect();
+    for (prefix, state) in sorted_prefixes.iter() {
+        for c in input_alphabet.iter() {
+            let ilabel = char_label(symt, *c);
+            let mut extended = prefix.clone();
+            extended.push(*c);
+            if let Some(next) = prefix_states.get(&extended) {
+                recompose.add_tr(*state, Tr::new(ilabel, EPS_LABEL, W::one(), *next))?;
+                continue;
+            }
+            let mut output = flush(prefix);
+            let next = match prefix_states.get(&vec![*c]) {
+                Some(next) => *next,
+                None => {
+                    output.push(*c);
+                    root
+                }
+            };
+            let olabels: Vec<Label> = output.iter().map(|o| char_label(symt, *o)).collect();
+            add_chain(&mut recompose, *state, ilabel, &olabels, next)?;
+        }
+        if *state != root {
+            let olabels: Vec<Label> = flush(prefix).iter().map(|o| char_label(symt, *o)).collect();
+            add_chain(&mut recompose, *state, EPS_LABEL, &olabels, end)?;
+        }
+    }
+
+    tr_sort(&mut recompose, ILabelCompare {});
+    compose::<W, F, F, _, _, _>(&decompose, &recompose)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::algorithms::transduce;
+    use crate::fst_impls::VectorFst;
+    use crate::semirings::TropicalWeight;
+
+    fn apply(fst: &VectorFst<TropicalWeight>, symt: &SymbolTable, input: &str) -> Result<String> {
+        let ilabels: Vec<Label> = input
+            .chars()
+            .map(|c| symt.get_label(c.to_string()).unwrap())
+            .collect();
+        let outputs = transduce(fst, &ilabels, 1)?;
+        assert!(!outputs.is_empty(), "no output for {:?}", input);
+        Ok(outputs[0]
+            .0
+            .iter()
+            .map(|l| symt.get_symbol(*l).unwrap())
+            .collect())
+    }
+
+    #[test]
+    fn test_case_folding() -> Result<()> {
+        let mut symt = SymbolTable::new();
+        symt.add_symbols(vec!["A", "b", "Σ", "ς", "\u{130}", "ß", "multi"]);
+        let fst: VectorFst<TropicalWeight> = case_folding(&mut symt)?;
+
+        assert_eq!(apply(&fst, &symt, "AbΣ")?, "abσ");
+        assert_eq!(apply(&fst, &symt, "ςß")?, "σss");
+        // The dotted capital I is folded into an i followed by a combining dot.
+        assert_eq!(apply(&fst, &symt, "\u{130}A")?, "i\u{307}a");
+        // The symbols that aren't single characters are not part of the alphabet.
+        let multi = symt.get_label("multi").unwrap();
+        assert!(transduce(&fst, &[multi], 1)?.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_fold_case() {
+        // Entries of CaseFolding.txt whose folding differs from the lowercase.
+        let foldings = [
+            ('ß', "ss"),
+            ('\u{1E9E}', "ss"),
+            ('ς', "σ"),
+            ('ſ', "s"),
+            ('µ', "μ"),
+            ('ϑ', "θ"),
+            ('\u{FB01}', "fi"),
+            ('\u{1F80}', "\u{1F00}\u{3B9}"),
+            ('\u{1FBC}', "\u{3B1}\u{3B9}"),
+            ('\u{AB70}', "\u{13A0}"),
+            ('\u{13F8}', "\u{13F0}"),
+        ];
+        for (c, folded) in foldings.iter() {
+            assert_eq!(fold_case(*c), *folded, "folding of {:?}", c);
+        }
+        // Entries equal to the lowercase, and characters without folding.
+        assert_eq!(fold_case('\u{212A}'), "k");
+        assert_eq!(fold_case('\u{130}'), "i\u{307}");
+        assert_eq!(fold_case('\u{131}'), "\u{131}");
+        assert_eq!(fold_case('\u{13A0}'), "\u{13A0}");
+        assert_eq!(fold_case('é'), "é");
+        assert_eq!(fold_case('7'), "7");
+    }
+
+    #[cfg(feature = "unicode")]
+    #[test]
+    fn test_nfd_and_nfc() -> Result<()> {
+        let mut symt = SymbolTable::new();
+        symt.add_symbols(vec!["e", "\u{301}", "é", "a", "à", "t"]);
+        let decompose: VectorFst<TropicalWeight> = nfd(&mut symt)?;
+        assert_eq!(apply(&decompose, &symt, "étà")?, "e\u{301}ta\u{300}");
+
+        let compose: VectorFst<TropicalWeight> = nfc(&mut symt)?;
+        assert_eq!(apply(&compose, &symt, "e\u{301}ta\u{300}")?, "étà");
+        assert_eq!(apply(&compose, &symt, "éta")?, "éta");
+        assert_eq!(apply(&compose, &symt, "e\u{301}\u{301}")?, "é\u{301}");
+        Ok(())
+    }
+}